@@ -0,0 +1 @@
+ALTER TABLE timesheet DROP COLUMN notes;
@@ -0,0 +1 @@
+ALTER TABLE timesheet ADD COLUMN notes text not null default ''; -- free text for the whole day
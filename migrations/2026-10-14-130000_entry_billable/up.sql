@@ -0,0 +1 @@
+ALTER TABLE time_entry ADD COLUMN billable boolean not null default false;
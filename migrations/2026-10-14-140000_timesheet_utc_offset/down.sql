@@ -0,0 +1 @@
+ALTER TABLE timesheet DROP COLUMN utc_offset_mins;
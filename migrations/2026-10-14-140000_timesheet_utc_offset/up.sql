@@ -0,0 +1 @@
+ALTER TABLE timesheet ADD COLUMN utc_offset_mins integer; -- where the day was recorded, NULL if unknown
@@ -0,0 +1,10 @@
+CREATE TABLE entry_template (
+    name text not null primary key,
+
+    duration_mins int not null,
+    description text not null,
+
+    project_key text not null,
+    ticket_key text null,
+    billable boolean not null default false
+);
@@ -0,0 +1,7 @@
+CREATE TABLE usage_metric (
+    day text not null,
+    feature text not null,
+    uses int not null,
+
+    primary key (day, feature)
+);
@@ -0,0 +1,4 @@
+CREATE TABLE preference (
+    key text not null primary key,
+    value text not null
+);
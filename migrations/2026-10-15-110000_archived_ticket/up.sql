@@ -0,0 +1,4 @@
+CREATE TABLE archived_ticket (
+    ticket_key text not null primary key,
+    archived_on text not null
+);
@@ -0,0 +1 @@
+ALTER TABLE time_entry DROP COLUMN work_type;
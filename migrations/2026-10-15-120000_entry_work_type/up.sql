@@ -0,0 +1 @@
+ALTER TABLE time_entry ADD COLUMN work_type text;
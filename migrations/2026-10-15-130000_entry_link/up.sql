@@ -0,0 +1,6 @@
+CREATE TABLE entry_link (
+    entry_id text not null,
+    position integer not null,
+    url text not null,
+    primary key (entry_id, position)
+);
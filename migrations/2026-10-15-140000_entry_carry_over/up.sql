@@ -0,0 +1 @@
+ALTER TABLE time_entry ADD COLUMN carry_over boolean not null default false;
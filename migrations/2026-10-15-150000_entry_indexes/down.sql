@@ -0,0 +1,2 @@
+DROP INDEX time_entry_ticket;
+DROP INDEX time_entry_day;
@@ -0,0 +1,5 @@
+-- Loading a day and ranges of days
+CREATE INDEX time_entry_day ON time_entry (timesheet_day, start_time);
+-- Ticket suggestions look up prefixes case-insensitively, which LIKE can only do with NOCASE
+CREATE INDEX time_entry_ticket ON time_entry (ticket_key COLLATE NOCASE, timesheet_day)
+    WHERE ticket_key IS NOT NULL;
@@ -0,0 +1 @@
+DROP TABLE timesheet_snapshot;
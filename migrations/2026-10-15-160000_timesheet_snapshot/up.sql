@@ -0,0 +1,8 @@
+-- Earlier states of a day, to look at and restore, see persist::history
+CREATE TABLE timesheet_snapshot (
+    day text not null,
+    taken_at text not null,
+    -- Notes, entries and their links as JSON
+    content text not null,
+    primary key (day, taken_at)
+);
@@ -0,0 +1,3 @@
+-- Bumped by every write of the entry, so that a writer notices when another one changed it.
+-- Starts at 1 for stored entries, 0 is left for entries that were never stored.
+ALTER TABLE time_entry ADD COLUMN revision integer not null default 1;
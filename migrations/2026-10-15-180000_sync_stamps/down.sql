@@ -0,0 +1,9 @@
+DROP TRIGGER timesheet_updated;
+DROP TRIGGER entry_link_deleted;
+DROP TRIGGER entry_link_inserted;
+DROP TRIGGER time_entry_deleted;
+DROP TRIGGER time_entry_updated;
+DROP TRIGGER time_entry_inserted;
+DROP TABLE entry_tombstone;
+ALTER TABLE timesheet DROP COLUMN changed_at;
+ALTER TABLE time_entry DROP COLUMN changed_at;
@@ -0,0 +1,54 @@
+-- When entries and notes last changed and which entries were deleted, to sync them between
+-- machines. Stamped by triggers so that every writer is covered, the sync sets the stamps of
+-- changes taken from another machine itself.
+ALTER TABLE time_entry ADD COLUMN changed_at text;
+ALTER TABLE timesheet ADD COLUMN changed_at text;
+-- Earlier changes count as long ago, so that any change made since wins over them
+UPDATE time_entry SET changed_at = '1970-01-01T00:00:00.000Z';
+UPDATE timesheet SET changed_at = '1970-01-01T00:00:00.000Z' WHERE notes != '';
+
+CREATE TABLE entry_tombstone
+(
+    id            text primary key not null,
+    timesheet_day text not null,
+    deleted_at    text not null
+);
+CREATE INDEX entry_tombstone_day ON entry_tombstone (timesheet_day);
+
+CREATE TRIGGER time_entry_inserted AFTER INSERT ON time_entry
+BEGIN
+    DELETE FROM entry_tombstone WHERE id = NEW.id;
+    UPDATE time_entry SET changed_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE id = NEW.id AND NEW.changed_at IS NULL;
+END;
+
+CREATE TRIGGER time_entry_updated AFTER UPDATE ON time_entry
+    WHEN NEW.changed_at IS OLD.changed_at
+BEGIN
+    UPDATE time_entry SET changed_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER time_entry_deleted AFTER DELETE ON time_entry
+BEGIN
+    INSERT OR REPLACE INTO entry_tombstone (id, timesheet_day, deleted_at)
+    VALUES (OLD.id, OLD.timesheet_day, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+CREATE TRIGGER entry_link_inserted AFTER INSERT ON entry_link
+BEGIN
+    UPDATE time_entry SET changed_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE id = NEW.entry_id;
+END;
+
+CREATE TRIGGER entry_link_deleted AFTER DELETE ON entry_link
+BEGIN
+    UPDATE time_entry SET changed_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE id = OLD.entry_id;
+END;
+
+-- Only edits count, an empty sheet created along with its first entry must not win over notes
+CREATE TRIGGER timesheet_updated AFTER UPDATE OF notes, utc_offset_mins ON timesheet
+    WHEN NEW.changed_at IS OLD.changed_at
+BEGIN
+    UPDATE timesheet SET changed_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE day = NEW.day;
+END;
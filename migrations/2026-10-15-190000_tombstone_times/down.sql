@@ -0,0 +1,9 @@
+DROP TRIGGER time_entry_deleted;
+CREATE TRIGGER time_entry_deleted AFTER DELETE ON time_entry
+BEGIN
+    INSERT OR REPLACE INTO entry_tombstone (id, timesheet_day, deleted_at)
+    VALUES (OLD.id, OLD.timesheet_day, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+ALTER TABLE entry_tombstone DROP COLUMN duration_mins;
+ALTER TABLE entry_tombstone DROP COLUMN start_time;
@@ -0,0 +1,12 @@
+-- Times of the deleted entry, so that imports can leave out entries that were deleted before.
+-- Unknown for deletions taken over from other machines.
+ALTER TABLE entry_tombstone ADD COLUMN start_time text;
+ALTER TABLE entry_tombstone ADD COLUMN duration_mins integer;
+
+DROP TRIGGER time_entry_deleted;
+CREATE TRIGGER time_entry_deleted AFTER DELETE ON time_entry
+BEGIN
+    INSERT OR REPLACE INTO entry_tombstone (id, timesheet_day, deleted_at, start_time, duration_mins)
+    VALUES (OLD.id, OLD.timesheet_day, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'), OLD.start_time,
+            OLD.duration_mins);
+END;
@@ -0,0 +1,2 @@
+-- Kept to the minute by exports and bookings, even if a rounding is configured
+ALTER TABLE time_entry ADD COLUMN exact boolean not null default false;
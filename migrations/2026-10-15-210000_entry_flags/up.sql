@@ -0,0 +1,2 @@
+-- Keys of the configured flags, separated by spaces
+ALTER TABLE time_entry ADD COLUMN flags text not null default '';
@@ -1,7 +1,8 @@
-use strum::Display;
+use serde::{Deserialize, Serialize};
+use strum::{Display, IntoStaticStr};
 use time::{Date, OffsetDateTime};
 
-#[derive(Debug, Clone, PartialEq, Eq, Display)]
+#[derive(Debug, Clone, PartialEq, Eq, Display, IntoStaticStr)]
 pub enum Action {
     Tick,
     Render,
@@ -14,6 +15,26 @@ pub enum Action {
     SetStatusLine(String),
     SetRelevantKeys(Vec<RelevantKey>),
     SetActivePage(Page),
+    /// Selects the entry with this id once the timesheet of its day is shown
+    SelectEntry(String),
+    /// Reconnects to the database of the profile, `None` for the default one
+    SwitchProfile(Option<String>),
+    /// Sends edits that are not saved yet right away, e.g. before switching the profile
+    FlushEdits,
+    /// Adds an entry to today that follows the clock, e.g. from the control socket
+    StartTimer(TimerStart),
+    StopTimer,
+    /// Syncs the recent days with other machines, see [crate::config::SyncConfig]
+    SyncNow,
+}
+
+/// Entry to start a timer for, the project defaults to the one configured for the ticket
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimerStart {
+    pub project: Option<String>,
+    pub ticket: Option<String>,
+    pub description: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -33,8 +54,14 @@ impl RelevantKey {
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Page {
-    Home { day: Date },
-    Calendar { day: Date },
+    Home {
+        day: Date,
+    },
+    Calendar {
+        day: Date,
+    },
+    /// Log of the current session
+    Logs,
 }
 
 impl Default for Page {
@@ -1,56 +1,102 @@
-use color_eyre::Result;
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::{
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use color_eyre::{Result, eyre::eyre};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::prelude::Rect;
+use time::{Date, OffsetDateTime, format_description::FormatItem, macros::format_description};
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
-use tracing::debug;
+use tracing::{debug, info};
 
 use crate::{
     action::{Action, Page},
     components::{
-        Component, calendar::Calendar, fps::FpsCounter, home::Home, statusbar::StatusBar,
+        Component,
+        calendar::Calendar,
+        fps::{FpsCounter, SharedRenderStats},
+        home::Home,
+        logs::LogViewer,
+        statusbar::StatusBar,
     },
     config::Config,
-    persist,
-    tui::{Event, Tui},
+    ipc::{IpcServer, Request, Response},
+    logging::ACTION_TARGET,
+    persist, settings,
+    tui::{Event, IdleRender, Tui},
 };
 
 pub struct App {
     config: Config,
     tick_rate: f64,
     frame_rate: f64,
+    idle_render: Option<IdleRender>,
     components: Vec<Box<dyn Component>>,
     should_quit: bool,
     should_suspend: bool,
     action_tx: mpsc::UnboundedSender<Action>,
     action_rx: mpsc::UnboundedReceiver<Action>,
-    persist_tx: UnboundedSender<persist::Command>,
-    persisted_rx: UnboundedReceiver<persist::Event>,
+    persist: PersistThread,
+    /// Operation of the persist thread that reported progress, Esc cancels it
+    operation: Option<persist::OperationId>,
+    ipc: IpcServer,
+    /// When the recent days were last synced, `None` until the first sync of the profile
+    last_sync: Option<Instant>,
+    /// Frame times and queue depth for the performance overlay of the [FpsCounter]
+    render_stats: SharedRenderStats,
+}
+
+/// Persist thread for the database of the active profile
+struct PersistThread {
+    tx: UnboundedSender<persist::Command>,
+    rx: UnboundedReceiver<persist::Event>,
+    handle: JoinHandle<()>,
+}
+
+impl PersistThread {
+    fn start() -> Result<Self> {
+        let (tx, cmd_rx) = mpsc::unbounded_channel();
+        let (evt_tx, rx) = mpsc::unbounded_channel();
+        let handle = persist::start_async_exporting(
+            &settings::data_dir(),
+            settings::get(),
+            settings::export_dir(),
+            settings::export_meta()?,
+            cmd_rx,
+            evt_tx,
+        )?;
+        Ok(Self { tx, rx, handle })
+    }
+
+    /// Waits for remaining commands to complete; closing the events channel initiates shutdown
+    fn stop(mut self) -> Result<()> {
+        self.rx.close();
+        self.handle
+            .join()
+            .map_err(|err| eyre!("Persist thread panicked: {err:?}"))
+    }
 }
 
 impl App {
-    pub fn new(
-        tick_rate: f64,
-        frame_rate: f64,
-        persist_tx: UnboundedSender<persist::Command>,
-        persisted_rx: UnboundedReceiver<persist::Event>,
-    ) -> Result<Self> {
+    pub fn new(tick_rate: f64, frame_rate: f64, idle_render: Option<IdleRender>) -> Result<Self> {
         let (action_tx, action_rx) = mpsc::unbounded_channel();
+        settings::refresh_holidays();
         Ok(Self {
             tick_rate,
             frame_rate,
-            components: vec![
-                Box::new(Home::default()),
-                Box::new(Calendar::default()),
-                Box::new(FpsCounter::default()),
-                Box::new(StatusBar::default()),
-            ],
+            idle_render,
+            components: vec![],
             should_quit: false,
             should_suspend: false,
-            config: Config::new()?,
+            config: settings::get().clone(),
             action_tx,
             action_rx,
-            persist_tx,
-            persisted_rx,
+            persist: PersistThread::start()?,
+            operation: None,
+            ipc: IpcServer::start()?,
+            last_sync: None,
+            render_stats: SharedRenderStats::default(),
         })
     }
 
@@ -58,27 +104,16 @@ impl App {
         let mut tui = Tui::new()?
             // .mouse(true) // uncomment this line to enable mouse support
             .tick_rate(self.tick_rate)
-            .frame_rate(self.frame_rate);
+            .frame_rate(self.frame_rate)
+            .idle_render(self.idle_render);
         tui.enter()?;
 
-        for component in self.components.iter_mut() {
-            component.register_action_handler(self.action_tx.clone())?;
-        }
-        for component in self.components.iter_mut() {
-            component.register_config_handler(self.config.clone())?;
-        }
-        for component in self.components.iter_mut() {
-            component.register_persist_handler(self.persist_tx.clone())?;
-        }
-        for component in self.components.iter_mut() {
-            component.init(tui.size()?)?;
-        }
-
+        self.start_components(&tui)?;
         let action_tx = self.action_tx.clone();
-        action_tx.send(Action::SetActivePage(Page::default()))?;
         loop {
             self.handle_events(&mut tui).await?;
             self.handle_persisted().await?;
+            self.handle_ipc()?;
             self.handle_actions(&mut tui)?;
             if self.should_suspend {
                 tui.suspend()?;
@@ -88,11 +123,61 @@ impl App {
                 tui.enter()?;
             } else if self.should_quit {
                 tui.stop()?;
-                self.persisted_rx.close();
                 break;
             }
         }
         tui.exit()?;
+        self.persist.stop()
+    }
+
+    /// Fresh components, so that nothing loaded from a previous profile is shown or saved
+    fn start_components(&mut self, tui: &Tui) -> Result<()> {
+        self.components = vec![
+            Box::new(Home::default()),
+            Box::new(Calendar::default()),
+            Box::new(LogViewer::default()),
+            Box::new(FpsCounter::new(self.render_stats.clone())),
+            Box::new(StatusBar::default()),
+        ];
+        for component in self.components.iter_mut() {
+            component.register_action_handler(self.action_tx.clone())?;
+        }
+        for component in self.components.iter_mut() {
+            component.register_config_handler(self.config.clone())?;
+        }
+        for component in self.components.iter_mut() {
+            component.register_persist_handler(self.persist.tx.clone())?;
+        }
+        for component in self.components.iter_mut() {
+            component.init(tui.size()?)?;
+        }
+        self.action_tx
+            .send(Action::SetActivePage(Page::default()))?;
+        Ok(())
+    }
+
+    fn switch_profile(&mut self, tui: &Tui, profile: Option<String>) -> Result<()> {
+        // Sent to the current database, stopping its persist thread below waits until stored
+        for component in self.components.iter_mut() {
+            if let Some(action) = component.update(Action::FlushEdits)? {
+                self.action_tx.send(action)?;
+            }
+        }
+        if let Err(err) = settings::switch_profile(profile.clone()) {
+            self.action_tx
+                .send(Action::SetStatusLine(format!("⛔ {err}")))?;
+            return Ok(());
+        }
+        info!("Switching to profile {profile:?}");
+        let previous = std::mem::replace(&mut self.persist, PersistThread::start()?);
+        previous.stop()?;
+        self.ipc.restart()?;
+        self.last_sync = None;
+        self.start_components(tui)?;
+        let name = profile.as_deref().unwrap_or("default");
+        self.action_tx.send(Action::SetStatusLine(format!(
+            "👤 Switched to profile {name}"
+        )))?;
         Ok(())
     }
 
@@ -101,6 +186,15 @@ impl App {
             return Ok(());
         };
         let action_tx = self.action_tx.clone();
+        if let Event::Key(key) = &event
+            && key.code == KeyCode::Esc
+            && key.kind == KeyEventKind::Press
+            && let Some(id) = self.operation.take()
+        {
+            // Not passed on, Esc would otherwise also e.g. close the page
+            self.persist.tx.send(persist::Command::Cancel { id })?;
+            return Ok(());
+        }
         match event {
             Event::Tick => action_tx.send(Action::Tick)?,
             Event::Render => action_tx.send(Action::Render)?,
@@ -132,8 +226,14 @@ impl App {
     }
 
     async fn handle_persisted(&mut self) -> Result<()> {
-        while let Ok(event) = self.persisted_rx.try_recv() {
+        while let Ok(event) = self.persist.rx.try_recv() {
             debug!("Persisted: {event:?}");
+            // Operations are handled one after the other, so any other event ends the one in progress
+            self.operation = match event {
+                persist::Event::Progress { id, .. } => Some(id),
+                _ => None,
+            };
+            self.ipc.handle_persisted(&event);
             for component in self.components.iter_mut() {
                 if let Some(action) = component.handle_persisted(event.clone())? {
                     self.action_tx.send(action)?;
@@ -143,10 +243,61 @@ impl App {
         Ok(())
     }
 
+    /// Requests on the control socket. Added entries are stored like imported ones, so that
+    /// the views reload; the timer is left to Home.
+    fn handle_ipc(&mut self) -> Result<()> {
+        while let Some((request, reply)) = self.ipc.try_recv() {
+            let response = match request {
+                Request::Add(new_entry) => {
+                    let added = new_entry.into_entry(&self.config).and_then(|entry| {
+                        let day = Date::parse(&entry.timesheet_day, ISO_DAY)?;
+                        Ok((day, entry))
+                    });
+                    match added {
+                        Ok((day, entry)) => {
+                            // Answered once stored, with how many entries were
+                            let command = self.ipc.await_import(day, entry, reply);
+                            self.persist.tx.send(command)?;
+                            continue;
+                        }
+                        Err(err) => Response::error(err),
+                    }
+                }
+                Request::Start(timer) => {
+                    self.action_tx.send(Action::StartTimer(timer))?;
+                    Response::ok()
+                }
+                Request::Stop => {
+                    self.action_tx.send(Action::StopTimer)?;
+                    Response::ok()
+                }
+                Request::Today => {
+                    let today = OffsetDateTime::now_local()?.date();
+                    let range = self.ipc.await_entries(today, reply);
+                    self.persist
+                        .tx
+                        .send(persist::Command::LoadEntriesInRange { range })?;
+                    continue;
+                }
+            };
+            // The client may have given up already
+            let _ = reply.send(response);
+        }
+        Ok(())
+    }
+
     fn handle_actions(&mut self, tui: &mut Tui) -> Result<()> {
+        self.render_stats
+            .lock()
+            .expect("render stats lock not poisoned")
+            .action_queue = self.action_rx.len();
         while let Ok(action) = self.action_rx.try_recv() {
             if action != Action::Tick && action != Action::Render {
+                // e.g. saved entries or a started timer change what is shown
+                tui.wake();
                 debug!("{action:?}");
+                let name: &'static str = (&action).into();
+                debug!(target: ACTION_TARGET, source = "app", action = name);
             }
             match action {
                 Action::Quit => self.should_quit = true,
@@ -155,6 +306,12 @@ impl App {
                 Action::ClearScreen => tui.terminal.clear()?,
                 Action::Resize(w, h) => self.handle_resize(tui, w, h)?,
                 Action::Render => self.render(tui)?,
+                Action::Tick => self.sync_if_due()?,
+                Action::SyncNow => self.sync()?,
+                Action::SwitchProfile(profile) => {
+                    self.switch_profile(tui, profile)?;
+                    continue;
+                }
                 _ => {}
             }
             for component in self.components.iter_mut() {
@@ -166,6 +323,30 @@ impl App {
         Ok(())
     }
 
+    /// Syncs every [crate::config::SyncConfig::interval_mins], starting right away
+    fn sync_if_due(&mut self) -> Result<()> {
+        let Some(sync) = &settings::get().sync else {
+            return Ok(());
+        };
+        let interval = Duration::from_secs(sync.interval_mins * 60);
+        if sync.interval_mins > 0 && self.last_sync.is_none_or(|it| it.elapsed() >= interval) {
+            self.sync()?;
+        }
+        Ok(())
+    }
+
+    fn sync(&mut self) -> Result<()> {
+        if settings::get().sync.is_none() {
+            self.action_tx.send(Action::SetStatusLine(
+                "No sync configured, see the sync section of the config".into(),
+            ))?;
+            return Ok(());
+        }
+        self.last_sync = Some(Instant::now());
+        self.persist.tx.send(persist::Command::Sync)?;
+        Ok(())
+    }
+
     fn handle_resize(&mut self, tui: &mut Tui, w: u16, h: u16) -> Result<()> {
         tui.resize(Rect::new(0, 0, w, h))?;
         self.render(tui)?;
@@ -173,6 +354,7 @@ impl App {
     }
 
     fn render(&mut self, tui: &mut Tui) -> Result<()> {
+        let started = Instant::now();
         tui.draw(|frame| {
             for component in self.components.iter_mut() {
                 if component.is_suspended() {
@@ -184,6 +366,12 @@ impl App {
                 }
             }
         })?;
+        self.render_stats
+            .lock()
+            .expect("render stats lock not poisoned")
+            .record_frame(started.elapsed());
         Ok(())
     }
 }
+
+const ISO_DAY: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
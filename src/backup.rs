@@ -0,0 +1,364 @@
+use std::{
+    fs,
+    io::{BufReader, BufWriter, Read},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use color_eyre::{
+    Result,
+    eyre::{Context, eyre},
+};
+use rusqlite::{OpenFlags, backup::Backup};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{CONFIG_FILES, Config},
+    persist::{self, archive},
+};
+
+/// Bumped for changes to the archive layout, not to the database schema
+const ARCHIVE_FORMAT: u32 = 1;
+const MANIFEST: &str = "manifest.json";
+const DATABASE: &str = persist::DATABASE_FILE;
+const CONFIG_PREFIX: &str = "config/";
+/// Databases of archived years, see [archive]
+const ARCHIVE_PREFIX: &str = "archive/";
+
+/// Describes the contents of an archive, stored next to them
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format: u32,
+    pub app_version: String,
+    /// Newest migration applied to the archived database
+    pub schema_version: String,
+    pub created_at: String,
+    pub config_files: Vec<String>,
+    /// Years with an archive database next to the main one
+    #[serde(default)]
+    pub archived_years: Vec<i32>,
+}
+
+#[derive(Debug)]
+pub struct ImportReport {
+    pub manifest: Manifest,
+    /// The database that was replaced, kept just in case
+    pub previous_database: Option<PathBuf>,
+    pub config_written: Vec<String>,
+    /// Already present in the config dir and not replaced
+    pub config_skipped: Vec<String>,
+}
+
+/// Writes a zstd compressed tar with a consistent snapshot of the database and the config
+/// files, e.g. `backup.tar.zst`
+pub fn export_archive(
+    data_dir: &Path,
+    config_dir: &Path,
+    config: &Config,
+    target: &Path,
+) -> Result<Manifest> {
+    // Migrates first, so that the manifest tells the schema of the snapshot
+    let mut conn = persist::prepare_connection(data_dir, &config.sqlite)?;
+    let schema_version = persist::schema_version(&mut conn)?;
+    drop(conn);
+
+    let snapshots = data_dir.join("backup-snapshots");
+    if snapshots.exists() {
+        fs::remove_dir_all(&snapshots).wrap_err("removing leftover snapshots")?;
+    }
+    fs::create_dir_all(&snapshots).wrap_err("creating snapshot dir")?;
+    let written = write_archive(data_dir, config_dir, &snapshots, schema_version, target);
+    if let Err(err) = fs::remove_dir_all(&snapshots) {
+        tracing::warn!("Failed to remove {}: {err}", snapshots.display());
+    }
+    written
+}
+
+fn write_archive(
+    data_dir: &Path,
+    config_dir: &Path,
+    snapshots: &Path,
+    schema_version: String,
+    target: &Path,
+) -> Result<Manifest> {
+    let database = snapshot(&data_dir.join(DATABASE), &snapshots.join(DATABASE))?;
+    let archived_years = archive::archived_years(data_dir)?;
+    let mut archives = vec![];
+    for year in &archived_years {
+        let path = archive::archive_path(data_dir, *year);
+        let name = path
+            .file_name()
+            .expect("archive to have a file name")
+            .to_string_lossy()
+            .to_string();
+        let copy = snapshot(&path, &snapshots.join(&name))?;
+        archives.push((format!("{ARCHIVE_PREFIX}{name}"), copy));
+    }
+
+    let mut config_entries = vec![];
+    for (name, _) in CONFIG_FILES {
+        let path = config_dir.join(name);
+        if path.exists() {
+            config_entries.push((name.to_string(), path));
+        }
+    }
+
+    let manifest = Manifest {
+        format: ARCHIVE_FORMAT,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version,
+        created_at: chrono::Local::now().to_rfc3339(),
+        config_files: config_entries
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect(),
+        archived_years,
+    };
+
+    let file = fs::File::create(target)
+        .wrap_err_with(|| format!("creating archive {}", target.display()))?;
+    let encoder = zstd::Encoder::new(BufWriter::new(file), 0)?;
+    let mut tar = tar::Builder::new(encoder);
+    let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+    let mut header = tar::Header::new_ustar();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|it| it.as_secs())
+            .unwrap_or_default(),
+    );
+    header.set_cksum();
+    tar.append_data(&mut header, MANIFEST, manifest_json.as_slice())?;
+    let files = [(DATABASE.to_string(), database)]
+        .into_iter()
+        .chain(archives)
+        .chain(
+            config_entries
+                .into_iter()
+                .map(|(name, path)| (format!("{CONFIG_PREFIX}{name}"), path)),
+        );
+    for (name, path) in files {
+        tar.append_path_with_name(&path, &name)
+            .wrap_err_with(|| format!("adding {name} to the archive"))?;
+    }
+    tar.into_inner()?.finish()?;
+    Ok(manifest)
+}
+
+/// Consistent copy of the database through the online backup API of SQLite, which copies
+/// pages until no other connection wrote in between
+fn snapshot(source: &Path, target: &Path) -> Result<PathBuf> {
+    let source_conn = rusqlite::Connection::open_with_flags(
+        source,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .wrap_err_with(|| format!("opening {}", source.display()))?;
+    let mut target_conn = rusqlite::Connection::open(target)
+        .wrap_err_with(|| format!("creating {}", target.display()))?;
+    Backup::new(&source_conn, &mut target_conn)?
+        .run_to_completion(256, Duration::from_millis(10), None)
+        .wrap_err_with(|| format!("taking a snapshot of {}", source.display()))?;
+    Ok(target.to_path_buf())
+}
+
+/// Replaces the database with the archived one and migrates it to the current schema.
+/// Config files are only written where none exist, unless `replace_config` is set.
+pub fn import_archive(
+    source: &Path,
+    data_dir: &Path,
+    config_dir: &Path,
+    config: &Config,
+    replace_config: bool,
+) -> Result<ImportReport> {
+    let file =
+        fs::File::open(source).wrap_err_with(|| format!("opening archive {}", source.display()))?;
+    let entries = read_entries(file).wrap_err("reading archive")?;
+    let content_of = |name: &str| {
+        entries
+            .iter()
+            .find(|(it, _)| it == name)
+            .map(|(_, content)| content)
+            .ok_or_else(|| eyre!("archive contains no {name}"))
+    };
+
+    let manifest: Manifest =
+        serde_json::from_slice(content_of(MANIFEST)?).wrap_err("reading archive manifest")?;
+    if manifest.format != ARCHIVE_FORMAT {
+        return Err(eyre!("unsupported archive format {}", manifest.format));
+    }
+    if manifest.schema_version > persist::latest_schema_version()? {
+        return Err(eyre!(
+            "archive was created by sheet-shark {} with a newer database schema, please update",
+            manifest.app_version
+        ));
+    }
+    let database = content_of(DATABASE)?;
+
+    fs::create_dir_all(data_dir)
+        .wrap_err_with(|| format!("creating data dir {}", data_dir.display()))?;
+    // Held until the import is done, so that no instance opens the database meanwhile
+    let _in_use = persist::lock_exclusive(data_dir)
+        .wrap_err("sheet-shark still has the database open, close it before importing a backup")?;
+    let previous_database = keep_previous_database(data_dir)?;
+    fs::write(data_dir.join(DATABASE), database).wrap_err("writing imported database")?;
+    for (name, content) in &entries {
+        let Some(name) = name.strip_prefix(ARCHIVE_PREFIX) else {
+            continue;
+        };
+        if Path::new(name).file_name() != Some(name.as_ref()) {
+            continue;
+        }
+        let dir = data_dir.join(archive::ARCHIVE_DIR);
+        fs::create_dir_all(&dir).wrap_err("creating archive dir")?;
+        fs::write(dir.join(name), content)
+            .wrap_err_with(|| format!("writing imported archive {name}"))?;
+    }
+    // Migrates to the current schema
+    persist::prepare_connection(data_dir, &config.sqlite)?;
+
+    let mut config_written = vec![];
+    let mut config_skipped = vec![];
+    for (name, content) in &entries {
+        let Some(name) = name.strip_prefix(CONFIG_PREFIX) else {
+            continue;
+        };
+        if !CONFIG_FILES.iter().any(|(known, _)| *known == name) {
+            continue;
+        }
+        let path = config_dir.join(name);
+        if path.exists() && !replace_config {
+            config_skipped.push(name.to_string());
+            continue;
+        }
+        fs::create_dir_all(config_dir)
+            .wrap_err_with(|| format!("creating config dir {}", config_dir.display()))?;
+        fs::write(&path, content)
+            .wrap_err_with(|| format!("writing config file {}", path.display()))?;
+        config_written.push(name.to_string());
+    }
+
+    Ok(ImportReport {
+        manifest,
+        previous_database,
+        config_written,
+        config_skipped,
+    })
+}
+
+/// Regular files of the archive with their content
+fn read_entries(file: fs::File) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut tar = tar::Archive::new(zstd::Decoder::new(BufReader::new(file))?);
+    let mut entries = vec![];
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let name = entry.path()?.to_string_lossy().to_string();
+        let mut content = vec![];
+        entry.read_to_end(&mut content)?;
+        entries.push((name, content));
+    }
+    Ok(entries)
+}
+
+/// Moves the database out of the way, together with its WAL files that must not be
+/// applied to the imported one, and its archives.
+fn keep_previous_database(data_dir: &Path) -> Result<Option<PathBuf>> {
+    let current = data_dir.join(DATABASE);
+    if !current.exists() {
+        return Ok(None);
+    }
+    let suffix = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let kept = data_dir.join(format!("{DATABASE}.before-import-{suffix}"));
+    for extension in ["", "-wal", "-shm"] {
+        let from = data_dir.join(format!("{DATABASE}{extension}"));
+        if from.exists() {
+            let to = PathBuf::from(format!("{}{extension}", kept.display()));
+            fs::rename(&from, &to)
+                .wrap_err_with(|| format!("moving {} out of the way", from.display()))?;
+        }
+    }
+    let archives = data_dir.join(archive::ARCHIVE_DIR);
+    if archives.exists() {
+        let to = data_dir.join(format!("{}.before-import-{suffix}", archive::ARCHIVE_DIR));
+        fs::rename(&archives, &to)
+            .wrap_err_with(|| format!("moving {} out of the way", archives.display()))?;
+    }
+    Ok(Some(kept))
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel::{
+        RunQueryDsl, SqliteConnection, connection::SimpleConnection, dsl::sql, sql_types::Text,
+    };
+
+    use super::*;
+    use crate::persist::SqliteStorage;
+
+    fn tmp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("sheet-shark-backup-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn descriptions(conn: &mut SqliteConnection) -> String {
+        diesel::select(sql::<Text>(
+            "(SELECT coalesce(group_concat(description, ','), '') FROM time_entry)",
+        ))
+        .get_result(conn)
+        .unwrap()
+    }
+
+    #[test]
+    fn test_exported_archive_imports_into_empty_data_dir() {
+        let config = Config::default();
+        let (data_dir, config_dir) = (tmp_dir("data"), tmp_dir("config"));
+        let mut conn = persist::prepare_connection(&data_dir, &config.sqlite).unwrap();
+        conn.batch_execute(
+            "INSERT INTO timesheet (day, status, notes) VALUES ('2026-10-15', 'OPEN', ''); \
+             INSERT INTO time_entry (id, timesheet_day, start_time, duration_mins, description, \
+             project_key) VALUES ('tent_01', '2026-10-15', '09:00', 30, 'standup', 'W');",
+        )
+        .unwrap();
+        fs::create_dir_all(&config_dir).unwrap();
+        fs::write(config_dir.join("config.yaml"), "default_project_key: W\n").unwrap();
+        let target = tmp_dir("archive.tar.zst");
+
+        let exported = export_archive(&data_dir, &config_dir, &config, &target).unwrap();
+        assert_eq!(exported.config_files, ["config.yaml"]);
+        drop(conn);
+
+        let (restored_data, restored_config) =
+            (tmp_dir("restored-data"), tmp_dir("restored-config"));
+        let report =
+            import_archive(&target, &restored_data, &restored_config, &config, false).unwrap();
+        assert_eq!(report.manifest.schema_version, exported.schema_version);
+        assert_eq!(report.config_written, ["config.yaml"]);
+        assert!(report.previous_database.is_none());
+        let mut conn = persist::prepare_connection(&restored_data, &config.sqlite).unwrap();
+        assert_eq!(descriptions(&mut conn), "standup");
+        assert_eq!(
+            fs::read_to_string(restored_config.join("config.yaml")).unwrap(),
+            "default_project_key: W\n"
+        );
+    }
+
+    #[test]
+    fn test_import_is_refused_while_database_is_open() {
+        let config = Config::default();
+        let (data_dir, config_dir) = (tmp_dir("open-data"), tmp_dir("open-config"));
+        let target = tmp_dir("open.tar.zst");
+        export_archive(&data_dir, &config_dir, &config, &target).unwrap();
+
+        let storage = SqliteStorage::open(&data_dir, &config).unwrap();
+        let err = import_archive(&target, &data_dir, &config_dir, &config, false).unwrap_err();
+        assert!(err.to_string().contains("close it"));
+        drop(storage);
+        assert!(import_archive(&target, &data_dir, &config_dir, &config, false).is_ok());
+    }
+}
@@ -1,6 +1,29 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use color_eyre::eyre::{Result, eyre};
+use futures::executor;
+use time::{Date, OffsetDateTime, format_description::FormatItem, macros::format_description};
+use tokio::sync::mpsc;
 
-use crate::config::{get_config_dir, get_data_dir};
+use crate::{
+    config::{get_config_dir, get_data_dir},
+    persist, settings,
+};
+
+mod add;
+mod archive;
+mod background;
+mod backup;
+mod branch_ticket;
+mod export_csv;
+mod import;
+mod paths;
+mod query;
+mod rename_ticket;
+mod report;
+mod scaffold;
+mod session_export;
+mod show;
+mod sync;
 
 #[derive(Parser, Debug)]
 #[command(author, version = version(), about)]
@@ -12,8 +35,123 @@ pub struct Cli {
     /// Frame rate, i.e. number of frames per second
     #[arg(short, long, value_name = "FLOAT", default_value_t = 15.0)]
     pub frame_rate: f64,
+
+    /// Seconds without input after which fewer frames are drawn, 0 never, defaults to the config
+    #[arg(long, value_name = "SECS")]
+    pub idle_render_after: Option<u64>,
+
+    /// Frame rate while idle, defaults to the config
+    #[arg(long, value_name = "FLOAT")]
+    pub idle_frame_rate: Option<f64>,
+
+    /// Work with the separate database of this profile, see the profiles section of the config
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    /// Run a single command without entering the TUI
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
 }
 
+#[derive(Subcommand, Debug)]
+pub enum CliCommand {
+    /// Add an entry, through the running TUI if built with the `daemon` feature
+    Add(add::Args),
+    /// Stay in the background and open the TUI or add an entry on a global hotkey, needs the `hotkey` feature
+    Background(background::Args),
+    /// Print the ticket of the branch checked out in the repos configured for the projects
+    BranchTicket(branch_ticket::Args),
+    /// Rename a ticket in all entries of a range of days, e.g. after it moved to another Jira project
+    RenameTicket(rename_ticket::Args),
+    /// Import the time entries of a day from Toggl or Clockify, as configured
+    Import(import::Args),
+    /// Fill in an empty day with the scaffold configured for its weekday, e.g. from a cron job
+    Scaffold(scaffold::Args),
+    /// Export several days into a single CSV with a date column, e.g. a month for invoicing
+    ExportCsv(export_csv::Args),
+    /// Move the whole database and config between machines, or archive them
+    Backup(backup::Args),
+    /// Move old timesheets into a database per year, which are still read for reports and the calendar
+    Archive(archive::Args),
+    /// Pull the recent days changed on other machines and push those changed here, see the sync section of the config
+    Sync(sync::Args),
+    /// Print the entries of a day as a table, as text or Markdown
+    Show(show::Args),
+    /// Print the entries of a range of days, or their totals, e.g. as JSON for scripts
+    Query(query::Args),
+    /// Print the result of a report from the config, as a table or CSV
+    Report(report::Args),
+    /// Print where the config, database, log and exports are, after applying the config
+    Paths(paths::Args),
+    /// Print the action log of the last session without times and days, to attach to bug reports
+    SessionExport(session_export::Args),
+}
+
+pub fn run(command: CliCommand) -> Result<()> {
+    match command {
+        CliCommand::Add(args) => add::run(args),
+        CliCommand::Background(args) => background::run(args),
+        CliCommand::BranchTicket(args) => branch_ticket::run(args),
+        CliCommand::RenameTicket(args) => rename_ticket::run(args),
+        CliCommand::Import(args) => import::run(args),
+        CliCommand::Scaffold(args) => scaffold::run(args),
+        CliCommand::ExportCsv(args) => export_csv::run(args),
+        CliCommand::Backup(args) => backup::run(args),
+        CliCommand::Archive(args) => archive::run(args),
+        CliCommand::Sync(args) => sync::run(args),
+        CliCommand::Show(args) => show::run(args),
+        CliCommand::Query(args) => query::run(args),
+        CliCommand::Report(args) => report::run(args),
+        CliCommand::Paths(args) => paths::run(args),
+        CliCommand::SessionExport(args) => session_export::run(args),
+    }
+}
+
+/// Starts the persist thread for a single command and waits for its response.
+/// Failures reported by the persist layer are converted to errors.
+fn run_persist_command(command: persist::Command) -> Result<persist::Event> {
+    let (persist_tx, persist_rx) = mpsc::unbounded_channel();
+    let (persisted_tx, mut persisted_rx) = mpsc::unbounded_channel();
+    let persist_handle = persist::start_async_exporting(
+        &settings::data_dir(),
+        settings::get(),
+        settings::export_dir(),
+        settings::export_meta()?,
+        persist_rx,
+        persisted_tx,
+    )?;
+
+    persist_tx.send(command)?;
+    let mut event = executor::block_on(persisted_rx.recv());
+    while let Some(persist::Event::Progress { .. }) = event {
+        event = executor::block_on(persisted_rx.recv());
+    }
+
+    // Closing the events channel shuts down the persist thread, see App::run
+    drop(persisted_rx);
+    persist_handle
+        .join()
+        .map_err(|err| eyre!("Persist thread panicked: {err:?}"))?;
+
+    match event {
+        Some(persist::Event::Failure(err)) => Err(eyre!("{err}\n{}", err.details)),
+        Some(event) => Ok(event),
+        None => Err(eyre!("Persist thread stopped without responding")),
+    }
+}
+
+fn parse_day(arg: &str) -> Result<Date, String> {
+    Date::parse(arg, ISO_DAY).map_err(|err| format!("expected YYYY-MM-DD: {err}"))
+}
+
+fn today() -> Date {
+    OffsetDateTime::now_local()
+        .expect("find local offset for date")
+        .date()
+}
+
+const ISO_DAY: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
 const VERSION_MESSAGE: &str = concat!(
     env!("CARGO_PKG_VERSION"),
     "-",
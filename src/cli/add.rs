@@ -0,0 +1,158 @@
+use std::{
+    env,
+    io::{self, Write},
+};
+
+use color_eyre::eyre::{Result, bail};
+use time::Date;
+
+use super::{ISO_DAY, parse_day, run_persist_command};
+use crate::{
+    ipc::{self, NewEntry, Request},
+    persist::{Command, Event},
+    settings,
+    shared::branch_ticket,
+};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// What was worked on
+    #[arg(required_unless_present = "prompt")]
+    pub description: Vec<String>,
+
+    /// Ask for the description, ticket and duration, e.g. in the terminal opened by the
+    /// hotkey of `sheet-shark background`
+    #[arg(long)]
+    pub prompt: bool,
+
+    /// Ticket key, e.g. SCRUM-17, defaults to the one in the branch of a configured repo
+    #[arg(long)]
+    pub ticket: Option<String>,
+
+    /// Don't look for a ticket in the branches of the configured repos
+    #[arg(long)]
+    pub no_branch: bool,
+
+    /// Project key, defaults to the one configured for the ticket
+    #[arg(long)]
+    pub project: Option<String>,
+
+    /// How long, e.g. 45, 1h15m or 3*25m [default: 30]
+    #[arg(long)]
+    pub duration: Option<String>,
+
+    /// Start time (HH:MM), defaults to the duration before now
+    #[arg(long)]
+    pub start: Option<String>,
+
+    /// Day to add to (YYYY-MM-DD), defaults to today
+    #[arg(long, value_parser = parse_day)]
+    pub day: Option<Date>,
+
+    #[arg(long)]
+    pub billable: bool,
+
+    /// Kind of work, one of the configured work types like `meeting`
+    #[arg(long)]
+    pub work_type: Option<String>,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    if !args.prompt {
+        return add(args);
+    }
+    let result = ask(args).and_then(add);
+    if let Err(err) = &result {
+        // The terminal opened for the prompt closes once we exit
+        eprintln!("{err}");
+        ask_line("Press Enter to close")?;
+    }
+    result
+}
+
+/// Fills in what was not given on the command line
+fn ask(mut args: Args) -> Result<Args> {
+    if args.description.is_empty() {
+        let description = ask_line("What did you work on?")?;
+        if description.is_empty() {
+            bail!("Nothing added without a description");
+        }
+        args.description = vec![description];
+    }
+    if args.ticket.is_none() {
+        let ticket = ask_line("Ticket (empty for the branch or none):")?;
+        args.ticket = Some(ticket).filter(|it| !it.is_empty());
+    }
+    if args.duration.is_none() {
+        let duration = ask_line("How long [30]:")?;
+        args.duration = Some(duration).filter(|it| !it.is_empty());
+    }
+    Ok(args)
+}
+
+fn ask_line(question: &str) -> Result<String> {
+    print!("{question} ");
+    io::stdout().flush()?;
+    let mut line = String::new();
+    io::stdin().read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+fn add(args: Args) -> Result<()> {
+    let (mut ticket, mut project) = (args.ticket, args.project);
+    if ticket.is_none() && !args.no_branch {
+        let working_dir = env::current_dir()?;
+        if let Some(inferred) = branch_ticket::infer(settings::get(), &working_dir)? {
+            println!(
+                "Using {} from branch {} in {}",
+                inferred.ticket,
+                inferred.branch,
+                inferred.repo.display()
+            );
+            ticket = Some(inferred.ticket);
+            project = project.or(Some(inferred.project_key));
+        }
+    }
+    let new_entry = NewEntry {
+        description: args.description.join(" "),
+        ticket,
+        project,
+        duration: args.duration,
+        start: args.start,
+        day: args.day.map(|it| it.format(ISO_DAY)).transpose()?,
+        billable: args.billable,
+        work_type: args.work_type,
+    };
+    let entry = new_entry.clone().into_entry(settings::get())?;
+    let summary = format!(
+        "{} {}m on {}",
+        entry.start_time, entry.duration_mins, entry.timesheet_day
+    );
+
+    match ipc::send_to_running(&Request::Add(new_entry))? {
+        Some(response) if response.ok => {
+            println!("Added {summary} in the running sheet-shark");
+            return Ok(());
+        }
+        Some(response) => bail!(
+            "The running sheet-shark refused the entry: {}",
+            response.error.unwrap_or_default()
+        ),
+        None => {}
+    }
+    let day = Date::parse(&entry.timesheet_day, ISO_DAY)?;
+    match run_persist_command(Command::ImportEntries {
+        day,
+        entries: vec![entry],
+        skip_deleted: false,
+    })? {
+        Event::EntriesImported { imported: 0, .. } => {
+            bail!("There is already an entry at {summary}")
+        }
+        Event::EntriesImported { .. } => {
+            println!("Added {summary}");
+            Ok(())
+        }
+        other => bail!("Unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,39 @@
+use color_eyre::eyre::{Result, bail};
+use time::{Date, Month};
+
+use super::{run_persist_command, today};
+use crate::{
+    persist::{Command, Event, archive::ARCHIVE_DIR},
+    settings,
+};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Archive whole months that ended at least this many months ago
+    #[arg(long, default_value_t = 24)]
+    pub older_than_months: u32,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let before = first_of_month_before(today(), args.older_than_months)?;
+    match run_persist_command(Command::ArchiveBefore { day: before })? {
+        Event::Archived { days: 0, .. } => println!("Nothing to archive before {before}"),
+        Event::Archived { days, entries } => println!(
+            "Archived {days} days with {entries} entries before {before} into {}",
+            settings::data_dir().join(ARCHIVE_DIR).display()
+        ),
+        other => bail!("Unexpected response: {other:?}"),
+    }
+    Ok(())
+}
+
+/// First day of the month `months` before the month of the day
+fn first_of_month_before(day: Date, months: u32) -> Result<Date> {
+    let months_since_zero = day.year() * 12 + day.month() as i32 - 1 - months as i32;
+    let month = Month::try_from((months_since_zero.rem_euclid(12) + 1) as u8)?;
+    Ok(Date::from_calendar_date(
+        months_since_zero.div_euclid(12),
+        month,
+        1,
+    )?)
+}
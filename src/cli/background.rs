@@ -0,0 +1,112 @@
+use color_eyre::eyre::{Result, bail};
+
+use crate::settings;
+
+#[derive(clap::Args, Debug)]
+pub struct Args {}
+
+pub fn run(_args: Args) -> Result<()> {
+    let config = &settings::get().background;
+    if config.terminal.is_empty() {
+        bail!("No terminal configured in background.terminal");
+    }
+    imp::wait_for_hotkeys(config)
+}
+
+#[cfg(feature = "hotkey")]
+mod imp {
+    use std::{env, process, str::FromStr};
+
+    use color_eyre::eyre::{Result, WrapErr, bail};
+    use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState, hotkey::HotKey};
+    use tracing::{info, warn};
+
+    use crate::{
+        config::BackgroundConfig,
+        desktop,
+        ipc::{self, Request},
+        settings,
+    };
+
+    pub fn wait_for_hotkeys(config: &BackgroundConfig) -> Result<()> {
+        // Otherwise the hotkeys fail to register in the background and are never pressed
+        if cfg!(target_os = "linux") && env::var_os("DISPLAY").is_none() {
+            bail!("Global hotkeys need an X11 display, but DISPLAY is not set");
+        }
+        let open = parse(&config.open_hotkey)?;
+        let add = parse(&config.add_hotkey)?;
+        // Unregisters the hotkeys when dropped
+        let manager = GlobalHotKeyManager::new()?;
+        manager.register_all(&[open, add])?;
+        println!(
+            "Press {} to open sheet-shark and {} to add an entry, Ctrl+C to stop",
+            config.open_hotkey, config.add_hotkey
+        );
+
+        for event in GlobalHotKeyEvent::receiver() {
+            if event.state != HotKeyState::Pressed {
+                continue;
+            }
+            let result = if event.id == open.id() {
+                open_tui(config)
+            } else if event.id == add.id() {
+                open_in_terminal(config, &["add", "--prompt"])
+            } else {
+                continue;
+            };
+            if let Err(err) = result {
+                warn!("Failed to open a terminal: {err:#}");
+                eprintln!("Failed to open a terminal: {err:#}");
+            }
+        }
+        Ok(())
+    }
+
+    fn parse(hotkey: &str) -> Result<HotKey> {
+        HotKey::from_str(hotkey).wrap_err_with(|| format!("invalid hotkey {hotkey}"))
+    }
+
+    /// Opens the TUI, unless one is already answering on the control socket
+    fn open_tui(config: &BackgroundConfig) -> Result<()> {
+        if ipc::send_to_running(&Request::Today)?.is_some() {
+            info!("sheet-shark is already open, not opening it again");
+            desktop::notify("sheet-shark is already open", "Switch to its terminal");
+            return Ok(());
+        }
+        open_in_terminal(config, &[])
+    }
+
+    fn open_in_terminal(config: &BackgroundConfig, args: &[&str]) -> Result<()> {
+        let (terminal, terminal_args) = config
+            .terminal
+            .split_first()
+            .expect("terminal checked to be configured");
+        let mut command = process::Command::new(terminal);
+        command.args(terminal_args).arg(env::current_exe()?);
+        if let Some(profile) = settings::profile() {
+            command.arg("--profile").arg(profile);
+        }
+        let mut child = command
+            .args(args)
+            .spawn()
+            .wrap_err_with(|| format!("run {terminal}"))?;
+        // Reaps the terminal once it is closed
+        std::thread::spawn(move || {
+            if let Err(err) = child.wait() {
+                warn!("Failed to wait for the terminal: {err}");
+            }
+        });
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "hotkey"))]
+mod imp {
+    use color_eyre::eyre::{Result, bail};
+
+    use crate::config::BackgroundConfig;
+
+    pub fn wait_for_hotkeys(_config: &BackgroundConfig) -> Result<()> {
+        bail!("Built without the `hotkey` feature, e.g. `cargo install --path . --features hotkey`")
+    }
+}
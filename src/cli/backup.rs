@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use color_eyre::eyre::Result;
+use itertools::Itertools;
+
+use crate::{backup, config::get_config_dir, settings};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: BackupCommand,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub enum BackupCommand {
+    /// Write the database and config into an archive, e.g. backup.tar.zst
+    Export { file: PathBuf },
+    /// Replace the database with the one from an archive, keeping the current one next to it
+    Import {
+        file: PathBuf,
+        /// Also overwrite existing config files with the archived ones
+        #[arg(long)]
+        replace_config: bool,
+    },
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let data_dir = settings::data_dir();
+    match args.command {
+        BackupCommand::Export { file } => {
+            let manifest =
+                backup::export_archive(&data_dir, &get_config_dir(), settings::get(), &file)?;
+            println!(
+                "Exported schema {} with {} config files to {}",
+                manifest.schema_version,
+                manifest.config_files.len(),
+                file.display()
+            );
+            if !manifest.archived_years.is_empty() {
+                println!(
+                    "Archived years included: {}",
+                    manifest.archived_years.iter().join(", ")
+                );
+            }
+        }
+        BackupCommand::Import {
+            file,
+            replace_config,
+        } => {
+            let report = backup::import_archive(
+                &file,
+                &data_dir,
+                &get_config_dir(),
+                settings::get(),
+                replace_config,
+            )?;
+            println!(
+                "Imported database from {} (sheet-shark {}, schema {})",
+                report.manifest.created_at,
+                report.manifest.app_version,
+                report.manifest.schema_version
+            );
+            if let Some(previous) = report.previous_database {
+                println!("Previous database kept at {}", previous.display());
+            }
+            if !report.config_written.is_empty() {
+                println!("Config written: {}", report.config_written.join(", "));
+            }
+            if !report.config_skipped.is_empty() {
+                println!(
+                    "Config kept, use --replace-config to overwrite: {}",
+                    report.config_skipped.join(", ")
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use std::env;
+
+use color_eyre::eyre::{Result, bail};
+
+use crate::{settings, shared::branch_ticket};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Also print the project, branch and repo, separated by tabs
+    #[arg(long)]
+    pub verbose: bool,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let working_dir = env::current_dir()?;
+    let Some(inferred) = branch_ticket::infer(settings::get(), &working_dir)? else {
+        bail!("No ticket in the branches of the repos configured for the projects");
+    };
+    if args.verbose {
+        println!(
+            "{}\t{}\t{}\t{}",
+            inferred.ticket,
+            inferred.project_key,
+            inferred.branch,
+            inferred.repo.display()
+        );
+    } else {
+        println!("{}", inferred.ticket);
+    }
+    Ok(())
+}
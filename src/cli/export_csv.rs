@@ -0,0 +1,64 @@
+use std::ops::RangeInclusive;
+
+use clap::ValueEnum;
+use color_eyre::eyre::{Result, eyre};
+use time::{Date, macros::format_description};
+
+use super::{parse_day, run_persist_command};
+use crate::{
+    components::home::export::{export_range_csv, export_range_jira_csv},
+    persist::{Command, Event},
+    shared::month_of,
+};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Month (YYYY-MM) or days (YYYY-MM-DD..YYYY-MM-DD) to export into a single CSV
+    #[arg(long, value_parser = parse_range)]
+    pub range: RangeInclusive<Date>,
+
+    #[arg(long, default_value = "calc")]
+    pub format: Format,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Format {
+    /// A row per entry with the date prepended, like the export of a day
+    Calc,
+    /// Worklogs of the defragmented days for Jira's CSV import, rounded as configured
+    Jira,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let command = Command::LoadEntriesInRange {
+        range: args.range.clone(),
+    };
+    match run_persist_command(command)? {
+        Event::EntriesInRangeLoaded { range, entries } => {
+            let count = entries.len();
+            let path = match args.format {
+                Format::Calc => export_range_csv(&range, entries)?,
+                Format::Jira => export_range_jira_csv(&range, entries)?,
+            };
+            println!("Exported {count} entries to {}", path.display());
+            Ok(())
+        }
+        other => Err(eyre!("Unexpected response: {other:?}")),
+    }
+}
+
+fn parse_range(arg: &str) -> Result<RangeInclusive<Date>, String> {
+    if let Some((first, last)) = arg.split_once("..") {
+        let (first, last) = (parse_day(first)?, parse_day(last)?);
+        if last < first {
+            return Err(format!("{last} is before {first}"));
+        }
+        return Ok(first..=last);
+    }
+    let first_of_month = Date::parse(
+        &format!("{arg}-01"),
+        format_description!("[year]-[month]-[day]"),
+    )
+    .map_err(|err| format!("expected YYYY-MM or YYYY-MM-DD..YYYY-MM-DD: {err}"))?;
+    Ok(month_of(first_of_month))
+}
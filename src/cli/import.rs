@@ -0,0 +1,67 @@
+use color_eyre::eyre::{Result, bail, eyre};
+use time::Date;
+
+use super::{ISO_DAY, parse_day, run_persist_command, today};
+use crate::{
+    import,
+    persist::{Command, Event},
+    settings,
+};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Day to import (YYYY-MM-DD), defaults to today
+    #[arg(long, value_parser = parse_day)]
+    pub day: Option<Date>,
+
+    /// Only print the entries that would be imported
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let config = settings::get();
+    let Some(import_config) = &config.import else {
+        bail!("No import source configured, see the import section of the example config");
+    };
+    let day = args.day.unwrap_or_else(today);
+    let iso_day = day.format(ISO_DAY)?;
+
+    let external = import::fetch_day(import_config, day)?;
+    let entries = import::map_entries(&external, &iso_day, import_config, config);
+    if args.dry_run {
+        for entry in &entries {
+            println!(
+                "{} {:>4}m {:<6} {:<12} {}",
+                entry.start_time,
+                entry.duration_mins,
+                entry.project_key,
+                entry.ticket_key.as_deref().unwrap_or("-"),
+                entry.description
+            );
+        }
+        println!("{} entries on {iso_day} (dry run)", entries.len());
+        return Ok(());
+    }
+
+    let command = Command::ImportEntries {
+        day,
+        entries,
+        skip_deleted: true,
+    };
+    match run_persist_command(command)? {
+        Event::EntriesImported {
+            day,
+            imported,
+            skipped,
+            deleted,
+        } => {
+            println!(
+                "Imported {imported} entries into {day}, skipped {skipped} existing and {deleted} \
+                 deleted before"
+            );
+            Ok(())
+        }
+        other => Err(eyre!("Unexpected response: {other:?}")),
+    }
+}
@@ -0,0 +1,57 @@
+use color_eyre::eyre::Result;
+
+use crate::{
+    config::{CONFIG_FILES, get_config_dir},
+    logging, persist, settings,
+};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Only print the path of this location, e.g. for `cd "$(sheet-shark paths --only data)"`
+    #[arg(long)]
+    pub only: Option<Location>,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Location {
+    Config,
+    Data,
+    Database,
+    Log,
+    Exports,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let config_dir = get_config_dir();
+    let data_dir = settings::data_dir();
+    let locations = [
+        (Location::Config, config_dir.clone()),
+        (Location::Data, data_dir.clone()),
+        (Location::Database, data_dir.join(persist::DATABASE_FILE)),
+        (Location::Log, logging::log_path()),
+        (Location::Exports, settings::export_dir()),
+    ];
+    if let Some(only) = args.only {
+        let (_, path) = locations
+            .iter()
+            .find(|(it, _)| *it == only)
+            .expect("all listed");
+        println!("{}", path.display());
+        return Ok(());
+    }
+
+    if let Some(profile) = settings::profile() {
+        println!("Profile   {profile}");
+    }
+    for (location, path) in &locations {
+        println!("{:<9} {}", format!("{location:?}"), path.display());
+    }
+    let config_files = CONFIG_FILES
+        .iter()
+        .map(|(file, _)| config_dir.join(file))
+        .filter(|it| it.exists());
+    for file in config_files {
+        println!("  read    {}", file.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use std::io::{Write, stdout};
+
+use clap::ValueEnum;
+use color_eyre::eyre::{Result, bail};
+use time::Date;
+
+use super::{parse_day, run_persist_command, today};
+use crate::{
+    persist::{Command, Event, TimeEntry},
+    shared::query::{self, Aggregate, EntryFilter, GroupBy},
+};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// First day to include (YYYY-MM-DD)
+    #[arg(long, value_parser = parse_day)]
+    pub from: Date,
+
+    /// Last day to include (YYYY-MM-DD), defaults to today
+    #[arg(long, value_parser = parse_day)]
+    pub to: Option<Date>,
+
+    /// Only entries of this project key, can be repeated
+    #[arg(long)]
+    pub project: Vec<String>,
+
+    /// Only entries of this ticket, can be repeated
+    #[arg(long)]
+    pub ticket: Vec<String>,
+
+    /// Only billable (true) or non-billable (false) entries
+    #[arg(long)]
+    pub billable: Option<bool>,
+
+    /// Also include breaks
+    #[arg(long)]
+    pub breaks: bool,
+
+    /// Print totals per day, project, ticket or work type instead of the entries
+    #[arg(long)]
+    pub group_by: Option<Grouping>,
+
+    #[arg(long, default_value = "text")]
+    pub format: Format,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Grouping {
+    Day,
+    Project,
+    Ticket,
+    WorkType,
+}
+
+impl From<Grouping> for GroupBy {
+    fn from(value: Grouping) -> Self {
+        match value {
+            Grouping::Day => GroupBy::Day,
+            Grouping::Project => GroupBy::Project,
+            Grouping::Ticket => GroupBy::Ticket,
+            Grouping::WorkType => GroupBy::WorkType,
+        }
+    }
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Format {
+    /// Aligned columns for reading
+    Text,
+    /// A JSON array, e.g. for jq
+    Json,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let to = args.to.unwrap_or_else(today);
+    if to < args.from {
+        bail!("--to ({to}) must not be before --from ({})", args.from);
+    }
+    let filter = EntryFilter {
+        projects: args.project,
+        tickets: args.ticket,
+        billable: args.billable,
+        include_breaks: args.breaks,
+    };
+
+    let command = Command::LoadEntriesInRange {
+        range: args.from..=to,
+    };
+    let entries = match run_persist_command(command)? {
+        Event::EntriesInRangeLoaded { entries, .. } => filter.apply(entries),
+        other => bail!("Unexpected response: {other:?}"),
+    };
+
+    let mut out = stdout().lock();
+    match (args.group_by, args.format) {
+        (None, Format::Json) => serde_json::to_writer_pretty(&mut out, &entries)?,
+        (None, Format::Text) => write_entries(&mut out, &entries)?,
+        (Some(group_by), format) => {
+            let aggregates = query::aggregate(&entries, group_by.into());
+            match format {
+                Format::Json => serde_json::to_writer_pretty(&mut out, &aggregates)?,
+                Format::Text => write_aggregates(&mut out, &aggregates)?,
+            }
+        }
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+fn write_entries(out: &mut impl Write, entries: &[TimeEntry]) -> Result<()> {
+    for entry in entries {
+        writeln!(
+            out,
+            "{} {} {:>5}m {:<4} {:<12} {}{}",
+            entry.timesheet_day,
+            entry.start_time,
+            entry.duration_mins,
+            entry.project_key,
+            entry.ticket_key.as_deref().unwrap_or("-"),
+            entry.description,
+            if entry.billable { " $" } else { "" },
+        )?;
+    }
+    let total: i64 = entries.iter().map(|it| it.duration_mins as i64).sum();
+    write!(out, "{} entries, {}", entries.len(), format_mins(total))?;
+    Ok(())
+}
+
+fn write_aggregates(out: &mut impl Write, aggregates: &[Aggregate]) -> Result<()> {
+    for aggregate in aggregates {
+        writeln!(
+            out,
+            "{:<12} {:>8} {:>8} $ {:>4} entries",
+            aggregate.key,
+            format_mins(aggregate.duration_mins),
+            format_mins(aggregate.billable_mins),
+            aggregate.entries,
+        )?;
+    }
+    let total: i64 = aggregates.iter().map(|it| it.duration_mins).sum();
+    write!(out, "Total {}", format_mins(total))?;
+    Ok(())
+}
+
+fn format_mins(mins: i64) -> String {
+    format!("{}h{:02}m", mins / 60, mins % 60)
+}
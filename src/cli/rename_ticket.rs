@@ -0,0 +1,52 @@
+use color_eyre::eyre::{Result, bail};
+use time::Date;
+
+use super::{parse_day, run_persist_command, today};
+use crate::persist::{Command, Event};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Current ticket key, e.g. SCRUM-17
+    pub from: String,
+
+    /// New ticket key, e.g. PLAT-3
+    pub to: String,
+
+    /// First day to rename in (YYYY-MM-DD)
+    #[arg(long, value_parser = parse_day)]
+    pub since: Date,
+
+    /// Last day to rename in (YYYY-MM-DD), defaults to today
+    #[arg(long, value_parser = parse_day)]
+    pub until: Option<Date>,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let until = args.until.unwrap_or_else(today);
+    if until < args.since {
+        bail!(
+            "--until ({until}) must not be before --since ({})",
+            args.since
+        );
+    }
+    if args.from == args.to {
+        bail!("Nothing to do, ticket keys are identical");
+    }
+
+    let command = Command::RenameTicket {
+        from: args.from,
+        to: args.to,
+        range: args.since..=until,
+    };
+    match run_persist_command(command)? {
+        Event::TicketRenamed {
+            from,
+            to,
+            rows_touched,
+        } => {
+            println!("Renamed {from} to {to} in {rows_touched} entries");
+            Ok(())
+        }
+        other => bail!("Unexpected response: {other:?}"),
+    }
+}
@@ -0,0 +1,66 @@
+use std::io::{Write, stdout};
+
+use clap::ValueEnum;
+use color_eyre::eyre::{Result, bail};
+use itertools::Itertools;
+
+use super::run_persist_command;
+use crate::{
+    export::csv,
+    persist::{Command, Event, ReportTable},
+    settings,
+};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Name of the report in the reports section of the config
+    pub name: String,
+
+    #[arg(long, default_value = "text")]
+    pub format: Format,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Format {
+    /// Aligned columns for reading
+    Text,
+    /// With a header row, e.g. for spreadsheets
+    Csv,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let config = settings::get();
+    let Some(report) = config.reports.get(&args.name) else {
+        let known = config.report_names().join(", ");
+        bail!("Unknown report {}, configured are: {known}", args.name);
+    };
+    let command = Command::RunReport {
+        name: args.name,
+        sql: report.sql.clone(),
+    };
+    let table = match run_persist_command(command)? {
+        Event::ReportLoaded { table, .. } => table,
+        other => bail!("Unexpected response: {other:?}"),
+    };
+
+    let mut out = stdout().lock();
+    match args.format {
+        Format::Text => write_table(&mut out, &table)?,
+        Format::Csv => csv::generate_report_csv_content(&table, &mut out)?,
+    }
+    Ok(())
+}
+
+fn write_table(out: &mut impl Write, table: &ReportTable) -> Result<()> {
+    let widths = table.column_widths();
+    for row in std::iter::once(&table.columns).chain(&table.rows) {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!("{value:<width$}"))
+            .join("  ");
+        writeln!(out, "{}", line.trim_end())?;
+    }
+    writeln!(out, "{} rows", table.rows.len())?;
+    Ok(())
+}
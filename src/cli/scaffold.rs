@@ -0,0 +1,130 @@
+use color_eyre::eyre::{Result, bail, eyre};
+use time::Date;
+
+use super::{ISO_DAY, parse_day, run_persist_command, today};
+use crate::{
+    config::Config,
+    persist::{Command, Event, TimeEntry, TimeEntryId},
+    settings,
+    shared::{
+        scaffold::{self, ScaffoldItem},
+        ticket_project,
+    },
+};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Day to fill in (YYYY-MM-DD), defaults to today
+    #[arg(long, value_parser = parse_day)]
+    pub day: Option<Date>,
+
+    /// Only print the entries that would be created
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let config = settings::get();
+    let day = args.day.unwrap_or_else(today);
+    let weekday = day.weekday();
+    let entries = scaffold::entries_for(config, weekday);
+    if entries.is_empty() {
+        println!("No scaffold configured for {weekday}");
+        return Ok(());
+    }
+    let templates = if scaffold::needs_templates(entries) {
+        match run_persist_command(Command::LoadTemplates)? {
+            Event::TemplatesLoaded(templates) => templates,
+            other => bail!("Unexpected response: {other:?}"),
+        }
+    } else {
+        vec![]
+    };
+    let items = scaffold::resolve(entries, &templates, config)?;
+
+    // Like in the TUI, only empty days start with the scaffold, so that runs can be repeated
+    let existing = match run_persist_command(Command::LoadTimesheet { day })? {
+        Event::TimesheetLoaded { entries, .. } => entries.len(),
+        other => bail!("Unexpected response: {other:?}"),
+    };
+    if existing > 0 {
+        println!("{day} already has {existing} entries, leaving it as it is");
+        return Ok(());
+    }
+
+    let iso_day = day.format(ISO_DAY)?;
+    let entries = items
+        .into_iter()
+        .map(|item| into_entry(item, &iso_day, config))
+        .collect::<Vec<_>>();
+    for entry in &entries {
+        println!(
+            "{} {:>4}m {:<6} {:<12} {}",
+            entry.start_time,
+            entry.duration_mins,
+            entry.project_key,
+            entry.ticket_key.as_deref().unwrap_or("-"),
+            entry.description
+        );
+    }
+    if args.dry_run {
+        println!(
+            "{} entries of the {weekday} scaffold on {iso_day} (dry run)",
+            entries.len()
+        );
+        return Ok(());
+    }
+
+    // Entries deleted from the day are left out, so that cron doesn't bring them back
+    let command = Command::ImportEntries {
+        day,
+        entries,
+        skip_deleted: true,
+    };
+    match run_persist_command(command)? {
+        Event::EntriesImported {
+            imported,
+            deleted: 0,
+            ..
+        } => {
+            println!("Created {imported} entries of the {weekday} scaffold on {iso_day}");
+            Ok(())
+        }
+        Event::EntriesImported {
+            imported, deleted, ..
+        } => {
+            println!(
+                "Created {imported} entries of the {weekday} scaffold on {iso_day}, left out \
+                 {deleted} deleted before"
+            );
+            Ok(())
+        }
+        other => Err(eyre!("Unexpected response: {other:?}")),
+    }
+}
+
+/// Tickets without a project in the scaffold are booked on the one configured for them
+fn into_entry(item: ScaffoldItem, iso_day: &str, config: &Config) -> TimeEntry {
+    let project_key = item
+        .project_key
+        .or_else(|| {
+            let ticket = item.ticket_key.as_deref()?;
+            ticket_project::project_for(config, ticket).map(str::to_string)
+        })
+        .unwrap_or_else(|| config.default_project_key.clone());
+    TimeEntry {
+        id: TimeEntryId::new().to_string(),
+        timesheet_day: iso_day.to_string(),
+        project_key,
+        ticket_key: item.ticket_key,
+        duration_mins: item.duration_mins as i32,
+        description: item.description,
+        start_time: item.start.format("%H:%M").to_string(),
+        billable: item.billable,
+        work_type: None,
+        carry_over: false,
+        exact: false,
+        flags: String::new(),
+        revision: 0,
+    }
+}
@@ -0,0 +1,40 @@
+use std::{
+    io::{Write, stdout},
+    path::PathBuf,
+};
+
+use color_eyre::eyre::{Result, bail};
+
+use super::VERSION_MESSAGE;
+use crate::{logging, settings, shared::log_lines};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Write to this file instead of printing it
+    #[arg(long, short)]
+    pub output: Option<PathBuf>,
+}
+
+pub fn run(args: Args) -> Result<()> {
+    // This command has a session of its own, without any actions, so that one is skipped
+    let keep_sessions = settings::get().action_log.keep_sessions;
+    let Some((path, content)) = (0..keep_sessions.max(1))
+        .map(logging::action_log_path)
+        .filter_map(|path| std::fs::read_to_string(&path).ok().map(|it| (path, it)))
+        .find(|(_, content)| !content.is_empty())
+    else {
+        bail!("No actions logged yet, enable `action_log` in the config and reproduce the problem");
+    };
+    let export = format!(
+        "# sheet-shark {VERSION_MESSAGE}, actions of the last session\n{}",
+        log_lines::anonymize(&content)
+    );
+    match args.output {
+        Some(output) => {
+            std::fs::write(&output, export)?;
+            println!("Exported {} to {}", path.display(), output.display());
+        }
+        None => write!(stdout().lock(), "{export}")?,
+    }
+    Ok(())
+}
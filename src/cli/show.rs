@@ -0,0 +1,55 @@
+use std::io::{Write, stdout};
+
+use clap::ValueEnum;
+use color_eyre::eyre::{Result, bail};
+use time::Date;
+
+use super::{parse_day, run_persist_command, today};
+use crate::{
+    export::day_table::{self, TableStyle},
+    persist::{Command, Event},
+    settings,
+};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {
+    /// Day to show (YYYY-MM-DD), defaults to today
+    #[arg(long, value_parser = parse_day)]
+    pub day: Option<Date>,
+
+    #[arg(long, default_value = "text")]
+    pub format: Format,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum Format {
+    /// Aligned columns for reading
+    Text,
+    /// A Markdown table, e.g. for notes or chats
+    Markdown,
+}
+
+impl From<Format> for TableStyle {
+    fn from(value: Format) -> Self {
+        match value {
+            Format::Text => TableStyle::Text,
+            Format::Markdown => TableStyle::Markdown,
+        }
+    }
+}
+
+pub fn run(args: Args) -> Result<()> {
+    let day = args.day.unwrap_or_else(today);
+    let entries = match run_persist_command(Command::LoadTimesheet { day })? {
+        Event::TimesheetLoaded { entries, .. } => entries,
+        other => bail!("Unexpected response: {other:?}"),
+    };
+    let mut out = stdout().lock();
+    if entries.is_empty() {
+        writeln!(out, "No entries on {day}")?;
+        return Ok(());
+    }
+    let table = day_table::generate_day_table(&entries, args.format.into(), settings::get());
+    write!(out, "{table}")?;
+    Ok(())
+}
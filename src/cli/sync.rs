@@ -0,0 +1,22 @@
+use color_eyre::eyre::{Result, bail};
+use itertools::Itertools;
+
+use super::run_persist_command;
+use crate::persist::{Command, Event};
+
+#[derive(clap::Args, Debug)]
+pub struct Args {}
+
+pub fn run(_args: Args) -> Result<()> {
+    match run_persist_command(Command::Sync)? {
+        Event::Synced { pulled, pushed } if pulled.is_empty() => {
+            println!("Synced, uploaded {pushed} days, nothing changed elsewhere")
+        }
+        Event::Synced { pulled, pushed } => println!(
+            "Synced, uploaded {pushed} days, took changes made elsewhere on {}",
+            pulled.iter().join(", ")
+        ),
+        other => bail!("Unexpected response: {other:?}"),
+    }
+    Ok(())
+}
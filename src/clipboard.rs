@@ -0,0 +1,26 @@
+use std::sync::Mutex;
+
+use color_eyre::{Result, eyre::eyre};
+use copypasta::{ClipboardContext, ClipboardProvider};
+use lazy_static::lazy_static;
+
+lazy_static! {
+    /// Shared by all components, `None` if there is no clipboard, e.g. without a display
+    static ref CLIPBOARD: Mutex<Option<ClipboardContext>> = ClipboardContext::new().ok().into();
+}
+
+pub fn copy(text: String) -> Result<()> {
+    let mut clip = CLIPBOARD.lock().expect("clipboard mutex not poisoned");
+    let clip = clip
+        .as_mut()
+        .ok_or_else(|| eyre!("No clipboard available"))?;
+    clip.set_contents(text).map_err(|err| eyre!("{err}"))
+}
+
+pub fn paste() -> Result<String> {
+    let mut clip = CLIPBOARD.lock().expect("clipboard mutex not poisoned");
+    let clip = clip
+        .as_mut()
+        .ok_or_else(|| eyre!("No clipboard available"))?;
+    clip.get_contents().map_err(|err| eyre!("{err}"))
+}
@@ -11,6 +11,7 @@ use crate::{action::Action, config::Config, persist, tui::Event};
 pub mod calendar;
 pub mod fps;
 pub mod home;
+pub mod logs;
 pub mod statusbar;
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
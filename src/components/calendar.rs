@@ -1,26 +1,64 @@
-use std::sync::Mutex;
+use std::ops::RangeInclusive;
 
 use color_eyre::{Result, eyre::Context};
-use copypasta::{ClipboardContext, ClipboardProvider};
 use crossterm::event::{KeyCode, KeyEvent};
 use educe::Educe;
+use itertools::Itertools;
 use lazy_static::lazy_static;
 use ratatui::prelude::*;
-use time::{Date, Duration, OffsetDateTime, format_description};
+use time::{
+    Date, Duration, OffsetDateTime, Weekday, format_description, format_description::FormatItem,
+    macros::format_description,
+};
 use tokio::sync::mpsc::UnboundedSender;
 
 use super::Component;
 use crate::{
     action::{Action, Page, RelevantKey},
+    clipboard,
+    components::home::export::{export_range_csv, export_report_csv, run_hooks},
+    export::booking_text,
     layout::LayoutSlot,
-    persist::{self, Command, Event, TimeEntry},
-    shared::summary::{SummaryJson, TimesheetSummary},
+    metrics,
+    persist::{self, Command, CommandKind, Event, TimeEntry, journal},
+    settings,
+    shared::{
+        insights::Feature,
+        month_of,
+        summary::{SummaryGrouping, SummaryJson, TimesheetSummary},
+        tz::DayZone,
+        validation::{self, Violation},
+        week_of,
+    },
+    widgets::text_popup::{TextInput, TextInputOutcome, TextPopup},
 };
 
+mod cache;
+use cache::{CachedDay, CalendarCache};
+
+mod profile_picker;
+use profile_picker::{PickerOutcome, ProfilePicker};
+
 mod widgets;
 use widgets::TimesheetCalendar;
 
+mod year_view;
+use year_view::YearView;
+
 mod export;
+use export::Booking;
+
+mod jira_picker;
+use jira_picker::{JiraOutcome, JiraPicker};
+
+mod jira_problems;
+use jira_problems::{JiraProblems, JiraProblemsOutcome};
+
+mod insights;
+use insights::InsightsPopup;
+
+mod reports;
+use reports::{ReportOutcome, ReportPopup};
 
 #[derive(Educe)]
 #[educe(Default)]
@@ -36,6 +74,25 @@ pub struct Calendar {
     days_with_timesheets: Vec<Date>,
     summary: Option<TimesheetSummary>,
     entries: Vec<TimeEntry>,
+    notes: String,
+    notes_input: Option<TextInput>,
+    /// Number of the week to jump to, in the year of the selected day
+    week_input: Option<TextInput>,
+    profile_picker: Option<ProfilePicker>,
+    jira_picker: Option<JiraPicker>,
+    jira_problems: Option<JiraProblems>,
+    year_view: Option<YearView>,
+    /// Lists the descriptions under each ticket of the summary
+    show_descriptions: bool,
+    /// What the rows of the summary and the copied JSON are grouped by
+    grouping: SummaryGrouping,
+    insights: Option<InsightsPopup>,
+    reports: Option<ReportPopup>,
+    /// Range of the combined CSV export that is waiting for its entries
+    pending_range_export: Option<RangeInclusive<Date>>,
+    /// Week of the Jira export that is waiting for its entries
+    pending_week_jira: Option<RangeInclusive<Date>>,
+    cache: CalendarCache,
 }
 
 impl Component for Calendar {
@@ -59,16 +116,132 @@ impl Component for Calendar {
     }
 
     fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if let Some(input) = &mut self.notes_input {
+            match input.handle_key_event(key) {
+                TextInputOutcome::Editing => {}
+                TextInputOutcome::Submitted(notes) => {
+                    self.notes_input = None;
+                    self.persist_tx
+                        .as_mut()
+                        .expect("persist tx")
+                        .send(Command::StoreNotes {
+                            day: self.day,
+                            notes,
+                        })?;
+                }
+                TextInputOutcome::Cancelled => self.notes_input = None,
+            }
+            return Ok(None);
+        }
+        if let Some(input) = &mut self.week_input {
+            return match input.handle_key_event(key) {
+                TextInputOutcome::Editing => Ok(None),
+                TextInputOutcome::Submitted(week) => {
+                    self.week_input = None;
+                    Ok(self.jump_to_week(&week))
+                }
+                TextInputOutcome::Cancelled => {
+                    self.week_input = None;
+                    Ok(None)
+                }
+            };
+        }
+        if let Some(picker) = &mut self.profile_picker {
+            return match picker.handle_key_event(key) {
+                PickerOutcome::Picking => Ok(None),
+                PickerOutcome::Picked(profile) => {
+                    self.profile_picker = None;
+                    Ok(Some(Action::SwitchProfile(profile)))
+                }
+                PickerOutcome::Cancelled => {
+                    self.profile_picker = None;
+                    Ok(None)
+                }
+            };
+        }
+        if let Some(insights) = &mut self.insights {
+            if !insights.handle_key_event(key) {
+                self.insights = None;
+            }
+            return Ok(None);
+        }
+        if let Some(reports) = &mut self.reports {
+            return match reports.handle_key_event(key) {
+                ReportOutcome::Open => Ok(None),
+                ReportOutcome::Run(name) => {
+                    let sql = settings::get().reports[&name].sql.clone();
+                    self.persist_tx
+                        .as_mut()
+                        .expect("persist tx")
+                        .send(Command::RunReport { name, sql })?;
+                    Ok(None)
+                }
+                ReportOutcome::Export(name, table) => {
+                    let today = OffsetDateTime::now_local()
+                        .wrap_err("find local offset for date")?
+                        .date();
+                    let message = match export_report_csv(&name, &table, today) {
+                        Ok(path) => {
+                            let message = format!("✅ Exported report to {}", path.display());
+                            run_hooks(path, today, self.action_tx.clone());
+                            message
+                        }
+                        Err(e) => format!("❌ Export failed: {e}"),
+                    };
+                    Ok(Some(Action::SetStatusLine(message)))
+                }
+                ReportOutcome::Closed => {
+                    self.reports = None;
+                    Ok(None)
+                }
+            };
+        }
+        if let Some(problems) = &mut self.jira_problems {
+            return match problems.handle_key_event(key) {
+                JiraProblemsOutcome::Open => Ok(None),
+                JiraProblemsOutcome::JumpTo(entry_idx) => {
+                    self.jira_problems = None;
+                    if let Some(entry) = entry_idx.and_then(|idx| self.entries.get(idx)) {
+                        self.action_tx
+                            .as_mut()
+                            .expect("action tx")
+                            .send(Action::SelectEntry(entry.id.clone()))?;
+                    }
+                    Ok(Some(Action::SetActivePage(Page::Home { day: self.day })))
+                }
+                JiraProblemsOutcome::Closed => {
+                    self.jira_problems = None;
+                    Ok(None)
+                }
+            };
+        }
+        if let Some(picker) = &mut self.jira_picker {
+            return match picker.handle_key_event(key) {
+                JiraOutcome::Picking => Ok(None),
+                JiraOutcome::Confirmed(bookings) => {
+                    self.jira_picker = None;
+                    self.record_usage(Feature::JiraExport);
+                    Ok(Some(Action::SetStatusLine(open_bookings(&bookings))))
+                }
+                JiraOutcome::Cancelled => {
+                    self.jira_picker = None;
+                    Ok(Some(Action::SetStatusLine("Jira export cancelled".into())))
+                }
+            };
+        }
         match key.code {
             _ if self.handle_day_movement(key) => Ok(None),
             KeyCode::Enter => Ok(Some(Action::SetActivePage(Page::Home { day: self.day }))),
             KeyCode::Char('c') => {
-                if let Some(_summary) = &self.summary {
-                    let summary_json = SummaryJson::from_entries(self.entries.clone());
+                if let Some(summary) = &self.summary {
+                    let summary_json = SummaryJson::from_summary(
+                        summary.clone(),
+                        settings::get().export.defrag_strategy,
+                        self.grouping,
+                    );
                     let json = serde_json::to_string(&summary_json)
                         .context("serializing timesheet summary")?;
-                    let mut clip = CLIPBOARD.lock().expect("clipboard mutex not poisoned");
-                    match clip.set_contents(json) {
+                    match clipboard::copy(json) {
                         Ok(_) => Ok(Some(Action::SetStatusLine("Summary copied!".into()))),
                         Err(_) => Ok(Some(Action::SetStatusLine("Failed to copy".into()))),
                     }
@@ -76,10 +249,45 @@ impl Component for Calendar {
                     Ok(Some(Action::SetStatusLine("No summary available".into())))
                 }
             }
+            KeyCode::Char('b') => {
+                let Some(summary) = &self.summary else {
+                    return Ok(Some(Action::SetStatusLine(
+                        "No timesheet data to copy".into(),
+                    )));
+                };
+                let config = &settings::get().export;
+                let text = booking_text::generate_booking_text(
+                    &summary.entries,
+                    &config.booking_line,
+                    &config.booking_separator,
+                );
+                if text.is_empty() {
+                    return Ok(Some(Action::SetStatusLine("No bookings to copy".into())));
+                }
+                match clipboard::copy(text) {
+                    Ok(_) => {
+                        self.record_usage(Feature::CopyBookings);
+                        Ok(Some(Action::SetStatusLine("Bookings copied!".into())))
+                    }
+                    Err(_) => Ok(Some(Action::SetStatusLine("Failed to copy".into()))),
+                }
+            }
             KeyCode::Char('e') => {
+                let problems = self.check_jira_export();
+                if !problems.is_empty() {
+                    self.jira_problems = Some(JiraProblems::new(problems));
+                    return Ok(None);
+                }
                 if let Some(summary) = &self.summary {
-                    match export::export(self.day, summary) {
-                        Ok(()) => Ok(Some(Action::SetStatusLine("Exported!".into()))),
+                    let comment = &settings::get().export.jira_comment;
+                    match export::plan_bookings(self.day, summary, comment) {
+                        Ok(bookings) if bookings.is_empty() => Ok(Some(Action::SetStatusLine(
+                            "No tickets with a Jira URL to export".into(),
+                        ))),
+                        Ok(bookings) => {
+                            self.jira_picker = Some(JiraPicker::new(bookings));
+                            Ok(None)
+                        }
                         Err(e) => Ok(Some(Action::SetStatusLine(format!("Export failed: {e}")))),
                     }
                 } else {
@@ -88,8 +296,89 @@ impl Component for Calendar {
                     )))
                 }
             }
+            KeyCode::Char('E') => {
+                let range = week_of(self.day);
+                self.persist_tx.as_mut().expect("persist tx").send(
+                    Command::LoadEntriesInRange {
+                        range: range.clone(),
+                    },
+                )?;
+                self.pending_week_jira = Some(range);
+                Ok(None)
+            }
+            KeyCode::Char('M') => {
+                let range = month_of(self.day);
+                self.persist_tx.as_mut().expect("persist tx").send(
+                    Command::LoadEntriesInRange {
+                        range: range.clone(),
+                    },
+                )?;
+                self.pending_range_export = Some(range);
+                Ok(Some(Action::SetStatusLine("Exporting month...".into())))
+            }
+            KeyCode::Char('y') => {
+                if self.year_view.take().is_none() {
+                    self.show_year_view()?;
+                }
+                Ok(None)
+            }
+            KeyCode::Char('d') => {
+                self.show_descriptions = !self.show_descriptions;
+                Ok(None)
+            }
+            KeyCode::Char('g') => {
+                self.grouping = self.grouping.next();
+                let label = self.grouping.label().to_lowercase();
+                Ok(Some(Action::SetStatusLine(format!(
+                    "Summary grouped by {label}"
+                ))))
+            }
+            KeyCode::Char('i') => {
+                let today = OffsetDateTime::now_local()
+                    .wrap_err("find local offset for date")?
+                    .date();
+                let insights = InsightsPopup::new(today);
+                let persist_tx = self.persist_tx.as_mut().expect("persist tx");
+                persist_tx.send(Command::LoadUsage {
+                    range: insights.range(),
+                })?;
+                persist_tx.send(Command::LoadEntriesInRange {
+                    range: insights.range(),
+                })?;
+                self.insights = Some(insights);
+                Ok(None)
+            }
+            KeyCode::Char('R') => {
+                let config = settings::get();
+                if config.reports.is_empty() {
+                    return Ok(Some(Action::SetStatusLine(
+                        "No reports configured, see the reports section of the config".into(),
+                    )));
+                }
+                self.reports = Some(ReportPopup::new(config));
+                Ok(None)
+            }
+            KeyCode::Char('n') => {
+                self.notes_input = Some(TextInput::new(self.notes.clone()));
+                Ok(None)
+            }
+            KeyCode::Char('W') => {
+                self.week_input = Some(TextInput::new(String::new()));
+                Ok(None)
+            }
+            KeyCode::Char('P') => {
+                let config = settings::get();
+                if config.profiles.is_empty() {
+                    return Ok(Some(Action::SetStatusLine(
+                        "No profiles configured, see the profiles section of the config".into(),
+                    )));
+                }
+                self.profile_picker = Some(ProfilePicker::new(config, settings::profile()));
+                Ok(None)
+            }
+            KeyCode::Char('s') => Ok(Some(Action::SyncNow)),
             KeyCode::Char('f') => {
-                let data_dir = crate::config::get_data_dir();
+                let data_dir = settings::data_dir();
                 match std::process::Command::new("xdg-open")
                     .arg(&data_dir)
                     .spawn()
@@ -121,31 +410,170 @@ impl Component for Calendar {
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
         let area = crate::layout::main_vert(LayoutSlot::MainCanvas, area);
 
-        let calendar_widget =
-            TimesheetCalendar::new(self.day, &self.days_with_timesheets, self.summary.as_ref());
+        let calendar_widget = TimesheetCalendar::new(
+            self.day,
+            &self.days_with_timesheets,
+            self.summary.as_ref(),
+            &self.notes,
+        )
+        .year_view(self.year_view.as_ref())
+        .descriptions(self.show_descriptions)
+        .grouping(self.grouping);
         frame.render_widget(calendar_widget, area);
 
+        if let Some(input) = &self.notes_input {
+            frame.render_widget(TextPopup::new(" 📝 Notes for this day ", input), area);
+        }
+        if let Some(input) = &self.week_input {
+            let title = format!(" 📅 Jump to week in {} ", self.day.to_iso_week_date().0);
+            frame.render_widget(TextPopup::new(title, input), area);
+        }
+        if let Some(picker) = &mut self.profile_picker {
+            picker.draw(frame, area);
+        }
+        if let Some(picker) = &mut self.jira_picker {
+            picker.draw(frame, area);
+        }
+        if let Some(problems) = &mut self.jira_problems {
+            problems.draw(frame, area);
+        }
+        if let Some(insights) = &mut self.insights {
+            insights.draw(frame, area);
+        }
+        if let Some(reports) = &mut self.reports {
+            reports.draw(frame, area);
+        }
+
         Ok(())
     }
 
     fn handle_persisted(&mut self, event: persist::Event) -> Result<Option<Action>> {
         match event {
-            Event::TimesheetsOfMonthLoaded { day, timesheets } if day == self.day => {
-                self.days_with_timesheets = vec![];
+            Event::TimesheetsOfMonthLoaded { day, timesheets } => {
                 let format = format_description::parse("[year]-[month]-[day]")?;
-                for timesheet in timesheets {
-                    if let Ok(day) = Date::parse(&timesheet.day, &format) {
-                        self.days_with_timesheets.push(day);
-                    }
+                let days_with_timesheets = timesheets
+                    .iter()
+                    .filter_map(|timesheet| Date::parse(&timesheet.day, &format).ok())
+                    .collect();
+                self.cache.store_month(day, days_with_timesheets);
+                if month_of(day) == month_of(self.day) {
+                    self.show_cached_month();
                 }
             }
             Event::TimesheetLoaded {
                 day,
-                timesheet: _,
+                timesheet,
                 entries,
-            } if day == self.day => {
-                self.entries = entries.clone();
-                self.summary = Some(TimesheetSummary::new(entries));
+                ..
+            } => {
+                // Also caches days loaded by the timesheet view
+                let summary = TimesheetSummary::new(entries.clone(), settings::get());
+                let cached = CachedDay {
+                    notes: timesheet.notes,
+                    entries,
+                    summary,
+                };
+                self.cache.store_day(day, cached);
+                if day == self.day {
+                    self.show_cached_day();
+                }
+            }
+            Event::EntryStored { .. }
+            | Event::Deleted
+            | Event::EntryRepaired { .. }
+            | Event::TicketRenamed { .. } => {
+                // Not tied to a day we know of
+                self.cache.clear();
+            }
+            Event::DailyTotalsLoaded { range, totals } => {
+                if let Some(year_view) = &mut self.year_view
+                    && year_view.range() == range
+                {
+                    year_view.handle_loaded(totals);
+                }
+            }
+            Event::EntriesImported { day, .. } => {
+                self.cache.invalidate_day(day);
+            }
+            Event::Synced { pulled, .. } => {
+                pulled
+                    .iter()
+                    .for_each(|day| self.cache.invalidate_day(*day));
+                if !self.suspended && pulled.iter().any(|it| month_of(*it) == month_of(self.day)) {
+                    self.fetch_for_new_day()?;
+                }
+            }
+            Event::Cancelled { .. } => {
+                self.pending_range_export = None;
+                self.pending_week_jira = None;
+            }
+            Event::EntriesInRangeLoaded { range, entries }
+                if self.pending_range_export.as_ref() == Some(&range) =>
+            {
+                self.pending_range_export = None;
+                let unusual_days = days_outside_limits(&range, &entries)?;
+                let message = match export_range_csv(&range, entries) {
+                    Ok(path) => {
+                        self.record_usage(Feature::MonthExport);
+                        let message = if unusual_days.is_empty() {
+                            format!("✅ Exported month to {}", path.display())
+                        } else {
+                            format!(
+                                "⚠️ Exported month to {}, check the working time of {}",
+                                path.display(),
+                                unusual_days.iter().join(", ")
+                            )
+                        };
+                        run_hooks(path, *range.start(), self.action_tx.clone());
+                        message
+                    }
+                    Err(e) => format!("❌ Export failed: {e}"),
+                };
+                return Ok(Some(Action::SetStatusLine(message)));
+            }
+            Event::EntriesInRangeLoaded { range, entries }
+                if self.pending_week_jira.as_ref() == Some(&range) =>
+            {
+                self.pending_week_jira = None;
+                return Ok(self.offer_week_bookings(entries));
+            }
+            Event::EntriesInRangeLoaded { range, entries }
+                if self.insights.as_ref().is_some_and(|it| it.range() == range) =>
+            {
+                if let Some(insights) = &mut self.insights {
+                    insights.handle_entries_loaded(entries);
+                }
+            }
+            Event::UsageLoaded { range, metrics } => {
+                if let Some(insights) = &mut self.insights
+                    && insights.range() == range
+                {
+                    insights.handle_usage_loaded(metrics);
+                }
+            }
+            Event::ReportLoaded { name, table } => {
+                if let Some(reports) = &mut self.reports {
+                    reports.handle_loaded(name, table);
+                }
+            }
+            Event::Failure(err) if err.command == CommandKind::RunReport => {
+                if let Some(reports) = &mut self.reports
+                    && reports.is_running()
+                {
+                    reports.handle_failed(&err.details);
+                }
+            }
+            Event::NotesStored { day, notes } => {
+                self.cache.invalidate_day(day);
+                if day != self.day {
+                    return Ok(None);
+                }
+                self.notes = notes;
+                if !self.suspended {
+                    // the day might not have had a timesheet before
+                    self.fetch_for_new_day()?;
+                    return Ok(Some(Action::SetStatusLine("📝 Notes saved".into())));
+                }
             }
             _ => {}
         }
@@ -163,6 +591,9 @@ impl Component for Calendar {
                 self.day = day;
                 self.suspended = false;
                 self.fetch_for_new_day()?;
+                if self.year_view.is_some() {
+                    self.show_year_view()?;
+                }
             }
             Action::SetActivePage(_) => {
                 self.suspended = true;
@@ -174,6 +605,68 @@ impl Component for Calendar {
 }
 
 impl Calendar {
+    /// Problems that would leave the selected day half-booked in Jira
+    fn check_jira_export(&self) -> Vec<Violation> {
+        let day = self.day.to_string();
+        let unsaved = journal::unconfirmed(&settings::data_dir())
+            .inspect_err(|err| tracing::warn!("Not checking for unsaved changes: {err:?}"))
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|it| it.timesheet_day == day)
+            .collect_vec();
+        validation::check_jira_export(
+            &self.entries,
+            &unsaved,
+            DayZone::of(self.day, None),
+            settings::get(),
+        )
+    }
+
+    /// Opens the picker with a booking per ticket of the week, or the problems of its days
+    fn offer_week_bookings(&mut self, entries: Vec<TimeEntry>) -> Option<Action> {
+        let config = settings::get();
+        let unsaved = journal::unconfirmed(&settings::data_dir())
+            .inspect_err(|err| tracing::warn!("Not checking for unsaved changes: {err:?}"))
+            .unwrap_or_default();
+        let mut problems = vec![];
+        let mut days = vec![];
+        for (day, entries) in &entries.into_iter().chunk_by(|it| it.timesheet_day.clone()) {
+            let entries = entries.collect_vec();
+            let unsaved = unsaved
+                .iter()
+                .filter(|it| it.timesheet_day == day)
+                .cloned()
+                .collect_vec();
+            let Ok(date) = Date::parse(&day, ISO_DAY) else {
+                continue;
+            };
+            let zone = DayZone::of(date, None);
+            for violation in validation::check_jira_export(&entries, &unsaved, zone, config) {
+                problems.push(Violation {
+                    // Entries of other days can't be jumped to
+                    entry_idx: None,
+                    message: format!("{day}: {}", violation.message),
+                    ..violation
+                });
+            }
+            days.push((date, TimesheetSummary::new(entries, config)));
+        }
+        if !problems.is_empty() {
+            self.jira_problems = Some(JiraProblems::new(problems));
+            return None;
+        }
+        match export::plan_week_bookings(&days, config) {
+            Ok(bookings) if bookings.is_empty() => Some(Action::SetStatusLine(
+                "No tickets with a Jira URL to export this week".into(),
+            )),
+            Ok(bookings) => {
+                self.jira_picker = Some(JiraPicker::new(bookings));
+                None
+            }
+            Err(e) => Some(Action::SetStatusLine(format!("Export failed: {e}"))),
+        }
+    }
+
     fn handle_day_movement(&mut self, key: KeyEvent) -> bool {
         let new_day = match key.code {
             KeyCode::PageUp => self.day.checked_sub(Duration::days(365)),
@@ -185,32 +678,172 @@ impl Calendar {
             _ => return false,
         }
         .expect("date math not to overflow");
+        self.move_to(new_day);
+        true
+    }
+
+    fn move_to(&mut self, new_day: Date) {
         self.day = new_day;
         let _ = self.fetch_for_new_day();
-        true
+        if self
+            .year_view
+            .as_ref()
+            .is_some_and(|it| it.year != new_day.year())
+        {
+            let _ = self.show_year_view();
+        }
     }
 
-    fn fetch_for_new_day(&mut self) -> Result<()> {
-        self.persist_tx
-            .as_mut()
-            .expect("persist tx")
-            .send(Command::LoadTimesheetsOfMonth { day: self.day })?;
+    /// Moves to the Monday of the ISO week with the number, like sprint planning refers to
+    /// weeks, in the ISO year of the selected day
+    fn jump_to_week(&mut self, week: &str) -> Option<Action> {
+        let year = self.day.to_iso_week_date().0;
+        let monday = week
+            .trim()
+            .parse()
+            .ok()
+            .and_then(|week| Date::from_iso_week_date(year, week, Weekday::Monday).ok());
+        match monday {
+            Some(monday) => {
+                self.move_to(monday);
+                None
+            }
+            None => Some(Action::SetStatusLine(format!(
+                "No week {} in {year}",
+                week.trim()
+            ))),
+        }
+    }
+
+    fn record_usage(&self, feature: Feature) {
+        metrics::record(self.persist_tx.as_ref().expect("persist tx"), feature);
+    }
+
+    /// Loads the totals each time, as days might have been edited since
+    fn show_year_view(&mut self) -> Result<()> {
+        let year_view = YearView::new(self.day.year());
         self.persist_tx
             .as_mut()
             .expect("persist tx")
-            .send(Command::LoadTimesheet { day: self.day })?;
-        self.days_with_timesheets = vec![];
+            .send(Command::LoadDailyTotals {
+                range: year_view.range(),
+            })?;
+        self.year_view = Some(year_view);
         Ok(())
     }
+
+    /// Shows what is cached for the day and only loads the rest
+    fn fetch_for_new_day(&mut self) -> Result<()> {
+        if !self.show_cached_month() {
+            if !self
+                .days_with_timesheets
+                .iter()
+                .any(|it| month_of(*it) == month_of(self.day))
+            {
+                self.days_with_timesheets = vec![];
+            }
+            self.persist_tx
+                .as_mut()
+                .expect("persist tx")
+                .send(Command::LoadTimesheetsOfMonth { day: self.day })?;
+        }
+        if !self.show_cached_day() {
+            self.summary = None;
+            self.entries = vec![];
+            self.notes = String::new();
+            self.persist_tx
+                .as_mut()
+                .expect("persist tx")
+                .send(Command::LoadTimesheet { day: self.day })?;
+        }
+        Ok(())
+    }
+
+    fn show_cached_month(&mut self) -> bool {
+        let Some(days) = self.cache.month(self.day) else {
+            return false;
+        };
+        self.days_with_timesheets = days.clone();
+        true
+    }
+
+    fn show_cached_day(&mut self) -> bool {
+        let Some(cached) = self.cache.day(self.day).cloned() else {
+            return false;
+        };
+        self.notes = cached.notes;
+        self.entries = cached.entries;
+        self.summary = Some(cached.summary);
+        true
+    }
+}
+
+/// Opens the bookings and names the tickets that did and did not open
+fn open_bookings(bookings: &[Booking]) -> String {
+    let failed = export::open_bookings(bookings);
+    let opened = bookings
+        .iter()
+        .filter(|booking| !failed.iter().any(|(it, _)| it.url == booking.url))
+        .map(|booking| booking.ticket_key.as_str())
+        .join(", ");
+    if failed.is_empty() {
+        return format!("✅ Opened {opened}");
+    }
+    let failed = failed
+        .iter()
+        .map(|(booking, err)| format!("{} ({err})", booking.ticket_key))
+        .join(", ");
+    if opened.is_empty() {
+        format!("❌ Failed to open {failed}")
+    } else {
+        format!("⚠️ Opened {opened}, failed to open {failed}")
+    }
+}
+
+/// Days of the range worked longer than allowed, or workdays logged too short, which are
+/// worth a look before closing the month
+fn days_outside_limits(range: &RangeInclusive<Date>, entries: &[TimeEntry]) -> Result<Vec<Date>> {
+    let config = settings::get();
+    let today = OffsetDateTime::now_local()
+        .wrap_err("find local offset for date")?
+        .date();
+    let by_day = entries
+        .iter()
+        .into_group_map_by(|it| it.timesheet_day.clone());
+    let mut days = vec![];
+    let mut day = *range.start();
+    while day <= *range.end() {
+        let entries = by_day
+            .get(&day.format(ISO_DAY)?)
+            .map(|it| it.iter().map(|it| (*it).clone()).collect_vec())
+            .unwrap_or_default();
+        let min_applies = day < today && settings::holiday_of(day).is_none();
+        if !validation::check_day_duration(&entries, day.weekday(), min_applies, config).is_empty()
+        {
+            days.push(day);
+        }
+        day = day.next_day().expect("not to exceed date range");
+    }
+    Ok(days)
 }
 
 lazy_static! {
     static ref KEYS: Vec<RelevantKey> = vec![
         RelevantKey::new("Enter", "Select"),
         RelevantKey::new("c", "Copy summary"),
-        RelevantKey::new("e", "Export to Jira"),
+        RelevantKey::new("b", "Copy bookings"),
+        RelevantKey::new("e/E", "Export day/week to Jira"),
+        RelevantKey::new("M", "Export month CSV"),
+        RelevantKey::new("n", "Notes"),
+        RelevantKey::new("y", "Year"),
+        RelevantKey::new("W", "Jump to week"),
+        RelevantKey::new("d", "Descriptions"),
+        RelevantKey::new("g", "Group by"),
+        RelevantKey::new("i", "Insights"),
+        RelevantKey::new("R", "Reports"),
+        RelevantKey::new("P", "Profile"),
+        RelevantKey::new("s", "Sync"),
     ];
-    static ref CLIPBOARD: Mutex<ClipboardContext> = ClipboardContext::new()
-        .expect("init clipboard context")
-        .into();
 }
+
+const ISO_DAY: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
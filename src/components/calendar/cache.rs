@@ -0,0 +1,65 @@
+use time::{Date, Month};
+
+use crate::{
+    persist::TimeEntry,
+    shared::{lru::LruCache, summary::TimesheetSummary},
+};
+
+const CACHED_DAYS: usize = 62;
+const CACHED_MONTHS: usize = 12;
+
+#[derive(Clone)]
+pub struct CachedDay {
+    pub notes: String,
+    pub entries: Vec<TimeEntry>,
+    pub summary: TimesheetSummary,
+}
+
+/// Loaded days and month overviews, so that browsing back and forth doesn't hit the database
+pub struct CalendarCache {
+    days: LruCache<Date, CachedDay>,
+    months: LruCache<(i32, Month), Vec<Date>>,
+}
+
+impl Default for CalendarCache {
+    fn default() -> Self {
+        Self {
+            days: LruCache::new(CACHED_DAYS),
+            months: LruCache::new(CACHED_MONTHS),
+        }
+    }
+}
+
+impl CalendarCache {
+    pub fn day(&mut self, day: Date) -> Option<&CachedDay> {
+        self.days.get(&day)
+    }
+
+    pub fn store_day(&mut self, day: Date, cached: CachedDay) {
+        self.days.insert(day, cached);
+    }
+
+    /// Days of the month containing the day that have a timesheet
+    pub fn month(&mut self, day: Date) -> Option<&Vec<Date>> {
+        self.months.get(&month_key(day))
+    }
+
+    pub fn store_month(&mut self, day: Date, days_with_timesheets: Vec<Date>) {
+        self.months.insert(month_key(day), days_with_timesheets);
+    }
+
+    /// The day changed, which might also have created or removed its timesheet
+    pub fn invalidate_day(&mut self, day: Date) {
+        self.days.remove(&day);
+        self.months.remove(&month_key(day));
+    }
+
+    pub fn clear(&mut self) {
+        self.days.clear();
+        self.months.clear();
+    }
+}
+
+fn month_key(day: Date) -> (i32, Month) {
+    (day.year(), day.month())
+}
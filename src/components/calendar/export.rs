@@ -1,10 +1,2 @@
-use time::Date;
-
-use crate::shared::summary::TimesheetSummary;
-use color_eyre::Result;
-
 mod jira;
-
-pub fn export(day: Date, summary: &TimesheetSummary) -> Result<()> {
-    jira::export_to_jira(day, summary)
-}
+pub use jira::{Booking, open_bookings, plan_bookings, plan_week_bookings};
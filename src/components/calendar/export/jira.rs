@@ -1,17 +1,101 @@
 use color_eyre::{Result, eyre::Context};
 use time::{Date, macros::format_description};
 
-use crate::shared::summary::TimesheetSummary;
+use crate::{
+    config::Config,
+    export::{booking_text::generate_worklog_comment, jira_csv::week_worklogs},
+    persist::TimeEntry,
+    shared::summary::{ProjectSummary, TimesheetSummary},
+};
 
-pub fn export_to_jira(day: Date, summary: &TimesheetSummary) -> Result<()> {
+/// Time to log on a ticket, through the Jira page opened by `url`
+#[derive(Debug, Clone)]
+pub struct Booking {
+    pub ticket_key: String,
+    pub minutes: i64,
+    /// Comment of the worklog, from [crate::config::ExportConfig::jira_comment]
+    pub comment: String,
+    pub url: String,
+}
+
+/// Bookings for all tickets of projects with a Jira URL, by ticket
+pub fn plan_bookings(
+    day: Date,
+    summary: &TimesheetSummary,
+    comment_template: &str,
+) -> Result<Vec<Booking>> {
     let date_str = format_date(day)?;
     let time_str = get_start_time(summary);
 
-    for project_summary in summary.projects.values() {
-        export_project_tickets(project_summary, &date_str, &time_str)?;
+    let mut bookings = vec![];
+    for (project_key, project_summary) in &summary.projects {
+        for (ticket_key, minutes, jira_base_url) in jira_tickets(project_summary) {
+            let entries = ticket_entries(summary, project_key, ticket_key);
+            let comment = generate_worklog_comment(comment_template, ticket_key, day, &entries);
+            bookings.push(Booking {
+                ticket_key: ticket_key.to_string(),
+                minutes,
+                url: format_booking_url(
+                    jira_base_url,
+                    ticket_key,
+                    minutes,
+                    &date_str,
+                    &time_str,
+                    &comment,
+                ),
+                comment,
+            });
+        }
     }
+    bookings.sort_by(|a, b| a.ticket_key.cmp(&b.ticket_key));
+    Ok(bookings)
+}
 
-    Ok(())
+/// One booking per ticket for a whole week, see [week_worklogs] for how its time is rounded
+/// and when it is logged. The comment covers the entries of all days.
+pub fn plan_week_bookings(
+    days: &[(Date, TimesheetSummary)],
+    config: &Config,
+) -> Result<Vec<Booking>> {
+    let mut bookings = vec![];
+    for worklog in week_worklogs(days, config) {
+        let Some(jira_base_url) = config
+            .projects
+            .get(&worklog.project_key)
+            .and_then(|it| it.jira_url.as_deref())
+        else {
+            continue;
+        };
+        let comment = generate_worklog_comment(
+            &config.export.jira_comment,
+            &worklog.ticket_key,
+            worklog.day,
+            &worklog.entries,
+        );
+        let minutes = i64::from(worklog.minutes);
+        bookings.push(Booking {
+            url: format_booking_url(
+                jira_base_url,
+                &worklog.ticket_key,
+                minutes,
+                &format_date(worklog.day)?,
+                &worklog.start_time,
+                &comment,
+            ),
+            ticket_key: worklog.ticket_key,
+            minutes,
+            comment,
+        });
+    }
+    Ok(bookings)
+}
+
+/// Opens each booking in the browser, returning the ones that failed to open
+pub fn open_bookings(bookings: &[Booking]) -> Vec<(&Booking, color_eyre::Report)> {
+    bookings
+        .iter()
+        .filter_map(|booking| open_url(&booking.url).err().map(|err| (booking, err)))
+        .collect()
 }
 
 fn format_date(day: Date) -> Result<String> {
@@ -26,34 +110,38 @@ fn get_start_time(summary: &TimesheetSummary) -> String {
         .unwrap_or_else(|| "09:00".to_string())
 }
 
-fn export_project_tickets(
-    project_summary: &crate::shared::summary::ProjectSummary,
-    date_str: &str,
-    time_str: &str,
-) -> Result<()> {
-    let project_config = match &project_summary.config {
-        Some(config) => config,
-        None => return Ok(()),
-    };
+/// Entries booked on the ticket of the project, `-` for the ones without ticket
+fn ticket_entries<'a>(
+    summary: &'a TimesheetSummary,
+    project_key: &str,
+    ticket_key: &str,
+) -> Vec<&'a TimeEntry> {
+    summary
+        .entries
+        .iter()
+        .filter(|it| it.project_key == project_key)
+        .filter(|it| it.ticket_key.as_deref().unwrap_or("-") == ticket_key)
+        .collect()
+}
 
-    let jira_base_url = match &project_config.jira_url {
-        Some(url) => url,
-        None => return Ok(()),
+/// Tickets with time on them, their minutes and the Jira URL of the project, if it has one.
+/// A single day is booked as it is, without rounding, unlike [plan_week_bookings].
+fn jira_tickets(project_summary: &ProjectSummary) -> Vec<(&str, i64, &str)> {
+    let Some(jira_base_url) = project_summary
+        .config
+        .as_ref()
+        .and_then(|it| it.jira_url.as_deref())
+    else {
+        return vec![];
     };
-
-    for (ticket_key, duration) in &project_summary.ticket_sums {
-        if ticket_key == "-" || duration.is_zero() {
-            continue;
-        }
-
-        let minutes = duration.whole_minutes();
-        let booking_url =
-            format_booking_url(jira_base_url, ticket_key, minutes, date_str, time_str);
-
-        open_url(&booking_url)?;
-    }
-
-    Ok(())
+    project_summary
+        .ticket_sums
+        .iter()
+        .filter(|(ticket_key, duration)| *ticket_key != "-" && !duration.is_zero())
+        .map(|(ticket_key, duration)| {
+            (ticket_key.as_str(), duration.whole_minutes(), jira_base_url)
+        })
+        .collect()
 }
 
 fn format_booking_url(
@@ -62,15 +150,33 @@ fn format_booking_url(
     minutes: i64,
     date_str: &str,
     time_str: &str,
+    comment: &str,
 ) -> String {
-    format!(
+    let mut url = format!(
         "{}/browse/{}?xxLogTime={}m&xxLogDate={}%20{}",
         jira_base_url.trim_end_matches('/'),
         ticket_key,
         minutes,
         date_str,
         time_str,
-    )
+    );
+    if !comment.is_empty() {
+        url.push_str("&xxLogComment=");
+        url.push_str(&url_encode(comment));
+    }
+    url
+}
+
+/// Percent-encodes everything but the unreserved characters of RFC 3986
+fn url_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
 }
 
 fn open_url(url: &str) -> Result<()> {
@@ -0,0 +1,100 @@
+use std::ops::RangeInclusive;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    text::Line,
+    widgets::{ListItem, ListState},
+};
+use time::{Date, Duration};
+
+use crate::{
+    persist::{TimeEntry, UsageMetric},
+    settings,
+    shared::insights::Insights,
+    widgets::list_popup::ListPopup,
+};
+
+/// Days before today that the insights are calculated over
+const INSIGHTS_DAYS: i64 = 28;
+
+/// How the user worked recently, once both usage metrics and entries are loaded
+pub struct InsightsPopup {
+    range: RangeInclusive<Date>,
+    metrics: Option<Vec<UsageMetric>>,
+    entries: Option<Vec<TimeEntry>>,
+    list_state: ListState,
+}
+
+impl InsightsPopup {
+    pub fn new(today: Date) -> Self {
+        Self {
+            range: today - Duration::days(INSIGHTS_DAYS - 1)..=today,
+            metrics: None,
+            entries: None,
+            list_state: ListState::default(),
+        }
+    }
+
+    pub fn range(&self) -> RangeInclusive<Date> {
+        self.range.clone()
+    }
+
+    pub fn handle_usage_loaded(&mut self, metrics: Vec<UsageMetric>) {
+        self.metrics = Some(metrics);
+    }
+
+    pub fn handle_entries_loaded(&mut self, entries: Vec<TimeEntry>) {
+        self.entries = Some(entries);
+    }
+
+    /// Whether the popup should stay open
+    pub fn handle_key_event(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Esc | KeyCode::Char('i') => return false,
+            _ => {}
+        }
+        true
+    }
+
+    fn lines(&self) -> Vec<Line<'static>> {
+        let (Some(metrics), Some(entries)) = (&self.metrics, &self.entries) else {
+            return vec![Line::from("Loading...").italic()];
+        };
+        let config = settings::get();
+        let insights = Insights::calculate(metrics, entries.clone(), config);
+
+        let mut lines = vec![match insights.switches_per_day {
+            Some(switches) => Line::from(format!(
+                "You context-switch {switches:.1}× per day on average"
+            )),
+            None => Line::from("No work recorded in this time"),
+        }];
+        if !config.metrics.enabled {
+            lines.push(Line::from("Usage counting is off, see the `metrics` config").dim());
+        } else if insights.active_days == 0 {
+            lines.push(Line::from("No usage recorded yet").dim());
+        } else {
+            lines.push(Line::from(format!("Usage on {} days:", insights.active_days)).dim());
+            for (label, uses) in insights.uses_per_day {
+                lines.push(Line::from(format!("  {label}: {uses:.1} per day")));
+            }
+        }
+        lines
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self.lines().into_iter().map(ListItem::from).collect();
+        let title = format!(
+            " 📈 Insights {} to {} ",
+            self.range.start(),
+            self.range.end()
+        );
+        let popup = ListPopup::new(title, &mut self.list_state, items).footer(" <Esc> Close ");
+        frame.render_widget(popup, area);
+    }
+}
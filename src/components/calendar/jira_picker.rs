@@ -0,0 +1,92 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    text::Line,
+    widgets::{ListItem, ListState},
+};
+
+use super::export::Booking;
+use crate::widgets::list_popup::ListPopup;
+
+/// Lets the user check which bookings to open before any browser tab is launched
+pub struct JiraPicker {
+    bookings: Vec<(Booking, bool)>,
+    list_state: ListState,
+}
+
+pub enum JiraOutcome {
+    Picking,
+    Confirmed(Vec<Booking>),
+    Cancelled,
+}
+
+impl JiraPicker {
+    pub fn new(bookings: Vec<Booking>) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select_first();
+        Self {
+            bookings: bookings.into_iter().map(|it| (it, true)).collect(),
+            list_state,
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key: KeyEvent) -> JiraOutcome {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Char(' ') => {
+                let selected = self.list_state.selected();
+                if let Some((_, included)) = selected.and_then(|idx| self.bookings.get_mut(idx)) {
+                    *included = !*included;
+                }
+            }
+            KeyCode::Char('a') => {
+                let all = self.bookings.iter().all(|(_, included)| *included);
+                for (_, included) in self.bookings.iter_mut() {
+                    *included = !all;
+                }
+            }
+            KeyCode::Enter => {
+                let confirmed = self
+                    .bookings
+                    .iter()
+                    .filter(|(_, included)| *included)
+                    .map(|(booking, _)| booking.clone())
+                    .collect_vec();
+                return if confirmed.is_empty() {
+                    JiraOutcome::Cancelled
+                } else {
+                    JiraOutcome::Confirmed(confirmed)
+                };
+            }
+            KeyCode::Esc => return JiraOutcome::Cancelled,
+            _ => {}
+        }
+        JiraOutcome::Picking
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self
+            .bookings
+            .iter()
+            .map(|(booking, included)| {
+                let marker = if *included { "☑" } else { "☐" };
+                let comment = if booking.comment.is_empty() {
+                    String::new()
+                } else {
+                    format!(" “{}”", booking.comment)
+                };
+                ListItem::from(Line::from(format!(
+                    "{marker} {} · {}m{comment} → {}",
+                    booking.ticket_key, booking.minutes, booking.url
+                )))
+            })
+            .collect_vec();
+        let popup = ListPopup::new(" 🔗 Export to Jira ", &mut self.list_state, items)
+            .footer(" <Space> Toggle  <a> All  <Enter> Open  <Esc> Cancel ")
+            .width_percent(80);
+        frame.render_widget(popup, area);
+    }
+}
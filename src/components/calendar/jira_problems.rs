@@ -0,0 +1,67 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    text::Line,
+    widgets::{ListItem, ListState},
+};
+
+use crate::{shared::validation::Violation, widgets::list_popup::ListPopup};
+
+/// Problems that keep the day from being exported to Jira, shown instead of the picker
+pub struct JiraProblems {
+    violations: Vec<Violation>,
+    list_state: ListState,
+}
+
+pub enum JiraProblemsOutcome {
+    Open,
+    /// Index of the entry to fix, `None` for problems concerning the whole day
+    JumpTo(Option<usize>),
+    Closed,
+}
+
+impl JiraProblems {
+    pub fn new(violations: Vec<Violation>) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select_first();
+        Self {
+            violations,
+            list_state,
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key: KeyEvent) -> JiraProblemsOutcome {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Enter => {
+                let selected = self.list_state.selected();
+                let entry_idx = selected
+                    .and_then(|idx| self.violations.get(idx))
+                    .and_then(|it| it.entry_idx);
+                return JiraProblemsOutcome::JumpTo(entry_idx);
+            }
+            KeyCode::Esc => return JiraProblemsOutcome::Closed,
+            _ => {}
+        }
+        JiraProblemsOutcome::Open
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self
+            .violations
+            .iter()
+            .map(|it| ListItem::from(Line::from(format!("⛔ {}", it.message))))
+            .collect_vec();
+        let title = format!(
+            " 🔗 Fix {} problems before exporting to Jira ",
+            self.violations.len()
+        );
+        let popup = ListPopup::new(title, &mut self.list_state, items)
+            .footer(" <Enter> Jump to entry  <Esc> Close ")
+            .width_percent(80);
+        frame.render_widget(popup, area);
+    }
+}
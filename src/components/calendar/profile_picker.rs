@@ -0,0 +1,78 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    text::Line,
+    widgets::{ListItem, ListState},
+};
+
+use crate::{config::Config, widgets::list_popup::ListPopup};
+
+pub struct ProfilePicker {
+    /// `None` is the default profile
+    profiles: Vec<Option<String>>,
+    active: Option<String>,
+    list_state: ListState,
+}
+
+pub enum PickerOutcome {
+    Picking,
+    Picked(Option<String>),
+    Cancelled,
+}
+
+impl ProfilePicker {
+    pub fn new(config: &Config, active: Option<String>) -> Self {
+        let profiles = std::iter::once(None)
+            .chain(
+                config
+                    .profile_names()
+                    .into_iter()
+                    .map(|it| Some(it.to_string())),
+            )
+            .collect_vec();
+        let mut list_state = ListState::default();
+        list_state.select(profiles.iter().position(|it| *it == active));
+        Self {
+            profiles,
+            active,
+            list_state,
+        }
+    }
+
+    pub fn handle_key_event(&mut self, key: KeyEvent) -> PickerOutcome {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Enter => {
+                let selected = self.list_state.selected();
+                return match selected.and_then(|idx| self.profiles.get(idx)) {
+                    Some(profile) if *profile != self.active => {
+                        PickerOutcome::Picked(profile.clone())
+                    }
+                    _ => PickerOutcome::Cancelled,
+                };
+            }
+            KeyCode::Esc => return PickerOutcome::Cancelled,
+            _ => {}
+        }
+        PickerOutcome::Picking
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self
+            .profiles
+            .iter()
+            .map(|profile| {
+                let marker = if *profile == self.active { "●" } else { " " };
+                let name = profile.as_deref().unwrap_or("default");
+                ListItem::from(Line::from(format!("{marker} {name}")))
+            })
+            .collect_vec();
+        let popup = ListPopup::new(" 👤 Profile ", &mut self.list_state, items)
+            .footer(" <Enter> Switch  <Esc> Cancel ")
+            .width_percent(40);
+        frame.render_widget(popup, area);
+    }
+}
@@ -0,0 +1,195 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{
+        Modifier, Style, Stylize,
+        palette::tailwind::{INDIGO, SLATE},
+    },
+    text::Line,
+    widgets::{Block, BorderType, Clear, ListItem, ListState, Padding, Row, Table, TableState},
+};
+
+use crate::{
+    config::Config,
+    persist::ReportTable,
+    widgets::list_popup::{ListPopup, centered_rect},
+};
+
+/// Columns wider than this are cut off, the CSV export has the full values
+const MAX_COLUMN_WIDTH: usize = 40;
+
+/// Lists the configured reports, then shows the result of the picked one as a table
+pub struct ReportPopup {
+    /// Name and description, sorted by name
+    reports: Vec<(String, Option<String>)>,
+    list_state: ListState,
+    /// Report being shown, with its result or error once loaded
+    shown: Option<(String, Option<Result<ReportTable, String>>)>,
+    table_state: TableState,
+}
+
+pub enum ReportOutcome {
+    Open,
+    Run(String),
+    Export(String, ReportTable),
+    Closed,
+}
+
+impl ReportPopup {
+    pub fn new(config: &Config) -> Self {
+        let reports = config
+            .report_names()
+            .into_iter()
+            .map(|name| (name.to_string(), config.reports[name].description.clone()))
+            .collect_vec();
+        let mut list_state = ListState::default();
+        list_state.select_first();
+        Self {
+            reports,
+            list_state,
+            shown: None,
+            table_state: TableState::default(),
+        }
+    }
+
+    pub fn handle_loaded(&mut self, name: String, table: ReportTable) {
+        if let Some((shown_name, result)) = &mut self.shown
+            && *shown_name == name
+        {
+            *result = Some(Ok(table));
+            self.table_state.select_first();
+        }
+    }
+
+    /// Shows the root cause instead of the result, e.g. the SQLite error
+    pub fn handle_failed(&mut self, details: &str) {
+        if let Some((_, result)) = &mut self.shown {
+            let details = strip_ansi_escapes::strip_str(details);
+            let root_cause = details
+                .lines()
+                .map(str::trim)
+                .take_while(|it| !it.starts_with("Location:") && !it.starts_with('━'))
+                .filter(|it| !it.is_empty())
+                .last()
+                .unwrap_or_default();
+            // Causes are numbered like `1: no such column`
+            let root_cause = match root_cause.split_once(": ") {
+                Some((num, cause)) if num.parse::<usize>().is_ok() => cause,
+                _ => root_cause,
+            };
+            *result = Some(Err(root_cause.to_string()));
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        matches!(self.shown, Some((_, None)))
+    }
+
+    pub fn handle_key_event(&mut self, key: KeyEvent) -> ReportOutcome {
+        if let Some((name, result)) = &self.shown {
+            match key.code {
+                KeyCode::Down => self.table_state.select_next(),
+                KeyCode::Up => self.table_state.select_previous(),
+                KeyCode::Char('x') => {
+                    if let Some(Ok(table)) = result {
+                        return ReportOutcome::Export(name.clone(), table.clone());
+                    }
+                }
+                KeyCode::Esc => self.shown = None,
+                _ => {}
+            }
+            return ReportOutcome::Open;
+        }
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Enter => {
+                let selected = self.list_state.selected();
+                if let Some((name, _)) = selected.and_then(|idx| self.reports.get(idx)) {
+                    self.shown = Some((name.clone(), None));
+                    self.table_state = TableState::default();
+                    return ReportOutcome::Run(name.clone());
+                }
+            }
+            KeyCode::Esc => return ReportOutcome::Closed,
+            _ => {}
+        }
+        ReportOutcome::Open
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        match &self.shown {
+            Some((name, Some(Ok(table)))) => {
+                draw_table(frame, area, name, table, &mut self.table_state)
+            }
+            Some((name, result)) => {
+                let line = match result {
+                    Some(Err(message)) => Line::from(format!("❌ {message}")),
+                    _ => Line::from("Running...").italic(),
+                };
+                let popup = ListPopup::new(
+                    format!(" 📊 {name} "),
+                    &mut self.list_state,
+                    vec![ListItem::from(line)],
+                )
+                .footer(" <Esc> Back ");
+                frame.render_widget(popup, area);
+            }
+            None => self.draw_picker(frame, area),
+        }
+    }
+
+    fn draw_picker(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self
+            .reports
+            .iter()
+            .map(|(name, description)| match description {
+                Some(description) => ListItem::from(Line::from(vec![
+                    name.into(),
+                    format!(" · {description}").dim(),
+                ])),
+                None => ListItem::from(Line::from(name.as_str())),
+            })
+            .collect_vec();
+        let popup = ListPopup::new(" 📊 Reports ", &mut self.list_state, items)
+            .footer(" <Enter> Run  <Esc> Close ");
+        frame.render_widget(popup, area);
+    }
+}
+
+fn draw_table(
+    frame: &mut Frame,
+    area: Rect,
+    name: &str,
+    table: &ReportTable,
+    table_state: &mut TableState,
+) {
+    let widths = table
+        .column_widths()
+        .into_iter()
+        .map(|it| Constraint::Length(it.min(MAX_COLUMN_WIDTH) as u16))
+        .collect_vec();
+    let header_and_border = 4;
+    let height = (table.rows.len() as u16 + header_and_border).min(area.height);
+    let area = centered_rect(area, 90, height);
+    frame.render_widget(Clear, area);
+
+    let block = Block::bordered()
+        .border_type(BorderType::Rounded)
+        .padding(Padding::horizontal(1))
+        .title(format!(" 📊 {name} · {} rows ", table.rows.len()))
+        .title_bottom(Line::from(" <x> Export CSV  <Esc> Back ").right_aligned())
+        .style(Style::new().bg(INDIGO.c950));
+    let header = Row::new(table.columns.clone())
+        .bold()
+        .underlined()
+        .bottom_margin(1);
+    let rows = table.rows.iter().map(|row| Row::new(row.clone()));
+    let widget = Table::new(rows, widths)
+        .header(header)
+        .block(block)
+        .row_highlight_style(Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD));
+    frame.render_stateful_widget(widget, area, table_state);
+}
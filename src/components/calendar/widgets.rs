@@ -1,3 +1,4 @@
+use itertools::Itertools;
 use ratatui::{
     prelude::*,
     style::palette::tailwind,
@@ -8,13 +9,20 @@ use ratatui::{
 };
 use time::{Date, Duration, OffsetDateTime, Weekday, ext::NumericalDuration};
 
-use crate::shared::{
-    BREAK_PROJECT_KEY,
-    summary::{ProjectSummary, TimesheetSummary},
+use super::year_view::{HEATMAP_HEIGHT, YearHeatmap, YearView};
+use crate::{
+    settings,
+    shared::{
+        BREAK_PROJECT_KEY,
+        summary::{ProjectSummary, SummaryGrouping, TimesheetSummary},
+    },
 };
 
 pub struct TimesheetSummaryPanel<'a> {
     summary: &'a TimesheetSummary,
+    /// Lists what was done under each ticket
+    descriptions: bool,
+    grouping: SummaryGrouping,
 }
 
 const TABLE_CONSTRAINTS: [Constraint; 3] = [
@@ -25,14 +33,37 @@ const TABLE_CONSTRAINTS: [Constraint; 3] = [
 
 impl<'a> TimesheetSummaryPanel<'a> {
     pub fn new(summary: &'a TimesheetSummary) -> Self {
-        Self { summary }
+        Self {
+            summary,
+            descriptions: false,
+            grouping: SummaryGrouping::Project,
+        }
+    }
+
+    pub fn descriptions(mut self, descriptions: bool) -> Self {
+        self.descriptions = descriptions;
+        self
+    }
+
+    /// Rows by another grouping than project and ticket, without descriptions
+    pub fn grouping(mut self, grouping: SummaryGrouping) -> Self {
+        self.grouping = grouping;
+        self
     }
 
     fn create_header(&self) -> Row<'_> {
-        Row::new(vec!["Project", "Ticket", "Duration"]).style(Style::new().bg(tailwind::LIME.c500))
+        let columns = match self.grouping {
+            SummaryGrouping::Project => vec!["Project", "Ticket", "Duration"],
+            grouping => vec![grouping.label(), "Share", "Duration"],
+        };
+        Row::new(columns).style(Style::new().bg(tailwind::LIME.c500))
     }
 
-    fn create_data_rows(&self) -> Vec<Row<'_>> {
+    /// `ticket_width` is where the descriptions are cut off
+    fn create_data_rows(&self, ticket_width: usize) -> Vec<Row<'_>> {
+        if self.grouping != SummaryGrouping::Project {
+            return self.create_group_rows();
+        }
         self.summary
             .projects
             .iter()
@@ -41,13 +72,46 @@ impl<'a> TimesheetSummaryPanel<'a> {
                 project_summary
                     .ticket_sums
                     .iter()
-                    .map(move |(ticket, duration)| {
-                        self.create_single_row(project_key, project_summary, ticket, duration)
+                    .flat_map(move |(ticket, duration)| {
+                        let row =
+                            self.create_single_row(project_key, project_summary, ticket, duration);
+                        let descriptions = project_summary.descriptions_of(ticket);
+                        let description_row =
+                            (self.descriptions && !descriptions.is_empty()).then(|| {
+                                Row::new(vec![String::new(), truncate(&descriptions, ticket_width)])
+                                    .style(Style::new().italic().dim())
+                            });
+                        [Some(row), description_row].into_iter().flatten()
                     })
             })
             .collect()
     }
 
+    fn create_group_rows(&self) -> Vec<Row<'_>> {
+        let total_mins = self
+            .summary
+            .calculate_total_duration()
+            .whole_minutes()
+            .max(1);
+        let config = settings::get();
+        self.summary
+            .grouped(self.grouping)
+            .into_iter()
+            .map(|group| {
+                let key = match self.grouping {
+                    SummaryGrouping::WorkType => config.work_type_label(&group.key).to_string(),
+                    _ => group.key,
+                };
+                let share = group.duration.whole_minutes() * 100 / total_mins;
+                Row::new(vec![
+                    key,
+                    format!("{share}%"),
+                    self.format_duration_display(&group.duration),
+                ])
+            })
+            .collect()
+    }
+
     fn create_single_row(
         &self,
         project_key: &str,
@@ -110,12 +174,71 @@ impl<'a> TimesheetSummaryPanel<'a> {
             text.push_str(&format!("{} - {} | ", start, end));
         }
 
-        text.push_str(&format!("Working time: {} | Break: {}", formatted_duration, formatted_break_duration));
+        text.push_str(&format!("Working time: {formatted_duration} | "));
+        if !self.summary.billable.is_zero() {
+            let non_billable = total_duration - self.summary.billable;
+            text.push_str(&format!(
+                "Billable: {} | Non-billable: {} | ",
+                self.format_duration_display(&self.summary.billable),
+                self.format_duration_display(&non_billable)
+            ));
+        }
+        let absence_duration = self.summary.calculate_absence_duration();
+        if !absence_duration.is_zero() {
+            text.push_str(&format!(
+                "Absent: {} | ",
+                self.format_duration_display(&absence_duration)
+            ));
+        }
+        text.push_str(&format!("Break: {formatted_break_duration}"));
 
         Paragraph::new(text)
             .style(Style::new().italic())
             .alignment(Alignment::Right)
     }
+
+    fn create_stats_paragraph(&self) -> Paragraph<'_> {
+        let stats = self.summary.stats();
+        let text = format!(
+            "Entries: {} | Tickets: {} | Longest block: {} | Fragmentation: {:.1} switches/h",
+            stats.entries,
+            stats.distinct_tickets,
+            self.format_duration_display(&stats.longest_block),
+            stats.switches_per_hour
+        );
+
+        Paragraph::new(text)
+            .style(Style::new().italic().dim())
+            .alignment(Alignment::Right)
+    }
+
+    /// Time per work type and the meeting/deep-work ratio, `None` if no work types were picked
+    fn create_work_type_paragraph(&self) -> Option<Paragraph<'_>> {
+        if self.summary.work_types.is_empty() {
+            return None;
+        }
+        let config = settings::get();
+        let mut text = self
+            .summary
+            .work_types
+            .iter()
+            .sorted_by_key(|(_, duration)| -**duration)
+            .map(|(key, duration)| {
+                let label = config.work_type_label(key);
+                format!("{label}: {}", self.format_duration_display(duration))
+            })
+            .join(" | ");
+        let (meeting, deep_work) = self.summary.meeting_and_deep_work(config);
+        if !deep_work.is_zero() {
+            let ratio = meeting.as_seconds_f64() / deep_work.as_seconds_f64();
+            text.push_str(&format!(" | Meetings per deep work: {ratio:.2}"));
+        }
+
+        let paragraph = Paragraph::new(text)
+            .style(Style::new().italic().dim())
+            .alignment(Alignment::Right);
+        Some(paragraph)
+    }
 }
 
 impl Widget for TimesheetSummaryPanel<'_> {
@@ -124,24 +247,83 @@ impl Widget for TimesheetSummaryPanel<'_> {
         Self: Sized,
     {
         let header = self.create_header();
-        let rows = self.create_data_rows();
+        let [_, ticket_area, _] = Layout::horizontal(TABLE_CONSTRAINTS).spacing(1).areas(area);
+        let rows = self.create_data_rows(ticket_area.width as usize);
         let total_duration = self.summary.calculate_total_duration();
 
         let table = Table::new(rows, TABLE_CONSTRAINTS).header(header);
 
         // Split area for table and total
+        let work_type_paragraph = self.create_work_type_paragraph();
         let layout = Layout::vertical([
             Constraint::Fill(1),
             Constraint::Length(1), // Space for total line
+            Constraint::Length(1), // Space for stats line
+            Constraint::Length(work_type_paragraph.is_some() as u16),
         ]);
         let areas = layout.split(area);
         let table_area = areas[0];
         let total_area = areas[1];
+        let stats_area = areas[2];
+        let work_type_area = areas[3];
 
         Widget::render(table, table_area, buf);
 
         let total_paragraph = self.create_total_paragraph(total_duration);
         Widget::render(total_paragraph, total_area, buf);
+        Widget::render(self.create_stats_paragraph(), stats_area, buf);
+        if let Some(paragraph) = work_type_paragraph {
+            Widget::render(paragraph, work_type_area, buf);
+        }
+    }
+}
+
+/// Cuts the text off with an ellipsis if it's longer than `width` characters
+fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(width.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Width of the week numbers left of the days, with their padding
+const WEEK_GUTTER_WIDTH: u16 = 3;
+
+/// The [Monthly] calendar with the ISO week number left of each row. Rows start on Sunday, so
+/// they are numbered by their Monday, which begins the ISO week.
+struct WeekNumbered<'a> {
+    monthly: Monthly<'a, CalendarEventStore>,
+    /// Selected day, its week number is highlighted
+    day: Date,
+}
+
+impl Widget for WeekNumbered<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [gutter_area, days_area] =
+            Layout::horizontal([Constraint::Length(WEEK_GUTTER_WIDTH), Constraint::Fill(1)])
+                .areas(area);
+        Widget::render(self.monthly, days_area, buf);
+
+        // Same rows as Monthly draws, below the month header
+        let first_of_month = self.day.replace_day(1).expect("first of month");
+        let first_sunday =
+            first_of_month - i64::from(first_of_month.weekday().number_days_from_sunday()).days();
+        let mut sunday = first_sunday;
+        for y in (gutter_area.y + 1)..gutter_area.bottom() {
+            if sunday.month() == first_of_month.month().next() {
+                break;
+            }
+            let monday = sunday + 1.days();
+            let style = if self.day >= sunday && self.day < sunday + 7.days() {
+                Style::new().fg(tailwind::AMBER.c300)
+            } else {
+                Style::new().dim()
+            };
+            buf.set_string(gutter_area.x, y, format!("{:>2}", monday.iso_week()), style);
+            sunday += 7.days();
+        }
     }
 }
 
@@ -149,6 +331,10 @@ pub struct TimesheetCalendar<'a> {
     day: Date,
     days_with_timesheets: &'a [Date],
     summary: Option<&'a TimesheetSummary>,
+    notes: &'a str,
+    year_view: Option<&'a YearView>,
+    descriptions: bool,
+    grouping: SummaryGrouping,
 }
 
 impl<'a> TimesheetCalendar<'a> {
@@ -156,14 +342,37 @@ impl<'a> TimesheetCalendar<'a> {
         day: Date,
         days_with_timesheets: &'a [Date],
         summary: Option<&'a TimesheetSummary>,
+        notes: &'a str,
     ) -> Self {
         Self {
             day,
             days_with_timesheets,
             summary,
+            notes,
+            year_view: None,
+            descriptions: false,
+            grouping: SummaryGrouping::Project,
         }
     }
 
+    /// Shows the heatmap of the whole year above the day instead of the month
+    pub fn year_view(mut self, year_view: Option<&'a YearView>) -> Self {
+        self.year_view = year_view;
+        self
+    }
+
+    /// See [TimesheetSummaryPanel::descriptions]
+    pub fn descriptions(mut self, descriptions: bool) -> Self {
+        self.descriptions = descriptions;
+        self
+    }
+
+    /// See [TimesheetSummaryPanel::grouping]
+    pub fn grouping(mut self, grouping: SummaryGrouping) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
     fn create_calendar_events(&self) -> CalendarEventStore {
         use ratatui::widgets::calendar::CalendarEventStore;
 
@@ -181,6 +390,20 @@ impl<'a> TimesheetCalendar<'a> {
                 .expect("not to exceed date range");
         }
 
+        // Days with timesheets are marked over holidays, as working on them is the exception
+        let mut current_day = self.day.replace_day(1).expect("first of month");
+        while current_day.month() == self.day.month() {
+            if settings::holiday_of(current_day).is_some() {
+                events.add(
+                    current_day,
+                    Style::default().fg(tailwind::ROSE.c400).italic(),
+                );
+            }
+            current_day = current_day
+                .checked_add(1.days())
+                .expect("not to exceed date range");
+        }
+
         for day_with_timesheet in self.days_with_timesheets.iter() {
             events.add(
                 *day_with_timesheet,
@@ -205,7 +428,7 @@ impl<'a> TimesheetCalendar<'a> {
         events
     }
 
-    fn create_calendar_widget(&self) -> Monthly<'_, CalendarEventStore> {
+    fn create_calendar_widget(&self) -> WeekNumbered<'_> {
         let start = self.day;
         let header_style = Style::default()
             .add_modifier(Modifier::BOLD)
@@ -214,17 +437,37 @@ impl<'a> TimesheetCalendar<'a> {
         let default_style = Style::default().bg(Color::Rgb(50, 50, 50));
 
         let events = self.create_calendar_events();
-        Monthly::new(
+        let monthly = Monthly::new(
             Date::from_calendar_date(start.year(), start.month(), 1).unwrap(),
             events,
         )
         .show_month_header(header_style)
-        .default_style(default_style)
+        .default_style(default_style);
+        WeekNumbered {
+            monthly,
+            day: self.day,
+        }
     }
 
     fn render_detail_panel(&self, area: Rect, buf: &mut Buffer) {
+        let holiday = settings::holiday_of(self.day).map(|it| format!("🎉 {it}"));
+        let notes = (!self.notes.is_empty()).then(|| format!("📝 {}", self.notes));
+        let heading = holiday.into_iter().chain(notes).join("  ");
+        let area = if heading.is_empty() {
+            area
+        } else {
+            let [notes_area, area] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)])
+                .spacing(1)
+                .areas(area);
+            let notes = Paragraph::new(heading).style(Style::new().italic());
+            Widget::render(notes, notes_area, buf);
+            area
+        };
+
         if let Some(summary) = self.summary {
-            let detail_panel = TimesheetSummaryPanel::new(summary);
+            let detail_panel = TimesheetSummaryPanel::new(summary)
+                .descriptions(self.descriptions)
+                .grouping(self.grouping);
             Widget::render(detail_panel, area, buf);
         } else {
             let text = Text::from("Loading summary...");
@@ -246,9 +489,19 @@ impl Widget for TimesheetCalendar<'_> {
         Widget::render(&block, area, buf);
         let area = block.inner(area);
 
+        if let Some(year_view) = self.year_view {
+            let [heatmap_area, detail_area] =
+                Layout::vertical([Constraint::Length(HEATMAP_HEIGHT), Constraint::Fill(1)])
+                    .spacing(1)
+                    .areas(area);
+            Widget::render(YearHeatmap::new(year_view, self.day), heatmap_area, buf);
+            self.render_detail_panel(detail_area, buf);
+            return;
+        }
+
         let cal = self.create_calendar_widget();
 
-        let calendar_width = 3 * 7;
+        let calendar_width = WEEK_GUTTER_WIDTH + 3 * 7;
         let layout = Layout::horizontal([Constraint::Max(calendar_width + 1), Constraint::Fill(1)]);
         let [calendar_area, detail_area] = (*layout.split(area)).try_into().unwrap();
         Widget::render(cal, calendar_area, buf);
@@ -0,0 +1,119 @@
+use std::{collections::HashMap, ops::RangeInclusive};
+
+use ratatui::{prelude::*, style::palette::tailwind};
+use time::{Date, Duration, Month, Weekday};
+
+/// Working minutes of every day in a year, shown as a heatmap with a column per week
+pub struct YearView {
+    pub year: i32,
+    /// Empty until loaded
+    totals: HashMap<Date, i64>,
+}
+
+impl YearView {
+    pub fn new(year: i32) -> Self {
+        Self {
+            year,
+            totals: HashMap::new(),
+        }
+    }
+
+    pub fn range(&self) -> RangeInclusive<Date> {
+        let first = Date::from_calendar_date(self.year, Month::January, 1).expect("valid year");
+        let last = Date::from_calendar_date(self.year, Month::December, 31).expect("valid year");
+        first..=last
+    }
+
+    pub fn handle_loaded(&mut self, totals: Vec<(Date, i64)>) {
+        self.totals = totals.into_iter().collect();
+    }
+
+    fn total_of(&self, day: Date) -> i64 {
+        self.totals.get(&day).copied().unwrap_or_default()
+    }
+}
+
+pub struct YearHeatmap<'a> {
+    view: &'a YearView,
+    day: Date,
+}
+
+/// Two columns per day, the second one as spacing
+const CELL_WIDTH: u16 = 2;
+const LABEL_WIDTH: u16 = 4;
+/// Month labels, one row per weekday and the totals
+pub const HEATMAP_HEIGHT: u16 = 1 + 7 + 1;
+
+impl<'a> YearHeatmap<'a> {
+    pub fn new(view: &'a YearView, day: Date) -> Self {
+        Self { view, day }
+    }
+
+    fn color_of(mins: i64) -> Color {
+        match mins {
+            ..=0 => tailwind::SLATE.c800,
+            1..120 => tailwind::GREEN.c900,
+            120..240 => tailwind::GREEN.c700,
+            240..360 => tailwind::GREEN.c500,
+            _ => tailwind::GREEN.c300,
+        }
+    }
+
+    fn format_mins(mins: i64) -> String {
+        format!("{}h{}m", mins / 60, mins % 60)
+    }
+
+    fn totals_line(&self) -> Line<'_> {
+        let worked_days = self.view.totals.values().filter(|it| **it > 0).count();
+        let year_total: i64 = self.view.totals.values().sum();
+        Line::from(format!(
+            "{}: {} | {}: {} on {worked_days} days",
+            self.day,
+            Self::format_mins(self.view.total_of(self.day)),
+            self.view.year,
+            Self::format_mins(year_total),
+        ))
+        .italic()
+    }
+}
+
+impl Widget for YearHeatmap<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer)
+    where
+        Self: Sized,
+    {
+        let range = self.view.range();
+        let first = *range.start();
+        let first_monday = first - Duration::days(first.weekday().number_days_from_monday() as i64);
+
+        for (row, weekday) in [(1, "Mo"), (3, "We"), (5, "Fr")] {
+            buf.set_string(area.x, area.y + row, weekday, Style::new().dim());
+        }
+
+        let mut day = first;
+        while range.contains(&day) {
+            let week = ((day - first_monday).whole_days() / 7) as u16;
+            let x = area.x + LABEL_WIDTH + week * CELL_WIDTH;
+            if x + CELL_WIDTH > area.right() {
+                break;
+            }
+            if day.day() == 1 {
+                let label = format!("{:.3}", day.month().to_string());
+                buf.set_string(x, area.y, label, Style::new().dim());
+            }
+            let y = area.y + 1 + day.weekday().number_days_from_monday() as u16;
+            let color = Self::color_of(self.view.total_of(day));
+            let (symbol, style) = if day == self.day {
+                ("◆", Style::new().fg(Color::White).bold())
+            } else if matches!(day.weekday(), Weekday::Saturday | Weekday::Sunday) {
+                ("■", Style::new().fg(color).dim())
+            } else {
+                ("■", Style::new().fg(color))
+            };
+            buf.set_string(x, y, symbol, style);
+            day = day.next_day().expect("not to exceed date range");
+        }
+
+        buf.set_line(area.x, area.y + 8, &self.totals_line(), area.width);
+    }
+}
@@ -1,19 +1,55 @@
-use std::time::Instant;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind};
+use itertools::Itertools;
 use ratatui::{
     Frame,
-    layout::{Constraint, Layout, Rect},
-    style::{Style, Stylize},
-    text::Span,
-    widgets::Paragraph,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Style, Stylize, palette::tailwind},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Clear, Paragraph},
 };
 
 use super::Component;
 
-use crate::action::Action;
+use crate::{action::Action, persist};
+
+/// Frames whose draw times are kept for the percentiles
+const FRAME_SAMPLES: usize = 240;
+
+/// Measured by the app loop, which the components can't see into
+#[derive(Debug, Default)]
+pub struct RenderStats {
+    /// Time each of the last [FRAME_SAMPLES] frames took to draw
+    frame_times: VecDeque<Duration>,
+    /// Actions that were waiting when the queue was last handled
+    pub action_queue: usize,
+}
+
+pub type SharedRenderStats = Arc<Mutex<RenderStats>>;
+
+impl RenderStats {
+    pub fn record_frame(&mut self, frame_time: Duration) {
+        if self.frame_times.len() == FRAME_SAMPLES {
+            self.frame_times.pop_front();
+        }
+        self.frame_times.push_back(frame_time);
+    }
+
+    /// Frame time that `percent` of the recent frames didn't exceed
+    fn percentile(&self, percent: usize) -> Option<Duration> {
+        let sorted = self.frame_times.iter().sorted().collect_vec();
+        let idx = (sorted.len().checked_sub(1)? * percent).div_ceil(100);
+        sorted.get(idx).copied().copied()
+    }
+}
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct FpsCounter {
     last_tick_update: Instant,
     tick_count: u32,
@@ -22,10 +58,14 @@ pub struct FpsCounter {
     last_frame_update: Instant,
     frame_count: u32,
     frames_per_second: f64,
+
+    render_stats: SharedRenderStats,
+    /// Shows frame times and queue depths instead of just the rates, toggled with F10
+    overlay: bool,
 }
 
-impl Default for FpsCounter {
-    fn default() -> Self {
+impl FpsCounter {
+    pub fn new(render_stats: SharedRenderStats) -> Self {
         Self {
             last_tick_update: Instant::now(),
             tick_count: 0,
@@ -33,11 +73,11 @@ impl Default for FpsCounter {
             last_frame_update: Instant::now(),
             frame_count: 0,
             frames_per_second: 0.0,
+            render_stats,
+            overlay: false,
         }
     }
-}
 
-impl FpsCounter {
     fn app_tick(&mut self) -> Result<()> {
         self.tick_count += 1;
         let now = Instant::now();
@@ -61,9 +101,73 @@ impl FpsCounter {
         }
         Ok(())
     }
+
+    fn overlay_lines(&self) -> Vec<Line<'_>> {
+        let (percentiles, action_queue) = {
+            let stats = self
+                .render_stats
+                .lock()
+                .expect("render stats lock not poisoned");
+            let percentiles = [50, 90, 99, 100]
+                .map(|percent| stats.percentile(percent).map_or("-".into(), format_millis))
+                .join(" / ");
+            (percentiles, stats.action_queue)
+        };
+        let persist = persist::stats();
+        let last_command = persist
+            .last_command
+            .map_or("-".into(), |(description, took)| {
+                format!("{description}, {}", format_millis(took))
+            });
+        let row = |label: &'static str, value: String| {
+            Line::from(vec![
+                Span::from(format!("{label:<20}")).dim(),
+                Span::from(value),
+            ])
+        };
+        vec![
+            row(
+                "Rates",
+                format!(
+                    "{:.1} ticks/s, {:.1} FPS",
+                    self.ticks_per_second, self.frames_per_second
+                ),
+            ),
+            row("Frame p50/90/99/max", percentiles),
+            row("Action queue", action_queue.to_string()),
+            row("Persist queue", persist.queued.to_string()),
+            row("Last DB command", last_command),
+        ]
+    }
+
+    fn draw_overlay(&self, frame: &mut Frame, area: Rect) {
+        let lines = self.overlay_lines();
+        let [area] = Layout::vertical([Constraint::Length(lines.len() as u16 + 2)]).areas(area);
+        let [_, area] = Layout::horizontal([Constraint::Fill(1), Constraint::Length(64)])
+            .flex(Flex::End)
+            .areas(area);
+        frame.render_widget(Clear, area);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .title(" ⏱️ Performance ")
+            .title_bottom(Line::from(" <F10> Hide ").right_aligned())
+            .style(Style::new().bg(tailwind::SLATE.c900));
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+fn format_millis(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
 }
 
 impl Component for FpsCounter {
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if key.code == KeyCode::F(10) && key.kind == KeyEventKind::Press {
+            self.overlay = !self.overlay;
+        }
+        Ok(None)
+    }
+
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
         match action {
             Action::Tick => self.app_tick()?,
@@ -74,6 +178,10 @@ impl Component for FpsCounter {
     }
 
     fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if self.overlay {
+            self.draw_overlay(frame, area);
+            return Ok(());
+        }
         let [area, _] = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(area);
         let [area, _] = Layout::horizontal([Constraint::Fill(1), Constraint::Max(2)]).areas(area);
         let message = format!(
@@ -1,5 +1,10 @@
-use std::vec;
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+    vec,
+};
 
+use chrono::Local;
 use color_eyre::Result;
 use crossterm::event::KeyEvent;
 use educe::Educe;
@@ -7,27 +12,45 @@ use lazy_static::lazy_static;
 use ratatui::prelude::*;
 use time::{Date, OffsetDateTime};
 use tokio::sync::mpsc::UnboundedSender;
+use tracing::warn;
 
 use super::Component;
 use crate::{
-    action::{Action, Page, RelevantKey},
+    action::{Action, Page, RelevantKey, TimerStart},
     components::home::{
+        action::HomeAction,
+        compare::ComparePane,
         editing::{EditMode, EditModeBehavior},
+        persist_handling::PersistRetry,
+        popup::{Popup, RecoverEdits},
         state::HomeState,
+        week_bar::WeekBar,
+    },
+    config::{Config, FocusConfig},
+    desktop, metrics,
+    persist::{self, journal::EditJournal},
+    settings,
+    shared::{
+        focus::{FocusEvent, FocusSuggestion, FocusTracker},
+        idle::IdleTracker,
+        insights::Feature,
+        summary::TimesheetSummary,
+        tz::{self, DayZone},
+        validation::Violation,
     },
-    config::Config,
-    persist,
-    shared::BREAK_PROJECT_KEY,
 };
 
 mod action;
+mod compare;
 mod draw;
 mod editing;
-mod export;
+pub(crate) mod export;
 mod key_handling;
 mod movement;
 mod persist_handling;
+mod popup;
 mod state;
+mod week_bar;
 mod item {}
 
 #[derive(Educe)]
@@ -42,14 +65,44 @@ pub struct Home {
     persist_tx: Option<UnboundedSender<persist::Command>>,
 
     edit_mode: Option<EditMode>,
+    popup: Option<Popup>,
+    compare: Option<ComparePane>,
+    week_bar: Option<WeekBar>,
     suspended: bool,
     state: HomeState,
+    violations: Vec<Violation>,
+    /// Left out of suggestions and flagged when booked on
+    archived_tickets: HashSet<String>,
+    idle: Option<IdleTracker>,
+    focus: Option<FocusTracker>,
+    /// Entries suggested from the focused window, waiting for review
+    focus_suggestions: Vec<FocusSuggestion>,
+    retry: PersistRetry,
+    /// Entries sent to be stored but not yet confirmed, to recover them after a crash
+    journal: Option<EditJournal>,
+    #[educe(Default(expression = Instant::now()))]
+    last_autosave: Instant,
+    terminal_title: String,
+    /// Working and absent minutes of today when last checked against the daily target
+    today_worked_mins: Option<u32>,
+    /// Ticket and description of a timer to start once today is loaded
+    pending_timer: Option<TimerStart>,
+    /// Templates were requested to fill in the scaffold of the empty day
+    pending_scaffold: bool,
+    /// Id of the entry to select once the day is loaded
+    pending_selection: Option<String>,
 
     need_status_line_reset: bool,
 }
 
 impl Home {
     fn send_persist(&mut self, command: persist::Command) {
+        if let persist::Command::StoreEntry { entry, version } = &command
+            && let Some(journal) = &mut self.journal
+            && let Err(err) = journal.record(entry, *version)
+        {
+            warn!("Failed to journal entry {}: {err:?}", entry.id);
+        }
         self.persist_tx
             .as_ref()
             .expect("persist_tx initialised")
@@ -57,6 +110,13 @@ impl Home {
             .expect("able to send persist msg")
     }
 
+    fn record_usage(&self, feature: Feature) {
+        metrics::record(
+            self.persist_tx.as_ref().expect("persist_tx initialised"),
+            feature,
+        );
+    }
+
     fn send_action(&mut self, action: Action) {
         self.action_tx
             .as_ref()
@@ -65,25 +125,248 @@ impl Home {
             .expect("able to send action msg")
     }
 
-    pub fn total_working_hours(&self) -> time::Duration {
-        self.state
+    fn autosave_edit(&mut self) -> Result<()> {
+        let interval = self.config.editing.autosave_secs;
+        if interval == 0 || self.last_autosave.elapsed() < Duration::from_secs(interval) {
+            return Ok(());
+        }
+        self.last_autosave = Instant::now();
+        let Some(edit_mode) = &mut self.edit_mode else {
+            return Ok(());
+        };
+        if edit_mode.autosave(&mut self.state) {
+            // persists the item, the edit mode and its buffer stay as they are
+            action::perform(self, HomeAction::None)?;
+        }
+        Ok(())
+    }
+
+    /// Saves the edited item and sends failed saves again without waiting for the backoff
+    fn flush_edits(&mut self) -> Result<()> {
+        if let Some(edit_mode) = &mut self.edit_mode {
+            edit_mode.autosave(&mut self.state);
+        }
+        self.retry.expedite();
+        self.retry_failed_persist()?;
+        action::perform(self, HomeAction::None)
+    }
+
+    fn retry_failed_persist(&mut self) -> Result<()> {
+        let Some(commands) = self.retry.take_due(Instant::now()) else {
+            return Ok(());
+        };
+        for command in commands {
+            match command {
+                // The item might have changed since, so save its current state instead
+                persist::Command::StoreEntry { entry, version } => {
+                    let item = self
+                        .state
+                        .items
+                        .iter_mut()
+                        .find(|it| it.id.to_string() == entry.id);
+                    if let Some(item) = item {
+                        item.version.notify_failed(version);
+                    }
+                }
+                command => self.send_persist(command),
+            }
+        }
+        action::perform(self, HomeAction::None)
+    }
+
+    /// Idle time is only offered as break on today's sheet, where it happened
+    /// Keeps tracking while in the calendar, but only switches the timer while today is shown
+    fn poll_focus(&mut self) -> Result<()> {
+        let Some(focus) = &mut self.focus else {
+            return Ok(());
+        };
+        for event in focus.poll(Local::now().naive_local()) {
+            let switch_timer = self.config.focus.switch_timer;
+            let action = match event {
+                FocusEvent::Settled(matched)
+                    if switch_timer && self.is_today() && !self.suspended =>
+                {
+                    HomeAction::StartTimer(TimerStart {
+                        project: matched.project,
+                        ticket: matched.ticket,
+                        description: matched.description,
+                    })
+                }
+                FocusEvent::Finished(suggestion) if !switch_timer => {
+                    HomeAction::QueueFocusSuggestion(suggestion)
+                }
+                _ => continue,
+            };
+            action::perform(self, action)?;
+        }
+        Ok(())
+    }
+
+    fn poll_idle(&mut self) -> Result<()> {
+        let Some(idle) = &mut self.idle else {
+            return Ok(());
+        };
+        let now = Local::now().naive_local();
+        let Some(span) = idle.poll(now) else {
+            return Ok(());
+        };
+        if span.start.date().to_string() != self.day.to_string()
+            || span.duration().num_minutes() <= 0
+        {
+            return Ok(());
+        }
+        action::perform(self, HomeAction::OfferIdleBreak(span))
+    }
+
+    /// Keeps the terminal title and the daily target notification current, only for today
+    fn update_desktop(&mut self) -> Result<()> {
+        let loaded_today = self
+            .state
+            .timesheet
+            .as_ref()
+            .is_some_and(|it| it.day == self.day.to_string());
+        if !self.is_today() || !loaded_today {
+            return Ok(());
+        }
+        let summary = self.summary();
+        let worked = summary.calculate_total_duration();
+
+        if self.config.desktop.terminal_title {
+            let title = format!(
+                "sheet-shark — {}h{}m today",
+                worked.whole_hours(),
+                worked.whole_minutes() % 60
+            );
+            if title != self.terminal_title {
+                desktop::set_terminal_title(&title)?;
+                self.terminal_title = title;
+            }
+        }
+
+        // Absences count towards the target like in the gauge
+        let done = worked + summary.calculate_absence_duration();
+        let done_mins = done.whole_minutes().max(0) as u32;
+        let previous = self.today_worked_mins.replace(done_mins);
+        // Public holidays have no target to reach
+        let target = self
+            .config
+            .home
+            .daily_target_mins
+            .filter(|_| settings::holiday_of(self.day).is_none());
+        if let (Some(previous), Some(target)) = (previous, target)
+            && previous < target
+            && done_mins >= target
+        {
+            desktop::notify(
+                "🎯 Daily target reached",
+                &format!("{}h{}m done today", done_mins / 60, done_mins % 60),
+            );
+        }
+        Ok(())
+    }
+
+    /// Lets the pinned item follow the clock, but not while the user is editing
+    fn extend_pinned(&mut self) -> Result<()> {
+        if self.edit_mode.is_some() || !self.is_today() {
+            return Ok(());
+        }
+        if self.state.extend_pinned(Local::now().time()) {
+            action::perform(self, HomeAction::None)?;
+        }
+        Ok(())
+    }
+
+    fn is_today(&self) -> bool {
+        let today = OffsetDateTime::now_local()
+            .expect("find local offset for date")
+            .date();
+        self.day == today
+    }
+
+    /// Whether the day is over, so that nothing more is logged on it
+    fn is_past(&self) -> bool {
+        OffsetDateTime::now_local().is_ok_and(|it| self.day < it.date())
+    }
+
+    /// Only loads when switching to another week, the shown day is kept current while drawing
+    fn load_week_bar(&mut self) {
+        if !self.config.home.week_bar
+            || self
+                .week_bar
+                .as_ref()
+                .is_some_and(|it| it.range.contains(&self.day))
+        {
+            return;
+        }
+        let week_bar = WeekBar::new(self.day);
+        self.send_persist(persist::Command::LoadEntriesInRange {
+            range: week_bar.range.clone(),
+        });
+        self.week_bar = Some(week_bar);
+    }
+
+    /// Summary of the items as they are shown, including edits that aren't saved yet
+    pub fn summary(&self) -> TimesheetSummary {
+        let day = self.day.to_string();
+        let entries = self
+            .state
             .items
             .iter()
-            .filter(|item| item.project != BREAK_PROJECT_KEY)
-            .map(|item| time::Duration::minutes(item.duration.as_secs() as i64 / 60))
-            .filter(|duration| !duration.is_zero())
-            .fold(time::Duration::ZERO, |acc, duration| acc + duration)
+            .map(|item| item.to_persist(&day))
+            .collect();
+        TimesheetSummary::new(entries, &self.config)
+    }
+
+    /// Zone the times of the shown day are interpreted in
+    pub fn day_zone(&self) -> DayZone {
+        let recorded = self
+            .state
+            .timesheet
+            .as_ref()
+            .and_then(|it| it.utc_offset_mins);
+        DayZone::of(self.day, recorded)
+    }
+
+    /// Remembers where today is recorded, unless it already is
+    fn record_utc_offset(&mut self) {
+        let is_today = OffsetDateTime::now_local().is_ok_and(|it| it.date() == self.day);
+        let unrecorded = self
+            .state
+            .timesheet
+            .as_ref()
+            .is_some_and(|it| it.utc_offset_mins.is_none());
+        if self.config.home.record_utc_offset && is_today && unrecorded {
+            self.send_persist(persist::Command::StoreUtcOffset {
+                day: self.day,
+                offset_mins: tz::current_offset_mins(),
+            });
+        }
     }
 }
 
 impl Component for Home {
     fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.idle = IdleTracker::from_config(&config.idle);
+        self.focus = start_focus_tracking(&config.focus);
+        self.state.show_end = config.home.end_column;
         self.config = config;
         Ok(())
     }
 
     fn register_persist_handler(&mut self, tx: UnboundedSender<persist::Command>) -> Result<()> {
         self.persist_tx = Some(tx);
+        // Once per profile, as the components are created again when switching
+        self.journal = EditJournal::open(&settings::data_dir())
+            .inspect_err(|err| warn!("Not journaling edits: {err:?}"))
+            .ok();
+        let recovered = self
+            .journal
+            .as_ref()
+            .map(|it| it.recovered())
+            .unwrap_or_default();
+        if !recovered.is_empty() {
+            self.popup = Some(RecoverEdits::new(recovered).into());
+        }
         Ok(())
     }
 
@@ -116,35 +399,120 @@ impl Component for Home {
         match action {
             Action::SetActivePage(Page::Home { day }) => {
                 self.send_persist(persist::Command::LoadTimesheet { day });
+                self.send_persist(persist::Command::LoadArchivedTickets);
                 self.action_tx
                     .as_mut()
                     .unwrap()
                     .send(Action::SetRelevantKeys(OUTSIDE_KEYS.to_vec()))
                     .expect("sent initial keys");
                 self.day = day;
+                self.compare = None;
                 self.suspended = false;
+                self.load_week_bar();
             }
             Action::SetActivePage(_) => {
                 self.suspended = true;
             }
+            Action::SelectEntry(id) => self.pending_selection = Some(id),
+            Action::StartTimer(timer) => action::perform(self, HomeAction::StartTimer(timer))?,
+            Action::StopTimer => action::perform(self, HomeAction::StopTimer)?,
+            Action::FlushEdits => self.flush_edits()?,
+            Action::Tick => {
+                self.retry_failed_persist()?;
+                self.extend_pinned()?;
+                self.update_desktop()?;
+                self.poll_focus()?;
+                if !self.suspended {
+                    self.autosave_edit()?;
+                    self.poll_idle()?;
+                }
+            }
             _ => {}
         }
         Ok(None)
     }
 }
 
+#[cfg(feature = "focus")]
+fn start_focus_tracking(config: &FocusConfig) -> Option<FocusTracker> {
+    FocusTracker::from_config(config).unwrap_or_else(|err| {
+        tracing::warn!("Disabling focus tracking: {err:?}");
+        None
+    })
+}
+
+#[cfg(not(feature = "focus"))]
+fn start_focus_tracking(config: &FocusConfig) -> Option<FocusTracker> {
+    if config.enabled {
+        tracing::debug!("Not built with focus tracking, ignoring the focus config");
+    }
+    None
+}
+
 lazy_static! {
     static ref OUTSIDE_KEYS: Vec<RelevantKey> = vec![
         RelevantKey::new("Arrows", "Move"),
         RelevantKey::new("Esc", "Exit to calendar"),
         RelevantKey::new("e", "Export"),
+        RelevantKey::new("M", "Shift day"),
+        RelevantKey::new("J", "Line up times"),
+        RelevantKey::new("a", "Absence"),
+        RelevantKey::new("v", "Paste entries"),
+        RelevantKey::new("B", "Copy bookings"),
+        RelevantKey::new("D", "Merge duplicates"),
+        RelevantKey::new("/", "Filter"),
+        RelevantKey::new("!", "Problems"),
+        RelevantKey::new("r", "Retry saving"),
+        RelevantKey::new("R", "Reload day"),
+        RelevantKey::new("H", "History"),
+        RelevantKey::new("n", "Notes"),
+        RelevantKey::new("K", "Archived tickets"),
+        RelevantKey::new("E", "End column"),
+        RelevantKey::new("C", "Compare"),
     ];
     static ref SELECTING_KEYS: Vec<RelevantKey> = vec![
         RelevantKey::new("Space", "Edit"),
-        RelevantKey::new("s", "Split"),
+        RelevantKey::new("s/%", "Split (by %)"),
         RelevantKey::new("Arrows", "Move"),
         RelevantKey::new("e", "Export"),
         RelevantKey::new("x", "Break"),
+        RelevantKey::new("b", "Billable"),
+        RelevantKey::new("y", "Work type"),
+        RelevantKey::new("g", "Flags"),
+        RelevantKey::new("c", "Carry over"),
+        RelevantKey::new("i", "Details"),
+        RelevantKey::new("p", "Pin"),
+        RelevantKey::new("l/o", "Links (open)"),
+        RelevantKey::new("d", "Diff with saved"),
+        RelevantKey::new("t/T", "Templates (save)"),
+        RelevantKey::new("f/F", "Fill gap (as break)"),
+        RelevantKey::new("m/M", "Shift times (whole day)"),
+        RelevantKey::new("+/-", "Longer/shorter"),
+        RelevantKey::new("J", "Line up times"),
+        RelevantKey::new("a", "Absence"),
+        RelevantKey::new("v", "Paste entries"),
+        RelevantKey::new("B", "Copy bookings"),
+        RelevantKey::new("D", "Merge duplicates"),
+        RelevantKey::new("/", "Filter"),
+        RelevantKey::new("!", "Problems"),
+        RelevantKey::new("r", "Retry saving"),
+        RelevantKey::new("R", "Reload day"),
+        RelevantKey::new("H", "History"),
+        RelevantKey::new("n", "Notes"),
+        RelevantKey::new("K", "Archived tickets"),
+        RelevantKey::new("E", "End column"),
+        RelevantKey::new("C", "Compare"),
+    ];
+    static ref COMPARE_KEYS: Vec<RelevantKey> = vec![
+        RelevantKey::new("Up/Down", "Select"),
+        RelevantKey::new("Left/Right", "Change day"),
+        RelevantKey::new("c", "Copy to edited day"),
+        RelevantKey::new("w", "Back to edited day"),
+        RelevantKey::new("C", "Close"),
+    ];
+    static ref EDITING_KEYS: Vec<RelevantKey> = vec![
+        RelevantKey::new("^", "Clear"),
+        RelevantKey::new("Tab", "Expand alias"),
+        RelevantKey::new("=", "Exact time"),
     ];
-    static ref EDITING_KEYS: Vec<RelevantKey> = vec![RelevantKey::new("^", "Clear"),];
 }
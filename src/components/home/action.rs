@@ -1,14 +1,49 @@
+use chrono::{NaiveTime, TimeDelta, Timelike};
 use color_eyre::eyre::{ErrReport, Result};
+use itertools::Itertools;
 use std::{ops::Add, time::Duration};
+use strum::IntoStaticStr;
+use time::OffsetDateTime;
+use tracing::{debug, warn};
 
 use crate::{
-    action::{Action, Page},
-    components::home::{EDITING_KEYS, Home, SELECTING_KEYS, editing::EditMode, state::TimeItem},
-    persist::{self, Command},
-    shared::BREAK_PROJECT_KEY,
+    action::{Action, Page, TimerStart},
+    clipboard,
+    components::home::{
+        COMPARE_KEYS, EDITING_KEYS, Home, SELECTING_KEYS,
+        compare::{self, ComparePane},
+        editing::EditMode,
+        export::{self, ExportFile},
+        popup::{
+            AbsencePicker, ArchivedTickets, CarryOver, Confirm, EntryDetails, EntryDiff,
+            ExportFormatPicker, ExportPreview, FlagPicker, FocusReview, History, IdleReturn, Links,
+            MergeDuplicates, Notes, PastePreview, Problems, RepairChain, ResolveConflict,
+            RowFilter, SaveTemplate, ScaffoldOffer, ShiftTimes, SplitShares, WorkTypePicker,
+        },
+        state::{ChainRepair, Cut, END_COLUMN, TimeItem},
+    },
+    config::DefragStrategy,
+    desktop,
+    export::{ExportFormat, booking_text},
+    logging::ACTION_TARGET,
+    persist::{
+        self, Command, DaySnapshot, EntryTemplate, SuggestionRequestId, TimeEntry, TimeEntryId,
+        journal,
+    },
+    settings,
+    shared::{
+        self, BREAK_PROJECT_KEY,
+        focus::FocusSuggestion,
+        idle::IdleSpan,
+        insights::Feature,
+        parse::{self, PastedEntry},
+        scaffold::{self, ScaffoldItem},
+        split::{self, Share},
+        ticket_project, time_expr, validation,
+    },
 };
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, IntoStaticStr)]
 pub enum HomeAction {
     None,
     Many(Vec<HomeAction>),
@@ -20,10 +55,150 @@ pub enum HomeAction {
 
     SetStatusLine(String),
     SplitItemDown(usize),
+    EditSplitShares(usize),
+    SplitByShares {
+        idx: usize,
+        shares: Vec<Share>,
+    },
     MergeItemDown(usize),
-    SuggestTickets(String),
+    FindDuplicates,
+    /// Each run of entries becomes its first one, with their durations summed up
+    MergeDuplicates(Vec<Vec<TimeEntryId>>),
+    SuggestTickets {
+        query: String,
+        request_id: SuggestionRequestId,
+    },
     Export,
+    PickExportFormats(Vec<ExportFormat>),
+    PrepareExport {
+        formats: Vec<ExportFormat>,
+        defrag: DefragStrategy,
+    },
+    WriteExport(Vec<ExportFile>),
     ToggleBreak,
+    ToggleBillable,
+    ToggleCarryOver,
+    /// Shows the flags of the selected entry to toggle them
+    ShowDetails,
+    SetFlags {
+        billable: bool,
+        carry_over: bool,
+        exact: bool,
+    },
+    PickWorkType,
+    SetWorkType(Option<String>),
+    PickFlags,
+    /// Keys of the configured flags to set on the selected entry
+    SetFlagKeys(Vec<String>),
+    PickAbsence,
+    /// Inserts an entry for the absence with the key at the next free slot
+    InsertAbsence(String),
+    ToggleEndColumn,
+    /// Sends the entries that could not be saved again
+    RetrySave,
+    /// Loads the saved values of the selected entry to show them next to the current ones
+    DiffSelected,
+    ShowEntryDiff {
+        id: TimeEntryId,
+        entry: Option<TimeEntry>,
+        links: Vec<String>,
+    },
+    /// Puts the saved values back into the entry with their id, forgetting its changes
+    DiscardChanges {
+        entry: TimeEntry,
+        links: Vec<String>,
+    },
+    /// Offers to resolve the conflict of the entry with the one another writer stored
+    ShowConflict {
+        entry: TimeEntry,
+        stored: Option<TimeEntry>,
+    },
+    /// Saves the values for the entry that another writer changed, based on their revision
+    KeepConflicting {
+        entry: TimeEntry,
+        revision: i32,
+    },
+    /// Removes the entry with the id that another writer deleted, forgetting its changes
+    ForgetDeleted(String),
+    /// Asks before reloading the day if that would lose unsaved changes
+    ConfirmReloadDay,
+    /// Loads the day again, forgetting the changes that are not being saved
+    ReloadDay,
+    /// Loads the earlier versions of the day to browse them
+    LoadHistory,
+    ShowHistory(Vec<DaySnapshot>),
+    /// Asks before restoring the version taken at the time
+    ConfirmRestoreSnapshot(String),
+    /// Like [HomeAction::ReloadDay], but with the version taken at the time
+    RestoreSnapshot(String),
+    TogglePin,
+    StartTimer(TimerStart),
+    StopTimer,
+    FillGapAfter {
+        idx: usize,
+        as_break: bool,
+    },
+    EditShift {
+        from_idx: usize,
+    },
+    ShiftItems {
+        from_idx: usize,
+        mins: i64,
+    },
+    /// Grows or shrinks the selected entry by this many minutes
+    NudgeDuration(i64),
+    /// Previews lining up the start times or durations of the whole day
+    EditRepairChain(ChainRepair),
+    RepairChain(ChainRepair),
+    ShowProblems,
+    EditFilter,
+    ClosePopup,
+    EditNotes,
+    StoreNotes(String),
+    EditLinks,
+    StoreLinks {
+        id: TimeEntryId,
+        urls: Vec<String>,
+    },
+    /// Opens the links of the selected entry in the browser
+    OpenLinks,
+    EditTemplateName,
+    SaveTemplate(String),
+    LoadTemplates,
+    InsertTemplate(EntryTemplate),
+    DeleteTemplate(String),
+    QueueFocusSuggestion(FocusSuggestion),
+    ReviewFocusSuggestions,
+    AcceptFocusSuggestion(FocusSuggestion),
+    DismissFocusSuggestion(FocusSuggestion),
+    PasteEntries,
+    ApplyPasted(Vec<PastedEntry>),
+    CopyBookings,
+    PrepareScaffold,
+    OfferScaffold(Vec<ScaffoldItem>),
+    ApplyScaffold(Vec<ScaffoldItem>),
+    /// Looks for entries to carry over before offering the scaffold
+    PrepareCarryOver,
+    OfferCarryOver(Vec<TimeEntry>),
+    ApplyCarryOver(Vec<TimeEntry>),
+    ManageArchivedTickets,
+    ArchiveTicket(String),
+    UnarchiveTicket(String),
+    ReplayRecoveredEdits(Vec<TimeEntry>),
+    DiscardRecoveredEdits,
+    /// Replaces the stored entry that could not be loaded, or deletes it if `None`
+    RepairEntry {
+        raw_id: String,
+        entry: Option<TimeEntry>,
+    },
+    OfferIdleBreak(IdleSpan),
+    InsertBreak(IdleSpan),
+
+    OpenCompare,
+    CloseCompare,
+    FocusCompare(bool),
+    ShiftCompareDay(i64),
+    CopyFromCompare,
 }
 
 impl From<ErrReport> for HomeAction {
@@ -53,6 +228,7 @@ pub fn perform(home: &mut Home, action: HomeAction) -> Result<()> {
         home.send_action(Action::SetStatusLine(String::new()));
     }
     let actions = do_perform(home, action)?;
+    refresh_violations(home);
     for action in actions {
         if matches!(action, Action::SetStatusLine(_)) {
             home.need_status_line_reset = true;
@@ -64,6 +240,10 @@ pub fn perform(home: &mut Home, action: HomeAction) -> Result<()> {
 }
 
 fn do_perform(home: &mut Home, action: HomeAction) -> Result<Vec<Action>> {
+    if !matches!(action, HomeAction::None | HomeAction::Many(_)) {
+        let name: &'static str = (&action).into();
+        debug!(target: ACTION_TARGET, source = "home", action = name, day = %home.day);
+    }
     let out_action = match action {
         HomeAction::Many(actions) => {
             let mut results = vec![];
@@ -75,7 +255,11 @@ fn do_perform(home: &mut Home, action: HomeAction) -> Result<Vec<Action>> {
             return Ok(results);
         }
         HomeAction::EnterEditSpecific(Some(mode)) => {
+            home.record_usage(Feature::Edit);
             home.state.table.select_column(Some(mode.get_column_num()));
+            if matches!(mode, EditMode::Project(_)) {
+                home.send_persist(Command::LoadRecentProjects);
+            }
             home.edit_mode = Some(mode);
             Action::SetRelevantKeys(EDITING_KEYS.to_vec())
         }
@@ -87,11 +271,9 @@ fn do_perform(home: &mut Home, action: HomeAction) -> Result<Vec<Action>> {
         }
         HomeAction::SetStatusLine(msg) => Action::SetStatusLine(msg),
         HomeAction::SplitItemDown(idx) => 'block: {
-            let original_item = home
-                .state
-                .items
-                .get_mut(idx)
-                .expect("item to split to exist");
+            let Some(original_item) = home.state.items.get_mut(idx) else {
+                return Ok(vec![]);
+            };
             original_item.version.touch();
             let duration_mins = original_item.duration.as_secs().div_ceil(60);
             if duration_mins <= 1 {
@@ -105,6 +287,62 @@ fn do_perform(home: &mut Home, action: HomeAction) -> Result<Vec<Action>> {
             );
             original_item.start_time += new_item.duration;
             home.state.items.insert(idx, new_item);
+            home.record_usage(Feature::Split);
+            return Ok(vec![]);
+        }
+        HomeAction::EditSplitShares(idx) => {
+            let Some(item) = home.state.items.get(idx) else {
+                return Ok(vec![]);
+            };
+            let duration_mins = item.duration.as_secs().div_ceil(60);
+            home.popup = Some(SplitShares::new(idx, duration_mins, &item.ticket).into());
+            return Ok(vec![]);
+        }
+        HomeAction::SplitByShares { idx, shares } => 'block: {
+            let Some(original_item) = home.state.items.get_mut(idx) else {
+                return Ok(vec![]);
+            };
+            let duration_mins = original_item.duration.as_secs().div_ceil(60);
+            let percents = shares.iter().map(|it| it.percent).collect_vec();
+            let mins = split::apportion(duration_mins, &percents);
+            if mins.contains(&0) {
+                break 'block Action::SetStatusLine(format!(
+                    "{duration_mins}m is too short to split that way"
+                ));
+            }
+            let project_of = |ticket: &str| {
+                ticket_project::project_for(&home.config, ticket)
+                    .filter(|it| *it != home.config.default_project_key)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| original_item.project.clone())
+            };
+            let projects = shares.iter().map(|it| project_of(&it.ticket)).collect_vec();
+            let mut shares = shares.into_iter().zip(mins).zip(projects);
+            // The original keeps the first share, the others follow it
+            let ((first, first_mins), first_project) = shares.next().expect("at least two shares");
+            original_item.version.touch();
+            original_item.duration = Duration::from_secs(first_mins * 60);
+            original_item.project = first_project;
+            original_item.ticket = first.ticket;
+            let mut start_time = original_item.start_time + original_item.duration;
+            let new_items = shares
+                .map(|((share, mins), project)| {
+                    let mut item =
+                        original_item.continuation(Duration::from_secs(mins * 60), start_time);
+                    start_time += item.duration;
+                    item.project = project;
+                    item.ticket = share.ticket;
+                    item
+                })
+                .collect_vec();
+            for item in new_items.iter().filter(|it| !it.links.is_empty()) {
+                home.send_persist(Command::StoreLinks {
+                    entry_id: item.id.clone(),
+                    urls: item.links.clone(),
+                });
+            }
+            home.state.items.splice(idx + 1..idx + 1, new_items);
+            home.record_usage(Feature::Split);
             return Ok(vec![]);
         }
         HomeAction::MergeItemDown(idx) => 'block: {
@@ -120,38 +358,1048 @@ fn do_perform(home: &mut Home, action: HomeAction) -> Result<Vec<Action>> {
             remaining_item.duration += obsolete_item.duration;
             remaining_item.description += &format!(" / {}", obsolete_item.description);
             home.state.items_to_delete.push(obsolete_item);
+            home.record_usage(Feature::Merge);
             return Ok(vec![]);
         }
+        HomeAction::FindDuplicates => match MergeDuplicates::new(&home.state.items) {
+            Some(popup) => {
+                home.popup = Some(popup.into());
+                return Ok(vec![]);
+            }
+            None => Action::SetStatusLine("🧹 No consecutive duplicate entries".into()),
+        },
+        HomeAction::MergeDuplicates(runs) => {
+            let items = &mut home.state.items;
+            let mut merged = 0;
+            for ids in runs {
+                let Some((kept_id, obsolete_ids)) = ids.split_first() else {
+                    continue;
+                };
+                let mut duration = Duration::ZERO;
+                for id in obsolete_ids {
+                    if let Some(idx) = items.iter().position(|it| it.id == *id) {
+                        let obsolete_item = items.remove(idx);
+                        duration += obsolete_item.duration;
+                        home.state.items_to_delete.push(obsolete_item);
+                        merged += 1;
+                    }
+                }
+                if let Some(kept_item) = items.iter_mut().find(|it| it.id == *kept_id) {
+                    kept_item.duration += duration;
+                    kept_item.version.touch();
+                }
+            }
+            home.record_usage(Feature::Merge);
+            Action::SetStatusLine(format!("🧹 Merged away {merged} duplicate entries"))
+        }
         HomeAction::ExitToCalendar => Action::SetActivePage(Page::Calendar { day: home.day }),
-        HomeAction::SuggestTickets(query) => {
+        HomeAction::SuggestTickets { query, request_id } => {
             if !query.is_empty() {
-                home.send_persist(Command::SuggestTickets { query });
+                home.send_persist(Command::SuggestTickets { query, request_id });
             }
             return Ok(vec![]);
         }
         HomeAction::Export => {
-            use crate::components::home::export;
-            match export::export_timesheet(&home.state.items, home.day) {
-                Ok(()) => Action::SetStatusLine("✅ Exported to CSV and JSON".into()),
+            if validation::has_hard_violations(&home.violations) {
+                return Ok(vec![Action::SetStatusLine(
+                    "⛔ Export blocked by problems, press ! for details".into(),
+                )]);
+            }
+            home.send_persist(Command::LoadPreference {
+                key: export::FORMATS_PREFERENCE.into(),
+            });
+            return Ok(vec![]);
+        }
+        HomeAction::PickExportFormats(formats) => {
+            if home.popup.is_none() {
+                let defrag = settings::get().export.defrag_strategy;
+                home.popup = Some(ExportFormatPicker::new(&formats, defrag).into());
+            }
+            return Ok(vec![]);
+        }
+        HomeAction::PrepareExport { formats, defrag } => {
+            home.send_persist(Command::StorePreference {
+                key: export::FORMATS_PREFERENCE.into(),
+                value: export::format_formats(&formats),
+            });
+            let timesheet = home.state.timesheet.as_ref();
+            let notes = timesheet.map(|it| it.notes.as_str()).unwrap_or_default();
+            let utc_offset_mins = timesheet.and_then(|it| it.utc_offset_mins);
+            let prepared = export::prepare_timesheet_export(
+                &home.state.items,
+                home.day,
+                notes,
+                utc_offset_mins,
+                &formats,
+                defrag,
+            );
+            match prepared {
+                Ok(files) => {
+                    let preview_lines = settings::get().export.preview_lines;
+                    home.popup = Some(ExportPreview::new(files, preview_lines).into());
+                    return Ok(vec![]);
+                }
                 Err(e) => Action::SetStatusLine(format!("❌ Export failed: {e}")),
             }
         }
+        HomeAction::WriteExport(files) => match files.iter().try_for_each(ExportFile::write) {
+            Ok(()) => {
+                home.record_usage(Feature::Export);
+                for file in &files {
+                    export::run_hooks(file.path.clone(), home.day, home.action_tx.clone());
+                }
+                let paths = files.iter().map(|it| it.path.display()).join(", ");
+                Action::SetStatusLine(format!("✅ Exported to {paths}"))
+            }
+            Err(e) => Action::SetStatusLine(format!("❌ Export failed: {e}")),
+        },
         HomeAction::ToggleBreak => {
+            if let Some(idx) = home.state.table.selected() {
+                home.state.toggle_break(idx);
+            }
+            return Ok(vec![]);
+        }
+        HomeAction::FillGapAfter { idx, as_break } => 'block: {
+            let Some(gap) = home.state.gap_after(idx) else {
+                break 'block Action::SetStatusLine("no gap after this entry to fill".into());
+            };
+            let start_time = home.state.items[idx].next_start_time();
+            let mut filler = TimeItem::new(gap, start_time);
+            if as_break {
+                filler.project = BREAK_PROJECT_KEY.into();
+            }
+            home.state.items.insert(idx + 1, filler);
+            home.state.table.select(Some(idx + 1));
+            Action::SetStatusLine(format!("Filled {}m gap", gap.as_secs() / 60))
+        }
+        HomeAction::EditShift { from_idx } => {
+            let Some(item) = home.state.items.get(from_idx) else {
+                return Ok(vec![]);
+            };
+            let from_time = item.start_time.format("%H:%M").to_string();
+            home.popup = Some(ShiftTimes::new(from_idx, &from_time).into());
+            return Ok(vec![]);
+        }
+        HomeAction::ShiftItems { from_idx, mins } => match home.state.shift_from(from_idx, mins) {
+            Ok(()) => {
+                let count = home.state.items.len() - from_idx;
+                Action::SetStatusLine(format!("⏩ Shifted {count} entries by {mins:+}m"))
+            }
+            Err(err) => Action::SetStatusLine(format!("Cannot shift: {err}")),
+        },
+        HomeAction::NudgeDuration(mins) => match EditMode::nudge_duration(&mut home.state, mins) {
+            Ok(duration) => Action::SetStatusLine(format!(
+                "⏱️ {mins:+}m, now {}",
+                humantime::format_duration(duration)
+            )),
+            Err(err) => Action::SetStatusLine(format!("Cannot change the duration: {err}")),
+        },
+        HomeAction::EditRepairChain(repair) => {
+            let repaired = match home.state.repaired_chain(repair, home.day, home.day_zone()) {
+                Ok(repaired) => repaired,
+                Err(err) => {
+                    return Ok(vec![Action::SetStatusLine(format!(
+                        "Cannot repair times: {err}"
+                    ))]);
+                }
+            };
+            let popup = RepairChain::new(repair, &home.state.items, &repaired);
+            if popup.changed() == 0 {
+                Action::SetStatusLine("🔗 Start times and durations already line up".into())
+            } else {
+                home.popup = Some(popup.into());
+                return Ok(vec![]);
+            }
+        }
+        HomeAction::RepairChain(repair) => {
+            match home.state.repaired_chain(repair, home.day, home.day_zone()) {
+                Ok(repaired) => {
+                    let changed = home.state.apply_chain(repaired);
+                    Action::SetStatusLine(format!("🔗 Lined up {changed} entries"))
+                }
+                Err(err) => Action::SetStatusLine(format!("Cannot repair times: {err}")),
+            }
+        }
+        HomeAction::ToggleBillable => {
+            if let Some(item) = home.state.maybe_selected_item_mut() {
+                item.billable = !item.billable;
+            }
+            return Ok(vec![]);
+        }
+        HomeAction::ToggleCarryOver => {
+            if let Some(item) = home.state.maybe_selected_item_mut() {
+                item.carry_over = !item.carry_over;
+            }
+            return Ok(vec![]);
+        }
+        HomeAction::ShowDetails => {
+            if let Some(item) = home
+                .state
+                .table
+                .selected()
+                .and_then(|it| home.state.items.get(it))
+            {
+                home.popup = Some(EntryDetails::new(item).into());
+            }
+            return Ok(vec![]);
+        }
+        HomeAction::SetFlags {
+            billable,
+            carry_over,
+            exact,
+        } => {
+            if let Some(item) = home.state.maybe_selected_item_mut() {
+                item.billable = billable;
+                item.carry_over = carry_over;
+                item.exact = exact;
+            }
+            return Ok(vec![]);
+        }
+        HomeAction::PickWorkType => {
+            let Some(item) = home
+                .state
+                .table
+                .selected()
+                .and_then(|it| home.state.items.get(it))
+            else {
+                return Ok(vec![]);
+            };
+            home.popup = Some(WorkTypePicker::new(item.work_type.as_deref()).into());
+            return Ok(vec![]);
+        }
+        HomeAction::SetWorkType(work_type) => {
+            if let Some(item) = home.state.maybe_selected_item_mut() {
+                item.work_type = work_type;
+            }
+            return Ok(vec![]);
+        }
+        HomeAction::PickFlags => 'block: {
+            let Some(item) = home
+                .state
+                .table
+                .selected()
+                .and_then(|it| home.state.items.get(it))
+            else {
+                return Ok(vec![]);
+            };
+            let Some(picker) = FlagPicker::new(&item.flags) else {
+                break 'block Action::SetStatusLine(
+                    "No flags configured, see the flags section of the config".into(),
+                );
+            };
+            home.popup = Some(picker.into());
+            return Ok(vec![]);
+        }
+        HomeAction::SetFlagKeys(flags) => {
             if let Some(item) = home.state.maybe_selected_item_mut() {
-                item.project = if item.project == BREAK_PROJECT_KEY {
-                    ""
-                } else {
-                    "x"
+                item.flags = flags;
+            }
+            return Ok(vec![]);
+        }
+        HomeAction::PickAbsence => match AbsencePicker::new() {
+            Some(picker) => {
+                home.popup = Some(picker.into());
+                return Ok(vec![]);
+            }
+            None => Action::SetStatusLine(
+                "No absences configured, see the absences section of the config".into(),
+            ),
+        },
+        HomeAction::InsertAbsence(key) => 'block: {
+            let Some(absence) = home.config.absences.get(&key) else {
+                break 'block Action::SetStatusLine(format!("Unknown absence {key}"));
+            };
+            let duration = match time_expr::evaluate_duration(&absence.duration) {
+                Ok(duration) => duration,
+                Err(err) => {
+                    break 'block Action::SetStatusLine(format!(
+                        "Invalid duration of absence {key}: {err}"
+                    ));
                 }
-                .into();
+            };
+            let start_time = home.state.next_free_slot(duration);
+            let mut item = TimeItem::new(duration, start_time);
+            item.project = absence.project.clone();
+            item.description = absence.label.clone();
+            let message = format!(
+                "🌴 Inserted {} at {}",
+                absence.label,
+                start_time.format("%H:%M")
+            );
+            let idx = home.state.insert_chronologically(item);
+            home.state.table.select(Some(idx));
+            home.state.ensure_column_selected();
+            Action::SetStatusLine(message)
+        }
+        HomeAction::RetrySave => {
+            let retried = home
+                .state
+                .items
+                .iter_mut()
+                .map(|it| it.version.retry())
+                .filter(|retried| *retried)
+                .count();
+            if retried == 0 {
+                Action::SetStatusLine("No failed saves to retry".into())
+            } else {
+                home.retry.reset_attempts();
+                Action::SetStatusLine(format!("💾 Retrying to save {retried} entries"))
+            }
+        }
+        HomeAction::DiffSelected => {
+            let selected = home.state.table.selected();
+            let Some(id) =
+                selected.and_then(|idx| home.state.items.get(idx).map(|it| it.id.clone()))
+            else {
+                return Ok(vec![]);
+            };
+            home.send_persist(Command::LoadEntry(id));
+            return Ok(vec![]);
+        }
+        HomeAction::ShowEntryDiff { id, entry, links } => {
+            let Some(day) = home.state.timesheet.as_ref().map(|it| it.day.clone()) else {
+                return Ok(vec![]);
+            };
+            let selected = home.state.table.selected();
+            let Some(item) = selected
+                .and_then(|idx| home.state.items.get(idx))
+                .filter(|it| it.id == id)
+            else {
+                return Ok(vec![]);
+            };
+            if home.popup.is_none() {
+                let current = (item.to_persist(&day), item.links.clone());
+                let saved = entry.map(|it| (it, links));
+                home.popup = Some(EntryDiff::new(saved, current, &item.version).into());
             }
             return Ok(vec![]);
         }
+        HomeAction::DiscardChanges { entry, links } => 'block: {
+            let Some(item) = home
+                .state
+                .items
+                .iter_mut()
+                .find(|it| it.id.to_string() == entry.id)
+            else {
+                break 'block Action::SetStatusLine("The entry is gone, nothing to discard".into());
+            };
+            let mut version = item.version.clone();
+            if !version.discard() {
+                break 'block Action::SetStatusLine(
+                    "The changes are being saved, try again once they are".into(),
+                );
+            }
+            let mut saved = TimeItem::try_from(&entry)?;
+            saved.links = links;
+            saved.version = version;
+            let local_version = item.version.local;
+            *item = saved;
+            if let Some(journal) = &mut home.journal
+                && let Err(err) = journal.confirm(&item.id, local_version)
+            {
+                warn!(
+                    "Failed to drop discarded entry {} from the journal: {err:?}",
+                    item.id
+                );
+            }
+            Action::SetStatusLine(format!("↩️ Discarded the changes of {}", entry.start_time))
+        }
+        HomeAction::ShowConflict { entry, stored } => 'block: {
+            let Some(item) = home
+                .state
+                .items
+                .iter()
+                .find(|it| it.id.to_string() == entry.id)
+            else {
+                return Ok(vec![]);
+            };
+            if home.popup.is_some() {
+                break 'block Action::SetStatusLine(format!(
+                    "⚡ {} was changed elsewhere and is not saved, r to resolve",
+                    entry.start_time
+                ));
+            }
+            let links = item.links.clone();
+            home.popup = Some(ResolveConflict::new(entry, stored, links).into());
+            return Ok(vec![]);
+        }
+        HomeAction::KeepConflicting { entry, revision } => 'block: {
+            let Some(item) = home
+                .state
+                .items
+                .iter_mut()
+                .find(|it| it.id.to_string() == entry.id)
+            else {
+                break 'block Action::SetStatusLine("The entry is gone, nothing to keep".into());
+            };
+            let mut kept = TimeItem::try_from(&entry)?;
+            kept.links = std::mem::take(&mut item.links);
+            kept.version = item.version.clone();
+            kept.version.touch();
+            kept.revision = revision;
+            *item = kept;
+            Action::SetStatusLine(format!("💾 Keeping the changes of {}", entry.start_time))
+        }
+        HomeAction::ForgetDeleted(id) => 'block: {
+            let Some(idx) = home
+                .state
+                .items
+                .iter()
+                .position(|it| it.id.to_string() == id)
+            else {
+                break 'block Action::SetStatusLine("The entry is gone already".into());
+            };
+            let item = home.state.items.remove(idx);
+            if let Some(journal) = &mut home.journal
+                && let Err(err) = journal.confirm(&item.id, item.version.local)
+            {
+                warn!(
+                    "Failed to drop deleted entry {} from the journal: {err:?}",
+                    item.id
+                );
+            }
+            if home.state.items.is_empty() {
+                // Starts over with an initial item
+                home.send_persist(Command::LoadTimesheet { day: home.day });
+            } else if home
+                .state
+                .table
+                .selected()
+                .is_some_and(|it| it >= home.state.items.len())
+            {
+                home.state.table.select(Some(home.state.items.len() - 1));
+            }
+            Action::SetStatusLine(format!(
+                "🗑️ Removed {}, it was deleted elsewhere",
+                item.start_time.format("%H:%M")
+            ))
+        }
+        HomeAction::ConfirmReloadDay => {
+            let unsaved = home
+                .state
+                .items
+                .iter()
+                .filter(|it| it.version.is_unsaved())
+                .count();
+            if unsaved == 0 {
+                return do_perform(home, HomeAction::ReloadDay);
+            }
+            if home.popup.is_none() {
+                let question = format!(
+                    "Reload {} from the database? {unsaved} unsaved changes will be lost.",
+                    home.day
+                );
+                home.popup =
+                    Some(Confirm::new("Reload day", question, HomeAction::ReloadDay).into());
+            }
+            return Ok(vec![]);
+        }
+        HomeAction::ReloadDay => {
+            let discarded = discard_unsaved(home);
+            home.send_persist(Command::LoadTimesheet { day: home.day });
+            if discarded == 0 {
+                Action::SetStatusLine(format!("🔄 Reloaded {}", home.day))
+            } else {
+                Action::SetStatusLine(format!(
+                    "🔄 Reloaded {}, discarded {discarded} unsaved changes",
+                    home.day
+                ))
+            }
+        }
+        HomeAction::LoadHistory => {
+            home.send_persist(Command::LoadSnapshots { day: home.day });
+            return Ok(vec![]);
+        }
+        HomeAction::ShowHistory(snapshots) => {
+            if snapshots.is_empty() {
+                Action::SetStatusLine(format!("🕰️ No earlier versions of {} yet", home.day))
+            } else {
+                if home.popup.is_none() {
+                    home.popup = Some(History::new(home.day, snapshots).into());
+                }
+                return Ok(vec![]);
+            }
+        }
+        HomeAction::ConfirmRestoreSnapshot(taken_at) => {
+            let unsaved = home
+                .state
+                .items
+                .iter()
+                .filter(|it| it.version.is_unsaved())
+                .count();
+            let mut question = format!(
+                "Restore {} as of {taken_at}? The current version stays in the history.",
+                home.day
+            );
+            if unsaved > 0 {
+                question += &format!(" {unsaved} unsaved changes will be lost.");
+            }
+            home.popup = Some(
+                Confirm::new(
+                    "Restore day",
+                    question,
+                    HomeAction::RestoreSnapshot(taken_at),
+                )
+                .into(),
+            );
+            return Ok(vec![]);
+        }
+        HomeAction::RestoreSnapshot(taken_at) => {
+            discard_unsaved(home);
+            home.send_persist(Command::RestoreSnapshot {
+                day: home.day,
+                taken_at,
+            });
+            return Ok(vec![]);
+        }
+        HomeAction::ToggleEndColumn => {
+            let state = &mut home.state;
+            state.show_end = !state.show_end;
+            if !state.show_end && state.table.selected_column() == Some(END_COLUMN) {
+                state.table.select_column(Some(END_COLUMN - 1));
+            }
+            let shown = if state.show_end { "shown" } else { "hidden" };
+            Action::SetStatusLine(format!("End column {shown}"))
+        }
+        HomeAction::TogglePin => 'block: {
+            let Some(item) = home.state.maybe_selected_item() else {
+                return Ok(vec![]);
+            };
+            if home.state.pinned.as_ref() == Some(&item.id) {
+                home.state.pinned = None;
+                break 'block Action::SetStatusLine("📌 Unpinned".into());
+            }
+            if item.project == BREAK_PROJECT_KEY {
+                break 'block Action::SetStatusLine("breaks cannot be pinned".into());
+            }
+            if !home.is_today() {
+                break 'block Action::SetStatusLine("only entries of today can be pinned".into());
+            }
+            let start_time = item.start_time.format("%H:%M");
+            home.state.pinned = Some(item.id.clone());
+            home.state.extend_pinned(chrono::Local::now().time());
+            Action::SetStatusLine(format!(
+                "📌 Pinned {start_time}, its duration now follows the clock"
+            ))
+        }
+        HomeAction::StartTimer(timer) => 'block: {
+            if !home.is_today() || home.suspended || home.state.timesheet.is_none() {
+                home.pending_timer = Some(timer);
+                let today = OffsetDateTime::now_local()?.date();
+                break 'block Action::SetActivePage(Page::Home { day: today });
+            }
+            if home.edit_mode.is_some() {
+                break 'block Action::SetStatusLine("⏱ Finish editing to start a timer".into());
+            }
+            let now = chrono::Local::now().time();
+            let now = NaiveTime::from_hms_opt(now.hour(), now.minute(), 0).expect("valid time");
+            // Ends the current timer where the new one starts
+            home.state.extend_pinned(now);
+            let mut item = TimeItem::new(Duration::ZERO, now);
+            item.ticket = timer.ticket.unwrap_or_default();
+            item.description = timer.description;
+            let project = timer.project.as_deref().or_else(|| {
+                ticket_project::project_for(&home.config, &item.ticket)
+                    .filter(|it| *it != home.config.default_project_key)
+            });
+            item.project = project.unwrap_or_default().to_string();
+            let label = format!("{} {}", item.ticket, item.description);
+            home.state.pinned = Some(item.id.clone());
+            let idx = home.state.insert_chronologically(item);
+            home.state.table.select(Some(idx));
+            Action::SetStatusLine(format!(
+                "⏱ Started {} at {}",
+                label.trim(),
+                now.format("%H:%M")
+            ))
+        }
+        HomeAction::StopTimer => 'block: {
+            if home.state.pinned.is_none() || !home.is_today() {
+                break 'block Action::SetStatusLine("⏱ No timer running".into());
+            }
+            home.state.extend_pinned(chrono::Local::now().time());
+            home.state.pinned = None;
+            Action::SetStatusLine("⏱ Timer stopped".into())
+        }
+        HomeAction::ShowProblems => {
+            home.popup = Some(Problems::new(home.violations.clone()).into());
+            return Ok(vec![]);
+        }
+        HomeAction::EditFilter => {
+            home.popup = Some(RowFilter::new(home.state.filter.clone()).into());
+            return Ok(vec![]);
+        }
+        HomeAction::ClosePopup => {
+            home.popup = None;
+            return Ok(vec![]);
+        }
+        HomeAction::EditNotes => {
+            let notes = home.state.timesheet.as_ref().map(|it| it.notes.clone());
+            home.popup = Some(Notes::new(notes.unwrap_or_default()).into());
+            return Ok(vec![]);
+        }
+        HomeAction::EditTemplateName => {
+            let Some(item) = home.state.maybe_selected_item() else {
+                return Ok(vec![]);
+            };
+            let suggested_name = item.ticket.clone();
+            home.popup = Some(SaveTemplate::new(suggested_name).into());
+            return Ok(vec![]);
+        }
+        HomeAction::SaveTemplate(name) => {
+            let Some(item) = home.state.maybe_selected_item() else {
+                return Ok(vec![]);
+            };
+            let entry = item.to_persist(&home.day.to_string());
+            home.send_persist(Command::StoreTemplate(EntryTemplate {
+                name,
+                project_key: entry.project_key,
+                ticket_key: entry.ticket_key,
+                duration_mins: entry.duration_mins,
+                description: entry.description,
+                billable: entry.billable,
+            }));
+            return Ok(vec![]);
+        }
+        HomeAction::LoadTemplates => {
+            home.send_persist(Command::LoadTemplates);
+            return Ok(vec![]);
+        }
+        HomeAction::InsertTemplate(template) => {
+            let duration = Duration::from_secs(template.duration_mins.max(0) as u64 * 60);
+            let start_time = home.state.next_free_slot(duration);
+            let mut item = TimeItem::new(duration, start_time);
+            if template.project_key != settings::get().default_project_key {
+                item.project = template.project_key;
+            }
+            item.ticket = template.ticket_key.unwrap_or_default();
+            item.description = template.description;
+            item.billable = template.billable;
+            let idx = home.state.insert_chronologically(item);
+            home.state.table.select(Some(idx));
+            home.state.ensure_column_selected();
+            home.record_usage(Feature::Template);
+            Action::SetStatusLine(format!(
+                "📋 Inserted {} at {}",
+                template.name,
+                start_time.format("%H:%M")
+            ))
+        }
+        HomeAction::DeleteTemplate(name) => {
+            home.send_persist(Command::DeleteTemplate { name });
+            return Ok(vec![]);
+        }
+        HomeAction::StoreNotes(notes) => {
+            if let Some(timesheet) = &mut home.state.timesheet {
+                timesheet.notes = notes.clone();
+            }
+            home.send_persist(Command::StoreNotes {
+                day: home.day,
+                notes,
+            });
+            return Ok(vec![]);
+        }
+        HomeAction::EditLinks => {
+            let Some(item) = home.state.maybe_selected_item() else {
+                return Ok(vec![]);
+            };
+            home.popup = Some(Links::new(item.id.clone(), item.links.clone()).into());
+            return Ok(vec![]);
+        }
+        HomeAction::StoreLinks { id, urls } => {
+            if let Some(item) = home.state.items.iter_mut().find(|it| it.id == id) {
+                item.links = urls.clone();
+            }
+            home.send_persist(Command::StoreLinks { entry_id: id, urls });
+            return Ok(vec![]);
+        }
+        HomeAction::OpenLinks => {
+            let Some(item) = home.state.maybe_selected_item() else {
+                return Ok(vec![]);
+            };
+            if item.links.is_empty() {
+                Action::SetStatusLine("No links attached, add some with l".into())
+            } else {
+                let failed = item
+                    .links
+                    .iter()
+                    .filter_map(|url| {
+                        std::process::Command::new("xdg-open")
+                            .arg(url)
+                            .spawn()
+                            .err()
+                            .map(|err| format!("{url}: {err}"))
+                    })
+                    .collect_vec();
+                match failed.first() {
+                    None => Action::SetStatusLine(format!("🔗 Opened {}", item.links.join(", "))),
+                    Some(err) => Action::SetStatusLine(format!("Failed to open {err}")),
+                }
+            }
+        }
+        HomeAction::QueueFocusSuggestion(suggestion) => {
+            let message = format!(
+                "🪟 Suggested {} from {} to {}, W to review",
+                suggestion.matched.label(),
+                suggestion.start.format("%H:%M"),
+                suggestion.end.format("%H:%M"),
+            );
+            home.focus_suggestions.push(suggestion);
+            Action::SetStatusLine(message)
+        }
+        HomeAction::ReviewFocusSuggestions => 'block: {
+            if home.focus_suggestions.is_empty() {
+                break 'block Action::SetStatusLine("🪟 No suggestions from window focus".into());
+            }
+            let suggestions = home.focus_suggestions.clone();
+            home.popup = Some(FocusReview::new(suggestions).into());
+            return Ok(vec![]);
+        }
+        HomeAction::AcceptFocusSuggestion(suggestion) => 'block: {
+            if suggestion.start.date().to_string() != home.day.to_string() {
+                break 'block Action::SetStatusLine(format!(
+                    "🪟 Suggestion belongs to {}, open that day to add it",
+                    suggestion.start.date()
+                ));
+            }
+            home.focus_suggestions.retain(|it| *it != suggestion);
+            let mins = (suggestion.end - suggestion.start).num_minutes().max(1) as u64;
+            let mut item = TimeItem::new(Duration::from_secs(mins * 60), suggestion.start.time());
+            let matched = suggestion.matched;
+            item.ticket = matched.ticket.unwrap_or_default();
+            item.description = matched.description;
+            let project = matched.project.as_deref().or_else(|| {
+                ticket_project::project_for(&home.config, &item.ticket)
+                    .filter(|it| *it != home.config.default_project_key)
+            });
+            item.project = project.unwrap_or_default().to_string();
+            let idx = home.state.insert_chronologically(item);
+            home.state.table.select(Some(idx));
+            return Ok(vec![]);
+        }
+        HomeAction::DismissFocusSuggestion(suggestion) => {
+            home.focus_suggestions.retain(|it| *it != suggestion);
+            return Ok(vec![]);
+        }
+        HomeAction::PasteEntries => 'block: {
+            let text = match clipboard::paste() {
+                Ok(text) => text,
+                Err(err) => break 'block Action::SetStatusLine(format!("📋 {err}")),
+            };
+            let pasted = parse::pasted_entries(&text);
+            if pasted.is_empty() {
+                break 'block Action::SetStatusLine(
+                    "📋 No times like 09:00-09:30 in the clipboard".into(),
+                );
+            }
+            home.popup = Some(PastePreview::new(pasted, &home.state.items).into());
+            return Ok(vec![]);
+        }
+        HomeAction::CopyBookings => {
+            let config = &home.config.export;
+            let text = booking_text::generate_booking_text(
+                &home.summary().entries,
+                &config.booking_line,
+                &config.booking_separator,
+            );
+            if text.is_empty() {
+                Action::SetStatusLine("No bookings to copy".into())
+            } else if let Err(err) = clipboard::copy(text) {
+                Action::SetStatusLine(format!("📋 {err}"))
+            } else {
+                home.record_usage(Feature::CopyBookings);
+                Action::SetStatusLine("📋 Bookings copied".into())
+            }
+        }
+        HomeAction::ApplyPasted(entries) => {
+            let count = entries.len();
+            for entry in entries {
+                let duration = Duration::from_secs(entry.duration_mins as u64 * 60);
+                let mut item = TimeItem::new(duration, entry.start);
+                if let Some(ticket) = entry.ticket_key {
+                    item.project = ticket_project::project_for(&home.config, &ticket)
+                        .unwrap_or_default()
+                        .to_string();
+                    item.ticket = ticket;
+                }
+                item.description = entry.description;
+                home.state.insert_chronologically(item);
+            }
+            Action::SetStatusLine(format!("📋 Added {count} pasted entries"))
+        }
+        HomeAction::PrepareScaffold => {
+            let entries = scaffold::entries_for(&home.config, home.day.weekday());
+            if scaffold::needs_templates(entries) {
+                home.pending_scaffold = true;
+                home.send_persist(Command::LoadTemplates);
+                return Ok(vec![]);
+            }
+            match scaffold::resolve(entries, &[], &home.config) {
+                Ok(items) => return do_perform(home, HomeAction::OfferScaffold(items)),
+                Err(err) => Action::SetStatusLine(format!("🧱 {err}")),
+            }
+        }
+        HomeAction::OfferScaffold(items) => {
+            // e.g. the recovered changes of the last session come first
+            if items.is_empty() || home.popup.is_some() {
+                return Ok(vec![]);
+            }
+            if home.config.scaffold.auto_apply {
+                return do_perform(home, HomeAction::ApplyScaffold(items));
+            }
+            let weekday = home.day.weekday().to_string();
+            home.popup = Some(ScaffoldOffer::new(weekday, items).into());
+            return Ok(vec![]);
+        }
+        HomeAction::ApplyScaffold(items) => {
+            let count = items.len();
+            for scaffold_item in items {
+                let duration = Duration::from_secs(scaffold_item.duration_mins as u64 * 60);
+                let mut item = TimeItem::new(duration, scaffold_item.start);
+                item.project = scaffold_item.project_key.unwrap_or_default();
+                item.ticket = scaffold_item.ticket_key.unwrap_or_default();
+                item.description = scaffold_item.description;
+                item.billable = scaffold_item.billable;
+                home.state.insert_chronologically(item);
+            }
+            Action::SetStatusLine(format!(
+                "🧱 Started {} with {count} entries",
+                home.day.weekday()
+            ))
+        }
+        HomeAction::PrepareCarryOver => {
+            if !home.config.editing.carry_over_prompt || !home.is_today() {
+                return do_perform(home, HomeAction::PrepareScaffold);
+            }
+            home.send_persist(Command::LoadCarryOver { day: home.day });
+            return Ok(vec![]);
+        }
+        HomeAction::OfferCarryOver(entries) => {
+            if entries.is_empty() || home.popup.is_some() {
+                return do_perform(home, HomeAction::PrepareScaffold);
+            }
+            home.popup = Some(CarryOver::new(entries).into());
+            return Ok(vec![]);
+        }
+        HomeAction::ApplyCarryOver(entries) => {
+            let count = entries.len();
+            let previous_end = home
+                .state
+                .items
+                .iter()
+                .map(|it| it.next_start_time())
+                .max()
+                .unwrap_or(NaiveTime::MIN);
+            let mut start = shared::next_start(
+                previous_end,
+                Some(chrono::Local::now().time()),
+                home.config.editing.start_rounding_mins,
+            );
+            for entry in entries {
+                let duration = Duration::from_secs(entry.duration_mins as u64 * 60);
+                let mut item = TimeItem::new(duration, start);
+                if entry.project_key != home.config.default_project_key {
+                    item.project = entry.project_key;
+                }
+                item.ticket = entry.ticket_key.unwrap_or_default();
+                item.description = entry.description;
+                item.billable = entry.billable;
+                item.work_type = entry.work_type;
+                start = item.next_start_time();
+                home.state.insert_chronologically(item);
+            }
+            Action::SetStatusLine(format!("↪ Carried over {count} entries"))
+        }
+        HomeAction::ManageArchivedTickets => {
+            let tickets = home.archived_tickets.iter().cloned().sorted().collect_vec();
+            home.popup = Some(ArchivedTickets::new(tickets).into());
+            return Ok(vec![]);
+        }
+        HomeAction::ArchiveTicket(ticket_key) => {
+            home.archived_tickets.insert(ticket_key.clone());
+            home.send_persist(Command::ArchiveTicket { ticket_key });
+            return Ok(vec![]);
+        }
+        HomeAction::UnarchiveTicket(ticket_key) => {
+            home.archived_tickets.remove(&ticket_key);
+            home.send_persist(Command::UnarchiveTicket { ticket_key });
+            return Ok(vec![]);
+        }
+        HomeAction::ReplayRecoveredEdits(entries) => {
+            let count = entries.len();
+            let reload = entries
+                .iter()
+                .any(|it| it.timesheet_day == home.day.to_string());
+            for entry in entries {
+                home.send_persist(Command::StoreEntry {
+                    entry,
+                    version: journal::REPLAYED_VERSION,
+                });
+            }
+            if reload {
+                home.send_persist(Command::LoadTimesheet { day: home.day });
+            }
+            Action::SetStatusLine(format!("🩹 Replayed {count} unsaved changes"))
+        }
+        HomeAction::DiscardRecoveredEdits => {
+            if let Some(journal) = &mut home.journal
+                && let Err(err) = journal.discard_recovered()
+            {
+                warn!("Failed to discard recovered edits: {err:?}");
+            }
+            Action::SetStatusLine("🗑️ Discarded unsaved changes of the last session".into())
+        }
+        HomeAction::RepairEntry { raw_id, entry } => {
+            let message = match &entry {
+                Some(entry) => format!("🩹 Repaired the entry at {}", entry.start_time),
+                None => format!("🗑️ Deleted the corrupted entry {raw_id:?}"),
+            };
+            let reload = entry.is_some();
+            home.send_persist(Command::RepairEntry { raw_id, entry });
+            if reload {
+                home.send_persist(Command::LoadTimesheet { day: home.day });
+            }
+            Action::SetStatusLine(message)
+        }
+        HomeAction::OfferIdleBreak(span) => {
+            if home.popup.is_none() {
+                desktop::notify(
+                    "☕ Welcome back",
+                    &format!(
+                        "Record {}m idle time as break?",
+                        span.duration().num_minutes()
+                    ),
+                );
+                home.popup = Some(IdleReturn::new(span).into());
+            }
+            return Ok(vec![]);
+        }
+        HomeAction::InsertBreak(span) => {
+            let mins = span.duration().num_minutes();
+            let start = span.start.time();
+            let start =
+                NaiveTime::from_hms_opt(start.hour(), start.minute(), 0).expect("valid time");
+            let cut = home.state.cut_out(start, start + TimeDelta::minutes(mins));
+            let mut item = TimeItem::new(Duration::from_secs(mins as u64 * 60), start);
+            item.project = BREAK_PROJECT_KEY.into();
+            let idx = home.state.insert_chronologically(item);
+            home.state.table.select(Some(idx));
+            let cut = match cut {
+                Cut::Nothing => "",
+                Cut::Shortened => ", the entry running then ends before it",
+                Cut::Split(rest) => {
+                    if !rest.links.is_empty() {
+                        home.send_persist(Command::StoreLinks {
+                            entry_id: rest.id.clone(),
+                            urls: rest.links.clone(),
+                        });
+                    }
+                    home.state.insert_chronologically(*rest);
+                    ", the entry running then continues after it"
+                }
+            };
+            Action::SetStatusLine(format!("☕ Recorded {mins}m idle time as break{cut}"))
+        }
+        HomeAction::OpenCompare => {
+            let day = compare::shift_day(home.day, home.day, -1);
+            home.compare = Some(ComparePane::new(day));
+            home.send_persist(Command::LoadTimesheet { day });
+            Action::SetRelevantKeys(COMPARE_KEYS.to_vec())
+        }
+        HomeAction::CloseCompare => {
+            home.compare = None;
+            Action::SetRelevantKeys(SELECTING_KEYS.to_vec())
+        }
+        HomeAction::FocusCompare(focused) => {
+            let Some(pane) = &mut home.compare else {
+                return Ok(vec![]);
+            };
+            pane.focused = focused;
+            if focused {
+                Action::SetRelevantKeys(COMPARE_KEYS.to_vec())
+            } else {
+                Action::SetRelevantKeys(SELECTING_KEYS.to_vec())
+            }
+        }
+        HomeAction::ShiftCompareDay(delta_days) => {
+            let Some(pane) = &home.compare else {
+                return Ok(vec![]);
+            };
+            let day = compare::shift_day(pane.day, home.day, delta_days);
+            home.compare = Some(ComparePane::new(day));
+            home.send_persist(Command::LoadTimesheet { day });
+            return Ok(vec![]);
+        }
+        HomeAction::CopyFromCompare => 'block: {
+            let Some(source) = home.compare.as_ref().and_then(|it| it.selected_item()) else {
+                break 'block Action::SetStatusLine("nothing selected to copy".into());
+            };
+            let message = format!("Copied {} {}", source.ticket, source.description);
+            compare::copy_into(&mut home.state, source);
+            Action::SetStatusLine(message)
+        }
         HomeAction::None => return Ok(vec![]),
     };
     Ok(vec![out_action])
 }
 
+/// Forgets the changes that are not being saved yet, before loading the day again
+fn discard_unsaved(home: &mut Home) -> usize {
+    let mut discarded = 0;
+    for item in home.state.items.iter_mut() {
+        let local_version = item.version.local;
+        // Changes being sent are saved before the day is loaded again
+        if !item.version.is_unsaved() || !item.version.discard() {
+            continue;
+        }
+        discarded += 1;
+        if let Some(journal) = &mut home.journal
+            && let Err(err) = journal.confirm(&item.id, local_version)
+        {
+            warn!(
+                "Failed to drop discarded entry {} from the journal: {err:?}",
+                item.id
+            );
+        }
+    }
+    home.state.items_to_delete.clear();
+    discarded
+}
+
+fn refresh_violations(home: &mut Home) {
+    let Some(day) = home.state.timesheet.as_ref().map(|it| it.day.clone()) else {
+        home.violations.clear();
+        return;
+    };
+    let entries = home
+        .state
+        .items
+        .iter()
+        .map(|item| item.to_persist(&day))
+        .collect_vec();
+    home.violations = validation::validate(&entries, home.day_zone(), settings::get());
+    home.violations.extend(validation::check_archived(
+        &entries,
+        &home.archived_tickets,
+        settings::get(),
+    ));
+    let holiday = settings::holiday_of(home.day);
+    if let Some(holiday) = &holiday {
+        home.violations.extend(validation::check_holiday(
+            &entries,
+            holiday,
+            settings::get(),
+        ));
+    }
+    let min_applies = holiday.is_none() && home.is_past();
+    home.violations.extend(validation::check_day_duration(
+        &entries,
+        home.day.weekday(),
+        min_applies,
+        settings::get(),
+    ));
+}
+
 fn save_any_dirty_state(home: &mut Home) {
     let day = if let Some(day) = home.state.timesheet.clone().map(|it| it.day) {
         day
@@ -160,9 +1408,14 @@ fn save_any_dirty_state(home: &mut Home) {
     };
 
     let mut commands_to_send = Vec::new();
+    let unsaveable = validation::unsaveable_idxs(&home.violations);
+    let mut blocked = 0;
 
-    for item in &mut home.state.items {
-        if item.version.should_save() {
+    for (idx, item) in home.state.items.iter_mut().enumerate() {
+        if item.version.should_save() && unsaveable.contains(&idx) {
+            // Stays dirty, so that it is saved once fixed
+            blocked += 1;
+        } else if item.version.should_save() {
             commands_to_send.push(persist::Command::StoreEntry {
                 entry: item.to_persist(&day),
                 version: item.version.local,
@@ -178,6 +1431,12 @@ fn save_any_dirty_state(home: &mut Home) {
     for command in commands_to_send {
         home.send_persist(command);
     }
+    if blocked > 0 {
+        home.need_status_line_reset = true;
+        home.send_action(Action::SetStatusLine(format!(
+            "⛔ {blocked} entries past midnight are not saved until fixed, press ! for details"
+        )));
+    }
 }
 
 fn split_in_half(n: u64) -> (u64, u64) {
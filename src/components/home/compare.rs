@@ -0,0 +1,134 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style, palette::tailwind},
+    widgets::{Block, BorderType, Borders, Table, TableState},
+};
+use time::{Date, Duration};
+use tracing::error;
+
+use crate::{
+    components::home::{
+        action::HomeAction,
+        draw::{TITLE_FORMAT, create_row_for_item, table_header, table_widths},
+        state::{HomeState, TimeItem},
+    },
+    persist::TimeEntry,
+};
+
+/// Read-only second day shown next to the edited one, to copy entries from.
+pub struct ComparePane {
+    pub day: Date,
+    /// `None` while loading
+    items: Option<Vec<TimeItem>>,
+    table: TableState,
+    pub focused: bool,
+}
+
+impl ComparePane {
+    pub fn new(day: Date) -> Self {
+        Self {
+            day,
+            items: None,
+            table: TableState::default(),
+            focused: true,
+        }
+    }
+
+    pub fn handle_loaded(&mut self, entries: Vec<TimeEntry>) {
+        let items: Vec<TimeItem> = entries
+            .iter()
+            .filter_map(|entry| match entry.try_into() {
+                Ok(ok) => Some(ok),
+                Err(err) => {
+                    error!("Failed to load corrupted time entry: {entry:?} due to {err:?}");
+                    None
+                }
+            })
+            .filter(|item: &TimeItem| !item.duration.is_zero())
+            .collect();
+        if !items.is_empty() {
+            self.table.select_first();
+        }
+        self.items = Some(items);
+    }
+
+    pub fn handle_key_event(&mut self, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Up => self.table.select_previous(),
+            KeyCode::Down => self.table.select_next(),
+            KeyCode::Left => return HomeAction::ShiftCompareDay(-1),
+            KeyCode::Right => return HomeAction::ShiftCompareDay(1),
+            KeyCode::Enter | KeyCode::Char('c') => return HomeAction::CopyFromCompare,
+            KeyCode::Char('w') | KeyCode::Esc => return HomeAction::FocusCompare(false),
+            KeyCode::Char('C') => return HomeAction::CloseCompare,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    pub fn selected_item(&self) -> Option<&TimeItem> {
+        let idx = self.table.selected()?;
+        self.items.as_ref()?.get(idx)
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect, show_end: bool) {
+        let title = self
+            .day
+            .format(TITLE_FORMAT)
+            .unwrap_or_else(|_| self.day.to_string());
+        let border_color = if self.focused {
+            tailwind::INDIGO.c300
+        } else {
+            tailwind::SLATE.c500
+        };
+        let block = Block::new()
+            .borders(!Borders::BOTTOM)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::new().fg(border_color))
+            .title(format!("{title} (reference)"));
+        frame.render_widget(&block, area);
+        let area = block.inner(area);
+
+        let Some(items) = &self.items else {
+            frame.render_widget("Loading...", area);
+            return;
+        };
+        if items.is_empty() {
+            frame.render_widget("No entries on this day", area);
+            return;
+        }
+
+        let rows = items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| create_row_for_item(i, item, false, false, show_end));
+        let mut table = Table::new(rows, table_widths(show_end)).header(table_header(show_end));
+        if self.focused {
+            table = table.row_highlight_style(Style::from(Modifier::REVERSED));
+        }
+        frame.render_stateful_widget(table, area, &mut self.table);
+    }
+}
+
+/// Next reference day in the given direction, skipping the edited day itself
+pub fn shift_day(current: Date, edited: Date, delta_days: i64) -> Date {
+    let next = current + Duration::days(delta_days);
+    if next == edited {
+        next + Duration::days(delta_days)
+    } else {
+        next
+    }
+}
+
+/// Copies the item into the edited day, placed chronologically by its start time.
+pub fn copy_into(state: &mut HomeState, source: &TimeItem) {
+    let mut copy = TimeItem::new(source.duration, source.start_time);
+    copy.project = source.project.clone();
+    copy.ticket = source.ticket.clone();
+    copy.description = source.description.clone();
+
+    let idx = state.insert_chronologically(copy);
+    state.table.select(Some(idx));
+}
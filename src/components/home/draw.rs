@@ -1,43 +1,190 @@
+use std::time::Duration;
+
 use crate::{
     components::home::{
         EditModeBehavior, Home,
         editing::EditMode,
-        state::{TIME_ITEM_WIDTH, TimeItem},
+        popup::PopupBehavior,
+        state::{END_COLUMN, TIME_ITEM_WIDTH, TimeItem},
     },
+    config::{HomeConfig, ValidationRule},
     layout::LayoutSlot,
-    shared::BREAK_PROJECT_KEY,
+    persist::TimeEntryId,
+    settings,
+    shared::{
+        BREAK_PROJECT_KEY,
+        summary::TimesheetSummary,
+        tz::DayZone,
+        validation::{self, Severity, Violation},
+    },
 };
 use color_eyre::Result;
 use ratatui::{
     Frame,
-    layout::{Constraint, Rect},
+    layout::{Constraint, Layout, Rect},
     style::{Modifier, Style, Stylize, palette::tailwind},
-    widgets::{Block, BorderType, Borders, Cell, Row, Table},
+    text::Text,
+    widgets::{Block, BorderType, Borders, Cell, LineGauge, Paragraph, Row, Table},
 };
-use time::{format_description::FormatItem, macros::format_description};
+use time::{Date, format_description::FormatItem, macros::format_description};
 
 pub(super) fn draw(home: &mut Home, frame: &mut Frame, area: Rect) -> Result<()> {
-    let area = render_frame(home, frame, area)?;
+    let mut area = crate::layout::main_vert(LayoutSlot::MainCanvas, area);
+    if let Some(compare) = &mut home.compare {
+        let [edited_area, compare_area] =
+            Layout::horizontal([Constraint::Fill(1), Constraint::Fill(1)]).areas(area);
+        compare.draw(frame, compare_area, home.state.show_end);
+        area = edited_area;
+    }
+    let summary = home.summary();
+    let mut area = render_frame(home, &summary, frame, area)?;
+    if let Some(target_mins) = home.config.home.daily_target_mins {
+        let [gauge_area, table_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+        match settings::holiday_of(home.day) {
+            Some(holiday) => draw_holiday_gauge(&summary, frame, gauge_area, &holiday),
+            None => draw_target_gauge(&summary, frame, gauge_area, target_mins),
+        }
+        area = table_area;
+    }
+    if let Some(warning) = home
+        .violations
+        .iter()
+        .find(|it| it.rule == ValidationRule::DayDuration)
+    {
+        let [banner_area, table_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+        let banner = Paragraph::new(format!(" ⚠️ {}", warning.message)).style(
+            Style::new()
+                .fg(tailwind::AMBER.c950)
+                .bg(tailwind::AMBER.c400),
+        );
+        frame.render_widget(banner, banner_area);
+        area = table_area;
+    }
+    if home.week_bar.is_some() {
+        let [week_area, table_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+        draw_week_bar(home, summary.calculate_total_duration(), frame, week_area);
+        area = table_area;
+    }
+    let zone = home.day_zone();
     let state = &mut home.state;
 
     let selected_idx = state.table.selected();
-    let table = draw_table(&state.items, selected_idx, &home.edit_mode);
-    frame.render_stateful_widget(table, area, &mut state.table);
+    let visible_idxs = state.visible_idxs();
+    let show_end = state.show_end;
+    let table = draw_table(
+        &state.items,
+        &visible_idxs,
+        selected_idx,
+        &home.edit_mode,
+        &home.violations,
+        home.day,
+        zone,
+        state.pinned.as_ref(),
+        show_end,
+    );
+    let table_state = if state.is_filtered() {
+        // The rendered rows are only the visible ones, so select by position among them
+        let position = selected_idx.and_then(|idx| visible_idxs.iter().position(|it| *it == idx));
+        state.filtered_table.select(position);
+        state
+            .filtered_table
+            .select_column(state.table.selected_column());
+        &mut state.filtered_table
+    } else {
+        &mut state.table
+    };
+    // The state selects logical columns, but the hidden end column isn't rendered
+    let logical_column = table_state.selected_column();
+    table_state.select_column(logical_column.map(|it| to_display_column(it, show_end)));
+    frame.render_stateful_widget(table, area, table_state);
 
     if let Some(edit_mode) = &mut home.edit_mode
-        && let Some(popup) = edit_mode.draw_popup(&state.table, TABLE_WIDTHS)
+        && let Some(popup) = edit_mode.draw_popup(table_state, table_widths(show_end))
     {
         frame.render_widget(popup, area);
     }
+    let display_column = table_state.selected_column();
+    table_state.select_column(display_column.map(|it| to_logical_column(it, show_end)));
+
+    if let Some(popup) = &mut home.popup {
+        popup.draw(frame, area);
+    }
 
     Ok(())
 }
 
-fn render_frame(home: &mut Home, frame: &mut Frame, area: Rect) -> Result<Rect> {
-    let area = crate::layout::main_vert(LayoutSlot::MainCanvas, area);
+fn draw_week_bar(home: &mut Home, worked: time::Duration, frame: &mut Frame, area: Rect) {
+    let shows_day = home
+        .state
+        .timesheet
+        .as_ref()
+        .is_some_and(|it| it.day == home.day.to_string());
+    let Some(week_bar) = &mut home.week_bar else {
+        return;
+    };
+    if shows_day {
+        week_bar.set_worked(home.day, worked);
+    }
+    week_bar.draw(frame, area, home.day);
+}
 
-    let total_hours = home.total_working_hours();
-    let title = if total_hours.is_zero() {
+/// Absences count towards the target, as they are expected hours that need not be worked
+fn draw_target_gauge(summary: &TimesheetSummary, frame: &mut Frame, area: Rect, target_mins: u32) {
+    let worked_mins = summary.calculate_total_duration().whole_minutes().max(0) as u32;
+    let absent_mins = summary.calculate_absence_duration().whole_minutes().max(0) as u32;
+    let ratio = (worked_mins + absent_mins) as f64 / target_mins.max(1) as f64;
+    let color = match ratio {
+        1.0.. => tailwind::GREEN.c500,
+        0.5.. => tailwind::YELLOW.c500,
+        _ => tailwind::RED.c500,
+    };
+    let absent = if absent_mins > 0 {
+        format!(" + {}h{}m absent", absent_mins / 60, absent_mins % 60)
+    } else {
+        String::new()
+    };
+    let label = format!(
+        "🎯 {}h{}m{absent} / {}h{}m",
+        worked_mins / 60,
+        worked_mins % 60,
+        target_mins / 60,
+        target_mins % 60
+    );
+    let gauge = LineGauge::default()
+        .ratio(ratio.min(1.0))
+        .label(label)
+        .filled_style(Style::new().fg(color))
+        .unfilled_style(Style::new().fg(tailwind::SLATE.c700));
+    frame.render_widget(gauge, area);
+}
+
+/// Nothing is expected on public holidays, so the gauge only shows what was worked anyway
+fn draw_holiday_gauge(summary: &TimesheetSummary, frame: &mut Frame, area: Rect, holiday: &str) {
+    let worked_mins = summary.calculate_total_duration().whole_minutes().max(0) as u32;
+    let label = format!(
+        "🎉 {holiday}, {}h{}m worked",
+        worked_mins / 60,
+        worked_mins % 60
+    );
+    let gauge = LineGauge::default()
+        .ratio(1.0)
+        .label(label)
+        .filled_style(Style::new().fg(tailwind::ROSE.c400));
+    frame.render_widget(gauge, area);
+}
+
+fn render_frame(
+    home: &Home,
+    summary: &TimesheetSummary,
+    frame: &mut Frame,
+    area: Rect,
+) -> Result<Rect> {
+    let total_hours = summary.calculate_total_duration();
+    let billable_hours = summary.billable;
+    let mut title = if total_hours.is_zero() {
         home.day.format(TITLE_FORMAT)?
     } else {
         format!(
@@ -47,6 +194,32 @@ fn render_frame(home: &mut Home, frame: &mut Frame, area: Rect) -> Result<Rect>
             total_hours.whole_minutes() % 60
         )
     };
+    if !billable_hours.is_zero() {
+        title.push_str(&format!(
+            " ({}h{}m $)",
+            billable_hours.whole_hours(),
+            billable_hours.whole_minutes() % 60
+        ));
+    }
+    if let Some(zone) = home.day_zone().label() {
+        title.push_str(&format!(" - 🌍 {zone}"));
+    }
+    if home.state.is_filtered() {
+        title.push_str(&format!(
+            " - 🔍 \"{}\" ({} of {})",
+            home.state.filter,
+            home.state.visible_idxs().len(),
+            home.state.items.len()
+        ));
+    }
+    if !home.violations.is_empty() {
+        let icon = if validation::has_hard_violations(&home.violations) {
+            "⛔"
+        } else {
+            "⚠️"
+        };
+        title.push_str(&format!(" - {icon} {} problems", home.violations.len()));
+    }
 
     let block = Block::new()
         .borders(!Borders::BOTTOM)
@@ -57,26 +230,33 @@ fn render_frame(home: &mut Home, frame: &mut Frame, area: Rect) -> Result<Rect>
     Ok(block.inner(area))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn draw_table<'a>(
     items: &'a [TimeItem],
+    visible_idxs: &[usize],
     selected_idx: Option<usize>,
     edit_mode: &Option<EditMode>,
+    violations: &[Violation],
+    day: Date,
+    zone: DayZone,
+    pinned: Option<&TimeEntryId>,
+    show_end: bool,
 ) -> Table<'a> {
-    let mismatching_idxs = mark_mismatching_items(items);
-    let rows = items
+    let mismatching_idxs = mark_mismatching_items(items, day, zone);
+    let rows = visible_idxs
         .iter()
-        .enumerate()
-        .map(draw_item(selected_idx, edit_mode, &mismatching_idxs));
+        .map(|idx| (*idx, &items[*idx]))
+        .map(draw_item(
+            selected_idx,
+            edit_mode,
+            &mismatching_idxs,
+            violations,
+            pinned,
+            show_end,
+        ));
 
-    let header = TABLE_HEADERS
-        .into_iter()
-        .map(Cell::from)
-        .collect::<Row>()
-        .height(1)
-        .bg(tailwind::INDIGO.c900);
-
-    let table = Table::new(rows, TABLE_WIDTHS)
-        .header(header)
+    let table = Table::new(rows, table_widths(show_end))
+        .header(table_header(show_end))
         .row_highlight_style(Style::from(Modifier::REVERSED))
         .cell_highlight_style(
             Style::from(Modifier::BOLD)
@@ -94,24 +274,103 @@ fn draw_item(
     selected_idx: Option<usize>,
     edit_mode: &Option<EditMode>,
     mismatching_idxs: &[usize],
+    violations: &[Violation],
+    pinned: Option<&TimeEntryId>,
+    show_end: bool,
 ) -> impl Fn((usize, &TimeItem)) -> Row {
     move |(i, item)| -> Row {
         let is_selected = Some(i) == selected_idx;
         if is_selected && let Some(edit_mode) = edit_mode {
-            edit_mode.style_selected_item(item)
+            let row = visible_row(edit_mode.selected_item_cells(item), item, show_end);
+            if item.project == BREAK_PROJECT_KEY {
+                style_break(row)
+            } else {
+                row
+            }
         } else {
-            create_row_for_item(i, item, mismatching_idxs.contains(&i))
+            let is_pinned = pinned == Some(&item.id);
+            let is_mismatch = mismatching_idxs.contains(&i);
+            let row = create_row_for_item(i, item, is_mismatch, is_pinned, show_end);
+            highlight_violations(row, validation::worst_severity_of(violations, i))
         }
     }
 }
 
-fn create_row_for_item(i: usize, item: &TimeItem, is_mismatch: bool) -> Row<'_> {
+pub(super) fn create_row_for_item(
+    i: usize,
+    item: &TimeItem,
+    is_mismatch: bool,
+    is_pinned: bool,
+    show_end: bool,
+) -> Row<'_> {
+    let mut cells = item.as_cells(is_mismatch);
     if item.project == BREAK_PROJECT_KEY {
-        let mut cells = item.as_cells(is_mismatch);
-        cells[2] = "🏖️🏖️🏖️".into();
-        Row::new(cells).bg(tailwind::EMERALD.c900)
+        cells[3] = "🏖️🏖️🏖️".into();
+        style_break(visible_row(cells, item, show_end))
+    } else if settings::get().absence_of_project(&item.project).is_some() {
+        style_absence(visible_row(cells, item, show_end))
     } else {
-        zebra_stripe(i, item.as_row(is_mismatch))
+        let bucket = duration_bucket(item.duration, &settings::get().home);
+        cells[5] = style_duration(cells[5].clone(), bucket);
+        if is_pinned {
+            let duration = cells[5].clone();
+            let style = duration.style;
+            cells[5] = Text::from(format!("⏱ {duration}")).style(style);
+        }
+        zebra_stripe(i, visible_row(cells, item, show_end))
+    }
+}
+
+/// How much the length of an entry stands out, see [HomeConfig::tiny_entry_mins]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DurationBucket {
+    Tiny,
+    Normal,
+    Long,
+}
+
+fn duration_bucket(duration: Duration, config: &HomeConfig) -> DurationBucket {
+    let mins = duration.as_secs() / 60;
+    if duration.is_zero() {
+        DurationBucket::Normal
+    } else if mins < config.tiny_entry_mins as u64 {
+        DurationBucket::Tiny
+    } else if config.long_entry_mins > 0 && mins > config.long_entry_mins as u64 {
+        DurationBucket::Long
+    } else {
+        DurationBucket::Normal
+    }
+}
+
+fn style_duration(cell: Text<'_>, bucket: DurationBucket) -> Text<'_> {
+    match bucket {
+        DurationBucket::Tiny => cell.dim(),
+        DurationBucket::Normal => cell,
+        DurationBucket::Long => cell.bold().fg(tailwind::SKY.c300),
+    }
+}
+
+/// The cells of the shown columns, followed by the symbols of the flags
+fn visible_row<'a>(cells: [Text<'a>; TIME_ITEM_WIDTH], item: &TimeItem, show_end: bool) -> Row<'a> {
+    let flags = settings::get().flag_symbols(item.flags.iter().map(String::as_str));
+    let mut cells = visible_columns(cells, show_end);
+    cells.push(Text::from(flags));
+    Row::new(cells)
+}
+
+fn style_break(row: Row) -> Row {
+    row.bg(tailwind::EMERALD.c900)
+}
+
+fn style_absence(row: Row) -> Row {
+    row.bg(tailwind::VIOLET.c900)
+}
+
+fn highlight_violations(row: Row, severity: Option<Severity>) -> Row {
+    match severity {
+        Some(Severity::Hard) => row.fg(tailwind::RED.c300).bold(),
+        Some(Severity::Warning) => row.fg(tailwind::AMBER.c300),
+        None => row,
     }
 }
 
@@ -123,7 +382,7 @@ fn zebra_stripe(i: usize, row: Row) -> Row {
     row.style(Style::new().bg(alternating_color))
 }
 
-pub fn mark_mismatching_items(items: &[TimeItem]) -> Vec<usize> {
+pub fn mark_mismatching_items(items: &[TimeItem], day: Date, zone: DayZone) -> Vec<usize> {
     let mut mismatching_indices = Vec::new();
 
     for (i, current_item) in items.iter().enumerate() {
@@ -131,7 +390,11 @@ pub fn mark_mismatching_items(items: &[TimeItem]) -> Vec<usize> {
             break;
         };
 
-        let expected_next_start_time = current_item.next_start_time();
+        let expected_next_start_time = zone.wall_clock_after(
+            day,
+            current_item.start_time,
+            current_item.duration.as_secs() as i64 / 60,
+        );
         let actual_next_start_time = next_item.start_time;
 
         if expected_next_start_time != actual_next_start_time {
@@ -142,15 +405,60 @@ pub fn mark_mismatching_items(items: &[TimeItem]) -> Vec<usize> {
     mismatching_indices
 }
 
-const TITLE_FORMAT: &[FormatItem<'static>] =
+pub(super) const TITLE_FORMAT: &[FormatItem<'static>] =
     format_description!("📅 [weekday], [year]-[month]-[day] (KW [week_number])");
 
 const TABLE_WIDTHS: [Constraint; TIME_ITEM_WIDTH] = [
     // + 1 is for padding.
     Constraint::Length(5),
+    Constraint::Length(5),
     Constraint::Length(3),
     Constraint::Max(20),
     Constraint::Fill(1),
     Constraint::Max(10),
 ];
-const TABLE_HEADERS: [&str; TIME_ITEM_WIDTH] = ["#", "", "Ticket", "Description", "Duration"];
+const TABLE_HEADERS: [&str; TIME_ITEM_WIDTH] =
+    ["#", "End", "", "Ticket", "Description", "Duration"];
+/// Slim column after the others with the flags of the entry, which is never selected
+const FLAGS_WIDTH: Constraint = Constraint::Length(6);
+
+pub(super) fn table_widths(show_end: bool) -> Vec<Constraint> {
+    let mut widths = visible_columns(TABLE_WIDTHS, show_end);
+    widths.push(FLAGS_WIDTH);
+    widths
+}
+
+pub(super) fn table_header(show_end: bool) -> Row<'static> {
+    visible_columns(TABLE_HEADERS, show_end)
+        .into_iter()
+        .chain([""])
+        .map(Cell::from)
+        .collect::<Row>()
+        .height(1)
+        .bg(tailwind::INDIGO.c900)
+}
+
+fn visible_columns<T>(columns: [T; TIME_ITEM_WIDTH], show_end: bool) -> Vec<T> {
+    columns
+        .into_iter()
+        .enumerate()
+        .filter(|(col, _)| show_end || *col != END_COLUMN)
+        .map(|(_, it)| it)
+        .collect()
+}
+
+fn to_display_column(logical: usize, show_end: bool) -> usize {
+    if show_end || logical < END_COLUMN || logical == usize::MAX {
+        logical
+    } else {
+        logical - 1
+    }
+}
+
+fn to_logical_column(display: usize, show_end: bool) -> usize {
+    if show_end || display < END_COLUMN {
+        display
+    } else {
+        display + 1
+    }
+}
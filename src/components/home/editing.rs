@@ -1,12 +1,14 @@
+use color_eyre::eyre::{OptionExt, Result, bail};
 use crossterm::event::KeyEvent;
 use enum_dispatch::enum_dispatch;
 use ratatui::prelude::Constraint;
-use ratatui::widgets::{Row, Table, TableState};
+use ratatui::text::Text;
+use ratatui::widgets::{Table, TableState};
 
 use crate::components::home::{
     action::HomeAction,
     editing::project::Project,
-    state::{HomeState, TimeItem},
+    state::{END_COLUMN, HomeState, TIME_ITEM_WIDTH, TimeItem},
 };
 use crate::persist;
 use crate::widgets::table_popup::TablePopup;
@@ -14,18 +16,21 @@ use crate::widgets::table_popup::TablePopup;
 mod shared;
 pub(super) use shared::EditModeBehavior;
 
+mod alias;
 mod description;
 mod duration;
+mod end;
 mod project;
 mod ticket;
 mod time;
 
-use self::{description::Description, duration::Duration, ticket::Ticket, time::Time};
+use self::{description::Description, duration::Duration, end::End, ticket::Ticket, time::Time};
 
 #[derive(PartialEq, Eq)]
 #[enum_dispatch(EditModeBehavior)]
 pub enum EditMode {
     Time,
+    End,
     Project,
     Ticket,
     Description,
@@ -37,6 +42,10 @@ impl EditMode {
         Time::default().into()
     }
 
+    pub fn of_end() -> Self {
+        End::default().into()
+    }
+
     pub fn of_project(state: &HomeState) -> Self {
         Project::new(state).into()
     }
@@ -56,21 +65,50 @@ impl EditMode {
     pub fn from_column_num(idx: usize, state: &HomeState) -> Option<Self> {
         Some(match idx {
             0 => Self::of_time(),
-            1 => Self::of_project(state),
-            2 => Self::of_ticket(state),
-            3 => Self::of_description(state),
-            4 | usize::MAX => Self::of_duration(), // MAX is set by select_last_column()
+            END_COLUMN => Self::of_end(),
+            2 => Self::of_project(state),
+            3 => Self::of_ticket(state),
+            4 => Self::of_description(state),
+            5 | usize::MAX => Self::of_duration(), // MAX is set by select_last_column()
             _ => return None,
         })
     }
 
+    /// Grows or shrinks the selected item without editing it, taking the difference from or
+    /// giving it to the following item, see [crate::shared::nudge_next]
+    pub fn nudge_duration(state: &mut HomeState, mins: i64) -> Result<std::time::Duration> {
+        let idx = state.table.selected().ok_or_eyre("No entry selected")?;
+        let item = state.items.get(idx).ok_or_eyre("No entry selected")?;
+        let current_mins = (item.duration.as_secs() / 60) as i64;
+        if current_mins + mins <= 0 {
+            bail!("{current_mins}m can't be shortened by {}m", -mins);
+        }
+        let duration = std::time::Duration::from_secs((current_mins + mins) as u64 * 60);
+        let end = item.next_start_time();
+        let new_end = item.start_time + duration;
+        if let Some(next) = state.items.get_mut(idx + 1) {
+            let next_mins = (next.duration.as_secs() / 60) as u32;
+            let nudged = crate::shared::nudge_next(end, new_end, next.start_time, next_mins)?;
+            if nudged != (next.start_time, next_mins) {
+                next.start_time = nudged.0;
+                next.duration = std::time::Duration::from_secs(u64::from(nudged.1) * 60);
+                next.version.touch();
+            }
+        }
+        let item = state.expect_selected_item_mut();
+        item.duration = duration;
+        item.version.touch();
+        Ok(duration)
+    }
+
     pub fn get_column_num(&self) -> usize {
         match self {
             EditMode::Time(_) => 0,
-            EditMode::Project(_) => 1,
-            EditMode::Ticket(_) => 2,
-            EditMode::Description(_) => 3,
-            EditMode::Duration(_) => 4,
+            EditMode::End(_) => END_COLUMN,
+            EditMode::Project(_) => 2,
+            EditMode::Ticket(_) => 3,
+            EditMode::Description(_) => 4,
+            EditMode::Duration(_) => 5,
         }
     }
 }
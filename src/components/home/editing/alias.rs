@@ -0,0 +1,62 @@
+use itertools::Itertools;
+use ratatui::{
+    layout::Constraint,
+    text::Line,
+    widgets::{ListItem, ListState, TableState},
+};
+
+use crate::{
+    components::home::state::HomeState, config::AliasConfig, settings, shared::alias,
+    widgets::table_popup::TablePopup,
+};
+
+/// Fills the alias named by the typed text into the selected item, returning it if found
+pub fn expand(state: &mut HomeState, typed: &str) -> Option<AliasConfig> {
+    let alias = alias::resolve(settings::get(), typed)?.clone();
+    let item = state.expect_selected_item_mut();
+    if let Some(project) = &alias.project {
+        item.project = project.clone();
+    }
+    if let Some(ticket) = &alias.ticket {
+        item.ticket = ticket.clone();
+    }
+    if let Some(description) = &alias.description {
+        item.description = description.clone();
+    }
+    item.version.touch();
+    Some(alias)
+}
+
+/// Lists the aliases that the typed text could expand to
+#[derive(Default)]
+pub struct AliasHints {
+    list_state: ListState,
+}
+
+impl AliasHints {
+    pub fn as_popup<'a, CI>(
+        &'a mut self,
+        typed: &str,
+        table_state: &'a TableState,
+        constraints: CI,
+    ) -> Option<TablePopup<'a>>
+    where
+        CI: IntoIterator<Item = Constraint>,
+    {
+        let items = alias::matching(settings::get(), typed)
+            .into_iter()
+            .map(|(name, it)| {
+                ListItem::from(Line::from(format!("{name} ⇥ {}", alias::describe(it))))
+            })
+            .collect_vec();
+        if items.is_empty() {
+            return None;
+        }
+        Some(TablePopup::new(
+            table_state,
+            &mut self.list_state,
+            items,
+            constraints,
+        ))
+    }
+}
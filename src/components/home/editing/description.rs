@@ -1,20 +1,28 @@
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
+    layout::Constraint,
     style::{Modifier, Style, Stylize, palette::tailwind},
     text::Text,
-    widgets::{Row, Table},
+    widgets::{Table, TableState},
 };
 use tracing::info;
 
 use super::EditModeBehavior;
-use crate::components::home::{
-    action::HomeAction,
-    editing::shared::BufEditBehavior,
-    state::{HomeState, TimeItem},
+use crate::{
+    components::home::{
+        action::HomeAction,
+        editing::{
+            alias::{self, AliasHints},
+            shared::BufEditBehavior,
+        },
+        state::{HomeState, TIME_ITEM_WIDTH, TimeItem},
+    },
+    widgets::table_popup::TablePopup,
 };
 
 pub struct Description {
     buf: BufEditBehavior,
+    aliases: AliasHints,
 }
 
 impl Description {
@@ -22,6 +30,7 @@ impl Description {
         let item = state.expect_selected_item();
         Self {
             buf: item.description.to_owned().into(),
+            aliases: Default::default(),
         }
     }
 
@@ -35,16 +44,24 @@ impl Description {
 impl EditModeBehavior for Description {
     fn handle_key_event(&mut self, state: &mut HomeState, key: KeyEvent) -> HomeAction {
         info!("Description key: {key:?}");
+        if key.code == KeyCode::Tab && alias::expand(state, &self.buf).is_some() {
+            self.buf = state.expect_selected_item().description.to_owned().into();
+            return HomeAction::None;
+        }
         if self.buf.should_save(key) {
             self.do_save(state);
         }
         self.buf.handle_key_event(state, key)
     }
 
-    fn style_selected_item<'a>(&self, item: &'a TimeItem) -> Row<'a> {
-        let mut cells = item.as_cells(false).clone();
-        cells[3] = Text::from(self.buf.to_owned());
-        Row::new(cells)
+    fn autosave(&mut self, state: &mut HomeState) -> bool {
+        self.buf.autosave_into(state, |item| &mut item.description)
+    }
+
+    fn selected_item_cells<'a>(&self, item: &'a TimeItem) -> [Text<'a>; TIME_ITEM_WIDTH] {
+        let mut cells = item.as_cells(false);
+        cells[4] = Text::from(self.buf.to_owned());
+        cells
     }
 
     fn style_table<'a>(&self, table: Table<'a>) -> Table<'a> {
@@ -54,4 +71,15 @@ impl EditModeBehavior for Description {
                 .bg(tailwind::INDIGO.c300),
         )
     }
+
+    fn draw_popup<'a, CI>(
+        &'a mut self,
+        table_state: &'a TableState,
+        constraints: CI,
+    ) -> Option<TablePopup<'a>>
+    where
+        CI: IntoIterator<Item = Constraint>,
+    {
+        self.aliases.as_popup(&self.buf, table_state, constraints)
+    }
 }
@@ -1,20 +1,23 @@
 use std::cmp::Ordering;
 
-use chrono::TimeDelta;
+use chrono::{NaiveTime, TimeDelta};
 use color_eyre::eyre::{Result, bail, eyre};
 use crossterm::event::KeyEvent;
-use humantime::parse_duration;
 use ratatui::{
     style::{Modifier, Style, Stylize, palette::tailwind},
     text::Text,
-    widgets::{Row, Table},
+    widgets::Table,
 };
 
 use super::EditModeBehavior;
-use crate::components::home::{
-    action::HomeAction,
-    editing::shared::BufEditBehavior,
-    state::{HomeState, TimeItem},
+use crate::{
+    components::home::{
+        action::HomeAction,
+        editing::shared::BufEditBehavior,
+        state::{HomeState, TIME_ITEM_WIDTH, TimeItem},
+    },
+    settings,
+    shared::{self, time_expr},
 };
 
 #[derive(Default)]
@@ -34,17 +37,26 @@ impl Duration {
             }
         }
 
-        if self.buf.parse::<u16>().is_ok() {
-            self.buf.push('m');
-        }
+        if let Some(end_time) = parse_end_time(&self.buf)? {
+            if end_time <= item.start_time {
+                bail!(
+                    "{} is not after the start at {}",
+                    end_time.format("%H:%M"),
+                    item.start_time.format("%H:%M")
+                );
+            }
+            let mins = (end_time - item.start_time).num_minutes() as u64;
+            item.duration = std::time::Duration::from_secs(mins * 60);
+        } else {
+            let parsed =
+                time_expr::evaluate_duration(&self.buf).map_err(|err| eyre!("Invalid: {err}"))?;
+            if parsed.as_secs() % 60 != 0 {
+                bail!("Duration must be a whole number of minutes (e.g. 15m)");
+            }
 
-        let parsed = parse_duration(&self.buf).map_err(|err| eyre!("Invalid: {err}"))?;
-        if parsed.as_secs() % 60 != 0 {
-            bail!("Duration must be a whole number of minutes (e.g. 15m)");
+            item.duration = parsed;
         }
 
-        item.duration = parsed;
-
         if state.is_last_row_selected() {
             Self::create_next_item(state);
         } else {
@@ -55,15 +67,24 @@ impl Duration {
         Ok(())
     }
 
-    fn create_next_item(state: &mut HomeState) {
-        let new_item = TimeItem::new(
-            Default::default(),
-            state.expect_selected_item().next_start_time(),
+    /// Adds an item after the last one, starting now if recording today as it happens
+    pub(super) fn create_next_item(state: &mut HomeState) {
+        let previous_end = state.expect_selected_item().next_start_time();
+        let now = chrono::Local::now();
+        let is_today = state
+            .timesheet
+            .as_ref()
+            .is_some_and(|it| it.day == now.format("%Y-%m-%d").to_string());
+        let start_time = shared::next_start(
+            previous_end,
+            is_today.then(|| now.time()),
+            settings::get().editing.start_rounding_mins,
         );
+        let new_item = TimeItem::new(Default::default(), start_time);
         state.items.push(new_item);
     }
 
-    fn adjust_following_items(state: &mut HomeState) {
+    pub(super) fn adjust_following_items(state: &mut HomeState) {
         let my_index = state.table.selected().expect("selected");
         let last_index = state.items.len() - 1;
         let new_end_time = state.expect_selected_item().next_start_time();
@@ -109,6 +130,22 @@ impl Duration {
     }
 }
 
+/// End time instead of a duration, like `=1030` or `until 10:30`
+fn parse_end_time(input: &str) -> Result<Option<NaiveTime>> {
+    let input = input.trim();
+    let Some(time) = input
+        .strip_prefix('=')
+        .or_else(|| input.strip_prefix("until "))
+    else {
+        return Ok(None);
+    };
+    let time = time.trim();
+    NaiveTime::parse_from_str(time, "%H%M")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+        .map(Some)
+        .map_err(|err| eyre!("Invalid end time {time}: {err}"))
+}
+
 impl EditModeBehavior for Duration {
     fn handle_key_event(&mut self, state: &mut HomeState, key: KeyEvent) -> HomeAction {
         if self.buf.should_save(key)
@@ -120,14 +157,14 @@ impl EditModeBehavior for Duration {
         self.buf.handle_key_event(state, key)
     }
 
-    fn style_selected_item<'a>(&self, item: &'a TimeItem) -> Row<'a> {
-        let mut cells = item.as_cells(false).clone();
+    fn selected_item_cells<'a>(&self, item: &'a TimeItem) -> [Text<'a>; TIME_ITEM_WIDTH] {
+        let mut cells = item.as_cells(false);
         let mut content = self.buf.to_owned();
         if content.is_empty() {
             content = "...".into();
         }
-        cells[4] = Text::from(content);
-        Row::new(cells)
+        cells[5] = Text::from(content);
+        cells
     }
 
     fn style_table<'a>(&self, table: Table<'a>) -> Table<'a> {
@@ -0,0 +1,84 @@
+use chrono::NaiveTime;
+use color_eyre::eyre::{Result, bail, eyre};
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    style::{Modifier, Style, Stylize, palette::tailwind},
+    text::Text,
+    widgets::Table,
+};
+
+use super::{EditModeBehavior, duration::Duration};
+use crate::components::home::{
+    action::HomeAction,
+    editing::shared::BufEditBehavior,
+    state::{END_COLUMN, HomeState, TIME_ITEM_WIDTH, TimeItem},
+};
+
+/// Edits where the item ends, which behaves like editing the next item's start
+#[derive(Default)]
+pub struct End {
+    buf: BufEditBehavior,
+}
+
+impl End {
+    fn handle_save(&self, state: &mut HomeState) -> Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+
+        let parsed = NaiveTime::parse_from_str((&self.buf).into(), "%H%M");
+        let parsed = parsed.map_err(|err| eyre!("invalid: {err}"))?;
+
+        let item = state.expect_selected_item_mut();
+        if parsed <= item.start_time {
+            bail!(
+                "{} is not after the start at {}",
+                parsed.format("%H:%M"),
+                item.start_time.format("%H:%M")
+            );
+        }
+        let mins = (parsed - item.start_time).num_minutes() as u64;
+        item.duration = std::time::Duration::from_secs(mins * 60);
+        item.version.touch();
+
+        if state.is_last_row_selected() {
+            Duration::create_next_item(state);
+        } else {
+            Duration::adjust_following_items(state);
+        }
+        Ok(())
+    }
+}
+
+impl EditModeBehavior for End {
+    fn handle_key_event(&mut self, state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        if self.buf.should_save(key)
+            && let Err(err) = self.handle_save(state)
+        {
+            return err.into();
+        }
+        match key.code {
+            KeyCode::Enter => HomeAction::ExitEdit,
+            KeyCode::Char(_) if self.buf.len() >= 4 => HomeAction::None,
+            _ => self.buf.handle_key_event(state, key),
+        }
+    }
+
+    fn selected_item_cells<'a>(&self, item: &'a TimeItem) -> [Text<'a>; TIME_ITEM_WIDTH] {
+        let mut cells = item.as_cells(false);
+        let mut content = self.buf.to_owned();
+        if content.is_empty() {
+            content = "...".into();
+        }
+        cells[END_COLUMN] = Text::from(content);
+        cells
+    }
+
+    fn style_table<'a>(&self, table: Table<'a>) -> Table<'a> {
+        table.cell_highlight_style(
+            Style::from(Modifier::UNDERLINED)
+                .not_reversed()
+                .bg(tailwind::INDIGO.c300),
+        )
+    }
+}
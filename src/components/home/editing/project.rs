@@ -1,42 +1,94 @@
-use crossterm::event::KeyEvent;
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
 use ratatui::{
+    layout::Constraint,
     style::{Modifier, Style, Stylize, palette::tailwind},
-    text::Text,
-    widgets::{Row, Table},
+    text::{Line, Span, Text},
+    widgets::{ListItem, ListState, Table, TableState},
 };
 
 use super::EditModeBehavior;
-use crate::components::home::{
-    action::HomeAction,
-    editing::shared::BufEditBehavior,
-    state::{HomeState, TimeItem},
+use crate::{
+    components::home::{
+        action::HomeAction,
+        editing::shared::BufEditBehavior,
+        state::{HomeState, TIME_ITEM_WIDTH, TimeItem},
+    },
+    persist::Event,
+    settings,
+    shared::{BREAK_PROJECT_KEY, fuzzy},
+    widgets::table_popup::TablePopup,
 };
 
 pub struct Project {
     buf: BufEditBehavior,
+    picker: ProjectPicker,
+    /// Project that is not configured and was saved once, saving it again uses it
+    unconfigured: Option<String>,
 }
 
 impl Project {
     pub fn new(state: &HomeState) -> Self {
         let item = state.expect_selected_item();
+        let mut picker = ProjectPicker::default();
+        picker.filter(&item.project);
         Self {
             buf: item.project.to_owned().into(),
+            picker,
+            unconfigured: None,
         }
     }
+
+    /// Whether the buffer is neither configured, nor used recently, nor already the project
+    fn needs_confirmation(&self, state: &HomeState) -> bool {
+        let project = self.buf.trim();
+        !project.is_empty()
+            && project != BREAK_PROJECT_KEY
+            && !settings::get().projects.contains_key(project)
+            && !self.picker.recent.iter().any(|it| it == project)
+            && state.expect_selected_item().project != project
+    }
 }
 
 impl EditModeBehavior for Project {
     fn handle_key_event(&mut self, state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match self.picker.handle_key_event(key) {
+            PickAction::Done => return HomeAction::None,
+            PickAction::Accept(picked) => {
+                self.buf = picked.into();
+            }
+            PickAction::None => {}
+        }
+
         if self.buf.should_save(key) {
+            if self.needs_confirmation(state) && self.unconfigured.as_deref() != Some(&*self.buf) {
+                self.unconfigured = Some(self.buf.to_string());
+                return HomeAction::SetStatusLine(format!(
+                    "❓ {} is not a configured project, press again to use it anyway",
+                    self.buf.trim()
+                ));
+            }
             state.expect_selected_item_mut().project = self.buf.to_owned();
         }
-        self.buf.handle_key_event(state, key)
+
+        let action = self.buf.handle_key_event(state, key);
+        if self.buf != self.picker.query {
+            self.picker.filter(&self.buf);
+        }
+        action
+    }
+
+    fn autosave(&mut self, state: &mut HomeState) -> bool {
+        if self.needs_confirmation(state) {
+            return false;
+        }
+        self.buf.autosave_into(state, |item| &mut item.project)
     }
 
-    fn style_selected_item<'a>(&self, item: &'a TimeItem) -> Row<'a> {
-        let mut cells = item.as_cells(false).clone();
-        cells[1] = Text::from(self.buf.to_owned());
-        Row::new(cells)
+    fn selected_item_cells<'a>(&self, item: &'a TimeItem) -> [Text<'a>; TIME_ITEM_WIDTH] {
+        let mut cells = item.as_cells(false);
+        cells[2] = Text::from(self.buf.to_owned());
+        cells
     }
 
     fn style_table<'a>(&self, table: Table<'a>) -> Table<'a> {
@@ -46,4 +98,155 @@ impl EditModeBehavior for Project {
                 .bg(tailwind::INDIGO.c300),
         )
     }
+
+    fn draw_popup<'a, CI>(
+        &'a mut self,
+        table_state: &'a TableState,
+        constraints: CI,
+    ) -> Option<TablePopup<'a>>
+    where
+        CI: IntoIterator<Item = Constraint>,
+    {
+        self.picker
+            .is_active()
+            .then(|| self.picker.as_popup(table_state, constraints))
+    }
+
+    fn handle_persisted(&mut self, event: Event) {
+        if let Event::RecentProjectsLoaded(recent) = event {
+            self.picker.recent = recent;
+            let query = std::mem::take(&mut self.picker.query);
+            self.picker.filter(&query);
+        }
+    }
+}
+
+/// Project offered by the picker
+struct ProjectChoice {
+    key: String,
+    /// `None` for projects that were used recently, but are not configured
+    internal_name: Option<String>,
+}
+
+#[derive(Default)]
+struct ProjectPicker {
+    query: String,
+    /// Keys of recent entries, most recently used first
+    recent: Vec<String>,
+    /// Configured and recent projects matching the query, best first
+    choices: Vec<ProjectChoice>,
+    list_state: ListState,
+    /// Closed with Esc, until the query changes
+    dismissed: bool,
+}
+
+enum PickAction {
+    None,
+    Done,
+    Accept(String),
+}
+
+impl ProjectPicker {
+    fn is_active(&self) -> bool {
+        !self.dismissed && !self.choices.is_empty()
+    }
+
+    /// Configured projects by key, then the ones only used recently, each ranked by how well
+    /// the key or internal name matches the query
+    fn filter(&mut self, query: &str) {
+        let config = settings::get();
+        let configured = config
+            .projects
+            .iter()
+            .sorted_by_key(|(key, _)| key.as_str())
+            .map(|(key, project)| ProjectChoice {
+                key: key.clone(),
+                internal_name: Some(project.internal_name.clone()),
+            });
+        let ad_hoc = self
+            .recent
+            .iter()
+            .filter(|it| !config.projects.contains_key(*it))
+            .map(|key| ProjectChoice {
+                key: key.clone(),
+                internal_name: None,
+            });
+        self.query = query.to_string();
+        let query = query.trim();
+        self.choices = configured
+            .chain(ad_hoc)
+            .filter_map(|choice| {
+                let texts = [Some(choice.key.as_str()), choice.internal_name.as_deref()];
+                let score = fuzzy::best_score(query, texts.into_iter().flatten())?;
+                Some((choice, score))
+            })
+            .sorted_by_key(|(_, score)| std::cmp::Reverse(*score))
+            .map(|(choice, _)| choice)
+            .collect();
+        self.dismissed = false;
+        self.list_state
+            .select((!query.is_empty() && !self.choices.is_empty()).then_some(0));
+    }
+
+    fn selected(&self) -> Option<&str> {
+        let idx = self.list_state.selected()?;
+        self.choices.get(idx).map(|it| it.key.as_str())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> PickAction {
+        if !self.is_active() {
+            return PickAction::None;
+        }
+        match key.code {
+            KeyCode::Down => {
+                self.list_state.select_next();
+                PickAction::Done
+            }
+            KeyCode::Up => {
+                if Some(0) == self.list_state.selected() {
+                    self.list_state.select(None);
+                } else {
+                    self.list_state.select_previous();
+                }
+                PickAction::Done
+            }
+            KeyCode::Esc => {
+                self.dismissed = true;
+                PickAction::Done
+            }
+            KeyCode::Tab | KeyCode::Enter | KeyCode::Right => match self.selected() {
+                Some(picked) => PickAction::Accept(picked.to_owned()),
+                None => PickAction::None,
+            },
+            _ => PickAction::None,
+        }
+    }
+
+    fn as_popup<'a, CI>(
+        &'a mut self,
+        table_state: &'a TableState,
+        constraints: CI,
+    ) -> TablePopup<'a>
+    where
+        CI: IntoIterator<Item = Constraint>,
+    {
+        let default_key = &settings::get().default_project_key;
+        let items = self
+            .choices
+            .iter()
+            .map(|it| {
+                let mut spans = vec![Span::from(it.key.as_str())];
+                match &it.internal_name {
+                    Some(name) => spans.push(Span::from(format!("  {name}")).dim()),
+                    None => spans.push(Span::from("  recent, not configured").dim().italic()),
+                }
+                if it.key == *default_key {
+                    spans.push(Span::from("  default").dim().italic());
+                }
+                ListItem::from(Line::from(spans))
+            })
+            .collect_vec();
+        // Leaves room for the internal name next to the key
+        TablePopup::new(table_state, &mut self.list_state, items, constraints).spanning(2)
+    }
 }
@@ -4,7 +4,8 @@ use crossterm::event::{KeyCode, KeyEvent};
 use enum_dispatch::enum_dispatch;
 use ratatui::{
     layout::Constraint,
-    widgets::{Row, Table, TableState},
+    text::Text,
+    widgets::{Table, TableState},
 };
 
 use crate::{
@@ -12,7 +13,7 @@ use crate::{
         action::HomeAction,
         editing::EditMode,
         movement::{handle_movement, is_movement},
-        state::{HomeState, TimeItem},
+        state::{HomeState, TIME_ITEM_WIDTH, TimeItem},
     },
     persist,
     widgets::table_popup::TablePopup,
@@ -22,8 +23,8 @@ use crate::{
 pub trait EditModeBehavior {
     fn handle_key_event(&mut self, state: &mut HomeState, key: KeyEvent) -> HomeAction;
     fn style_table<'a>(&self, table: Table<'a>) -> Table<'a>;
-    fn style_selected_item<'a>(&self, item: &'a TimeItem) -> Row<'a> {
-        item.as_row(false)
+    fn selected_item_cells<'a>(&self, item: &'a TimeItem) -> [Text<'a>; TIME_ITEM_WIDTH] {
+        item.as_cells(false)
     }
     fn draw_popup<'a, CI>(
         &'a mut self,
@@ -36,6 +37,11 @@ pub trait EditModeBehavior {
         None
     }
     fn handle_persisted(&mut self, _event: persist::Event) {}
+    /// Commits the buffer to the selected item without leaving edit mode, returns whether
+    /// anything changed. Modes whose save rearranges other items only save explicitly.
+    fn autosave(&mut self, _state: &mut HomeState) -> bool {
+        false
+    }
 }
 
 #[derive(Default)]
@@ -86,6 +92,26 @@ impl BufEditBehavior {
         self.buf.push(chr);
     }
 
+    /// Writes the buffer to the field if it differs, see [EditModeBehavior::autosave]
+    pub fn autosave_into(
+        &self,
+        state: &mut HomeState,
+        field: fn(&mut TimeItem) -> &mut String,
+    ) -> bool {
+        let Some(idx) = state.table.selected() else {
+            return false;
+        };
+        let Some(item) = state.items.get_mut(idx) else {
+            return false;
+        };
+        if *field(item) == self.buf {
+            return false;
+        }
+        *field(item) = self.buf.clone();
+        item.version.touch();
+        true
+    }
+
     /// Evaluates whether this [key] should trigger a save before being further handled with
     /// [handle_key_event]. If the save fails, handling should not continue.
     pub fn should_save(&self, key: KeyEvent) -> bool {
@@ -1,28 +1,35 @@
-use std::ops::Deref;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 use crossterm::event::{KeyCode, KeyEvent};
 use itertools::Itertools;
 use ratatui::{
     layout::Constraint,
     style::{Modifier, Style, Stylize, palette::tailwind},
-    text::{Line, Text},
-    widgets::{ListItem, ListState, Row, Table, TableState},
+    text::{Line, Span, Text},
+    widgets::{ListItem, ListState, Table, TableState},
 };
+use time::{Date, OffsetDateTime};
 
 use super::EditModeBehavior;
 use crate::{
     components::home::{
         action::HomeAction,
-        editing::shared::BufEditBehavior,
-        state::{HomeState, TimeItem},
+        editing::{
+            alias::{self, AliasHints},
+            shared::BufEditBehavior,
+        },
+        state::{HomeState, TIME_ITEM_WIDTH, TimeItem},
     },
-    persist::Event,
+    persist::{Event, SuggestionRequestId, TicketSuggestion},
+    settings,
+    shared::ticket_project,
     widgets::table_popup::TablePopup,
 };
 
 pub struct Ticket {
     buf: BufEditBehavior,
     suggestion: TicketsSuggestion,
+    aliases: AliasHints,
 }
 
 impl Ticket {
@@ -31,12 +38,23 @@ impl Ticket {
         Self {
             buf: item.ticket.to_owned().into(),
             suggestion: Default::default(),
+            aliases: Default::default(),
         }
     }
 }
 
 impl EditModeBehavior for Ticket {
     fn handle_key_event(&mut self, state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        // Accepting a suggestion only loses against an alias named exactly like the input
+        let named_alias = settings::get().aliases.contains_key(self.buf.trim());
+        if key.code == KeyCode::Tab
+            && (named_alias || self.suggestion.selected().is_none())
+            && alias::expand(state, &self.buf).is_some()
+        {
+            self.buf = state.expect_selected_item().ticket.to_owned().into();
+            self.suggestion = Default::default();
+            return HomeAction::None;
+        }
         match self.suggestion.handle_key_event(key) {
             SuggestAction::Done => return HomeAction::None,
             SuggestAction::Accept(suggested) => {
@@ -46,23 +64,34 @@ impl EditModeBehavior for Ticket {
         }
 
         if self.buf.should_save(key) {
-            state.expect_selected_item_mut().ticket = self.buf.to_owned();
+            let item = state.expect_selected_item_mut();
+            item.ticket =
+                ticket_project::normalize(settings::get(), Some(&item.project), &self.buf);
+            fill_project_from_ticket(item);
         }
 
         let action = self.buf.handle_key_event(state, key);
 
         if self.buf != self.suggestion.query {
-            self.suggestion.query = self.buf.to_string();
-            action + HomeAction::SuggestTickets(self.buf.to_string())
+            let request_id = self.suggestion.start_query(self.buf.to_string());
+            action
+                + HomeAction::SuggestTickets {
+                    query: self.buf.to_string(),
+                    request_id,
+                }
         } else {
             action
         }
     }
 
-    fn style_selected_item<'a>(&self, item: &'a TimeItem) -> Row<'a> {
-        let mut cells = item.as_cells(false).clone();
-        cells[2] = Text::from(self.buf.to_owned());
-        Row::new(cells)
+    fn autosave(&mut self, state: &mut HomeState) -> bool {
+        self.buf.autosave_into(state, |item| &mut item.ticket)
+    }
+
+    fn selected_item_cells<'a>(&self, item: &'a TimeItem) -> [Text<'a>; TIME_ITEM_WIDTH] {
+        let mut cells = item.as_cells(false);
+        cells[3] = Text::from(self.buf.to_owned());
+        cells
     }
 
     fn style_table<'a>(&self, table: Table<'a>) -> Table<'a> {
@@ -84,21 +113,45 @@ impl EditModeBehavior for Ticket {
         if self.suggestion.is_active() {
             Some(self.suggestion.as_popup(table_state, constraints))
         } else {
-            None
+            self.aliases.as_popup(&self.buf, table_state, constraints)
         }
     }
 
     fn handle_persisted(&mut self, event: Event) {
-        if let Event::TicketsSuggested { query, ticket_keys } = event {
-            self.suggestion.handle_result(query, ticket_keys);
+        if let Event::TicketsSuggested {
+            query,
+            request_id,
+            suggestions,
+        } = event
+        {
+            self.suggestion
+                .handle_result(query, request_id, suggestions);
         }
     }
 }
 
+/// Only for items that have no project yet, i.e. would use the default one
+fn fill_project_from_ticket(item: &mut TimeItem) {
+    if !item.project.is_empty() {
+        return;
+    }
+    let config = settings::get();
+    if let Some(project) = ticket_project::project_for(config, &item.ticket)
+        && project != config.default_project_key
+    {
+        item.project = project.to_string();
+    }
+}
+
+/// Shared across edit sessions so that late responses for a previous session are never accepted.
+static NEXT_REQUEST_ID: AtomicU64 = AtomicU64::new(1);
+
 #[derive(Default)]
 struct TicketsSuggestion {
     query: String,
-    suggestions: Vec<String>,
+    /// Id of the most recently sent query; `0` if none is in flight
+    pending_request_id: SuggestionRequestId,
+    suggestions: Vec<TicketSuggestion>,
     list_state: ListState,
 }
 
@@ -113,10 +166,22 @@ impl TicketsSuggestion {
         !self.query.is_empty() && !self.suggestions.is_empty()
     }
 
-    pub fn handle_result(&mut self, query: String, suggestions: Vec<String>) {
-        if query != self.query {
+    pub fn start_query(&mut self, query: String) -> SuggestionRequestId {
+        self.query = query;
+        self.pending_request_id = NEXT_REQUEST_ID.fetch_add(1, Ordering::Relaxed);
+        self.pending_request_id
+    }
+
+    pub fn handle_result(
+        &mut self,
+        query: String,
+        request_id: SuggestionRequestId,
+        suggestions: Vec<TicketSuggestion>,
+    ) {
+        if request_id != self.pending_request_id || query != self.query {
             return; // outdated result, new query in flight
         }
+        self.pending_request_id = 0;
         let no_suggestions_before = self.suggestions.is_empty();
         self.suggestions = suggestions;
         if no_suggestions_before && !self.suggestions.is_empty() {
@@ -126,7 +191,7 @@ impl TicketsSuggestion {
 
     pub fn selected(&self) -> Option<&str> {
         if let Some(idx) = self.list_state.selected() {
-            self.suggestions.get(idx).map(|x| x.as_str())
+            self.suggestions.get(idx).map(|x| x.ticket_key.as_str())
         } else {
             None
         }
@@ -174,12 +239,31 @@ impl TicketsSuggestion {
     where
         CI: IntoIterator<Item = Constraint>,
     {
+        let config = settings::get();
+        let today = OffsetDateTime::now_local().map_or(Date::MIN, |it| it.date());
         let items = self
             .suggestions
             .iter()
-            .map(|it| ListItem::from(Line::from(it.deref())))
+            .map(|it| {
+                let mut spans = vec![Span::from(it.ticket_key.as_str())];
+                if let Some(project) = ticket_project::project_for(config, &it.ticket_key) {
+                    spans.push(Span::from(format!("  → {project}")).dim());
+                }
+                let usage = format!("  {}× · {}", it.uses, format_last_used(it.last_used, today));
+                spans.push(Span::from(usage).dim().italic());
+                ListItem::from(Line::from(spans))
+            })
             .collect_vec();
         let state = &mut self.list_state;
-        TablePopup::new(table_state, state, items, constraints)
+        // Leaves room for the usage next to the ticket
+        TablePopup::new(table_state, state, items, constraints).spanning(2)
+    }
+}
+
+fn format_last_used(last_used: Date, today: Date) -> String {
+    match (today - last_used).whole_days() {
+        ..=0 => "today".into(),
+        1 => "yesterday".into(),
+        days => format!("{days}d ago"),
     }
 }
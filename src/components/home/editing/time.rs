@@ -6,19 +6,25 @@ use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::{
     style::{Modifier, Style, Stylize, palette::tailwind},
     text::Text,
-    widgets::{Row, Table},
+    widgets::Table,
 };
 
 use super::EditModeBehavior;
-use crate::components::home::{
-    action::HomeAction,
-    editing::shared::BufEditBehavior,
-    state::{HomeState, TimeItem},
+use crate::{
+    components::home::{
+        action::HomeAction,
+        editing::shared::BufEditBehavior,
+        state::{HomeState, TIME_ITEM_WIDTH, TimeItem},
+    },
+    settings, shared,
 };
 
 #[derive(Default)]
 pub struct Time {
     buf: BufEditBehavior,
+    /// Keeps the typed time instead of snapping it, see
+    /// [crate::config::EditingConfig::snap_start_mins]
+    exact: bool,
 }
 
 impl Time {
@@ -26,9 +32,41 @@ impl Time {
         let item = state.expect_selected_item();
         Self {
             buf: item.start_time.format("%H%M").to_string().into(),
+            exact: false,
         }
     }
 
+    fn snap_mins(&self) -> u32 {
+        if self.exact {
+            0
+        } else {
+            settings::get().editing.snap_start_mins
+        }
+    }
+
+    /// Tells which time will be saved while typing, if snapping changes it
+    fn snap_hint(&self) -> HomeAction {
+        let grid_mins = settings::get().editing.snap_start_mins;
+        if self.buf.len() < 4 {
+            return HomeAction::None;
+        }
+        let Ok(typed) = NaiveTime::parse_from_str((&self.buf).into(), "%H%M") else {
+            return HomeAction::None;
+        };
+        let snapped = shared::snap_to_grid(typed, grid_mins);
+        if snapped == typed {
+            return HomeAction::None;
+        }
+        let typed = typed.format("%H:%M");
+        let snapped = snapped.format("%H:%M");
+        let hint = if self.exact {
+            format!("Keeping {typed} exactly, <=> snaps it to {snapped}")
+        } else {
+            format!("Snaps to {snapped}, <=> keeps {typed} exactly")
+        };
+        HomeAction::SetStatusLine(hint)
+    }
+
     fn handle_save(&self, state: &mut HomeState) -> Result<()> {
         if self.buf.is_empty() {
             return Ok(());
@@ -36,6 +74,11 @@ impl Time {
 
         let parsed = NaiveTime::parse_from_str((&self.buf).into(), "%H%M");
         let parsed = parsed.map_err(|err| eyre!("invalid: {err}"))?;
+        if parsed == state.expect_selected_item().start_time {
+            // Times that were only passed through are not snapped
+            return Ok(());
+        }
+        let parsed = shared::snap_to_grid(parsed, self.snap_mins());
 
         self.ensure_not_before_previous(state, parsed)?;
         self.ensure_not_after_next(state, parsed)?;
@@ -137,19 +180,26 @@ impl EditModeBehavior for Time {
         }
         match key.code {
             KeyCode::Enter => HomeAction::ExitEdit,
+            KeyCode::Char('=') => {
+                self.exact = !self.exact;
+                self.snap_hint()
+            }
             KeyCode::Char(_) if self.buf.len() >= 4 => HomeAction::None,
-            _ => self.buf.handle_key_event(state, key),
+            _ => match self.buf.handle_key_event(state, key) {
+                HomeAction::None => self.snap_hint(),
+                action => action,
+            },
         }
     }
 
-    fn style_selected_item<'a>(&self, item: &'a TimeItem) -> Row<'a> {
-        let mut cells = item.as_cells(false).clone();
+    fn selected_item_cells<'a>(&self, item: &'a TimeItem) -> [Text<'a>; TIME_ITEM_WIDTH] {
+        let mut cells = item.as_cells(false);
         let mut content = self.buf.to_owned();
         if content.is_empty() {
             content = format!("{}", item.start_time.format("%H%M"));
         }
         cells[0] = Text::from(content);
-        Row::new(cells)
+        cells
     }
 
     fn style_table<'a>(&self, table: Table<'a>) -> Table<'a> {
@@ -1,55 +1,222 @@
-use std::{fs, path::PathBuf};
+use std::{fs, ops::RangeInclusive, path::PathBuf, str::FromStr};
 
 use color_eyre::{Result, eyre::Context};
-use time::Date;
+use itertools::Itertools;
+use time::{Date, format_description::FormatItem, macros::format_description};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::error;
 
 use crate::{
+    action::Action,
     components::home::state::TimeItem,
-    config::{Config, get_data_dir},
+    config::DefragStrategy,
+    export::{ExportFormat, csv, hooks, jira_csv, locale::CsvLocale},
+    persist::{EntryLink, ReportTable, TimeEntry},
+    settings,
 };
 
-pub mod csv;
-pub mod json;
+/// Generated export file, not written yet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportFile {
+    pub path: PathBuf,
+    pub content: String,
+}
 
-pub fn export_timesheet(items: &[TimeItem], day: Date) -> Result<()> {
-    let csv_path = build_export_file_path(day, "csv")?;
-    let json_path = build_export_file_path(day, "json")?;
+impl ExportFile {
+    /// Moves the file to the path, which is kept clear of existing files if configured
+    pub fn set_path(&mut self, path: PathBuf) {
+        self.path = if settings::get().export.keep_existing {
+            unused_path(path)
+        } else {
+            path
+        };
+    }
 
-    if let Some(parent) = csv_path.parent() {
-        fs::create_dir_all(parent).wrap_err("Failed to create export directory")?;
+    pub fn write(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).wrap_err("Failed to create export directory")?;
+        }
+        fs::write(&self.path, &self.content)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
     }
+}
+
+/// Key of the [crate::persist::Command::StorePreference] with the last picked formats
+pub const FORMATS_PREFERENCE: &str = "export_formats";
+
+/// Generates the export of the day in each format, to be previewed before writing
+pub fn prepare_timesheet_export(
+    items: &[TimeItem],
+    day: Date,
+    notes: &str,
+    utc_offset_mins: Option<i32>,
+    formats: &[ExportFormat],
+    defrag: DefragStrategy,
+) -> Result<Vec<(ExportFormat, ExportFile)>> {
+    let iso_day = day.format(ISO_DAY)?;
+    let entries = items
+        .iter()
+        .map(|item| item.to_persist(&iso_day))
+        .collect_vec();
+    let links = items
+        .iter()
+        .flat_map(|item| {
+            item.links
+                .iter()
+                .enumerate()
+                .map(|(position, url)| EntryLink {
+                    entry_id: item.id.to_string(),
+                    position: position as i32,
+                    url: url.clone(),
+                })
+        })
+        .collect_vec();
+    let meta = settings::export_meta()?;
+    let mut config = settings::get().clone();
+    config.export.defrag_strategy = defrag;
+    formats
+        .iter()
+        .map(|format| {
+            let content = format.generate(
+                &entries,
+                &links,
+                day,
+                notes,
+                utc_offset_mins,
+                &meta,
+                &config,
+            )?;
+            let mut file = ExportFile {
+                path: PathBuf::new(),
+                content,
+            };
+            file.set_path(format.path_in(&settings::export_dir(), day));
+            Ok((*format, file))
+        })
+        .collect()
+}
+
+/// Formats stored as preference, like `csv,json`, unknown ones are skipped
+pub fn parse_formats(value: &str) -> Vec<ExportFormat> {
+    value
+        .split(',')
+        .filter_map(|it| ExportFormat::from_str(it.trim()).ok())
+        .collect()
+}
+
+pub fn format_formats(formats: &[ExportFormat]) -> String {
+    formats.iter().join(",")
+}
+
+/// Writes all entries of the range to a single CSV and returns its path
+pub fn export_range_csv(range: &RangeInclusive<Date>, entries: Vec<TimeEntry>) -> Result<PathBuf> {
+    let days = group_by_day(entries);
+    let first = range.start().format(ISO_DAY)?;
+    let last = range.end().format(ISO_DAY)?;
+    let path = settings::export_dir()
+        .join(range.start().year().to_string())
+        .join(format!("{first}_{last}.csv"));
 
-    let csv_file = fs::File::create(&csv_path)
-        .with_context(|| format!("Failed to create CSV file at {}", csv_path.display()))?;
-    csv::generate_csv_content(items, csv_file)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).wrap_err("Failed to create export directory")?;
+    }
+    let mut csv_file = fs::File::create(&path)
+        .with_context(|| format!("Failed to create CSV file at {}", path.display()))?;
+    settings::export_meta()?.write_csv_comment(settings::get(), &mut csv_file)?;
+    let locale = CsvLocale::from_config(&settings::get().export.locale)?;
+    csv::generate_combined_csv_content(&days, &locale, csv_file)?;
+    Ok(path)
+}
 
-    let json_content = json::generate_json_content(items, day)?;
-    fs::write(&json_path, json_content)
-        .with_context(|| format!("Failed to write JSON file at {}", json_path.display()))?;
+/// Writes the worklogs of the range to a single CSV for Jira's import and returns its path
+pub fn export_range_jira_csv(
+    range: &RangeInclusive<Date>,
+    entries: Vec<TimeEntry>,
+) -> Result<PathBuf> {
+    let days = group_by_day(entries);
+    let first = range.start().format(ISO_DAY)?;
+    let last = range.end().format(ISO_DAY)?;
+    let path = settings::export_dir()
+        .join(range.start().year().to_string())
+        .join(format!("{first}_{last}-jira.csv"));
 
-    Ok(())
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).wrap_err("Failed to create export directory")?;
+    }
+    let csv_file = fs::File::create(&path)
+        .with_context(|| format!("Failed to create CSV file at {}", path.display()))?;
+    jira_csv::generate_jira_csv_content(&days, settings::get(), csv_file)?;
+    Ok(path)
 }
 
-fn build_export_file_path(day: Date, extension: &str) -> Result<PathBuf> {
-    let data_dir = get_data_dir();
-    let year = day.year();
-    let month = u8::from(day.month());
-    let day_num = day.day();
+/// Writes the report to a CSV named after it and the day it ran, and returns its path
+pub fn export_report_csv(name: &str, table: &ReportTable, day: Date) -> Result<PathBuf> {
+    let iso_day = day.format(ISO_DAY)?;
+    let mut content = vec![];
+    csv::generate_report_csv_content(table, &mut content)?;
+    let mut file = ExportFile {
+        path: PathBuf::new(),
+        content: String::from_utf8(content).wrap_err("CSV export is not UTF-8")?,
+    };
+    file.set_path(
+        settings::export_dir()
+            .join("reports")
+            .join(format!("{name}_{iso_day}.csv")),
+    );
+    file.write()?;
+    Ok(file.path)
+}
 
-    let filename = format!("{year:04}-{month:02}-{day_num:02}.{extension}");
-    let file_path = data_dir
-        .join("exports")
-        .join(year.to_string())
-        .join(format!("{month:02}"))
-        .join(filename);
+/// Runs the configured export hooks for the file in the background, failures are shown in the
+/// status line
+pub fn run_hooks(path: PathBuf, day: Date, action_tx: Option<UnboundedSender<Action>>) {
+    hooks::spawn(
+        settings::get().export.hooks.clone(),
+        path,
+        day,
+        move |err| {
+            if let Some(tx) = action_tx {
+                let _ = tx.send(Action::SetStatusLine(format!(
+                    "❌ Export hook failed: {err}"
+                )));
+            }
+        },
+    );
+}
 
-    Ok(file_path)
+fn group_by_day(entries: Vec<TimeEntry>) -> Vec<(Date, Vec<TimeEntry>)> {
+    entries
+        .into_iter()
+        .chunk_by(|entry| entry.timesheet_day.clone())
+        .into_iter()
+        .filter_map(|(day, entries)| match Date::parse(&day, ISO_DAY) {
+            Ok(date) => Some((date, entries.collect())),
+            Err(err) => {
+                error!("Skipping time entries of corrupted day in export: {day} due to {err:?}");
+                None
+            }
+        })
+        .collect()
 }
 
-pub(super) fn get_project_key(project: &str) -> String {
-    if project.is_empty() {
-        Config::get().default_project_key.clone()
-    } else {
-        project.to_string()
+/// `2025-09-22-1.csv` for `2025-09-22.csv`, or the next free number
+fn unused_path(path: PathBuf) -> PathBuf {
+    if !path.exists() {
+        return path;
     }
+    let stem = path
+        .file_stem()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let extension = path.extension().map(|it| it.to_string_lossy().to_string());
+    (1..)
+        .map(|counter| match &extension {
+            Some(extension) => path.with_file_name(format!("{stem}-{counter}.{extension}")),
+            None => path.with_file_name(format!("{stem}-{counter}")),
+        })
+        .find(|candidate| !candidate.exists())
+        .expect("some counter to be free")
 }
+
+const ISO_DAY: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
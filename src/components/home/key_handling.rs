@@ -5,6 +5,8 @@ use crate::components::home::{
     action::HomeAction,
     editing::{EditMode, EditModeBehavior},
     movement::handle_movement,
+    popup::PopupBehavior,
+    state::ChainRepair,
 };
 
 pub fn handle(home: &mut Home, key: KeyEvent) -> HomeAction {
@@ -12,6 +14,14 @@ pub fn handle(home: &mut Home, key: KeyEvent) -> HomeAction {
         return HomeAction::None;
     }
 
+    if let Some(popup) = &mut home.popup {
+        return popup.handle_key_event(&mut home.state, key);
+    }
+    if let Some(compare) = &mut home.compare
+        && compare.focused
+    {
+        return compare.handle_key_event(key);
+    }
     match &mut home.edit_mode {
         Some(mode) => mode.handle_key_event(&mut home.state, key),
         None => handle_outside_edit(home, key),
@@ -31,9 +41,13 @@ fn handle_outside_edit(home: &mut Home, key: KeyEvent) -> HomeAction {
     }
     match key.code {
         KeyCode::End => {
-            state.table.select_last();
+            state.table.select(state.visible_idxs().last().copied());
             state.table.select_last_column();
         }
+        KeyCode::Esc if state.is_filtered() => {
+            state.set_filter("");
+            return HomeAction::SetStatusLine("Filter cleared".into());
+        }
         KeyCode::Esc => {
             return HomeAction::ExitToCalendar;
         }
@@ -49,17 +63,129 @@ fn handle_outside_edit(home: &mut Home, key: KeyEvent) -> HomeAction {
                 return HomeAction::SplitItemDown(idx);
             }
         }
+        KeyCode::Char('%') => {
+            if let Some(idx) = state.table.selected() {
+                return HomeAction::EditSplitShares(idx);
+            }
+        }
         KeyCode::Char('S') => {
             if let Some(idx) = state.table.selected() {
                 return HomeAction::MergeItemDown(idx);
             }
         }
+        KeyCode::Char('D') => {
+            return HomeAction::FindDuplicates;
+        }
         KeyCode::Char('e') => {
             return HomeAction::Export;
         }
         KeyCode::Char('x') => {
             return HomeAction::ToggleBreak;
         }
+        KeyCode::Char('b') => {
+            return HomeAction::ToggleBillable;
+        }
+        KeyCode::Char('c') => {
+            return HomeAction::ToggleCarryOver;
+        }
+        KeyCode::Char('i') => {
+            return HomeAction::ShowDetails;
+        }
+        KeyCode::Char('y') => {
+            return HomeAction::PickWorkType;
+        }
+        KeyCode::Char('g') => {
+            return HomeAction::PickFlags;
+        }
+        KeyCode::Char('a') => {
+            return HomeAction::PickAbsence;
+        }
+        KeyCode::Char('r') => {
+            return HomeAction::RetrySave;
+        }
+        KeyCode::Char('R') => {
+            return HomeAction::ConfirmReloadDay;
+        }
+        KeyCode::Char('v') => {
+            return HomeAction::PasteEntries;
+        }
+        KeyCode::Char('B') => {
+            return HomeAction::CopyBookings;
+        }
+        KeyCode::Char('p') => {
+            return HomeAction::TogglePin;
+        }
+        KeyCode::Char('t') => {
+            return HomeAction::LoadTemplates;
+        }
+        KeyCode::Char('T') => {
+            return HomeAction::EditTemplateName;
+        }
+        KeyCode::Char('f') | KeyCode::Char('F') => {
+            if let Some(idx) = state.table.selected() {
+                let as_break = key.code == KeyCode::Char('F');
+                return HomeAction::FillGapAfter { idx, as_break };
+            }
+        }
+        KeyCode::Char('m') => {
+            if let Some(idx) = state.table.selected() {
+                return HomeAction::EditShift { from_idx: idx };
+            }
+        }
+        KeyCode::Char('+') | KeyCode::Char('-') if state.table.selected().is_some() => {
+            let step = home.config.editing.duration_step_mins as i64;
+            let mins = if key.code == KeyCode::Char('+') {
+                step
+            } else {
+                -step
+            };
+            return HomeAction::NudgeDuration(mins);
+        }
+        KeyCode::Char('M') => {
+            return HomeAction::EditShift { from_idx: 0 };
+        }
+        KeyCode::Char('J') => {
+            return HomeAction::EditRepairChain(ChainRepair::Starts);
+        }
+        KeyCode::Char('W') => {
+            return HomeAction::ReviewFocusSuggestions;
+        }
+        KeyCode::Char('/') => {
+            return HomeAction::EditFilter;
+        }
+        KeyCode::Char('!') => {
+            return HomeAction::ShowProblems;
+        }
+        KeyCode::Char('n') => {
+            return HomeAction::EditNotes;
+        }
+        KeyCode::Char('l') if state.table.selected().is_some() => {
+            return HomeAction::EditLinks;
+        }
+        KeyCode::Char('d') if state.table.selected().is_some() => {
+            return HomeAction::DiffSelected;
+        }
+        KeyCode::Char('o') if state.table.selected().is_some() => {
+            return HomeAction::OpenLinks;
+        }
+        KeyCode::Char('E') => {
+            return HomeAction::ToggleEndColumn;
+        }
+        KeyCode::Char('H') => {
+            return HomeAction::LoadHistory;
+        }
+        KeyCode::Char('K') => {
+            return HomeAction::ManageArchivedTickets;
+        }
+        KeyCode::Char('C') if home.compare.is_some() => {
+            return HomeAction::CloseCompare;
+        }
+        KeyCode::Char('C') => {
+            return HomeAction::OpenCompare;
+        }
+        KeyCode::Char('w') if home.compare.is_some() => {
+            return HomeAction::FocusCompare(true);
+        }
         _ => {}
     }
     HomeAction::None
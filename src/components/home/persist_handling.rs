@@ -1,35 +1,120 @@
-use std::time::Duration;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
 use crate::{
     components::home::{
         EditModeBehavior, Home,
         action::HomeAction,
+        export,
+        popup::{CorruptedEntry, RepairEntries, TemplatePicker},
         state::{HomeState, TimeItem},
     },
-    persist::{self, Event, TimeEntry, Timesheet},
+    desktop,
+    export::ExportFormat,
+    persist::{self, Command, EntryLink, Event, PersistError, TimeEntry, Timesheet},
+    shared::scaffold,
 };
 use chrono::NaiveTime;
-use tracing::error;
+use itertools::Itertools;
+use tracing::{error, warn};
+
+const MAX_RETRIES: u32 = 5;
+
+/// Commands that failed with a retryable error and are sent again after a backoff.
+#[derive(Default)]
+pub struct PersistRetry {
+    /// Failed attempts by the id of the entry the command stores or deletes
+    attempts: HashMap<String, u32>,
+    due: Option<Instant>,
+    commands: Vec<Command>,
+}
+
+impl PersistRetry {
+    pub fn take_due(&mut self, now: Instant) -> Option<Vec<Command>> {
+        if self.due.is_none_or(|due| now < due) {
+            return None;
+        }
+        self.due = None;
+        Some(self.commands.drain(..).collect())
+    }
+
+    /// Makes the waiting commands due right away
+    pub fn expedite(&mut self) {
+        if !self.commands.is_empty() {
+            self.due = Some(Instant::now());
+        }
+    }
+
+    /// Allows as many retries as for a new failure, e.g. when the user asks to retry
+    pub fn reset_attempts(&mut self) {
+        self.attempts.clear();
+    }
+}
+
+/// Entry that a repeatable command stores or deletes, see [Command::repeatable_copy]
+fn entry_id_of(command: &Command) -> Option<String> {
+    match command {
+        Command::StoreEntry { entry, .. } => Some(entry.id.clone()),
+        Command::DeleteEntry(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
 
 pub fn handle(home: &mut Home, event: Event) -> HomeAction {
+    if let Event::EntryStored { id, .. } = &event {
+        home.retry.attempts.remove(&id.to_string());
+    }
+    if let Event::EntryStored { id, version, .. } = &event
+        && let Some(journal) = &mut home.journal
+        && let Err(err) = journal.confirm(id, *version)
+    {
+        warn!("Failed to confirm entry {id} in the journal: {err:?}");
+    }
     match event {
-        persist::Event::EntryStored { id, version } if version != -1 => {
+        persist::Event::Failure(err) => handle_failure(home, err),
+        persist::Event::EntryStored {
+            id,
+            version,
+            revision,
+        } if version != -1 => {
             for entry in home.state.items.iter_mut() {
                 if entry.id == id {
                     entry.version.notify_saved(version);
+                    entry.revision = revision;
                     return HomeAction::SetStatusLine(format!("Stored: {id} v{version}"));
                 }
             }
             HomeAction::None
         }
+        persist::Event::TimesheetLoaded { entries, day, .. }
+            if !home.suspended && home.compare.as_ref().is_some_and(|it| it.day == day) =>
+        {
+            home.compare
+                .as_mut()
+                .expect("checked above")
+                .handle_loaded(entries);
+            HomeAction::None
+        }
         persist::Event::TimesheetLoaded {
             timesheet,
             entries,
-            day: _,
-        } if !home.suspended => {
+            links,
+            day,
+        } if !home.suspended && day == home.day => {
             // prevent creating timesheets when browsing calendar
             let day = timesheet.day.to_string();
-            home.state = into_state(timesheet, entries);
+            let before_break = std::mem::take(&mut home.state.before_break);
+            let was_empty = entries.is_empty();
+            let pinned = home.state.pinned.take();
+            let show_end = home.state.show_end;
+            let (state, corrupted) = into_state(timesheet, entries, links);
+            home.state = state;
+            // Ids are unique, so entries of other days never match
+            home.state.before_break = before_break;
+            home.state.pinned = pinned;
+            home.state.show_end = show_end;
             if home.state.items.is_empty() {
                 // Without an initial item it's not possible to add one
                 let mut item = TimeItem::new(Duration::ZERO, NaiveTime::MIN);
@@ -40,8 +125,156 @@ pub fn handle(home: &mut Home, event: Event) -> HomeAction {
                 });
                 home.state.items.push(item);
             }
+            home.record_utc_offset();
+            if !corrupted.is_empty() && home.popup.is_none() {
+                home.popup = Some(RepairEntries::new(corrupted).into());
+            }
+            if let Some(timer) = home.pending_timer.take() {
+                return HomeAction::StartTimer(timer);
+            }
+            if was_empty {
+                return HomeAction::PrepareCarryOver;
+            }
+            if let Some(id) = home.pending_selection.take()
+                && let Some(idx) = home
+                    .state
+                    .items
+                    .iter()
+                    .position(|it| it.id.to_string() == id)
+            {
+                home.state.table.select(Some(idx));
+                home.state.ensure_column_selected();
+                return HomeAction::EnterSelect;
+            }
             HomeAction::SetStatusLine(format!("Loaded: {day}"))
         }
+        persist::Event::EntriesInRangeLoaded { range, entries }
+            if home.week_bar.as_ref().is_some_and(|it| it.range == range) =>
+        {
+            home.week_bar
+                .as_mut()
+                .expect("checked above")
+                .handle_loaded(&entries);
+            HomeAction::None
+        }
+        persist::Event::EntriesImported { day, imported, .. }
+            if !home.suspended && day == home.day && imported > 0 =>
+        {
+            // e.g. from `sheet-shark add`, reloading would drop what is being edited
+            if home.edit_mode.is_some() || home.popup.is_some() {
+                return HomeAction::SetStatusLine(format!(
+                    "📥 {imported} entries added elsewhere, reopen the day to see them"
+                ));
+            }
+            home.send_persist(Command::LoadTimesheet { day });
+            HomeAction::SetStatusLine(format!("📥 {imported} entries added"))
+        }
+        persist::Event::CarryOverLoaded { day, entries } if !home.suspended && day == home.day => {
+            HomeAction::OfferCarryOver(entries)
+        }
+        persist::Event::NotesStored { day, .. } if !home.suspended && day == home.day => {
+            HomeAction::SetStatusLine("📝 Notes saved".into())
+        }
+        persist::Event::TemplatesLoaded(templates) if home.pending_scaffold => {
+            home.pending_scaffold = false;
+            let entries = scaffold::entries_for(&home.config, home.day.weekday());
+            match scaffold::resolve(entries, &templates, &home.config) {
+                Ok(items) => HomeAction::OfferScaffold(items),
+                Err(err) => HomeAction::SetStatusLine(format!("🧱 {err}")),
+            }
+        }
+        persist::Event::TemplatesLoaded(templates) if !home.suspended => {
+            if templates.is_empty() {
+                return HomeAction::SetStatusLine(
+                    "No templates yet, save the selected entry with T".into(),
+                );
+            }
+            if home.popup.is_none() {
+                home.popup = Some(TemplatePicker::new(templates).into());
+            }
+            HomeAction::None
+        }
+        persist::Event::PreferenceLoaded { key, value }
+            if !home.suspended && key == export::FORMATS_PREFERENCE =>
+        {
+            let formats = value
+                .map(|it| export::parse_formats(&it))
+                .filter(|it| !it.is_empty())
+                .unwrap_or_else(|| vec![ExportFormat::Csv, ExportFormat::Json]);
+            HomeAction::PickExportFormats(formats)
+        }
+        persist::Event::EntryConflict {
+            entry,
+            version,
+            stored,
+        } => {
+            let item = home
+                .state
+                .items
+                .iter_mut()
+                .find(|it| it.id.to_string() == entry.id);
+            let Some(item) = item else {
+                return HomeAction::None;
+            };
+            // Stays unsaved, until resolved or sent again with r
+            item.version.notify_given_up(version);
+            HomeAction::ShowConflict { entry, stored }
+        }
+        persist::Event::EntryLoaded { id, entry, links } if !home.suspended => {
+            HomeAction::ShowEntryDiff { id, entry, links }
+        }
+        persist::Event::SnapshotsLoaded { day, snapshots }
+            if !home.suspended && day == home.day =>
+        {
+            HomeAction::ShowHistory(snapshots)
+        }
+        persist::Event::SnapshotRestored {
+            day,
+            taken_at,
+            entries,
+        } if !home.suspended && day == home.day => {
+            home.send_persist(Command::LoadTimesheet { day });
+            HomeAction::SetStatusLine(format!("🕰️ Restored {entries} entries as of {taken_at}"))
+        }
+        persist::Event::Synced { pulled, .. } if !home.suspended && pulled.contains(&home.day) => {
+            let unsaved = home
+                .state
+                .items
+                .iter()
+                .filter(|it| it.version.is_unsaved())
+                .count();
+            if unsaved > 0 {
+                // Kept, storing them brings up any conflict with the pulled changes
+                return HomeAction::SetStatusLine(format!(
+                    "⇅ {} changed elsewhere, <R> to reload it",
+                    home.day
+                ));
+            }
+            home.send_persist(Command::LoadTimesheet { day: home.day });
+            HomeAction::None
+        }
+        persist::Event::ArchivedTicketsLoaded(tickets) => {
+            home.archived_tickets = tickets.into_iter().collect();
+            HomeAction::None
+        }
+        persist::Event::TicketArchived { ticket_key } if !home.suspended => {
+            HomeAction::SetStatusLine(format!("🗄️ Archived {ticket_key}, no longer suggested"))
+        }
+        persist::Event::TicketUnarchived { ticket_key } if !home.suspended => {
+            HomeAction::SetStatusLine(format!("🗄️ Unarchived {ticket_key}"))
+        }
+        persist::Event::TemplateStored { name } if !home.suspended => {
+            HomeAction::SetStatusLine(format!("💾 Saved template {name}"))
+        }
+        persist::Event::TemplateDeleted { name } if !home.suspended => {
+            HomeAction::SetStatusLine(format!("🗑️ Deleted template {name}"))
+        }
+        persist::Event::UtcOffsetStored { day, offset_mins } if day == home.day => {
+            if let Some(timesheet) = &mut home.state.timesheet {
+                timesheet.utc_offset_mins = Some(offset_mins);
+            }
+            HomeAction::None
+        }
         event if !home.suspended => {
             if let Some(edit_mode) = &mut home.edit_mode {
                 edit_mode.handle_persisted(event);
@@ -52,20 +285,79 @@ pub fn handle(home: &mut Home, event: Event) -> HomeAction {
     }
 }
 
-fn into_state(timesheet: Timesheet, entries: Vec<TimeEntry>) -> HomeState {
+fn handle_failure(home: &mut Home, err: PersistError) -> HomeAction {
+    let Some(command) = err.repeatable.clone().filter(|_| err.is_retryable()) else {
+        // Sending the same data again won't help, it is sent again with the next change
+        give_up(home, err.repeatable.as_ref());
+        return HomeAction::None;
+    };
+    let id = entry_id_of(&command).unwrap_or_default();
+    let attempts = home.retry.attempts.entry(id.clone()).or_default();
+    if *attempts >= MAX_RETRIES {
+        home.retry.attempts.remove(&id);
+        give_up(home, Some(&command));
+        return HomeAction::SetStatusLine(format!("⚡ {err}, giving up for now, r to retry"));
+    }
+    *attempts += 1;
+    let backoff = Duration::from_millis(250 << *attempts);
+    home.retry.due = Some(Instant::now() + backoff);
+    let message = format!("⏳ {err}, retrying...");
+
+    home.retry.commands.push(command);
+    HomeAction::SetStatusLine(message)
+}
+
+/// Marks the entry that could not be stored, so that it shows until saved or retried with `r`
+fn give_up(home: &mut Home, command: Option<&Command>) {
+    let Some(Command::StoreEntry { entry, version }) = command else {
+        return;
+    };
+    let item = home
+        .state
+        .items
+        .iter_mut()
+        .find(|it| it.id.to_string() == entry.id);
+    if let Some(item) = item {
+        item.version.notify_given_up(*version);
+    }
+    if home.config.desktop.bell_on_save_failure {
+        desktop::ring_bell();
+    }
+}
+
+/// State with the entries that could be loaded, and the ones that are corrupted
+fn into_state(
+    timesheet: Timesheet,
+    entries: Vec<TimeEntry>,
+    links: Vec<EntryLink>,
+) -> (HomeState, Vec<CorruptedEntry>) {
+    let mut corrupted = vec![];
+    let mut links = links
+        .into_iter()
+        .map(|it| (it.entry_id, it.url))
+        .into_group_map();
     let items = entries
         .into_iter()
-        .filter_map(|entry| match (&entry).try_into() {
-            Ok(ok) => Some(ok),
+        .filter_map(|entry| match TimeItem::try_from(&entry) {
+            Ok(mut item) => {
+                item.links = links.remove(&entry.id).unwrap_or_default();
+                Some(item)
+            }
             Err(err) => {
                 error!("Failed to load corrupted time entry: {entry:?} due to {err:?}");
+                corrupted.push(CorruptedEntry {
+                    raw_id: entry.id.clone(),
+                    entry,
+                    error: format!("{err:#}"),
+                });
                 None
             }
         })
         .collect();
-    HomeState {
+    let state = HomeState {
         timesheet: Some(timesheet),
         items,
         ..Default::default()
-    }
+    };
+    (state, corrupted)
 }
@@ -0,0 +1,102 @@
+use crossterm::event::KeyEvent;
+use enum_dispatch::enum_dispatch;
+use ratatui::{Frame, layout::Rect};
+
+use crate::components::home::{action::HomeAction, state::HomeState};
+
+mod absence_picker;
+mod archived_tickets;
+mod carry_over;
+mod confirm;
+mod entry_details;
+mod entry_diff;
+mod export_format_picker;
+mod export_preview;
+mod flag_picker;
+mod focus_review;
+mod history;
+mod idle_return;
+mod links;
+mod merge_duplicates;
+mod notes;
+mod paste_preview;
+mod problems;
+mod recover_edits;
+mod repair_chain;
+mod repair_entries;
+mod resolve_conflict;
+mod row_filter;
+mod save_template;
+mod scaffold_offer;
+mod shift_times;
+mod split_shares;
+mod template_picker;
+mod work_type_picker;
+
+pub use absence_picker::AbsencePicker;
+pub use archived_tickets::ArchivedTickets;
+pub use carry_over::CarryOver;
+pub use confirm::Confirm;
+pub use entry_details::EntryDetails;
+pub use entry_diff::EntryDiff;
+pub use export_format_picker::ExportFormatPicker;
+pub use export_preview::ExportPreview;
+pub use flag_picker::FlagPicker;
+pub use focus_review::FocusReview;
+pub use history::History;
+pub use idle_return::IdleReturn;
+pub use links::Links;
+pub use merge_duplicates::MergeDuplicates;
+pub use notes::Notes;
+pub use paste_preview::PastePreview;
+pub use problems::Problems;
+pub use recover_edits::RecoverEdits;
+pub use repair_chain::RepairChain;
+pub use repair_entries::{CorruptedEntry, RepairEntries};
+pub use resolve_conflict::ResolveConflict;
+pub use row_filter::RowFilter;
+pub use save_template::SaveTemplate;
+pub use scaffold_offer::ScaffoldOffer;
+pub use shift_times::ShiftTimes;
+pub use split_shares::SplitShares;
+pub use template_picker::TemplatePicker;
+pub use work_type_picker::WorkTypePicker;
+
+/// Overlays on top of the Home table that take precedence for key handling while open.
+#[enum_dispatch]
+pub trait PopupBehavior {
+    fn handle_key_event(&mut self, state: &mut HomeState, key: KeyEvent) -> HomeAction;
+    fn draw(&mut self, frame: &mut Frame, area: Rect);
+}
+
+#[enum_dispatch(PopupBehavior)]
+pub enum Popup {
+    Problems,
+    IdleReturn,
+    Notes,
+    ExportFormatPicker,
+    ExportPreview,
+    SaveTemplate,
+    TemplatePicker,
+    ShiftTimes,
+    RowFilter,
+    FocusReview,
+    RecoverEdits,
+    SplitShares,
+    ArchivedTickets,
+    ScaffoldOffer,
+    RepairEntries,
+    WorkTypePicker,
+    PastePreview,
+    MergeDuplicates,
+    Links,
+    CarryOver,
+    AbsencePicker,
+    EntryDiff,
+    Confirm,
+    RepairChain,
+    History,
+    ResolveConflict,
+    EntryDetails,
+    FlagPicker,
+}
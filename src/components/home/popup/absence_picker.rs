@@ -0,0 +1,71 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{ListItem, ListState},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    settings,
+    widgets::list_popup::ListPopup,
+};
+
+/// Configured absences, the picked one is inserted at the next free slot
+pub struct AbsencePicker {
+    keys: Vec<&'static str>,
+    list_state: ListState,
+}
+
+impl AbsencePicker {
+    /// `None` if no absences are configured
+    pub fn new() -> Option<Self> {
+        let keys = settings::get().absence_keys();
+        if keys.is_empty() {
+            return None;
+        }
+        let mut list_state = ListState::default();
+        list_state.select_first();
+        Some(Self { keys, list_state })
+    }
+}
+
+impl PopupBehavior for AbsencePicker {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Enter => {
+                let Some(key) = self.list_state.selected().and_then(|it| self.keys.get(it)) else {
+                    return HomeAction::None;
+                };
+                return HomeAction::ClosePopup + HomeAction::InsertAbsence(key.to_string());
+            }
+            KeyCode::Esc | KeyCode::Char('a') => return HomeAction::ClosePopup,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let config = settings::get();
+        let items = self
+            .keys
+            .iter()
+            .map(|key| {
+                let absence = &config.absences[*key];
+                ListItem::from(Line::from(vec![
+                    Span::from(absence.label.as_str()),
+                    Span::from(format!("  {} on {}", absence.duration, absence.project)).dim(),
+                ]))
+            })
+            .collect_vec();
+        let popup = ListPopup::new(" 🌴 Absence ", &mut self.list_state, items)
+            .footer(" <Enter> Insert  <Esc> Cancel ");
+        frame.render_widget(popup, area);
+    }
+}
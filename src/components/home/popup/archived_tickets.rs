@@ -0,0 +1,89 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    widgets::{ListItem, ListState},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    widgets::list_popup::ListPopup,
+};
+
+/// Tickets left out of suggestions, with the ticket of the selected entry to archive next
+pub struct ArchivedTickets {
+    tickets: Vec<String>,
+    list_state: ListState,
+}
+
+impl ArchivedTickets {
+    pub fn new(tickets: Vec<String>) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select_first();
+        Self {
+            tickets,
+            list_state,
+        }
+    }
+
+    fn archive_selected_entry(&mut self, state: &HomeState) -> HomeAction {
+        let Some(ticket) = state
+            .table
+            .selected()
+            .and_then(|idx| state.items.get(idx))
+            .map(|it| it.ticket.clone())
+            .filter(|it| !it.is_empty())
+        else {
+            return HomeAction::SetStatusLine("The selected entry has no ticket".into());
+        };
+        if !self.tickets.contains(&ticket) {
+            self.tickets.push(ticket.clone());
+            self.tickets.sort();
+        }
+        let idx = self.tickets.iter().position(|it| *it == ticket);
+        self.list_state.select(idx);
+        HomeAction::ArchiveTicket(ticket)
+    }
+
+    fn unarchive_highlighted(&mut self) -> HomeAction {
+        let Some(idx) = self
+            .list_state
+            .selected()
+            .filter(|idx| *idx < self.tickets.len())
+        else {
+            return HomeAction::None;
+        };
+        HomeAction::UnarchiveTicket(self.tickets.remove(idx))
+    }
+}
+
+impl PopupBehavior for ArchivedTickets {
+    fn handle_key_event(&mut self, state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Char('a') => return self.archive_selected_entry(state),
+            KeyCode::Char('u') | KeyCode::Delete => return self.unarchive_highlighted(),
+            KeyCode::Esc | KeyCode::Enter => return HomeAction::ClosePopup,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let items = if self.tickets.is_empty() {
+            vec![ListItem::new("No archived tickets yet".dim())]
+        } else {
+            self.tickets
+                .iter()
+                .map(|it| ListItem::new(it.as_str()))
+                .collect_vec()
+        };
+        let popup = ListPopup::new(" 🗄️ Archived tickets ", &mut self.list_state, items)
+            .footer(" <a> Archive ticket of selected entry  <u> Unarchive  <Esc> Close ");
+        frame.render_widget(popup, area);
+    }
+}
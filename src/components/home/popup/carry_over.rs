@@ -0,0 +1,97 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{ListItem, ListState},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    persist::TimeEntry,
+    widgets::list_popup::ListPopup,
+};
+
+/// Offered when opening today's empty timesheet, with the entries of the previous day that
+/// were marked to carry over, all picked at first
+pub struct CarryOver {
+    entries: Vec<(TimeEntry, bool)>,
+    list_state: ListState,
+}
+
+impl CarryOver {
+    pub fn new(entries: Vec<TimeEntry>) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select_first();
+        Self {
+            entries: entries.into_iter().map(|it| (it, true)).collect(),
+            list_state,
+        }
+    }
+}
+
+impl PopupBehavior for CarryOver {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Char(' ') => {
+                let selected = self.list_state.selected();
+                if let Some((_, picked)) = selected.and_then(|idx| self.entries.get_mut(idx)) {
+                    *picked = !*picked;
+                }
+            }
+            KeyCode::Enter => {
+                let picked = self
+                    .entries
+                    .iter()
+                    .filter(|(_, picked)| *picked)
+                    .map(|(entry, _)| entry.clone())
+                    .collect_vec();
+                let apply = if picked.is_empty() {
+                    HomeAction::None
+                } else {
+                    HomeAction::ApplyCarryOver(picked)
+                };
+                return HomeAction::ClosePopup + apply + HomeAction::PrepareScaffold;
+            }
+            KeyCode::Esc => return HomeAction::ClosePopup + HomeAction::PrepareScaffold,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self
+            .entries
+            .iter()
+            .map(|(entry, picked)| {
+                let marker = if *picked { "☑" } else { "☐" };
+                let label = [
+                    entry.ticket_key.as_deref(),
+                    Some(entry.description.as_str()),
+                ]
+                .into_iter()
+                .flatten()
+                .filter(|it| !it.is_empty())
+                .join(" ");
+                ListItem::from(Line::from(vec![
+                    Span::from(format!("{marker} {label}")),
+                    Span::from(format!("  {}m", entry.duration_mins)).dim(),
+                ]))
+            })
+            .collect_vec();
+        let day = self
+            .entries
+            .first()
+            .map(|(it, _)| it.timesheet_day.as_str())
+            .unwrap_or_default();
+        let title = format!(" ↪ Carry over from {day} ");
+        let popup = ListPopup::new(title, &mut self.list_state, items)
+            .footer(" <Space> Toggle  <Enter> Add picked  <Esc> Start without ");
+        frame.render_widget(popup, area);
+    }
+}
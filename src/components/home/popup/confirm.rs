@@ -0,0 +1,63 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Style, palette::tailwind},
+    text::Line,
+    widgets::{Block, BorderType, Clear, Padding, Paragraph, Wrap},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    widgets::list_popup::centered_rect,
+};
+
+/// Asks before performing an action that can't be undone
+pub struct Confirm {
+    title: String,
+    question: String,
+    on_confirm: Box<HomeAction>,
+}
+
+impl Confirm {
+    pub fn new(
+        title: impl Into<String>,
+        question: impl Into<String>,
+        on_confirm: HomeAction,
+    ) -> Self {
+        Self {
+            title: title.into(),
+            question: question.into(),
+            on_confirm: Box::new(on_confirm),
+        }
+    }
+}
+
+impl PopupBehavior for Confirm {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                let on_confirm = std::mem::replace(&mut *self.on_confirm, HomeAction::None);
+                HomeAction::ClosePopup + on_confirm
+            }
+            KeyCode::Char('n') | KeyCode::Esc => HomeAction::ClosePopup,
+            _ => HomeAction::None,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let popup_area = centered_rect(area, 50, 5);
+        frame.render_widget(Clear, popup_area);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .padding(Padding::horizontal(1))
+            .title(format!(" ⚠️ {} ", self.title))
+            .title_bottom(Line::from(" <y> Yes  <n/Esc> No ").right_aligned())
+            .style(Style::new().bg(tailwind::RED.c950));
+        let question = Paragraph::new(self.question.as_str())
+            .wrap(Wrap { trim: true })
+            .block(block);
+        frame.render_widget(question, popup_area);
+    }
+}
@@ -0,0 +1,92 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{ListItem, ListState},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{
+        action::HomeAction,
+        state::{HomeState, TimeItem},
+    },
+    widgets::list_popup::ListPopup,
+};
+
+const FLAGS: [(&str, &str); 3] = [
+    ("Billable", "booked to the customer"),
+    ("Carry over", "offered again the next day"),
+    ("Exact", "never rounded by exports and bookings"),
+];
+
+/// Flags of the selected entry that have no column of their own, to toggle them together
+pub struct EntryDetails {
+    label: String,
+    flags: [bool; 3],
+    list_state: ListState,
+}
+
+impl EntryDetails {
+    pub fn new(item: &TimeItem) -> Self {
+        let label = [item.ticket.as_str(), item.description.as_str()]
+            .into_iter()
+            .filter(|it| !it.is_empty())
+            .join(" ");
+        let mut list_state = ListState::default();
+        list_state.select_first();
+        Self {
+            label: format!("{} {label}", item.start_time.format("%H:%M")),
+            flags: [item.billable, item.carry_over, item.exact],
+            list_state,
+        }
+    }
+}
+
+impl PopupBehavior for EntryDetails {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Char(' ') => {
+                let selected = self.list_state.selected();
+                if let Some(flag) = selected.and_then(|idx| self.flags.get_mut(idx)) {
+                    *flag = !*flag;
+                }
+            }
+            KeyCode::Enter => {
+                let [billable, carry_over, exact] = self.flags;
+                return HomeAction::ClosePopup
+                    + HomeAction::SetFlags {
+                        billable,
+                        carry_over,
+                        exact,
+                    };
+            }
+            KeyCode::Esc => return HomeAction::ClosePopup,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let items = FLAGS
+            .iter()
+            .zip(self.flags)
+            .map(|((label, hint), set)| {
+                let marker = if set { "☑" } else { "☐" };
+                ListItem::from(Line::from(vec![
+                    Span::from(format!("{marker} {label}")),
+                    Span::from(format!("  {hint}")).dim(),
+                ]))
+            })
+            .collect_vec();
+        let title = format!(" {} ", self.label.trim());
+        let popup = ListPopup::new(title, &mut self.list_state, items)
+            .footer(" <Space> Toggle  <Enter> Apply  <Esc> Cancel ");
+        frame.render_widget(popup, area);
+    }
+}
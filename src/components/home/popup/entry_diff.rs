@@ -0,0 +1,148 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Style, Stylize, palette::tailwind},
+    text::Line,
+    widgets::{Block, BorderType, Clear, Padding, Row, Table},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    persist::TimeEntry,
+    settings,
+    shared::DataVersion,
+    widgets::list_popup::centered_rect,
+};
+
+/// Saved values of the selected entry next to the ones that will be written, with the option
+/// to put the saved ones back
+pub struct EntryDiff {
+    /// Label, saved and current value of each field
+    fields: Vec<(&'static str, String, String)>,
+    saved: Option<Box<(TimeEntry, Vec<String>)>>,
+    state: &'static str,
+}
+
+impl EntryDiff {
+    pub fn new(
+        saved: Option<(TimeEntry, Vec<String>)>,
+        current: (TimeEntry, Vec<String>),
+        version: &DataVersion,
+    ) -> Self {
+        let state = if saved.is_none() {
+            "Not saved yet"
+        } else if version.has_failed() {
+            "⚡ Saving failed, retry with r"
+        } else if version.sent.is_some() {
+            "💾 Being saved"
+        } else if version.should_save() {
+            "✏️ Changed, saved soon"
+        } else {
+            "✅ Saved"
+        };
+        Self {
+            fields: fields(saved.as_ref(), &current),
+            saved: saved.map(Box::new),
+            state,
+        }
+    }
+}
+
+fn fields(
+    saved: Option<&(TimeEntry, Vec<String>)>,
+    current: &(TimeEntry, Vec<String>),
+) -> Vec<(&'static str, String, String)> {
+    let (current, current_links) = current;
+    let values = |entry: &TimeEntry, links: &[String]| {
+        [
+            ("Start", entry.start_time.clone()),
+            ("Duration", format!("{}m", entry.duration_mins)),
+            ("Project", entry.project_key.clone()),
+            ("Ticket", entry.ticket_key.clone().unwrap_or_default()),
+            ("Description", entry.description.clone()),
+            ("Billable", yes_no(entry.billable)),
+            ("Work type", entry.work_type.clone().unwrap_or_default()),
+            ("Carry over", yes_no(entry.carry_over)),
+            ("Exact", yes_no(entry.exact)),
+            (
+                "Flags",
+                settings::get().flag_symbols(entry.flags.split_whitespace()),
+            ),
+            ("Links", links.join(" ")),
+        ]
+    };
+    let current = values(current, current_links);
+    let saved = saved.map(|(entry, links)| values(entry, links).map(|(_, value)| value));
+    current
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (label, value))| {
+            let saved = saved.as_ref().map(|it| it[idx].clone()).unwrap_or_default();
+            (label, saved, value)
+        })
+        .collect()
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_string()
+}
+
+impl PopupBehavior for EntryDiff {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Char('d') => {
+                let Some((entry, links)) = self.saved.as_deref().cloned() else {
+                    return HomeAction::SetStatusLine(
+                        "Not saved yet, nothing to go back to".into(),
+                    );
+                };
+                if self
+                    .fields
+                    .iter()
+                    .all(|(_, saved, current)| saved == current)
+                {
+                    return HomeAction::SetStatusLine("No changes to discard".into());
+                }
+                HomeAction::ClosePopup + HomeAction::DiscardChanges { entry, links }
+            }
+            KeyCode::Esc | KeyCode::Enter => HomeAction::ClosePopup,
+            _ => HomeAction::None,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let header = Row::new(["", "Saved", "Current"]).bold();
+        // Borders and header
+        let height = self.fields.len() as u16 + 3;
+        let rows = self.fields.iter().map(|(label, saved, current)| {
+            let style = if saved == current {
+                Style::new()
+            } else {
+                Style::new().fg(tailwind::AMBER.c300)
+            };
+            Row::new([*label, saved.as_str(), current.as_str()]).style(style)
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(12),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header)
+        .column_spacing(2);
+
+        let popup_area = centered_rect(area, 80, height);
+        frame.render_widget(Clear, popup_area);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .padding(Padding::horizontal(1))
+            .title(format!(" 🔍 Saved vs. current - {} ", self.state))
+            .title_bottom(Line::from(" <d> Discard changes  <Esc> Close ").right_aligned())
+            .style(Style::new().bg(tailwind::INDIGO.c950));
+        frame.render_widget(table.block(block), popup_area);
+    }
+}
@@ -0,0 +1,104 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    text::Line,
+    widgets::{ListItem, ListState},
+};
+use strum::IntoEnumIterator;
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    config::DefragStrategy,
+    export::ExportFormat,
+    widgets::list_popup::ListPopup,
+};
+
+/// Which formats to export the day in, preselected with the last picked ones, and how the
+/// defragmented one is ordered
+pub struct ExportFormatPicker {
+    formats: Vec<(ExportFormat, bool)>,
+    defrag: DefragStrategy,
+    list_state: ListState,
+}
+
+impl ExportFormatPicker {
+    pub fn new(picked: &[ExportFormat], defrag: DefragStrategy) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select_first();
+        Self {
+            formats: ExportFormat::iter()
+                .map(|it| (it, picked.contains(&it)))
+                .collect(),
+            defrag,
+            list_state,
+        }
+    }
+}
+
+impl PopupBehavior for ExportFormatPicker {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Char(' ') => {
+                let selected = self.list_state.selected();
+                if let Some((_, picked)) = selected.and_then(|idx| self.formats.get_mut(idx)) {
+                    *picked = !*picked;
+                }
+            }
+            KeyCode::Char('s') => {
+                self.defrag = DefragStrategy::iter()
+                    .cycle()
+                    .skip_while(|it| *it != self.defrag)
+                    .nth(1)
+                    .unwrap_or_default();
+            }
+            KeyCode::Enter => {
+                let picked = self
+                    .formats
+                    .iter()
+                    .filter(|(_, picked)| *picked)
+                    .map(|(format, _)| *format)
+                    .collect_vec();
+                if picked.is_empty() {
+                    return HomeAction::SetStatusLine("pick at least one format".into());
+                }
+                return HomeAction::ClosePopup
+                    + HomeAction::PrepareExport {
+                        formats: picked,
+                        defrag: self.defrag,
+                    };
+            }
+            KeyCode::Esc => {
+                return HomeAction::ClosePopup
+                    + HomeAction::SetStatusLine("Export cancelled".into());
+            }
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self
+            .formats
+            .iter()
+            .map(|(format, picked)| {
+                let marker = if *picked { "☑" } else { "☐" };
+                let line = match format {
+                    ExportFormat::Defrag | ExportFormat::JiraCsv => {
+                        format!("{marker} {} ({})", format.label(), self.defrag.label())
+                    }
+                    _ => format!("{marker} {}", format.label()),
+                };
+                ListItem::from(Line::from(line))
+            })
+            .collect_vec();
+        let popup = ListPopup::new(" 📤 Export as ", &mut self.list_state, items)
+            .footer(" <Space> Toggle  <s> Defrag order  <Enter> Preview  <Esc> Cancel ")
+            .width_percent(40);
+        frame.render_widget(popup, area);
+    }
+}
@@ -0,0 +1,131 @@
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Style, Stylize, palette::tailwind::INDIGO},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Clear, Padding, Paragraph},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, export::ExportFile, state::HomeState},
+    export::ExportFormat,
+    widgets::{
+        list_popup::centered_rect,
+        text_popup::{TextInput, TextInputOutcome, TextPopup},
+    },
+};
+
+/// First lines of the generated files and where they go, written only on confirmation
+pub struct ExportPreview {
+    /// One file per picked format
+    files: Vec<(ExportFormat, ExportFile)>,
+    shown: usize,
+    preview_lines: usize,
+    path_input: Option<TextInput>,
+}
+
+impl ExportPreview {
+    pub fn new(files: Vec<(ExportFormat, ExportFile)>, preview_lines: usize) -> Self {
+        Self {
+            files,
+            shown: 0,
+            preview_lines,
+            path_input: None,
+        }
+    }
+
+    fn handle_path_input(&mut self, key: KeyEvent) -> HomeAction {
+        let input = self.path_input.as_mut().expect("path being edited");
+        match input.handle_key_event(key) {
+            TextInputOutcome::Editing => {}
+            TextInputOutcome::Submitted(path) => {
+                if !path.is_empty() {
+                    self.files[self.shown].1.set_path(PathBuf::from(path));
+                }
+                self.path_input = None;
+            }
+            TextInputOutcome::Cancelled => self.path_input = None,
+        }
+        HomeAction::None
+    }
+
+    fn title(&self) -> String {
+        let formats = self
+            .files
+            .iter()
+            .map(|(format, _)| format.label())
+            .join(" + ");
+        format!(" 📤 Export {formats} ")
+    }
+}
+
+impl PopupBehavior for ExportPreview {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        if self.path_input.is_some() {
+            return self.handle_path_input(key);
+        }
+        match key.code {
+            KeyCode::Enter => {
+                let files = self.files.iter().map(|(_, file)| file.clone()).collect();
+                return HomeAction::ClosePopup + HomeAction::WriteExport(files);
+            }
+            KeyCode::Tab => self.shown = (self.shown + 1) % self.files.len(),
+            KeyCode::Char('p') => {
+                let path = self.files[self.shown].1.path.display().to_string();
+                self.path_input = Some(TextInput::new(path));
+            }
+            KeyCode::Esc => {
+                return HomeAction::ClosePopup
+                    + HomeAction::SetStatusLine("Export cancelled".into());
+            }
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let (format, file) = &self.files[self.shown];
+        let mut target = vec![
+            Span::from(format!("{} → ", format.label())).bold(),
+            Span::from(file.path.display().to_string()),
+        ];
+        if file.path.exists() {
+            target.push(Span::from("  ⚠️ overwrites existing file").yellow());
+        }
+        let mut lines = vec![Line::from(target), Line::default()];
+        let content_lines = file.content.lines().count();
+        lines.extend(
+            file.content
+                .lines()
+                .take(self.preview_lines)
+                .map(|it| Line::from(it.to_string())),
+        );
+        if content_lines > self.preview_lines {
+            let more = content_lines - self.preview_lines;
+            lines.push(Line::from(format!("… {more} more lines")).italic());
+        }
+
+        let popup_area = centered_rect(area, 80, lines.len() as u16 + 2);
+        frame.render_widget(Clear, popup_area);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .padding(Padding::horizontal(1))
+            .title(self.title())
+            .title_bottom(
+                Line::from(" <Enter> Write  <Tab> Next file  <p> Path  <Esc> Cancel ")
+                    .right_aligned(),
+            )
+            .style(Style::new().bg(INDIGO.c950));
+        frame.render_widget(Paragraph::new(lines).block(block), popup_area);
+
+        if let Some(input) = &self.path_input {
+            let title = format!(" {} path ", format.label());
+            frame.render_widget(TextPopup::new(title, input), area);
+        }
+    }
+}
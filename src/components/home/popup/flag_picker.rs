@@ -0,0 +1,103 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{ListItem, ListState},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    settings,
+    widgets::list_popup::ListPopup,
+};
+
+/// The configured flags by label, toggled on the selected entry with their number
+pub struct FlagPicker {
+    keys: Vec<&'static str>,
+    set: Vec<String>,
+    list_state: ListState,
+}
+
+impl FlagPicker {
+    /// `None` if no flags are configured
+    pub fn new(set: &[String]) -> Option<Self> {
+        let keys = settings::get().flag_keys();
+        if keys.is_empty() {
+            return None;
+        }
+        let mut list_state = ListState::default();
+        list_state.select_first();
+        Some(Self {
+            keys,
+            set: set.to_vec(),
+            list_state,
+        })
+    }
+
+    fn toggle(&mut self, idx: usize) {
+        let Some(key) = self.keys.get(idx) else {
+            return;
+        };
+        match self.set.iter().position(|it| it == key) {
+            Some(position) => {
+                self.set.remove(position);
+            }
+            None => self.set.push(key.to_string()),
+        }
+    }
+}
+
+impl PopupBehavior for FlagPicker {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Char(digit @ '1'..='9') => {
+                let idx = digit as usize - '1' as usize;
+                if idx < self.keys.len() {
+                    self.toggle(idx);
+                    self.list_state.select(Some(idx));
+                }
+            }
+            KeyCode::Char(' ') => {
+                if let Some(idx) = self.list_state.selected() {
+                    self.toggle(idx);
+                }
+            }
+            KeyCode::Enter => {
+                return HomeAction::ClosePopup + HomeAction::SetFlagKeys(self.set.clone());
+            }
+            KeyCode::Esc => return HomeAction::ClosePopup,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let config = settings::get();
+        let items = self
+            .keys
+            .iter()
+            .enumerate()
+            .map(|(idx, key)| {
+                let marker = if self.set.iter().any(|it| it == key) {
+                    "☑"
+                } else {
+                    "☐"
+                };
+                let flag = &config.flags[*key];
+                ListItem::from(Line::from(vec![
+                    Span::from(format!("{} ", idx + 1)).dim(),
+                    Span::from(format!("{marker} {} {}", flag.symbol, flag.label)),
+                ]))
+            })
+            .collect_vec();
+        let popup = ListPopup::new(" 🚩 Flags ", &mut self.list_state, items)
+            .footer(" <1-9/Space> Toggle  <Enter> Apply  <Esc> Cancel ");
+        frame.render_widget(popup, area);
+    }
+}
@@ -0,0 +1,93 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{ListItem, ListState},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    shared::focus::FocusSuggestion,
+    widgets::list_popup::ListPopup,
+};
+
+/// Entries suggested from the focused window, each to be added or dismissed
+pub struct FocusReview {
+    suggestions: Vec<FocusSuggestion>,
+    list_state: ListState,
+}
+
+impl FocusReview {
+    pub fn new(suggestions: Vec<FocusSuggestion>) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select_first();
+        Self {
+            suggestions,
+            list_state,
+        }
+    }
+
+    /// Takes the selected suggestion off the list, closing once all are reviewed
+    fn take_selected(&mut self, to_action: fn(FocusSuggestion) -> HomeAction) -> HomeAction {
+        let Some(idx) = self
+            .list_state
+            .selected()
+            .filter(|idx| *idx < self.suggestions.len())
+        else {
+            return HomeAction::None;
+        };
+        let action = to_action(self.suggestions.remove(idx));
+        if self.suggestions.is_empty() {
+            action + HomeAction::ClosePopup
+        } else {
+            action
+        }
+    }
+}
+
+impl PopupBehavior for FocusReview {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Enter => return self.take_selected(HomeAction::AcceptFocusSuggestion),
+            KeyCode::Char('d') | KeyCode::Delete => {
+                return self.take_selected(HomeAction::DismissFocusSuggestion);
+            }
+            KeyCode::Esc => return HomeAction::ClosePopup,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self
+            .suggestions
+            .iter()
+            .map(|it| {
+                let mins = (it.end - it.start).num_minutes();
+                ListItem::from(Line::from(vec![
+                    Span::from(format!(
+                        "{}-{} ",
+                        it.start.format("%H:%M"),
+                        it.end.format("%H:%M")
+                    ))
+                    .dim(),
+                    Span::from(it.matched.label()),
+                    Span::from(format!("  {mins}m")).dim(),
+                ]))
+            })
+            .collect_vec();
+        let popup = ListPopup::new(
+            " 🪟 Suggested from window focus ",
+            &mut self.list_state,
+            items,
+        )
+        .footer(" <Enter> Add  <d> Dismiss  <Esc> Later ");
+        frame.render_widget(popup, area);
+    }
+}
@@ -0,0 +1,119 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Style, Stylize, palette::tailwind},
+    text::Line,
+    widgets::{Block, BorderType, Clear, Padding, Paragraph, Row, Table, Wrap},
+};
+use time::Date;
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    persist::DaySnapshot,
+    widgets::list_popup::centered_rect,
+};
+
+/// Earlier versions of the day, one at a time and read-only, to restore one of them
+pub struct History {
+    day: Date,
+    /// Latest first
+    snapshots: Vec<DaySnapshot>,
+    shown: usize,
+}
+
+impl History {
+    pub fn new(day: Date, snapshots: Vec<DaySnapshot>) -> Self {
+        Self {
+            day,
+            snapshots,
+            shown: 0,
+        }
+    }
+}
+
+impl PopupBehavior for History {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Left if self.shown + 1 < self.snapshots.len() => self.shown += 1,
+            KeyCode::Right => self.shown = self.shown.saturating_sub(1),
+            KeyCode::Enter => {
+                let taken_at = self.snapshots[self.shown].taken_at.clone();
+                return HomeAction::ClosePopup + HomeAction::ConfirmRestoreSnapshot(taken_at);
+            }
+            KeyCode::Esc => return HomeAction::ClosePopup,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let snapshot = &self.snapshots[self.shown];
+        let header = Row::new(["Start", "Duration", "Project", "Ticket", "Description"]).bold();
+        let rows = snapshot.entries.iter().map(|entry| {
+            let links = snapshot
+                .links
+                .iter()
+                .filter(|it| it.entry_id == entry.id)
+                .count();
+            let mut description = entry.description.clone();
+            if links > 0 {
+                description += &format!(" 🔗{links}");
+            }
+            Row::new([
+                entry.start_time.clone(),
+                format!("{}m", entry.duration_mins),
+                entry.project_key.clone(),
+                entry.ticket_key.clone().unwrap_or_default(),
+                description,
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(5),
+                Constraint::Length(8),
+                Constraint::Length(8),
+                Constraint::Length(12),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header)
+        .column_spacing(1);
+        let notes = snapshot.notes.lines().map(Line::from).collect_vec();
+
+        // Borders, header and a blank line before the notes
+        let notes_height = if notes.is_empty() { 0 } else { notes.len() + 1 };
+        let height = snapshot.entries.len() + notes_height + 3;
+        let popup_area = centered_rect(area, 80, height as u16);
+        frame.render_widget(Clear, popup_area);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .padding(Padding::horizontal(1))
+            .title(format!(
+                " 🕰️ {} as of {} ({}/{}) ",
+                self.day,
+                snapshot.taken_at,
+                self.shown + 1,
+                self.snapshots.len()
+            ))
+            .title_bottom(
+                Line::from(" <Left/Right> Older/newer  <Enter> Restore  <Esc> Close ")
+                    .right_aligned(),
+            )
+            .style(Style::new().bg(tailwind::INDIGO.c950));
+        let inner = block.inner(popup_area);
+        frame.render_widget(block, popup_area);
+        let [table_area, notes_area] = Layout::vertical([
+            Constraint::Length(snapshot.entries.len() as u16 + 1),
+            Constraint::Fill(1),
+        ])
+        .spacing(1)
+        .areas(inner);
+        frame.render_widget(table, table_area);
+        let notes = Paragraph::new(notes).italic().wrap(Wrap { trim: false });
+        frame.render_widget(notes, notes_area);
+    }
+}
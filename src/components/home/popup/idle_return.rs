@@ -0,0 +1,62 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    widgets::{ListItem, ListState},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    shared::idle::IdleSpan,
+    widgets::list_popup::ListPopup,
+};
+
+/// Offered when the user comes back after being idle, to account for the idle time.
+pub struct IdleReturn {
+    span: IdleSpan,
+    list_state: ListState,
+}
+
+impl IdleReturn {
+    pub fn new(span: IdleSpan) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select_first();
+        Self { span, list_state }
+    }
+
+    fn record_break(&self) -> HomeAction {
+        HomeAction::ClosePopup + HomeAction::ExitEdit + HomeAction::InsertBreak(self.span)
+    }
+}
+
+impl PopupBehavior for IdleReturn {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Char('b') => return self.record_break(),
+            KeyCode::Enter if self.list_state.selected() == Some(0) => return self.record_break(),
+            KeyCode::Enter | KeyCode::Char('d') | KeyCode::Esc => return HomeAction::ClosePopup,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let items = vec![
+            ListItem::new("☕ Record as break (b)"),
+            ListItem::new("🗑️ Discard (d)"),
+        ];
+        let title = format!(
+            " 💤 Idle from {} to {} ({}m) ",
+            self.span.start.format("%H:%M"),
+            self.span.end.format("%H:%M"),
+            self.span.duration().num_minutes()
+        );
+        let popup = ListPopup::new(title, &mut self.list_state, items)
+            .footer(" <Enter> Choose  <Esc> Discard ")
+            .width_percent(40);
+        frame.render_widget(popup, area);
+    }
+}
@@ -0,0 +1,99 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{ListItem, ListState},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    persist::TimeEntryId,
+    widgets::{
+        list_popup::ListPopup,
+        text_popup::{TextInput, TextInputOutcome},
+    },
+};
+
+/// Links attached to an entry, with a line at the bottom to add another one
+pub struct Links {
+    id: TimeEntryId,
+    urls: Vec<String>,
+    input: TextInput,
+    /// Selects one of the links to remove, or the input line after them
+    list_state: ListState,
+}
+
+impl Links {
+    pub fn new(id: TimeEntryId, urls: Vec<String>) -> Self {
+        let list_state = ListState::default().with_selected(Some(urls.len()));
+        Self {
+            id,
+            urls,
+            input: TextInput::default(),
+            list_state,
+        }
+    }
+
+    fn selected(&self) -> usize {
+        self.list_state.selected().unwrap_or(self.urls.len())
+    }
+
+    fn remove_selected(&mut self) {
+        let idx = self.selected();
+        if idx < self.urls.len() {
+            self.urls.remove(idx);
+        }
+    }
+}
+
+impl PopupBehavior for Links {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Down => {
+                let next = (self.selected() + 1).min(self.urls.len());
+                self.list_state.select(Some(next));
+            }
+            KeyCode::Up => {
+                let previous = self.selected().saturating_sub(1);
+                self.list_state.select(Some(previous));
+            }
+            KeyCode::Delete => self.remove_selected(),
+            _ => match self.input.handle_key_event(key) {
+                TextInputOutcome::Editing => {}
+                TextInputOutcome::Submitted(url) if url.is_empty() => {
+                    let urls = std::mem::take(&mut self.urls);
+                    let id = self.id.clone();
+                    return HomeAction::ClosePopup + HomeAction::StoreLinks { id, urls };
+                }
+                TextInputOutcome::Submitted(url) => {
+                    self.urls.push(url);
+                    self.input = TextInput::default();
+                    self.list_state.select(Some(self.urls.len()));
+                }
+                TextInputOutcome::Cancelled => return HomeAction::ClosePopup,
+            },
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let input = Line::from(vec![
+            Span::from("+ ").dim(),
+            Span::from(format!("{}▏", self.input.text())),
+        ]);
+        let items = self
+            .urls
+            .iter()
+            .map(|it| ListItem::new(it.as_str()))
+            .chain([ListItem::new(input)])
+            .collect_vec();
+        let popup = ListPopup::new(" 🔗 Links of this entry ", &mut self.list_state, items)
+            .footer(" <Enter> Add, or save if empty  <Del> Remove  <Esc> Cancel ")
+            .width_percent(70);
+        frame.render_widget(popup, area);
+    }
+}
@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use humantime::format_duration;
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{ListItem, ListState},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{
+        action::HomeAction,
+        state::{HomeState, TimeItem},
+    },
+    persist::TimeEntryId,
+    shared::dedupe,
+    widgets::list_popup::ListPopup,
+};
+
+/// Consecutive entries that would become one
+struct DuplicateRun {
+    /// The first one is kept
+    ids: Vec<TimeEntryId>,
+    label: String,
+    duration: Duration,
+}
+
+/// Preview of merging consecutive entries with the same project, ticket and description. They
+/// also need to agree on billable, work type, flags and being exact, so that merging loses
+/// nothing.
+pub struct MergeDuplicates {
+    runs: Vec<DuplicateRun>,
+    list_state: ListState,
+}
+
+impl MergeDuplicates {
+    /// `None` if there is nothing to merge
+    pub fn new(items: &[TimeItem]) -> Option<Self> {
+        let runs = dedupe::consecutive_runs(items, |a, b| {
+            a.project == b.project
+                && a.ticket == b.ticket
+                && a.description.trim() == b.description.trim()
+                && a.billable == b.billable
+                && a.work_type == b.work_type
+                && a.exact == b.exact
+                && a.flags == b.flags
+        })
+        .into_iter()
+        .map(|range| {
+            let run = &items[range];
+            let first = &run[0];
+            let label = [
+                first.start_time.format("%H:%M").to_string(),
+                first.ticket.clone(),
+                first.description.trim().to_string(),
+            ]
+            .into_iter()
+            .filter(|it| !it.is_empty())
+            .join(" ");
+            DuplicateRun {
+                ids: run.iter().map(|it| it.id.clone()).collect(),
+                label,
+                duration: run.iter().map(|it| it.duration).sum(),
+            }
+        })
+        .collect_vec();
+        if runs.is_empty() {
+            return None;
+        }
+        Some(Self {
+            runs,
+            list_state: ListState::default(),
+        })
+    }
+}
+
+impl PopupBehavior for MergeDuplicates {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Enter => {
+                let runs = self.runs.iter().map(|it| it.ids.clone()).collect();
+                return HomeAction::ClosePopup + HomeAction::MergeDuplicates(runs);
+            }
+            KeyCode::Esc => return HomeAction::ClosePopup,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self
+            .runs
+            .iter()
+            .map(|run| {
+                ListItem::from(Line::from(vec![
+                    Span::from(run.label.as_str()),
+                    Span::from(format!(
+                        "  {} entries → {}",
+                        run.ids.len(),
+                        format_duration(run.duration)
+                    ))
+                    .dim(),
+                ]))
+            })
+            .collect_vec();
+        let obsolete: usize = self.runs.iter().map(|it| it.ids.len() - 1).sum();
+        let title = format!(" 🧹 Merge consecutive duplicates, {obsolete} entries fewer ");
+        let popup = ListPopup::new(title, &mut self.list_state, items)
+            .footer(" <Enter> Merge  <Esc> Cancel ")
+            .width_percent(70);
+        frame.render_widget(popup, area);
+    }
+}
@@ -0,0 +1,36 @@
+use crossterm::event::KeyEvent;
+use ratatui::{Frame, layout::Rect};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    widgets::text_popup::{TextInput, TextInputOutcome, TextPopup},
+};
+
+pub struct Notes {
+    input: TextInput,
+}
+
+impl Notes {
+    pub fn new(notes: String) -> Self {
+        Self {
+            input: TextInput::new(notes),
+        }
+    }
+}
+
+impl PopupBehavior for Notes {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match self.input.handle_key_event(key) {
+            TextInputOutcome::Editing => HomeAction::None,
+            TextInputOutcome::Submitted(notes) => {
+                HomeAction::ClosePopup + HomeAction::StoreNotes(notes)
+            }
+            TextInputOutcome::Cancelled => HomeAction::ClosePopup,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(TextPopup::new(" 📝 Notes for this day ", &self.input), area);
+    }
+}
@@ -0,0 +1,139 @@
+use chrono::TimeDelta;
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Stylize, palette::tailwind},
+    text::{Line, Span},
+    widgets::{ListItem, ListState},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{
+        action::HomeAction,
+        state::{HomeState, TimeItem},
+    },
+    shared::parse::PastedEntry,
+    widgets::list_popup::ListPopup,
+};
+
+/// How a pasted entry relates to the entries of the day
+enum PasteChange {
+    Add,
+    /// Added, but overlaps the entry with this start and description
+    Overlaps(String),
+    /// Same times, ticket and description as an existing entry, not added again
+    Duplicate,
+}
+
+/// Entries parsed from the clipboard, shown against the day before adding them
+pub struct PastePreview {
+    entries: Vec<(PastedEntry, PasteChange)>,
+    list_state: ListState,
+}
+
+impl PastePreview {
+    pub fn new(pasted: Vec<PastedEntry>, items: &[TimeItem]) -> Self {
+        let entries = pasted
+            .into_iter()
+            .map(|entry| {
+                let change = change_for(&entry, items);
+                (entry, change)
+            })
+            .collect();
+        Self {
+            entries,
+            list_state: ListState::default(),
+        }
+    }
+
+    fn to_add(&self) -> Vec<PastedEntry> {
+        self.entries
+            .iter()
+            .filter(|(_, change)| !matches!(change, PasteChange::Duplicate))
+            .map(|(entry, _)| entry.clone())
+            .collect()
+    }
+}
+
+fn change_for(entry: &PastedEntry, items: &[TimeItem]) -> PasteChange {
+    let end = entry.start + TimeDelta::minutes(entry.duration_mins as i64);
+    let ticket = entry.ticket_key.as_deref().unwrap_or_default();
+    let mut overlapping = items
+        .iter()
+        .filter(|it| !it.duration.is_zero())
+        .filter(|it| it.start_time < end && entry.start < it.next_start_time());
+    let Some(first) = overlapping.clone().next() else {
+        return PasteChange::Add;
+    };
+    let is_duplicate = overlapping.any(|it| {
+        it.start_time == entry.start
+            && it.duration.as_secs() == entry.duration_mins as u64 * 60
+            && it.ticket == ticket
+            && it.description.trim() == entry.description
+    });
+    if is_duplicate {
+        PasteChange::Duplicate
+    } else {
+        let label = format!("{} {}", first.start_time.format("%H:%M"), first.description);
+        PasteChange::Overlaps(label)
+    }
+}
+
+impl PopupBehavior for PastePreview {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Enter | KeyCode::Char('a') => {
+                return HomeAction::ClosePopup + HomeAction::ApplyPasted(self.to_add());
+            }
+            KeyCode::Esc => return HomeAction::ClosePopup,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self
+            .entries
+            .iter()
+            .map(|(entry, change)| {
+                let label = [
+                    entry.ticket_key.as_deref(),
+                    Some(entry.description.as_str()),
+                ]
+                .into_iter()
+                .flatten()
+                .filter(|it| !it.is_empty())
+                .join(" ");
+                let (marker, note) = match change {
+                    PasteChange::Add => (Span::from("+ ").fg(tailwind::GREEN.c400), None),
+                    PasteChange::Overlaps(existing) => (
+                        Span::from("~ ").fg(tailwind::AMBER.c300),
+                        Some(format!("  overlaps {existing}")),
+                    ),
+                    PasteChange::Duplicate => {
+                        (Span::from("= ").dim(), Some("  already there".to_string()))
+                    }
+                };
+                let mut spans = vec![
+                    marker,
+                    Span::from(format!("{} ", entry.start.format("%H:%M"))).dim(),
+                    Span::from(label),
+                    Span::from(format!("  {}m", entry.duration_mins)).dim(),
+                ];
+                spans.extend(note.map(|it| Span::from(it).fg(tailwind::AMBER.c300)));
+                ListItem::from(Line::from(spans))
+            })
+            .collect_vec();
+        let count = self.to_add().len();
+        let title = format!(" 📋 Add {count} pasted entries ");
+        let popup = ListPopup::new(title, &mut self.list_state, items)
+            .footer(" <Enter> Add  <Esc> Cancel ")
+            .width_percent(80);
+        frame.render_widget(popup, area);
+    }
+}
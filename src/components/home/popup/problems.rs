@@ -0,0 +1,77 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    text::Line,
+    widgets::{ListItem, ListState},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    shared::validation::{Severity, Violation},
+    widgets::list_popup::ListPopup,
+};
+
+pub struct Problems {
+    violations: Vec<Violation>,
+    list_state: ListState,
+}
+
+impl Problems {
+    pub fn new(violations: Vec<Violation>) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select_first();
+        Self {
+            violations,
+            list_state,
+        }
+    }
+}
+
+impl PopupBehavior for Problems {
+    fn handle_key_event(&mut self, state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Enter => {
+                let selected = self.list_state.selected();
+                if let Some(entry_idx) = selected
+                    .and_then(|idx| self.violations.get(idx))
+                    .and_then(|it| it.entry_idx)
+                {
+                    state.table.select(Some(entry_idx));
+                    state.ensure_column_selected();
+                }
+                return HomeAction::ClosePopup + HomeAction::EnterSelect;
+            }
+            KeyCode::Esc | KeyCode::Char('!') => return HomeAction::ClosePopup,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self
+            .violations
+            .iter()
+            .map(|it| {
+                let icon = match it.severity {
+                    Severity::Hard => "⛔",
+                    Severity::Warning => "⚠️",
+                };
+                ListItem::from(Line::from(format!("{icon} {}", it.message)))
+            })
+            .collect_vec();
+        let title = if self.violations.is_empty() {
+            " ✅ No problems ".to_string()
+        } else {
+            format!(" Problems ({}) ", self.violations.len())
+        };
+        let popup = ListPopup::new(title, &mut self.list_state, items)
+            .footer(" <Enter> Jump to entry  <Esc> Close ")
+            .width_percent(80);
+        frame.render_widget(popup, area);
+    }
+}
@@ -0,0 +1,84 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{ListItem, ListState},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    persist::TimeEntry,
+    widgets::list_popup::ListPopup,
+};
+
+/// Changes that were sent to be saved but never confirmed, e.g. because of a crash
+pub struct RecoverEdits {
+    entries: Vec<TimeEntry>,
+    list_state: ListState,
+}
+
+impl RecoverEdits {
+    pub fn new(mut entries: Vec<TimeEntry>) -> Self {
+        entries.sort_by(|a, b| {
+            (&a.timesheet_day, &a.start_time).cmp(&(&b.timesheet_day, &b.start_time))
+        });
+        let mut list_state = ListState::default();
+        list_state.select_first();
+        Self {
+            entries,
+            list_state,
+        }
+    }
+}
+
+impl PopupBehavior for RecoverEdits {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            // Ctrl+D quits, which must not discard anything
+            return HomeAction::None;
+        }
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Enter | KeyCode::Char('r') => {
+                return HomeAction::ClosePopup
+                    + HomeAction::ReplayRecoveredEdits(self.entries.clone());
+            }
+            KeyCode::Char('d') => {
+                return HomeAction::ClosePopup + HomeAction::DiscardRecoveredEdits;
+            }
+            KeyCode::Esc => return HomeAction::ClosePopup,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self
+            .entries
+            .iter()
+            .map(|it| {
+                ListItem::from(Line::from(vec![
+                    Span::from(format!("{} {} ", it.timesheet_day, it.start_time)).dim(),
+                    Span::from(format!(
+                        "{} {}",
+                        it.ticket_key.as_deref().unwrap_or(&it.project_key),
+                        it.description
+                    )),
+                    Span::from(format!("  {}m", it.duration_mins)).dim(),
+                ]))
+            })
+            .collect_vec();
+        let popup = ListPopup::new(
+            " 🩹 Unsaved changes from the last session ",
+            &mut self.list_state,
+            items,
+        )
+        .footer(" <Enter> Replay  <d> Discard  <Esc> Ask again next time ");
+        frame.render_widget(popup, area);
+    }
+}
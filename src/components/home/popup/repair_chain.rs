@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use chrono::NaiveTime;
+use crossterm::event::{KeyCode, KeyEvent};
+use humantime::format_duration;
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Style, Stylize, palette::tailwind},
+    text::Line,
+    widgets::{Block, BorderType, Clear, Padding, Row, Table},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{
+        action::HomeAction,
+        state::{ChainRepair, HomeState, TimeItem},
+    },
+    widgets::list_popup::centered_rect,
+};
+
+/// Start times and durations before and after lining up the day, to check before applying
+pub struct RepairChain {
+    repair: ChainRepair,
+    /// Entry, then the old and new start and duration
+    rows: Vec<(String, [String; 4])>,
+    changed: usize,
+}
+
+impl RepairChain {
+    pub fn new(
+        repair: ChainRepair,
+        items: &[TimeItem],
+        repaired: &[(NaiveTime, Duration)],
+    ) -> Self {
+        let rows = items
+            .iter()
+            .zip(repaired)
+            .map(|(item, (start_time, duration))| {
+                let label = [item.ticket.as_str(), item.description.as_str()]
+                    .into_iter()
+                    .filter(|it| !it.is_empty())
+                    .join(" ");
+                let times = [
+                    item.start_time.format("%H:%M").to_string(),
+                    start_time.format("%H:%M").to_string(),
+                    format_duration(item.duration).to_string(),
+                    format_duration(*duration).to_string(),
+                ];
+                (label, times)
+            })
+            .collect_vec();
+        let changed = rows
+            .iter()
+            .filter(|(_, [start, new_start, duration, new_duration])| {
+                start != new_start || duration != new_duration
+            })
+            .count();
+        Self {
+            repair,
+            rows,
+            changed,
+        }
+    }
+
+    pub fn changed(&self) -> usize {
+        self.changed
+    }
+}
+
+impl PopupBehavior for RepairChain {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Tab => {
+                HomeAction::ClosePopup + HomeAction::EditRepairChain(self.repair.other())
+            }
+            KeyCode::Enter => HomeAction::ClosePopup + HomeAction::RepairChain(self.repair),
+            KeyCode::Esc => HomeAction::ClosePopup,
+            _ => HomeAction::None,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let header = Row::new(["Start", "", "Duration", "", "Entry"]).bold();
+        let rows = self.rows.iter().map(|(label, times)| {
+            let [start, new_start, duration, new_duration] = times;
+            let changed = |old: &String, new: &String| {
+                if old == new {
+                    Line::from("")
+                } else {
+                    Line::from(format!("→ {new}")).fg(tailwind::AMBER.c300)
+                }
+            };
+            Row::new([
+                Line::from(start.as_str()),
+                changed(start, new_start),
+                Line::from(duration.as_str()),
+                changed(duration, new_duration),
+                Line::from(label.as_str()),
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(5),
+                Constraint::Length(7),
+                Constraint::Length(8),
+                Constraint::Length(10),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header)
+        .column_spacing(1);
+
+        // Borders and header
+        let height = self.rows.len() as u16 + 3;
+        let popup_area = centered_rect(area, 70, height);
+        frame.render_widget(Clear, popup_area);
+        let (title, other) = match self.repair {
+            ChainRepair::Starts => ("start times", "durations"),
+            ChainRepair::Durations => ("durations", "start times"),
+        };
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .padding(Padding::horizontal(1))
+            .title(format!(" 🔗 Recompute {title} - {} changes ", self.changed))
+            .title_bottom(
+                Line::from(format!(
+                    " <Tab> Recompute {other}  <Enter> Apply  <Esc> Cancel "
+                ))
+                .right_aligned(),
+            )
+            .style(Style::new().bg(tailwind::INDIGO.c950));
+        frame.render_widget(table.block(block), popup_area);
+    }
+}
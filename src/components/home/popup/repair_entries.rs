@@ -0,0 +1,180 @@
+use std::str::FromStr;
+
+use chrono::NaiveTime;
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{ListItem, ListState},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{
+        action::HomeAction,
+        state::{HomeState, TimeItem},
+    },
+    persist::{TimeEntry, TimeEntryId},
+    widgets::{
+        list_popup::ListPopup,
+        text_popup::{TextInput, TextInputOutcome, TextPopup},
+    },
+};
+
+/// Entry as stored in the database that could not be loaded
+pub struct CorruptedEntry {
+    /// Id of the row, which might itself be the problem
+    pub raw_id: String,
+    pub entry: TimeEntry,
+    pub error: String,
+}
+
+/// Lists the entries of the day that could not be loaded, to fix or delete them
+pub struct RepairEntries {
+    corrupted: Vec<CorruptedEntry>,
+    list_state: ListState,
+    /// Start time being typed for the selected entry
+    start_input: Option<TextInput>,
+}
+
+impl RepairEntries {
+    pub fn new(corrupted: Vec<CorruptedEntry>) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select_first();
+        Self {
+            corrupted,
+            list_state,
+            start_input: None,
+        }
+    }
+
+    fn selected_mut(&mut self) -> Option<&mut CorruptedEntry> {
+        let idx = self.list_state.selected()?;
+        self.corrupted.get_mut(idx)
+    }
+
+    /// Stores the entry once it can be loaded, otherwise shows what is still wrong
+    fn repair_selected(&mut self) -> HomeAction {
+        let Some(corrupted) = self.selected_mut() else {
+            return HomeAction::None;
+        };
+        if let Err(err) = TimeItem::try_from(&corrupted.entry) {
+            corrupted.error = format!("{err:#}");
+            return HomeAction::SetStatusLine(format!("Still corrupted: {err:#}"));
+        }
+        let idx = self.list_state.selected().expect("selected above");
+        let repaired = self.corrupted.remove(idx);
+        self.close_if_done()
+            + HomeAction::RepairEntry {
+                raw_id: repaired.raw_id,
+                entry: Some(repaired.entry),
+            }
+    }
+
+    fn delete_selected(&mut self) -> HomeAction {
+        let Some(idx) = self
+            .list_state
+            .selected()
+            .filter(|it| *it < self.corrupted.len())
+        else {
+            return HomeAction::None;
+        };
+        let deleted = self.corrupted.remove(idx);
+        self.close_if_done()
+            + HomeAction::RepairEntry {
+                raw_id: deleted.raw_id,
+                entry: None,
+            }
+    }
+
+    fn close_if_done(&self) -> HomeAction {
+        if self.corrupted.is_empty() {
+            HomeAction::ClosePopup
+        } else {
+            HomeAction::None
+        }
+    }
+
+    fn handle_start_input(&mut self, key: KeyEvent) -> HomeAction {
+        let input = self.start_input.as_mut().expect("editing start");
+        match input.handle_key_event(key) {
+            TextInputOutcome::Editing => HomeAction::None,
+            TextInputOutcome::Submitted(start) => {
+                let Ok(parsed) = NaiveTime::from_str(&start) else {
+                    return HomeAction::SetStatusLine(format!("{start:?} is not HH:MM"));
+                };
+                self.start_input = None;
+                if let Some(corrupted) = self.selected_mut() {
+                    corrupted.entry.start_time = parsed.format("%H:%M").to_string();
+                }
+                self.repair_selected()
+            }
+            TextInputOutcome::Cancelled => {
+                self.start_input = None;
+                HomeAction::None
+            }
+        }
+    }
+}
+
+impl PopupBehavior for RepairEntries {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        if self.start_input.is_some() {
+            return self.handle_start_input(key);
+        }
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Enter | KeyCode::Char('s') => {
+                if let Some(corrupted) = self.selected_mut() {
+                    let start = corrupted.entry.start_time.clone();
+                    self.start_input = Some(TextInput::new(start));
+                }
+            }
+            KeyCode::Char('i') => {
+                if let Some(corrupted) = self.selected_mut() {
+                    corrupted.entry.id = TimeEntryId::new().to_string();
+                }
+                return self.repair_selected();
+            }
+            KeyCode::Char('d') | KeyCode::Delete => return self.delete_selected(),
+            KeyCode::Esc => return HomeAction::ClosePopup,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        if let Some(input) = &self.start_input {
+            let title = " 🩹 Start time of the entry, HH:MM ";
+            frame.render_widget(TextPopup::new(title, input), area);
+            return;
+        }
+        let items = self
+            .corrupted
+            .iter()
+            .map(|it| {
+                let entry = &it.entry;
+                ListItem::from(Line::from(vec![
+                    Span::from(format!("⛔ {} ", it.error)).red(),
+                    Span::from(format!("id={:?} ", entry.id)).dim(),
+                    Span::from(format!("start_time={:?} ", entry.start_time)),
+                    Span::from(format!("duration_mins={} ", entry.duration_mins)),
+                    Span::from(format!(
+                        "project={:?} ticket={:?} ",
+                        entry.project_key, entry.ticket_key
+                    )),
+                    Span::from(format!("description={:?}", entry.description)),
+                ]))
+            })
+            .collect_vec();
+        let title = format!(" 🩹 {} entries could not be loaded ", self.corrupted.len());
+        let popup = ListPopup::new(title, &mut self.list_state, items)
+            .footer(" <s> Fix start  <i> New id  <d> Delete  <Esc> Later ")
+            .width_percent(90);
+        frame.render_widget(popup, area);
+    }
+}
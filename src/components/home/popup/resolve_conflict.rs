@@ -0,0 +1,198 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Rect},
+    style::{Style, Stylize, palette::tailwind},
+    text::Line,
+    widgets::{Block, BorderType, Clear, Padding, Row, Table, TableState},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    persist::TimeEntry,
+    settings,
+    widgets::list_popup::centered_rect,
+};
+
+const LABELS: [&str; 10] = [
+    "Start",
+    "Duration",
+    "Project",
+    "Ticket",
+    "Description",
+    "Billable",
+    "Work type",
+    "Carry over",
+    "Exact",
+    "Flags",
+];
+
+/// Changes of an entry that another writer changed or deleted meanwhile, next to the stored
+/// values, to pick per field which ones to keep
+pub struct ResolveConflict {
+    mine: Box<TimeEntry>,
+    /// `None` if it was deleted
+    theirs: Option<Box<TimeEntry>>,
+    links: Vec<String>,
+    /// Field index, mine and their value, and whether theirs is kept, of the differing fields
+    fields: Vec<(usize, String, String, bool)>,
+    table: TableState,
+}
+
+impl ResolveConflict {
+    pub fn new(mine: TimeEntry, theirs: Option<TimeEntry>, links: Vec<String>) -> Self {
+        let mine_values = values(&mine);
+        let fields = match &theirs {
+            Some(theirs) => mine_values
+                .into_iter()
+                .zip(values(theirs))
+                .enumerate()
+                .filter(|(_, (mine, theirs))| mine != theirs)
+                .map(|(idx, (mine, theirs))| (idx, mine, theirs, false))
+                .collect(),
+            None => mine_values
+                .into_iter()
+                .enumerate()
+                .map(|(idx, mine)| (idx, mine, String::new(), false))
+                .collect(),
+        };
+        Self {
+            mine: Box::new(mine),
+            theirs: theirs.map(Box::new),
+            links,
+            fields,
+            table: TableState::new().with_selected(0),
+        }
+    }
+
+    /// My values, except for the fields where theirs are kept
+    fn merged(&self) -> TimeEntry {
+        let mut merged = *self.mine.clone();
+        if let Some(theirs) = &self.theirs {
+            for (idx, _, _, _) in self.fields.iter().filter(|it| it.3) {
+                take_field(*idx, &mut merged, theirs);
+            }
+        }
+        merged
+    }
+}
+
+fn values(entry: &TimeEntry) -> [String; 10] {
+    [
+        entry.start_time.clone(),
+        format!("{}m", entry.duration_mins),
+        entry.project_key.clone(),
+        entry.ticket_key.clone().unwrap_or_default(),
+        entry.description.clone(),
+        yes_no(entry.billable),
+        entry.work_type.clone().unwrap_or_default(),
+        yes_no(entry.carry_over),
+        yes_no(entry.exact),
+        settings::get().flag_symbols(entry.flags.split_whitespace()),
+    ]
+}
+
+fn take_field(idx: usize, into: &mut TimeEntry, from: &TimeEntry) {
+    match idx {
+        0 => into.start_time = from.start_time.clone(),
+        1 => into.duration_mins = from.duration_mins,
+        2 => into.project_key = from.project_key.clone(),
+        3 => into.ticket_key = from.ticket_key.clone(),
+        4 => into.description = from.description.clone(),
+        5 => into.billable = from.billable,
+        6 => into.work_type = from.work_type.clone(),
+        7 => into.carry_over = from.carry_over,
+        8 => into.exact = from.exact,
+        9 => into.flags = from.flags.clone(),
+        _ => {}
+    }
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_string()
+}
+
+impl PopupBehavior for ResolveConflict {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Up => self.table.select_previous(),
+            KeyCode::Down => self.table.select_next(),
+            KeyCode::Char(' ') if self.theirs.is_some() => {
+                if let Some(field) = self.table.selected().and_then(|it| self.fields.get_mut(it)) {
+                    field.3 = !field.3;
+                }
+            }
+            KeyCode::Enter => {
+                let revision = self.theirs.as_ref().map_or(0, |it| it.revision);
+                return HomeAction::ClosePopup
+                    + HomeAction::KeepConflicting {
+                        entry: self.merged(),
+                        revision,
+                    };
+            }
+            KeyCode::Char('t') => {
+                let action = match self.theirs.as_deref() {
+                    Some(entry) => HomeAction::DiscardChanges {
+                        entry: entry.clone(),
+                        links: self.links.clone(),
+                    },
+                    None => HomeAction::ForgetDeleted(self.mine.id.clone()),
+                };
+                return HomeAction::ClosePopup + action;
+            }
+            KeyCode::Esc => return HomeAction::ClosePopup,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let deleted = self.theirs.is_none();
+        let header = Row::new(["", "Mine", if deleted { "" } else { "Theirs" }]).bold();
+        let rows = self.fields.iter().map(|(idx, mine, theirs, take_theirs)| {
+            let (mine, theirs) = if *take_theirs {
+                (Line::from(mine.as_str()).dim(), Line::from(theirs.as_str()))
+            } else if deleted {
+                (Line::from(mine.as_str()), Line::from(""))
+            } else {
+                (Line::from(mine.as_str()), Line::from(theirs.as_str()).dim())
+            };
+            Row::new([Line::from(LABELS[*idx]), mine, theirs])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(12),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(header)
+        .column_spacing(2)
+        .row_highlight_style(Style::new().fg(tailwind::AMBER.c300));
+
+        // Borders and header
+        let height = self.fields.len() as u16 + 3;
+        let popup_area = centered_rect(area, 80, height);
+        frame.render_widget(Clear, popup_area);
+        let (title, keys) = if deleted {
+            (
+                format!(" ⚡ {} was deleted elsewhere ", self.mine.start_time),
+                " <Enter> Keep mine  <t> Delete  <Esc> Later ",
+            )
+        } else {
+            (
+                format!(" ⚡ {} was changed elsewhere ", self.mine.start_time),
+                " <Space> Mine/theirs  <Enter> Apply  <t> Take theirs  <Esc> Later ",
+            )
+        };
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .padding(Padding::horizontal(1))
+            .title(title)
+            .title_bottom(Line::from(keys).right_aligned())
+            .style(Style::new().bg(tailwind::INDIGO.c950));
+        frame.render_stateful_widget(table.block(block), popup_area, &mut self.table);
+    }
+}
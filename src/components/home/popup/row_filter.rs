@@ -0,0 +1,59 @@
+use crossterm::event::KeyEvent;
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Style, palette::tailwind::INDIGO},
+    text::Line,
+    widgets::{Block, BorderType, Clear, Padding, Paragraph},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    widgets::text_popup::{TextInput, TextInputOutcome},
+};
+
+/// Narrows the rows while typing, Enter keeps the filter and Esc drops it
+pub struct RowFilter {
+    input: TextInput,
+}
+
+impl RowFilter {
+    pub fn new(query: String) -> Self {
+        Self {
+            input: TextInput::new(query),
+        }
+    }
+}
+
+impl PopupBehavior for RowFilter {
+    fn handle_key_event(&mut self, state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match self.input.handle_key_event(key) {
+            TextInputOutcome::Editing => {
+                state.set_filter(self.input.text());
+                HomeAction::None
+            }
+            TextInputOutcome::Submitted(query) => {
+                state.set_filter(&query);
+                HomeAction::ClosePopup
+            }
+            TextInputOutcome::Cancelled => {
+                state.set_filter("");
+                HomeAction::ClosePopup + HomeAction::SetStatusLine("Filter cleared".into())
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let [_, area] = Layout::vertical([Constraint::Fill(1), Constraint::Length(3)]).areas(area);
+        frame.render_widget(Clear, area);
+        let block = Block::bordered()
+            .border_type(BorderType::Rounded)
+            .padding(Padding::horizontal(1))
+            .title(" 🔍 Filter ticket, project, description or flag ")
+            .title_bottom(Line::from(" <Enter> Keep  <Esc> Clear ").right_aligned())
+            .style(Style::new().bg(INDIGO.c950));
+        let text = format!("{}▏", self.input.text());
+        frame.render_widget(Paragraph::new(text).block(block), area);
+    }
+}
@@ -0,0 +1,40 @@
+use crossterm::event::KeyEvent;
+use ratatui::{Frame, layout::Rect};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    widgets::text_popup::{TextInput, TextInputOutcome, TextPopup},
+};
+
+/// Asks for the name to save the selected item as a template under
+pub struct SaveTemplate {
+    input: TextInput,
+}
+
+impl SaveTemplate {
+    pub fn new(suggested_name: String) -> Self {
+        Self {
+            input: TextInput::new(suggested_name),
+        }
+    }
+}
+
+impl PopupBehavior for SaveTemplate {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match self.input.handle_key_event(key) {
+            TextInputOutcome::Editing => HomeAction::None,
+            TextInputOutcome::Submitted(name) if name.trim().is_empty() => {
+                HomeAction::SetStatusLine("templates need a name".into())
+            }
+            TextInputOutcome::Submitted(name) => {
+                HomeAction::ClosePopup + HomeAction::SaveTemplate(name.trim().to_string())
+            }
+            TextInputOutcome::Cancelled => HomeAction::ClosePopup,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(TextPopup::new(" 💾 Save as template ", &self.input), area);
+    }
+}
@@ -0,0 +1,71 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    text::{Line, Span},
+    widgets::{ListItem, ListState},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    shared::scaffold::ScaffoldItem,
+    widgets::list_popup::ListPopup,
+};
+
+/// Offered when opening an empty day that has a scaffold for its weekday
+pub struct ScaffoldOffer {
+    weekday: String,
+    items: Vec<ScaffoldItem>,
+    list_state: ListState,
+}
+
+impl ScaffoldOffer {
+    pub fn new(weekday: String, items: Vec<ScaffoldItem>) -> Self {
+        Self {
+            weekday,
+            items,
+            list_state: ListState::default(),
+        }
+    }
+}
+
+impl PopupBehavior for ScaffoldOffer {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Enter | KeyCode::Char('a') => {
+                return HomeAction::ClosePopup + HomeAction::ApplyScaffold(self.items.clone());
+            }
+            KeyCode::Esc => return HomeAction::ClosePopup,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self
+            .items
+            .iter()
+            .map(|it| {
+                let label = [it.ticket_key.as_deref(), Some(it.description.as_str())]
+                    .into_iter()
+                    .flatten()
+                    .filter(|it| !it.is_empty())
+                    .join(" ");
+                ListItem::from(Line::from(vec![
+                    Span::from(format!("{} ", it.start.format("%H:%M"))).dim(),
+                    Span::from(label),
+                    Span::from(format!("  {}m", it.duration_mins)).dim(),
+                ]))
+            })
+            .collect_vec();
+        let title = format!(" 🧱 Start this {} with ", self.weekday);
+        let popup = ListPopup::new(title, &mut self.list_state, items)
+            .footer(" <Enter> Add these entries  <Esc> Start empty ");
+        frame.render_widget(popup, area);
+    }
+}
@@ -0,0 +1,55 @@
+use crossterm::event::KeyEvent;
+use ratatui::{Frame, layout::Rect};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    shared::time_expr,
+    widgets::text_popup::{TextInput, TextInputOutcome, TextPopup},
+};
+
+/// Asks how far to move the items from the index on, e.g. after a delayed start
+pub struct ShiftTimes {
+    from_idx: usize,
+    title: String,
+    input: TextInput,
+}
+
+impl ShiftTimes {
+    pub fn new(from_idx: usize, from_time: &str) -> Self {
+        let title = if from_idx == 0 {
+            " ⏩ Shift the whole day by, e.g. +30 or -1h ".to_string()
+        } else {
+            format!(" ⏩ Shift from {from_time} on by, e.g. +30 or -1h ")
+        };
+        Self {
+            from_idx,
+            title,
+            input: TextInput::default(),
+        }
+    }
+}
+
+impl PopupBehavior for ShiftTimes {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match self.input.handle_key_event(key) {
+            TextInputOutcome::Editing => HomeAction::None,
+            TextInputOutcome::Submitted(offset) => match time_expr::evaluate_offset_mins(&offset) {
+                Ok(0) => HomeAction::ClosePopup,
+                Ok(mins) => {
+                    HomeAction::ClosePopup
+                        + HomeAction::ShiftItems {
+                            from_idx: self.from_idx,
+                            mins,
+                        }
+                }
+                Err(err) => HomeAction::SetStatusLine(format!("Invalid: {err}")),
+            },
+            TextInputOutcome::Cancelled => HomeAction::ClosePopup,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(TextPopup::new(self.title.as_str(), &self.input), area);
+    }
+}
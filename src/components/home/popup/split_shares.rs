@@ -0,0 +1,56 @@
+use crossterm::event::KeyEvent;
+use ratatui::{Frame, layout::Rect};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    shared::split,
+    widgets::text_popup::{TextInput, TextInputOutcome, TextPopup},
+};
+
+/// Asks for the tickets and percentages to split the item across
+pub struct SplitShares {
+    idx: usize,
+    title: String,
+    input: TextInput,
+}
+
+impl SplitShares {
+    pub fn new(idx: usize, duration_mins: u64, ticket: &str) -> Self {
+        let initial = if ticket.is_empty() {
+            String::new()
+        } else {
+            format!("50% {ticket}, ")
+        };
+        Self {
+            idx,
+            title: format!(
+                " ✂️ Split {duration_mins}m by %, e.g. 50% SCRUM-1, 30% SCRUM-2, 20% PLAT-9 "
+            ),
+            input: TextInput::new(initial),
+        }
+    }
+}
+
+impl PopupBehavior for SplitShares {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match self.input.handle_key_event(key) {
+            TextInputOutcome::Editing => HomeAction::None,
+            TextInputOutcome::Submitted(input) => match split::parse_shares(&input) {
+                Ok(shares) => {
+                    HomeAction::ClosePopup
+                        + HomeAction::SplitByShares {
+                            idx: self.idx,
+                            shares,
+                        }
+                }
+                Err(err) => HomeAction::SetStatusLine(format!("Invalid: {err}")),
+            },
+            TextInputOutcome::Cancelled => HomeAction::ClosePopup,
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(TextPopup::new(self.title.as_str(), &self.input), area);
+    }
+}
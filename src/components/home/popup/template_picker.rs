@@ -0,0 +1,98 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    text::Line,
+    widgets::{ListItem, ListState},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    persist::EntryTemplate,
+    widgets::list_popup::ListPopup,
+};
+
+/// Saved templates to insert into the day at the next free time slot
+pub struct TemplatePicker {
+    templates: Vec<EntryTemplate>,
+    list_state: ListState,
+}
+
+impl TemplatePicker {
+    pub fn new(templates: Vec<EntryTemplate>) -> Self {
+        let mut list_state = ListState::default();
+        list_state.select_first();
+        Self {
+            templates,
+            list_state,
+        }
+    }
+
+    fn selected(&self) -> Option<&EntryTemplate> {
+        self.list_state
+            .selected()
+            .and_then(|idx| self.templates.get(idx))
+    }
+}
+
+impl PopupBehavior for TemplatePicker {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Enter => {
+                let Some(template) = self.selected() else {
+                    return HomeAction::ClosePopup;
+                };
+                return HomeAction::ClosePopup + HomeAction::InsertTemplate(template.clone());
+            }
+            KeyCode::Char('d') => {
+                let Some(idx) = self
+                    .list_state
+                    .selected()
+                    .filter(|it| *it < self.templates.len())
+                else {
+                    return HomeAction::None;
+                };
+                let template = self.templates.remove(idx);
+                return HomeAction::DeleteTemplate(template.name);
+            }
+            KeyCode::Esc | KeyCode::Char('t') => return HomeAction::ClosePopup,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let items = self
+            .templates
+            .iter()
+            .map(|it| {
+                let details = [
+                    Some(it.project_key.as_str()),
+                    it.ticket_key.as_deref(),
+                    Some(it.description.as_str()).filter(|it| !it.is_empty()),
+                ]
+                .into_iter()
+                .flatten()
+                .join(" · ");
+                let billable = if it.billable { " $" } else { "" };
+                ListItem::from(Line::from(format!(
+                    "{} → {details} · {}m{billable}",
+                    it.name, it.duration_mins
+                )))
+            })
+            .collect_vec();
+        let title = if self.templates.is_empty() {
+            " 📋 No templates left ".to_string()
+        } else {
+            format!(" 📋 Templates ({}) ", self.templates.len())
+        };
+        let popup = ListPopup::new(title, &mut self.list_state, items)
+            .footer(" <Enter> Insert  <d> Delete  <Esc> Close ")
+            .width_percent(70);
+        frame.render_widget(popup, area);
+    }
+}
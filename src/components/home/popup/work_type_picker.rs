@@ -0,0 +1,64 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    text::Line,
+    widgets::{ListItem, ListState},
+};
+
+use super::PopupBehavior;
+use crate::{
+    components::home::{action::HomeAction, state::HomeState},
+    settings,
+    widgets::list_popup::ListPopup,
+};
+
+/// Configured work types to pick for the selected entry, the last item clears it
+pub struct WorkTypePicker {
+    keys: Vec<&'static str>,
+    list_state: ListState,
+}
+
+impl WorkTypePicker {
+    pub fn new(current: Option<&str>) -> Self {
+        let keys = settings::get().work_type_keys();
+        let current_idx = current.and_then(|current| keys.iter().position(|it| *it == current));
+        let mut list_state = ListState::default();
+        list_state.select(Some(current_idx.unwrap_or_default()));
+        Self { keys, list_state }
+    }
+}
+
+impl PopupBehavior for WorkTypePicker {
+    fn handle_key_event(&mut self, _state: &mut HomeState, key: KeyEvent) -> HomeAction {
+        match key.code {
+            KeyCode::Down => self.list_state.select_next(),
+            KeyCode::Up => self.list_state.select_previous(),
+            KeyCode::Enter => {
+                let picked = self
+                    .list_state
+                    .selected()
+                    .and_then(|idx| self.keys.get(idx))
+                    .map(|it| it.to_string());
+                return HomeAction::ClosePopup + HomeAction::SetWorkType(picked);
+            }
+            KeyCode::Esc | KeyCode::Char('y') => return HomeAction::ClosePopup,
+            _ => {}
+        }
+        HomeAction::None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) {
+        let config = settings::get();
+        let items = self
+            .keys
+            .iter()
+            .map(|key| ListItem::from(Line::from(config.work_type_label(key))))
+            .chain([ListItem::from(Line::from("None"))])
+            .collect_vec();
+        let popup = ListPopup::new(" 🏷️ Work type ", &mut self.list_state, items)
+            .footer(" <Enter> Pick  <Esc> Cancel ");
+        frame.render_widget(popup, area);
+    }
+}
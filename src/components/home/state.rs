@@ -1,20 +1,20 @@
+use std::collections::HashMap;
 use std::time::Duration;
 use std::{ops::Range, str::FromStr};
 
-use chrono::NaiveTime;
-use color_eyre::eyre::Context;
+use chrono::{NaiveTime, TimeDelta, Timelike};
+use color_eyre::eyre::{Context, Result, bail};
 use educe::Educe;
 use humantime::format_duration;
-use ratatui::style::Style;
 use ratatui::style::palette::tailwind;
-use ratatui::{
-    text::Text,
-    widgets::{Row, TableState},
-};
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::{text::Text, widgets::TableState};
+use time::Date;
 
-use crate::config::Config;
 use crate::persist::{self, TimeEntryId, Timesheet};
-use crate::shared::DataVersion;
+use crate::settings;
+use crate::shared::{BREAK_PROJECT_KEY, DataVersion, tz::DayZone};
 
 #[derive(Debug)]
 pub struct TimeItem {
@@ -24,7 +24,20 @@ pub struct TimeItem {
     pub ticket: String,
     pub description: String,
     pub duration: Duration,
+    pub billable: bool,
+    /// Key of the configured work type
+    pub work_type: Option<String>,
+    /// Unfinished, offered again when starting the next day
+    pub carry_over: bool,
+    /// Kept to the minute, even if exports or bookings round
+    pub exact: bool,
+    /// Keys of the configured flags, in the order they were set
+    pub flags: Vec<String>,
+    /// URLs attached to the entry, stored separately with [persist::Command::StoreLinks]
+    pub links: Vec<String>,
     pub version: DataVersion,
+    /// Stored revision that changes are based on, see [persist::Event::EntryConflict]
+    pub revision: i32,
 }
 
 impl TimeItem {
@@ -36,7 +49,30 @@ impl TimeItem {
             ticket: Default::default(),
             project: Default::default(),
             description: Default::default(),
+            billable: false,
+            work_type: None,
+            carry_over: false,
+            exact: false,
+            flags: vec![],
+            links: vec![],
             version: DataVersion::fresh(),
+            revision: 0,
+        }
+    }
+
+    /// New item continuing this one at `start_time`, with everything but its id and times
+    pub fn continuation(&self, duration: Duration, start_time: NaiveTime) -> Self {
+        Self {
+            project: self.project.clone(),
+            ticket: self.ticket.clone(),
+            description: self.description.clone(),
+            billable: self.billable,
+            work_type: self.work_type.clone(),
+            carry_over: self.carry_over,
+            exact: self.exact,
+            flags: self.flags.clone(),
+            links: self.links.clone(),
+            ..Self::new(duration, start_time)
         }
     }
 
@@ -48,14 +84,21 @@ impl TimeItem {
             project: "".into(),
             description: "Loading...".into(),
             duration: Default::default(),
+            billable: false,
+            work_type: None,
+            carry_over: false,
+            exact: false,
+            flags: vec![],
+            links: vec![],
             version: DataVersion::fresh(),
+            revision: 0,
         }
     }
 
     pub fn to_persist(&self, day: &str) -> persist::TimeEntry {
         let duration_mins = self.duration.as_secs().div_ceil(60) as i32;
         let project_key = if self.project.is_empty() {
-            Config::get().default_project_key.clone()
+            settings::get().default_project_key.clone()
         } else {
             self.project.clone()
         };
@@ -67,6 +110,12 @@ impl TimeItem {
             project_key,
             description: self.description.to_string(),
             start_time: self.start_time.format("%H:%M").to_string(),
+            billable: self.billable,
+            work_type: self.work_type.clone(),
+            carry_over: self.carry_over,
+            exact: self.exact,
+            flags: self.flags.join(" "),
+            revision: self.revision,
         }
     }
 }
@@ -75,7 +124,7 @@ impl TryFrom<&persist::TimeEntry> for TimeItem {
     type Error = color_eyre::Report;
 
     fn try_from(value: &persist::TimeEntry) -> Result<Self, Self::Error> {
-        let project = if value.project_key == Config::get().default_project_key {
+        let project = if value.project_key == settings::get().default_project_key {
             "".to_owned()
         } else {
             value.project_key.clone()
@@ -87,44 +136,107 @@ impl TryFrom<&persist::TimeEntry> for TimeItem {
             project,
             description: value.description.to_string(),
             duration: Duration::from_secs(value.duration_mins as u64 * 60),
+            billable: value.billable,
+            work_type: value.work_type.clone(),
+            carry_over: value.carry_over,
+            exact: value.exact,
+            flags: value.flags.split_whitespace().map(str::to_string).collect(),
+            links: vec![],
             version: DataVersion::loaded(),
+            revision: value.revision,
         })
     }
 }
 
-pub const TIME_ITEM_WIDTH: usize = 5;
+pub const TIME_ITEM_WIDTH: usize = 6;
+/// Column with the end time, only shown if [HomeState::show_end] is set
+pub const END_COLUMN: usize = 1;
 
 impl TimeItem {
-    pub fn as_row<'a>(&'a self, mark_as_mismatch: bool) -> Row<'a> {
-        Row::new(self.as_cells(mark_as_mismatch))
-    }
-
     /// Needed because ratatui's Row doesn't expose its contents
     pub fn as_cells<'a>(&'a self, mark_as_mismatch: bool) -> [Text<'a>; TIME_ITEM_WIDTH] {
-        let formatted_duration = if self.duration.is_zero() {
-            "".to_string()
-        } else {
-            format!("{}", format_duration(self.duration))
+        let formatted_duration = match (self.duration.is_zero(), self.billable) {
+            (true, _) => "".to_string(),
+            (false, true) => format!("{} $", format_duration(self.duration)),
+            (false, false) => format!("{}", format_duration(self.duration)),
         };
         let duration_style = if mark_as_mismatch {
             Style::default().bg(tailwind::ROSE.c500)
         } else {
             Style::default()
         };
+        let formatted_end = if self.duration.is_zero() {
+            "".to_string()
+        } else {
+            self.next_start_time().format("%H:%M").to_string()
+        };
         [
             Text::from(self.start_time.format("%H:%M").to_string()),
+            Text::from(formatted_end),
             Text::from(&self.project as &str),
             Text::from(&self.ticket as &str),
-            Text::from(&self.description as &str),
+            self.description_cell(),
             Text::from(formatted_duration).style(duration_style),
         ]
     }
 
+    /// Description followed by the label of the work type and markers for carrying over, being
+    /// exact, links and failed saves, if any
+    fn description_cell(&self) -> Text<'_> {
+        let mut spans = vec![Span::from(&self.description as &str)];
+        if let Some(work_type) = &self.work_type {
+            let label = settings::get().work_type_label(work_type);
+            spans.push(Span::from(format!(" · {label}")).dim());
+        }
+        if self.carry_over {
+            spans.push(Span::from(" ↪"));
+        }
+        if self.exact {
+            spans.push(Span::from(" ="));
+        }
+        match self.links.len() {
+            0 => {}
+            1 => spans.push(Span::from(" 🔗")),
+            n => spans.push(Span::from(format!(" 🔗{n}"))),
+        }
+        if self.version.has_failed() {
+            spans.push(Span::from(" ⚡ not saved").red().bold());
+        }
+        Text::from(Line::from(spans))
+    }
+
     pub fn next_start_time(&self) -> NaiveTime {
         self.start_time + self.duration
     }
 }
 
+/// Which value of the items [HomeState::repaired_chain] recomputes, so that each one ends
+/// where the next one starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainRepair {
+    /// From the first start on, each item starts when the previous one ends
+    Starts,
+    /// Each item lasts until the next one starts, the last one keeps its duration
+    Durations,
+}
+
+impl ChainRepair {
+    pub fn other(self) -> Self {
+        match self {
+            ChainRepair::Starts => ChainRepair::Durations,
+            ChainRepair::Durations => ChainRepair::Starts,
+        }
+    }
+}
+
+/// What [HomeState::cut_out] did to the item running at its start
+pub enum Cut {
+    Nothing,
+    Shortened,
+    /// Shortened, the part after the cut is this item, still to be inserted
+    Split(Box<TimeItem>),
+}
+
 #[derive(Educe)]
 #[educe(Default)]
 pub struct HomeState {
@@ -133,9 +245,151 @@ pub struct HomeState {
     #[educe(Default(expression = vec![TimeItem::loading()]))]
     pub items: Vec<TimeItem>,
     pub items_to_delete: Vec<TimeItem>,
+    /// Project and ticket of items turned into breaks, to restore when toggled back
+    pub before_break: HashMap<TimeEntryId, (String, String)>,
+    /// Item currently worked on, its duration follows the clock
+    pub pinned: Option<TimeEntryId>,
+    /// Only rows containing this are shown, see [HomeState::is_visible]
+    pub filter: String,
+    /// Scroll position among the visible rows while filtered, [HomeState::table] keeps
+    /// selecting by item index
+    pub filtered_table: TableState,
+    /// Whether the end column is drawn, it's skipped when moving between columns otherwise
+    pub show_end: bool,
 }
 
 impl HomeState {
+    /// Turns the item into a break, or back into what it was before
+    pub fn toggle_break(&mut self, idx: usize) {
+        let Some(item) = self.items.get_mut(idx) else {
+            return;
+        };
+        if item.project == BREAK_PROJECT_KEY {
+            let (project, ticket) = self.before_break.remove(&item.id).unwrap_or_default();
+            item.project = project;
+            item.ticket = ticket;
+        } else {
+            let project = std::mem::replace(&mut item.project, BREAK_PROJECT_KEY.into());
+            let ticket = std::mem::take(&mut item.ticket);
+            self.before_break.insert(item.id.clone(), (project, ticket));
+        }
+        item.version.touch();
+    }
+
+    /// Extends the pinned item up to `now`, never shortening it. Returns whether it changed.
+    pub fn extend_pinned(&mut self, now: NaiveTime) -> bool {
+        let Some(item) = self.pinned_item_mut() else {
+            return false;
+        };
+        let elapsed_mins = (now - item.start_time).num_minutes().max(0) as u64;
+        let duration = Duration::from_secs(elapsed_mins * 60);
+        if duration <= item.duration {
+            return false;
+        }
+        item.duration = duration;
+        item.version.touch();
+        true
+    }
+
+    /// Makes room from `start` until `end`, e.g. for the time the user was idle: the item
+    /// running at `start` ends there and the part of it after `end` continues as a new item,
+    /// which takes over the pin
+    pub fn cut_out(&mut self, start: NaiveTime, end: NaiveTime) -> Cut {
+        let Some(idx) = self
+            .items
+            .iter()
+            .position(|it| it.start_time <= start && start < it.next_start_time())
+        else {
+            return Cut::Nothing;
+        };
+        let item = &mut self.items[idx];
+        let item_end = item.next_start_time();
+        item.duration = (start - item.start_time).to_std().unwrap_or_default();
+        item.version.touch();
+        if item_end <= end {
+            if self.pinned.as_ref() == Some(&item.id) {
+                self.pinned = None;
+            }
+            return Cut::Shortened;
+        }
+        let rest = (item_end - end).to_std().unwrap_or_default();
+        let rest = item.continuation(rest, end);
+        if self.pinned.as_ref() == Some(&item.id) {
+            self.pinned = Some(rest.id.clone());
+        }
+        Cut::Split(Box::new(rest))
+    }
+
+    /// Narrows the rows to the query and moves the selection onto a visible row
+    pub fn set_filter(&mut self, query: &str) {
+        self.filter = query.trim().to_lowercase();
+        if let Some(idx) = self.table.selected()
+            && !self.matches_filter(idx)
+        {
+            let visible = self.visible_idxs();
+            let next_visible = visible.iter().find(|it| **it > idx).or(visible.last());
+            self.table.select(next_visible.copied().or(Some(idx)));
+        }
+    }
+
+    pub fn is_filtered(&self) -> bool {
+        !self.filter.is_empty()
+    }
+
+    /// Whether the ticket, project, description or the label or symbol of a flag contain the
+    /// filter, ignoring case. The selected row stays visible, so that editing it away from the
+    /// filter works.
+    pub fn is_visible(&self, idx: usize) -> bool {
+        self.table.selected() == Some(idx) || self.matches_filter(idx)
+    }
+
+    fn matches_filter(&self, idx: usize) -> bool {
+        let Some(item) = self.items.get(idx) else {
+            return false;
+        };
+        let config = settings::get();
+        let flags = item
+            .flags
+            .iter()
+            .filter_map(|it| config.flags.get(it))
+            .flat_map(|it| [&it.label, &it.symbol]);
+        !self.is_filtered()
+            || [&item.ticket, &item.project, &item.description]
+                .into_iter()
+                .chain(flags)
+                .any(|it| it.to_lowercase().contains(&self.filter))
+    }
+
+    pub fn visible_idxs(&self) -> Vec<usize> {
+        (0..self.items.len())
+            .filter(|idx| self.is_visible(*idx))
+            .collect()
+    }
+
+    /// Selects the closest visible row before or after the selected one, or the first one.
+    /// Returns whether there was one.
+    pub fn select_visible(&mut self, forward: bool) -> bool {
+        let current = self.table.selected();
+        let visible = self.visible_idxs();
+        let target = match current {
+            None => visible.first(),
+            Some(idx) if forward => visible.iter().find(|it| **it > idx),
+            Some(idx) => visible.iter().rev().find(|it| **it < idx),
+        };
+        match target {
+            Some(idx) => {
+                self.table.select(Some(*idx));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn pinned_item_mut(&mut self) -> Option<&mut TimeItem> {
+        let id = self.pinned.as_ref()?;
+        self.items.iter_mut().find(|it| &it.id == id)
+    }
+
     pub fn expect_selected_item(&self) -> &TimeItem {
         self.maybe_selected_item().expect("an item to be selected")
     }
@@ -181,4 +435,149 @@ impl HomeState {
     pub fn drain_items(&mut self, range: Range<usize>) {
         self.items_to_delete.extend(self.items.drain(range));
     }
+
+    /// Free time between the end of this item and the start of the next one, if any
+    pub fn gap_after(&self, idx: usize) -> Option<Duration> {
+        let current = self.items.get(idx)?;
+        let next = self.items.get(idx + 1)?;
+        (next.start_time - current.next_start_time())
+            .to_std()
+            .ok()
+            .filter(|gap| !gap.is_zero())
+    }
+
+    /// Start of the first gap that fits the duration, otherwise the end of the last item
+    pub fn next_free_slot(&self, duration: Duration) -> NaiveTime {
+        let fitting_gap = (0..self.items.len())
+            .find(|idx| self.gap_after(*idx).is_some_and(|gap| gap >= duration));
+        if let Some(idx) = fitting_gap {
+            return self.items[idx].next_start_time();
+        }
+        self.items
+            .iter()
+            .filter(|it| !it.duration.is_zero())
+            .map(TimeItem::next_start_time)
+            .max()
+            .or_else(|| self.items.last().map(|it| it.start_time))
+            .unwrap_or(NaiveTime::MIN)
+    }
+
+    /// Moves this and all following items by the minutes, keeping their durations.
+    /// Refuses to move items onto the previous one or across midnight.
+    pub fn shift_from(&mut self, idx: usize, mins: i64) -> Result<()> {
+        let day_mins = 24 * 60;
+        for item in self.items.iter().skip(idx) {
+            let start = (item.start_time.num_seconds_from_midnight() / 60) as i64 + mins;
+            let end = start + (item.duration.as_secs() / 60) as i64;
+            if start < 0 || end > day_mins {
+                bail!(
+                    "{} would move across midnight",
+                    item.start_time.format("%H:%M")
+                );
+            }
+        }
+        let delta = TimeDelta::minutes(mins);
+        if let (Some(previous), Some(first)) = (
+            idx.checked_sub(1).and_then(|it| self.items.get(it)),
+            self.items.get(idx),
+        ) && first.start_time + delta < previous.next_start_time()
+        {
+            bail!(
+                "would overlap the entry at {}",
+                previous.start_time.format("%H:%M")
+            );
+        }
+        for item in self.items.iter_mut().skip(idx) {
+            item.start_time += delta;
+            item.version.touch();
+        }
+        Ok(())
+    }
+
+    /// Start and duration of each item once the gaps and overlaps between them are resolved.
+    /// Refuses to move items across midnight or to run them backwards.
+    pub fn repaired_chain(
+        &self,
+        repair: ChainRepair,
+        day: Date,
+        zone: DayZone,
+    ) -> Result<Vec<(NaiveTime, Duration)>> {
+        let mut repaired = Vec::with_capacity(self.items.len());
+        match repair {
+            ChainRepair::Starts => {
+                let Some(first) = self.items.first() else {
+                    return Ok(repaired);
+                };
+                let mut start = first.start_time;
+                let mut elapsed_mins = (start.num_seconds_from_midnight() / 60) as u64;
+                for item in &self.items {
+                    let mins = item.duration.as_secs() / 60;
+                    elapsed_mins += mins;
+                    if elapsed_mins > 24 * 60 {
+                        bail!("{} would end after midnight", start.format("%H:%M"));
+                    }
+                    repaired.push((start, item.duration));
+                    start = zone.wall_clock_after(day, start, mins as i64);
+                }
+            }
+            ChainRepair::Durations => {
+                for (idx, item) in self.items.iter().enumerate() {
+                    let Some(next) = self.items.get(idx + 1) else {
+                        repaired.push((item.start_time, item.duration));
+                        break;
+                    };
+                    let mins = zone.minutes_between(day, item.start_time, next.start_time);
+                    if mins < 0 {
+                        bail!(
+                            "{} starts before {}",
+                            next.start_time.format("%H:%M"),
+                            item.start_time.format("%H:%M")
+                        );
+                    }
+                    repaired.push((item.start_time, Duration::from_secs(mins as u64 * 60)));
+                }
+            }
+        }
+        Ok(repaired)
+    }
+
+    /// Applies the result of [HomeState::repaired_chain], returning how many items changed
+    pub fn apply_chain(&mut self, repaired: Vec<(NaiveTime, Duration)>) -> usize {
+        let mut changed = 0;
+        for (item, (start_time, duration)) in self.items.iter_mut().zip(repaired) {
+            if item.start_time != start_time || item.duration != duration {
+                item.start_time = start_time;
+                item.duration = duration;
+                item.version.touch();
+                changed += 1;
+            }
+        }
+        changed
+    }
+
+    /// Inserts before the first item starting later and returns the new index.
+    /// A trailing item without duration stays last, since that is where new entries are typed.
+    pub fn insert_chronologically(&mut self, item: TimeItem) -> usize {
+        let trailing_blank = match self.items.last() {
+            Some(last) if last.duration.is_zero() => self.items.pop(),
+            _ => None,
+        };
+
+        let insert_idx = self
+            .items
+            .iter()
+            .position(|it| it.start_time > item.start_time)
+            .unwrap_or(self.items.len());
+        self.items.insert(insert_idx, item);
+
+        if let Some(mut blank) = trailing_blank {
+            let last_end = self.items.last().expect("just inserted").next_start_time();
+            if blank.start_time < last_end {
+                blank.start_time = last_end;
+                blank.version.touch();
+            }
+            self.items.push(blank);
+        }
+        insert_idx
+    }
 }
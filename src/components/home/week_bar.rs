@@ -0,0 +1,82 @@
+use std::{collections::HashMap, ops::RangeInclusive};
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Style, Stylize, palette::tailwind},
+    text::{Line, Span},
+};
+use time::{Date, Duration, format_description::FormatItem, macros::format_description};
+
+use crate::{
+    persist::TimeEntry,
+    shared::{
+        query::{self, EntryFilter, GroupBy},
+        week_of,
+    },
+};
+
+/// Hours worked on each day of the week around the shown day
+pub struct WeekBar {
+    pub range: RangeInclusive<Date>,
+    /// Empty until loaded
+    worked: HashMap<Date, Duration>,
+}
+
+impl WeekBar {
+    pub fn new(day: Date) -> Self {
+        Self {
+            range: week_of(day),
+            worked: HashMap::new(),
+        }
+    }
+
+    pub fn handle_loaded(&mut self, entries: &[TimeEntry]) {
+        self.worked.clear();
+        let entries = EntryFilter::default().apply(entries.to_vec());
+        for aggregate in query::aggregate(&entries, GroupBy::Day) {
+            let Ok(day) = Date::parse(&aggregate.key, ISO_DAY) else {
+                continue;
+            };
+            self.worked
+                .insert(day, Duration::minutes(aggregate.duration_mins));
+        }
+    }
+
+    /// Keeps the bar current for days edited since loading it
+    pub fn set_worked(&mut self, day: Date, worked: Duration) {
+        if self.range.contains(&day) {
+            self.worked.insert(day, worked);
+        }
+    }
+
+    pub fn draw(&self, frame: &mut Frame, area: Rect, current_day: Date) {
+        let mut spans = vec![];
+        let mut total = Duration::ZERO;
+        let mut day = *self.range.start();
+        while self.range.contains(&day) {
+            if !spans.is_empty() {
+                spans.push(Span::from(" | ").dark_gray());
+            }
+            let worked = self.worked.get(&day).copied().unwrap_or_default();
+            total += worked;
+            let hours = if worked.is_zero() {
+                "–".to_string()
+            } else {
+                format!("{:.1}", worked.as_seconds_f32() / 3600.0)
+            };
+            let weekday = &day.weekday().to_string()[..2];
+            let style = if day == current_day {
+                Style::new().bold().fg(tailwind::INDIGO.c300)
+            } else {
+                Style::new()
+            };
+            spans.push(Span::styled(format!("{weekday} {hours}"), style));
+            day = day.next_day().expect("week not to end at the end of time");
+        }
+        spans.push(Span::from(format!("  Σ {:.1}h", total.as_seconds_f32() / 3600.0)).italic());
+        frame.render_widget(Line::from(spans), area);
+    }
+}
+
+const ISO_DAY: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
@@ -0,0 +1,293 @@
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::PathBuf,
+};
+
+use color_eyre::{Result, eyre::Context};
+use crossterm::event::{KeyCode, KeyEvent};
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Modifier, Style, Stylize, palette::tailwind},
+    text::Line,
+    widgets::{Block, BorderType, Borders, List, ListItem, ListState},
+};
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::Level;
+
+use super::Component;
+use crate::{
+    action::{Action, Page, RelevantKey},
+    layout::{self, LayoutSlot},
+    logging,
+    shared::log_lines::{self, LogLine},
+    widgets::text_popup::{TextInput, TextInputOutcome, TextPopup},
+};
+
+/// Lines kept in memory, older ones are dropped
+const MAX_LINES: usize = 10_000;
+
+/// Log of the current session, opened with F11 from any page. Follows new lines while
+/// scrolled to the end.
+pub struct LogViewer {
+    action_tx: Option<UnboundedSender<Action>>,
+    active: bool,
+    /// Page to go back to
+    previous: Page,
+    path: PathBuf,
+    /// Bytes of the file that were read
+    read_to: u64,
+    /// Start of a line that is still being written
+    partial: String,
+    lines: Vec<LogLine>,
+    max_level: Level,
+    query: String,
+    search_input: Option<TextInput>,
+    list_state: ListState,
+    follow: bool,
+}
+
+impl Default for LogViewer {
+    fn default() -> Self {
+        Self {
+            action_tx: None,
+            active: false,
+            previous: Page::default(),
+            path: logging::log_path(),
+            read_to: 0,
+            partial: String::new(),
+            lines: vec![],
+            max_level: Level::INFO,
+            query: String::new(),
+            search_input: None,
+            list_state: ListState::default(),
+            follow: true,
+        }
+    }
+}
+
+impl LogViewer {
+    fn send_action(&mut self, action: Action) {
+        if let Some(tx) = &self.action_tx {
+            tx.send(action).expect("action channel open");
+        }
+    }
+
+    /// Reads what was appended to the log since the last call
+    fn read_new_lines(&mut self) -> Result<()> {
+        let mut file = File::open(&self.path)
+            .wrap_err_with(|| format!("opening log {}", self.path.display()))?;
+        let len = file.metadata().wrap_err("reading log size")?.len();
+        if len < self.read_to {
+            // Written again from the start, e.g. by another session
+            self.read_to = 0;
+            self.partial.clear();
+            self.lines.clear();
+        }
+        file.seek(SeekFrom::Start(self.read_to))
+            .wrap_err("seeking in log")?;
+        let mut appended = vec![];
+        file.read_to_end(&mut appended).wrap_err("reading log")?;
+        self.read_to += appended.len() as u64;
+
+        self.partial.push_str(&String::from_utf8_lossy(&appended));
+        let Some(last_newline) = self.partial.rfind('\n') else {
+            return Ok(());
+        };
+        let complete: String = self.partial.drain(..=last_newline).collect();
+        let previous = self.lines.last().map_or(Level::INFO, |it| it.level);
+        self.lines
+            .extend(log_lines::parse_lines(&complete, previous));
+        if self.lines.len() > MAX_LINES {
+            self.lines.drain(..self.lines.len() - MAX_LINES);
+        }
+        Ok(())
+    }
+
+    fn visible_lines(&self) -> Vec<&LogLine> {
+        self.lines
+            .iter()
+            .filter(|it| it.is_shown(self.max_level, &self.query))
+            .collect()
+    }
+
+    fn set_max_level(&mut self, level: Level) -> Option<Action> {
+        self.max_level = level;
+        self.follow = true;
+        Some(Action::SetStatusLine(format!(
+            "📜 Showing {level} and more severe"
+        )))
+    }
+
+    fn handle_search_key(&mut self, key: KeyEvent) -> Option<Action> {
+        let input = self.search_input.as_mut().expect("searching");
+        match input.handle_key_event(key) {
+            TextInputOutcome::Editing => return None,
+            TextInputOutcome::Submitted(query) => {
+                self.query = query;
+                self.follow = true;
+            }
+            TextInputOutcome::Cancelled => {}
+        }
+        self.search_input = None;
+        None
+    }
+
+    fn scroll(&mut self, delta: isize) {
+        let visible = self.visible_lines().len();
+        let current = self
+            .list_state
+            .selected()
+            .unwrap_or(visible.saturating_sub(1));
+        let next = current
+            .saturating_add_signed(delta)
+            .min(visible.saturating_sub(1));
+        self.list_state.select(Some(next));
+        self.follow = next + 1 >= visible;
+    }
+}
+
+impl Component for LogViewer {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.action_tx = Some(tx);
+        Ok(())
+    }
+
+    fn handle_key_event(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if !self.active {
+            return Ok(match key.code {
+                KeyCode::F(11) => Some(Action::SetActivePage(Page::Logs)),
+                _ => None,
+            });
+        }
+        if self.search_input.is_some() {
+            return Ok(self.handle_search_key(key));
+        }
+        Ok(match key.code {
+            KeyCode::F(11) | KeyCode::Esc => Some(Action::SetActivePage(self.previous)),
+            KeyCode::Char('e') => self.set_max_level(Level::ERROR),
+            KeyCode::Char('w') => self.set_max_level(Level::WARN),
+            KeyCode::Char('i') => self.set_max_level(Level::INFO),
+            KeyCode::Char('d') => self.set_max_level(Level::DEBUG),
+            KeyCode::Char('t') => self.set_max_level(Level::TRACE),
+            KeyCode::Char('/') => {
+                self.search_input = Some(TextInput::new(self.query.clone()));
+                None
+            }
+            KeyCode::Up => {
+                self.scroll(-1);
+                None
+            }
+            KeyCode::Down => {
+                self.scroll(1);
+                None
+            }
+            KeyCode::PageUp => {
+                self.scroll(-20);
+                None
+            }
+            KeyCode::PageDown => {
+                self.scroll(20);
+                None
+            }
+            KeyCode::Home => {
+                self.list_state.select_first();
+                self.follow = false;
+                None
+            }
+            KeyCode::End => {
+                self.follow = true;
+                None
+            }
+            _ => None,
+        })
+    }
+
+    fn update(&mut self, action: Action) -> Result<Option<Action>> {
+        match action {
+            Action::SetActivePage(Page::Logs) => {
+                self.active = true;
+                self.follow = true;
+                self.send_action(Action::SetRelevantKeys(KEYS.to_vec()));
+                if let Err(err) = self.read_new_lines() {
+                    return Ok(Some(Action::SetStatusLine(format!("⛔ {err}"))));
+                }
+            }
+            Action::SetActivePage(page) => {
+                self.active = false;
+                self.previous = page;
+            }
+            Action::Tick if self.active => {
+                if let Err(err) = self.read_new_lines() {
+                    tracing::debug!("Failed to tail the log: {err:?}");
+                }
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    fn draw(&mut self, frame: &mut Frame, area: Rect) -> Result<()> {
+        if !self.active {
+            return Ok(());
+        }
+        let area = layout::main_vert(LayoutSlot::MainCanvas, area);
+        let items = self
+            .visible_lines()
+            .into_iter()
+            .map(|it| ListItem::from(Line::from(it.text.clone()).style(level_style(it.level))))
+            .collect_vec();
+        let mut title = format!(
+            "📜 {} - {} and more severe",
+            self.path.display(),
+            self.max_level
+        );
+        if !self.query.is_empty() {
+            title.push_str(&format!(
+                " - 🔍 \"{}\" ({} of {})",
+                self.query,
+                items.len(),
+                self.lines.len()
+            ));
+        }
+        let follow_to = items.len().checked_sub(1);
+        let block = Block::new()
+            .borders(!Borders::BOTTOM)
+            .border_type(BorderType::Rounded)
+            .title(title);
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(Style::from(Modifier::REVERSED));
+        if self.follow {
+            self.list_state.select(follow_to);
+        }
+        frame.render_stateful_widget(list, area, &mut self.list_state);
+
+        if let Some(input) = &self.search_input {
+            frame.render_widget(TextPopup::new(" 🔍 Search the log ", input), area);
+        }
+        Ok(())
+    }
+}
+
+fn level_style(level: Level) -> Style {
+    match level {
+        Level::ERROR => Style::new().fg(tailwind::RED.c400).bold(),
+        Level::WARN => Style::new().fg(tailwind::AMBER.c300),
+        Level::INFO => Style::new(),
+        _ => Style::new().fg(tailwind::SLATE.c500),
+    }
+}
+
+lazy_static! {
+    static ref KEYS: Vec<RelevantKey> = vec![
+        RelevantKey::new("F11/Esc", "Back"),
+        RelevantKey::new("e/w/i/d/t", "Level"),
+        RelevantKey::new("/", "Search"),
+        RelevantKey::new("Arrows", "Scroll"),
+        RelevantKey::new("End", "Follow"),
+    ];
+}
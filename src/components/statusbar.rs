@@ -2,25 +2,32 @@ use color_eyre::Result;
 use ratatui::{
     Frame,
     layout::{Alignment, Rect},
-    style::Stylize,
-    text::{Span, Text},
-    widgets::{Block, BorderType, Borders, Padding},
+    style::{Style, Stylize, palette::tailwind},
+    text::{Line, Span, Text},
+    widgets::{Block, BorderType, Borders, LineGauge, Padding},
 };
+use time::{OffsetDateTime, format_description::FormatItem, macros::format_description};
 
 use super::Component;
 
 use crate::{
     action::{Action, RelevantKey},
     layout::LayoutSlot,
-    persist,
+    persist, settings,
 };
 
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct StatusBar {
     status_line: String,
     keys: Vec<RelevantKey>,
+    /// Steps done and total of the operation in progress, shown instead of the keys
+    progress: Option<(u32, u32)>,
+    /// Outcome of the last sync with other machines, shown on the left
+    sync: Option<String>,
 }
 
+const CLOCK: &[FormatItem<'static>] = format_description!("[hour]:[minute]");
+
 impl Component for StatusBar {
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
         match action {
@@ -35,10 +42,38 @@ impl Component for StatusBar {
     }
 
     fn handle_persisted(&mut self, event: persist::Event) -> Result<Option<Action>> {
-        if let persist::Event::Failure(msg) = event {
-            Ok(Some(Action::SetStatusLine(format!("⚡ DB error: {msg}"))))
-        } else {
-            Ok(None)
+        self.progress = match event {
+            persist::Event::Progress { done, total, .. } => Some((done, total)),
+            _ => None,
+        };
+        let now = || {
+            OffsetDateTime::now_local()
+                .ok()
+                .and_then(|it| it.format(CLOCK).ok())
+                .unwrap_or_default()
+        };
+        match &event {
+            persist::Event::Synced { .. } => self.sync = Some(format!(" ⇅ {} ", now())),
+            persist::Event::Failure(err) if err.command == persist::CommandKind::Sync => {
+                self.sync = Some(format!(" ⇅ ⚡ {} ", now()));
+            }
+            _ => {}
+        }
+        match event {
+            persist::Event::Synced { pulled, pushed } if !pulled.is_empty() || pushed > 0 => {
+                Ok(Some(Action::SetStatusLine(format!(
+                    "⇅ Synced, {} days changed elsewhere, {pushed} uploaded",
+                    pulled.len()
+                ))))
+            }
+            // Retryable failures are reported by the component retrying them
+            persist::Event::Failure(err) if !err.is_retryable() => {
+                Ok(Some(Action::SetStatusLine(format!("⚡ {err}"))))
+            }
+            persist::Event::Cancelled { .. } => {
+                Ok(Some(Action::SetStatusLine("⏹ Cancelled".into())))
+            }
+            _ => Ok(None),
         }
     }
 
@@ -51,8 +86,26 @@ impl Component for StatusBar {
             .padding(Padding::horizontal(2))
             .title(self.status_line.clone())
             .title_alignment(Alignment::Center);
+        let block = match &self.sync {
+            Some(sync) => block.title(Line::from(sync.as_str()).left_aligned()),
+            None => block,
+        };
+        let block = match settings::profile() {
+            Some(profile) => block.title(Line::from(format!(" 👤 {profile} ")).right_aligned()),
+            None => block,
+        };
         frame.render_widget(&block, area);
 
+        if let Some((done, total)) = self.progress {
+            let gauge = LineGauge::default()
+                .ratio(done as f64 / total.max(1) as f64)
+                .label(format!("⏳ {done}/{total}  <Esc> Cancel "))
+                .filled_style(Style::new().fg(tailwind::INDIGO.c400))
+                .unfilled_style(Style::new().fg(tailwind::SLATE.c700));
+            frame.render_widget(gauge, block.inner(area));
+            return Ok(());
+        }
+
         let mut keys_text = Text::default();
         let mut first = true;
         for key in self.keys.iter() {
@@ -1,27 +1,708 @@
 #![allow(dead_code)] // Remove this once you start using the code
 
-use std::{collections::HashMap, env, path::PathBuf, sync::OnceLock};
+use std::{
+    collections::HashMap,
+    env,
+    path::{Path, PathBuf},
+};
 
-use color_eyre::Result;
+use color_eyre::{Result, eyre::eyre};
 use config::{Environment, File};
-use directories::ProjectDirs;
+use directories::{ProjectDirs, UserDirs};
 use lazy_static::lazy_static;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use strum::{Display, EnumIter};
 
 const DEFAULT_CONFIG: &str = include_str!("../.config/config.json5");
 
+/// Where things are stored, each can be set in the config or with e.g. `SHEET_SHARK_LOG_DIR`
 #[derive(Clone, Debug, Deserialize, Default)]
 pub struct AppConfig {
+    /// Database and edit journal
     #[serde(default)]
     pub data_dir: PathBuf,
     #[serde(default)]
     pub config_dir: PathBuf,
+    /// Log of the last session, in the XDG state dir by default
+    #[serde(default)]
+    pub log_dir: PathBuf,
+    /// Exported timesheets and reports, in the documents dir by default
+    #[serde(default)]
+    pub export_dir: PathBuf,
 }
 
 #[derive(Clone, Debug, Deserialize, Default, Serialize)]
 pub struct ProjectConfig {
     pub internal_name: String,
     pub jira_url: Option<String>,
+    /// Entries of this project need a description
+    #[serde(default)]
+    pub description_required: bool,
+    /// Regex that ticket keys of this project need to match entirely, e.g. `SCRUM-\d+`
+    pub ticket_pattern: Option<TicketPattern>,
+    /// Git repositories worked on for this project, their branch names suggest the ticket
+    #[serde(default)]
+    pub repos: Vec<PathBuf>,
+}
+
+/// [ProjectConfig::ticket_pattern], compiled once when the config is loaded
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub struct TicketPattern {
+    pattern: String,
+    /// Matches whole ticket keys, or why the pattern is invalid
+    regex: Result<Regex, String>,
+}
+
+impl TicketPattern {
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    pub fn regex(&self) -> Result<&Regex, &str> {
+        self.regex.as_ref().map_err(String::as_str)
+    }
+}
+
+impl From<String> for TicketPattern {
+    fn from(pattern: String) -> Self {
+        let regex = Regex::new(&format!("^(?:{pattern})$")).map_err(|err| err.to_string());
+        Self { pattern, regex }
+    }
+}
+
+impl From<TicketPattern> for String {
+    fn from(pattern: TicketPattern) -> Self {
+        pattern.pattern
+    }
+}
+
+/// Filled into an entry when its name is typed in the ticket or description, then Tab
+#[derive(Clone, Debug, Deserialize, Default, PartialEq, Eq)]
+pub struct AliasConfig {
+    pub project: Option<String>,
+    pub ticket: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ValidationConfig {
+    /// Entries longer than this are flagged
+    pub max_entry_mins: Option<u32>,
+    /// Work outside of these hours is flagged
+    pub working_hours: Option<WorkingHours>,
+    /// Rules that block exporting when violated, all others are only warnings
+    pub hard_rules: Vec<ValidationRule>,
+    /// Working time of a day above this is flagged, e.g. the legal limit
+    pub max_day_mins: Option<u32>,
+    /// Working time of a past workday below this is flagged, absences count towards it
+    pub min_day_mins: Option<u32>,
+    /// Weekdays like `monday` that [Self::min_day_mins] applies to
+    pub workdays: Vec<Weekday>,
+}
+
+impl ValidationConfig {
+    /// Whether the weekday is one of the [Self::workdays]
+    pub fn is_workday(&self, weekday: time::Weekday) -> bool {
+        self.workdays.contains(&weekday.into())
+    }
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            max_entry_mins: None,
+            working_hours: None,
+            // Only configured per project, so nothing is blocked out of the box
+            hard_rules: vec![
+                ValidationRule::DescriptionRequired,
+                ValidationRule::TicketFormat,
+            ],
+            max_day_mins: None,
+            min_day_mins: None,
+            workdays: vec![
+                Weekday::Monday,
+                Weekday::Tuesday,
+                Weekday::Wednesday,
+                Weekday::Thursday,
+                Weekday::Friday,
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl From<time::Weekday> for Weekday {
+    fn from(weekday: time::Weekday) -> Self {
+        match weekday {
+            time::Weekday::Monday => Self::Monday,
+            time::Weekday::Tuesday => Self::Tuesday,
+            time::Weekday::Wednesday => Self::Wednesday,
+            time::Weekday::Thursday => Self::Thursday,
+            time::Weekday::Friday => Self::Friday,
+            time::Weekday::Saturday => Self::Saturday,
+            time::Weekday::Sunday => Self::Sunday,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct WorkingHours {
+    /// Earliest start, `HH:MM`
+    pub from: String,
+    /// Latest end, `HH:MM`
+    pub until: String,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ValidationRule {
+    MaxEntryLength,
+    DescriptionRequired,
+    WorkingHours,
+    TicketFormat,
+    /// Start times that happen twice or not at all because of a DST transition
+    WallClock,
+    /// Tickets that were archived, e.g. because they are closed
+    ArchivedTicket,
+    /// Work on a project with a Jira URL that can't be booked, checked before exporting to Jira
+    JiraBooking,
+    /// Changes that are not saved yet, checked before exporting to Jira
+    Unsaved,
+    /// Entries ending after midnight, which always block saving and exporting
+    DayOverflow,
+    /// Work logged on a public holiday, see [HolidayConfig]
+    Holiday,
+    /// Working time of the whole day outside of [ValidationConfig::min_day_mins] and
+    /// [ValidationConfig::max_day_mins]
+    DayDuration,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct IdleConfig {
+    /// Offer recording a break after this many minutes without input, disabled if unset
+    pub pause_after_mins: Option<u32>,
+    /// Command printing the idle time in milliseconds
+    pub command: String,
+}
+
+impl Default for IdleConfig {
+    fn default() -> Self {
+        Self {
+            pause_after_mins: None,
+            command: "xprintidle".to_string(),
+        }
+    }
+}
+
+/// Entries suggested from the focused window, needs the `focus` feature
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct FocusConfig {
+    /// Off unless enabled, since every window title is looked at
+    pub enabled: bool,
+    /// Command printing the title of the focused window
+    pub command: String,
+    /// Minutes a rule has to match before it is suggested or switches the timer
+    pub min_mins: u32,
+    /// Start a timer for the matched rule instead of queueing suggestions for review
+    pub switch_timer: bool,
+    /// The first rule matching the title wins
+    pub rules: Vec<FocusRule>,
+}
+
+impl Default for FocusConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            command: "xdotool getactivewindow getwindowname".to_string(),
+            min_mins: 5,
+            switch_timer: false,
+            rules: vec![],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct FocusRule {
+    /// Regex on the window title, its groups can be used like `$1` in the fields below
+    pub pattern: String,
+    pub project: Option<String>,
+    pub ticket: Option<String>,
+    pub description: String,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct EditingConfig {
+    /// Commit the text being edited to the entry after this many seconds, `0` disables
+    pub autosave_secs: u64,
+    /// Entries added at the end of today start now, rounded to this many minutes, unless the
+    /// previous one ends later. `0` always starts them at the previous end.
+    pub start_rounding_mins: u32,
+    /// Start times typed into the time column are snapped to this many minutes, `0` keeps them
+    /// as typed
+    pub snap_start_mins: u32,
+    /// Offer the entries marked to carry over when opening today's empty timesheet
+    pub carry_over_prompt: bool,
+    /// Minutes that `+` and `-` add to or take from the selected entry
+    pub duration_step_mins: u32,
+}
+
+impl Default for EditingConfig {
+    fn default() -> Self {
+        Self {
+            autosave_secs: 5,
+            start_rounding_mins: 5,
+            snap_start_mins: 0,
+            carry_over_prompt: true,
+            duration_step_mins: 5,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ExportConfig {
+    /// Lines of each file shown in the preview before writing
+    pub preview_lines: usize,
+    /// Append a counter to the file name instead of overwriting an existing export
+    pub keep_existing: bool,
+    /// Line copied for each ticket, with `{ticket}`, `{project}`, `{duration}`, `{minutes}`
+    /// and `{description}` filled in
+    pub booking_line: String,
+    /// Goes between the lines of copied bookings
+    pub booking_separator: String,
+    /// Comment of worklogs booked in Jira, with `{ticket}`, `{day}` and `{descriptions}`
+    /// filled in, none if empty
+    pub jira_comment: String,
+    /// Numbers, dates and header names of CSV exports
+    pub locale: ExportLocaleConfig,
+    /// Start CSV exports with comment lines like `# app_version: 0.1.0`, which not every
+    /// spreadsheet skips
+    pub csv_meta_header: bool,
+    /// Formats like `csv` and `json` that are written again once a day stopped changing, so
+    /// that tools watching the export dir see current files. Overwrites, unlike `keep_existing`.
+    pub auto_formats: Vec<String>,
+    /// Seconds without changes to a day before it is exported again
+    pub auto_delay_secs: u64,
+    /// Shell commands run after a file was exported, with its path as `$1` and the day as `$2`
+    pub hooks: Vec<String>,
+    /// How the defragmented export orders the day, can be changed when picking the formats
+    pub defrag_strategy: DefragStrategy,
+    /// Worklogs in the Jira CSV export are rounded to this many minutes, 0 keeps them exact
+    pub jira_rounding_mins: u32,
+}
+
+impl Default for ExportConfig {
+    fn default() -> Self {
+        Self {
+            preview_lines: 12,
+            keep_existing: false,
+            booking_line: "{ticket}: {duration} — {description}".to_string(),
+            booking_separator: "; ".to_string(),
+            jira_comment: "{descriptions}".to_string(),
+            locale: ExportLocaleConfig::default(),
+            csv_meta_header: false,
+            auto_formats: vec![],
+            auto_delay_secs: 5,
+            hooks: vec![],
+            defrag_strategy: DefragStrategy::default(),
+            jira_rounding_mins: 15,
+        }
+    }
+}
+
+/// How CSV exports are written, so that spreadsheets in other languages read them correctly
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ExportLocaleConfig {
+    /// Defaults for the other fields
+    pub language: ExportLanguage,
+    /// Write hours like `0,25`, columns are then separated by `;`
+    pub decimal_comma: Option<bool>,
+    /// Format of dates like `[day].[month].[year]`, see
+    /// <https://time-rs.github.io/book/api/format-description.html>
+    pub date_format: Option<String>,
+    /// Header names like `duration` to what is written instead
+    pub headers: HashMap<String, String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportLanguage {
+    #[default]
+    En,
+    De,
+}
+
+/// Order of the defragmented day, which packs the worked time into as few blocks as possible
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Display, EnumIter)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum DefragStrategy {
+    /// Consecutive entries on the same ticket merged, in the order they were worked on
+    Chronological,
+    /// Projects in order of their first entry, each with all its tickets
+    #[default]
+    ByProject,
+    /// Tickets in order of their first entry, regardless of project
+    ByTicket,
+    /// Whole tickets fitted between breaks, so that breaks split as few of them as possible
+    MinimizeSwitches,
+}
+
+impl DefragStrategy {
+    pub fn label(&self) -> &'static str {
+        match self {
+            DefragStrategy::Chronological => "chronological",
+            DefragStrategy::ByProject => "by project",
+            DefragStrategy::ByTicket => "by ticket",
+            DefragStrategy::MinimizeSwitches => "fewest switches",
+        }
+    }
+}
+
+/// Public holidays are marked in the calendar, need no working time and are warned about
+/// when logging work on them
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct HolidayConfig {
+    /// Country whose nationwide holidays are bundled
+    pub region: Option<HolidayRegion>,
+    /// Calendar with the holidays as all-day events, fetched at startup and kept in the data
+    /// dir for offline use
+    pub ics_url: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HolidayRegion {
+    At,
+    De,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct SqliteConfig {
+    pub journal_mode: JournalMode,
+    pub synchronous: Synchronous,
+    /// How long to wait for other processes holding a lock on the database, e.g. backups
+    pub busy_timeout_ms: u32,
+}
+
+impl Default for SqliteConfig {
+    fn default() -> Self {
+        Self {
+            journal_mode: JournalMode::Wal,
+            synchronous: Synchronous::Normal,
+            busy_timeout_ms: 5000,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct DesktopConfig {
+    /// Show today's working time in the title of the terminal window
+    pub terminal_title: bool,
+    /// Notify when reaching the daily target or being back from idle, needs the
+    /// `notifications` feature
+    pub notifications: bool,
+    /// Ring the terminal bell when an entry could not be saved
+    pub bell_on_save_failure: bool,
+    /// Render at [Self::idle_frame_rate] after this many seconds without input, `0` never does
+    pub idle_render_after_secs: u64,
+    /// Frames per second while idle, any input goes back to the full frame rate at once
+    pub idle_frame_rate: f64,
+}
+
+impl Default for DesktopConfig {
+    fn default() -> Self {
+        Self {
+            terminal_title: true,
+            notifications: false,
+            bell_on_save_failure: false,
+            idle_render_after_secs: 30,
+            idle_frame_rate: 1.0,
+        }
+    }
+}
+
+/// Hotkeys of `sheet-shark background`, needs the `hotkey` feature
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct BackgroundConfig {
+    /// Opens the TUI in a terminal, like `ctrl+alt+s` or `super+shift+KeyT`
+    pub open_hotkey: String,
+    /// Asks for an entry in a terminal and adds it, like `sheet-shark add --prompt`
+    pub add_hotkey: String,
+    /// Command opening a terminal, the sheet-shark command line is appended to it
+    pub terminal: Vec<String>,
+}
+
+impl Default for BackgroundConfig {
+    fn default() -> Self {
+        Self {
+            open_hotkey: "ctrl+alt+s".to_string(),
+            add_hotkey: "ctrl+alt+a".to_string(),
+            terminal: vec!["x-terminal-emulator".to_string(), "-e".to_string()],
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    /// Count how often features are used, for the insights in the calendar. The counts
+    /// are only stored in the local database.
+    pub enabled: bool,
+}
+
+/// Separate log of what was done in the TUI, for bug reports
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct ActionLogConfig {
+    /// Log the name of every action at debug level, never what was typed into entries
+    pub enabled: bool,
+    /// Logs of this many sessions are kept, older ones are deleted at startup
+    pub keep_sessions: usize,
+}
+
+impl Default for ActionLogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            keep_sessions: 5,
+        }
+    }
+}
+
+/// Marker set on entries with `g` on the timesheet, e.g. for ones that need a review
+#[derive(Clone, Debug, Deserialize)]
+pub struct FlagConfig {
+    /// Shown in the slim column at the end of the timesheet
+    pub symbol: String,
+    pub label: String,
+}
+
+/// Kind of work an entry is, picked with `y` on the timesheet
+#[derive(Clone, Debug, Deserialize)]
+pub struct WorkTypeConfig {
+    /// Shown instead of the key
+    pub label: String,
+    /// Side of the meeting/deep-work ratio this counts towards, if any
+    pub counts_as: Option<WorkKind>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkKind {
+    Meeting,
+    DeepWork,
+}
+
+/// Kind of absence inserted with `a` on the timesheet, like a half day of vacation
+#[derive(Clone, Debug, Deserialize)]
+pub struct AbsenceConfig {
+    /// Shown in the picker and used as the description of its entries
+    pub label: String,
+    /// Project key of its entries, which keeps them out of the project sums
+    pub project: String,
+    /// Time it takes up on the timesheet, like `4h`
+    pub duration: String,
+}
+
+/// Read-only query shown as a table with `R` in the calendar or by `sheet-shark report`
+#[derive(Clone, Debug, Deserialize)]
+pub struct ReportConfig {
+    /// Shown next to the name when picking a report
+    pub description: Option<String>,
+    /// A single `SELECT` or `WITH` statement, e.g. over the `time_entry` table. Of the
+    /// archived timesheets, the newest nine years are included.
+    pub sql: String,
+}
+
+/// See <https://sqlite.org/pragma.html#pragma_journal_mode>
+#[derive(Clone, Copy, Debug, Deserialize, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "UPPERCASE")]
+pub enum JournalMode {
+    Delete,
+    Truncate,
+    Persist,
+    Memory,
+    Wal,
+}
+
+/// See <https://sqlite.org/pragma.html#pragma_synchronous>
+#[derive(Clone, Copy, Debug, Deserialize, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "UPPERCASE")]
+pub enum Synchronous {
+    Off,
+    Normal,
+    Full,
+    Extra,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct HomeConfig {
+    /// Show the hours worked on each day of the week above the timesheet
+    pub week_bar: bool,
+    /// Store the UTC offset with today's timesheet, so that it reads correctly after travelling
+    pub record_utc_offset: bool,
+    /// Working time per day to show progress against, no progress bar if unset
+    pub daily_target_mins: Option<u32>,
+    /// Show where each entry ends next to its start, can be toggled with `E`
+    pub end_column: bool,
+    /// Entries shorter than this are dimmed in the table, 0 to not dim any
+    pub tiny_entry_mins: u32,
+    /// Entries longer than this are highlighted in the table, 0 to not highlight any
+    pub long_entry_mins: u32,
+}
+
+impl Default for HomeConfig {
+    fn default() -> Self {
+        Self {
+            week_bar: true,
+            record_utc_offset: false,
+            daily_target_mins: None,
+            end_column: false,
+            tiny_entry_mins: 10,
+            long_entry_mins: 90,
+        }
+    }
+}
+
+/// Entries that empty days start with, per weekday
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ScaffoldConfig {
+    /// Fill empty days right away instead of offering it
+    pub auto_apply: bool,
+    /// Lowercase weekday like `monday` to its entries
+    pub days: HashMap<String, Vec<ScaffoldEntry>>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ScaffoldEntry {
+    /// `HH:MM`
+    pub start: String,
+    /// Stored template to take the fields from, those set here take precedence
+    pub template: Option<String>,
+    /// Like `15m` or `1h30m`, required without a template
+    pub duration: Option<String>,
+    pub project: Option<String>,
+    pub ticket: Option<String>,
+    pub description: Option<String>,
+    pub billable: Option<bool>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ProfileConfig {
+    /// Holds the database of this profile, separate from all others
+    pub data_dir: PathBuf,
+    /// Exports of this profile, a folder named after it in the export dir by default
+    pub export_dir: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ImportConfig {
+    pub provider: ImportProvider,
+    pub api_token: String,
+    /// Clockify only, defaults to the active workspace of the user
+    pub workspace_id: Option<String>,
+    /// Project or tag name in the external tracker to sheet-shark project key
+    #[serde(default)]
+    pub mapping: HashMap<String, String>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportProvider {
+    Toggl,
+    Clockify,
+}
+
+/// Syncing the recent days with other machines through files in a shared place, see
+/// [crate::sync]
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct SyncConfig {
+    pub target: SyncTarget,
+    /// Folder of the dir and git targets
+    pub path: Option<PathBuf>,
+    /// Collection of the webdav target, like a Nextcloud folder
+    pub url: Option<String>,
+    pub username: Option<String>,
+    /// Password in plain text, better taken from `password_env` or `password_command`
+    pub password: Option<String>,
+    /// Environment variable holding the password
+    pub password_env: Option<String>,
+    /// Shell command printing the password, like `pass show nextcloud`
+    pub password_command: Option<String>,
+    pub strategy: SyncStrategy,
+    /// Days back from today that are synced
+    pub days: u32,
+    /// Minutes between syncs while the TUI runs, 0 to only sync on demand
+    pub interval_mins: u64,
+    /// Seconds until a webdav request or git command that doesn't respond is given up
+    pub timeout_secs: u64,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            target: SyncTarget::Dir,
+            path: None,
+            url: None,
+            username: None,
+            password: None,
+            password_env: None,
+            password_command: None,
+            strategy: SyncStrategy::Merge,
+            days: 62,
+            interval_mins: 15,
+            timeout_secs: 30,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncTarget {
+    /// Folder shared by other means, e.g. Syncthing or a network drive
+    Dir,
+    /// Clone of a Git repository, pulled before and pushed after syncing
+    Git,
+    Webdav,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncStrategy {
+    /// Newest version of each entry and of the notes
+    Merge,
+    /// Whole day as it was changed last, on whichever machine
+    LastWriteWins,
 }
 
 #[derive(Clone, Debug, Default, Deserialize)]
@@ -30,9 +711,60 @@ pub struct Config {
     pub config: AppConfig,
     #[serde(default)]
     pub projects: HashMap<String, ProjectConfig>,
+    #[serde(default)]
+    pub aliases: HashMap<String, AliasConfig>,
+    /// Ticket prefix like `SCRUM-*` to the project key filled in for such tickets
+    #[serde(default)]
+    pub ticket_projects: HashMap<String, String>,
     pub default_project_key: String,
+    #[serde(default)]
+    pub validation: ValidationConfig,
+    #[serde(default)]
+    pub idle: IdleConfig,
+    #[serde(default)]
+    pub focus: FocusConfig,
+    #[serde(default)]
+    pub editing: EditingConfig,
+    #[serde(default)]
+    pub home: HomeConfig,
+    #[serde(default)]
+    pub export: ExportConfig,
+    #[serde(default)]
+    pub scaffold: ScaffoldConfig,
+    #[serde(default)]
+    pub sqlite: SqliteConfig,
+    #[serde(default)]
+    pub desktop: DesktopConfig,
+    #[serde(default)]
+    pub background: BackgroundConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub action_log: ActionLogConfig,
+    #[serde(default)]
+    pub reports: HashMap<String, ReportConfig>,
+    #[serde(default)]
+    pub work_types: HashMap<String, WorkTypeConfig>,
+    #[serde(default)]
+    pub flags: HashMap<String, FlagConfig>,
+    #[serde(default)]
+    pub absences: HashMap<String, AbsenceConfig>,
+    #[serde(default)]
+    pub holidays: HolidayConfig,
+    pub import: Option<ImportConfig>,
+    pub sync: Option<SyncConfig>,
+    #[serde(default)]
+    pub profiles: HashMap<String, ProfileConfig>,
 }
 
+/// Files in the config dir that are read, later ones override earlier ones
+pub const CONFIG_FILES: [(&str, config::FileFormat); 4] = [
+    ("config.json5", config::FileFormat::Json5),
+    ("config.json", config::FileFormat::Json),
+    ("config.yaml", config::FileFormat::Yaml),
+    ("config.toml", config::FileFormat::Toml),
+];
+
 lazy_static! {
     pub static ref PROJECT_NAME: String = env!("CARGO_CRATE_NAME").to_uppercase().to_string();
     pub static ref DATA_FOLDER: Option<PathBuf> =
@@ -45,9 +777,8 @@ lazy_static! {
             .map(PathBuf::from);
 }
 
-static CONFIG: OnceLock<Config> = OnceLock::new();
-
 impl Config {
+    /// Loads the built-in defaults, overridden by the files in the config dir and the environment
     pub fn new() -> Result<Self, config::ConfigError> {
         let data_dir = get_data_dir();
         let config_dir = get_config_dir();
@@ -55,38 +786,99 @@ impl Config {
         let mut builder = config::Config::builder()
             .set_default("data_dir", data_dir.to_str().unwrap())?
             .set_default("config_dir", config_dir.to_str().unwrap())?
+            .set_default("log_dir", get_state_dir().to_str().unwrap())?
+            .set_default("export_dir", default_export_dir().to_str().unwrap())?
             .add_source(File::from_str(DEFAULT_CONFIG, config::FileFormat::Json5));
 
-        let config_files = [
-            ("config.json5", config::FileFormat::Json5),
-            ("config.json", config::FileFormat::Json),
-            ("config.yaml", config::FileFormat::Yaml),
-            ("config.toml", config::FileFormat::Toml),
-        ];
-        for (file, format) in &config_files {
+        for (file, format) in &CONFIG_FILES {
             let source = config::File::from(config_dir.join(file))
                 .format(*format)
                 .required(false);
             builder = builder.add_source(source);
         }
 
-        let cfg: Self = builder
+        builder
             .add_source(Environment::with_prefix("SHEET_SHARK"))
             .build()?
-            .try_deserialize()?;
+            .try_deserialize()
+    }
+
+    /// Data dir of the named profile, or the default data dir without a profile
+    pub fn data_dir_of(&self, profile: Option<&str>) -> Result<PathBuf> {
+        let Some(name) = profile else {
+            return Ok(self.config.data_dir.clone());
+        };
+        Ok(self.profile(name)?.data_dir.clone())
+    }
+
+    /// Export dir of the named profile, or the configured export dir without a profile
+    pub fn export_dir_of(&self, profile: Option<&str>) -> Result<PathBuf> {
+        let Some(name) = profile else {
+            return Ok(self.config.export_dir.clone());
+        };
+        let export_dir = self.profile(name)?.export_dir.clone();
+        Ok(export_dir.unwrap_or_else(|| self.config.export_dir.join(name)))
+    }
+
+    fn profile(&self, name: &str) -> Result<&ProfileConfig> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| eyre!("Unknown profile {name}, see the profiles section of the config"))
+    }
+
+    /// Configured report names, sorted
+    pub fn report_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.reports.keys().map(String::as_str).collect();
+        names.sort();
+        names
+    }
+
+    /// Configured work type keys, sorted by label
+    pub fn work_type_keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.work_types.keys().map(String::as_str).collect();
+        keys.sort_by_key(|it| &self.work_types[*it].label);
+        keys
+    }
+
+    /// Label of the work type, or the key itself if it's no longer configured
+    pub fn work_type_label<'a>(&'a self, key: &'a str) -> &'a str {
+        self.work_types.get(key).map_or(key, |it| it.label.as_str())
+    }
+
+    /// Configured flag keys, sorted by label
+    pub fn flag_keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.flags.keys().map(String::as_str).collect();
+        keys.sort_by_key(|it| &self.flags[*it].label);
+        keys
+    }
 
-        CONFIG.set(cfg.clone()).expect("no config set yet");
+    /// Symbols of the flags, with a question mark for ones that are no longer configured
+    pub fn flag_symbols<'a>(&self, keys: impl IntoIterator<Item = &'a str>) -> String {
+        keys.into_iter()
+            .map(|key| self.flags.get(key).map_or("?", |it| it.symbol.as_str()))
+            .collect()
+    }
 
-        Ok(cfg)
+    /// Configured absence keys, sorted by label
+    pub fn absence_keys(&self) -> Vec<&str> {
+        let mut keys: Vec<&str> = self.absences.keys().map(String::as_str).collect();
+        keys.sort_by_key(|it| &self.absences[*it].label);
+        keys
     }
 
-    pub fn get() -> &'static Self {
-        CONFIG.get().expect("config loaded")
+    /// Key of the absence whose entries are booked on the project, if any
+    pub fn absence_of_project(&self, project_key: &str) -> Option<&str> {
+        self.absences
+            .iter()
+            .find(|(_, absence)| absence.project == project_key)
+            .map(|(key, _)| key.as_str())
     }
 
-    #[cfg(test)]
-    pub fn set_for_tests(config: Config) {
-        let _ = CONFIG.set(config);
+    /// Configured profile names, sorted
+    pub fn profile_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.profiles.keys().map(String::as_str).collect();
+        names.sort();
+        names
     }
 }
 
@@ -100,6 +892,22 @@ pub fn get_data_dir() -> PathBuf {
     }
 }
 
+/// XDG state dir where supported, i.e. on Linux, the data dir otherwise
+pub fn get_state_dir() -> PathBuf {
+    project_directory()
+        .and_then(|it| it.state_dir().map(Path::to_path_buf))
+        .unwrap_or_else(get_data_dir)
+}
+
+fn default_export_dir() -> PathBuf {
+    UserDirs::new()
+        .and_then(|it| {
+            it.document_dir()
+                .map(|dir| dir.join(env!("CARGO_PKG_NAME")))
+        })
+        .unwrap_or_else(|| get_data_dir().join("exports"))
+}
+
 pub fn get_config_dir() -> PathBuf {
     if let Some(s) = CONFIG_FOLDER.clone() {
         s
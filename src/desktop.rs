@@ -0,0 +1,48 @@
+//! Integration with the desktop around the terminal, as enabled in the `desktop` config
+
+use std::io::{Write, stdout};
+
+use color_eyre::Result;
+use crossterm::terminal::SetTitle;
+
+use crate::settings;
+
+pub fn set_terminal_title(title: &str) -> Result<()> {
+    crossterm::execute!(stdout(), SetTitle(title))?;
+    Ok(())
+}
+
+/// Beeps or flashes, depending on how the terminal handles the bell character
+pub fn ring_bell() {
+    if let Err(err) = stdout().write_all(b"\x07").and_then(|()| stdout().flush()) {
+        tracing::warn!("Failed to ring the terminal bell: {err}");
+    }
+}
+
+/// Shows a desktop notification in the background, if enabled
+pub fn notify(summary: &str, body: &str) {
+    if !settings::get().desktop.notifications {
+        return;
+    }
+    show_notification(summary.to_string(), body.to_string());
+}
+
+#[cfg(feature = "notifications")]
+fn show_notification(summary: String, body: String) {
+    // Talking to the notification daemon may block for a moment
+    std::thread::spawn(move || {
+        let result = notify_rust::Notification::new()
+            .appname("sheet-shark")
+            .summary(&summary)
+            .body(&body)
+            .show();
+        if let Err(err) = result {
+            tracing::warn!("Failed to show notification {summary:?}: {err}");
+        }
+    });
+}
+
+#[cfg(not(feature = "notifications"))]
+fn show_notification(summary: String, _body: String) {
+    tracing::debug!("Not built with notifications, skipping {summary:?}");
+}
@@ -0,0 +1,228 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{Result, eyre::Context};
+use serde::Serialize;
+use strum::{Display, EnumIter, EnumString};
+use time::Date;
+
+use self::locale::CsvLocale;
+use crate::{
+    config::Config,
+    persist::{self, EntryLink, TimeEntry},
+    shared::{defrag, summary::TimesheetSummary},
+};
+
+pub mod booking_text;
+pub mod csv;
+pub mod day_table;
+pub mod hooks;
+pub mod jira_csv;
+pub mod json;
+pub mod locale;
+
+/// Everything a day can be exported as, stored by its snake case name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString, EnumIter)]
+#[strum(serialize_all = "snake_case")]
+pub enum ExportFormat {
+    /// LibreOffice Calc compatible, one row per entry
+    Csv,
+    /// Entries with project details and totals
+    Json,
+    /// One block per ticket, as calculated by [defrag::calculate]
+    Defrag,
+    /// Worklogs of the defragmented blocks, for Jira's CSV import
+    JiraCsv,
+}
+
+/// Where an export came from, so that downstream tools can detect format changes. Part of the
+/// JSON meta block, and of CSV exports as comment lines if
+/// [crate::config::ExportConfig::csv_meta_header] is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportMeta {
+    pub app_version: String,
+    /// Newest migration known to the app, like `20261015140000`
+    pub schema_version: String,
+    /// Config profile the data belongs to, `None` for the default one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Minutes that start times of new entries were rounded to
+    pub start_rounding_mins: u32,
+}
+
+impl ExportMeta {
+    pub fn new(profile: Option<String>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            schema_version: persist::latest_schema_version()?,
+            profile,
+            start_rounding_mins: config.editing.start_rounding_mins,
+        })
+    }
+
+    /// Comment lines like `# app_version: 0.1.0` above the header of CSV exports, if configured
+    pub fn write_csv_comment(&self, config: &Config, mut writer: impl Write) -> Result<()> {
+        if !config.export.csv_meta_header {
+            return Ok(());
+        }
+        let mut lines = vec![
+            ("app_version", self.app_version.clone()),
+            ("schema_version", self.schema_version.clone()),
+        ];
+        if let Some(profile) = &self.profile {
+            lines.push(("profile", profile.clone()));
+        }
+        lines.push(("start_rounding_mins", self.start_rounding_mins.to_string()));
+        for (key, value) in lines {
+            writeln!(writer, "# {key}: {value}").wrap_err("writing CSV meta comment")?;
+        }
+        Ok(())
+    }
+}
+
+impl ExportFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Json => "JSON",
+            ExportFormat::Defrag => "Defragmented CSV",
+            ExportFormat::JiraCsv => "Jira worklog CSV",
+        }
+    }
+
+    /// Like `2025-09-22.csv`, unique per format
+    pub fn file_name(&self, day: Date) -> String {
+        match self {
+            ExportFormat::Csv => format!("{day}.csv"),
+            ExportFormat::Json => format!("{day}.json"),
+            ExportFormat::Defrag => format!("{day}-defrag.csv"),
+            ExportFormat::JiraCsv => format!("{day}-jira.csv"),
+        }
+    }
+
+    /// Where the export of the day goes, in a folder per month like `2025/09/2025-09-22.csv`
+    pub fn path_in(&self, export_dir: &Path, day: Date) -> PathBuf {
+        export_dir
+            .join(day.year().to_string())
+            .join(format!("{:02}", u8::from(day.month())))
+            .join(self.file_name(day))
+    }
+
+    /// Content of the export of the day's entries in this format, only JSON includes the links.
+    /// Every format carries the [ExportMeta], CSV ones only if configured. Jira's import would
+    /// take its comment lines for worklogs, so it never does.
+    #[allow(clippy::too_many_arguments)]
+    pub fn generate(
+        &self,
+        entries: &[TimeEntry],
+        links: &[EntryLink],
+        day: Date,
+        notes: &str,
+        utc_offset_mins: Option<i32>,
+        meta: &ExportMeta,
+        config: &Config,
+    ) -> Result<String> {
+        let mut content = vec![];
+        match self {
+            ExportFormat::Csv => {
+                let locale = CsvLocale::from_config(&config.export.locale)?;
+                meta.write_csv_comment(config, &mut content)?;
+                csv::generate_csv_content(entries, &locale, &mut content)?
+            }
+            ExportFormat::Json => {
+                return json::generate_json_content(
+                    entries,
+                    links,
+                    day,
+                    notes,
+                    utc_offset_mins,
+                    meta,
+                    config,
+                );
+            }
+            ExportFormat::Defrag => {
+                let summary = TimesheetSummary::new(entries.to_vec(), config);
+                meta.write_csv_comment(config, &mut content)?;
+                let defragmented = defrag::calculate(&summary, config.export.defrag_strategy);
+                csv::generate_defrag_csv_content(&defragmented, &mut content)?
+            }
+            ExportFormat::JiraCsv => jira_csv::generate_jira_csv_content(
+                &[(day, entries.to_vec())],
+                config,
+                &mut content,
+            )?,
+        }
+        String::from_utf8(content).wrap_err_with(|| format!("{} export is not UTF-8", self.label()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use itertools::Itertools;
+    use strum::IntoEnumIterator;
+    use time::macros::date;
+
+    use super::*;
+
+    #[test]
+    fn test_formats_round_trip_with_unique_file_names() {
+        let day = date!(2025 - 09 - 22);
+        let names = ExportFormat::iter()
+            .map(|it| it.file_name(day))
+            .collect_vec();
+        assert_eq!(
+            names,
+            [
+                "2025-09-22.csv",
+                "2025-09-22.json",
+                "2025-09-22-defrag.csv",
+                "2025-09-22-jira.csv"
+            ]
+        );
+        for format in ExportFormat::iter() {
+            assert_eq!(ExportFormat::from_str(&format.to_string()).unwrap(), format);
+        }
+    }
+
+    #[test]
+    fn test_csv_formats_start_with_meta_comment_if_configured() {
+        let mut config = Config::default();
+        let meta = ExportMeta::new(Some("acme".into()), &config).unwrap();
+        let day = date!(2025 - 09 - 22);
+        let generate = |format: ExportFormat, config: &Config| {
+            format.generate(&[], &[], day, "", None, &meta, config)
+        };
+        assert!(
+            !generate(ExportFormat::Csv, &config)
+                .unwrap()
+                .starts_with('#')
+        );
+
+        config.export.csv_meta_header = true;
+        for format in [ExportFormat::Csv, ExportFormat::Defrag] {
+            let content = generate(format, &config).unwrap();
+            let comment = content
+                .lines()
+                .take_while(|it| it.starts_with('#'))
+                .collect_vec();
+            assert_eq!(
+                comment,
+                [
+                    format!("# app_version: {}", env!("CARGO_PKG_VERSION")),
+                    format!("# schema_version: {}", meta.schema_version),
+                    "# profile: acme".to_string(),
+                    format!(
+                        "# start_rounding_mins: {}",
+                        config.editing.start_rounding_mins
+                    ),
+                ]
+            );
+        }
+        let json = generate(ExportFormat::Json, &config).unwrap();
+        assert!(json.contains("\"profile\": \"acme\""), "{json}");
+    }
+}
@@ -0,0 +1,132 @@
+use itertools::Itertools;
+use time::{Date, format_description::FormatItem, macros::format_description};
+
+use crate::persist::TimeEntry;
+
+const ISO_DAY: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
+/// Plain-text list of the time per ticket, for pasting into tools that don't take CSV.
+///
+/// Each ticket is written with `line`, filling in `{ticket}`, `{project}`, `{duration}`,
+/// `{minutes}` and `{description}`, and joined with `separator`. Entries without a ticket
+/// are listed under their project key. Tickets are in order of their first entry, the
+/// distinct descriptions of their entries are joined with `, `.
+pub fn generate_booking_text(entries: &[TimeEntry], line: &str, separator: &str) -> String {
+    entries
+        .iter()
+        .filter(|entry| entry.duration_mins > 0)
+        .into_group_map_by(|entry| entry.ticket_key.as_deref().unwrap_or(&entry.project_key))
+        .into_iter()
+        .sorted_by_key(|(_, entries)| entries.iter().map(|it| &it.start_time).min().cloned())
+        .map(|(ticket, entries)| {
+            let minutes: i32 = entries.iter().map(|it| it.duration_mins).sum();
+            let description = entries
+                .iter()
+                .map(|it| it.description.trim())
+                .filter(|it| !it.is_empty())
+                .unique()
+                .join(", ");
+            line.replace("{ticket}", ticket)
+                .replace("{project}", &entries[0].project_key)
+                .replace("{duration}", &format_duration(minutes))
+                .replace("{minutes}", &minutes.to_string())
+                .replace("{description}", &description)
+        })
+        .join(separator)
+}
+
+/// Comment of the worklog booked on a ticket, filling in `{ticket}`, `{day}` like `2025-09-22`
+/// and `{descriptions}`, the distinct descriptions of its entries joined with `, `
+pub fn generate_worklog_comment(
+    template: &str,
+    ticket: &str,
+    day: Date,
+    entries: &[&TimeEntry],
+) -> String {
+    let descriptions = entries
+        .iter()
+        .filter(|entry| entry.duration_mins > 0)
+        .map(|it| it.description.trim())
+        .filter(|it| !it.is_empty())
+        .unique()
+        .join(", ");
+    let day = day.format(ISO_DAY).unwrap_or_else(|_| day.to_string());
+    template
+        .replace("{ticket}", ticket)
+        .replace("{day}", &day)
+        .replace("{descriptions}", &descriptions)
+        .trim()
+        .to_string()
+}
+
+pub(super) fn format_duration(mins: i32) -> String {
+    match (mins / 60, mins % 60) {
+        (0, mins) => format!("{mins}m"),
+        (hours, 0) => format!("{hours}h"),
+        (hours, mins) => format!("{hours}h{mins}m"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(start_time: &str, mins: i32, ticket: Option<&str>, description: &str) -> TimeEntry {
+        TimeEntry {
+            id: start_time.to_string(),
+            timesheet_day: "2025-09-22".to_string(),
+            ticket_key: ticket.map(str::to_string),
+            description: description.to_string(),
+            ..TimeEntry::sample(start_time, mins)
+        }
+    }
+
+    #[test]
+    fn test_sums_up_tickets_in_order() {
+        let entries = vec![
+            entry("09:00", 60, Some("SCRUM-17"), "fix login"),
+            entry("10:00", 45, Some("PLAT-3"), "review"),
+            entry("10:45", 30, Some("SCRUM-17"), "fix login"),
+            entry("11:15", 0, Some("PLAT-4"), "nothing yet"),
+        ];
+
+        assert_eq!(
+            generate_booking_text(&entries, "{ticket}: {duration} — {description}", "; "),
+            "SCRUM-17: 1h30m — fix login; PLAT-3: 45m — review"
+        );
+    }
+
+    #[test]
+    fn test_entries_without_ticket_under_project() {
+        let entries = vec![
+            entry("09:00", 60, None, "standup"),
+            entry("10:00", 60, None, "planning"),
+        ];
+
+        assert_eq!(
+            generate_booking_text(&entries, "{ticket} {minutes} {description}", "\n"),
+            "W 120 standup, planning"
+        );
+    }
+
+    #[test]
+    fn test_worklog_comment_joins_distinct_descriptions() {
+        let entries = [
+            entry("09:00", 60, Some("SCRUM-17"), "fix login"),
+            entry("10:00", 30, Some("SCRUM-17"), " fix login "),
+            entry("10:30", 30, Some("SCRUM-17"), "review"),
+            entry("11:00", 0, Some("SCRUM-17"), "not yet"),
+        ];
+        let entries = entries.iter().collect::<Vec<_>>();
+        let day = time::macros::date!(2025 - 09 - 22);
+
+        assert_eq!(
+            generate_worklog_comment("{day} {ticket}: {descriptions}", "SCRUM-17", day, &entries),
+            "2025-09-22 SCRUM-17: fix login, review"
+        );
+        assert_eq!(
+            generate_worklog_comment("{descriptions}", "SCRUM-17", day, &[]),
+            ""
+        );
+    }
+}
@@ -0,0 +1,450 @@
+use std::io::Write;
+
+use chrono::{NaiveTime, Timelike};
+use color_eyre::{Result, eyre::Context};
+use csv::WriterBuilder;
+use time::Date;
+
+use super::locale::CsvLocale;
+use crate::{
+    persist::{ReportTable, TimeEntry},
+    shared::{BREAK_PROJECT_KEY, defrag::DefragmentedEntry},
+};
+
+/// Generate CSV content in LibreOffice Calc compatible format
+pub fn generate_csv_content<W: Write>(
+    entries: &[TimeEntry],
+    locale: &CsvLocale,
+    writer: W,
+) -> Result<()> {
+    let mut csv_writer = WriterBuilder::new()
+        .has_headers(false)
+        .delimiter(locale.delimiter())
+        .from_writer(writer);
+
+    write_csv_header(&mut csv_writer, locale, false)?;
+    write_csv_items(&mut csv_writer, locale, entries, None)?;
+
+    csv_writer.flush().context("Failed to flush CSV writer")?;
+    Ok(())
+}
+
+/// Generate one CSV for several days in the same format, with the date prepended to each row
+pub fn generate_combined_csv_content<W: Write>(
+    days: &[(Date, Vec<TimeEntry>)],
+    locale: &CsvLocale,
+    writer: W,
+) -> Result<()> {
+    let mut csv_writer = WriterBuilder::new()
+        .has_headers(false)
+        .delimiter(locale.delimiter())
+        .from_writer(writer);
+
+    write_csv_header(&mut csv_writer, locale, true)?;
+    for (day, entries) in days {
+        write_csv_items(&mut csv_writer, locale, entries, Some(&locale.date(*day)?))?;
+    }
+
+    csv_writer.flush().context("Failed to flush CSV writer")?;
+    Ok(())
+}
+
+/// Generate CSV content of the defragmented timeline, one row per ticket block
+pub fn generate_defrag_csv_content<W: Write>(
+    entries: &[DefragmentedEntry],
+    writer: W,
+) -> Result<()> {
+    let mut csv_writer = WriterBuilder::new().from_writer(writer);
+
+    for entry in entries {
+        csv_writer
+            .serialize(entry)
+            .context("Failed to write CSV record")?;
+    }
+
+    csv_writer.flush().context("Failed to flush CSV writer")?;
+    Ok(())
+}
+
+/// Generate CSV content of a report, with its column names as header
+pub fn generate_report_csv_content<W: Write>(table: &ReportTable, writer: W) -> Result<()> {
+    let mut csv_writer = WriterBuilder::new().has_headers(false).from_writer(writer);
+
+    csv_writer
+        .write_record(&table.columns)
+        .context("Failed to write CSV header")?;
+    for row in &table.rows {
+        csv_writer
+            .write_record(row)
+            .context("Failed to write CSV record")?;
+    }
+
+    csv_writer.flush().context("Failed to flush CSV writer")?;
+    Ok(())
+}
+
+fn write_csv_items<W: Write>(
+    csv_writer: &mut csv::Writer<W>,
+    locale: &CsvLocale,
+    entries: &[TimeEntry],
+    date: Option<&str>,
+) -> Result<()> {
+    // Filter and process non-zero duration entries
+    entries
+        .iter()
+        .filter(|entry| entry.duration_mins > 0)
+        .try_for_each(|entry| {
+            let (start_time, end_time) = entry.time_span()?;
+
+            write_csv_record(
+                csv_writer,
+                locale,
+                date,
+                start_time,
+                end_time,
+                &entry.project_key,
+                entry.ticket_key.as_deref().unwrap_or_default(),
+                &entry.description,
+                entry.duration_mins as u64 * 60,
+            )
+        })
+}
+
+/// Write the CSV header row with all required columns for LibreOffice Calc
+fn write_csv_header<W: Write>(
+    csv_writer: &mut csv::Writer<W>,
+    locale: &CsvLocale,
+    with_date: bool,
+) -> Result<()> {
+    let date_column = with_date.then_some("date");
+    let columns = date_column.into_iter().chain([
+        "", // empty column
+        "start",
+        "",
+        "",
+        "", // start columns
+        "end",
+        "",
+        "",
+        "",              // end columns
+        "proj",          // project column
+        "tracking code", // ticket column
+        "",
+        "",         // empty columns + description column placeholder
+        "duration", // duration formatted
+        "min",      // duration in minutes
+        "h",        // duration in hours
+    ]);
+    csv_writer
+        .write_record(columns.map(|it| locale.header(it)))
+        .context("Failed to write CSV header")
+}
+
+/// Write a single CSV record for a time entry
+#[allow(clippy::too_many_arguments)]
+fn write_csv_record<W: Write>(
+    csv_writer: &mut csv::Writer<W>,
+    locale: &CsvLocale,
+    date: Option<&str>,
+    start_time: NaiveTime,
+    end_time: NaiveTime,
+    project_key: &str,
+    ticket: &str,
+    description: &str,
+    duration_secs: u64,
+) -> Result<()> {
+    let duration_minutes = duration_secs.div_ceil(60); // Round up to next minute
+    let duration_hours = duration_secs as f64 / 3600.0;
+    let duration_formatted = format_duration_hms(duration_secs);
+
+    // legacy consistency
+    let display_project = if project_key == BREAK_PROJECT_KEY {
+        "Pause"
+    } else {
+        project_key
+    };
+
+    csv_writer
+        .write_record(date.into_iter().chain([
+            "",                                         // empty column
+            &start_time.hour().to_string(),             // start hour
+            &start_time.minute().to_string(),           // start minute
+            &start_time.format("%H:%M:%S").to_string(), // start time formatted
+            "",                                         // empty column
+            &end_time.hour().to_string(),               // end hour
+            &end_time.minute().to_string(),             // end minute
+            &end_time.format("%H:%M:%S").to_string(),   // end time formatted
+            "",                                         // empty column
+            display_project,                            // project key
+            ticket,                                     // ticket number
+            "",                                         // empty column
+            description,                                // description
+            &duration_formatted,                        // duration HH:MM:SS
+            &duration_minutes.to_string(),              // duration in minutes
+            &locale.decimal(duration_hours),            // duration in decimal hours
+        ]))
+        .context("Failed to write CSV record")
+}
+
+/// Format duration in seconds as HH:MM:SS
+fn format_duration_hms(duration_secs: u64) -> String {
+    let hours = duration_secs / 3600;
+    let minutes = (duration_secs % 3600) / 60;
+    let seconds = duration_secs % 60;
+    format!("{hours:02}:{minutes:02}:{seconds:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Create a test TimeEntry with the given parameters, an empty project means the default project
+    fn create_test_item(
+        start_hour: u32,
+        start_minute: u32,
+        duration_minutes: i32,
+        project: &str,
+        ticket: &str,
+        description: &str,
+    ) -> TimeEntry {
+        let project_key = if project.is_empty() {
+            "TEST-PROJECT"
+        } else {
+            project
+        };
+        TimeEntry {
+            timesheet_day: "2025-09-22".to_string(),
+            project_key: project_key.to_string(),
+            ticket_key: Some(ticket.to_string()).filter(|it| !it.is_empty()),
+            description: description.to_string(),
+            ..TimeEntry::sample(
+                &format!("{start_hour:02}:{start_minute:02}"),
+                duration_minutes,
+            )
+        }
+    }
+
+    #[test]
+    fn test_generate_csv_content_basic() {
+        let items = vec![
+            create_test_item(8, 40, 20, "", "SCRUM-17", "post vacation catchup"),
+            create_test_item(9, 0, 15, "", "SCRUM-17", "abst clemens+"),
+        ];
+
+        let mut output = Vec::new();
+        generate_csv_content(&items, &CsvLocale::default(), &mut output).unwrap();
+
+        let csv_string = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = csv_string.lines().collect();
+
+        // Check header
+        assert_eq!(
+            lines[0],
+            ",start,,,,end,,,,proj,tracking code,,,duration,min,h"
+        );
+
+        // Check first data row - should use TEST-PROJECT as default
+        assert!(lines[1].contains("8,40,08:40:00"));
+        assert!(lines[1].contains("9,0,09:00:00"));
+        assert!(lines[1].contains("TEST-PROJECT"));
+        assert!(lines[1].contains("SCRUM-17"));
+        assert!(lines[1].contains("post vacation catchup"));
+        assert!(lines[1].contains("00:20:00"));
+        assert!(lines[1].contains("20"));
+        // Just check that the fractional hour is approximately 1/3
+        assert!(lines[1].contains("0.333333"));
+
+        // Check second data row
+        assert!(lines[2].contains("9,0,09:00:00"));
+        assert!(lines[2].contains("9,15,09:15:00"));
+        assert!(lines[2].contains("TEST-PROJECT"));
+        assert!(lines[2].contains("SCRUM-17"));
+        assert!(lines[2].contains("abst clemens+"));
+        assert!(lines[2].contains("00:15:00"));
+        assert!(lines[2].contains("15"));
+        assert!(lines[2].contains("0.25"));
+    }
+
+    #[test]
+    fn test_generate_csv_content_pause_conversion() {
+        let items = vec![create_test_item(12, 5, 50, "x", "", "lunch break")];
+
+        let mut output = Vec::new();
+        generate_csv_content(&items, &CsvLocale::default(), &mut output).unwrap();
+
+        let csv_string = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = csv_string.lines().collect();
+
+        assert!(lines[1].contains("Pause"));
+        assert!(!lines[1].contains("x,"));
+    }
+
+    #[test]
+    fn test_generate_csv_content_skip_zero_duration() {
+        let items = vec![
+            create_test_item(8, 40, 20, "", "SCRUM-17", "real work"),
+            create_test_item(9, 0, 0, "", "EMPTY-1", "should be skipped"),
+            create_test_item(9, 0, 15, "", "SCRUM-17", "more work"),
+        ];
+
+        let mut output = Vec::new();
+        generate_csv_content(&items, &CsvLocale::default(), &mut output).unwrap();
+
+        let csv_string = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = csv_string.lines().collect();
+
+        // Should have header + 2 data rows (zero duration item skipped)
+        assert_eq!(lines.len(), 3);
+        assert!(!csv_string.contains("should be skipped"));
+        assert!(csv_string.contains("real work"));
+        assert!(csv_string.contains("more work"));
+    }
+
+    #[test]
+    fn test_format_duration_hms() {
+        assert_eq!(format_duration_hms(3661), "01:01:01"); // 1 hour, 1 minute, 1 second
+        assert_eq!(format_duration_hms(3600), "01:00:00"); // 1 hour
+        assert_eq!(format_duration_hms(60), "00:01:00"); // 1 minute
+        assert_eq!(format_duration_hms(1), "00:00:01"); // 1 second
+        assert_eq!(format_duration_hms(0), "00:00:00"); // 0 seconds
+    }
+
+    #[test]
+    fn test_csv_format_structure() {
+        let items = vec![create_test_item(
+            8,
+            40,
+            20,
+            "PROJECT-1",
+            "TICKET-123",
+            "test description",
+        )];
+
+        let mut output = Vec::new();
+        generate_csv_content(&items, &CsvLocale::default(), &mut output).unwrap();
+
+        let csv_string = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = csv_string.lines().collect();
+
+        // Parse the data row and check column count and structure
+        let data_row = lines[1];
+        let columns: Vec<&str> = data_row.split(',').collect();
+
+        // Should have 16 columns total
+        assert_eq!(columns.len(), 16);
+
+        // Check specific column positions
+        assert_eq!(columns[0], ""); // empty
+        assert_eq!(columns[1], "8"); // start hour
+        assert_eq!(columns[2], "40"); // start minute
+        assert_eq!(columns[3], "08:40:00"); // start time
+        assert_eq!(columns[4], ""); // empty
+        assert_eq!(columns[5], "9"); // end hour
+        assert_eq!(columns[6], "0"); // end minute
+        assert_eq!(columns[7], "09:00:00"); // end time
+        assert_eq!(columns[8], ""); // empty
+        assert_eq!(columns[9], "PROJECT-1"); // project
+        assert_eq!(columns[10], "TICKET-123"); // ticket
+        assert_eq!(columns[11], ""); // empty
+        assert_eq!(columns[12], "test description"); // description
+        assert_eq!(columns[13], "00:20:00"); // duration formatted
+        assert_eq!(columns[14], "20"); // duration minutes
+        // Check that fractional hours is approximately correct (20 minutes = 1/3 hour)
+        let hours: f64 = columns[15].parse().unwrap();
+        assert!((hours - 0.3333333333333333).abs() < 0.0001);
+    }
+
+    #[test]
+    fn test_generate_combined_csv_content_prepends_date() {
+        let days = vec![
+            (
+                time::macros::date!(2025 - 09 - 22),
+                vec![create_test_item(8, 40, 20, "W", "SCRUM-17", "first day")],
+            ),
+            (
+                time::macros::date!(2025 - 09 - 23),
+                vec![
+                    create_test_item(9, 0, 15, "W", "SCRUM-18", "second day"),
+                    create_test_item(9, 15, 0, "W", "", "not yet filled"),
+                ],
+            ),
+        ];
+
+        let mut output = Vec::new();
+        generate_combined_csv_content(&days, &CsvLocale::default(), &mut output).unwrap();
+
+        let csv_string = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = csv_string.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert_eq!(
+            lines[0],
+            "date,,start,,,,end,,,,proj,tracking code,,,duration,min,h"
+        );
+        assert!(lines[1].starts_with("2025-09-22,,8,40,08:40:00"));
+        assert!(lines[2].starts_with("2025-09-23,,9,0,09:00:00"));
+        assert_eq!(lines[2].split(',').count(), 17);
+    }
+
+    #[test]
+    fn test_generate_combined_csv_content_localized() {
+        let days = vec![(
+            time::macros::date!(2025 - 09 - 22),
+            vec![create_test_item(8, 40, 15, "W", "SCRUM-17", "standup")],
+        )];
+        let locale = CsvLocale::from_config(&crate::config::ExportLocaleConfig {
+            language: crate::config::ExportLanguage::De,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let mut output = Vec::new();
+        generate_combined_csv_content(&days, &locale, &mut output).unwrap();
+
+        let csv_string = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = csv_string.lines().collect();
+        assert_eq!(
+            lines[0],
+            "Datum;;Beginn;;;;Ende;;;;Projekt;Ticket;;;Dauer;Min;Std"
+        );
+        assert!(lines[1].starts_with("22.09.2025;;8;40;08:40:00"));
+        assert!(lines[1].ends_with(";00:15:00;15;0,25"));
+    }
+
+    #[test]
+    fn test_generate_report_csv_content() {
+        let table = ReportTable {
+            columns: vec!["month".to_string(), "hours".to_string()],
+            rows: vec![
+                vec!["2025-09".to_string(), "12.5".to_string()],
+                vec!["2025-10".to_string(), String::new()],
+            ],
+        };
+
+        let mut output = Vec::new();
+        generate_report_csv_content(&table, &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "month,hours\n2025-09,12.5\n2025-10,\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_defrag_csv_content() {
+        let entries = vec![DefragmentedEntry {
+            project_key: "W".to_string(),
+            ticket_key: "SCRUM-17".to_string(),
+            start_time: "08:40".to_string(),
+            end_time: "09:15".to_string(),
+        }];
+
+        let mut output = Vec::new();
+        generate_defrag_csv_content(&entries, &mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "project_key,ticket_key,start_time,end_time\nW,SCRUM-17,08:40,09:15\n"
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use chrono::{NaiveTime, TimeDelta};
+use itertools::Itertools;
+
+use super::booking_text::format_duration;
+use crate::{config::Config, persist::TimeEntry, shared::BREAK_PROJECT_KEY};
+
+/// Layout of [generate_day_table]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableStyle {
+    /// Aligned columns for reading in a terminal
+    Text,
+    /// GitHub-flavoured Markdown, e.g. for pasting into notes
+    Markdown,
+}
+
+const HEADER: [&str; 7] = [
+    "Start",
+    "End",
+    "Duration",
+    "Project",
+    "Ticket",
+    "Work type",
+    "Description",
+];
+
+/// The entries of a day in order of their start time, followed by the total without breaks.
+/// Entries without duration are left out.
+pub fn generate_day_table(entries: &[TimeEntry], style: TableStyle, config: &Config) -> String {
+    let rows = entries
+        .iter()
+        .filter(|entry| entry.duration_mins > 0)
+        .sorted_by(|a, b| a.start_time.cmp(&b.start_time))
+        .map(|entry| row_of(entry, style, config))
+        .collect_vec();
+    let widths = (0..HEADER.len())
+        .map(|col| {
+            rows.iter()
+                .map(|row| row[col].chars().count())
+                .chain([HEADER[col].len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect_vec();
+    let header = HEADER.map(str::to_string);
+
+    let mut lines = vec![format_row(&header, &widths, style)];
+    lines.push(match style {
+        TableStyle::Text => widths.iter().map(|it| "-".repeat(*it)).join("  "),
+        TableStyle::Markdown => {
+            format!("|{}|", widths.iter().map(|it| "-".repeat(it + 2)).join("|"))
+        }
+    });
+    lines.extend(rows.iter().map(|row| format_row(row, &widths, style)));
+
+    let worked = entries
+        .iter()
+        .filter(|it| it.project_key != BREAK_PROJECT_KEY);
+    let total: i32 = worked.clone().map(|it| it.duration_mins).sum();
+    let billable: i32 = worked
+        .filter(|it| it.billable)
+        .map(|it| it.duration_mins)
+        .sum();
+    let total = format!(
+        "Total {}, billable {}",
+        format_duration(total),
+        format_duration(billable)
+    );
+    lines.push(String::new());
+    lines.push(match style {
+        TableStyle::Text => total,
+        TableStyle::Markdown => format!("**{total}**"),
+    });
+    lines.join("\n") + "\n"
+}
+
+fn row_of(entry: &TimeEntry, style: TableStyle, config: &Config) -> [String; 7] {
+    let start = NaiveTime::parse_from_str(&entry.start_time, "%H:%M").ok();
+    let end = start.map_or("?".to_string(), |it| {
+        (it + TimeDelta::minutes(entry.duration_mins as i64))
+            .format("%H:%M")
+            .to_string()
+    });
+    let project = if entry.project_key == BREAK_PROJECT_KEY {
+        "break"
+    } else {
+        &entry.project_key
+    };
+    let work_type = entry
+        .work_type
+        .as_deref()
+        .map(|it| config.work_type_label(it))
+        .unwrap_or_default();
+    let billable = if entry.billable { " $" } else { "" };
+    let cells = [
+        entry.start_time.clone(),
+        end,
+        format_duration(entry.duration_mins),
+        project.to_string(),
+        entry.ticket_key.clone().unwrap_or_default(),
+        work_type.to_string(),
+        format!("{}{billable}", entry.description.trim()),
+    ];
+    match style {
+        TableStyle::Text => cells,
+        TableStyle::Markdown => cells.map(|it| it.replace('|', "\\|")),
+    }
+}
+
+fn format_row(cells: &[String; 7], widths: &[usize], style: TableStyle) -> String {
+    let mut padded = cells
+        .iter()
+        .zip(widths)
+        .map(|(cell, width)| format!("{cell:<width$}"));
+    match style {
+        TableStyle::Text => padded.join("  ").trim_end().to_string(),
+        TableStyle::Markdown => format!("| {} |", padded.join(" | ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(start_time: &str, mins: i32, project: &str, description: &str) -> TimeEntry {
+        TimeEntry {
+            id: start_time.to_string(),
+            timesheet_day: "2025-09-22".to_string(),
+            billable: project == "W",
+            project_key: project.to_string(),
+            ticket_key: (project == "W").then(|| "SCRUM-17".to_string()),
+            description: description.to_string(),
+            ..TimeEntry::sample(start_time, mins)
+        }
+    }
+
+    #[test]
+    fn test_aligned_text_and_markdown() {
+        let entries = vec![
+            entry("10:00", 30, BREAK_PROJECT_KEY, ""),
+            entry("09:00", 60, "W", "fix login | logout"),
+            entry("10:30", 90, "E", "planning"),
+            entry("12:00", 0, "E", ""),
+        ];
+        let config = Config::default();
+
+        assert_eq!(
+            generate_day_table(&entries, TableStyle::Text, &config),
+            "\
+Start  End    Duration  Project  Ticket    Work type  Description
+-----  -----  --------  -------  --------  ---------  --------------------
+09:00  10:00  1h        W        SCRUM-17             fix login | logout $
+10:00  10:30  30m       break
+10:30  12:00  1h30m     E                             planning
+
+Total 2h30m, billable 1h
+"
+        );
+        assert_eq!(
+            generate_day_table(&entries[1..2], TableStyle::Markdown, &config),
+            "\
+| Start | End   | Duration | Project | Ticket   | Work type | Description           |
+|-------|-------|----------|---------|----------|-----------|-----------------------|
+| 09:00 | 10:00 | 1h       | W       | SCRUM-17 |           | fix login \\| logout $ |
+
+**Total 1h, billable 1h**
+"
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use std::{path::Path, process::Command};
+
+use color_eyre::{
+    Result,
+    eyre::{Context, bail},
+};
+use time::{Date, format_description::FormatItem, macros::format_description};
+use tracing::{info, warn};
+
+const ISO_DAY: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
+/// Runs each hook as a shell command with the exported file as `$1` and the day as `$2`.
+/// Their output goes to the log, the first one that fails stops the rest.
+pub fn run(hooks: &[String], path: &Path, day: Date) -> Result<()> {
+    let iso_day = day.format(ISO_DAY)?;
+    for hook in hooks {
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(hook)
+            .arg("sh")
+            .arg(path)
+            .arg(&iso_day)
+            .output()
+            .wrap_err_with(|| format!("running export hook {hook}"))?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stdout.trim().is_empty() {
+            info!("Export hook {hook} printed: {}", stdout.trim_end());
+        }
+        if !stderr.trim().is_empty() {
+            warn!("Export hook {hook} reported: {}", stderr.trim_end());
+        }
+        if !output.status.success() {
+            bail!("export hook {hook} failed: {}", output.status);
+        }
+        info!("Export hook {hook} done for {}", path.display());
+    }
+    Ok(())
+}
+
+/// [run]s the hooks on a thread of their own, since uploads may take a while, and passes the
+/// error message to `on_failure`
+pub fn spawn(
+    hooks: Vec<String>,
+    path: impl AsRef<Path> + Send + 'static,
+    day: Date,
+    on_failure: impl FnOnce(String) + Send + 'static,
+) {
+    if hooks.is_empty() {
+        return;
+    }
+    std::thread::spawn(move || {
+        if let Err(err) = run(&hooks, path.as_ref(), day) {
+            warn!(
+                "Export hooks for {} failed: {err:?}",
+                path.as_ref().display()
+            );
+            on_failure(err.to_string());
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    #[test]
+    fn test_passes_path_and_day_and_stops_at_failure() {
+        let dir = std::env::temp_dir().join(format!("sheet-shark-hooks-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let marker = dir.join("marker");
+        let hooks = vec![
+            format!("echo \"$1 $2\" > {}", marker.display()),
+            "echo oops >&2; exit 3".to_string(),
+            format!("rm {}", marker.display()),
+        ];
+
+        let err = run(
+            &hooks,
+            Path::new("/tmp/2025-09-22.csv"),
+            date!(2025 - 09 - 22),
+        )
+        .unwrap_err();
+
+        assert!(err.to_string().contains("exit status: 3"), "{err}");
+        assert_eq!(
+            std::fs::read_to_string(&marker).unwrap(),
+            "/tmp/2025-09-22.csv 2025-09-22\n"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
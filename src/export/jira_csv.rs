@@ -0,0 +1,335 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Write,
+};
+
+use color_eyre::{Result, eyre::Context};
+use csv::WriterBuilder;
+use itertools::Itertools;
+use time::Date;
+
+use super::booking_text::generate_worklog_comment;
+use crate::{
+    config::Config,
+    persist::TimeEntry,
+    shared::{defrag, summary::TimesheetSummary},
+};
+
+/// Columns mapped to the worklog fields when importing the CSV in Jira
+const HEADER: [&str; 4] = [
+    "Issue Key",
+    "Date Started",
+    "Time Spent (seconds)",
+    "Comment",
+];
+
+/// Worklogs for Jira's CSV import, one row per block of the defragmented days. Time without
+/// ticket can't be logged and is left out, the rest is rounded to
+/// [crate::config::ExportConfig::jira_rounding_mins], except for the time of
+/// [TimeEntry::exact] entries. Comments follow [crate::config::ExportConfig::jira_comment].
+pub fn generate_jira_csv_content<W: Write>(
+    days: &[(Date, Vec<TimeEntry>)],
+    config: &Config,
+    writer: W,
+) -> Result<()> {
+    let mut csv_writer = WriterBuilder::new().has_headers(false).from_writer(writer);
+    csv_writer
+        .write_record(HEADER)
+        .context("Failed to write CSV header")?;
+
+    for (day, entries) in days {
+        let summary = TimesheetSummary::new(entries.clone(), config);
+        // Exact time of each ticket, taken up by its blocks in order
+        let mut exact_mins: HashMap<(String, String), u32> = HashMap::new();
+        for entry in summary.entries.iter().filter(|it| it.exact) {
+            let ticket_key = entry.ticket_key.clone().unwrap_or_else(|| "-".into());
+            *exact_mins
+                .entry((entry.project_key.clone(), ticket_key))
+                .or_default() += entry.duration_mins.max(0) as u32;
+        }
+        for block in defrag::calculate(&summary, config.export.defrag_strategy) {
+            if block.ticket_key == "-" {
+                continue;
+            }
+            let block_mins = block.duration_mins();
+            let block_exact_mins = exact_mins
+                .get_mut(&(block.project_key.clone(), block.ticket_key.clone()))
+                .map(|remaining| {
+                    let taken = (*remaining).min(block_mins);
+                    *remaining -= taken;
+                    taken
+                })
+                .unwrap_or_default();
+            let minutes = round_block(
+                block_mins,
+                block_exact_mins,
+                config.export.jira_rounding_mins,
+            );
+            if minutes == 0 {
+                continue;
+            }
+            let ticket_entries = summary
+                .entries
+                .iter()
+                .filter(|it| it.project_key == block.project_key)
+                .filter(|it| it.ticket_key.as_deref() == Some(block.ticket_key.as_str()))
+                .collect_vec();
+            let comment = generate_worklog_comment(
+                &config.export.jira_comment,
+                &block.ticket_key,
+                *day,
+                &ticket_entries,
+            );
+            csv_writer
+                .write_record([
+                    block.ticket_key.clone(),
+                    format!("{day} {}", block.start_time),
+                    (minutes * 60).to_string(),
+                    comment,
+                ])
+                .context("Failed to write CSV record")?;
+        }
+    }
+
+    csv_writer.flush().context("Failed to flush CSV writer")?;
+    Ok(())
+}
+
+/// Time of a ticket over several days, logged as one worklog
+#[derive(Debug)]
+pub struct WeekWorklog<'a> {
+    pub project_key: String,
+    pub ticket_key: String,
+    /// First day with time on the ticket
+    pub day: Date,
+    /// Start of the first block of the ticket on that day
+    pub start_time: String,
+    pub minutes: u32,
+    /// Entries on the ticket of all days
+    pub entries: Vec<&'a TimeEntry>,
+}
+
+/// One worklog per ticket for the days, like the rows of [generate_jira_csv_content] but
+/// summed up. Each is logged on the first day with time on the ticket, at the start of its
+/// first block in the day defragmented by [crate::config::ExportConfig::defrag_strategy].
+/// The sum is rounded once, except for the time of [TimeEntry::exact] entries.
+pub fn week_worklogs<'a>(
+    days: &'a [(Date, TimesheetSummary)],
+    config: &Config,
+) -> Vec<WeekWorklog<'a>> {
+    // By ticket first, in the order the worklogs are listed
+    let mut weekly: BTreeMap<(String, String), WeekWorklog> = BTreeMap::new();
+    let mut exact_mins: HashMap<(String, String), u32> = HashMap::new();
+    for (day, summary) in days {
+        for entry in summary.entries.iter().filter(|it| it.exact) {
+            let ticket_key = entry.ticket_key.clone().unwrap_or_else(|| "-".into());
+            *exact_mins
+                .entry((ticket_key, entry.project_key.clone()))
+                .or_default() += entry.duration_mins.max(0) as u32;
+        }
+        for block in defrag::calculate(summary, config.export.defrag_strategy) {
+            if block.ticket_key == "-" {
+                continue;
+            }
+            let key = (block.ticket_key.clone(), block.project_key.clone());
+            let worklog = weekly.entry(key).or_insert_with(|| WeekWorklog {
+                project_key: block.project_key.clone(),
+                ticket_key: block.ticket_key.clone(),
+                day: *day,
+                start_time: block.start_time.clone(),
+                minutes: 0,
+                entries: vec![],
+            });
+            worklog.minutes += block.duration_mins();
+        }
+        for ((ticket_key, project_key), worklog) in weekly.iter_mut() {
+            worklog.entries.extend(
+                summary
+                    .entries
+                    .iter()
+                    .filter(|it| &it.project_key == project_key)
+                    .filter(|it| it.ticket_key.as_ref() == Some(ticket_key)),
+            );
+        }
+    }
+    weekly
+        .into_iter()
+        .map(|(key, worklog)| {
+            let exact = exact_mins.get(&key).copied().unwrap_or_default();
+            let minutes = round_block(
+                worklog.minutes,
+                exact.min(worklog.minutes),
+                config.export.jira_rounding_mins,
+            );
+            WeekWorklog { minutes, ..worklog }
+        })
+        .filter(|it| it.minutes > 0)
+        .collect()
+}
+
+/// Nearest multiple of `step`, but at least one step so that short work is still logged
+fn round_minutes(minutes: u32, step: u32) -> u32 {
+    if step == 0 || minutes == 0 {
+        return minutes;
+    }
+    ((minutes + step / 2) / step).max(1) * step
+}
+
+/// Rounds the minutes of a block except for the exact ones among them. These already log the
+/// work, so the rest may round down to nothing.
+fn round_block(minutes: u32, exact_mins: u32, step: u32) -> u32 {
+    if exact_mins == 0 {
+        return round_minutes(minutes, step);
+    }
+    let rest = minutes - exact_mins;
+    let rounded_rest = (rest + step / 2)
+        .checked_div(step)
+        .map_or(rest, |it| it * step);
+    exact_mins + rounded_rest
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+    use crate::{config::DefragStrategy, shared::BREAK_PROJECT_KEY};
+
+    fn entry(start_time: &str, mins: i32, project: &str, ticket: Option<&str>) -> TimeEntry {
+        TimeEntry {
+            id: start_time.to_string(),
+            timesheet_day: "2025-09-22".to_string(),
+            project_key: project.to_string(),
+            ticket_key: ticket.map(str::to_string),
+            description: format!("work at {start_time}"),
+            ..TimeEntry::sample(start_time, mins)
+        }
+    }
+
+    #[test]
+    fn test_rounds_defragmented_tickets_and_leaves_out_time_without_ticket() {
+        let mut config = Config::default();
+        config.export.jira_comment = "{ticket}: {descriptions}".into();
+        let entries = vec![
+            entry("09:00", 20, "W", Some("SCRUM-1")),
+            entry("09:20", 10, "W", None),
+            entry("09:30", 30, BREAK_PROJECT_KEY, None),
+            entry("10:00", 52, "W", Some("SCRUM-2")),
+            entry("10:52", 5, "W", Some("SCRUM-1")),
+        ];
+        let day = date!(2025 - 09 - 22);
+        let mut content = vec![];
+        generate_jira_csv_content(&[(day, entries)], &config, &mut content).unwrap();
+
+        assert_eq!(
+            String::from_utf8(content).unwrap(),
+            "Issue Key,Date Started,Time Spent (seconds),Comment\n\
+             SCRUM-1,2025-09-22 09:00,1800,\"SCRUM-1: work at 09:00, work at 10:52\"\n\
+             SCRUM-2,2025-09-22 10:05,2700,SCRUM-2: work at 10:00\n"
+        );
+    }
+
+    #[test]
+    fn test_exact_entries_are_not_rounded() {
+        let mut config = Config::default();
+        config.export.jira_comment = "{ticket}".into();
+        let mut legal_break = entry("09:00", 17, "W", Some("HR-1"));
+        legal_break.exact = true;
+        let entries = vec![
+            legal_break,
+            entry("09:17", 20, "W", Some("SCRUM-1")),
+            entry("09:37", 4, "W", Some("HR-1")),
+        ];
+        let day = date!(2025 - 09 - 22);
+        let mut content = vec![];
+        generate_jira_csv_content(&[(day, entries)], &config, &mut content).unwrap();
+
+        assert_eq!(
+            String::from_utf8(content).unwrap(),
+            "Issue Key,Date Started,Time Spent (seconds),Comment\n\
+             HR-1,2025-09-22 09:00,1020,HR-1\n\
+             SCRUM-1,2025-09-22 09:21,900,SCRUM-1\n"
+        );
+    }
+
+    #[test]
+    fn test_week_worklogs_round_the_sum_of_the_days() {
+        let mut config = Config::default();
+        config.export.defrag_strategy = DefragStrategy::ByTicket;
+        let mut legal_break = entry("09:00", 17, "W", Some("HR-1"));
+        legal_break.exact = true;
+        let monday = vec![
+            entry("09:00", 20, "W", Some("SCRUM-2")),
+            entry("09:20", 20, "W", Some("SCRUM-1")),
+            entry("09:40", 10, "W", None),
+        ];
+        let tuesday = vec![
+            legal_break,
+            entry("09:17", 20, "W", Some("SCRUM-1")),
+            entry("09:37", 4, "W", Some("HR-1")),
+        ];
+        let days = [
+            (
+                date!(2025 - 09 - 22),
+                TimesheetSummary::new(monday, &config),
+            ),
+            (
+                date!(2025 - 09 - 23),
+                TimesheetSummary::new(tuesday, &config),
+            ),
+        ];
+
+        let worklogs = week_worklogs(&days, &config)
+            .into_iter()
+            .map(|it| {
+                (
+                    it.ticket_key,
+                    it.day,
+                    it.start_time,
+                    it.minutes,
+                    it.entries.len(),
+                )
+            })
+            .collect_vec();
+
+        assert_eq!(
+            worklogs,
+            vec![
+                ("HR-1".into(), date!(2025 - 09 - 23), "09:00".into(), 17, 2),
+                // 40 minutes, each day on its own would have booked 15 + 15
+                (
+                    "SCRUM-1".into(),
+                    date!(2025 - 09 - 22),
+                    "09:20".into(),
+                    45,
+                    2
+                ),
+                (
+                    "SCRUM-2".into(),
+                    date!(2025 - 09 - 22),
+                    "09:00".into(),
+                    15,
+                    1
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_round_block_keeps_exact_minutes() {
+        assert_eq!(round_block(17, 17, 15), 17);
+        assert_eq!(round_block(25, 17, 15), 32);
+        assert_eq!(round_block(21, 17, 15), 17);
+        assert_eq!(round_block(7, 0, 15), 15);
+        assert_eq!(round_block(21, 17, 0), 21);
+    }
+
+    #[test]
+    fn test_round_minutes_keeps_short_work() {
+        assert_eq!(round_minutes(7, 15), 15);
+        assert_eq!(round_minutes(22, 15), 15);
+        assert_eq!(round_minutes(23, 15), 30);
+        assert_eq!(round_minutes(23, 0), 23);
+        assert_eq!(round_minutes(0, 15), 0);
+    }
+}
@@ -0,0 +1,493 @@
+use std::collections::HashMap;
+
+use chrono::FixedOffset;
+use color_eyre::{Result, eyre::Context};
+use itertools::Itertools;
+use serde::Serialize;
+use time::Date;
+
+use super::ExportMeta;
+use crate::{
+    config::Config,
+    persist::{EntryLink, TimeEntry},
+    shared::BREAK_PROJECT_KEY,
+};
+
+#[derive(Serialize)]
+struct JsonExport {
+    meta: JsonMeta,
+    projects: HashMap<String, JsonProject>,
+    entries: Vec<JsonEntry>,
+}
+
+#[derive(Serialize)]
+struct JsonMeta {
+    #[serde(flatten)]
+    export: ExportMeta,
+    day: String,
+    exported_at: String,
+    start_time: Option<String>,
+    end_time: Option<String>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    notes: String,
+    /// Where the day was recorded, like `+02:00`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    utc_offset: Option<String>,
+    billable_mins: u64,
+    non_billable_mins: u64,
+    /// Working time by work type key
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    work_type_mins: HashMap<String, u64>,
+    /// Time away by absence key, neither billable nor non-billable work
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    absence_mins: HashMap<String, u64>,
+}
+
+#[derive(Serialize)]
+enum ProjectKind {
+    AdHoc,
+    Configured,
+    SpecialBreak,
+    Absence,
+}
+
+#[derive(Serialize)]
+struct JsonProject {
+    internal_name: String,
+    kind: ProjectKind,
+}
+
+#[derive(Serialize)]
+struct JsonEntry {
+    start: String,
+    end: String,
+    project_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ticket: Option<String>,
+    duration_mins: u64,
+    description: String,
+    billable: bool,
+    /// Must be kept to the minute by whatever rounds the exported times
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    exact: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    work_type: Option<String>,
+    /// Keys of the configured flags
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    flags: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    links: Vec<String>,
+    /// Key of the absence, for entries on its project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    absence: Option<String>,
+}
+
+pub fn generate_json_content(
+    entries: &[TimeEntry],
+    links: &[EntryLink],
+    day: Date,
+    notes: &str,
+    utc_offset_mins: Option<i32>,
+    export_meta: &ExportMeta,
+    config: &Config,
+) -> Result<String> {
+    let mut meta = JsonMeta {
+        export: export_meta.clone(),
+        day: day.to_string(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        start_time: entries.first().map(|it| it.start_time.clone()),
+        end_time: entries.last().map(|it| it.start_time.clone()),
+        notes: notes.to_string(),
+        utc_offset: utc_offset_mins
+            .and_then(|mins| FixedOffset::east_opt(mins * 60))
+            .map(|it| it.to_string()),
+        billable_mins: 0,
+        non_billable_mins: 0,
+        work_type_mins: HashMap::new(),
+        absence_mins: HashMap::new(),
+    };
+
+    let used_projects: std::collections::HashSet<String> = entries
+        .iter()
+        .filter(|entry| entry.duration_mins > 0)
+        .map(|entry| entry.project_key.clone())
+        .collect();
+
+    let projects = used_projects
+        .into_iter()
+        .map(|project_key| {
+            let absence = config
+                .absence_of_project(&project_key)
+                .map(|it| &config.absences[it]);
+            let config = config.projects.get(&project_key);
+            let internal_name = match (config, absence) {
+                (Some(config), _) => config.internal_name.clone(),
+                (None, Some(absence)) => absence.label.clone(),
+                (None, None) => project_key.clone(),
+            };
+            let kind = match config {
+                _ if project_key == BREAK_PROJECT_KEY => ProjectKind::SpecialBreak,
+                _ if absence.is_some() => ProjectKind::Absence,
+                Some(_) => ProjectKind::Configured,
+                _ => ProjectKind::AdHoc,
+            };
+            let project_info = JsonProject {
+                internal_name,
+                kind,
+            };
+            (project_key, project_info)
+        })
+        .collect();
+
+    let entries: Vec<JsonEntry> = entries
+        .iter()
+        .filter(|entry| entry.duration_mins > 0)
+        .map(|entry| {
+            let (start_time, end_time) = entry.time_span()?;
+
+            Ok(JsonEntry {
+                start: start_time.format("%H:%M").to_string(),
+                end: end_time.format("%H:%M").to_string(),
+                project_key: entry.project_key.clone(),
+                ticket: entry.ticket_key.clone().filter(|it| !it.is_empty()),
+                duration_mins: entry.duration_mins as u64,
+                description: entry.description.clone(),
+                billable: entry.billable,
+                exact: entry.exact,
+                work_type: entry.work_type.clone(),
+                flags: entry.flags.split_whitespace().map(str::to_string).collect(),
+                links: links
+                    .iter()
+                    .filter(|it| it.entry_id == entry.id)
+                    .sorted_by_key(|it| it.position)
+                    .map(|it| it.url.clone())
+                    .collect(),
+                absence: config
+                    .absence_of_project(&entry.project_key)
+                    .map(str::to_string),
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    for entry in &entries {
+        if let Some(absence) = &entry.absence {
+            *meta.absence_mins.entry(absence.clone()).or_default() += entry.duration_mins;
+        }
+    }
+    let worked = entries
+        .iter()
+        .filter(|entry| entry.project_key != BREAK_PROJECT_KEY && entry.absence.is_none());
+    let (billable, non_billable): (Vec<_>, Vec<_>) =
+        worked.clone().partition(|entry| entry.billable);
+    meta.billable_mins = billable.iter().map(|it| it.duration_mins).sum();
+    meta.non_billable_mins = non_billable.iter().map(|it| it.duration_mins).sum();
+    for entry in worked {
+        if let Some(work_type) = &entry.work_type {
+            *meta.work_type_mins.entry(work_type.clone()).or_default() += entry.duration_mins;
+        }
+    }
+
+    let json_export = JsonExport {
+        meta,
+        projects,
+        entries,
+    };
+
+    serde_json::to_string_pretty(&json_export).context("Failed to serialize JSON export")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use time::macros::date;
+
+    fn test_config() -> Config {
+        let mut projects = std::collections::HashMap::new();
+        projects.insert(
+            "TEST-PROJECT".to_string(),
+            crate::config::ProjectConfig {
+                internal_name: "Test Project".to_string(),
+                jira_url: Some("https://test.atlassian.net".to_string()),
+                ..Default::default()
+            },
+        );
+        projects.insert(
+            "W".to_string(),
+            crate::config::ProjectConfig {
+                internal_name: "Work Project".to_string(),
+                jira_url: None,
+                ..Default::default()
+            },
+        );
+
+        Config {
+            default_project_key: "TEST-PROJECT".to_string(),
+            projects,
+            ..Default::default()
+        }
+    }
+
+    fn test_meta() -> ExportMeta {
+        ExportMeta {
+            app_version: "1.2.3".to_string(),
+            schema_version: "20261015140000".to_string(),
+            profile: Some("acme".to_string()),
+            start_rounding_mins: 5,
+        }
+    }
+
+    /// An empty project means the default project
+    fn create_test_item(
+        start_hour: u32,
+        start_minute: u32,
+        duration_minutes: i32,
+        project: &str,
+        ticket: &str,
+        description: &str,
+    ) -> TimeEntry {
+        let project_key = if project.is_empty() {
+            "TEST-PROJECT"
+        } else {
+            project
+        };
+        TimeEntry {
+            timesheet_day: "2025-09-22".to_string(),
+            project_key: project_key.to_string(),
+            ticket_key: Some(ticket.to_string()).filter(|it| !it.is_empty()),
+            description: description.to_string(),
+            ..TimeEntry::sample(
+                &format!("{start_hour:02}:{start_minute:02}"),
+                duration_minutes,
+            )
+        }
+    }
+
+    #[test]
+    fn test_generate_json_content_basic() {
+        let config = test_config();
+
+        let items = vec![
+            create_test_item(8, 40, 20, "", "SCRUM-17", "post vacation catchup"),
+            create_test_item(9, 0, 15, "W", "TICKET-123", "work task"),
+        ];
+
+        let day = date!(2025 - 09 - 22);
+        let json_content =
+            generate_json_content(&items, &[], day, "", None, &test_meta(), &config).unwrap();
+
+        // Parse the JSON to verify structure
+        let json_value: serde_json::Value = serde_json::from_str(&json_content).unwrap();
+
+        // Check meta
+        assert_eq!(json_value["meta"]["day"], "2025-09-22");
+        assert_eq!(json_value["meta"]["app_version"], "1.2.3");
+        assert_eq!(json_value["meta"]["schema_version"], "20261015140000");
+        assert_eq!(json_value["meta"]["profile"], "acme");
+        assert_eq!(json_value["meta"]["start_rounding_mins"], 5);
+        assert!(json_value["meta"]["exported_at"].is_string());
+        assert!(json_value["meta"]["notes"].is_null());
+
+        // Check projects
+        assert!(json_value["projects"]["TEST-PROJECT"].is_object());
+        assert_eq!(
+            json_value["projects"]["TEST-PROJECT"]["internal_name"],
+            "Test Project"
+        );
+        assert_eq!(json_value["projects"]["TEST-PROJECT"]["kind"], "Configured");
+
+        assert!(json_value["projects"]["W"].is_object());
+        assert_eq!(json_value["projects"]["W"]["internal_name"], "Work Project");
+        assert_eq!(json_value["projects"]["W"]["kind"], "Configured");
+
+        // Check entries
+        let entries = json_value["entries"].as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+
+        // First entry
+        assert_eq!(entries[0]["start"], "08:40");
+        assert_eq!(entries[0]["end"], "09:00");
+        assert_eq!(entries[0]["project_key"], "TEST-PROJECT");
+        assert_eq!(entries[0]["ticket"], "SCRUM-17");
+        assert_eq!(entries[0]["duration_mins"], 20);
+        assert_eq!(entries[0]["description"], "post vacation catchup");
+
+        // Second entry
+        assert_eq!(entries[1]["start"], "09:00");
+        assert_eq!(entries[1]["end"], "09:15");
+        assert_eq!(entries[1]["project_key"], "W");
+        assert_eq!(entries[1]["ticket"], "TICKET-123");
+        assert_eq!(entries[1]["duration_mins"], 15);
+        assert_eq!(entries[1]["description"], "work task");
+    }
+
+    #[test]
+    fn test_generate_json_content_pause_conversion() {
+        let config = test_config();
+
+        let items = vec![create_test_item(12, 5, 50, "x", "", "lunch break")];
+
+        let day = date!(2025 - 09 - 22);
+        let json_content =
+            generate_json_content(&items, &[], day, "", None, &test_meta(), &config).unwrap();
+
+        let json_value: serde_json::Value = serde_json::from_str(&json_content).unwrap();
+        let entries = json_value["entries"].as_array().unwrap();
+
+        assert_eq!(entries[0]["project_key"], "x");
+        assert!(entries[0]["ticket"].is_null());
+    }
+
+    #[test]
+    fn test_generate_json_content_marks_absences() {
+        let mut config = test_config();
+        config.absences.insert(
+            "sick".to_string(),
+            crate::config::AbsenceConfig {
+                label: "Sick".to_string(),
+                project: "SICK".to_string(),
+                duration: "8h".to_string(),
+            },
+        );
+        let items = vec![
+            create_test_item(8, 0, 240, "SICK", "", "Sick"),
+            create_test_item(12, 0, 60, "W", "TICKET-123", "work task"),
+        ];
+
+        let day = date!(2025 - 09 - 22);
+        let json_content =
+            generate_json_content(&items, &[], day, "", None, &test_meta(), &config).unwrap();
+
+        let json_value: serde_json::Value = serde_json::from_str(&json_content).unwrap();
+        assert_eq!(json_value["projects"]["SICK"]["internal_name"], "Sick");
+        assert_eq!(json_value["projects"]["SICK"]["kind"], "Absence");
+        assert_eq!(json_value["entries"][0]["absence"], "sick");
+        assert!(json_value["entries"][1]["absence"].is_null());
+        assert_eq!(json_value["meta"]["absence_mins"]["sick"], 240);
+        assert_eq!(json_value["meta"]["non_billable_mins"], 60);
+    }
+
+    #[test]
+    fn test_generate_json_content_skip_zero_duration() {
+        let config = test_config();
+
+        let items = vec![
+            create_test_item(8, 40, 20, "", "SCRUM-17", "real work"),
+            create_test_item(9, 0, 0, "", "EMPTY-1", "should be skipped"),
+            create_test_item(9, 0, 15, "", "SCRUM-17", "more work"),
+        ];
+
+        let day = date!(2025 - 09 - 22);
+        let json_content =
+            generate_json_content(&items, &[], day, "", None, &test_meta(), &config).unwrap();
+
+        let json_value: serde_json::Value = serde_json::from_str(&json_content).unwrap();
+        let entries = json_value["entries"].as_array().unwrap();
+
+        // Should have 2 entries (zero duration item skipped)
+        assert_eq!(entries.len(), 2);
+        assert!(!json_content.contains("should be skipped"));
+    }
+
+    #[test]
+    fn test_generate_json_content_includes_notes() {
+        let config = test_config();
+
+        let items = vec![create_test_item(8, 0, 30, "", "SCRUM-17", "incident")];
+
+        let day = date!(2025 - 09 - 22);
+        let json_content =
+            generate_json_content(&items, &[], day, "on-call", None, &test_meta(), &config)
+                .unwrap();
+
+        let json_value: serde_json::Value = serde_json::from_str(&json_content).unwrap();
+        assert_eq!(json_value["meta"]["notes"], "on-call");
+        assert!(json_value["meta"].get("utc_offset").is_none());
+    }
+
+    #[test]
+    fn test_generate_json_content_includes_utc_offset() {
+        let config = test_config();
+
+        let items = vec![create_test_item(8, 0, 30, "", "SCRUM-17", "flight")];
+
+        let day = date!(2025 - 09 - 22);
+        let json_content =
+            generate_json_content(&items, &[], day, "", Some(540), &test_meta(), &config).unwrap();
+
+        let json_value: serde_json::Value = serde_json::from_str(&json_content).unwrap();
+        assert_eq!(json_value["meta"]["utc_offset"], "+09:00");
+    }
+
+    #[test]
+    fn test_generate_json_content_billable_totals() {
+        let config = test_config();
+
+        let mut billable_item = create_test_item(8, 0, 45, "W", "TICKET-1", "client work");
+        billable_item.billable = true;
+        let items = vec![
+            billable_item,
+            create_test_item(8, 45, 15, "", "SCRUM-17", "standup"),
+            create_test_item(9, 0, 30, "x", "", "break"),
+        ];
+
+        let day = date!(2025 - 09 - 22);
+        let json_content =
+            generate_json_content(&items, &[], day, "", None, &test_meta(), &config).unwrap();
+
+        let json_value: serde_json::Value = serde_json::from_str(&json_content).unwrap();
+        assert_eq!(json_value["meta"]["billable_mins"], 45);
+        assert_eq!(json_value["meta"]["non_billable_mins"], 15);
+        assert_eq!(json_value["entries"][0]["billable"], true);
+        assert_eq!(json_value["entries"][1]["billable"], false);
+    }
+
+    #[test]
+    fn test_generate_json_content_includes_flags() {
+        let config = test_config();
+
+        let mut flagged_item = create_test_item(8, 0, 30, "", "SCRUM-17", "review");
+        flagged_item.flags = "important needs_review".to_string();
+        let items = vec![
+            flagged_item,
+            create_test_item(8, 30, 30, "", "SCRUM-18", "coding"),
+        ];
+
+        let day = date!(2025 - 09 - 22);
+        let json_content =
+            generate_json_content(&items, &[], day, "", None, &test_meta(), &config).unwrap();
+
+        let json_value: serde_json::Value = serde_json::from_str(&json_content).unwrap();
+        assert_eq!(
+            json_value["entries"][0]["flags"],
+            serde_json::json!(["important", "needs_review"])
+        );
+        assert!(json_value["entries"][1].get("flags").is_none());
+    }
+
+    #[test]
+    fn test_generate_json_content_includes_links_in_order() {
+        let config = test_config();
+
+        let items = vec![
+            create_test_item(8, 0, 30, "", "SCRUM-17", "review"),
+            create_test_item(8, 30, 30, "", "SCRUM-18", "coding"),
+        ];
+        let link = |position, url: &str| EntryLink {
+            entry_id: items[0].id.clone(),
+            position,
+            url: url.to_string(),
+        };
+        let links = vec![link(1, "https://notes"), link(0, "https://git/pr/1")];
+
+        let day = date!(2025 - 09 - 22);
+        let json_content =
+            generate_json_content(&items, &links, day, "", None, &test_meta(), &config).unwrap();
+
+        let json_value: serde_json::Value = serde_json::from_str(&json_content).unwrap();
+        assert_eq!(
+            json_value["entries"][0]["links"],
+            serde_json::json!(["https://git/pr/1", "https://notes"])
+        );
+        assert!(json_value["entries"][1].get("links").is_none());
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+
+use color_eyre::{Result, eyre::Context};
+use time::{Date, format_description::OwnedFormatItem};
+
+use crate::config::{ExportLanguage, ExportLocaleConfig};
+
+/// Numbers, dates and header names of CSV exports, resolved from [ExportLocaleConfig]
+#[derive(Debug, Clone)]
+pub struct CsvLocale {
+    decimal_comma: bool,
+    date_format: OwnedFormatItem,
+    headers: HashMap<String, String>,
+}
+
+impl CsvLocale {
+    pub fn from_config(config: &ExportLocaleConfig) -> Result<Self> {
+        let (decimal_comma, date_format, headers): (_, _, &[(&str, &str)]) = match config.language {
+            ExportLanguage::En => (false, "[year]-[month]-[day]", &[]),
+            ExportLanguage::De => (true, "[day].[month].[year]", GERMAN_HEADERS),
+        };
+        let date_format = config.date_format.as_deref().unwrap_or(date_format);
+        let mut all_headers: HashMap<_, _> = headers
+            .iter()
+            .map(|(name, translated)| (name.to_string(), translated.to_string()))
+            .collect();
+        all_headers.extend(config.headers.clone());
+        Ok(Self {
+            decimal_comma: config.decimal_comma.unwrap_or(decimal_comma),
+            date_format: time::format_description::parse_owned::<2>(date_format)
+                .wrap_err_with(|| format!("Invalid export date format {date_format:?}"))?,
+            headers: all_headers,
+        })
+    }
+
+    /// Columns are separated by `;` if commas are taken by numbers
+    pub fn delimiter(&self) -> u8 {
+        if self.decimal_comma { b';' } else { b',' }
+    }
+
+    pub fn header<'a>(&'a self, name: &'a str) -> &'a str {
+        self.headers.get(name).map_or(name, String::as_str)
+    }
+
+    pub fn decimal(&self, value: f64) -> String {
+        let formatted = value.to_string();
+        if self.decimal_comma {
+            formatted.replace('.', ",")
+        } else {
+            formatted
+        }
+    }
+
+    pub fn date(&self, day: Date) -> Result<String> {
+        day.format(&self.date_format)
+            .wrap_err("Failed to format export date")
+    }
+}
+
+impl Default for CsvLocale {
+    fn default() -> Self {
+        Self::from_config(&ExportLocaleConfig::default()).expect("default format to be valid")
+    }
+}
+
+const GERMAN_HEADERS: &[(&str, &str)] = &[
+    ("date", "Datum"),
+    ("start", "Beginn"),
+    ("end", "Ende"),
+    ("proj", "Projekt"),
+    ("tracking code", "Ticket"),
+    ("duration", "Dauer"),
+    ("min", "Min"),
+    ("h", "Std"),
+];
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    #[test]
+    fn test_german_defaults_can_be_overridden() {
+        let config = ExportLocaleConfig {
+            language: ExportLanguage::De,
+            headers: HashMap::from([("proj".into(), "Kunde".into())]),
+            ..Default::default()
+        };
+        let locale = CsvLocale::from_config(&config).unwrap();
+
+        assert_eq!(locale.decimal(0.25), "0,25");
+        assert_eq!(locale.delimiter(), b';');
+        assert_eq!(locale.date(date!(2025 - 09 - 22)).unwrap(), "22.09.2025");
+        assert_eq!(locale.header("duration"), "Dauer");
+        assert_eq!(locale.header("proj"), "Kunde");
+        assert_eq!(locale.header(""), "");
+
+        let english = CsvLocale::default();
+        assert_eq!(english.decimal(0.25), "0.25");
+        assert_eq!(english.date(date!(2025 - 09 - 22)).unwrap(), "2025-09-22");
+        assert_eq!(english.header("duration"), "duration");
+
+        let invalid = ExportLocaleConfig {
+            date_format: Some("[dai]".into()),
+            ..Default::default()
+        };
+        assert!(CsvLocale::from_config(&invalid).is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use chrono::{DateTime, Local, NaiveDate, TimeZone};
+use color_eyre::{Result, eyre::eyre};
+use time::Date;
+
+use crate::{
+    config::{Config, ImportConfig, ImportProvider},
+    persist::{TimeEntry, TimeEntryId},
+    shared::parse::split_ticket,
+};
+
+mod clockify;
+mod toggl;
+
+/// Time entry as fetched from an external tracker, before mapping it to a [TimeEntry]
+#[derive(Debug, Clone)]
+pub struct ExternalEntry {
+    pub start: DateTime<Local>,
+    pub duration_secs: i64,
+    pub project: Option<String>,
+    pub tags: Vec<String>,
+    pub description: String,
+}
+
+/// Fetches the finished entries of the day from the configured tracker.
+pub fn fetch_day(config: &ImportConfig, day: Date) -> Result<Vec<ExternalEntry>> {
+    let (start, end) = local_day_bounds(day)?;
+    let agent = ureq::Agent::new_with_defaults();
+    let mut entries = match config.provider {
+        ImportProvider::Toggl => toggl::fetch(&agent, config, start, end)?,
+        ImportProvider::Clockify => clockify::fetch(&agent, config, start, end)?,
+    };
+    entries.retain(|it| it.start >= start && it.start < end);
+    entries.sort_by_key(|it| it.start);
+    Ok(entries)
+}
+
+fn local_day_bounds(day: Date) -> Result<(DateTime<Local>, DateTime<Local>)> {
+    let naive = NaiveDate::from_ymd_opt(day.year(), day.month() as u32, day.day() as u32)
+        .ok_or_else(|| eyre!("invalid day {day}"))?;
+    let local_midnight = |date: NaiveDate| {
+        Local
+            .from_local_datetime(&date.and_hms_opt(0, 0, 0).expect("midnight exists"))
+            .earliest()
+            .ok_or_else(|| eyre!("no local midnight on {date}"))
+    };
+    let next = naive.succ_opt().ok_or_else(|| eyre!("day after {day}"))?;
+    Ok((local_midnight(naive)?, local_midnight(next)?))
+}
+
+/// Maps entries to the day's timesheet. Project keys come from the mapping by project name,
+/// then by tag, otherwise the default project. A leading ticket key in the description is
+/// moved to the ticket, e.g. `SCRUM-17 standup`.
+pub fn map_entries(
+    entries: &[ExternalEntry],
+    iso_day: &str,
+    import: &ImportConfig,
+    config: &Config,
+) -> Vec<TimeEntry> {
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let duration_mins = (entry.duration_secs + 30) / 60;
+            if duration_mins <= 0 {
+                return None;
+            }
+            let project_key = entry
+                .project
+                .iter()
+                .chain(entry.tags.iter())
+                .find_map(|name| import.mapping.get(name))
+                .unwrap_or(&config.default_project_key)
+                .clone();
+            let (ticket_key, description) = split_ticket(&entry.description);
+            Some(TimeEntry {
+                id: TimeEntryId::new().to_string(),
+                timesheet_day: iso_day.to_string(),
+                project_key,
+                ticket_key,
+                duration_mins: duration_mins as i32,
+                description,
+                start_time: entry.start.format("%H:%M").to_string(),
+                billable: false,
+                work_type: None,
+                carry_over: false,
+                exact: false,
+                flags: String::new(),
+                revision: 0,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn import_config() -> ImportConfig {
+        let mut mapping = HashMap::new();
+        mapping.insert("Client Work".to_string(), "W".to_string());
+        mapping.insert("lunch".to_string(), "x".to_string());
+        ImportConfig {
+            provider: ImportProvider::Toggl,
+            api_token: String::new(),
+            workspace_id: None,
+            mapping,
+        }
+    }
+
+    fn external(
+        hour: u32,
+        duration_secs: i64,
+        project: Option<&str>,
+        tags: &[&str],
+    ) -> ExternalEntry {
+        ExternalEntry {
+            start: Local.with_ymd_and_hms(2025, 9, 22, hour, 0, 0).unwrap(),
+            duration_secs,
+            project: project.map(str::to_string),
+            tags: tags.iter().map(|it| it.to_string()).collect(),
+            description: "SCRUM-17: standup".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_maps_projects_then_tags_then_default() {
+        let config = Config {
+            default_project_key: "E".to_string(),
+            ..Default::default()
+        };
+        let entries = vec![
+            external(9, 1790, Some("Client Work"), &["lunch"]),
+            external(12, 3600, Some("Unknown"), &["lunch"]),
+            external(13, 600, None, &[]),
+        ];
+
+        let mapped = map_entries(&entries, "2025-09-22", &import_config(), &config);
+
+        let projects: Vec<_> = mapped.iter().map(|it| it.project_key.as_str()).collect();
+        assert_eq!(projects, vec!["W", "x", "E"]);
+        assert_eq!(mapped[0].start_time, "09:00");
+        assert_eq!(mapped[0].duration_mins, 30);
+        assert_eq!(mapped[0].ticket_key.as_deref(), Some("SCRUM-17"));
+        assert_eq!(mapped[0].description, "standup");
+    }
+
+    #[test]
+    fn test_skips_entries_shorter_than_a_minute() {
+        let entries = vec![external(9, 20, None, &[])];
+
+        let mapped = map_entries(&entries, "2025-09-22", &import_config(), &Config::default());
+
+        assert!(mapped.is_empty());
+    }
+
+    #[test]
+    fn test_description_without_ticket_is_kept() {
+        assert_eq!(
+            split_ticket(" fix build for scrum-17 "),
+            (None, "fix build for scrum-17".to_string())
+        );
+    }
+}
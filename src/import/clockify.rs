@@ -0,0 +1,90 @@
+use chrono::{DateTime, Local, Utc};
+use color_eyre::{Result, eyre::Context};
+use serde::Deserialize;
+
+use super::ExternalEntry;
+use crate::config::ImportConfig;
+
+const API_URL: &str = "https://api.clockify.me/api/v1";
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClockifyUser {
+    id: String,
+    active_workspace: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClockifyEntry {
+    description: Option<String>,
+    time_interval: ClockifyInterval,
+    project: Option<ClockifyNamed>,
+    #[serde(default)]
+    tags: Vec<ClockifyNamed>,
+}
+
+#[derive(Deserialize)]
+struct ClockifyInterval {
+    start: DateTime<Local>,
+    /// `None` while running
+    end: Option<DateTime<Local>>,
+}
+
+#[derive(Deserialize)]
+struct ClockifyNamed {
+    name: String,
+}
+
+pub(super) fn fetch(
+    agent: &ureq::Agent,
+    config: &ImportConfig,
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+) -> Result<Vec<ExternalEntry>> {
+    let user: ClockifyUser = agent
+        .get(format!("{API_URL}/user"))
+        .header("X-Api-Key", &config.api_token)
+        .call()
+        .and_then(|mut it| it.body_mut().read_json())
+        .wrap_err("fetching Clockify user")?;
+    let workspace_id = config
+        .workspace_id
+        .as_ref()
+        .unwrap_or(&user.active_workspace);
+
+    let entries: Vec<ClockifyEntry> = agent
+        .get(format!(
+            "{API_URL}/workspaces/{workspace_id}/user/{}/time-entries",
+            user.id
+        ))
+        .header("X-Api-Key", &config.api_token)
+        .query("start", format_utc(start))
+        .query("end", format_utc(end))
+        .query("hydrated", "true")
+        .query("page-size", "1000")
+        .call()
+        .and_then(|mut it| it.body_mut().read_json())
+        .wrap_err("fetching Clockify time entries")?;
+
+    let entries = entries
+        .into_iter()
+        .filter_map(|it| {
+            let end = it.time_interval.end?;
+            Some(ExternalEntry {
+                start: it.time_interval.start,
+                duration_secs: (end - it.time_interval.start).num_seconds(),
+                project: it.project.map(|it| it.name),
+                tags: it.tags.into_iter().map(|it| it.name).collect(),
+                description: it.description.unwrap_or_default(),
+            })
+        })
+        .collect();
+    Ok(entries)
+}
+
+fn format_utc(time: DateTime<Local>) -> String {
+    time.with_timezone(&Utc)
+        .format("%Y-%m-%dT%H:%M:%SZ")
+        .to_string()
+}
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use chrono::{DateTime, Local};
+use color_eyre::{Result, eyre::Context};
+use serde::Deserialize;
+
+use super::ExternalEntry;
+use crate::config::ImportConfig;
+
+const API_URL: &str = "https://api.track.toggl.com/api/v9";
+
+#[derive(Deserialize)]
+struct TogglEntry {
+    description: Option<String>,
+    start: DateTime<Local>,
+    stop: Option<DateTime<Local>>,
+    /// Seconds, negative while running
+    duration: i64,
+    project_id: Option<i64>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TogglProject {
+    id: i64,
+    name: String,
+}
+
+pub(super) fn fetch(
+    agent: &ureq::Agent,
+    config: &ImportConfig,
+    start: DateTime<Local>,
+    end: DateTime<Local>,
+) -> Result<Vec<ExternalEntry>> {
+    let auth = format!(
+        "Basic {}",
+        BASE64_STANDARD.encode(format!("{}:api_token", config.api_token))
+    );
+
+    let projects: Vec<TogglProject> = agent
+        .get(format!("{API_URL}/me/projects"))
+        .header("Authorization", &auth)
+        .call()
+        .and_then(|mut it| it.body_mut().read_json())
+        .wrap_err("fetching Toggl projects")?;
+    let project_names: HashMap<i64, String> =
+        projects.into_iter().map(|it| (it.id, it.name)).collect();
+
+    let entries: Vec<TogglEntry> = agent
+        .get(format!("{API_URL}/me/time_entries"))
+        .header("Authorization", &auth)
+        .query("start_date", start.to_rfc3339())
+        .query("end_date", end.to_rfc3339())
+        .call()
+        .and_then(|mut it| it.body_mut().read_json())
+        .wrap_err("fetching Toggl time entries")?;
+
+    let entries = entries
+        .into_iter()
+        .filter(|it| it.stop.is_some() && it.duration >= 0)
+        .map(|it| ExternalEntry {
+            start: it.start,
+            duration_secs: it.duration,
+            project: it.project_id.and_then(|id| project_names.get(&id)).cloned(),
+            tags: it.tags,
+            description: it.description.unwrap_or_default(),
+        })
+        .collect();
+    Ok(entries)
+}
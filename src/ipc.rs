@@ -0,0 +1,397 @@
+//! Control socket of the running TUI, so that `sheet-shark add` and scripts like Stream Deck
+//! buttons can log time without a second process writing to the database.
+//! Needs the `daemon` feature and a Unix socket in the data directory.
+//!
+//! Each line is a JSON [Request], answered by a JSON [Response] line, for example:
+//! `{"command": "start", "ticket": "SCRUM-17"}`.
+
+use std::ops::RangeInclusive;
+
+use chrono::{Local, NaiveTime, TimeDelta};
+use color_eyre::{
+    Result,
+    eyre::{bail, eyre},
+};
+use serde::{Deserialize, Serialize};
+use time::{Date, OffsetDateTime, format_description::FormatItem, macros::format_description};
+use tokio::sync::{
+    mpsc::{self, UnboundedReceiver, UnboundedSender},
+    oneshot,
+};
+
+use crate::{
+    action::TimerStart,
+    config::Config,
+    persist::{Command, CommandKind, Event, TimeEntry, TimeEntryId},
+    shared::{ticket_project, time_expr},
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum Request {
+    /// Adds a finished entry to a day
+    Add(NewEntry),
+    /// Adds an entry to today that follows the clock, ending the current one
+    Start(TimerStart),
+    /// Stops the entry following the clock
+    Stop,
+    /// Entries of today
+    Today,
+}
+
+/// Entry as given on the command line, defaults are filled in by [NewEntry::into_entry]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct NewEntry {
+    pub description: String,
+    pub ticket: Option<String>,
+    /// Defaults to the project configured for the ticket
+    pub project: Option<String>,
+    /// Like `45`, `1h15m` or `3*25m`, defaults to 30 minutes
+    pub duration: Option<String>,
+    /// `HH:MM`, defaults to the duration before now
+    pub start: Option<String>,
+    /// `YYYY-MM-DD`, defaults to today
+    pub day: Option<String>,
+    pub billable: bool,
+    /// Key of a configured work type
+    pub work_type: Option<String>,
+}
+
+impl NewEntry {
+    pub fn into_entry(self, config: &Config) -> Result<TimeEntry> {
+        let duration = self.duration.as_deref().unwrap_or("30");
+        let duration_mins = time_expr::evaluate_duration(duration)?.as_secs() / 60;
+        if duration_mins == 0 {
+            bail!("Duration must be at least a minute");
+        }
+        let duration_mins =
+            i32::try_from(duration_mins).map_err(|_| eyre!("Duration {duration} is too long"))?;
+        let start = match &self.start {
+            Some(start) => NaiveTime::parse_from_str(start, "%H:%M")?,
+            None => {
+                let now = Local::now().time();
+                now.overflowing_sub_signed(TimeDelta::minutes(duration_mins.into()))
+                    .0
+                    .min(now)
+            }
+        };
+        let day = match &self.day {
+            Some(day) => Date::parse(day, ISO_DAY)?,
+            None => OffsetDateTime::now_local()?.date(),
+        };
+        let project_key = self
+            .project
+            .or_else(|| {
+                let ticket = self.ticket.as_deref()?;
+                ticket_project::project_for(config, ticket).map(str::to_string)
+            })
+            .unwrap_or_else(|| config.default_project_key.clone());
+        if let Some(work_type) = &self.work_type
+            && !config.work_types.contains_key(work_type)
+        {
+            let known = config.work_type_keys().join(", ");
+            bail!("Unknown work type {work_type}, configured are: {known}");
+        }
+        Ok(TimeEntry {
+            id: TimeEntryId::new().to_string(),
+            timesheet_day: day.format(ISO_DAY)?,
+            project_key,
+            ticket_key: self.ticket.filter(|it| !it.is_empty()),
+            duration_mins,
+            description: self.description,
+            start_time: start.format("%H:%M").to_string(),
+            billable: self.billable,
+            work_type: self.work_type,
+            carry_over: false,
+            exact: false,
+            flags: String::new(),
+            revision: 0,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Response {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entries: Option<Vec<TimeEntry>>,
+    /// Entries that were added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub imported: Option<usize>,
+}
+
+impl Response {
+    pub fn ok() -> Self {
+        Self {
+            ok: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn error(error: impl ToString) -> Self {
+        Self {
+            error: Some(error.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Request with the channel to answer it on
+pub type Received = (Request, oneshot::Sender<Response>);
+
+/// Requests received from other processes while the TUI runs
+pub struct IpcServer {
+    tx: UnboundedSender<Received>,
+    rx: UnboundedReceiver<Received>,
+    /// Answered once the entries of the day are loaded
+    pending_today: Vec<(Date, oneshot::Sender<Response>)>,
+    /// Answered in order once the entries added to the day are imported
+    pending_adds: Vec<(Date, oneshot::Sender<Response>)>,
+    _socket: Option<imp::BoundSocket>,
+}
+
+impl IpcServer {
+    /// Listens in the data directory of the active profile, unless another instance already does
+    pub fn start() -> Result<Self> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let socket = imp::bind(tx.clone())?;
+        Ok(Self {
+            tx,
+            rx,
+            pending_today: vec![],
+            pending_adds: vec![],
+            _socket: socket,
+        })
+    }
+
+    /// Moves to the data directory of the profile switched to
+    pub fn restart(&mut self) -> Result<()> {
+        self._socket = None;
+        self.pending_today.clear();
+        self.pending_adds.clear();
+        self._socket = imp::bind(self.tx.clone())?;
+        Ok(())
+    }
+
+    pub fn try_recv(&mut self) -> Option<Received> {
+        self.rx.try_recv().ok()
+    }
+
+    /// Answers with the entries of the day once they are loaded, returns the range to load
+    pub fn await_entries(
+        &mut self,
+        day: Date,
+        reply: oneshot::Sender<Response>,
+    ) -> RangeInclusive<Date> {
+        self.pending_today.push((day, reply));
+        day..=day
+    }
+
+    /// Answers with the number of entries added once the entry is stored, returns the
+    /// command that stores it
+    pub fn await_import(
+        &mut self,
+        day: Date,
+        entry: TimeEntry,
+        reply: oneshot::Sender<Response>,
+    ) -> Command {
+        self.pending_adds.push((day, reply));
+        Command::ImportEntries {
+            day,
+            entries: vec![entry],
+            skip_deleted: false,
+        }
+    }
+
+    pub fn handle_persisted(&mut self, event: &Event) {
+        match event {
+            Event::EntriesImported { day, imported, .. } => {
+                // Imports are stored in order, so the first one waiting for the day is done
+                let Some(idx) = self.pending_adds.iter().position(|(it, ..)| it == day) else {
+                    return;
+                };
+                let (.., reply) = self.pending_adds.remove(idx);
+                let response = match imported {
+                    0 => Response::error("There is already an entry at that time"),
+                    imported => Response {
+                        imported: Some(*imported),
+                        ..Response::ok()
+                    },
+                };
+                let _ = reply.send(response);
+            }
+            // Like above, the first add waiting is the one that failed
+            Event::Failure(err) if err.command == CommandKind::ImportEntries => {
+                if self.pending_adds.is_empty() {
+                    return;
+                }
+                let (.., reply) = self.pending_adds.remove(0);
+                let _ = reply.send(Response::error(err));
+            }
+            Event::EntriesInRangeLoaded { range, entries } => self.answer_today(range, entries),
+            _ => {}
+        }
+    }
+
+    fn answer_today(&mut self, range: &RangeInclusive<Date>, entries: &[TimeEntry]) {
+        let (answered, pending) = std::mem::take(&mut self.pending_today)
+            .into_iter()
+            .partition(|(day, _)| *range == (*day..=*day));
+        self.pending_today = pending;
+        for (_, reply) in answered {
+            let response = Response {
+                entries: Some(entries.to_vec()),
+                ..Response::ok()
+            };
+            // The client may have given up already
+            let _ = reply.send(response);
+        }
+    }
+}
+
+/// Returns the response of the running instance, `None` if there is none
+pub fn send_to_running(request: &Request) -> Result<Option<Response>> {
+    imp::send(request)
+}
+
+const ISO_DAY: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
+#[cfg(all(unix, feature = "daemon"))]
+mod imp {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        os::unix::net::UnixStream,
+        path::PathBuf,
+    };
+
+    use color_eyre::{Result, eyre::Context};
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt},
+        net::UnixListener,
+        sync::{mpsc::UnboundedSender, oneshot},
+        task::JoinHandle,
+    };
+    use tracing::{info, warn};
+
+    use super::{Received, Request, Response};
+    use crate::settings;
+
+    fn socket_path() -> PathBuf {
+        settings::data_dir().join("sheet-shark.sock")
+    }
+
+    /// Removes the socket once the TUI stops listening
+    pub struct BoundSocket {
+        path: PathBuf,
+        task: JoinHandle<()>,
+    }
+
+    impl Drop for BoundSocket {
+        fn drop(&mut self) {
+            self.task.abort();
+            if let Err(err) = std::fs::remove_file(&self.path) {
+                warn!("Failed to remove {}: {err}", self.path.display());
+            }
+        }
+    }
+
+    pub fn bind(tx: UnboundedSender<Received>) -> Result<Option<BoundSocket>> {
+        let path = socket_path();
+        if path.exists() {
+            if UnixStream::connect(&path).is_ok() {
+                warn!(
+                    "Another instance listens on {}, not accepting requests",
+                    path.display()
+                );
+                return Ok(None);
+            }
+            // Left over from an instance that did not stop cleanly
+            std::fs::remove_file(&path)
+                .wrap_err_with(|| format!("remove stale socket {}", path.display()))?;
+        }
+        let listener =
+            UnixListener::bind(&path).wrap_err_with(|| format!("listen on {}", path.display()))?;
+        info!("Accepting requests on {}", path.display());
+        let task = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        tokio::spawn(serve(stream, tx.clone()));
+                    }
+                    Err(err) => warn!("Failed to accept on the control socket: {err}"),
+                }
+            }
+        });
+        Ok(Some(BoundSocket { path, task }))
+    }
+
+    /// Answers each request line until the client closes the connection
+    async fn serve(stream: tokio::net::UnixStream, tx: UnboundedSender<Received>) {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = tokio::io::BufReader::new(reader).lines();
+        loop {
+            let line = match lines.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => return,
+                Err(err) => {
+                    warn!("Failed to read from the control socket: {err}");
+                    return;
+                }
+            };
+            let response = match serde_json::from_str::<Request>(&line) {
+                Ok(request) => {
+                    let (reply_tx, reply_rx) = oneshot::channel();
+                    if tx.send((request, reply_tx)).is_err() {
+                        Response::error("sheet-shark is shutting down")
+                    } else {
+                        reply_rx
+                            .await
+                            .unwrap_or_else(|_| Response::error("no answer"))
+                    }
+                }
+                Err(err) => Response::error(format!("invalid request: {err}")),
+            };
+            let Ok(mut response) = serde_json::to_string(&response) else {
+                return;
+            };
+            response.push('\n');
+            if let Err(err) = writer.write_all(response.as_bytes()).await {
+                warn!("Failed to answer on the control socket: {err}");
+                return;
+            }
+        }
+    }
+
+    pub fn send(request: &Request) -> Result<Option<Response>> {
+        let Ok(mut stream) = UnixStream::connect(socket_path()) else {
+            return Ok(None);
+        };
+        writeln!(stream, "{}", serde_json::to_string(request)?)?;
+        let mut response = String::new();
+        BufReader::new(stream)
+            .read_line(&mut response)
+            .wrap_err("read response of the running instance")?;
+        Ok(Some(serde_json::from_str(&response)?))
+    }
+}
+
+#[cfg(not(all(unix, feature = "daemon")))]
+mod imp {
+    use color_eyre::Result;
+    use tokio::sync::mpsc::UnboundedSender;
+
+    use super::{Received, Request, Response};
+
+    pub type BoundSocket = ();
+
+    pub fn bind(_tx: UnboundedSender<Received>) -> Result<Option<BoundSocket>> {
+        Ok(None)
+    }
+
+    pub fn send(_request: &Request) -> Result<Option<Response>> {
+        Ok(None)
+    }
+}
@@ -0,0 +1,44 @@
+//! Data layer of sheet-shark, for tools that want to read or write its timesheets
+//! without going through the TUI.
+//!
+//! Everything that is configurable takes the [config::Config] explicitly, load it once
+//! with [config::Config::new] to get the same settings as the TUI.
+//!
+//! ```no_run
+//! use sheet_shark::{config::{self, Config}, persist, shared::summary::TimesheetSummary};
+//! use time::macros::date;
+//! use tokio::sync::mpsc;
+//!
+//! # fn main() -> color_eyre::Result<()> {
+//! let config = Config::new()?;
+//! let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
+//! let (evt_tx, mut evt_rx) = mpsc::unbounded_channel();
+//! let handle = persist::start_async(&config::get_data_dir(), &config, cmd_rx, evt_tx)?;
+//!
+//! cmd_tx.send(persist::Command::LoadTimesheet { day: date!(2025 - 09 - 22) })?;
+//! if let Some(persist::Event::TimesheetLoaded { entries, .. }) = evt_rx.blocking_recv() {
+//!     let summary = TimesheetSummary::new(entries, &config);
+//!     println!("worked {}", summary.calculate_total_duration());
+//! }
+//!
+//! drop(evt_rx); // shuts down the persist thread
+//! handle.join().expect("persist thread to finish");
+//! # Ok(())
+//! # }
+//! ```
+
+/// Portable archives of the database and config, e.g. for moving machines
+pub mod backup;
+/// Config file types and locations
+pub mod config;
+/// Exports of time entries, in each of the [export::ExportFormat]s
+pub mod export;
+/// Fetching time entries from external trackers
+pub mod import;
+/// Storage driven by [persist::Command]s on a separate thread, SQLite unless another
+/// [persist::Storage] is plugged in
+pub mod persist;
+/// Domain logic on top of the stored entries, like summaries and validation
+pub mod shared;
+/// Syncing the recent days with other machines through a JSON file of each day
+pub mod sync;
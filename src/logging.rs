@@ -1,26 +1,69 @@
+use std::path::PathBuf;
+
 use color_eyre::Result;
 use tracing_error::ErrorLayer;
-use tracing_subscriber::{EnvFilter, fmt, prelude::*};
+use tracing_subscriber::{EnvFilter, filter::Targets, fmt, prelude::*};
 
 use crate::config;
 
 lazy_static::lazy_static! {
     pub static ref LOG_ENV: String = format!("{}_LOG_LEVEL", config::PROJECT_NAME.clone());
     pub static ref LOG_FILE: String = format!("{}.log", env!("CARGO_PKG_NAME"));
+    /// Read before the config is loaded for the rest of the process, so errors in it are
+    /// reported later
+    static ref STARTUP_CONFIG: Option<config::Config> = config::Config::new().ok();
+    static ref LOG_DIR: PathBuf = STARTUP_CONFIG
+        .as_ref()
+        .map(|it| it.config.log_dir.clone())
+        .unwrap_or_else(config::get_state_dir);
+}
+
+/// Target of the events that go to the action log instead of the regular one. Only names of
+/// actions are logged there, since their payload may be what was typed into an entry.
+pub const ACTION_TARGET: &str = "user_action";
+
+/// File the log of the current session is written to, shared by all profiles
+pub fn log_path() -> PathBuf {
+    LOG_DIR.join(LOG_FILE.clone())
+}
+
+/// Action log of the `session`th last session, `0` being the current one
+pub fn action_log_path(session: usize) -> PathBuf {
+    let name = env!("CARGO_PKG_NAME");
+    match session {
+        0 => LOG_DIR.join(format!("{name}-actions.log")),
+        n => LOG_DIR.join(format!("{name}-actions.{n}.log")),
+    }
+}
+
+/// Moves the logs of earlier sessions one further and deletes the oldest, a session without
+/// any actions, e.g. of a CLI command, is not kept
+fn rotate_action_logs(keep_sessions: usize) -> Result<()> {
+    let current = action_log_path(0);
+    if current.metadata().map_or(true, |it| it.len() == 0) {
+        return Ok(());
+    }
+    let _ = std::fs::remove_file(action_log_path(keep_sessions.max(1) - 1));
+    for session in (0..keep_sessions.saturating_sub(1)).rev() {
+        let path = action_log_path(session);
+        if path.exists() {
+            std::fs::rename(path, action_log_path(session + 1))?;
+        }
+    }
+    Ok(())
 }
 
 pub fn init() -> Result<()> {
-    let directory = config::get_data_dir();
-    std::fs::create_dir_all(directory.clone())?;
-    let log_path = directory.join(LOG_FILE.clone());
-    let log_file = std::fs::File::create(log_path)?;
+    std::fs::create_dir_all(LOG_DIR.as_path())?;
+    let log_file = std::fs::File::create(log_path())?;
     let env_filter = EnvFilter::builder().with_default_directive(tracing::Level::INFO.into());
     // If the `RUST_LOG` environment variable is set, use that as the default, otherwise use the
     // value of the `LOG_ENV` environment variable. If the `LOG_ENV` environment variable contains
     // errors, then this will return an error.
     let env_filter = env_filter
         .try_from_env()
-        .or_else(|_| env_filter.with_env_var(LOG_ENV.clone()).from_env())?;
+        .or_else(|_| env_filter.with_env_var(LOG_ENV.clone()).from_env())?
+        .add_directive(format!("{ACTION_TARGET}=off").parse()?);
     let file_subscriber = fmt::layer()
         .with_file(true)
         .with_line_number(true)
@@ -28,8 +71,27 @@ pub fn init() -> Result<()> {
         .with_target(false)
         .with_ansi(false)
         .with_filter(env_filter);
+
+    let action_log = STARTUP_CONFIG
+        .as_ref()
+        .map(|it| &it.action_log)
+        .filter(|it| it.enabled);
+    let action_subscriber = match action_log {
+        Some(action_log) => {
+            rotate_action_logs(action_log.keep_sessions)?;
+            let action_file = std::fs::File::create(action_log_path(0))?;
+            let layer = fmt::layer()
+                .with_writer(action_file)
+                .with_target(false)
+                .with_ansi(false)
+                .with_filter(Targets::new().with_target(ACTION_TARGET, tracing::Level::DEBUG));
+            Some(layer)
+        }
+        None => None,
+    };
     tracing_subscriber::registry()
         .with(file_subscriber)
+        .with(action_subscriber)
         .with(ErrorLayer::default())
         .try_init()?;
     Ok(())
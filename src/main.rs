@@ -2,41 +2,47 @@ use std::time::Duration;
 
 use clap::Parser;
 use cli::Cli;
-use color_eyre::eyre::{Result, WrapErr, eyre};
+use color_eyre::eyre::{Result, WrapErr};
 use futures::executor;
-use tokio::sync::mpsc;
 
-use crate::app::App;
+use sheet_shark::{backup, config, export, import, persist, shared};
+
+use crate::{app::App, tui::IdleRender};
 
 mod action;
 mod app;
 mod cli;
+mod clipboard;
 mod components;
-mod config;
+mod desktop;
 mod errors;
+mod ipc;
 mod layout;
 mod logging;
-mod persist;
-mod shared;
+mod metrics;
+mod settings;
 mod tui;
 mod widgets;
 
 fn main() -> Result<()> {
     bootstrap(|| {
         let args = Cli::parse();
-
-        let (persist_tx, persist_rx) = mpsc::unbounded_channel();
-        let (persisted_tx, persisted_rx) = mpsc::unbounded_channel();
-        let persist_handle = persist::start_async(persist_rx, persisted_tx)?;
-
-        let app = App::new(args.tick_rate, args.frame_rate, persist_tx, persisted_rx)?;
-        executor::block_on(app.run())?;
-
-        // Allow remaining actions on the persist thread to complete; App closes channel to initiate shutdown
-        persist_handle
-            .join()
-            .map_err(|err| eyre!("Persist thread panicked: {err:?}"))?;
-        Ok(())
+        settings::init(args.profile)?;
+        if let Some(command) = args.command {
+            return cli::run(command);
+        }
+
+        let desktop = &settings::get().desktop;
+        let idle_after = args
+            .idle_render_after
+            .unwrap_or(desktop.idle_render_after_secs);
+        let idle_frame_rate = args.idle_frame_rate.unwrap_or(desktop.idle_frame_rate);
+        let idle_render = (idle_after > 0 && idle_frame_rate > 0.0).then(|| IdleRender {
+            after: Duration::from_secs(idle_after),
+            frame_rate: idle_frame_rate,
+        });
+        let app = App::new(args.tick_rate, args.frame_rate, idle_render)?;
+        executor::block_on(app.run())
     })
 }
 
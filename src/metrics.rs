@@ -0,0 +1,16 @@
+//! Local counts of feature usage, as enabled in the `metrics` config. Nothing leaves the database.
+
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::warn;
+
+use crate::{persist::Command, settings, shared::insights::Feature};
+
+pub fn record(persist_tx: &UnboundedSender<Command>, feature: Feature) {
+    if !settings::get().metrics.enabled {
+        return;
+    }
+    let feature = feature.to_string();
+    if let Err(err) = persist_tx.send(Command::RecordUsage { feature }) {
+        warn!("Failed to record usage: {err}");
+    }
+}
@@ -1,34 +1,139 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
 use color_eyre::{
     Result,
     eyre::{Context, eyre},
 };
-use diesel::{Connection, SqliteConnection};
+use diesel::{
+    Connection, SqliteConnection, connection::SimpleConnection, migration::MigrationSource,
+    sqlite::Sqlite,
+};
 use diesel_migrations::{EmbeddedMigrations, MigrationHarness, embed_migrations};
 use tokio::{
     runtime::Builder,
     select,
-    sync::mpsc::{UnboundedReceiver, UnboundedSender},
+    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
     task::LocalSet,
+    time::{Instant, sleep_until},
 };
 use tracing::{debug, error, info, warn};
 
+use self::auto_export::AutoExport;
+use crate::{
+    config::{Config, SqliteConfig},
+    export::ExportMeta,
+};
+
+pub mod archive;
+mod auto_export;
+mod error;
 mod handle;
+mod history;
+pub mod journal;
 pub mod model;
+mod operation;
 mod schema;
+mod storage;
+mod sync;
+mod tombstone;
+pub use error::*;
 pub use model::*;
+pub use operation::{BackgroundOperation, Cancelled, Operation};
+pub use storage::{SqliteStorage, Storage, StoredDay, SyncJob};
+
+/// Name of the database in the data dir
+pub const DATABASE_FILE: &str = "sharkdb.sqlite";
 
-use crate::config::get_data_dir;
+/// Locked shared while a [SqliteStorage] has the database open, see [lock_exclusive]
+const LOCK_FILE: &str = "sharkdb.lock";
 
 const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
 
+/// Suggestions are requested on every keystroke, only the last one of a burst is run
+const SUGGESTION_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Load of the persist thread, e.g. to tell slow disks apart from slow rendering
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PersistStats {
+    /// Commands that were waiting when the last one was taken
+    pub queued: usize,
+    /// What the last command did and how long that took
+    pub last_command: Option<(&'static str, Duration)>,
+}
+
+static STATS: Mutex<PersistStats> = Mutex::new(PersistStats {
+    queued: 0,
+    last_command: None,
+});
+
+/// [PersistStats] of the running persist thread
+pub fn stats() -> PersistStats {
+    *STATS.lock().expect("stats lock not poisoned")
+}
+
+/// Opens the database in the data dir, running any pending migrations, and starts a thread
+/// that handles commands until either channel is closed.
 pub fn start_async(
+    data_dir: &Path,
+    config: &Config,
     cmd_rx: UnboundedReceiver<Command>,
     evt_tx: UnboundedSender<Event>,
 ) -> Result<std::thread::JoinHandle<()>> {
+    start_with(SqliteStorage::open(data_dir, config)?, cmd_rx, evt_tx)
+}
+
+/// Like [start_async], but also writes the configured
+/// [crate::config::ExportConfig::auto_formats] of changed days into the export dir, once they
+/// stopped changing and before shutting down
+pub fn start_async_exporting(
+    data_dir: &Path,
+    config: &Config,
+    export_dir: PathBuf,
+    meta: ExportMeta,
+    cmd_rx: UnboundedReceiver<Command>,
+    evt_tx: UnboundedSender<Event>,
+) -> Result<std::thread::JoinHandle<()>> {
+    let storage = SqliteStorage::open(data_dir, config)?;
+    let auto_export = AutoExport::new(config, export_dir, meta);
+    start(storage, auto_export, cmd_rx, evt_tx)
+}
+
+/// Like [start_async], but with commands carried out by another [Storage]
+pub fn start_with<S: Storage + Send + 'static>(
+    storage: S,
+    cmd_rx: UnboundedReceiver<Command>,
+    evt_tx: UnboundedSender<Event>,
+) -> Result<std::thread::JoinHandle<()>> {
+    start(storage, None, cmd_rx, evt_tx)
+}
+
+fn start<S: Storage + Send + 'static>(
+    storage: S,
+    auto_export: Option<AutoExport>,
+    cmd_rx: UnboundedReceiver<Command>,
+    evt_tx: UnboundedSender<Event>,
+) -> Result<std::thread::JoinHandle<()>> {
+    let (background_tx, background_rx) = mpsc::unbounded_channel();
     let handler = PersistHandler {
-        conn: prepare_connection()?,
+        storage,
         cmd_rx,
         evt_tx,
+        pending_suggestion: None,
+        auto_export,
+        next_operation_id: 1,
+        deferred: VecDeque::new(),
+        background: None,
+        background_tx,
+        background_rx,
     };
     let runtime = Builder::new_current_thread()
         .enable_all()
@@ -44,12 +149,70 @@ pub fn start_async(
     Ok(handle)
 }
 
-fn prepare_connection() -> Result<SqliteConnection> {
-    let mut db_url = get_data_dir();
-    db_url.push("sharkdb.sqlite");
+/// Version of the newest migration known to this build, like `20261014140000`
+pub fn latest_schema_version() -> Result<String> {
+    let migrations = MigrationSource::<Sqlite>::migrations(&MIGRATIONS).map_err(|e| eyre!(e))?;
+    migrations
+        .iter()
+        .map(|it| it.name().version().to_string())
+        .max()
+        .ok_or_else(|| eyre!("no migrations embedded"))
+}
+
+/// Version of the newest migration applied to the database
+pub(crate) fn schema_version(conn: &mut SqliteConnection) -> Result<String> {
+    let applied = conn.applied_migrations().map_err(|e| eyre!(e))?;
+    applied
+        .iter()
+        .map(|it| it.to_string())
+        .max()
+        .ok_or_else(|| eyre!("database has no migrations applied"))
+}
+
+/// Shared lock on the database, failing while [lock_exclusive] holds it
+pub(crate) fn lock_shared(data_dir: &Path) -> Result<File> {
+    let file = open_lock_file(data_dir)?;
+    file.try_lock_shared()
+        .wrap_err("a backup is being imported, try again once it is done")?;
+    Ok(file)
+}
+
+/// Lock on the database for replacing it, failing while any instance has it open
+pub fn lock_exclusive(data_dir: &Path) -> Result<File> {
+    let file = open_lock_file(data_dir)?;
+    file.try_lock().wrap_err("database is in use")?;
+    Ok(file)
+}
+
+fn open_lock_file(data_dir: &Path) -> Result<File> {
+    std::fs::create_dir_all(data_dir)
+        .wrap_err_with(|| format!("creating data dir {}", data_dir.display()))?;
+    let path = data_dir.join(LOCK_FILE);
+    File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .wrap_err_with(|| format!("opening {}", path.display()))
+}
+
+pub(crate) fn prepare_connection(
+    data_dir: &Path,
+    sqlite: &SqliteConfig,
+) -> Result<SqliteConnection> {
+    std::fs::create_dir_all(data_dir)
+        .wrap_err_with(|| format!("creating data dir {}", data_dir.display()))?;
+    let db_url = data_dir.join(DATABASE_FILE);
     let db_url = db_url.to_str().expect("path to convert to string");
     let mut conn = SqliteConnection::establish(db_url)
         .wrap_err_with(|| format!("connecting to sqlite {db_url}"))?;
+    // busy_timeout first, switching the journal mode needs a lock too
+    let pragmas = format!(
+        "PRAGMA busy_timeout = {}; PRAGMA journal_mode = {}; PRAGMA synchronous = {};",
+        sqlite.busy_timeout_ms, sqlite.journal_mode, sqlite.synchronous
+    );
+    conn.batch_execute(&pragmas)
+        .wrap_err_with(|| format!("tuning sqlite connection: {pragmas}"))?;
 
     debug!("Running any pending migrations now.");
     match conn.run_pending_migrations(MIGRATIONS) {
@@ -63,46 +226,181 @@ fn prepare_connection() -> Result<SqliteConnection> {
     Ok(conn)
 }
 
-struct PersistHandler {
-    conn: SqliteConnection,
+struct PersistHandler<S> {
+    storage: S,
     cmd_rx: UnboundedReceiver<model::Command>,
     evt_tx: UnboundedSender<model::Event>,
+    /// Debounced suggestion query and when to run it
+    pending_suggestion: Option<(model::Command, Instant)>,
+    /// Debounced exports of changed days, if configured
+    auto_export: Option<AutoExport>,
+    next_operation_id: OperationId,
+    /// Commands that arrived while an operation was reporting progress, in order
+    deferred: VecDeque<model::Command>,
+    /// Operation running on a thread of its own, with the flag that cancels it
+    background: Option<(OperationId, Arc<AtomicBool>)>,
+    background_tx: UnboundedSender<(OperationId, Result<Event>)>,
+    /// Results of the background operations
+    background_rx: UnboundedReceiver<(OperationId, Result<Event>)>,
 }
 
-impl PersistHandler {
+impl<S: Storage> PersistHandler<S> {
     async fn run(mut self) -> Result<()> {
         loop {
+            if let Some(work) = self.deferred.pop_front() {
+                info!("Persistence command, deferred: {work:?}");
+                self.try_handle(work).await;
+                continue;
+            }
+            let suggestion_due = self.pending_suggestion.as_ref().map(|(_, due)| *due);
+            let export_due = self.auto_export.as_ref().and_then(|it| it.next_due());
             select! {
                 biased; // Stop should take prio
                 _ = self.evt_tx.closed() => {
                     debug!("Persistence events channel closed, shutting down persist handler...");
                     self.cmd_rx.close();
-                    while let Ok(leftover_cmd) = self.cmd_rx.try_recv() {
+                    while let Some(leftover_cmd) = self.deferred.pop_front().or_else(|| self.cmd_rx.try_recv().ok()) {
                         warn!("Still handling leftover command {leftover_cmd:?}");
                         self.try_handle(leftover_cmd).await;
                     }
+                    if let Some((id, cancelled)) = self.background.take() {
+                        info!("Cancelling background operation {id} to shut down");
+                        cancelled.store(true, Ordering::Relaxed);
+                        self.background_rx.recv().await;
+                    }
+                    if let Some(auto_export) = &mut self.auto_export {
+                        auto_export.export_due(&mut self.storage, true);
+                    }
                     return Ok(());
                 },
                 work_opt = self.cmd_rx.recv() => {
                     let work = work_opt.expect("nobody else to close the cmd_rx");
+                    if matches!(work, Command::SuggestTickets { .. }) {
+                        // Replaces any superseded query that didn't run yet
+                        debug!("Debouncing suggestion: {work:?}");
+                        let due = Instant::now() + SUGGESTION_DEBOUNCE;
+                        self.pending_suggestion = Some((work, due));
+                        continue;
+                    }
                     info!("Persistence command: {work:?}");
                     self.try_handle(work).await;
                 }
+                Some((id, result)) = self.background_rx.recv() => {
+                    self.background = None;
+                    self.report(CommandKind::Sync, None, id, result, None);
+                }
+                // The future is created even while disabled, hence the fallback
+                _ = sleep_until(suggestion_due.unwrap_or_else(Instant::now)),
+                    if suggestion_due.is_some() =>
+                {
+                    let (work, _) = self.pending_suggestion.take().expect("suggestion due");
+                    info!("Persistence command: {work:?}");
+                    self.try_handle(work).await;
+                }
+                _ = sleep_until(export_due.unwrap_or_else(Instant::now)), if export_due.is_some() => {
+                    let auto_export = self.auto_export.as_mut().expect("export due");
+                    auto_export.export_due(&mut self.storage, false);
+                }
             }
         }
     }
 
     async fn try_handle(&mut self, cmd: model::Command) {
-        match handle::handle(&mut self.conn, cmd).await {
+        if let Command::Cancel { id } = cmd {
+            match &self.background {
+                Some((running, cancelled)) if *running == id => {
+                    cancelled.store(true, Ordering::Relaxed)
+                }
+                _ => debug!("Ignoring cancel of operation {id}, not running"),
+            }
+            return;
+        }
+        if matches!(cmd, Command::Sync) {
+            self.start_sync();
+            return;
+        }
+        let queued = self.cmd_rx.len() + self.deferred.len();
+        STATS.lock().expect("stats lock not poisoned").queued = queued;
+        let started = Instant::now();
+        let description = cmd.describe();
+        let changed_day = self
+            .auto_export
+            .as_ref()
+            .and_then(|_| self.storage.day_changed_by(&cmd));
+        let id = self.next_operation_id;
+        self.next_operation_id += 1;
+        let mut operation = Operation::new(id, &self.evt_tx, &mut self.cmd_rx, &mut self.deferred);
+        let kind = CommandKind::from(&cmd);
+        let repeatable = cmd.repeatable_copy();
+        let result = self.storage.handle(cmd, &mut operation).await;
+        STATS.lock().expect("stats lock not poisoned").last_command =
+            Some((description, started.elapsed()));
+        self.report(kind, repeatable, id, result, changed_day);
+    }
+
+    /// Runs [Storage::sync_job] on a thread of its own, unless a sync is still running
+    fn start_sync(&mut self) {
+        if let Some((id, _)) = &self.background {
+            info!("Not syncing, operation {id} still syncing");
+            return;
+        }
+        let id = self.next_operation_id;
+        self.next_operation_id += 1;
+        let job = match self.storage.sync_job() {
+            Ok(job) => job,
+            Err(err) => return self.report(CommandKind::Sync, None, id, Err(err), None),
+        };
+        let mut operation = BackgroundOperation::new(id, self.evt_tx.clone());
+        let cancelled = operation.cancelled();
+        let done_tx = self.background_tx.clone();
+        let spawned = std::thread::Builder::new()
+            .name("sync".into())
+            .spawn(move || {
+                let result = job(&mut operation);
+                if done_tx.send((id, result)).is_err() {
+                    debug!("Persist thread gone before sync {id} was done");
+                }
+            });
+        match spawned {
+            Ok(_) => self.background = Some((id, cancelled)),
+            Err(err) => self.report(CommandKind::Sync, None, id, Err(err.into()), None),
+        }
+    }
+
+    /// Sends the result of the operation, scheduling exports of the days it changed
+    fn report(
+        &mut self,
+        command: CommandKind,
+        repeatable: Option<Command>,
+        id: OperationId,
+        result: Result<Event>,
+        changed_day: Option<time::Date>,
+    ) {
+        match result {
             Ok(event) => {
                 debug!("Persistence response: {event:?}");
+                if let (Some(auto_export), Some(day)) = (&mut self.auto_export, changed_day) {
+                    auto_export.schedule(day);
+                }
+                if let (Some(auto_export), Event::Synced { pulled, .. }) =
+                    (&mut self.auto_export, &event)
+                {
+                    pulled.iter().for_each(|day| auto_export.schedule(*day));
+                }
                 if let Err(err) = self.evt_tx.send(event) {
                     debug!("Unable to send persistence event: {err:?}");
                 }
             }
+            Err(err) if err.is::<Cancelled>() => {
+                info!("Cancelled operation {id}: {}", command.describe());
+                if let Err(err) = self.evt_tx.send(model::Event::Cancelled { id }) {
+                    debug!("Unable to send cancellation: {err:?}");
+                }
+            }
             Err(err) => {
                 error!("Error handling persistence command: {err:?}");
-                let event = model::Event::Failure(format!("{err:?}"));
+                let event =
+                    model::Event::Failure(PersistError::from_report(command, repeatable, &err));
                 if let Err(err) = self.evt_tx.send(event) {
                     debug!("Unable to send persistence error: {err:?}");
                 }
@@ -0,0 +1,601 @@
+use std::{
+    fs,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{Result, eyre::Context};
+use diesel::{
+    Connection, QueryableByName, RunQueryDsl, SqliteConnection, connection::SimpleConnection,
+    sql_types::Text,
+};
+use diesel_migrations::MigrationHarness;
+use itertools::Itertools;
+use time::{Date, OffsetDateTime, format_description::FormatItem, macros::format_description};
+use tracing::{info, warn};
+
+use super::{Command, MIGRATIONS, operation::Operation};
+
+/// Directory in the data dir with the archive database of each year
+pub const ARCHIVE_DIR: &str = "archive";
+
+/// SQLite attaches at most 10 databases at once, unless built with a higher
+/// `SQLITE_MAX_ATTACHED`, and `VACUUM` needs one of them. Connections attach the archives of
+/// the years a command needs and detach others to make room, see [attach_years].
+const MAX_ATTACHED: usize = 9;
+
+/// Tables of the timesheets that are moved into archives, the others stay in the main database
+const ARCHIVED_TABLES: [&str; 3] = ["timesheet", "time_entry", "entry_link"];
+
+const ISO_DAY: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
+/// Database the timesheets of the year are archived in
+pub fn archive_path(data_dir: &Path, year: i32) -> PathBuf {
+    data_dir
+        .join(ARCHIVE_DIR)
+        .join(format!("sharkdb-{year}.sqlite"))
+}
+
+/// Years with an archive database in the data dir, oldest first
+pub fn archived_years(data_dir: &Path) -> Result<Vec<i32>> {
+    let dir = data_dir.join(ARCHIVE_DIR);
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let years = fs::read_dir(&dir)
+        .wrap_err_with(|| format!("listing archives in {}", dir.display()))?
+        .filter_map(|it| it.ok())
+        .filter_map(|it| {
+            let name = it.file_name().into_string().ok()?;
+            name.strip_prefix("sharkdb-")?
+                .strip_suffix(".sqlite")?
+                .parse()
+                .ok()
+        })
+        .sorted()
+        .collect();
+    Ok(years)
+}
+
+fn schema_of(year: i32) -> String {
+    format!("archive_{year}")
+}
+
+/// Creates the archive of the year if needed, migrates it to the current schema and attaches
+/// it to the main database as `archive_<year>`
+fn attach(conn: &mut SqliteConnection, data_dir: &Path, year: i32) -> Result<()> {
+    let path = archive_path(data_dir, year);
+    fs::create_dir_all(data_dir.join(ARCHIVE_DIR)).wrap_err("creating archive dir")?;
+    let path = path.to_str().expect("path to convert to string");
+    let mut archive = SqliteConnection::establish(path)
+        .wrap_err_with(|| format!("connecting to archive {path}"))?;
+    archive
+        .run_pending_migrations(MIGRATIONS)
+        .map_err(|e| color_eyre::eyre::eyre!(e))
+        .wrap_err_with(|| format!("migrating archive {path}"))?;
+    drop(archive);
+    conn.batch_execute(&format!(
+        "ATTACH DATABASE '{}' AS {}",
+        path.replace('\'', "''"),
+        schema_of(year)
+    ))
+    .wrap_err_with(|| format!("attaching archive {path}"))?;
+    Ok(())
+}
+
+/// Attaches the archives of the years, at most [MAX_ATTACHED], creating the missing ones.
+/// Archives of other years are detached if there is no room for them, oldest first.
+/// Returns whether any were attached.
+fn attach_years(conn: &mut SqliteConnection, years: &[i32]) -> Result<bool> {
+    let attached = attached_years(conn)?;
+    let missing = years
+        .iter()
+        .filter(|it| !attached.contains(it))
+        .copied()
+        .collect_vec();
+    if missing.is_empty() {
+        return Ok(false);
+    }
+    let room = MAX_ATTACHED.saturating_sub(attached.len());
+    let unneeded = attached.iter().filter(|it| !years.contains(it));
+    for year in unneeded.take(missing.len().saturating_sub(room)) {
+        conn.batch_execute(&format!("DETACH DATABASE {}", schema_of(*year)))
+            .wrap_err_with(|| format!("detaching archive of {year}"))?;
+    }
+    let data_dir = data_dir_of(conn)?;
+    for year in missing {
+        attach(conn, &data_dir, year)?;
+    }
+    Ok(true)
+}
+
+#[derive(QueryableByName)]
+struct AttachedDatabase {
+    #[diesel(sql_type = Text)]
+    name: String,
+    #[diesel(sql_type = Text)]
+    file: String,
+}
+
+fn attached_databases(conn: &mut SqliteConnection) -> Result<Vec<AttachedDatabase>> {
+    diesel::sql_query("SELECT name, file FROM pragma_database_list ORDER BY seq")
+        .load(conn)
+        .wrap_err("listing attached databases")
+}
+
+/// Years of the attached archives, oldest first
+fn attached_years(conn: &mut SqliteConnection) -> Result<Vec<i32>> {
+    let years = attached_databases(conn)?
+        .into_iter()
+        .filter_map(|it| it.name.strip_prefix("archive_")?.parse().ok())
+        .sorted()
+        .collect();
+    Ok(years)
+}
+
+fn data_dir_of(conn: &mut SqliteConnection) -> Result<PathBuf> {
+    attached_databases(conn)?
+        .into_iter()
+        .find(|it| it.name == "main")
+        .and_then(|it| Path::new(&it.file).parent().map(Path::to_path_buf))
+        .ok_or_else(|| color_eyre::eyre::eyre!("main database has no file"))
+}
+
+/// Archived years the command reads if it only reads and should see archived timesheets too,
+/// which it then does on a connection with [read_years]. Reports may read any day and see the
+/// newest [MAX_ATTACHED] years, ranges attach the years of each chunk as they load it, see
+/// [read_range].
+pub(super) fn years_read_by(
+    conn: &mut SqliteConnection,
+    cmd: &Command,
+) -> Result<Option<Vec<i32>>> {
+    let archived = archived_years(&data_dir_of(conn)?)?;
+    if archived.is_empty() {
+        return Ok(None);
+    }
+    let range: RangeInclusive<i32> = match cmd {
+        Command::LoadTimesheet { day } => {
+            return Ok(is_archived(conn, *day)?.then(|| vec![day.year()]));
+        }
+        Command::LoadEntry(id) => {
+            let year =
+                day_of_entry(conn, &id.to_string())?.and_then(|day| day.get(..4)?.parse().ok());
+            return Ok(year.map(|it| vec![it]));
+        }
+        Command::LoadTimesheetsOfMonth { day } => day.year()..=day.year(),
+        Command::LoadEntriesInRange { range } | Command::LoadDailyTotals { range } => {
+            range.start().year()..=range.end().year()
+        }
+        // Six months back at most
+        Command::SuggestTickets { .. } | Command::LoadRecentProjects => {
+            let year = OffsetDateTime::now_local()?.year();
+            year - 1..=year
+        }
+        Command::RunReport { .. } => i32::MIN..=i32::MAX,
+        _ => return Ok(None),
+    };
+    let years = archived
+        .into_iter()
+        .filter(|it| range.contains(it))
+        .collect_vec();
+    if years.is_empty() {
+        return Ok(None);
+    }
+    if years.len() > MAX_ATTACHED && matches!(cmd, Command::RunReport { .. }) {
+        warn!(
+            "Report reads the newest {MAX_ATTACHED} of {} archived years, SQLite attaches no \
+             more at once",
+            years.len()
+        );
+    }
+    Ok(Some(
+        years[years.len().saturating_sub(MAX_ATTACHED)..].to_vec(),
+    ))
+}
+
+/// Attaches the archives of the years in the range for [read_years], before a range command
+/// loads a chunk of it
+pub(super) fn read_range(conn: &mut SqliteConnection, range: &RangeInclusive<Date>) -> Result<()> {
+    let years = archived_years(&data_dir_of(conn)?)?
+        .into_iter()
+        .filter(|it| (range.start().year()..=range.end().year()).contains(it))
+        .collect_vec();
+    if years.is_empty() {
+        return Ok(());
+    }
+    read_years(conn, &years)
+}
+
+/// Attaches the archives of the years to a connection that just reads, for
+/// [years_read_by]. Whenever others were attached, the archived tables are shadowed again
+/// by temporary views that include the rows of the attached archives, so that queries read
+/// them unchanged. Writing fails while they exist.
+pub(super) fn read_years(conn: &mut SqliteConnection, years: &[i32]) -> Result<()> {
+    if !attach_years(conn, years)? {
+        return Ok(());
+    }
+    let schemas = std::iter::once("main".to_string())
+        .chain(attached_years(conn)?.into_iter().map(schema_of))
+        .collect_vec();
+    for table in ARCHIVED_TABLES {
+        let selects = schemas
+            .iter()
+            .map(|schema| format!("SELECT * FROM {schema}.{table}"))
+            .join(" UNION ALL ");
+        conn.batch_execute(&format!(
+            "DROP VIEW IF EXISTS temp.{table}; CREATE TEMP VIEW {table} AS {selects}"
+        ))
+        .wrap_err_with(|| format!("reading {table} through the archives"))?;
+    }
+    Ok(())
+}
+
+#[derive(QueryableByName)]
+struct Day {
+    #[diesel(sql_type = Text)]
+    day: String,
+}
+
+/// Whether the timesheet of the day is in the archive of its year and not in the main
+/// database
+fn is_archived(conn: &mut SqliteConnection, day: Date) -> Result<bool> {
+    let year = day.year();
+    if !archive_path(&data_dir_of(conn)?, year).exists() {
+        return Ok(false);
+    }
+    attach_years(conn, &[year])?;
+    let day = day.format(ISO_DAY)?;
+    let found: Vec<Day> = diesel::sql_query(format!(
+        "SELECT day FROM {}.timesheet WHERE day = ?1 \
+         AND day NOT IN (SELECT day FROM main.timesheet)",
+        schema_of(year)
+    ))
+    .bind::<Text, _>(&day)
+    .load(conn)
+    .wrap_err_with(|| format!("looking up {day} in the archive"))?;
+    Ok(!found.is_empty())
+}
+
+/// Moves the day the command writes to back into the main database, so that archived days
+/// can still be edited. They are archived again by the next [archive_before].
+pub(super) fn restore_for(conn: &mut SqliteConnection, cmd: &Command) -> Result<()> {
+    let day = match cmd {
+        Command::StoreEntry { entry, .. } => Some(entry.timesheet_day.clone()),
+        Command::StoreNotes { day, .. }
+        | Command::StoreUtcOffset { day, .. }
+        | Command::ImportEntries { day, .. }
+        | Command::RestoreSnapshot { day, .. } => Some(day.format(ISO_DAY)?),
+        Command::DeleteEntry(id) | Command::StoreLinks { entry_id: id, .. } => {
+            day_of_entry(conn, &id.to_string())?
+        }
+        Command::RepairEntry { raw_id, .. } => day_of_entry(conn, raw_id)?,
+        _ => None,
+    };
+    if let Some(day) = day {
+        restore_day(conn, &day)?;
+    }
+    Ok(())
+}
+
+/// Day of the entry if it is archived, looked up in as many archives at once as can be
+/// attached, newest first
+fn day_of_entry(conn: &mut SqliteConnection, id: &str) -> Result<Option<String>> {
+    let in_main: Vec<Day> =
+        diesel::sql_query("SELECT timesheet_day AS day FROM main.time_entry WHERE id = ?1")
+            .bind::<Text, _>(id)
+            .load(conn)
+            .wrap_err_with(|| format!("looking up entry {id}"))?;
+    if !in_main.is_empty() {
+        return Ok(None);
+    }
+    let archived = archived_years(&data_dir_of(conn)?)?;
+    for years in archived.iter().rev().chunks(MAX_ATTACHED).into_iter() {
+        let years = years.copied().collect_vec();
+        attach_years(conn, &years)?;
+        let selects = years
+            .iter()
+            .map(|year| {
+                format!(
+                    "SELECT timesheet_day AS day FROM {}.time_entry WHERE id = ?1",
+                    schema_of(*year)
+                )
+            })
+            .join(" UNION ALL ");
+        let found: Vec<Day> = diesel::sql_query(selects)
+            .bind::<Text, _>(id)
+            .load(conn)
+            .wrap_err_with(|| format!("looking up entry {id} in the archives"))?;
+        if let Some(found) = found.into_iter().next() {
+            return Ok(Some(found.day));
+        }
+    }
+    Ok(None)
+}
+
+fn restore_day(conn: &mut SqliteConnection, day: &str) -> Result<()> {
+    let Some(year) = day.get(..4).and_then(|it| it.parse().ok()) else {
+        return Ok(());
+    };
+    if !archive_path(&data_dir_of(conn)?, year).exists() {
+        return Ok(());
+    }
+    attach_years(conn, &[year])?;
+    let schema = schema_of(year);
+    let restored = conn
+        .transaction(|conn| {
+            let entries_of_day =
+                format!("SELECT id FROM {schema}.time_entry WHERE timesheet_day = ?1");
+            let statements = [
+                format!(
+                    "INSERT OR IGNORE INTO main.timesheet SELECT * FROM {schema}.timesheet \
+                     WHERE day = ?1"
+                ),
+                format!(
+                    "INSERT OR REPLACE INTO main.time_entry SELECT * FROM {schema}.time_entry \
+                     WHERE timesheet_day = ?1"
+                ),
+                format!(
+                    "INSERT OR REPLACE INTO main.entry_link SELECT * FROM {schema}.entry_link \
+                     WHERE entry_id IN ({entries_of_day})"
+                ),
+                format!("DELETE FROM {schema}.entry_link WHERE entry_id IN ({entries_of_day})"),
+                format!("DELETE FROM {schema}.time_entry WHERE timesheet_day = ?1"),
+                format!("DELETE FROM {schema}.timesheet WHERE day = ?1"),
+                // Left by the deletes above, the entries were only moved
+                format!("DELETE FROM {schema}.entry_tombstone WHERE timesheet_day = ?1"),
+            ];
+            let mut touched = 0;
+            for statement in statements {
+                touched += diesel::sql_query(statement)
+                    .bind::<Text, _>(day)
+                    .execute(conn)?;
+            }
+            diesel::result::QueryResult::Ok(touched)
+        })
+        .wrap_err_with(|| format!("restoring {day} from the archive"))?;
+    if restored > 0 {
+        info!("Restored {day} from the archive to be edited");
+    }
+    Ok(())
+}
+
+/// Renames the ticket in the archived entries of the range, like in the main database
+pub(super) fn rename_ticket(
+    conn: &mut SqliteConnection,
+    from: &str,
+    to: &str,
+    first_day: &str,
+    last_day: &str,
+) -> Result<usize> {
+    let first_year = first_day.get(..4).and_then(|it| it.parse().ok());
+    let last_year = last_day.get(..4).and_then(|it| it.parse().ok());
+    let (Some(first_year), Some(last_year)) = (first_year, last_year) else {
+        return Ok(0);
+    };
+    let years = archived_years(&data_dir_of(conn)?)?
+        .into_iter()
+        .filter(|it| (first_year..=last_year).contains(it))
+        .collect_vec();
+    let mut renamed = 0;
+    for years in years.chunks(MAX_ATTACHED) {
+        attach_years(conn, years)?;
+        for year in years {
+            renamed += diesel::sql_query(format!(
+                "UPDATE {}.time_entry SET ticket_key = ?1, revision = revision + 1 \
+                 WHERE ticket_key = ?2 AND timesheet_day BETWEEN ?3 AND ?4",
+                schema_of(*year)
+            ))
+            .bind::<Text, _>(to)
+            .bind::<Text, _>(from)
+            .bind::<Text, _>(first_day)
+            .bind::<Text, _>(last_day)
+            .execute(conn)
+            .wrap_err_with(|| format!("rename ticket {from} to {to} in the archive of {year}"))?;
+        }
+    }
+    Ok(renamed)
+}
+
+/// Moves the timesheets before the day into the archives of their years, creating them if
+/// needed.
+/// Returns the number of days and entries moved.
+pub(super) fn archive_before(
+    conn: &mut SqliteConnection,
+    before: Date,
+    operation: &mut Operation<'_>,
+) -> Result<(usize, usize)> {
+    let before = before.format(ISO_DAY)?;
+    let days: Vec<Day> = diesel::sql_query("SELECT day FROM main.timesheet WHERE day < ?1")
+        .bind::<Text, _>(&before)
+        .load(conn)
+        .wrap_err("finding days to archive")?;
+    let years: Vec<i32> = days
+        .iter()
+        .filter_map(|it| it.day.get(..4)?.parse().ok())
+        .unique()
+        .sorted()
+        .collect();
+    if years.is_empty() {
+        return Ok((0, 0));
+    }
+
+    let (mut days_moved, mut entries_moved) = (0, 0);
+    for (step, year) in years.iter().enumerate() {
+        operation.progress(step as u32, years.len() as u32)?;
+        attach_years(conn, &[*year])?;
+        let schema = schema_of(*year);
+        let first = format!("{year}-01-01");
+        let until = format!("{}-01-01", year + 1).min(before.clone());
+        let (days, entries) = conn
+            .transaction(|conn| {
+                let entries_of_range = "SELECT id FROM main.time_entry \
+                    WHERE timesheet_day >= ?1 AND timesheet_day < ?2";
+                let run = |conn: &mut SqliteConnection, statement: String| {
+                    diesel::sql_query(statement)
+                        .bind::<Text, _>(&first)
+                        .bind::<Text, _>(&until)
+                        .execute(conn)
+                };
+                let days = run(
+                    conn,
+                    format!(
+                        "INSERT OR REPLACE INTO {schema}.timesheet SELECT * FROM main.timesheet \
+                         WHERE day >= ?1 AND day < ?2"
+                    ),
+                )?;
+                let entries = run(
+                    conn,
+                    format!(
+                        "INSERT OR REPLACE INTO {schema}.time_entry SELECT * FROM main.time_entry \
+                         WHERE timesheet_day >= ?1 AND timesheet_day < ?2"
+                    ),
+                )?;
+                run(
+                    conn,
+                    format!(
+                        "INSERT OR REPLACE INTO {schema}.entry_link SELECT * FROM main.entry_link \
+                         WHERE entry_id IN ({entries_of_range})"
+                    ),
+                )?;
+                run(
+                    conn,
+                    format!("DELETE FROM main.entry_link WHERE entry_id IN ({entries_of_range})"),
+                )?;
+                run(
+                    conn,
+                    "DELETE FROM main.time_entry WHERE timesheet_day >= ?1 AND timesheet_day < ?2"
+                        .to_string(),
+                )?;
+                run(
+                    conn,
+                    "DELETE FROM main.timesheet WHERE day >= ?1 AND day < ?2".to_string(),
+                )?;
+                run(
+                    conn,
+                    "DELETE FROM main.entry_tombstone WHERE timesheet_day >= ?1 AND timesheet_day < ?2"
+                        .to_string(),
+                )?;
+                diesel::result::QueryResult::Ok((days, entries))
+            })
+            .wrap_err_with(|| format!("archiving {year} before {before}"))?;
+        info!("Archived {days} days with {entries} entries of {year}");
+        days_moved += days;
+        entries_moved += entries;
+    }
+    // Gives the space of the moved rows back
+    conn.batch_execute("VACUUM main")
+        .wrap_err("compacting the database")?;
+    Ok((days_moved, entries_moved))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, fs};
+
+    use time::macros::date;
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::{config::SqliteConfig, persist::prepare_connection};
+
+    fn days_in(conn: &mut SqliteConnection, schema: &str) -> Vec<String> {
+        diesel::sql_query(format!("SELECT day FROM {schema}.timesheet ORDER BY day"))
+            .load::<Day>(conn)
+            .unwrap()
+            .into_iter()
+            .map(|it| it.day)
+            .collect()
+    }
+
+    #[test]
+    fn test_archives_per_year_and_restores_edited_day() {
+        let dir = std::env::temp_dir().join(format!("sheet-shark-archive-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut conn = prepare_connection(&dir, &SqliteConfig::default()).unwrap();
+        conn.batch_execute(
+            "INSERT INTO timesheet (day, status, notes) VALUES \
+               ('2024-03-04', 'OPEN', ''), ('2025-02-03', 'OPEN', ''), ('2026-10-15', 'OPEN', ''); \
+             INSERT INTO time_entry \
+               (id, timesheet_day, start_time, duration_mins, description, project_key, billable) \
+             VALUES ('a', '2024-03-04', '09:00', 60, 'old', 'W', 1), \
+               ('b', '2025-02-03', '09:00', 30, 'older', 'W', 1); \
+             INSERT INTO entry_link (entry_id, position, url) VALUES ('a', 0, 'https://x');",
+        )
+        .unwrap();
+
+        let (_cmd_tx, mut cmd_rx) = mpsc::unbounded_channel();
+        let (evt_tx, _evt_rx) = mpsc::unbounded_channel();
+        let mut deferred = VecDeque::new();
+        let mut operation = Operation::new(1, &evt_tx, &mut cmd_rx, &mut deferred);
+        let moved = archive_before(&mut conn, date!(2026 - 01 - 01), &mut operation).unwrap();
+        assert_eq!(moved, (2, 2));
+        assert_eq!(archived_years(&dir).unwrap(), vec![2024, 2025]);
+        assert_eq!(days_in(&mut conn, "main"), vec!["2026-10-15"]);
+        assert_eq!(days_in(&mut conn, "archive_2024"), vec!["2024-03-04"]);
+        assert_eq!(days_in(&mut conn, "archive_2025"), vec!["2025-02-03"]);
+
+        let mut reader = prepare_connection(&dir, &SqliteConfig::default()).unwrap();
+        read_years(&mut reader, &[2024, 2025]).unwrap();
+        assert_eq!(days_in(&mut reader, "temp").len(), 3);
+        drop(reader);
+
+        restore_day(&mut conn, "2024-03-04").unwrap();
+        assert_eq!(days_in(&mut conn, "main"), vec!["2024-03-04", "2026-10-15"]);
+        assert!(days_in(&mut conn, "archive_2024").is_empty());
+        let links: i64 =
+            diesel::dsl::sql::<diesel::sql_types::BigInt>("SELECT COUNT(*) FROM main.entry_link")
+                .get_result(&mut conn)
+                .unwrap();
+        assert_eq!(links, 1);
+
+        drop(conn);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_archives_more_years_than_sqlite_can_attach() {
+        let dir =
+            std::env::temp_dir().join(format!("sheet-shark-archive-years-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut conn = prepare_connection(&dir, &SqliteConfig::default()).unwrap();
+        let values = (2010..2025)
+            .map(|year| format!("('{year}-06-01', 'OPEN', '')"))
+            .join(", ");
+        conn.batch_execute(&format!(
+            "INSERT INTO timesheet (day, status, notes) VALUES {values}"
+        ))
+        .unwrap();
+
+        let (_cmd_tx, mut cmd_rx) = mpsc::unbounded_channel();
+        let (evt_tx, _evt_rx) = mpsc::unbounded_channel();
+        let mut deferred = VecDeque::new();
+        let mut operation = Operation::new(1, &evt_tx, &mut cmd_rx, &mut deferred);
+        let moved = archive_before(&mut conn, date!(2026 - 01 - 01), &mut operation).unwrap();
+        assert_eq!(moved, (15, 0));
+        assert_eq!(archived_years(&dir).unwrap().len(), 15);
+        drop(conn);
+
+        let mut reader = prepare_connection(&dir, &SqliteConfig::default()).unwrap();
+        let report = Command::RunReport {
+            name: "all".into(),
+            sql: "SELECT * FROM timesheet".into(),
+        };
+        let years = years_read_by(&mut reader, &report).unwrap().unwrap();
+        assert_eq!(years, (2016..2025).collect_vec());
+        read_years(&mut reader, &years).unwrap();
+        assert_eq!(days_in(&mut reader, "temp").len(), MAX_ATTACHED);
+        // Makes room for the oldest year once a command reads it
+        read_years(&mut reader, &[2010]).unwrap();
+        assert!(days_in(&mut reader, "temp").contains(&"2010-06-01".to_string()));
+        drop(reader);
+
+        let mut writer = prepare_connection(&dir, &SqliteConfig::default()).unwrap();
+        assert!(is_archived(&mut writer, date!(2010 - 06 - 01)).unwrap());
+        for year in 2010..2025 {
+            restore_day(&mut writer, &format!("{year}-06-01")).unwrap();
+        }
+        assert_eq!(days_in(&mut writer, "main").len(), 15);
+        drop(writer);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
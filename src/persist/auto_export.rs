@@ -0,0 +1,185 @@
+use std::{collections::BTreeMap, fs, path::PathBuf, str::FromStr, time::Duration};
+
+use color_eyre::{Result, eyre::Context};
+use itertools::Itertools;
+use time::{Date, format_description::FormatItem, macros::format_description};
+use tokio::time::Instant;
+use tracing::{error, info, warn};
+
+use super::storage::{Storage, StoredDay};
+use crate::{
+    config::Config,
+    export::{ExportFormat, ExportMeta, hooks},
+    shared::{tz::DayZone, validation},
+};
+
+const ISO_DAY: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
+/// Writes the exports of changed days again once they stopped changing for a while, see
+/// [crate::config::ExportConfig::auto_formats]
+pub(super) struct AutoExport {
+    formats: Vec<ExportFormat>,
+    delay: Duration,
+    export_dir: PathBuf,
+    meta: ExportMeta,
+    config: Config,
+    /// Changed days and when to export them
+    pending: BTreeMap<Date, Instant>,
+}
+
+impl AutoExport {
+    /// `None` unless formats to export automatically are configured
+    pub(super) fn new(config: &Config, export_dir: PathBuf, meta: ExportMeta) -> Option<Self> {
+        let formats = config
+            .export
+            .auto_formats
+            .iter()
+            .filter_map(|it| match ExportFormat::from_str(it.trim()) {
+                Ok(format) => Some(format),
+                Err(_) => {
+                    warn!("Unknown format to export automatically: {it}");
+                    None
+                }
+            })
+            .unique()
+            .collect_vec();
+        if formats.is_empty() {
+            return None;
+        }
+        Some(Self {
+            formats,
+            delay: Duration::from_secs(config.export.auto_delay_secs),
+            export_dir,
+            meta,
+            config: config.clone(),
+            pending: BTreeMap::new(),
+        })
+    }
+
+    /// Postpones the export of the day while it keeps changing
+    pub(super) fn schedule(&mut self, day: Date) {
+        self.pending.insert(day, Instant::now() + self.delay);
+    }
+
+    pub(super) fn next_due(&self) -> Option<Instant> {
+        self.pending.values().min().copied()
+    }
+
+    /// Exports the days that are due, all of them if `flush` is set, e.g. when shutting down
+    pub(super) fn export_due(&mut self, storage: &mut impl Storage, flush: bool) {
+        let now = Instant::now();
+        let due = self
+            .pending
+            .iter()
+            .filter(|(_, due)| flush || **due <= now)
+            .map(|(day, _)| *day)
+            .collect_vec();
+        for day in due {
+            self.pending.remove(&day);
+            if let Err(err) = self.export_day(storage, day) {
+                error!("Automatic export of {day} failed: {err:?}");
+            }
+        }
+    }
+
+    fn export_day(&self, storage: &mut impl Storage, day: Date) -> Result<()> {
+        let iso_day = day.format(ISO_DAY)?;
+        let stored = storage.load_day(day)?;
+        let Some(StoredDay {
+            timesheet: sheet,
+            entries,
+            links,
+        }) = stored.filter(|it| !it.entries.is_empty())
+        else {
+            // Files of a day that was emptied are left alone
+            info!("Nothing to export automatically on {iso_day}");
+            return Ok(());
+        };
+        let violations = validation::validate(
+            &entries,
+            DayZone::of(day, sheet.utc_offset_mins),
+            &self.config,
+        );
+        if validation::has_hard_violations(&violations) {
+            warn!("Not exporting {iso_day} automatically, it has problems that block exporting");
+            return Ok(());
+        }
+
+        for format in &self.formats {
+            let content = format.generate(
+                &entries,
+                &links,
+                day,
+                &sheet.notes,
+                sheet.utc_offset_mins,
+                &self.meta,
+                &self.config,
+            )?;
+            let path = format.path_in(&self.export_dir, day);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)
+                    .wrap_err_with(|| format!("creating export dir {}", parent.display()))?;
+            }
+            fs::write(&path, content).wrap_err_with(|| format!("writing {}", path.display()))?;
+            info!("Exported {iso_day} automatically to {}", path.display());
+            // Failures are logged, there is no status line to show them in
+            hooks::spawn(self.config.export.hooks.clone(), path, day, |_| {});
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use diesel::connection::SimpleConnection;
+    use time::macros::date;
+
+    use super::*;
+    use crate::{
+        config::SqliteConfig,
+        persist::{Command, SqliteStorage, prepare_connection},
+    };
+
+    #[test]
+    fn test_exports_changed_day_once_it_is_due() {
+        let dir = std::env::temp_dir().join(format!("sheet-shark-export-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut conn = prepare_connection(&dir.join("data"), &SqliteConfig::default()).unwrap();
+        conn.batch_execute(
+            "INSERT INTO timesheet (day, status, notes) VALUES ('2025-09-22', 'OPEN', ''); \
+             INSERT INTO time_entry \
+               (id, timesheet_day, start_time, duration_mins, description, project_key, billable) \
+             VALUES ('a', '2025-09-22', '09:00', 60, 'planning', 'W', 1);",
+        )
+        .unwrap();
+        drop(conn);
+        let mut config = Config::default();
+        config.export.auto_formats = vec!["json".into(), "unknown".into(), "json".into()];
+        config.export.auto_delay_secs = 60;
+        let meta = ExportMeta::new(None, &config).unwrap();
+        assert!(AutoExport::new(&Config::default(), dir.clone(), meta.clone()).is_none());
+        let mut auto_export = AutoExport::new(&config, dir.join("export"), meta).unwrap();
+        assert_eq!(auto_export.formats, vec![ExportFormat::Json]);
+        let mut storage = SqliteStorage::open(&dir.join("data"), &config).unwrap();
+
+        let cmd = Command::StoreNotes {
+            day: date!(2025 - 09 - 22),
+            notes: String::new(),
+        };
+        let day = storage.day_changed_by(&cmd).unwrap();
+        auto_export.schedule(day);
+        let first_due = auto_export.next_due().unwrap();
+        auto_export.schedule(day);
+        assert!(auto_export.next_due().unwrap() >= first_due);
+
+        let path = ExportFormat::Json.path_in(&dir.join("export"), day);
+        auto_export.export_due(&mut storage, false);
+        assert!(!path.exists());
+        auto_export.export_due(&mut storage, true);
+        assert!(fs::read_to_string(&path).unwrap().contains("planning"));
+        assert_eq!(auto_export.next_due(), None);
+
+        drop(storage);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
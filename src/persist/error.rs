@@ -0,0 +1,240 @@
+use std::fmt::{self, Display};
+
+use color_eyre::eyre::ErrReport;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+
+use super::{Command, CommandKind};
+
+/// Failure of a persist command, classified so that the UI can react to it.
+#[derive(Debug, Clone)]
+pub struct PersistError {
+    pub kind: PersistErrorKind,
+    /// Which command failed
+    pub command: CommandKind,
+    /// Copy of the failed command if it is safe to send again, see [Command::repeatable_copy]
+    pub repeatable: Option<Command>,
+    /// Full error chain for logs
+    pub details: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PersistErrorKind {
+    /// Another process holds a lock on the database, retrying later should work
+    Busy,
+    /// The data violates a database constraint, retrying the same data won't help
+    Constraint,
+    /// The database file cannot be written, e.g. disk full or read-only
+    Storage,
+    /// The database file is damaged
+    Corrupt,
+    Other,
+}
+
+impl PersistError {
+    pub fn from_report(command: CommandKind, repeatable: Option<Command>, err: &ErrReport) -> Self {
+        let kind = err
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<DieselError>())
+            .map(classify)
+            .unwrap_or(PersistErrorKind::Other);
+        Self {
+            kind,
+            command,
+            repeatable,
+            details: format!("{err:?}"),
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        self.kind == PersistErrorKind::Busy && self.repeatable.is_some()
+    }
+}
+
+fn classify(err: &DieselError) -> PersistErrorKind {
+    let DieselError::DatabaseError(kind, info) = err else {
+        return PersistErrorKind::Other;
+    };
+    match kind {
+        DatabaseErrorKind::UniqueViolation
+        | DatabaseErrorKind::ForeignKeyViolation
+        | DatabaseErrorKind::NotNullViolation
+        | DatabaseErrorKind::CheckViolation => PersistErrorKind::Constraint,
+        DatabaseErrorKind::ReadOnlyTransaction => PersistErrorKind::Storage,
+        _ => classify_message(info.message()),
+    }
+}
+
+/// SQLite reports most operational errors with an unknown kind, so go by its messages
+fn classify_message(message: &str) -> PersistErrorKind {
+    let message = message.to_lowercase();
+    if message.contains("locked") || message.contains("busy") {
+        PersistErrorKind::Busy
+    } else if message.contains("malformed") || message.contains("not a database") {
+        PersistErrorKind::Corrupt
+    } else if message.contains("disk") || message.contains("readonly") {
+        PersistErrorKind::Storage
+    } else if message.contains("constraint") {
+        PersistErrorKind::Constraint
+    } else {
+        PersistErrorKind::Other
+    }
+}
+
+impl Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self.kind {
+            PersistErrorKind::Busy => "Database is busy, another sheet-shark might be running",
+            PersistErrorKind::Constraint => "Data was rejected by the database",
+            PersistErrorKind::Storage => "Cannot write to the database, is the disk full?",
+            PersistErrorKind::Corrupt => "Database file is damaged, restore a backup",
+            PersistErrorKind::Other => "Unexpected database error",
+        };
+        write!(f, "{description} ({})", self.command.describe())
+    }
+}
+
+impl Command {
+    /// Short description of what was attempted, for error messages
+    pub fn describe(&self) -> &'static str {
+        CommandKind::from(self).describe()
+    }
+
+    /// Copy to send again after a failure, only of the commands that store the same outcome
+    /// however often they are repeated. Others aren't copied, they might be large.
+    pub fn repeatable_copy(&self) -> Option<Command> {
+        matches!(self, Command::StoreEntry { .. } | Command::DeleteEntry(_)).then(|| self.clone())
+    }
+}
+
+impl CommandKind {
+    /// See [Command::describe]
+    pub fn describe(&self) -> &'static str {
+        match self {
+            CommandKind::StoreEntry => "saving entry",
+            CommandKind::DeleteEntry => "deleting entry",
+            CommandKind::RepairEntry => "repairing entry",
+            CommandKind::LoadTimesheet => "loading timesheet",
+            CommandKind::LoadEntry => "loading entry",
+            CommandKind::LoadTimesheetsOfMonth => "loading month",
+            CommandKind::SuggestTickets => "suggesting tickets",
+            CommandKind::RenameTicket => "renaming ticket",
+            CommandKind::StoreNotes => "saving notes",
+            CommandKind::StoreLinks => "saving links",
+            CommandKind::ImportEntries => "importing entries",
+            CommandKind::LoadEntriesInRange => "loading entries",
+            CommandKind::LoadDailyTotals => "loading daily totals",
+            CommandKind::StoreTemplate => "saving template",
+            CommandKind::LoadTemplates => "loading templates",
+            CommandKind::DeleteTemplate => "deleting template",
+            CommandKind::RecordUsage => "recording usage",
+            CommandKind::LoadUsage => "loading usage",
+            CommandKind::StorePreference => "saving preference",
+            CommandKind::LoadPreference => "loading preference",
+            CommandKind::RunReport => "running report",
+            CommandKind::StoreUtcOffset => "saving UTC offset",
+            CommandKind::ArchiveTicket => "archiving ticket",
+            CommandKind::UnarchiveTicket => "unarchiving ticket",
+            CommandKind::LoadArchivedTickets => "loading archived tickets",
+            CommandKind::LoadRecentProjects => "loading recent projects",
+            CommandKind::LoadCarryOver => "loading entries to carry over",
+            CommandKind::ArchiveBefore => "archiving old timesheets",
+            CommandKind::LoadSnapshots => "loading earlier versions of the day",
+            CommandKind::RestoreSnapshot => "restoring an earlier version of the day",
+            CommandKind::Sync => "syncing with other machines",
+            CommandKind::Cancel => "cancelling",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use color_eyre::eyre::{Context, eyre};
+    use diesel::result::DatabaseErrorInformation;
+
+    use super::*;
+    use crate::persist::{TimeEntry, TimeEntryId};
+
+    struct Info(&'static str);
+
+    impl DatabaseErrorInformation for Info {
+        fn message(&self) -> &str {
+            self.0
+        }
+        fn details(&self) -> Option<&str> {
+            None
+        }
+        fn hint(&self) -> Option<&str> {
+            None
+        }
+        fn table_name(&self) -> Option<&str> {
+            None
+        }
+        fn column_name(&self) -> Option<&str> {
+            None
+        }
+        fn constraint_name(&self) -> Option<&str> {
+            None
+        }
+        fn statement_position(&self) -> Option<i32> {
+            None
+        }
+    }
+
+    fn failed(kind: DatabaseErrorKind, message: &'static str) -> PersistError {
+        let diesel_err = DieselError::DatabaseError(kind, Box::new(Info(message)));
+        let report = Err::<(), _>(diesel_err)
+            .wrap_err("saving time entry")
+            .unwrap_err();
+        let command = Command::DeleteEntry(TimeEntryId::new());
+        PersistError::from_report(CommandKind::DeleteEntry, command.repeatable_copy(), &report)
+    }
+
+    #[test]
+    fn test_classifies_wrapped_diesel_errors() {
+        let busy = failed(DatabaseErrorKind::Unknown, "database is locked");
+        assert_eq!(busy.kind, PersistErrorKind::Busy);
+        assert!(busy.is_retryable());
+
+        let unique = failed(DatabaseErrorKind::UniqueViolation, "UNIQUE failed");
+        assert_eq!(unique.kind, PersistErrorKind::Constraint);
+        assert!(!unique.is_retryable());
+
+        let full = failed(DatabaseErrorKind::Unknown, "database or disk is full");
+        assert_eq!(full.kind, PersistErrorKind::Storage);
+
+        let corrupt = failed(
+            DatabaseErrorKind::Unknown,
+            "database disk image is malformed",
+        );
+        assert_eq!(corrupt.kind, PersistErrorKind::Corrupt);
+    }
+
+    #[test]
+    fn test_only_entries_are_kept_to_retry() {
+        let busy = eyre!(DieselError::DatabaseError(
+            DatabaseErrorKind::Unknown,
+            Box::new(Info("database is locked")),
+        ));
+        let load = Command::LoadTemplates;
+        let err =
+            PersistError::from_report(CommandKind::LoadTemplates, load.repeatable_copy(), &busy);
+        assert_eq!(err.kind, PersistErrorKind::Busy);
+        assert!(!err.is_retryable());
+
+        let store = Command::StoreEntry {
+            entry: TimeEntry::sample("09:00", 30),
+            version: 1,
+        };
+        assert!(store.repeatable_copy().is_some());
+    }
+
+    #[test]
+    fn test_other_errors_have_friendly_message() {
+        let err = PersistError::from_report(CommandKind::DeleteEntry, None, &eyre!("parsing id"));
+        assert_eq!(err.kind, PersistErrorKind::Other);
+        assert_eq!(
+            err.to_string(),
+            "Unexpected database error (deleting entry)"
+        );
+    }
+}
@@ -1,95 +1,299 @@
-use std::str::FromStr;
+use std::{collections::BTreeMap, ops::RangeInclusive, str::FromStr};
 
-use color_eyre::{Result, eyre::Context};
+use color_eyre::{
+    Result,
+    eyre::{Context, bail},
+};
 use diesel::{
-    RunQueryDsl, SqliteConnection,
-    dsl::count,
-    prelude::*,
-    sql_types::{Nullable, Text},
+    RunQueryDsl, SqliteConnection, dsl::count_star, prelude::*, query_builder::QueryFragment,
+    query_dsl::LoadQuery, sql_types::Text, sqlite::Sqlite,
 };
 
 use time::{
-    Date, OffsetDateTime, ext::NumericalDuration, format_description::FormatItem,
+    Date, Month, OffsetDateTime, ext::NumericalDuration, format_description::FormatItem,
     macros::format_description,
 };
 use tracing::{info, warn};
 
-use crate::persist::{
-    Command, Event, TimeEntry, TimeEntryId, Timesheet,
-    schema::{
-        time_entry::{self},
-        timesheet,
+use super::{archive, history, journal, operation::Operation, tombstone};
+use crate::{
+    config::Config,
+    persist::{
+        Command, EntryLink, EntryTemplate, Event, ReportTable, SuggestionRequestId, TimeEntry,
+        TimeEntryId, Timesheet, UsageMetric,
+        schema::{
+            archived_ticket, entry_link, entry_template, preference,
+            time_entry::{self},
+            timesheet, usage_metric,
+        },
     },
+    shared::{BREAK_PROJECT_KEY, chunks_of, holidays::Holidays, report, ticket_rank},
 };
 
-pub(super) async fn handle(conn: &mut SqliteConnection, cmd: Command) -> Result<Event> {
+pub(super) async fn handle(
+    conn: &mut SqliteConnection,
+    written: &mut Written,
+    cmd: Command,
+    operation: &mut Operation<'_>,
+    read_through: bool,
+) -> Result<Event> {
+    archive::restore_for(conn, &cmd)?;
+    history::snapshot_before(conn, &cmd);
+    handle_command(conn, written, cmd, operation, read_through).await
+}
+
+async fn handle_command(
+    conn: &mut SqliteConnection,
+    written: &mut Written,
+    cmd: Command,
+    operation: &mut Operation<'_>,
+    read_through: bool,
+) -> Result<Event> {
     match cmd {
-        Command::StoreEntry { entry, version } => store_entry(conn, entry, version).await,
-        Command::DeleteEntry(id) => delete_entry(conn, id).await,
-        Command::LoadTimesheet { day } => load_timesheet(conn, day).await,
+        Command::StoreEntry { entry, version } => store_entry(conn, written, entry, version).await,
+        Command::DeleteEntry(id) => delete_entry(conn, written, id).await,
+        Command::RepairEntry { raw_id, entry } => repair_entry(conn, raw_id, entry).await,
+        Command::LoadTimesheet { day } => load_timesheet(conn, written, day, read_through).await,
+        Command::LoadEntry(id) => load_entry(conn, id).await,
         Command::LoadTimesheetsOfMonth { day } => load_timesheets_of_month(conn, day).await,
-        Command::SuggestTickets { query } => suggest_tickets(conn, query).await,
+        Command::SuggestTickets { query, request_id } => {
+            suggest_tickets(conn, query, request_id).await
+        }
+        Command::RenameTicket { from, to, range } => rename_ticket(conn, from, to, range).await,
+        Command::StoreNotes { day, notes } => store_notes(conn, day, notes).await,
+        Command::StoreLinks { entry_id, urls } => store_links(conn, entry_id, urls).await,
+        Command::ImportEntries {
+            day,
+            entries,
+            skip_deleted,
+        } => import_entries(conn, day, entries, skip_deleted).await,
+        Command::LoadEntriesInRange { range } => {
+            load_entries_in_range(conn, range, operation, read_through).await
+        }
+        Command::LoadDailyTotals { range } => {
+            load_daily_totals(conn, range, operation, read_through).await
+        }
+        Command::StoreTemplate(template) => store_template(conn, template).await,
+        Command::LoadTemplates => load_templates(conn).await,
+        Command::DeleteTemplate { name } => delete_template(conn, name).await,
+        Command::RecordUsage { feature } => record_usage(conn, feature).await,
+        Command::LoadUsage { range } => load_usage(conn, range).await,
+        Command::StorePreference { key, value } => store_preference(conn, key, value).await,
+        Command::LoadPreference { key } => load_preference(conn, key).await,
+        Command::RunReport { name, sql } => run_report(conn, name, sql).await,
+        Command::StoreUtcOffset { day, offset_mins } => {
+            store_utc_offset(conn, day, offset_mins).await
+        }
+        Command::ArchiveTicket { ticket_key } => archive_ticket(conn, ticket_key).await,
+        Command::UnarchiveTicket { ticket_key } => unarchive_ticket(conn, ticket_key).await,
+        Command::LoadArchivedTickets => load_archived_tickets(conn).await,
+        Command::LoadRecentProjects => load_recent_projects(conn).await,
+        Command::LoadCarryOver { day } => load_carry_over(conn, day).await,
+        Command::ArchiveBefore { day } => {
+            let (days, entries) = archive::archive_before(conn, day, operation)?;
+            Ok(Event::Archived { days, entries })
+        }
+        Command::LoadSnapshots { day } => {
+            let snapshots = history::load(conn, day)?;
+            Ok(Event::SnapshotsLoaded { day, snapshots })
+        }
+        Command::RestoreSnapshot { day, taken_at } => {
+            let entries = history::restore(conn, day, &taken_at, history::now()?)?;
+            Ok(Event::SnapshotRestored {
+                day,
+                taken_at,
+                entries,
+            })
+        }
+        Command::Sync => bail!("Syncing runs on a thread of its own, see Storage::sync_job"),
+        Command::Cancel { id } => bail!("Operation {id} is not running"),
     }
 }
 
-async fn store_entry(conn: &mut SqliteConnection, entry: TimeEntry, version: i32) -> Result<Event> {
-    if entry.is_empty_default() {
-        let deleted_rowcount = diesel::delete(time_entry::table)
+/// Revisions of the entries written last through one database, `0` once they were deleted.
+/// Own writes are never conflicts, even while the UI still sends the revision it loaded.
+#[derive(Default)]
+pub(super) struct Written(BTreeMap<String, i32>);
+
+impl Written {
+    fn get(&self, id: &str) -> Option<i32> {
+        self.0.get(id).copied()
+    }
+
+    fn record(&mut self, id: &str, revision: i32) {
+        self.0.insert(id.to_string(), revision);
+    }
+}
+
+async fn store_entry(
+    conn: &mut SqliteConnection,
+    written: &mut Written,
+    entry: TimeEntry,
+    version: i32,
+) -> Result<Event> {
+    let id = TimeEntryId::from_str(&entry.id)?;
+    if !entry.is_empty_default() {
+        ensure_timesheet_exists(conn, &entry.timesheet_day).await?;
+    }
+    // Holds the write lock from the check until the write, so that no other writer gets between
+    conn.immediate_transaction(|conn| {
+        let stored = time_entry::table
             .filter(time_entry::id.eq(&entry.id))
-            .execute(conn)?;
-        if deleted_rowcount > 0 {
-            info!("Deleted entry that now matches the empty default: {entry:?}");
-        } else {
-            info!("Not storing entry that is the empty default");
+            .select(TimeEntry::as_select())
+            .get_result(conn)
+            .optional()?;
+        let own = written.get(&entry.id);
+        let conflict = match &stored {
+            Some(stored) => stored.revision != entry.revision && own != Some(stored.revision),
+            None => entry.revision > 0 && own != Some(0),
+        };
+        if conflict && version != journal::REPLAYED_VERSION {
+            warn!("Not storing entry changed by another writer: {entry:?}, now {stored:?}");
+            return Ok(Event::EntryConflict {
+                entry,
+                version,
+                stored,
+            });
         }
-        return Ok(Event::EntryStored {
-            id: TimeEntryId::from_str(&entry.id)?,
-            version,
-        });
-    }
-    ensure_timesheet_exists(conn, &entry.timesheet_day).await?;
 
-    diesel::insert_into(time_entry::table)
-        .values(&entry)
-        .on_conflict(time_entry::id)
-        .do_update()
-        .set(&entry)
-        .execute(conn)
-        .wrap_err("saving time entry")?;
-    Ok(Event::EntryStored {
-        id: TimeEntryId::from_str(&entry.id)?,
-        version,
+        if entry.is_empty_default() {
+            let deleted_rowcount = diesel::delete(time_entry::table)
+                .filter(time_entry::id.eq(&entry.id))
+                .execute(conn)?;
+            diesel::delete(entry_link::table.filter(entry_link::entry_id.eq(&entry.id)))
+                .execute(conn)?;
+            if deleted_rowcount > 0 {
+                info!("Deleted entry that now matches the empty default: {entry:?}");
+                written.record(&entry.id, 0);
+            } else {
+                info!("Not storing entry that is the empty default");
+            }
+            return Ok(Event::EntryStored {
+                id,
+                version,
+                revision: 0,
+            });
+        }
+        let revision = match &stored {
+            Some(stored) => stored.revision,
+            None => entry.revision.max(own.unwrap_or(0)),
+        } + 1;
+        let entry = TimeEntry { revision, ..entry };
+        diesel::insert_into(time_entry::table)
+            .values(&entry)
+            .on_conflict(time_entry::id)
+            .do_update()
+            .set(&entry)
+            .execute(conn)
+            .wrap_err("saving time entry")?;
+        written.record(&entry.id, revision);
+        Ok(Event::EntryStored {
+            id,
+            version,
+            revision,
+        })
     })
 }
 
-async fn delete_entry(conn: &mut SqliteConnection, id: TimeEntryId) -> Result<Event> {
-    diesel::delete(time_entry::table.filter(time_entry::id.eq(id.to_string())))
-        .execute(conn)
-        .wrap_err("delete entry")?;
+async fn delete_entry(
+    conn: &mut SqliteConnection,
+    written: &mut Written,
+    id: TimeEntryId,
+) -> Result<Event> {
+    let id = id.to_string();
+    conn.transaction(|conn| {
+        diesel::delete(time_entry::table.filter(time_entry::id.eq(&id))).execute(conn)?;
+        diesel::delete(entry_link::table.filter(entry_link::entry_id.eq(&id))).execute(conn)?;
+        diesel::result::QueryResult::Ok(())
+    })
+    .wrap_err("delete entry")?;
+    written.record(&id, 0);
     Ok(Event::Deleted)
 }
 
-async fn load_timesheet(conn: &mut SqliteConnection, day: Date) -> Result<Event> {
+async fn store_links(
+    conn: &mut SqliteConnection,
+    entry_id: TimeEntryId,
+    urls: Vec<String>,
+) -> Result<Event> {
+    let id = entry_id.to_string();
+    let links = urls
+        .into_iter()
+        .enumerate()
+        .map(|(position, url)| EntryLink {
+            entry_id: id.clone(),
+            position: position as i32,
+            url,
+        })
+        .collect::<Vec<_>>();
+    conn.transaction(|conn| {
+        diesel::delete(entry_link::table.filter(entry_link::entry_id.eq(&id))).execute(conn)?;
+        diesel::insert_into(entry_link::table)
+            .values(&links)
+            .execute(conn)?;
+        diesel::result::QueryResult::Ok(())
+    })
+    .wrap_err_with(|| format!("store links of {id}"))?;
+    Ok(Event::LinksStored { entry_id })
+}
+
+async fn repair_entry(
+    conn: &mut SqliteConnection,
+    raw_id: String,
+    entry: Option<TimeEntry>,
+) -> Result<Event> {
+    conn.transaction(|conn| {
+        diesel::delete(time_entry::table.filter(time_entry::id.eq(&raw_id))).execute(conn)?;
+        if let Some(entry) = &entry {
+            diesel::insert_into(time_entry::table)
+                .values(entry)
+                .execute(conn)?;
+        } else {
+            diesel::delete(entry_link::table.filter(entry_link::entry_id.eq(&raw_id)))
+                .execute(conn)?;
+        }
+        diesel::result::QueryResult::Ok(())
+    })
+    .wrap_err_with(|| format!("repair entry {raw_id:?}"))?;
+    info!("Repaired entry {raw_id:?}, now {entry:?}");
+    Ok(Event::EntryRepaired { raw_id })
+}
+
+/// Archived days are read through the archive views, which can't be cleaned up
+async fn load_timesheet(
+    conn: &mut SqliteConnection,
+    written: &mut Written,
+    day: Date,
+    archived: bool,
+) -> Result<Event> {
     let timesheet = load_timesheet_or_dummy(conn, day).await?;
     let entries = TimeEntry::belonging_to(&timesheet)
         .select(TimeEntry::as_select())
         .order_by(time_entry::start_time)
         .load::<TimeEntry>(conn)
         .wrap_err("loading timesheet entries")?;
-    if entries.is_empty() {
+    if !archived && entries.is_empty() && timesheet.notes.is_empty() {
         warn!("Noticed empty timesheet while loading, cleaning it up: {day}");
         delete_timesheet(conn, day).await?;
-    } else if entries.len() == 1
+    } else if !archived
+        && entries.len() == 1
         && entries[0].start_time == "00:00"
         && entries[0].duration_mins == 0
     {
         warn!("Cleaning up dummy entry: {day}");
-        delete_entry(conn, TimeEntryId::from_str(&entries[0].id)?).await?;
+        delete_entry(conn, written, TimeEntryId::from_str(&entries[0].id)?).await?;
     }
+    let links = entry_link::table
+        .filter(entry_link::entry_id.eq_any(entries.iter().map(|it| &it.id)))
+        .select(EntryLink::as_select())
+        .order_by((entry_link::entry_id, entry_link::position))
+        .load(conn)
+        .wrap_err("loading links of entries")?;
     Ok(Event::TimesheetLoaded {
         day,
         timesheet,
         entries,
+        links,
     })
 }
 
@@ -101,6 +305,23 @@ async fn delete_timesheet(conn: &mut SqliteConnection, day: Date) -> Result<()>
     Ok(())
 }
 
+async fn load_entry(conn: &mut SqliteConnection, id: TimeEntryId) -> Result<Event> {
+    let raw_id = id.to_string();
+    let entry = time_entry::table
+        .filter(time_entry::id.eq(&raw_id))
+        .select(TimeEntry::as_select())
+        .get_result(conn)
+        .optional()
+        .wrap_err_with(|| format!("load entry {raw_id}"))?;
+    let links = entry_link::table
+        .filter(entry_link::entry_id.eq(&raw_id))
+        .select(entry_link::url)
+        .order_by(entry_link::position)
+        .load(conn)
+        .wrap_err_with(|| format!("load links of entry {raw_id}"))?;
+    Ok(Event::EntryLoaded { id, entry, links })
+}
+
 async fn load_timesheets_of_month(conn: &mut SqliteConnection, day: Date) -> Result<Event> {
     let month_like = day.format(ISO_MONTH_WILDCARD)?;
     let timesheets = timesheet::table
@@ -115,6 +336,8 @@ async fn ensure_timesheet_exists(conn: &mut SqliteConnection, day: &str) -> Resu
     let sheet = Timesheet {
         day: day.to_string(),
         status: "OPEN".to_string(),
+        notes: String::new(),
+        utc_offset_mins: None,
     };
     diesel::insert_into(timesheet::table)
         .values(&sheet)
@@ -139,40 +362,731 @@ async fn load_timesheet_or_dummy(conn: &mut SqliteConnection, day: Date) -> Resu
     let dummy = Timesheet {
         day: day.to_string(),
         status: "OPEN".to_string(),
+        notes: String::new(),
+        utc_offset_mins: None,
     };
     Ok(dummy)
 }
 
-define_sql_function!(fn lower(x: Nullable<Text>) -> Text);
-
-async fn suggest_tickets(conn: &mut SqliteConnection, query: String) -> Result<Event> {
-    let query_lower = query.to_lowercase();
+async fn suggest_tickets(
+    conn: &mut SqliteConnection,
+    query: String,
+    request_id: SuggestionRequestId,
+) -> Result<Event> {
     let six_months_ago = OffsetDateTime::now_local()?
         .date()
         .saturating_sub((6 * 30).days());
-    let six_months_ago = six_months_ago.format(ISO_DAY)?;
-    let filter = time_entry::timesheet_day
-        .gt(six_months_ago)
-        .and(time_entry::ticket_key.is_not_null());
+    let uses_per_day: Vec<(String, String, i64)> =
+        ticket_uses_query(&query, six_months_ago.format(ISO_DAY)?).get_results(conn)?;
+    let uses_per_day = uses_per_day
+        .into_iter()
+        .map(|(ticket_key, day, uses)| Ok((ticket_key, Date::parse(&day, ISO_DAY)?, uses as u32)))
+        .collect::<Result<Vec<_>>>()?;
+    let today = OffsetDateTime::now_local()?.date();
+    Ok(Event::TicketsSuggested {
+        suggestions: ticket_rank::rank(uses_per_day, today),
+        query,
+        request_id,
+    })
+}
 
-    let mut select = time_entry::table
+/// Uses of tickets starting like the query per day since `since`. Always the same shape, so that
+/// diesel caches the prepared statement across keystrokes. SQLite's LIKE ignores the case of
+/// ASCII letters, so that the prefix is looked up in the `NOCASE` index of the ticket key.
+fn ticket_uses_query(
+    query: &str,
+    since: String,
+) -> impl LoadQuery<'static, SqliteConnection, (String, String, i64)> + QueryFragment<Sqlite> {
+    let (project_pattern, issue_pattern) = match query.split_once('-') {
+        Some((jira_project, issue_key)) => (format!("{jira_project}%"), format!("%-{issue_key}%")),
+        None => (format!("{query}%"), "%".to_string()),
+    };
+    let filter = time_entry::ticket_key
+        .like(project_pattern)
+        .and(time_entry::ticket_key.like(issue_pattern))
+        .and(time_entry::timesheet_day.gt(since))
+        .and(time_entry::ticket_key.is_not_null())
+        .and(
+            time_entry::ticket_key
+                .assume_not_null()
+                .ne_all(archived_ticket::table.select(archived_ticket::ticket_key)),
+        );
+    time_entry::table
         .filter(filter)
-        .group_by(time_entry::ticket_key)
-        .select(time_entry::ticket_key.assume_not_null())
-        .order_by(count(time_entry::ticket_key))
-        .into_boxed();
+        .group_by((time_entry::ticket_key, time_entry::timesheet_day))
+        .select((
+            time_entry::ticket_key.assume_not_null(),
+            time_entry::timesheet_day,
+            count_star(),
+        ))
+}
+
+async fn rename_ticket(
+    conn: &mut SqliteConnection,
+    from: String,
+    to: String,
+    range: RangeInclusive<Date>,
+) -> Result<Event> {
+    let first_day = range.start().format(ISO_DAY)?;
+    let last_day = range.end().format(ISO_DAY)?;
+    let rows_touched = diesel::update(time_entry::table)
+        .filter(time_entry::ticket_key.eq(&from))
+        .filter(time_entry::timesheet_day.between(&first_day, &last_day))
+        .set((
+            time_entry::ticket_key.eq(&to),
+            time_entry::revision.eq(time_entry::revision + 1),
+        ))
+        .execute(conn)
+        .wrap_err_with(|| format!("rename ticket {from} to {to} from {first_day} to {last_day}"))?
+        + archive::rename_ticket(conn, &from, &to, &first_day, &last_day)?;
+    info!("Renamed ticket {from} to {to} in {rows_touched} entries");
+    Ok(Event::TicketRenamed {
+        from,
+        to,
+        rows_touched,
+    })
+}
+
+async fn store_notes(conn: &mut SqliteConnection, day: Date, notes: String) -> Result<Event> {
+    let iso_day = day.format(ISO_DAY)?;
+    ensure_timesheet_exists(conn, &iso_day).await?;
+    diesel::update(timesheet::table.filter(timesheet::day.eq(&iso_day)))
+        .set(timesheet::notes.eq(&notes))
+        .execute(conn)
+        .wrap_err_with(|| format!("store notes of {iso_day}"))?;
+    Ok(Event::NotesStored { day, notes })
+}
+
+async fn store_utc_offset(
+    conn: &mut SqliteConnection,
+    day: Date,
+    offset_mins: i32,
+) -> Result<Event> {
+    let iso_day = day.format(ISO_DAY)?;
+    ensure_timesheet_exists(conn, &iso_day).await?;
+    diesel::update(timesheet::table.filter(timesheet::day.eq(&iso_day)))
+        .set(timesheet::utc_offset_mins.eq(offset_mins))
+        .execute(conn)
+        .wrap_err_with(|| format!("store UTC offset of {iso_day}"))?;
+    Ok(Event::UtcOffsetStored { day, offset_mins })
+}
+
+/// Days loaded per step of range operations, which report progress if they need several
+const DAYS_PER_STEP: i64 = 31;
+
+/// Reading through the archives, the ones of each chunk are attached before it is loaded, so
+/// that ranges over more years than SQLite attaches at once are read whole
+async fn load_entries_in_range(
+    conn: &mut SqliteConnection,
+    range: RangeInclusive<Date>,
+    operation: &mut Operation<'_>,
+    read_through: bool,
+) -> Result<Event> {
+    let chunks = chunks_of(range.clone(), DAYS_PER_STEP);
+    let mut entries = vec![];
+    for (idx, chunk) in chunks.iter().enumerate() {
+        if read_through {
+            archive::read_range(conn, chunk)?;
+        }
+        let first_day = chunk.start().format(ISO_DAY)?;
+        let last_day = chunk.end().format(ISO_DAY)?;
+        let loaded: Vec<TimeEntry> = time_entry::table
+            .filter(time_entry::timesheet_day.between(&first_day, &last_day))
+            .select(TimeEntry::as_select())
+            .order_by((time_entry::timesheet_day, time_entry::start_time))
+            .load(conn)
+            .wrap_err_with(|| format!("load entries from {first_day} to {last_day}"))?;
+        entries.extend(loaded);
+        report_step(operation, idx, chunks.len())?;
+    }
+    Ok(Event::EntriesInRangeLoaded { range, entries })
+}
+
+/// Attaches the archives of each chunk like [load_entries_in_range]
+async fn load_daily_totals(
+    conn: &mut SqliteConnection,
+    range: RangeInclusive<Date>,
+    operation: &mut Operation<'_>,
+    read_through: bool,
+) -> Result<Event> {
+    let chunks = chunks_of(range.clone(), DAYS_PER_STEP);
+    let mut totals = vec![];
+    for (idx, chunk) in chunks.iter().enumerate() {
+        if read_through {
+            archive::read_range(conn, chunk)?;
+        }
+        let first_day = chunk.start().format(ISO_DAY)?;
+        let last_day = chunk.end().format(ISO_DAY)?;
+        let rows: Vec<(String, Option<i64>)> = time_entry::table
+            .filter(time_entry::timesheet_day.between(&first_day, &last_day))
+            .filter(time_entry::project_key.ne(BREAK_PROJECT_KEY))
+            .group_by(time_entry::timesheet_day)
+            .select((
+                time_entry::timesheet_day,
+                diesel::dsl::sum(time_entry::duration_mins),
+            ))
+            .order_by(time_entry::timesheet_day)
+            .load(conn)
+            .wrap_err_with(|| format!("load daily totals from {first_day} to {last_day}"))?;
+        for (day, mins) in rows {
+            let date = Date::parse(&day, ISO_DAY).wrap_err_with(|| format!("day {day}"))?;
+            totals.push((date, mins.unwrap_or_default()));
+        }
+        report_step(operation, idx, chunks.len())?;
+    }
+    Ok(Event::DailyTotalsLoaded { range, totals })
+}
+
+/// Progress after step `idx`, only for operations with several steps
+fn report_step(operation: &mut Operation<'_>, idx: usize, steps: usize) -> Result<()> {
+    if steps > 1 {
+        operation.progress(idx as u32 + 1, steps as u32)?;
+    }
+    Ok(())
+}
+
+async fn store_template(conn: &mut SqliteConnection, template: EntryTemplate) -> Result<Event> {
+    diesel::insert_into(entry_template::table)
+        .values(&template)
+        .on_conflict(entry_template::name)
+        .do_update()
+        .set(&template)
+        .execute(conn)
+        .wrap_err_with(|| format!("store template {}", template.name))?;
+    Ok(Event::TemplateStored {
+        name: template.name,
+    })
+}
+
+async fn load_templates(conn: &mut SqliteConnection) -> Result<Event> {
+    let templates = entry_template::table
+        .select(EntryTemplate::as_select())
+        .order_by(entry_template::name)
+        .load(conn)
+        .wrap_err("load templates")?;
+    Ok(Event::TemplatesLoaded(templates))
+}
+
+async fn delete_template(conn: &mut SqliteConnection, name: String) -> Result<Event> {
+    diesel::delete(entry_template::table)
+        .filter(entry_template::name.eq(&name))
+        .execute(conn)
+        .wrap_err_with(|| format!("delete template {name}"))?;
+    Ok(Event::TemplateDeleted { name })
+}
 
-    if let Some((jira_project, issue_key)) = query_lower.split_once('-') {
-        select = select
-            .filter(lower(time_entry::ticket_key).like(format!("{jira_project}%")))
-            .filter(lower(time_entry::ticket_key).like(format!("%-{issue_key}%")));
-    } else {
-        select = select.filter(lower(time_entry::ticket_key).like(format!("{query_lower}%")));
+async fn record_usage(conn: &mut SqliteConnection, feature: String) -> Result<Event> {
+    let today = OffsetDateTime::now_local()?.date().format(ISO_DAY)?;
+    diesel::insert_into(usage_metric::table)
+        .values((
+            usage_metric::day.eq(&today),
+            usage_metric::feature.eq(&feature),
+            usage_metric::uses.eq(1),
+        ))
+        .on_conflict((usage_metric::day, usage_metric::feature))
+        .do_update()
+        .set(usage_metric::uses.eq(usage_metric::uses + 1))
+        .execute(conn)
+        .wrap_err_with(|| format!("record usage of {feature}"))?;
+    Ok(Event::UsageRecorded { feature })
+}
+
+async fn load_usage(conn: &mut SqliteConnection, range: RangeInclusive<Date>) -> Result<Event> {
+    let first_day = range.start().format(ISO_DAY)?;
+    let last_day = range.end().format(ISO_DAY)?;
+    let metrics = usage_metric::table
+        .filter(usage_metric::day.between(&first_day, &last_day))
+        .select(UsageMetric::as_select())
+        .order_by((usage_metric::day, usage_metric::feature))
+        .load(conn)
+        .wrap_err_with(|| format!("load usage from {first_day} to {last_day}"))?;
+    Ok(Event::UsageLoaded { range, metrics })
+}
+
+async fn store_preference(
+    conn: &mut SqliteConnection,
+    key: String,
+    value: String,
+) -> Result<Event> {
+    diesel::insert_into(preference::table)
+        .values((preference::key.eq(&key), preference::value.eq(&value)))
+        .on_conflict(preference::key)
+        .do_update()
+        .set(preference::value.eq(&value))
+        .execute(conn)
+        .wrap_err_with(|| format!("store preference {key}"))?;
+    Ok(Event::PreferenceStored { key })
+}
+
+async fn archive_ticket(conn: &mut SqliteConnection, ticket_key: String) -> Result<Event> {
+    let today = OffsetDateTime::now_local()?.date().format(ISO_DAY)?;
+    diesel::insert_into(archived_ticket::table)
+        .values((
+            archived_ticket::ticket_key.eq(&ticket_key),
+            archived_ticket::archived_on.eq(&today),
+        ))
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .wrap_err_with(|| format!("archive ticket {ticket_key}"))?;
+    Ok(Event::TicketArchived { ticket_key })
+}
+
+async fn unarchive_ticket(conn: &mut SqliteConnection, ticket_key: String) -> Result<Event> {
+    diesel::delete(archived_ticket::table.filter(archived_ticket::ticket_key.eq(&ticket_key)))
+        .execute(conn)
+        .wrap_err_with(|| format!("unarchive ticket {ticket_key}"))?;
+    Ok(Event::TicketUnarchived { ticket_key })
+}
+
+async fn load_archived_tickets(conn: &mut SqliteConnection) -> Result<Event> {
+    let tickets = archived_ticket::table
+        .select(archived_ticket::ticket_key)
+        .order_by(archived_ticket::ticket_key)
+        .load(conn)
+        .wrap_err("load archived tickets")?;
+    Ok(Event::ArchivedTicketsLoaded(tickets))
+}
+
+async fn load_recent_projects(conn: &mut SqliteConnection) -> Result<Event> {
+    let six_months_ago = OffsetDateTime::now_local()?
+        .date()
+        .saturating_sub((6 * 30).days());
+    let projects = time_entry::table
+        .filter(time_entry::timesheet_day.gt(six_months_ago.format(ISO_DAY)?))
+        .filter(time_entry::project_key.ne(BREAK_PROJECT_KEY))
+        .group_by(time_entry::project_key)
+        .select(time_entry::project_key)
+        .order_by(diesel::dsl::max(time_entry::timesheet_day).desc())
+        .load(conn)
+        .wrap_err("load recent projects")?;
+    Ok(Event::RecentProjectsLoaded(projects))
+}
+
+async fn load_carry_over(conn: &mut SqliteConnection, day: Date) -> Result<Event> {
+    let previous_day: Option<String> = time_entry::table
+        .filter(time_entry::timesheet_day.lt(day.format(ISO_DAY)?))
+        .select(diesel::dsl::max(time_entry::timesheet_day))
+        .get_result(conn)
+        .wrap_err("find previous day")?;
+    let Some(previous_day) = previous_day else {
+        return Ok(Event::CarryOverLoaded {
+            day,
+            entries: vec![],
+        });
+    };
+    let entries = time_entry::table
+        .filter(time_entry::timesheet_day.eq(&previous_day))
+        .filter(time_entry::carry_over.eq(true))
+        .order_by(time_entry::start_time)
+        .select(TimeEntry::as_select())
+        .load(conn)
+        .wrap_err_with(|| format!("load entries to carry over from {previous_day}"))?;
+    Ok(Event::CarryOverLoaded { day, entries })
+}
+
+async fn load_preference(conn: &mut SqliteConnection, key: String) -> Result<Event> {
+    let value = preference::table
+        .filter(preference::key.eq(&key))
+        .select(preference::value)
+        .get_result(conn)
+        .optional()
+        .wrap_err_with(|| format!("load preference {key}"))?;
+    Ok(Event::PreferenceLoaded { key, value })
+}
+
+#[derive(QueryableByName)]
+struct ReportColumn {
+    #[diesel(sql_type = Text)]
+    name: String,
+}
+
+#[derive(QueryableByName)]
+struct ReportRow {
+    #[diesel(sql_type = Text)]
+    values: String,
+}
+
+/// Diesel needs to know the columns of a query up front, so the report is created as a
+/// temporary view, whose columns are looked up and whose rows are fetched as JSON arrays.
+/// Views cannot contain statements that write, and the view is gone after the transaction.
+async fn run_report(conn: &mut SqliteConnection, name: String, sql: String) -> Result<Event> {
+    let select = report::validate_select(&sql).wrap_err_with(|| format!("report {name}"))?;
+    let (columns, rows) = conn
+        .transaction(|conn| {
+            diesel::sql_query(format!("CREATE TEMP VIEW {REPORT_VIEW} AS {select}"))
+                .execute(conn)?;
+            let columns: Vec<String> = diesel::sql_query(format!(
+                "SELECT name FROM pragma_table_info('{REPORT_VIEW}', 'temp') ORDER BY cid"
+            ))
+            .load::<ReportColumn>(conn)?
+            .into_iter()
+            .map(|it| it.name)
+            .collect();
+            let values = columns
+                .iter()
+                .map(|it| report::quote_identifier(it))
+                .collect::<Vec<_>>()
+                .join(", ");
+            let rows = diesel::sql_query(format!(
+                "SELECT json_array({values}) AS \"values\" FROM temp.{REPORT_VIEW}"
+            ))
+            .load::<ReportRow>(conn)?;
+            diesel::sql_query(format!("DROP VIEW temp.{REPORT_VIEW}")).execute(conn)?;
+            diesel::result::QueryResult::Ok((columns, rows))
+        })
+        .wrap_err_with(|| format!("run report {name}"))?;
+
+    let mut table = ReportTable {
+        columns,
+        rows: Vec::with_capacity(rows.len()),
+    };
+    for row in rows {
+        let values: Vec<serde_json::Value> = serde_json::from_str(&row.values)
+            .wrap_err_with(|| format!("row of report {name}: {}", row.values))?;
+        table
+            .rows
+            .push(values.into_iter().map(report_value).collect());
     }
+    info!("Report {name} returned {} rows", table.rows.len());
+    Ok(Event::ReportLoaded { name, table })
+}
+
+/// Lists the configured absences in the temporary `absence` table, so that reports can tell
+/// time away apart from work, e.g. when comparing against the expected hours
+pub(super) fn register_absences(conn: &mut SqliteConnection, config: &Config) -> Result<()> {
+    diesel::sql_query(
+        "CREATE TEMP TABLE IF NOT EXISTS absence \
+         (key TEXT PRIMARY KEY, project_key TEXT NOT NULL, label TEXT NOT NULL)",
+    )
+    .execute(conn)
+    .wrap_err("create absence table")?;
+    for (key, absence) in &config.absences {
+        diesel::sql_query(
+            "INSERT OR REPLACE INTO temp.absence (key, project_key, label) VALUES (?, ?, ?)",
+        )
+        .bind::<Text, _>(key)
+        .bind::<Text, _>(&absence.project)
+        .bind::<Text, _>(&absence.label)
+        .execute(conn)
+        .wrap_err_with(|| format!("register absence {key}"))?;
+    }
+    Ok(())
+}
+
+/// Years before the current one whose holidays reports can see
+const HOLIDAY_YEARS_BACK: i32 = 5;
+
+/// Lists the public holidays around the current year in the temporary `holiday` table, so that
+/// reports can leave them out of the expected hours
+pub(super) fn register_holidays(conn: &mut SqliteConnection, config: &Config) -> Result<()> {
+    diesel::sql_query(
+        "CREATE TEMP TABLE IF NOT EXISTS holiday (day TEXT PRIMARY KEY, name TEXT NOT NULL)",
+    )
+    .execute(conn)
+    .wrap_err("create holiday table")?;
+    let holidays = Holidays::load(&config.holidays, &config.config.data_dir)?;
+    let year = OffsetDateTime::now_utc().year();
+    let range = Date::from_ordinal_date(year - HOLIDAY_YEARS_BACK, 1)?
+        ..=Date::from_calendar_date(year + 1, Month::December, 31)?;
+    for (day, name) in holidays.between(range) {
+        diesel::sql_query("INSERT OR REPLACE INTO temp.holiday (day, name) VALUES (?, ?)")
+            .bind::<Text, _>(day.format(ISO_DAY)?)
+            .bind::<Text, _>(&name)
+            .execute(conn)
+            .wrap_err_with(|| format!("register holiday {day}"))?;
+    }
+    Ok(())
+}
 
-    let ticket_keys = select.get_results(conn)?;
-    Ok(Event::TicketsSuggested { ticket_keys, query })
+fn report_value(value: serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(text) => text,
+        other => other.to_string(),
+    }
+}
+
+async fn import_entries(
+    conn: &mut SqliteConnection,
+    day: Date,
+    entries: Vec<TimeEntry>,
+    skip_deleted: bool,
+) -> Result<Event> {
+    let iso_day = day.format(ISO_DAY)?;
+    ensure_timesheet_exists(conn, &iso_day).await?;
+    let (imported, skipped, deleted) = conn
+        .transaction(|conn| {
+            let existing: Vec<(String, i32)> = time_entry::table
+                .filter(time_entry::timesheet_day.eq(&iso_day))
+                .select((time_entry::start_time, time_entry::duration_mins))
+                .load(conn)?;
+            let deleted_times = if skip_deleted {
+                tombstone::deleted_times(conn, &iso_day)?
+            } else {
+                vec![]
+            };
+            let (new, duplicates): (Vec<_>, Vec<_>) = entries
+                .into_iter()
+                .map(|entry| TimeEntry {
+                    revision: entry.revision.max(1),
+                    ..entry
+                })
+                .partition(|entry| {
+                    !existing.contains(&(entry.start_time.clone(), entry.duration_mins))
+                });
+            let (new, deleted): (Vec<_>, Vec<_>) = new.into_iter().partition(|entry| {
+                !deleted_times.contains(&(entry.start_time.clone(), entry.duration_mins))
+            });
+            diesel::insert_into(time_entry::table)
+                .values(&new)
+                .execute(conn)?;
+            diesel::result::QueryResult::Ok((new.len(), duplicates.len(), deleted.len()))
+        })
+        .wrap_err_with(|| format!("import entries into {iso_day}"))?;
+    info!(
+        "Imported {imported} entries into {iso_day}, skipped {skipped} existing and {deleted} \
+         deleted"
+    );
+    Ok(Event::EntriesImported {
+        day,
+        imported,
+        skipped,
+        deleted,
+    })
 }
 
+const REPORT_VIEW: &str = "sheet_shark_report";
+
 const ISO_DAY: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
 const ISO_MONTH_WILDCARD: &[FormatItem<'static>] = format_description!("[year]-[month]-%");
+
+#[cfg(test)]
+mod tests {
+    use diesel::{connection::SimpleConnection, debug_query};
+
+    use super::*;
+    use crate::{config::SqliteConfig, persist::prepare_connection};
+
+    #[derive(QueryableByName, Debug)]
+    struct PlanStep {
+        #[diesel(sql_type = Text)]
+        detail: String,
+    }
+
+    #[test]
+    fn test_ticket_suggestions_look_up_prefix_in_index() {
+        let dir = std::env::temp_dir().join(format!("sheet-shark-suggest-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut conn = prepare_connection(&dir, &SqliteConfig::default()).unwrap();
+        // Five years with eight entries a day, spread over three Jira projects
+        conn.batch_execute(
+            "WITH RECURSIVE n(i) AS (SELECT 0 UNION ALL SELECT i + 1 FROM n WHERE i < 1825 * 8 - 1) \
+             INSERT INTO time_entry \
+               (id, timesheet_day, start_time, duration_mins, description, project_key, ticket_key) \
+             SELECT 'e' || i, date('2021-10-15', '+' || (i / 8) || ' days'), \
+               printf('%02d:00', 8 + i % 8), 60, '', 'W', \
+               printf('%s-%d', CASE i % 3 WHEN 0 THEN 'SCRUM' WHEN 1 THEN 'OPS' ELSE 'DEV' END, i % 500) \
+             FROM n;",
+        )
+        .unwrap();
+        let since = "2026-04-15".to_string();
+
+        let query = ticket_uses_query("scrum-12", since.clone());
+        let sql = debug_query::<Sqlite, _>(&query).to_string();
+        let (sql, _) = sql.split_once(" -- binds").unwrap();
+        let plan: Vec<PlanStep> = diesel::sql_query(format!("EXPLAIN QUERY PLAN {sql}"))
+            .bind::<Text, _>("scrum%")
+            .bind::<Text, _>("%-12%")
+            .bind::<Text, _>(&since)
+            .load(&mut conn)
+            .unwrap();
+        assert!(
+            plan.iter()
+                .any(|it| it.detail.contains("INDEX time_entry_ticket")),
+            "{plan:?}"
+        );
+
+        let uses = query.get_results(&mut conn).unwrap();
+        assert!(!uses.is_empty());
+        assert!(
+            uses.iter()
+                .all(|(ticket, day, _)| ticket.starts_with("SCRUM-12") && *day > since),
+            "{uses:?}"
+        );
+
+        drop(conn);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_store_entry_detects_changes_of_other_writers() {
+        let dir = std::env::temp_dir().join(format!("sheet-shark-revision-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut conn = prepare_connection(&dir, &SqliteConfig::default()).unwrap();
+        let id = TimeEntryId::new().to_string();
+        let entry = TimeEntry {
+            id: id.clone(),
+            description: "planning".to_string(),
+            ..TimeEntry::sample("09:00", 30)
+        };
+        let mut written = Written::default();
+        let mut store = |entry: &TimeEntry, version| {
+            futures::executor::block_on(store_entry(
+                &mut conn,
+                &mut written,
+                entry.clone(),
+                version,
+            ))
+            .unwrap()
+        };
+
+        assert!(matches!(
+            store(&entry, 1),
+            Event::EntryStored { revision: 1, .. }
+        ));
+        // Own writes build on each other, even if sent before the revision came back
+        let coding = TimeEntry {
+            description: "coding".to_string(),
+            ..entry.clone()
+        };
+        assert!(matches!(
+            store(&coding, 2),
+            Event::EntryStored { revision: 2, .. }
+        ));
+
+        let mut other = prepare_connection(&dir, &SqliteConfig::default()).unwrap();
+        other
+            .batch_execute(&format!(
+                "UPDATE time_entry SET description = 'review', revision = revision + 1 \
+                 WHERE id = '{id}'"
+            ))
+            .unwrap();
+        let Event::EntryConflict { stored, .. } = store(&coding, 3) else {
+            panic!("expected a conflict");
+        };
+        let stored = stored.unwrap();
+        assert_eq!(
+            (stored.description.as_str(), stored.revision),
+            ("review", 3)
+        );
+
+        // Based on their revision, the entry is stored again
+        assert!(matches!(
+            store(
+                &TimeEntry {
+                    revision: 3,
+                    ..coding.clone()
+                },
+                4
+            ),
+            Event::EntryStored { revision: 4, .. }
+        ));
+        let coding = TimeEntry {
+            revision: 4,
+            ..coding
+        };
+        other
+            .batch_execute(&format!("DELETE FROM time_entry WHERE id = '{id}'"))
+            .unwrap();
+        assert!(matches!(
+            store(&coding, 5),
+            Event::EntryConflict { stored: None, .. }
+        ));
+        // Replayed edits are stored regardless
+        assert!(matches!(
+            store(&coding, journal::REPLAYED_VERSION),
+            Event::EntryStored { revision: 5, .. }
+        ));
+
+        drop(conn);
+        drop(other);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_removing_entries_removes_their_links() {
+        let dir = std::env::temp_dir().join(format!("sheet-shark-links-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut conn = prepare_connection(&dir, &SqliteConfig::default()).unwrap();
+        let mut written = Written::default();
+        let entry = TimeEntry {
+            description: "incident".to_string(),
+            ..TimeEntry::sample("09:00", 30)
+        };
+        let link_count = |conn: &mut SqliteConnection, id: &str| -> i64 {
+            entry_link::table
+                .filter(entry_link::entry_id.eq(id))
+                .count()
+                .get_result(conn)
+                .unwrap()
+        };
+        let store_linked =
+            |conn: &mut SqliteConnection, written: &mut Written, entry: &TimeEntry| {
+                futures::executor::block_on(async {
+                    store_entry(conn, written, entry.clone(), 1).await.unwrap();
+                    let id = TimeEntryId::from_str(&entry.id).unwrap();
+                    let urls = vec!["https://example.com/1".to_string()];
+                    store_links(conn, id, urls).await.unwrap();
+                });
+            };
+
+        store_linked(&mut conn, &mut written, &entry);
+        assert_eq!(link_count(&mut conn, &entry.id), 1);
+        let emptied = TimeEntry {
+            id: entry.id.clone(),
+            revision: 1,
+            ..TimeEntry::sample("00:00", 0)
+        };
+        futures::executor::block_on(store_entry(&mut conn, &mut written, emptied, 2)).unwrap();
+        assert_eq!(link_count(&mut conn, &entry.id), 0);
+
+        // e.g. deleted from the repair popup
+        let repaired = TimeEntry {
+            id: TimeEntryId::new().to_string(),
+            ..entry
+        };
+        store_linked(&mut conn, &mut written, &repaired);
+        futures::executor::block_on(repair_entry(&mut conn, repaired.id.clone(), None)).unwrap();
+        assert_eq!(link_count(&mut conn, &repaired.id), 0);
+
+        drop(conn);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_import_leaves_out_deleted_entries_if_asked() {
+        let dir = std::env::temp_dir().join(format!("sheet-shark-import-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut conn = prepare_connection(&dir, &SqliteConfig::default()).unwrap();
+        let imported = |start_time: &str| TimeEntry {
+            description: "standup".to_string(),
+            ..TimeEntry::sample(start_time, 30)
+        };
+        let import = |conn: &mut SqliteConnection, entries, skip_deleted| {
+            let day = time::macros::date!(2026 - 10 - 15);
+            futures::executor::block_on(import_entries(conn, day, entries, skip_deleted)).unwrap()
+        };
+
+        import(&mut conn, vec![imported("09:00"), imported("10:00")], true);
+        diesel::delete(time_entry::table.filter(time_entry::start_time.eq("09:00")))
+            .execute(&mut conn)
+            .unwrap();
+        assert!(matches!(
+            import(&mut conn, vec![imported("09:00"), imported("10:00")], true),
+            Event::EntriesImported {
+                imported: 0,
+                skipped: 1,
+                deleted: 1,
+                ..
+            }
+        ));
+        // e.g. added by hand again
+        assert!(matches!(
+            import(&mut conn, vec![imported("09:00")], false),
+            Event::EntriesImported { imported: 1, .. }
+        ));
+
+        drop(conn);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,365 @@
+use color_eyre::{
+    Result,
+    eyre::{Context, OptionExt},
+};
+use diesel::{RunQueryDsl, SqliteConnection, prelude::*};
+use serde::{Deserialize, Serialize};
+use time::{
+    Date, OffsetDateTime, PrimitiveDateTime, ext::NumericalDuration,
+    format_description::FormatItem, macros::format_description,
+};
+use tracing::{info, warn};
+
+use super::{
+    Command, DaySnapshot, EntryLink, TimeEntry, Timesheet,
+    schema::{entry_link, time_entry, timesheet, timesheet_snapshot},
+    storage,
+};
+
+const ISO_DAY: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+const TAKEN_AT: &[FormatItem<'static>] =
+    format_description!("[year]-[month]-[day] [hour]:[minute]:[second]");
+
+/// Snapshots older than this are dropped when taking new ones
+const KEEP_DAYS: i64 = 90;
+
+/// What a snapshot holds of the day, stored as JSON
+#[derive(Serialize, Deserialize, PartialEq)]
+struct Content {
+    notes: String,
+    entries: Vec<TimeEntry>,
+    links: Vec<EntryLink>,
+}
+
+/// Keeps the state of the day the command is about to change, at most once per hour, so that
+/// it can be restored later. Failing to do so doesn't keep the command from running.
+pub(super) fn snapshot_before(conn: &mut SqliteConnection, cmd: &Command) {
+    let Some(day) = storage::day_changed_by(conn, cmd) else {
+        return;
+    };
+    if let Err(err) = now().and_then(|now| take_hourly(conn, day, now)) {
+        warn!("Failed to take a snapshot of {day}: {err:?}");
+    }
+}
+
+/// Snapshots of the day, latest first
+pub(super) fn load(conn: &mut SqliteConnection, day: Date) -> Result<Vec<DaySnapshot>> {
+    let iso_day = day.format(ISO_DAY)?;
+    let rows: Vec<(String, String)> = timesheet_snapshot::table
+        .filter(timesheet_snapshot::day.eq(&iso_day))
+        .select((timesheet_snapshot::taken_at, timesheet_snapshot::content))
+        .order_by(timesheet_snapshot::taken_at.desc())
+        .load(conn)
+        .wrap_err_with(|| format!("load snapshots of {iso_day}"))?;
+    rows.into_iter()
+        .map(|(taken_at, content)| {
+            let content: Content = serde_json::from_str(&content)
+                .wrap_err_with(|| format!("read snapshot of {iso_day} at {taken_at}"))?;
+            Ok(DaySnapshot {
+                taken_at,
+                notes: content.notes,
+                entries: content.entries,
+                links: content.links,
+            })
+        })
+        .collect()
+}
+
+/// Replaces notes, entries and links of the day with those of the snapshot, after taking
+/// one of the current state so that the restore can be undone the same way
+pub(super) fn restore(
+    conn: &mut SqliteConnection,
+    day: Date,
+    taken_at: &str,
+    now: PrimitiveDateTime,
+) -> Result<usize> {
+    let iso_day = day.format(ISO_DAY)?;
+    let content: String = timesheet_snapshot::table
+        .filter(timesheet_snapshot::day.eq(&iso_day))
+        .filter(timesheet_snapshot::taken_at.eq(taken_at))
+        .select(timesheet_snapshot::content)
+        .get_result(conn)
+        .optional()?
+        .ok_or_eyre(format!("No snapshot of {iso_day} at {taken_at}"))?;
+    let content: Content = serde_json::from_str(&content)
+        .wrap_err_with(|| format!("read snapshot of {iso_day} at {taken_at}"))?;
+    take(conn, &iso_day, now, None)?;
+
+    let ids = content
+        .entries
+        .iter()
+        .map(|it| it.id.as_str())
+        .collect::<Vec<_>>();
+    conn.transaction(|conn| {
+        // Above the current revisions, so that other writers notice the restore
+        let newest: Option<i32> = time_entry::table
+            .filter(
+                time_entry::timesheet_day
+                    .eq(&iso_day)
+                    .or(time_entry::id.eq_any(&ids)),
+            )
+            .select(diesel::dsl::max(time_entry::revision))
+            .get_result(conn)?;
+        let entries = content
+            .entries
+            .iter()
+            .map(|entry| TimeEntry {
+                revision: entry.revision.max(newest.unwrap_or(0)) + 1,
+                ..entry.clone()
+            })
+            .collect::<Vec<_>>();
+        let current = time_entry::table
+            .filter(time_entry::timesheet_day.eq(&iso_day))
+            .select(time_entry::id);
+        diesel::delete(entry_link::table.filter(entry_link::entry_id.eq_any(current)))
+            .execute(conn)?;
+        diesel::delete(entry_link::table.filter(entry_link::entry_id.eq_any(&ids)))
+            .execute(conn)?;
+        diesel::delete(
+            time_entry::table.filter(
+                time_entry::timesheet_day
+                    .eq(&iso_day)
+                    .or(time_entry::id.eq_any(&ids)),
+            ),
+        )
+        .execute(conn)?;
+        let sheet = Timesheet {
+            day: iso_day.clone(),
+            status: "OPEN".to_string(),
+            notes: content.notes.clone(),
+            utc_offset_mins: None,
+        };
+        diesel::insert_into(timesheet::table)
+            .values(&sheet)
+            .on_conflict(timesheet::day)
+            .do_update()
+            .set(timesheet::notes.eq(&content.notes))
+            .execute(conn)?;
+        diesel::insert_into(time_entry::table)
+            .values(&entries)
+            .execute(conn)?;
+        diesel::insert_into(entry_link::table)
+            .values(&content.links)
+            .execute(conn)?;
+        diesel::result::QueryResult::Ok(())
+    })
+    .wrap_err_with(|| format!("restore {iso_day} to {taken_at}"))?;
+    info!(
+        "Restored {iso_day} to its snapshot at {taken_at} with {} entries",
+        content.entries.len()
+    );
+    Ok(content.entries.len())
+}
+
+pub(super) fn now() -> Result<PrimitiveDateTime> {
+    let now = OffsetDateTime::now_local()?;
+    Ok(PrimitiveDateTime::new(now.date(), now.time()))
+}
+
+/// Takes a snapshot unless there already is one since the start of the hour
+pub(super) fn take_hourly(
+    conn: &mut SqliteConnection,
+    day: Date,
+    now: PrimitiveDateTime,
+) -> Result<bool> {
+    let iso_day = day.format(ISO_DAY)?;
+    let latest: Option<(String, String)> = timesheet_snapshot::table
+        .filter(timesheet_snapshot::day.eq(&iso_day))
+        .select((timesheet_snapshot::taken_at, timesheet_snapshot::content))
+        .order_by(timesheet_snapshot::taken_at.desc())
+        .first(conn)
+        .optional()?;
+    let hour_start = now.replace_minute(0)?.replace_second(0)?.format(TAKEN_AT)?;
+    if latest
+        .as_ref()
+        .is_some_and(|(taken_at, _)| *taken_at >= hour_start)
+    {
+        return Ok(false);
+    }
+    take(conn, &iso_day, now, latest.map(|(_, content)| content))
+}
+
+/// Stores the current state of the day unless it has no entries or notes, or is the same as
+/// in the latest snapshot
+fn take(
+    conn: &mut SqliteConnection,
+    iso_day: &str,
+    now: PrimitiveDateTime,
+    latest: Option<String>,
+) -> Result<bool> {
+    let notes: Option<String> = timesheet::table
+        .filter(timesheet::day.eq(iso_day))
+        .select(timesheet::notes)
+        .get_result(conn)
+        .optional()?;
+    let entries = time_entry::table
+        .filter(time_entry::timesheet_day.eq(iso_day))
+        .select(TimeEntry::as_select())
+        .order_by((time_entry::start_time, time_entry::id))
+        .load(conn)?;
+    let links = entry_link::table
+        .filter(
+            entry_link::entry_id.eq_any(
+                time_entry::table
+                    .filter(time_entry::timesheet_day.eq(iso_day))
+                    .select(time_entry::id),
+            ),
+        )
+        .select(EntryLink::as_select())
+        .order_by((entry_link::entry_id, entry_link::position))
+        .load(conn)?;
+    let content = Content {
+        notes: notes.unwrap_or_default(),
+        entries,
+        links,
+    };
+    if content.entries.is_empty() && content.notes.is_empty() {
+        return Ok(false);
+    }
+    let latest = match latest {
+        Some(latest) => Some(latest),
+        None => timesheet_snapshot::table
+            .filter(timesheet_snapshot::day.eq(iso_day))
+            .select(timesheet_snapshot::content)
+            .order_by(timesheet_snapshot::taken_at.desc())
+            .first(conn)
+            .optional()?,
+    };
+    let content = serde_json::to_string(&content)?;
+    if latest.is_some_and(|it| it == content) {
+        return Ok(false);
+    }
+
+    let taken_at = now.format(TAKEN_AT)?;
+    diesel::replace_into(timesheet_snapshot::table)
+        .values((
+            timesheet_snapshot::day.eq(iso_day),
+            timesheet_snapshot::taken_at.eq(&taken_at),
+            timesheet_snapshot::content.eq(&content),
+        ))
+        .execute(conn)
+        .wrap_err_with(|| format!("take snapshot of {iso_day}"))?;
+    let oldest_kept = (now - KEEP_DAYS.days()).format(TAKEN_AT)?;
+    diesel::delete(timesheet_snapshot::table.filter(timesheet_snapshot::taken_at.lt(oldest_kept)))
+        .execute(conn)
+        .wrap_err("drop old snapshots")?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use diesel::connection::SimpleConnection;
+    use time::macros::{date, datetime};
+
+    use super::*;
+    use crate::{config::SqliteConfig, persist::prepare_connection};
+
+    fn descriptions(conn: &mut SqliteConnection) -> Vec<String> {
+        time_entry::table
+            .select(time_entry::description)
+            .order_by(time_entry::start_time)
+            .load(conn)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_takes_snapshots_hourly_and_restores_them() {
+        let dir = std::env::temp_dir().join(format!("sheet-shark-history-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut conn = prepare_connection(&dir, &SqliteConfig::default()).unwrap();
+        let day = date!(2026 - 10 - 15);
+        assert!(!take_hourly(&mut conn, day, datetime!(2026-10-15 09:10)).unwrap());
+
+        conn.batch_execute(
+            "INSERT INTO timesheet (day, status, notes) VALUES ('2026-10-15', 'OPEN', 'first'); \
+             INSERT INTO time_entry \
+               (id, timesheet_day, start_time, duration_mins, description, project_key, billable) \
+             VALUES ('a', '2026-10-15', '09:00', 60, 'planning', 'W', 1); \
+             INSERT INTO entry_link (entry_id, position, url) VALUES ('a', 0, 'https://x');",
+        )
+        .unwrap();
+        assert!(take_hourly(&mut conn, day, datetime!(2026-10-15 09:20)).unwrap());
+        conn.batch_execute("UPDATE time_entry SET description = 'coding'")
+            .unwrap();
+        // Once per hour, and only if something changed
+        assert!(!take_hourly(&mut conn, day, datetime!(2026-10-15 09:59)).unwrap());
+        assert!(take_hourly(&mut conn, day, datetime!(2026-10-15 10:00)).unwrap());
+        assert!(!take_hourly(&mut conn, day, datetime!(2026-10-15 11:00)).unwrap());
+
+        conn.batch_execute(
+            "DELETE FROM entry_link; UPDATE timesheet SET notes = ''; \
+             INSERT INTO time_entry \
+               (id, timesheet_day, start_time, duration_mins, description, project_key, billable) \
+             VALUES ('b', '2026-10-15', '10:00', 30, 'review', 'W', 1);",
+        )
+        .unwrap();
+        let snapshots = load(&mut conn, day).unwrap();
+        let taken = snapshots
+            .iter()
+            .map(|it| it.taken_at.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(taken, ["2026-10-15 10:00:00", "2026-10-15 09:20:00"]);
+
+        let restored = restore(
+            &mut conn,
+            day,
+            "2026-10-15 09:20:00",
+            datetime!(2026-10-15 11:30),
+        )
+        .unwrap();
+        assert_eq!(restored, 1);
+        assert_eq!(descriptions(&mut conn), ["planning"]);
+        let notes: String = timesheet::table
+            .select(timesheet::notes)
+            .get_result(&mut conn)
+            .unwrap();
+        assert_eq!(notes, "first");
+        let urls: Vec<String> = entry_link::table
+            .select(entry_link::url)
+            .load(&mut conn)
+            .unwrap();
+        assert_eq!(urls, ["https://x"]);
+
+        // The state before restoring can be restored in turn
+        let latest = load(&mut conn, day).unwrap().remove(0);
+        assert_eq!(latest.taken_at, "2026-10-15 11:30:00");
+        restore(
+            &mut conn,
+            day,
+            &latest.taken_at,
+            datetime!(2026-10-15 11:31),
+        )
+        .unwrap();
+        assert_eq!(descriptions(&mut conn), ["coding", "review"]);
+    }
+
+    #[test]
+    fn test_drops_old_snapshots() {
+        let dir =
+            std::env::temp_dir().join(format!("sheet-shark-history-prune-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut conn = prepare_connection(&dir, &SqliteConfig::default()).unwrap();
+        conn.batch_execute(
+            "INSERT INTO timesheet (day, status, notes) \
+               VALUES ('2026-03-02', 'OPEN', 'old'), ('2026-10-15', 'OPEN', 'new');",
+        )
+        .unwrap();
+        take_hourly(
+            &mut conn,
+            date!(2026 - 03 - 02),
+            datetime!(2026-03-02 17:00),
+        )
+        .unwrap();
+        take_hourly(
+            &mut conn,
+            date!(2026 - 10 - 15),
+            datetime!(2026-10-15 09:00),
+        )
+        .unwrap();
+
+        assert!(load(&mut conn, date!(2026 - 03 - 02)).unwrap().is_empty());
+        assert_eq!(load(&mut conn, date!(2026 - 10 - 15)).unwrap().len(), 1);
+    }
+}
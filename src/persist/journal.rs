@@ -0,0 +1,226 @@
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{Result, eyre::Context};
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use super::{TimeEntry, TimeEntryId};
+use crate::shared::DataVersionNumber;
+
+const JOURNAL_FILE: &str = "edits.journal";
+
+/// Version that recovered entries are replayed with, so that no open item takes it as its own
+pub const REPLAYED_VERSION: DataVersionNumber = -1;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Record {
+    Store {
+        entry: TimeEntry,
+        version: DataVersionNumber,
+    },
+    Confirmed {
+        id: String,
+        version: DataVersionNumber,
+    },
+}
+
+/// Append-only file of entries sent to be stored, until the persist thread confirms them.
+/// What is left over after a crash is offered for replay on the next start.
+pub struct EditJournal {
+    path: PathBuf,
+    file: File,
+    /// Latest unconfirmed version per entry id
+    pending: HashMap<String, (TimeEntry, DataVersionNumber)>,
+    /// Ids that were pending when the journal was opened
+    recovered: Vec<String>,
+}
+
+impl EditJournal {
+    /// Opens the journal in the data dir, keeping only the entries that were never confirmed
+    pub fn open(data_dir: &Path) -> Result<Self> {
+        let path = data_dir.join(JOURNAL_FILE);
+        let mut pending = read_pending(&path)?;
+        for (_, version) in pending.values_mut() {
+            *version = REPLAYED_VERSION;
+        }
+        let mut recovered: Vec<_> = pending.keys().cloned().collect();
+        recovered.sort();
+        // Appending, so that writes continue at the start once the file was emptied
+        let file = File::create(&path)
+            .and_then(|_| OpenOptions::new().append(true).open(&path))
+            .wrap_err_with(|| format!("creating edit journal {}", path.display()))?;
+        let mut journal = Self {
+            path,
+            file,
+            pending: HashMap::new(),
+            recovered,
+        };
+        for (entry, version) in pending.into_values() {
+            journal.record(&entry, version)?;
+        }
+        Ok(journal)
+    }
+
+    /// Entries of the previous session that were never confirmed, sorted by id
+    pub fn recovered(&self) -> Vec<TimeEntry> {
+        self.recovered
+            .iter()
+            .filter_map(|id| self.pending.get(id))
+            .map(|(entry, _)| entry.clone())
+            .collect()
+    }
+
+    /// Forgets the recovered entries, e.g. after the user declined to replay them
+    pub fn discard_recovered(&mut self) -> Result<()> {
+        for id in std::mem::take(&mut self.recovered) {
+            if let Some((_, version)) = self.pending.get(&id) {
+                let version = *version;
+                self.append(&Record::Confirmed { id, version })?;
+            }
+        }
+        self.compact_if_done()
+    }
+
+    /// Call before sending the entry to be stored
+    pub fn record(&mut self, entry: &TimeEntry, version: DataVersionNumber) -> Result<()> {
+        self.pending
+            .insert(entry.id.clone(), (entry.clone(), version));
+        self.append(&Record::Store {
+            entry: entry.clone(),
+            version,
+        })
+    }
+
+    /// Call when the entry was stored, earlier versions are confirmed by later ones
+    pub fn confirm(&mut self, id: &TimeEntryId, version: DataVersionNumber) -> Result<()> {
+        let id = id.to_string();
+        match self.pending.get(&id) {
+            Some((_, pending)) if *pending <= version => {}
+            _ => return Ok(()),
+        }
+        self.append(&Record::Confirmed { id, version })?;
+        self.compact_if_done()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    fn append(&mut self, record: &Record) -> Result<()> {
+        if let Record::Confirmed { id, .. } = record {
+            self.pending.remove(id);
+        }
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+        self.file
+            .write_all(line.as_bytes())
+            .and_then(|_| self.file.sync_data())
+            .wrap_err_with(|| format!("writing edit journal {}", self.path.display()))
+    }
+
+    /// Empties the file once everything is confirmed, so that it does not keep growing
+    fn compact_if_done(&mut self) -> Result<()> {
+        if self.pending.is_empty() {
+            self.file
+                .set_len(0)
+                .wrap_err_with(|| format!("truncating edit journal {}", self.path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+/// Entries in the journal of the data dir that were not stored yet, without taking it over
+pub fn unconfirmed(data_dir: &Path) -> Result<Vec<TimeEntry>> {
+    let pending = read_pending(&data_dir.join(JOURNAL_FILE))?;
+    Ok(pending.into_values().map(|(entry, _)| entry).collect())
+}
+
+fn read_pending(path: &Path) -> Result<HashMap<String, (TimeEntry, DataVersionNumber)>> {
+    let mut pending = HashMap::new();
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(pending),
+        Err(err) => {
+            return Err(err).wrap_err_with(|| format!("opening edit journal {}", path.display()));
+        }
+    };
+    for line in BufReader::new(file).lines() {
+        let line = line.wrap_err("reading edit journal")?;
+        // The last line might be cut off by the crash
+        match serde_json::from_str(&line) {
+            Ok(Record::Store { entry, version }) => {
+                pending.insert(entry.id.clone(), (entry, version));
+            }
+            Ok(Record::Confirmed { id, version }) => {
+                if pending.get(&id).is_some_and(|(_, it)| *it <= version) {
+                    pending.remove(&id);
+                }
+            }
+            Err(err) => warn!("Skipping unreadable edit journal line {line:?}: {err}"),
+        }
+    }
+    Ok(pending)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, str::FromStr};
+
+    use super::*;
+
+    fn entry(id: &TimeEntryId, description: &str) -> TimeEntry {
+        TimeEntry {
+            id: id.to_string(),
+            timesheet_day: "2025-09-22".into(),
+            project_key: "E".into(),
+            description: description.into(),
+            billable: true,
+            ..TimeEntry::sample("09:00", 30)
+        }
+    }
+
+    #[test]
+    fn test_recovers_unconfirmed_entries() {
+        let dir = std::env::temp_dir().join(format!("sheet-shark-journal-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let stored = TimeEntryId::new();
+        let lost = TimeEntryId::new();
+
+        let mut journal = EditJournal::open(&dir).unwrap();
+        journal.record(&entry(&stored, "stored"), 1).unwrap();
+        journal.record(&entry(&lost, "first"), 1).unwrap();
+        journal.record(&entry(&lost, "second"), 2).unwrap();
+        journal.confirm(&stored, 1).unwrap();
+        journal.confirm(&lost, 1).unwrap();
+        drop(journal);
+
+        let mut journal = EditJournal::open(&dir).unwrap();
+        let recovered = journal.recovered();
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(recovered[0].description, "second");
+
+        journal
+            .confirm(
+                &TimeEntryId::from_str(&recovered[0].id).unwrap(),
+                REPLAYED_VERSION,
+            )
+            .unwrap();
+        assert!(journal.is_empty());
+        assert_eq!(fs::metadata(dir.join(JOURNAL_FILE)).unwrap().len(), 0);
+        journal.record(&entry(&stored, "again"), 2).unwrap();
+        assert_eq!(unconfirmed(&dir).unwrap()[0].description, "again");
+        let content = fs::read_to_string(dir.join(JOURNAL_FILE)).unwrap();
+        assert!(content.starts_with('{'), "{content:?}");
+        journal.confirm(&stored, 2).unwrap();
+        drop(journal);
+        assert!(EditJournal::open(&dir).unwrap().recovered().is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,40 +1,183 @@
+use std::{ops::RangeInclusive, str::FromStr};
+
 use crate::shared::DataVersionNumber;
 
-use super::schema::*;
+use super::{PersistError, schema::*};
+use chrono::{NaiveTime, TimeDelta};
+use color_eyre::{Result, eyre::Context};
 use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::EnumDiscriminants;
 use time::Date;
 use type_safe_id::{StaticType, TypeSafeId};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, EnumDiscriminants)]
+#[strum_discriminants(name(CommandKind))]
 pub enum Command {
+    /// Fails with [Event::EntryConflict] if another writer changed the entry since the revision
+    /// it is based on, unless it is replayed from the journal
     StoreEntry {
         entry: TimeEntry,
         version: DataVersionNumber,
     },
     DeleteEntry(TimeEntryId),
+    /// Replaces an entry that could not be read by its fixed version, or deletes it if `None`
+    RepairEntry {
+        raw_id: String,
+        entry: Option<TimeEntry>,
+    },
     LoadTimesheet {
         day: Date,
     },
+    /// Saved values of a single entry, e.g. to compare them against unsaved changes
+    LoadEntry(TimeEntryId),
     LoadTimesheetsOfMonth {
         day: Date,
     },
     SuggestTickets {
         query: String,
+        request_id: SuggestionRequestId,
+    },
+    RenameTicket {
+        from: String,
+        to: String,
+        range: RangeInclusive<Date>,
+    },
+    StoreNotes {
+        day: Date,
+        notes: String,
+    },
+    /// Replaces the links attached to the entry, in this order
+    StoreLinks {
+        entry_id: TimeEntryId,
+        urls: Vec<String>,
+    },
+    LoadEntriesInRange {
+        range: RangeInclusive<Date>,
+    },
+    /// Working minutes per day, breaks excluded
+    LoadDailyTotals {
+        range: RangeInclusive<Date>,
+    },
+    StoreUtcOffset {
+        day: Date,
+        offset_mins: i32,
+    },
+    /// Replaces any template with the same name
+    StoreTemplate(EntryTemplate),
+    LoadTemplates,
+    DeleteTemplate {
+        name: String,
+    },
+    /// Counts a use of the feature today, see [crate::shared::insights::Feature]
+    RecordUsage {
+        feature: String,
+    },
+    LoadUsage {
+        range: RangeInclusive<Date>,
+    },
+    /// Remembers a choice of the user, e.g. the last export formats
+    StorePreference {
+        key: String,
+        value: String,
+    },
+    LoadPreference {
+        key: String,
+    },
+    /// Runs a configured report, which is checked to only read
+    RunReport {
+        name: String,
+        sql: String,
+    },
+    /// Leaves the ticket out of suggestions, e.g. once it is closed
+    ArchiveTicket {
+        ticket_key: String,
+    },
+    UnarchiveTicket {
+        ticket_key: String,
+    },
+    LoadArchivedTickets,
+    /// Project keys of recent entries, for the project picker
+    LoadRecentProjects,
+    /// Entries marked to carry over on the last day before this one that has entries
+    LoadCarryOver {
+        day: Date,
+    },
+    /// Adds the entries to the day, except for those that already exist with the same times
+    ImportEntries {
+        day: Date,
+        entries: Vec<TimeEntry>,
+        /// Also leaves out those deleted from the day before, so that repeated imports don't
+        /// bring them back
+        skip_deleted: bool,
+    },
+    /// Earlier states of the day, see [crate::persist::history]
+    LoadSnapshots {
+        day: Date,
+    },
+    /// Puts the day back the way it was when the snapshot was taken
+    RestoreSnapshot {
+        day: Date,
+        taken_at: String,
+    },
+    /// Moves the timesheets before the day into the archive database of their year, see
+    /// [crate::persist::archive]
+    ArchiveBefore {
+        day: Date,
+    },
+    /// Pulls and pushes the recent days, see [crate::sync], failing if syncing is not
+    /// configured
+    Sync,
+    /// Stops the operation that reported [Event::Progress] with this id, which then responds
+    /// with [Event::Cancelled] instead of its result
+    Cancel {
+        id: OperationId,
     },
 }
 
 #[derive(Debug, Clone)]
 pub enum Event {
-    Failure(String),
+    Failure(PersistError),
+    /// Sent while an operation that takes a while is running, until its result is sent
+    Progress {
+        id: OperationId,
+        done: u32,
+        total: u32,
+    },
+    Cancelled {
+        id: OperationId,
+    },
     Deleted,
+    EntryRepaired {
+        raw_id: String,
+    },
     EntryStored {
         id: TimeEntryId,
         version: DataVersionNumber,
+        /// Stored revision of the entry now
+        revision: i32,
+    },
+    /// The entry was not stored because another writer, e.g. a second sheet-shark, changed
+    /// or deleted it after the revision it is based on
+    EntryConflict {
+        entry: TimeEntry,
+        version: DataVersionNumber,
+        /// `None` if it was deleted
+        stored: Option<TimeEntry>,
+    },
+    /// `None` if the entry was not saved yet
+    EntryLoaded {
+        id: TimeEntryId,
+        entry: Option<TimeEntry>,
+        /// URLs in order of their position
+        links: Vec<String>,
     },
     TimesheetLoaded {
         day: Date,
         timesheet: Timesheet,
         entries: Vec<TimeEntry>,
+        /// Of all entries, ordered by entry and position
+        links: Vec<EntryLink>,
     },
     TimesheetsOfMonthLoaded {
         day: Date,
@@ -42,8 +185,120 @@ pub enum Event {
     },
     TicketsSuggested {
         query: String,
-        ticket_keys: Vec<String>,
+        request_id: SuggestionRequestId,
+        /// Best match first, see [crate::shared::ticket_rank]
+        suggestions: Vec<TicketSuggestion>,
+    },
+    TicketRenamed {
+        from: String,
+        to: String,
+        rows_touched: usize,
+    },
+    NotesStored {
+        day: Date,
+        notes: String,
+    },
+    LinksStored {
+        entry_id: TimeEntryId,
+    },
+    UtcOffsetStored {
+        day: Date,
+        offset_mins: i32,
+    },
+    /// Ordered by day and start time
+    EntriesInRangeLoaded {
+        range: RangeInclusive<Date>,
+        entries: Vec<TimeEntry>,
+    },
+    TemplateStored {
+        name: String,
+    },
+    /// Ordered by name
+    TemplatesLoaded(Vec<EntryTemplate>),
+    TemplateDeleted {
+        name: String,
+    },
+    UsageRecorded {
+        feature: String,
+    },
+    /// Ordered by day
+    UsageLoaded {
+        range: RangeInclusive<Date>,
+        metrics: Vec<UsageMetric>,
     },
+    PreferenceStored {
+        key: String,
+    },
+    /// `None` if never stored
+    PreferenceLoaded {
+        key: String,
+        value: Option<String>,
+    },
+    ReportLoaded {
+        name: String,
+        table: ReportTable,
+    },
+    /// Only days with entries, ordered by day
+    DailyTotalsLoaded {
+        range: RangeInclusive<Date>,
+        totals: Vec<(Date, i64)>,
+    },
+    EntriesImported {
+        day: Date,
+        imported: usize,
+        skipped: usize,
+        /// Left out since they were deleted before, see [Command::ImportEntries]
+        deleted: usize,
+    },
+    TicketArchived {
+        ticket_key: String,
+    },
+    TicketUnarchived {
+        ticket_key: String,
+    },
+    /// Ordered by ticket key
+    ArchivedTicketsLoaded(Vec<String>),
+    /// Most recently used first
+    RecentProjectsLoaded(Vec<String>),
+    CarryOverLoaded {
+        day: Date,
+        entries: Vec<TimeEntry>,
+    },
+    Archived {
+        days: usize,
+        entries: usize,
+    },
+    /// Latest first
+    SnapshotsLoaded {
+        day: Date,
+        snapshots: Vec<DaySnapshot>,
+    },
+    SnapshotRestored {
+        day: Date,
+        taken_at: String,
+        entries: usize,
+    },
+    Synced {
+        /// Days that changed because of changes made elsewhere
+        pulled: Vec<Date>,
+        /// Number of days uploaded
+        pushed: usize,
+    },
+}
+
+/// Id of a command being handled, to tell its progress apart and cancel it
+pub type OperationId = u64;
+
+/// Monotonically increasing id of a suggestion query, so that the UI can discard responses
+/// to superseded queries even if they arrive out of order.
+pub type SuggestionRequestId = u64;
+
+/// Ticket of recent entries, with how often and when it was last used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TicketSuggestion {
+    pub ticket_key: String,
+    pub uses: u32,
+    pub last_used: Date,
 }
 
 #[derive(Insertable, Queryable, Identifiable, Selectable, Debug, Clone)]
@@ -52,10 +307,24 @@ pub enum Event {
 pub struct Timesheet {
     pub day: String,
     pub status: String,
+    pub notes: String,
+    /// Offset from UTC in effect when the day was recorded, `None` if unknown
+    pub utc_offset_mins: Option<i32>,
 }
 
 #[derive(
-    Queryable, Insertable, AsChangeset, Identifiable, Selectable, Debug, Clone, Associations,
+    Queryable,
+    Insertable,
+    AsChangeset,
+    Identifiable,
+    Selectable,
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    Associations,
+    Serialize,
+    Deserialize,
 )]
 #[diesel(check_for_backend(diesel::sqlite::Sqlite))]
 #[diesel(belongs_to(Timesheet, foreign_key = timesheet_day))]
@@ -70,6 +339,23 @@ pub struct TimeEntry {
     pub duration_mins: i32,
     pub description: String,
     pub start_time: String,
+    pub billable: bool,
+    /// Key of the configured work type, see [crate::config::Config::work_types]
+    #[serde(default)]
+    pub work_type: Option<String>,
+    /// Unfinished, offered again when starting the next day
+    #[serde(default)]
+    pub carry_over: bool,
+    /// Kept to the minute, even if exports or bookings round, e.g. for legally required breaks
+    #[serde(default)]
+    pub exact: bool,
+    /// Keys of the configured [crate::config::Config::flags] set on the entry, separated by
+    /// spaces
+    #[serde(default)]
+    pub flags: String,
+    /// Stored revision the entry is based on, see [Event::EntryConflict], `0` if never stored
+    #[serde(default)]
+    pub revision: i32,
 }
 
 impl TimeEntry {
@@ -78,6 +364,111 @@ impl TimeEntry {
             && self.duration_mins == 0
             && self.description.is_empty()
             && self.start_time == "00:00"
+            && !self.billable
+            && self.work_type.is_none()
+            && !self.carry_over
+            && !self.exact
+            && self.flags.is_empty()
+    }
+
+    /// Start and end time, from the stored `HH:MM` start and the duration
+    pub fn time_span(&self) -> Result<(NaiveTime, NaiveTime)> {
+        let start = NaiveTime::from_str(&self.start_time)
+            .wrap_err_with(|| format!("start_time of {}", self.id))?;
+        Ok((start, start + TimeDelta::minutes(self.duration_mins as i64)))
+    }
+}
+
+/// URL attached to an entry, e.g. of a pull request or meeting notes
+#[derive(
+    Queryable, Insertable, Selectable, Debug, Clone, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(table_name = entry_link)]
+pub struct EntryLink {
+    pub entry_id: String,
+    /// Order among the links of the entry, starting at `0`
+    pub position: i32,
+    pub url: String,
+}
+
+/// Notes, entries and links of a day as they were at some point, before it was changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DaySnapshot {
+    /// Local time as `YYYY-MM-DD HH:MM:SS`
+    pub taken_at: String,
+    pub notes: String,
+    /// Ordered by start time
+    pub entries: Vec<TimeEntry>,
+    pub links: Vec<EntryLink>,
+}
+
+/// Project, ticket, description and duration saved under a name, to insert into any day
+#[derive(Queryable, Insertable, AsChangeset, Selectable, Debug, Clone, PartialEq, Eq)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(table_name = entry_template)]
+pub struct EntryTemplate {
+    pub name: String,
+    pub project_key: String,
+    pub ticket_key: Option<String>,
+    pub duration_mins: i32,
+    pub description: String,
+    pub billable: bool,
+}
+
+/// How often a feature was used on a day, only recorded if enabled in the config
+#[derive(Queryable, Selectable, Debug, Clone, PartialEq, Eq)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+#[diesel(table_name = usage_metric)]
+pub struct UsageMetric {
+    pub day: String,
+    pub feature: String,
+    pub uses: i32,
+}
+
+/// Result of a report in the order of its query, with every value as text
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReportTable {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl ReportTable {
+    /// Characters of the longest value or name of each column
+    pub fn column_widths(&self) -> Vec<usize> {
+        (0..self.columns.len())
+            .map(|idx| {
+                std::iter::once(&self.columns)
+                    .chain(&self.rows)
+                    .filter_map(|row| row.get(idx))
+                    .map(|it| it.chars().count())
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+impl TimeEntry {
+    /// Entry on project `W` on 2026-10-15 with a new id, tests change the rest with struct
+    /// update syntax
+    pub fn sample(start_time: &str, duration_mins: i32) -> Self {
+        Self {
+            id: TimeEntryId::new().to_string(),
+            timesheet_day: "2026-10-15".to_string(),
+            project_key: "W".to_string(),
+            ticket_key: None,
+            duration_mins,
+            description: String::new(),
+            start_time: start_time.to_string(),
+            billable: false,
+            work_type: None,
+            carry_over: false,
+            exact: false,
+            flags: String::new(),
+            revision: 0,
+        }
     }
 }
 
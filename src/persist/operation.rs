@@ -0,0 +1,142 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use color_eyre::Result;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tracing::debug;
+
+use super::model::{Command, Event, OperationId};
+
+/// Command being handled, for the ones that take a while to report progress and notice that
+/// they were cancelled
+pub struct Operation<'a> {
+    pub id: OperationId,
+    evt_tx: &'a UnboundedSender<Event>,
+    cmd_rx: &'a mut UnboundedReceiver<Command>,
+    /// Commands that arrived meanwhile, handled once the operation is done
+    deferred: &'a mut VecDeque<Command>,
+}
+
+/// Error of an operation that was cancelled, reported as [Event::Cancelled]
+#[derive(Debug)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+impl<'a> Operation<'a> {
+    pub(super) fn new(
+        id: OperationId,
+        evt_tx: &'a UnboundedSender<Event>,
+        cmd_rx: &'a mut UnboundedReceiver<Command>,
+        deferred: &'a mut VecDeque<Command>,
+    ) -> Self {
+        Self {
+            id,
+            evt_tx,
+            cmd_rx,
+            deferred,
+        }
+    }
+
+    /// Reports that `done` of `total` steps are done, failing with [Cancelled] if a
+    /// [Command::Cancel] for this operation arrived meanwhile
+    pub fn progress(&mut self, done: u32, total: u32) -> Result<()> {
+        let id = self.id;
+        if let Err(err) = self.evt_tx.send(Event::Progress { id, done, total }) {
+            debug!("Unable to send progress: {err:?}");
+        }
+        while let Ok(cmd) = self.cmd_rx.try_recv() {
+            match cmd {
+                Command::Cancel { id } if id == self.id => return Err(Cancelled.into()),
+                // Might be meant for a background operation
+                other => self.deferred.push_back(other),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Command handled on a thread of its own, like [Command::Sync], so that other commands need
+/// not wait for it. Cancelling it sets a flag that the next progress report fails on.
+pub struct BackgroundOperation {
+    pub id: OperationId,
+    evt_tx: UnboundedSender<Event>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl BackgroundOperation {
+    pub(super) fn new(id: OperationId, evt_tx: UnboundedSender<Event>) -> Self {
+        Self {
+            id,
+            evt_tx,
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Flag that cancels the operation once set
+    pub(super) fn cancelled(&self) -> Arc<AtomicBool> {
+        self.cancelled.clone()
+    }
+
+    /// Like [Operation::progress]
+    pub fn progress(&mut self, done: u32, total: u32) -> Result<()> {
+        let id = self.id;
+        if let Err(err) = self.evt_tx.send(Event::Progress { id, done, total }) {
+            debug!("Unable to send progress: {err:?}");
+        }
+        if self.cancelled.load(Ordering::Relaxed) {
+            return Err(Cancelled.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    #[test]
+    fn test_cancel_stops_operation_and_defers_other_commands() {
+        let (cmd_tx, mut cmd_rx) = mpsc::unbounded_channel();
+        let (evt_tx, mut evt_rx) = mpsc::unbounded_channel();
+        let mut deferred = VecDeque::new();
+        let mut operation = Operation::new(7, &evt_tx, &mut cmd_rx, &mut deferred);
+
+        cmd_tx.send(Command::LoadTemplates).unwrap();
+        cmd_tx.send(Command::Cancel { id: 6 }).unwrap();
+        assert!(operation.progress(1, 3).is_ok());
+
+        cmd_tx.send(Command::Cancel { id: 7 }).unwrap();
+        let err = operation.progress(2, 3).unwrap_err();
+        assert!(err.is::<Cancelled>());
+
+        assert!(matches!(
+            evt_rx.try_recv(),
+            Ok(Event::Progress {
+                id: 7,
+                done: 1,
+                total: 3
+            })
+        ));
+        assert!(matches!(deferred.pop_front(), Some(Command::LoadTemplates)));
+        assert!(matches!(
+            deferred.pop_front(),
+            Some(Command::Cancel { id: 6 })
+        ));
+        assert!(deferred.is_empty());
+    }
+}
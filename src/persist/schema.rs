@@ -1,5 +1,48 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    archived_ticket (ticket_key) {
+        ticket_key -> Text,
+        archived_on -> Text,
+    }
+}
+
+diesel::table! {
+    entry_link (entry_id, position) {
+        entry_id -> Text,
+        position -> Integer,
+        url -> Text,
+    }
+}
+
+diesel::table! {
+    entry_tombstone (id) {
+        id -> Text,
+        timesheet_day -> Text,
+        deleted_at -> Text,
+        start_time -> Nullable<Text>,
+        duration_mins -> Nullable<Integer>,
+    }
+}
+
+diesel::table! {
+    entry_template (name) {
+        name -> Text,
+        duration_mins -> Integer,
+        description -> Text,
+        project_key -> Text,
+        ticket_key -> Nullable<Text>,
+        billable -> Bool,
+    }
+}
+
+diesel::table! {
+    preference (key) {
+        key -> Text,
+        value -> Text,
+    }
+}
+
 diesel::table! {
     time_entry (id) {
         id -> Text,
@@ -9,6 +52,13 @@ diesel::table! {
         description -> Text,
         project_key -> Text,
         ticket_key -> Nullable<Text>,
+        billable -> Bool,
+        work_type -> Nullable<Text>,
+        carry_over -> Bool,
+        revision -> Integer,
+        changed_at -> Nullable<Text>,
+        exact -> Bool,
+        flags -> Text,
     }
 }
 
@@ -16,9 +66,38 @@ diesel::table! {
     timesheet (day) {
         day -> Text,
         status -> Text,
+        notes -> Text,
+        utc_offset_mins -> Nullable<Integer>,
+        changed_at -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    timesheet_snapshot (day, taken_at) {
+        day -> Text,
+        taken_at -> Text,
+        content -> Text,
+    }
+}
+
+diesel::table! {
+    usage_metric (day, feature) {
+        day -> Text,
+        feature -> Text,
+        uses -> Integer,
     }
 }
 
 diesel::joinable!(time_entry -> timesheet (timesheet_day));
 
-diesel::allow_tables_to_appear_in_same_query!(time_entry, timesheet,);
+diesel::allow_tables_to_appear_in_same_query!(
+    archived_ticket,
+    entry_link,
+    entry_template,
+    entry_tombstone,
+    preference,
+    time_entry,
+    timesheet,
+    timesheet_snapshot,
+    usage_metric,
+);
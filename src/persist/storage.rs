@@ -0,0 +1,317 @@
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{
+    Result,
+    eyre::{Context, OptionExt, bail},
+};
+use diesel::{RunQueryDsl, SqliteConnection, prelude::*};
+use time::{Date, OffsetDateTime, format_description::FormatItem, macros::format_description};
+use tracing::warn;
+
+use super::{
+    Command, EntryLink, Event, TimeEntry, Timesheet, archive, handle, lock_shared,
+    operation::{BackgroundOperation, Operation},
+    prepare_connection,
+    schema::{entry_link, time_entry, timesheet},
+    sync, tombstone,
+};
+use crate::config::{Config, SqliteConfig, SyncConfig};
+
+const ISO_DAY: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
+/// Backend that carries out the [Command]s on the persist thread. Components only send
+/// commands and receive [Event]s, so that another backend, like a server shared by a team, can
+/// be plugged in with [super::start_with] without touching them. [SqliteStorage] is the
+/// default, others go behind a cargo feature each.
+// Run on the local set of the persist thread, so the futures need not be Send
+#[allow(async_fn_in_trait)]
+pub trait Storage {
+    /// Carries out the command, the ones that take a while report their progress and notice
+    /// that they were cancelled through the operation
+    async fn handle(&mut self, cmd: Command, operation: &mut Operation<'_>) -> Result<Event>;
+
+    /// Day the command is about to change, looked up before it runs since deleted entries
+    /// can't be found afterwards
+    fn day_changed_by(&mut self, cmd: &Command) -> Option<Date>;
+
+    /// The day with its entries and links, to export it once it changed, `None` if it has no
+    /// timesheet
+    fn load_day(&mut self, day: Date) -> Result<Option<StoredDay>>;
+
+    /// Work of [Command::Sync], run on a thread of its own so that a slow remote doesn't hold
+    /// up the other commands
+    fn sync_job(&mut self) -> Result<SyncJob> {
+        bail!("Syncing is not supported by this storage")
+    }
+}
+
+/// See [Storage::sync_job]
+pub type SyncJob = Box<dyn FnOnce(&mut BackgroundOperation) -> Result<Event> + Send>;
+
+/// Timesheet of a day with its entries, ordered by start time, and their links
+#[derive(Debug, Clone)]
+pub struct StoredDay {
+    pub timesheet: Timesheet,
+    pub entries: Vec<TimeEntry>,
+    pub links: Vec<EntryLink>,
+}
+
+/// The SQLite database in the data dir, with the archives attached as needed
+pub struct SqliteStorage {
+    conn: SqliteConnection,
+    /// Reads through the archives, see [archive::years_read_by], opened once there is one
+    archive_reader: Option<SqliteConnection>,
+    /// Keeps backups from being imported meanwhile
+    _in_use: File,
+    written: handle::Written,
+    data_dir: PathBuf,
+    sqlite: SqliteConfig,
+    sync: Option<SyncConfig>,
+}
+
+impl SqliteStorage {
+    /// Opens the database, running any pending migrations, registers the configured
+    /// absences and holidays and forgets entries deleted long ago
+    pub fn open(data_dir: &Path, config: &Config) -> Result<Self> {
+        let in_use = lock_shared(data_dir)?;
+        let mut conn = prepare_connection(data_dir, &config.sqlite)?;
+        handle::register_absences(&mut conn, config)?;
+        handle::register_holidays(&mut conn, config)?;
+        let today = OffsetDateTime::now_local()?.date();
+        if let Err(err) = tombstone::prune(&mut conn, today, config.sync.as_ref()) {
+            warn!("Failed to drop old tombstones: {err:?}");
+        }
+        Ok(Self {
+            conn,
+            archive_reader: None,
+            _in_use: in_use,
+            written: handle::Written::default(),
+            data_dir: data_dir.to_path_buf(),
+            sqlite: config.sqlite.clone(),
+            sync: config.sync.clone(),
+        })
+    }
+}
+
+impl SqliteStorage {
+    fn open_archive_reader(&mut self, years: &[i32]) -> Result<()> {
+        if self.archive_reader.is_none() {
+            self.archive_reader = Some(prepare_connection(&self.data_dir, &self.sqlite)?);
+        }
+        let reader = self.archive_reader.as_mut().expect("reader to be opened");
+        archive::read_years(reader, years)
+    }
+}
+
+impl Storage for SqliteStorage {
+    async fn handle(&mut self, cmd: Command, operation: &mut Operation<'_>) -> Result<Event> {
+        let read_years = archive::years_read_by(&mut self.conn, &cmd)?;
+        let conn = match &read_years {
+            Some(years) => {
+                self.open_archive_reader(years)?;
+                self.archive_reader.as_mut().expect("reader to be opened")
+            }
+            None => &mut self.conn,
+        };
+        let read_through = read_years.is_some();
+        handle::handle(conn, &mut self.written, cmd, operation, read_through).await
+    }
+
+    fn day_changed_by(&mut self, cmd: &Command) -> Option<Date> {
+        day_changed_by(&mut self.conn, cmd)
+    }
+
+    fn load_day(&mut self, day: Date) -> Result<Option<StoredDay>> {
+        let conn = &mut self.conn;
+        let iso_day = day.format(ISO_DAY)?;
+        let timesheet = timesheet::table
+            .filter(timesheet::day.eq(&iso_day))
+            .select(Timesheet::as_select())
+            .get_result(conn)
+            .optional()
+            .wrap_err_with(|| format!("load timesheet {iso_day}"))?;
+        let Some(timesheet) = timesheet else {
+            return Ok(None);
+        };
+        let entries = time_entry::table
+            .filter(time_entry::timesheet_day.eq(&iso_day))
+            .select(TimeEntry::as_select())
+            .order_by(time_entry::start_time)
+            .load(conn)
+            .wrap_err_with(|| format!("load entries of {iso_day}"))?;
+        let links = entry_link::table
+            .filter(entry_link::entry_id.eq_any(entries.iter().map(|it| &it.id)))
+            .select(EntryLink::as_select())
+            .order_by((entry_link::entry_id, entry_link::position))
+            .load(conn)
+            .wrap_err_with(|| format!("load links of {iso_day}"))?;
+        Ok(Some(StoredDay {
+            timesheet,
+            entries,
+            links,
+        }))
+    }
+
+    /// Syncs through a connection of its own, the one of the persist thread stays free
+    fn sync_job(&mut self) -> Result<SyncJob> {
+        let config = self
+            .sync
+            .clone()
+            .ok_or_eyre("No sync configured, see the sync section of the config")?;
+        let data_dir = self.data_dir.clone();
+        let sqlite = self.sqlite.clone();
+        Ok(Box::new(move |operation| {
+            let mut conn = prepare_connection(&data_dir, &sqlite)?;
+            sync::run(&mut conn, &config, operation)
+        }))
+    }
+}
+
+/// See [Storage::day_changed_by]
+pub(super) fn day_changed_by(conn: &mut SqliteConnection, cmd: &Command) -> Option<Date> {
+    let day = match cmd {
+        Command::StoreEntry { entry, .. } => Some(entry.timesheet_day.clone()),
+        Command::StoreNotes { day, .. }
+        | Command::StoreUtcOffset { day, .. }
+        | Command::ImportEntries { day, .. }
+        | Command::RestoreSnapshot { day, .. } => return Some(*day),
+        Command::DeleteEntry(id) | Command::StoreLinks { entry_id: id, .. } => {
+            day_of_entry(conn, &id.to_string())
+        }
+        Command::RepairEntry { raw_id, .. } => day_of_entry(conn, raw_id),
+        _ => None,
+    };
+    day.and_then(|it| Date::parse(&it, ISO_DAY).ok())
+}
+
+fn day_of_entry(conn: &mut SqliteConnection, id: &str) -> Option<String> {
+    time_entry::table
+        .filter(time_entry::id.eq(id))
+        .select(time_entry::timesheet_day)
+        .get_result(conn)
+        .optional()
+        .inspect_err(|err| warn!("Looking up the day of entry {id} failed: {err:?}"))
+        .ok()
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::persist::{TimeEntryId, start_with};
+
+    /// Keeps the entries in memory, for checking that the persist thread only goes through
+    /// the trait
+    #[derive(Default)]
+    struct MemoryStorage {
+        entries: BTreeMap<String, TimeEntry>,
+        /// Syncing waits until this is dropped
+        sync_gate: Option<std::sync::mpsc::Receiver<()>>,
+    }
+
+    impl Storage for MemoryStorage {
+        async fn handle(&mut self, cmd: Command, operation: &mut Operation<'_>) -> Result<Event> {
+            match cmd {
+                Command::StoreEntry { entry, version } => {
+                    operation.progress(1, 1)?;
+                    let id = entry.id.parse()?;
+                    self.entries.insert(entry.id.clone(), entry);
+                    Ok(Event::EntryStored {
+                        id,
+                        version,
+                        revision: 1,
+                    })
+                }
+                Command::DeleteEntry(id) => {
+                    self.entries.remove(&id.to_string());
+                    Ok(Event::Deleted)
+                }
+                other => color_eyre::eyre::bail!("not supported: {}", other.describe()),
+            }
+        }
+
+        fn day_changed_by(&mut self, _cmd: &Command) -> Option<Date> {
+            None
+        }
+
+        fn load_day(&mut self, _day: Date) -> Result<Option<StoredDay>> {
+            Ok(None)
+        }
+
+        fn sync_job(&mut self) -> Result<SyncJob> {
+            let gate = self.sync_gate.take().ok_or_eyre("synced already")?;
+            Ok(Box::new(move |_| {
+                let _ = gate.recv();
+                Ok(Event::Synced {
+                    pulled: vec![],
+                    pushed: 2,
+                })
+            }))
+        }
+    }
+
+    #[test]
+    fn test_sync_does_not_hold_up_other_commands() {
+        let (gate_tx, gate_rx) = std::sync::mpsc::channel();
+        let storage = MemoryStorage {
+            sync_gate: Some(gate_rx),
+            ..MemoryStorage::default()
+        };
+        let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
+        let (evt_tx, mut evt_rx) = mpsc::unbounded_channel();
+        let handle = start_with(storage, cmd_rx, evt_tx).unwrap();
+
+        cmd_tx.send(Command::Sync).unwrap();
+        cmd_tx
+            .send(Command::DeleteEntry(TimeEntryId::new()))
+            .unwrap();
+        assert!(matches!(evt_rx.blocking_recv(), Some(Event::Deleted)));
+        drop(gate_tx);
+        assert!(matches!(
+            evt_rx.blocking_recv(),
+            Some(Event::Synced { pushed: 2, .. })
+        ));
+
+        drop(evt_rx);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_persist_thread_runs_commands_on_other_storage() {
+        let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
+        let (evt_tx, mut evt_rx) = mpsc::unbounded_channel();
+        let handle = start_with(MemoryStorage::default(), cmd_rx, evt_tx).unwrap();
+        let id = TimeEntryId::new();
+        let entry = TimeEntry {
+            id: id.to_string(),
+            description: "planning".to_string(),
+            ..TimeEntry::sample("09:00", 30)
+        };
+
+        cmd_tx
+            .send(Command::StoreEntry { entry, version: 3 })
+            .unwrap();
+        assert!(matches!(
+            evt_rx.blocking_recv(),
+            Some(Event::Progress { done: 1, .. })
+        ));
+        assert!(matches!(
+            evt_rx.blocking_recv(),
+            Some(Event::EntryStored { version: 3, .. })
+        ));
+        cmd_tx.send(Command::LoadTemplates).unwrap();
+        assert!(matches!(evt_rx.blocking_recv(), Some(Event::Failure(_))));
+        cmd_tx.send(Command::DeleteEntry(id)).unwrap();
+        assert!(matches!(evt_rx.blocking_recv(), Some(Event::Deleted)));
+
+        drop(evt_rx);
+        handle.join().unwrap();
+    }
+}
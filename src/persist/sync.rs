@@ -0,0 +1,412 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use color_eyre::{Result, eyre::Context};
+use diesel::{RunQueryDsl, SqliteConnection, prelude::*, sql_types::Text};
+use time::{
+    Date, OffsetDateTime, ext::NumericalDuration, format_description::FormatItem,
+    macros::format_description,
+};
+use tracing::info;
+
+use super::{
+    EntryLink, Event, TimeEntry, Timesheet, history,
+    operation::BackgroundOperation,
+    schema::{entry_link, entry_tombstone, time_entry, timesheet},
+    tombstone,
+};
+use crate::{
+    config::SyncConfig,
+    sync::{self, INDEX_FILE, Remote, SyncDay, SyncEntry, SyncIndex, Tombstone},
+};
+
+const ISO_DAY: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
+#[derive(QueryableByName)]
+struct DayStamp {
+    #[diesel(sql_type = Text)]
+    day: String,
+    #[diesel(sql_type = Text)]
+    stamp: String,
+}
+
+/// Pulls the days of the window that changed elsewhere and pushes those that changed here,
+/// see [crate::sync]. Days whose latest change is the one in the index are skipped.
+pub(super) fn run(
+    conn: &mut SqliteConnection,
+    config: &SyncConfig,
+    operation: &mut BackgroundOperation,
+) -> Result<Event> {
+    let mut remote = sync::remote_for(config)?;
+    let today = OffsetDateTime::now_local()?.date();
+    let first_day = (today - i64::from(config.days).days()).format(ISO_DAY)?;
+    let synced = sync_window(conn, remote.as_mut(), config, &first_day, operation)?;
+    tombstone::prune(conn, today, Some(config))?;
+    Ok(synced)
+}
+
+fn sync_window(
+    conn: &mut SqliteConnection,
+    remote: &mut dyn Remote,
+    config: &SyncConfig,
+    first_day: &str,
+    operation: &mut BackgroundOperation,
+) -> Result<Event> {
+    remote.prepare()?;
+    let mut index: SyncIndex = match remote.get(INDEX_FILE)? {
+        Some(content) => serde_json::from_str(&content).wrap_err("read sync index")?,
+        None => SyncIndex::default(),
+    };
+    let pushed_index = index.clone();
+    let local = local_stamps(conn, first_day)?;
+    let days = local
+        .keys()
+        .chain(index.days.keys())
+        .filter(|day| day.as_str() >= first_day)
+        .filter(|day| local.get(*day) != index.days.get(*day))
+        .cloned()
+        .collect::<BTreeSet<_>>();
+
+    let total = days.len() as u32;
+    let mut pulled = vec![];
+    let mut pushed = 0;
+    for (done, iso_day) in days.iter().enumerate() {
+        operation.progress(done as u32, total)?;
+        let local_day = load_day(conn, iso_day)?;
+        let file = sync::day_file(iso_day);
+        let remote_day = match remote.get(&file)? {
+            Some(content) => serde_json::from_str(&content)
+                .wrap_err_with(|| format!("read {file} of the sync target"))?,
+            None => SyncDay::empty(iso_day),
+        };
+        let merged = sync::merge(&local_day, &remote_day, config.strategy);
+        if merged != local_day {
+            apply_day(conn, &local_day, &merged)?;
+            pulled.push(Date::parse(iso_day, ISO_DAY)?);
+        }
+        if merged != remote_day {
+            remote.put(&file, &serde_json::to_string_pretty(&merged)?)?;
+            pushed += 1;
+        }
+        if let Some(stamp) = merged.stamp() {
+            index.days.insert(iso_day.clone(), stamp.to_string());
+        }
+    }
+    operation.progress(total, total)?;
+
+    // Older days are not synced anymore
+    index.days.retain(|day, _| day.as_str() >= first_day);
+    if index.days != pushed_index.days {
+        remote.put(INDEX_FILE, &serde_json::to_string_pretty(&index)?)?;
+    }
+    remote.finish()?;
+    info!(
+        "Synced {} days, pulled {} and pushed {pushed}",
+        days.len(),
+        pulled.len()
+    );
+    Ok(Event::Synced { pulled, pushed })
+}
+
+/// Latest change of each day since the first one, of days with any stamped change
+fn local_stamps(conn: &mut SqliteConnection, first_day: &str) -> Result<BTreeMap<String, String>> {
+    let stamps: Vec<DayStamp> = diesel::sql_query(
+        "SELECT day, MAX(stamp) AS stamp FROM ( \
+           SELECT timesheet_day AS day, changed_at AS stamp FROM time_entry \
+           WHERE timesheet_day >= ?1 \
+           UNION ALL SELECT day, changed_at FROM timesheet WHERE day >= ?1 \
+           UNION ALL SELECT timesheet_day, deleted_at FROM entry_tombstone \
+           WHERE timesheet_day >= ?1 \
+         ) WHERE stamp IS NOT NULL GROUP BY day",
+    )
+    .bind::<Text, _>(first_day)
+    .load(conn)
+    .wrap_err("load the latest changes of each day")?;
+    Ok(stamps.into_iter().map(|it| (it.day, it.stamp)).collect())
+}
+
+/// The day as it is pushed, empty if it has no timesheet
+fn load_day(conn: &mut SqliteConnection, iso_day: &str) -> Result<SyncDay> {
+    let sheet: Option<(String, Option<i32>, Option<String>)> = timesheet::table
+        .filter(timesheet::day.eq(iso_day))
+        .select((
+            timesheet::notes,
+            timesheet::utc_offset_mins,
+            timesheet::changed_at,
+        ))
+        .get_result(conn)
+        .optional()
+        .wrap_err_with(|| format!("load timesheet {iso_day}"))?;
+    let entries: Vec<(TimeEntry, Option<String>)> = time_entry::table
+        .filter(time_entry::timesheet_day.eq(iso_day))
+        .select((TimeEntry::as_select(), time_entry::changed_at))
+        .load(conn)
+        .wrap_err_with(|| format!("load entries of {iso_day}"))?;
+    let links = entry_link::table
+        .filter(entry_link::entry_id.eq_any(entries.iter().map(|(it, _)| &it.id)))
+        .select(EntryLink::as_select())
+        .order_by((entry_link::entry_id, entry_link::position))
+        .load(conn)
+        .wrap_err_with(|| format!("load links of {iso_day}"))?;
+    let tombstones = entry_tombstone::table
+        .filter(entry_tombstone::timesheet_day.eq(iso_day))
+        .select((entry_tombstone::id, entry_tombstone::deleted_at))
+        .load::<(String, String)>(conn)
+        .wrap_err_with(|| format!("load deleted entries of {iso_day}"))?;
+
+    let (notes, utc_offset_mins, changed_at) = sheet.unwrap_or_default();
+    let mut day = SyncDay {
+        notes,
+        utc_offset_mins,
+        changed_at,
+        entries: entries
+            .into_iter()
+            .map(|(entry, changed_at)| SyncEntry {
+                links: links
+                    .iter()
+                    .filter(|it| it.entry_id == entry.id)
+                    .map(|it| it.url.clone())
+                    .collect(),
+                entry: TimeEntry {
+                    revision: 0,
+                    ..entry
+                },
+                changed_at,
+            })
+            .collect(),
+        tombstones: tombstones
+            .into_iter()
+            .map(|(id, deleted_at)| Tombstone { id, deleted_at })
+            .collect(),
+        ..SyncDay::empty(iso_day)
+    };
+    // Same order as merged days, so that unchanged ones compare equal
+    day.normalize();
+    Ok(day)
+}
+
+/// Writes what differs between the local and the merged day, with the stamps of the merged
+/// one so that the day is not pushed again as changed. A snapshot is taken first, to undo the
+/// sync from the history.
+fn apply_day(conn: &mut SqliteConnection, local: &SyncDay, merged: &SyncDay) -> Result<()> {
+    let iso_day = &merged.day;
+    let day = Date::parse(iso_day, ISO_DAY)?;
+    history::take_hourly(conn, day, history::now()?)?;
+    let local_entries = local
+        .entries
+        .iter()
+        .map(|it| (it.entry.id.as_str(), it))
+        .collect::<BTreeMap<_, _>>();
+    let alive = merged
+        .entries
+        .iter()
+        .map(|it| it.entry.id.as_str())
+        .collect::<BTreeSet<_>>();
+
+    conn.immediate_transaction(|conn| {
+        let sheet = Timesheet {
+            day: iso_day.clone(),
+            status: "OPEN".to_string(),
+            notes: merged.notes.clone(),
+            utc_offset_mins: merged.utc_offset_mins,
+        };
+        let sheet_changed = (&local.notes, local.utc_offset_mins, &local.changed_at)
+            != (&merged.notes, merged.utc_offset_mins, &merged.changed_at);
+        if sheet_changed {
+            diesel::insert_into(timesheet::table)
+                .values((&sheet, timesheet::changed_at.eq(&merged.changed_at)))
+                .on_conflict(timesheet::day)
+                .do_update()
+                .set((
+                    timesheet::notes.eq(&merged.notes),
+                    timesheet::utc_offset_mins.eq(merged.utc_offset_mins),
+                    timesheet::changed_at.eq(&merged.changed_at),
+                ))
+                .execute(conn)?;
+        } else {
+            diesel::insert_into(timesheet::table)
+                .values(&sheet)
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+        }
+
+        for synced in &merged.entries {
+            if local_entries.get(synced.entry.id.as_str()) == Some(&synced) {
+                continue;
+            }
+            let id = &synced.entry.id;
+            // Counts as a change by another writer for a TUI that still has the entry open
+            let revision: Option<i32> = time_entry::table
+                .find(id)
+                .select(time_entry::revision)
+                .get_result(conn)
+                .optional()?;
+            let entry = TimeEntry {
+                revision: revision.unwrap_or(0) + 1,
+                ..synced.entry.clone()
+            };
+            // Changing the links stamps the entry, and so does an update that keeps the stamp
+            diesel::delete(entry_link::table.filter(entry_link::entry_id.eq(id))).execute(conn)?;
+            let changed_at = time_entry::changed_at.eq(&synced.changed_at);
+            diesel::insert_into(time_entry::table)
+                .values((&entry, changed_at))
+                .on_conflict(time_entry::id)
+                .do_update()
+                .set((&entry, changed_at))
+                .execute(conn)?;
+            let links = synced
+                .links
+                .iter()
+                .enumerate()
+                .map(|(position, url)| EntryLink {
+                    entry_id: id.clone(),
+                    position: position as i32,
+                    url: url.clone(),
+                })
+                .collect::<Vec<_>>();
+            if !links.is_empty() {
+                diesel::insert_into(entry_link::table)
+                    .values(&links)
+                    .execute(conn)?;
+                diesel::update(time_entry::table.find(id))
+                    .set(changed_at)
+                    .execute(conn)?;
+            }
+        }
+
+        let gone = local
+            .entries
+            .iter()
+            .map(|it| it.entry.id.as_str())
+            .filter(|id| !alive.contains(id))
+            .collect::<Vec<_>>();
+        diesel::delete(entry_link::table.filter(entry_link::entry_id.eq_any(&gone)))
+            .execute(conn)?;
+        diesel::delete(time_entry::table.filter(time_entry::id.eq_any(&gone))).execute(conn)?;
+        // Keeps the times recorded when deleting here
+        for it in merged
+            .tombstones
+            .iter()
+            .filter(|it| !local.tombstones.contains(it))
+        {
+            diesel::insert_into(entry_tombstone::table)
+                .values((
+                    entry_tombstone::id.eq(&it.id),
+                    entry_tombstone::timesheet_day.eq(iso_day),
+                    entry_tombstone::deleted_at.eq(&it.deleted_at),
+                ))
+                .on_conflict(entry_tombstone::id)
+                .do_update()
+                .set(entry_tombstone::deleted_at.eq(&it.deleted_at))
+                .execute(conn)?;
+        }
+        diesel::result::QueryResult::Ok(())
+    })
+    .wrap_err_with(|| format!("apply the synced {iso_day}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::Path};
+
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::{
+        config::{SqliteConfig, SyncStrategy},
+        persist::prepare_connection,
+        sync::DirRemote,
+    };
+
+    fn open(dir: &Path) -> SqliteConnection {
+        let _ = fs::remove_dir_all(dir);
+        let mut conn = prepare_connection(dir, &SqliteConfig::default()).unwrap();
+        diesel::insert_into(timesheet::table)
+            .values((
+                timesheet::day.eq("2026-10-15"),
+                timesheet::status.eq("OPEN"),
+                timesheet::notes.eq(""),
+            ))
+            .execute(&mut conn)
+            .unwrap();
+        conn
+    }
+
+    fn store(conn: &mut SqliteConnection, id: &str, start_time: &str, description: &str) {
+        let entry = TimeEntry {
+            id: id.to_string(),
+            description: description.to_string(),
+            revision: 1,
+            ..TimeEntry::sample(start_time, 30)
+        };
+        diesel::replace_into(time_entry::table)
+            .values(&entry)
+            .execute(conn)
+            .unwrap();
+    }
+
+    fn descriptions(conn: &mut SqliteConnection) -> Vec<String> {
+        time_entry::table
+            .select(time_entry::description)
+            .order_by(time_entry::start_time)
+            .load(conn)
+            .unwrap()
+    }
+
+    fn sync(conn: &mut SqliteConnection, remote: &Path) -> (Vec<Date>, usize) {
+        let (evt_tx, _evt_rx) = mpsc::unbounded_channel();
+        let mut operation = BackgroundOperation::new(1, evt_tx);
+        let mut remote = DirRemote {
+            path: remote.to_path_buf(),
+        };
+        let config = SyncConfig {
+            strategy: SyncStrategy::Merge,
+            ..SyncConfig::default()
+        };
+        match sync_window(conn, &mut remote, &config, "2026-10-01", &mut operation).unwrap() {
+            Event::Synced { pulled, pushed } => (pulled, pushed),
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_sync_pulls_changes_and_deletions_of_other_machine() {
+        let tmp = std::env::temp_dir().join(format!("sheet-shark-sync-{}", std::process::id()));
+        let remote = tmp.join("remote");
+        let _ = fs::remove_dir_all(&remote);
+        let mut laptop = open(&tmp.join("laptop"));
+        let mut desktop = open(&tmp.join("desktop"));
+
+        store(&mut laptop, "a", "09:00", "standup");
+        store(&mut laptop, "b", "09:30", "planning");
+        assert_eq!(sync(&mut laptop, &remote), (vec![], 1));
+        assert_eq!(
+            sync(&mut desktop, &remote),
+            (vec![time::macros::date!(2026 - 10 - 15)], 0)
+        );
+        assert_eq!(descriptions(&mut desktop), ["standup", "planning"]);
+        // Nothing changed since
+        assert_eq!(sync(&mut desktop, &remote), (vec![], 0));
+        assert_eq!(sync(&mut laptop, &remote), (vec![], 0));
+
+        // Stamps have milliseconds, each machine's changes must come after the other's
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        store(&mut desktop, "a", "09:00", "daily");
+        diesel::delete(time_entry::table.find("b"))
+            .execute(&mut desktop)
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        store(&mut laptop, "c", "11:00", "review");
+        assert_eq!(sync(&mut desktop, &remote).1, 1);
+        assert_eq!(sync(&mut laptop, &remote).1, 1);
+        assert_eq!(descriptions(&mut laptop), ["daily", "review"]);
+        let revision: i32 = time_entry::table
+            .find("a")
+            .select(time_entry::revision)
+            .get_result(&mut laptop)
+            .unwrap();
+        assert_eq!(revision, 2);
+        sync(&mut desktop, &remote);
+        assert_eq!(descriptions(&mut desktop), ["daily", "review"]);
+        assert_eq!(sync(&mut laptop, &remote), (vec![], 0));
+    }
+}
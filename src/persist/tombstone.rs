@@ -0,0 +1,117 @@
+use color_eyre::{Result, eyre::Context};
+use diesel::{RunQueryDsl, SqliteConnection, prelude::*};
+use time::{
+    Date, ext::NumericalDuration, format_description::FormatItem, macros::format_description,
+};
+use tracing::info;
+
+use super::schema::entry_tombstone;
+use crate::config::SyncConfig;
+
+const ISO_DAY: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
+/// Deleted entries of days this long ago are forgotten, unless more days are synced
+const KEEP_DAYS: u32 = 365;
+
+/// Start time and duration of the entries that were deleted from the day, as recorded by the
+/// `time_entry_deleted` trigger. Deletions taken over from other machines have no times.
+pub(super) fn deleted_times(
+    conn: &mut SqliteConnection,
+    iso_day: &str,
+) -> QueryResult<Vec<(String, i32)>> {
+    let times: Vec<(Option<String>, Option<i32>)> = entry_tombstone::table
+        .filter(entry_tombstone::timesheet_day.eq(iso_day))
+        .select((entry_tombstone::start_time, entry_tombstone::duration_mins))
+        .load(conn)?;
+    Ok(times
+        .into_iter()
+        .filter_map(|(start_time, duration_mins)| Some((start_time?, duration_mins?)))
+        .collect())
+}
+
+/// Drops the deleted entries of days before the kept ones, which are [KEEP_DAYS] or all the
+/// synced [SyncConfig::days] back from today
+pub(super) fn prune(
+    conn: &mut SqliteConnection,
+    today: Date,
+    sync: Option<&SyncConfig>,
+) -> Result<usize> {
+    let keep_days = sync.map_or(KEEP_DAYS, |it| it.days.max(KEEP_DAYS));
+    let first_kept = (today - i64::from(keep_days).days()).format(ISO_DAY)?;
+    let pruned = diesel::delete(
+        entry_tombstone::table.filter(entry_tombstone::timesheet_day.lt(&first_kept)),
+    )
+    .execute(conn)
+    .wrap_err("drop old tombstones")?;
+    if pruned > 0 {
+        info!("Forgot {pruned} entries deleted from days before {first_kept}");
+    }
+    Ok(pruned)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+    use crate::{
+        config::SqliteConfig,
+        persist::{
+            TimeEntry, prepare_connection,
+            schema::{time_entry, timesheet},
+        },
+    };
+
+    fn insert(conn: &mut SqliteConnection, id: &str, day: &str, start_time: &str) {
+        diesel::insert_or_ignore_into(timesheet::table)
+            .values((
+                timesheet::day.eq(day),
+                timesheet::status.eq("OPEN"),
+                timesheet::notes.eq(""),
+            ))
+            .execute(conn)
+            .unwrap();
+        let entry = TimeEntry {
+            id: id.to_string(),
+            timesheet_day: day.to_string(),
+            description: "standup".to_string(),
+            revision: 1,
+            ..TimeEntry::sample(start_time, 30)
+        };
+        diesel::insert_into(time_entry::table)
+            .values(&entry)
+            .execute(conn)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_deletions_are_recorded_until_pruned() {
+        let dir =
+            std::env::temp_dir().join(format!("sheet-shark-tombstone-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut conn = prepare_connection(&dir, &SqliteConfig::default()).unwrap();
+        insert(&mut conn, "a", "2026-10-15", "09:00");
+        insert(&mut conn, "b", "2026-10-15", "09:30");
+        insert(&mut conn, "c", "2024-10-15", "09:00");
+        diesel::delete(time_entry::table.filter(time_entry::id.ne("b")))
+            .execute(&mut conn)
+            .unwrap();
+
+        assert_eq!(
+            deleted_times(&mut conn, "2026-10-15").unwrap(),
+            [("09:00".to_string(), 30)]
+        );
+        // Added again, so no longer deleted
+        insert(&mut conn, "a", "2026-10-15", "09:00");
+        assert!(deleted_times(&mut conn, "2026-10-15").unwrap().is_empty());
+
+        let sync = SyncConfig {
+            days: 800,
+            ..SyncConfig::default()
+        };
+        let today = date!(2026 - 10 - 15);
+        assert_eq!(prune(&mut conn, today, Some(&sync)).unwrap(), 0);
+        assert_eq!(prune(&mut conn, today, None).unwrap(), 1);
+        assert!(deleted_times(&mut conn, "2024-10-15").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,94 @@
+use std::{path::PathBuf, sync::OnceLock, sync::RwLock};
+
+use color_eyre::Result;
+use time::Date;
+use tracing::warn;
+
+use crate::{
+    config::Config,
+    export::ExportMeta,
+    shared::holidays::{self, Holidays},
+};
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+static PROFILE: RwLock<Option<String>> = RwLock::new(None);
+/// Loaded on first use and again after [refresh_holidays] fetched the calendar
+static HOLIDAYS: RwLock<Option<Holidays>> = RwLock::new(None);
+
+/// Loads the config for the whole process, call once at startup before [get]
+pub fn init(profile: Option<String>) -> Result<()> {
+    let config = Config::new()?;
+    config.data_dir_of(profile.as_deref())?;
+    CONFIG.set(config).expect("no config set yet");
+    *PROFILE.write().expect("profile lock not poisoned") = profile;
+    Ok(())
+}
+
+/// Config of this process, while the library always receives it explicitly
+pub fn get() -> &'static Config {
+    CONFIG.get().expect("config loaded")
+}
+
+/// Active profile, `None` for the default one
+pub fn profile() -> Option<String> {
+    PROFILE.read().expect("profile lock not poisoned").clone()
+}
+
+/// Only changes where data is read from and written to afterwards, see `App` for reconnecting
+pub fn switch_profile(profile: Option<String>) -> Result<()> {
+    get().data_dir_of(profile.as_deref())?;
+    *PROFILE.write().expect("profile lock not poisoned") = profile;
+    Ok(())
+}
+
+/// Data dir of the active profile
+pub fn data_dir() -> PathBuf {
+    get()
+        .data_dir_of(profile().as_deref())
+        .expect("active profile to be configured")
+}
+
+/// Export dir of the active profile
+pub fn export_dir() -> PathBuf {
+    get()
+        .export_dir_of(profile().as_deref())
+        .expect("active profile to be configured")
+}
+
+/// Written into exports of the active profile
+pub fn export_meta() -> Result<ExportMeta> {
+    ExportMeta::new(profile(), get())
+}
+
+/// Name of the public holiday on the day, if it is one
+pub fn holiday_of(day: Date) -> Option<String> {
+    if let Some(holidays) = HOLIDAYS
+        .read()
+        .expect("holidays lock not poisoned")
+        .as_ref()
+    {
+        return holidays.name_of(day);
+    }
+    let config = get();
+    let holidays =
+        Holidays::load(&config.holidays, &config.config.data_dir).unwrap_or_else(|err| {
+            warn!("Loading holidays failed: {err:?}");
+            Holidays::default()
+        });
+    let name = holidays.name_of(day);
+    *HOLIDAYS.write().expect("holidays lock not poisoned") = Some(holidays);
+    name
+}
+
+/// Fetches the configured holiday calendar in the background, the cached one is used meanwhile
+pub fn refresh_holidays() {
+    let Some(url) = get().holidays.ics_url.clone() else {
+        return;
+    };
+    std::thread::spawn(
+        move || match holidays::fetch(&url, &get().config.data_dir) {
+            Ok(()) => *HOLIDAYS.write().expect("holidays lock not poisoned") = None,
+            Err(err) => warn!("Refreshing holidays failed: {err:?}"),
+        },
+    );
+}
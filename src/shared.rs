@@ -1,10 +1,112 @@
+use std::ops::RangeInclusive;
+
+use chrono::{NaiveTime, Timelike};
+use time::{Date, Duration};
 use tracing::warn;
 
+pub mod alias;
+pub mod branch_ticket;
+pub mod dedupe;
 pub mod defrag;
+pub mod focus;
+pub mod fuzzy;
+pub mod holidays;
+pub mod idle;
+pub mod insights;
+pub mod log_lines;
+pub mod lru;
+pub mod parse;
+pub mod query;
+pub mod report;
+pub mod scaffold;
+pub mod split;
 pub mod summary;
+pub mod ticket_project;
+pub mod ticket_rank;
+pub mod time_expr;
+pub mod tz;
+pub mod validation;
 
 pub const BREAK_PROJECT_KEY: &str = "x";
 
+/// First to last day of the month containing the day
+pub fn month_of(day: Date) -> RangeInclusive<Date> {
+    let first = day.replace_day(1).expect("first of month exists");
+    let last = day
+        .replace_day(day.month().length(day.year()))
+        .expect("last of month exists");
+    first..=last
+}
+
+/// Monday to Sunday of the week containing the day
+pub fn week_of(day: Date) -> RangeInclusive<Date> {
+    let monday = day - Duration::days(day.weekday().number_days_from_monday() as i64);
+    monday..=monday + Duration::days(6)
+}
+
+/// Consecutive parts of the range with at most `max_days` days each, e.g. to load a year in
+/// steps that progress can be reported for
+pub fn chunks_of(range: RangeInclusive<Date>, max_days: i64) -> Vec<RangeInclusive<Date>> {
+    let mut chunks = vec![];
+    let mut start = *range.start();
+    while start <= *range.end() {
+        let end = (start + Duration::days(max_days - 1)).min(*range.end());
+        chunks.push(start..=end);
+        start = end + Duration::days(1);
+    }
+    chunks
+}
+
+/// Start of an entry added after one that ends at `previous_end`. While recording the current
+/// day, that is `now` rounded to the nearest `round_mins` if later, so that the entry starts
+/// when the work did. `round_mins` of `0` always starts at the previous end.
+pub fn next_start(previous_end: NaiveTime, now: Option<NaiveTime>, round_mins: u32) -> NaiveTime {
+    let rounded_now = now.filter(|_| round_mins > 0).and_then(|now| {
+        let mins = now.num_seconds_from_midnight().div_ceil(60);
+        let rounded = (mins + round_mins / 2) / round_mins * round_mins;
+        NaiveTime::from_num_seconds_from_midnight_opt(rounded * 60, 0)
+    });
+    rounded_now.map_or(previous_end, |it| it.max(previous_end))
+}
+
+/// Start and minutes of the entry after one that is nudged from `end` to `new_end`. An entry
+/// starting right at the end gives or takes the difference, one after a gap only gives what
+/// the nudged one grows into it. Fails rather than using it up; one without minutes yet, the
+/// last being typed, just moves.
+pub fn nudge_next(
+    end: NaiveTime,
+    new_end: NaiveTime,
+    next_start: NaiveTime,
+    next_mins: u32,
+) -> color_eyre::Result<(NaiveTime, u32)> {
+    if next_start != end && new_end <= next_start {
+        return Ok((next_start, next_mins));
+    }
+    if next_mins == 0 {
+        return Ok((new_end, 0));
+    }
+    let taken = (new_end - next_start).num_minutes();
+    match i64::from(next_mins) - taken {
+        mins if mins > 0 => Ok((new_end, mins as u32)),
+        _ => color_eyre::eyre::bail!("the next entry has only {next_mins}m to give"),
+    }
+}
+
+/// Nearest multiple of `grid_mins` minutes, the one before if the next would be past midnight.
+/// `grid_mins` of `0` keeps the time as is.
+pub fn snap_to_grid(time: NaiveTime, grid_mins: u32) -> NaiveTime {
+    if grid_mins == 0 {
+        return time;
+    }
+    let mins = time.num_seconds_from_midnight() / 60;
+    let snapped = (mins + grid_mins / 2) / grid_mins * grid_mins;
+    NaiveTime::from_num_seconds_from_midnight_opt(snapped * 60, 0)
+        .or_else(|| {
+            NaiveTime::from_num_seconds_from_midnight_opt(mins / grid_mins * grid_mins * 60, 0)
+        })
+        .unwrap_or(time)
+}
+
 /// Simple local version tracker for saving with a single actor.
 ///
 /// This does not assume that the remote stores version numbers and is suitable
@@ -23,6 +125,8 @@ pub struct DataVersion {
 
     /// local version number that has been sent to be saved
     pub sent: Option<DataVersionNumber>,
+    /// local version number whose save failed for good, until a later one is saved
+    pub failed: Option<DataVersionNumber>,
 }
 
 pub type DataVersionNumber = i32;
@@ -33,6 +137,7 @@ impl DataVersion {
             local: 1,
             saved: 0,
             sent: None,
+            failed: None,
         }
     }
 
@@ -41,11 +146,13 @@ impl DataVersion {
             local: 1,
             saved: 1,
             sent: None,
+            failed: None,
         }
     }
 
     pub fn touch(&mut self) {
-        if self.local > self.saved { // already touched and not saved, combine these changes into one version
+        if self.local > self.saved && self.sent != Some(self.local) {
+            // already touched and not sent, combine these changes into one version
         } else {
             self.local += 1;
         }
@@ -64,6 +171,9 @@ impl DataVersion {
             return;
         }
         self.saved = saved_version;
+        if self.failed.is_some_and(|it| it <= saved_version) {
+            self.failed = None;
+        }
         if let Some(sent) = self.sent
             && sent == saved_version
         {
@@ -71,6 +181,51 @@ impl DataVersion {
         }
     }
 
+    /// Sending this version failed, so it should be sent again
+    pub fn notify_failed(&mut self, failed_version: DataVersionNumber) {
+        if self.sent == Some(failed_version) {
+            self.sent = None;
+        }
+    }
+
+    /// Saving this version won't be retried, it stays sent until the next change or [Self::retry]
+    pub fn notify_given_up(&mut self, failed_version: DataVersionNumber) {
+        if self.sent == Some(failed_version) {
+            self.failed = Some(failed_version);
+        }
+    }
+
+    /// Sends the version that failed for good again, `false` if nothing failed
+    pub fn retry(&mut self) -> bool {
+        if !self.has_failed() {
+            return false;
+        }
+        self.sent = None;
+        true
+    }
+
+    /// Forgets the local changes once the saved values were put back, `false` while they are
+    /// being sent, since they might be saved after all
+    pub fn discard(&mut self) -> bool {
+        if self.sent.is_some() && !self.has_failed() {
+            return false;
+        }
+        self.local = self.saved;
+        self.sent = None;
+        self.failed = None;
+        true
+    }
+
+    /// Changes that are not being sent, e.g. because saving them is blocked or failed for good
+    pub fn is_unsaved(&self) -> bool {
+        self.should_save() || self.has_failed()
+    }
+
+    /// Changes that could not be saved and are not being sent
+    pub fn has_failed(&self) -> bool {
+        self.failed.is_some() && self.is_dirty()
+    }
+
     pub fn should_save(&self) -> bool {
         self.is_dirty() && self.sent != Some(self.local)
     }
@@ -79,3 +234,163 @@ impl DataVersion {
         self.saved != self.local
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    #[test]
+    fn test_chunks_cover_range_without_overlap() {
+        assert_eq!(
+            chunks_of(date!(2025 - 01 - 01)..=date!(2025 - 03 - 05), 31),
+            vec![
+                date!(2025 - 01 - 01)..=date!(2025 - 01 - 31),
+                date!(2025 - 02 - 01)..=date!(2025 - 03 - 03),
+                date!(2025 - 03 - 04)..=date!(2025 - 03 - 05),
+            ]
+        );
+        let day = date!(2025 - 09 - 22);
+        assert_eq!(chunks_of(day..=day, 31), vec![day..=day]);
+    }
+
+    #[test]
+    fn test_nudge_takes_from_next_entry_without_using_it_up() {
+        let time = |hour, min| NaiveTime::from_hms_opt(hour, min, 0).unwrap();
+        let (end, next_start) = (time(10, 0), time(10, 0));
+
+        assert_eq!(
+            nudge_next(end, time(10, 5), next_start, 30).unwrap(),
+            (time(10, 5), 25)
+        );
+        assert_eq!(
+            nudge_next(end, time(9, 55), next_start, 30).unwrap(),
+            (time(9, 55), 35)
+        );
+        assert!(nudge_next(end, time(10, 5), next_start, 5).is_err());
+        assert!(nudge_next(end, time(10, 15), next_start, 5).is_err());
+        assert_eq!(
+            nudge_next(end, time(10, 5), next_start, 0).unwrap(),
+            (time(10, 5), 0)
+        );
+    }
+
+    #[test]
+    fn test_nudge_grows_into_gap_before_next_entry() {
+        let time = |hour, min| NaiveTime::from_hms_opt(hour, min, 0).unwrap();
+        let (end, next_start) = (time(10, 0), time(10, 10));
+
+        assert_eq!(
+            nudge_next(end, time(10, 5), next_start, 30).unwrap(),
+            (next_start, 30)
+        );
+        assert_eq!(
+            nudge_next(end, time(9, 55), next_start, 30).unwrap(),
+            (next_start, 30)
+        );
+        assert_eq!(
+            nudge_next(end, time(10, 15), next_start, 30).unwrap(),
+            (time(10, 15), 25)
+        );
+    }
+
+    #[test]
+    fn test_next_start_is_rounded_now_unless_before_previous_end() {
+        let time = |hour, min| NaiveTime::from_hms_opt(hour, min, 0).unwrap();
+        let previous_end = time(10, 0);
+
+        assert_eq!(next_start(previous_end, None, 5), previous_end);
+        assert_eq!(next_start(previous_end, Some(time(9, 40)), 5), previous_end);
+        assert_eq!(next_start(previous_end, Some(time(14, 2)), 5), time(14, 0));
+        assert_eq!(next_start(previous_end, Some(time(14, 3)), 5), time(14, 5));
+        assert_eq!(next_start(previous_end, Some(time(14, 3)), 15), time(14, 0));
+        assert_eq!(next_start(previous_end, Some(time(14, 3)), 0), previous_end);
+        assert_eq!(
+            next_start(previous_end, Some(time(23, 58)), 5),
+            previous_end
+        );
+    }
+
+    #[test]
+    fn test_snap_to_grid_stays_within_day() {
+        let time = |hour, min| NaiveTime::from_hms_opt(hour, min, 0).unwrap();
+
+        assert_eq!(snap_to_grid(time(9, 2), 5), time(9, 0));
+        assert_eq!(snap_to_grid(time(9, 3), 5), time(9, 5));
+        assert_eq!(snap_to_grid(time(9, 7), 15), time(9, 0));
+        assert_eq!(snap_to_grid(time(9, 8), 15), time(9, 15));
+        assert_eq!(snap_to_grid(time(9, 3), 0), time(9, 3));
+        assert_eq!(snap_to_grid(time(23, 58), 5), time(23, 55));
+    }
+
+    #[test]
+    fn test_change_while_sending_is_saved_after_it() {
+        let mut version = DataVersion::loaded();
+        version.touch();
+        version.touch();
+        assert_eq!(version.local, 2, "changes before sending are combined");
+        version.mark_sent();
+
+        version.touch();
+        assert_eq!(version.local, 3);
+        version.notify_saved(2);
+        assert!(version.should_save());
+        version.mark_sent();
+        version.notify_saved(3);
+        assert!(!version.is_dirty());
+    }
+
+    #[test]
+    fn test_failed_save_is_kept_until_retried_and_saved() {
+        let mut version = DataVersion::loaded();
+        version.touch();
+        version.mark_sent();
+        assert!(!version.should_save());
+
+        version.notify_given_up(version.local);
+        assert!(version.has_failed());
+        assert!(!version.should_save());
+
+        assert!(version.retry());
+        assert!(version.should_save());
+        version.mark_sent();
+        assert!(version.has_failed());
+
+        version.notify_saved(version.local);
+        assert!(!version.has_failed());
+        assert!(!version.retry());
+    }
+
+    #[test]
+    fn test_discard_only_while_not_being_sent() {
+        let mut version = DataVersion::loaded();
+        version.touch();
+        version.mark_sent();
+        assert!(!version.discard());
+
+        version.notify_given_up(version.local);
+        assert!(version.discard());
+        assert!(!version.should_save());
+        assert!(!version.has_failed());
+
+        version.touch();
+        assert!(version.should_save());
+        assert!(version.discard());
+        assert!(!version.should_save());
+    }
+
+    #[test]
+    fn test_unsaved_unless_being_sent() {
+        let mut version = DataVersion::loaded();
+        assert!(!version.is_unsaved());
+        version.touch();
+        assert!(version.is_unsaved());
+        version.mark_sent();
+        assert!(!version.is_unsaved());
+        version.notify_given_up(version.local);
+        assert!(version.is_unsaved());
+        version.notify_saved(version.local);
+        assert!(!version.is_unsaved());
+    }
+}
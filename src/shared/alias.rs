@@ -0,0 +1,97 @@
+use itertools::Itertools;
+
+use crate::config::{AliasConfig, Config};
+
+/// Aliases whose name starts with the typed text, by name
+pub fn matching<'a>(config: &'a Config, typed: &str) -> Vec<(&'a str, &'a AliasConfig)> {
+    let typed = typed.trim();
+    if typed.is_empty() {
+        return vec![];
+    }
+    config
+        .aliases
+        .iter()
+        .filter(|(name, _)| name.starts_with(typed))
+        .map(|(name, alias)| (name.as_str(), alias))
+        .sorted_by_key(|(name, _)| *name)
+        .collect()
+}
+
+/// The alias named exactly like the typed text, or the only one starting with it
+pub fn resolve<'a>(config: &'a Config, typed: &str) -> Option<&'a AliasConfig> {
+    if let Some(exact) = config.aliases.get(typed.trim()) {
+        return Some(exact);
+    }
+    match matching(config, typed)[..] {
+        [(_, only)] => Some(only),
+        _ => None,
+    }
+}
+
+/// What the alias fills in, e.g. `SCRUM-1 · standup`
+pub fn describe(alias: &AliasConfig) -> String {
+    [&alias.project, &alias.ticket, &alias.description]
+        .into_iter()
+        .flatten()
+        .join(" · ")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn alias(ticket: &str, description: &str) -> AliasConfig {
+        AliasConfig {
+            project: None,
+            ticket: Some(ticket.to_string()),
+            description: Some(description.to_string()),
+        }
+    }
+
+    fn config() -> Config {
+        let aliases = HashMap::from([
+            ("st".to_string(), alias("SCRUM-1", "standup")),
+            ("stp".to_string(), alias("SCRUM-2", "sprint planning")),
+            ("rv".to_string(), alias("SCRUM-3", "review")),
+        ]);
+        Config {
+            aliases,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_matching_is_sorted_by_name() {
+        let config = config();
+
+        let names = matching(&config, "st")
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect_vec();
+
+        assert_eq!(names, vec!["st", "stp"]);
+        assert!(matching(&config, " ").is_empty());
+    }
+
+    #[test]
+    fn test_resolve_prefers_exact_then_unique_prefix() {
+        let config = config();
+
+        assert_eq!(resolve(&config, "st "), Some(&alias("SCRUM-1", "standup")));
+        assert_eq!(resolve(&config, "r"), Some(&alias("SCRUM-3", "review")));
+        assert_eq!(resolve(&config, "s"), None);
+        assert_eq!(resolve(&config, "x"), None);
+    }
+
+    #[test]
+    fn test_describe_skips_missing_fields() {
+        assert_eq!(describe(&alias("SCRUM-1", "standup")), "SCRUM-1 · standup");
+        let project_only = AliasConfig {
+            project: Some("E".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(describe(&project_only), "E");
+    }
+}
@@ -0,0 +1,170 @@
+use std::{
+    cmp::Reverse,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use color_eyre::{Result, eyre::Context};
+use directories::BaseDirs;
+use regex::Regex;
+
+use crate::config::Config;
+
+/// Used for projects without a `ticket_pattern`
+const DEFAULT_TICKET_PATTERN: &str = r"[A-Z][A-Z0-9]+-\d+";
+
+/// Ticket found in the branch checked out in one of the configured repos
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchTicket {
+    pub project_key: String,
+    pub repo: PathBuf,
+    pub branch: String,
+    pub ticket: String,
+}
+
+/// Looks at the repo containing the working directory, otherwise at the one whose branch was
+/// switched most recently. Projects find tickets with their `ticket_pattern` in the branch name.
+pub fn infer(config: &Config, working_dir: &Path) -> Result<Option<BranchTicket>> {
+    let mut candidates = vec![];
+    for (project_key, project) in &config.projects {
+        for repo in &project.repos {
+            let repo = expand_home(repo);
+            let Some(head) = head_file(&repo)? else {
+                continue;
+            };
+            let switched_at = fs::metadata(&head)
+                .and_then(|it| it.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            let contains_working_dir = working_dir.starts_with(&repo);
+            candidates.push((contains_working_dir, switched_at, project_key, repo, head));
+        }
+    }
+    candidates.sort_by_key(|it| Reverse((it.0, it.1)));
+
+    for (_, _, project_key, repo, head) in candidates {
+        let Some(branch) = read_branch(&head)? else {
+            continue;
+        };
+        let pattern = config.projects[project_key]
+            .ticket_pattern
+            .as_ref()
+            .map_or(DEFAULT_TICKET_PATTERN, |it| it.as_str());
+        let pattern = Regex::new(pattern)
+            .wrap_err_with(|| format!("ticket_pattern of project {project_key}"))?;
+        if let Some(ticket) = ticket_from_branch(&branch, &pattern) {
+            return Ok(Some(BranchTicket {
+                project_key: project_key.clone(),
+                repo,
+                branch,
+                ticket,
+            }));
+        }
+    }
+    Ok(None)
+}
+
+/// First match of the pattern, upper-cased since branch names are often lower case
+pub fn ticket_from_branch(branch: &str, pattern: &Regex) -> Option<String> {
+    let upper = branch.to_uppercase();
+    pattern
+        .find(branch)
+        .or_else(|| pattern.find(&upper))
+        .map(|it| it.as_str().to_uppercase())
+}
+
+/// `HEAD` of the repo, also for worktrees where `.git` is a file pointing to the real one
+fn head_file(repo: &Path) -> Result<Option<PathBuf>> {
+    let dot_git = repo.join(".git");
+    if dot_git.is_dir() {
+        return Ok(Some(dot_git.join("HEAD")));
+    }
+    if !dot_git.is_file() {
+        return Ok(None);
+    }
+    let content =
+        fs::read_to_string(&dot_git).wrap_err_with(|| format!("read {}", dot_git.display()))?;
+    Ok(content
+        .trim()
+        .strip_prefix("gitdir: ")
+        .map(|git_dir| repo.join(git_dir).join("HEAD")))
+}
+
+/// Name of the checked out branch, `None` if detached
+fn read_branch(head: &Path) -> Result<Option<String>> {
+    let content = fs::read_to_string(head).wrap_err_with(|| format!("read {}", head.display()))?;
+    Ok(content
+        .trim()
+        .strip_prefix("ref: refs/heads/")
+        .map(str::to_string))
+}
+
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), BaseDirs::new()) {
+        (Ok(rest), Some(dirs)) => dirs.home_dir().join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::config::ProjectConfig;
+
+    #[test]
+    fn test_ticket_from_branch() {
+        let default = Regex::new(DEFAULT_TICKET_PATTERN).unwrap();
+        assert_eq!(
+            ticket_from_branch("feature/SCRUM-17-login", &default),
+            Some("SCRUM-17".into())
+        );
+        assert_eq!(
+            ticket_from_branch("bugfix/plat-3_crash", &default),
+            Some("PLAT-3".into())
+        );
+        assert_eq!(ticket_from_branch("main", &default), None);
+        let scrum_only = Regex::new(r"SCRUM-\d+").unwrap();
+        assert_eq!(
+            ticket_from_branch("PLAT-3-and-SCRUM-9", &scrum_only),
+            Some("SCRUM-9".into())
+        );
+    }
+
+    #[test]
+    fn test_infer_prefers_repo_of_working_dir() {
+        let root = std::env::temp_dir().join(format!("sheet-shark-branch-{}", std::process::id()));
+        let api = root.join("api");
+        let web = root.join("web");
+        for (repo, branch) in [(&api, "feature/API-12-auth"), (&web, "WEB-7")] {
+            fs::create_dir_all(repo.join(".git")).unwrap();
+            fs::write(
+                repo.join(".git/HEAD"),
+                format!("ref: refs/heads/{branch}\n"),
+            )
+            .unwrap();
+        }
+        let project = |repos: Vec<PathBuf>| ProjectConfig {
+            repos,
+            ..Default::default()
+        };
+        let config = Config {
+            projects: HashMap::from([
+                ("A".to_string(), project(vec![api.clone()])),
+                (
+                    "W".to_string(),
+                    project(vec![web.clone(), root.join("missing")]),
+                ),
+            ]),
+            ..Default::default()
+        };
+
+        let inferred = infer(&config, &web.join("src")).unwrap().unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(inferred.ticket, "WEB-7");
+        assert_eq!(inferred.project_key, "W");
+        assert_eq!(inferred.repo, web);
+    }
+}
@@ -0,0 +1,37 @@
+use std::ops::Range;
+
+/// Index ranges of at least two consecutive items that are duplicates of the first one, e.g.
+/// entries of the live timer or an import that were cut into pieces
+pub fn consecutive_runs<T>(
+    items: &[T],
+    is_duplicate: impl Fn(&T, &T) -> bool,
+) -> Vec<Range<usize>> {
+    let mut runs = vec![];
+    let mut start = 0;
+    for idx in 1..=items.len() {
+        let continues = idx < items.len() && is_duplicate(&items[start], &items[idx]);
+        if !continues {
+            if idx - start >= 2 {
+                runs.push(start..idx);
+            }
+            start = idx;
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_runs_of_two_or_more() {
+        let items = ["a", "a", "b", "a", "c", "c", "c"];
+
+        let same = |a: &&str, b: &&str| a == b;
+
+        assert_eq!(consecutive_runs(&items, same), vec![0..2, 4..7]);
+        assert!(consecutive_runs(&items[2..4], same).is_empty());
+        assert!(consecutive_runs(&[], same).is_empty());
+    }
+}
@@ -1,8 +1,10 @@
 use std::collections::VecDeque;
 
+use itertools::Itertools;
 use serde::Serialize;
 
-use crate::shared::{BREAK_PROJECT_KEY, summary::TimesheetSummary};
+pub use crate::config::DefragStrategy;
+use crate::shared::summary::TimesheetSummary;
 
 #[derive(Serialize, Debug, Clone)]
 pub struct DefragmentedEntry {
@@ -12,6 +14,14 @@ pub struct DefragmentedEntry {
     pub end_time: String,
 }
 
+impl DefragmentedEntry {
+    pub fn duration_mins(&self) -> u32 {
+        let start = parse_time_to_minutes(&self.start_time).unwrap_or_default();
+        let end = parse_time_to_minutes(&self.end_time).unwrap_or_default();
+        end.saturating_sub(start)
+    }
+}
+
 #[derive(Debug, Clone)]
 struct ProjectTicket {
     project_key: String,
@@ -31,52 +41,144 @@ impl Break {
     }
 }
 
-pub fn calculate(summary: &TimesheetSummary) -> Vec<DefragmentedEntry> {
+pub fn calculate(summary: &TimesheetSummary, strategy: DefragStrategy) -> Vec<DefragmentedEntry> {
     let start_time = match &summary.start_time {
         Some(time) => time.as_str(),
         None => return Vec::new(),
     };
 
     let start_minutes = parse_time_to_minutes(start_time).expect("Valid start time");
-    let project_tickets = collect_project_tickets_in_chronological_order(summary);
+    let project_tickets = match strategy {
+        DefragStrategy::Chronological => collect_runs_in_chronological_order(summary),
+        DefragStrategy::ByProject => collect_project_tickets_in_chronological_order(summary),
+        DefragStrategy::ByTicket | DefragStrategy::MinimizeSwitches => {
+            collect_tickets_by_first_start(summary)
+        }
+    };
 
     if project_tickets.is_empty() {
         return Vec::new();
     }
 
     let breaks = parse_breaks(summary);
+    if strategy == DefragStrategy::MinimizeSwitches {
+        return allocate_project_tickets_with_breaks(
+            fit_tickets_between_breaks(project_tickets, &breaks, start_minutes),
+            breaks,
+            start_minutes,
+        );
+    }
     allocate_project_tickets_with_breaks(project_tickets, breaks, start_minutes)
 }
 
-/// Collects project tickets sorted by the first start time of each project
+/// Consecutive entries on the same ticket merged, in the order they were worked on
+fn collect_runs_in_chronological_order(summary: &TimesheetSummary) -> Vec<ProjectTicket> {
+    let mut runs: Vec<ProjectTicket> = Vec::new();
+    for entry in &summary.entries {
+        let ticket_key = entry.ticket_key.as_deref().unwrap_or("-");
+        let minutes = entry.duration_mins as u32;
+        match runs.last_mut() {
+            Some(last)
+                if last.project_key == entry.project_key && last.ticket_key == ticket_key =>
+            {
+                last.duration_mins += minutes;
+            }
+            _ => runs.push(ProjectTicket {
+                project_key: entry.project_key.clone(),
+                ticket_key: ticket_key.to_string(),
+                duration_mins: minutes,
+            }),
+        }
+    }
+    runs
+}
+
+/// Total time of each ticket, sorted by when work on it started
+fn collect_tickets_by_first_start(summary: &TimesheetSummary) -> Vec<ProjectTicket> {
+    let mut tickets: Vec<ProjectTicket> = Vec::new();
+    for entry in &summary.entries {
+        let ticket_key = entry.ticket_key.as_deref().unwrap_or("-");
+        let minutes = entry.duration_mins as u32;
+        match tickets
+            .iter_mut()
+            .find(|it| it.project_key == entry.project_key && it.ticket_key == ticket_key)
+        {
+            Some(ticket) => ticket.duration_mins += minutes,
+            None => tickets.push(ProjectTicket {
+                project_key: entry.project_key.clone(),
+                ticket_key: ticket_key.to_string(),
+                duration_mins: minutes,
+            }),
+        }
+    }
+    tickets
+}
+
+/// Tickets grouped by project, projects sorted by their first start time and tickets within
+/// them by theirs
 fn collect_project_tickets_in_chronological_order(
     summary: &TimesheetSummary,
 ) -> Vec<ProjectTicket> {
-    let mut project_keys: Vec<(&String, &Option<String>)> = summary
-        .projects
+    let tickets = collect_tickets_by_first_start(summary);
+    let projects = tickets
         .iter()
-        .filter(|(project_key, _)| *project_key != BREAK_PROJECT_KEY)
-        .map(|(key, summary)| (key, &summary.first_start))
-        .collect();
+        .map(|it| it.project_key.clone())
+        .unique()
+        .collect_vec();
+    tickets
+        .into_iter()
+        .sorted_by_key(|it| projects.iter().position(|p| *p == it.project_key))
+        .collect()
+}
 
-    project_keys.sort_by_key(|(_, first_start)| *first_start);
-
-    let mut project_tickets = Vec::new();
-    for (project_key, _) in project_keys {
-        let project_summary = &summary.projects[project_key];
-        for (ticket_key, duration) in &project_summary.ticket_sums {
-            let minutes = duration.whole_minutes();
-            if minutes > 0 {
-                project_tickets.push(ProjectTicket {
-                    project_key: project_key.clone(),
-                    ticket_key: ticket_key.clone(),
-                    duration_mins: minutes as u32,
-                });
+/// Reorders the tickets so that whole ones fill the time up to each break, splitting a ticket
+/// only if none of the remaining ones fits. The longest one is split then, continuing after
+/// the break.
+fn fit_tickets_between_breaks(
+    mut tickets: Vec<ProjectTicket>,
+    breaks: &[Break],
+    start_minutes: u32,
+) -> Vec<ProjectTicket> {
+    let mut result: Vec<ProjectTicket> = Vec::new();
+    let mut breaks = breaks.iter().peekable();
+    let mut current_minutes = start_minutes;
+
+    while !tickets.is_empty() {
+        while let Some(next_break) = breaks.peek()
+            && next_break.has_started_at(current_minutes)
+        {
+            current_minutes += next_break.duration_mins;
+            breaks.next();
+        }
+        let capacity = breaks
+            .peek()
+            .map_or(u32::MAX, |it| it.start_mins - current_minutes);
+
+        let idx = tickets
+            .iter()
+            .position(|it| it.duration_mins <= capacity)
+            .or_else(|| tickets.iter().position_max_by_key(|it| it.duration_mins))
+            .expect("tickets not empty");
+        let mut ticket = tickets.remove(idx);
+        if ticket.duration_mins > capacity {
+            let mut rest = ticket.clone();
+            rest.duration_mins -= capacity;
+            ticket.duration_mins = capacity;
+            tickets.insert(0, rest);
+        }
+        current_minutes += ticket.duration_mins;
+
+        match result.last_mut() {
+            Some(last)
+                if last.project_key == ticket.project_key
+                    && last.ticket_key == ticket.ticket_key =>
+            {
+                last.duration_mins += ticket.duration_mins;
             }
+            _ => result.push(ticket),
         }
     }
-
-    project_tickets
+    result
 }
 
 /// Converts breaks from the summary into sorted internal format
@@ -143,10 +245,10 @@ fn allocate_project_tickets_with_breaks(
 
 fn parse_time_to_minutes(time: &str) -> Option<u32> {
     let parts: Vec<&str> = time.split(':').collect();
-    if parts.len() == 2 {
-        if let (Ok(hours), Ok(minutes)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>()) {
-            return Some(hours * 60 + minutes);
-        }
+    if parts.len() == 2
+        && let (Ok(hours), Ok(minutes)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>())
+    {
+        return Some(hours * 60 + minutes);
     }
     None
 }
@@ -162,34 +264,29 @@ mod tests {
     use crate::config::Config;
 
     use super::*;
-    use crate::persist::TimeEntry;
+    use crate::{persist::TimeEntry, shared::BREAK_PROJECT_KEY};
 
     #[test]
     fn test_no_breaks() {
-        Config::set_for_tests(Default::default());
         let entries = vec![
             TimeEntry {
                 id: "1".to_string(),
                 timesheet_day: "2026-01-08".to_string(),
-                start_time: "09:00".to_string(),
-                duration_mins: 120,
                 project_key: "PROJECT1".to_string(),
                 ticket_key: Some("TICKET-1".to_string()),
-                description: String::new(),
+                ..TimeEntry::sample("09:00", 120)
             },
             TimeEntry {
                 id: "2".to_string(),
                 timesheet_day: "2026-01-08".to_string(),
-                start_time: "11:00".to_string(),
-                duration_mins: 60,
                 project_key: "PROJECT2".to_string(),
                 ticket_key: Some("TICKET-2".to_string()),
-                description: String::new(),
+                ..TimeEntry::sample("11:00", 60)
             },
         ];
 
-        let summary = TimesheetSummary::new(entries);
-        let result = calculate(&summary);
+        let summary = TimesheetSummary::new(entries, &Config::default());
+        let result = calculate(&summary, DefragStrategy::ByProject);
 
         assert_eq!(result.len(), 2); // Now we have 2 separate entries
 
@@ -208,39 +305,31 @@ mod tests {
 
     #[test]
     fn test_with_break() {
-        Config::set_for_tests(Default::default());
         let entries = vec![
             TimeEntry {
                 id: "1".to_string(),
                 timesheet_day: "2026-01-08".to_string(),
-                start_time: "09:00".to_string(),
-                duration_mins: 120,
                 project_key: "PROJECT1".to_string(),
                 ticket_key: Some("TICKET-1".to_string()),
-                description: String::new(),
+                ..TimeEntry::sample("09:00", 120)
             },
             TimeEntry {
                 id: "2".to_string(),
                 timesheet_day: "2026-01-08".to_string(),
-                start_time: "11:00".to_string(),
-                duration_mins: 30,
                 project_key: BREAK_PROJECT_KEY.to_string(),
-                ticket_key: None,
-                description: String::new(),
+                ..TimeEntry::sample("11:00", 30)
             },
             TimeEntry {
                 id: "3".to_string(),
                 timesheet_day: "2026-01-08".to_string(),
-                start_time: "11:30".to_string(),
-                duration_mins: 60,
                 project_key: "PROJECT2".to_string(),
                 ticket_key: Some("TICKET-2".to_string()),
-                description: String::new(),
+                ..TimeEntry::sample("11:30", 60)
             },
         ];
 
-        let summary = TimesheetSummary::new(entries);
-        let result = calculate(&summary);
+        let summary = TimesheetSummary::new(entries, &Config::default());
+        let result = calculate(&summary, DefragStrategy::ByProject);
 
         // Should have 2 entries: PROJECT1 before break, PROJECT2 after break
         assert_eq!(result.len(), 2);
@@ -260,30 +349,25 @@ mod tests {
 
     #[test]
     fn test_break_cuts_allocation() {
-        Config::set_for_tests(Default::default());
         let entries = vec![
+            // 3 hours of project work with a 30 min break in the middle
             TimeEntry {
                 id: "1".to_string(),
                 timesheet_day: "2026-01-08".to_string(),
-                start_time: "09:00".to_string(),
-                duration_mins: 180, // 3 hours of project work
                 project_key: "PROJECT1".to_string(),
                 ticket_key: Some("TICKET-1".to_string()),
-                description: String::new(),
+                ..TimeEntry::sample("09:00", 180)
             },
             TimeEntry {
                 id: "2".to_string(),
                 timesheet_day: "2026-01-08".to_string(),
-                start_time: "10:30".to_string(),
-                duration_mins: 30, // 30 min break in the middle
                 project_key: BREAK_PROJECT_KEY.to_string(),
-                ticket_key: None,
-                description: String::new(),
+                ..TimeEntry::sample("10:30", 30)
             },
         ];
 
-        let summary = TimesheetSummary::new(entries);
-        let result = calculate(&summary);
+        let summary = TimesheetSummary::new(entries, &Config::default());
+        let result = calculate(&summary, DefragStrategy::ByProject);
 
         assert_eq!(result.len(), 2);
         assert_eq!(result[0].project_key, "PROJECT1");
@@ -295,4 +379,109 @@ mod tests {
         assert_eq!(result[1].start_time, "11:00"); // Resume after break
         assert_eq!(result[1].end_time, "12:30"); // 90 remaining minutes
     }
+
+    fn entry(start_time: &str, duration_mins: i32, project: &str, ticket: &str) -> TimeEntry {
+        TimeEntry {
+            id: start_time.to_string(),
+            timesheet_day: "2026-01-08".to_string(),
+            project_key: project.to_string(),
+            ticket_key: (!ticket.is_empty()).then(|| ticket.to_string()),
+            ..TimeEntry::sample(start_time, duration_mins)
+        }
+    }
+
+    fn blocks(entries: Vec<TimeEntry>, strategy: DefragStrategy) -> Vec<String> {
+        let summary = TimesheetSummary::new(entries, &Config::default());
+        calculate(&summary, strategy)
+            .into_iter()
+            .map(|it| {
+                format!(
+                    "{}-{} {}/{}",
+                    it.start_time, it.end_time, it.project_key, it.ticket_key
+                )
+            })
+            .collect()
+    }
+
+    fn interleaved_day() -> Vec<TimeEntry> {
+        vec![
+            entry("09:00", 60, "A", "T1"),
+            entry("10:00", 30, "B", "T2"),
+            entry("10:30", 60, "A", "T1"),
+            entry("11:30", 30, BREAK_PROJECT_KEY, ""),
+            entry("12:00", 30, "B", "T3"),
+            entry("12:30", 30, "A", "T4"),
+        ]
+    }
+
+    #[test]
+    fn test_chronological_keeps_order_worked() {
+        assert_eq!(
+            blocks(interleaved_day(), DefragStrategy::Chronological),
+            [
+                "09:00-10:00 A/T1",
+                "10:00-10:30 B/T2",
+                "10:30-11:30 A/T1",
+                "12:00-12:30 B/T3",
+                "12:30-13:00 A/T4",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_by_project_groups_tickets_of_project() {
+        assert_eq!(
+            blocks(interleaved_day(), DefragStrategy::ByProject),
+            [
+                "09:00-11:00 A/T1",
+                "11:00-11:30 A/T4",
+                "12:00-12:30 B/T2",
+                "12:30-13:00 B/T3",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_by_ticket_ignores_projects() {
+        assert_eq!(
+            blocks(interleaved_day(), DefragStrategy::ByTicket),
+            [
+                "09:00-11:00 A/T1",
+                "11:00-11:30 B/T2",
+                "12:00-12:30 B/T3",
+                "12:30-13:00 A/T4",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_minimize_switches_fits_whole_tickets_before_breaks() {
+        let entries = vec![
+            entry("09:00", 30, "A", "T1"),
+            entry("09:30", 30, "B", "T2"),
+            entry("10:00", 30, BREAK_PROJECT_KEY, ""),
+            entry("10:30", 60, "A", "T1"),
+            entry("11:30", 30, "B", "T2"),
+        ];
+        assert_eq!(
+            blocks(entries.clone(), DefragStrategy::ByTicket),
+            ["09:00-10:00 A/T1", "10:30-11:00 A/T1", "11:00-12:00 B/T2",]
+        );
+        assert_eq!(
+            blocks(entries, DefragStrategy::MinimizeSwitches),
+            ["09:00-10:00 B/T2", "10:30-12:00 A/T1"]
+        );
+
+        // Nothing fits before the break, so the longest ticket is split
+        let entries = vec![
+            entry("09:00", 30, "A", "T1"),
+            entry("09:30", 30, BREAK_PROJECT_KEY, ""),
+            entry("10:00", 45, "B", "T2"),
+            entry("10:45", 60, "A", "T1"),
+        ];
+        assert_eq!(
+            blocks(entries, DefragStrategy::MinimizeSwitches),
+            ["09:00-09:30 A/T1", "10:00-11:00 A/T1", "11:00-11:45 B/T2",]
+        );
+    }
 }
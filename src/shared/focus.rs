@@ -0,0 +1,274 @@
+use std::process::Command;
+
+use chrono::{NaiveDateTime, TimeDelta};
+use color_eyre::{
+    Result,
+    eyre::{Context, bail},
+};
+use regex::Regex;
+use tracing::warn;
+
+use crate::config::{FocusConfig, FocusRule};
+
+/// Source of the title of the focused window.
+pub trait FocusProvider {
+    fn focused_title(&mut self) -> Result<String>;
+}
+
+/// Runs a command that prints the title, e.g. `xdotool getactivewindow getwindowname` on X11
+pub struct CommandFocusProvider {
+    command: String,
+}
+
+impl FocusProvider for CommandFocusProvider {
+    fn focused_title(&mut self) -> Result<String> {
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(&self.command)
+            .output()
+            .wrap_err_with(|| format!("running focus command {}", self.command))?;
+        if !output.status.success() {
+            bail!("focus command {} failed: {}", self.command, output.status);
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+/// What a rule makes of a title, with the groups of its pattern filled in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FocusMatch {
+    pub project: Option<String>,
+    pub ticket: Option<String>,
+    pub description: String,
+}
+
+impl FocusMatch {
+    /// Ticket and description, for messages
+    pub fn label(&self) -> String {
+        let ticket = self.ticket.as_deref().unwrap_or_default();
+        format!("{ticket} {}", self.description).trim().to_string()
+    }
+}
+
+pub struct FocusRules {
+    rules: Vec<(Regex, FocusRule)>,
+}
+
+impl FocusRules {
+    pub fn new(rules: &[FocusRule]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let regex = Regex::new(&rule.pattern)
+                    .wrap_err_with(|| format!("focus rule pattern {:?}", rule.pattern))?;
+                Ok((regex, rule.clone()))
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Result of the first rule matching the title
+    pub fn match_title(&self, title: &str) -> Option<FocusMatch> {
+        self.rules.iter().find_map(|(regex, rule)| {
+            let captures = regex.captures(title)?;
+            let expand = |template: &str| {
+                let mut expanded = String::new();
+                captures.expand(template, &mut expanded);
+                expanded
+            };
+            Some(FocusMatch {
+                project: rule.project.as_deref().map(expand),
+                ticket: rule.ticket.as_deref().map(expand),
+                description: expand(&rule.description),
+            })
+        })
+    }
+}
+
+/// Time a rule matched without interruption
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FocusSuggestion {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub matched: FocusMatch,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FocusEvent {
+    /// A rule has matched for the minimum time, e.g. to switch the timer
+    Settled(FocusMatch),
+    /// The focus moved away from a rule that had settled
+    Finished(FocusSuggestion),
+}
+
+/// Polls a [FocusProvider] and reports how long each rule matched the focused window.
+pub struct FocusTracker {
+    provider: Box<dyn FocusProvider>,
+    rules: FocusRules,
+    min_duration: TimeDelta,
+    poll_interval: TimeDelta,
+    last_poll: Option<NaiveDateTime>,
+    /// Match of the focused window, `None` if no rule matches, and since when
+    current: Option<(Option<FocusMatch>, NaiveDateTime)>,
+    settled: bool,
+    disabled: bool,
+}
+
+impl FocusTracker {
+    pub fn from_config(config: &FocusConfig) -> Result<Option<Self>> {
+        if !config.enabled || config.rules.is_empty() {
+            return Ok(None);
+        }
+        let provider = CommandFocusProvider {
+            command: config.command.clone(),
+        };
+        Ok(Some(Self::new(
+            Box::new(provider),
+            FocusRules::new(&config.rules)?,
+            TimeDelta::minutes(config.min_mins as i64),
+        )))
+    }
+
+    pub fn new(
+        provider: Box<dyn FocusProvider>,
+        rules: FocusRules,
+        min_duration: TimeDelta,
+    ) -> Self {
+        Self {
+            provider,
+            rules,
+            min_duration,
+            poll_interval: TimeDelta::seconds(10),
+            last_poll: None,
+            current: None,
+            settled: false,
+            disabled: false,
+        }
+    }
+
+    /// Call regularly, e.g. on tick
+    pub fn poll(&mut self, now: NaiveDateTime) -> Vec<FocusEvent> {
+        if self.disabled {
+            return vec![];
+        }
+        if let Some(last_poll) = self.last_poll
+            && now - last_poll < self.poll_interval
+        {
+            return vec![];
+        }
+        self.last_poll = Some(now);
+
+        match self.provider.focused_title() {
+            Ok(title) => self.observe(now, &title),
+            Err(err) => {
+                warn!("Disabling focus tracking: {err:?}");
+                self.disabled = true;
+                vec![]
+            }
+        }
+    }
+
+    fn observe(&mut self, now: NaiveDateTime, title: &str) -> Vec<FocusEvent> {
+        let matched = self.rules.match_title(title);
+        let mut events = vec![];
+        match &self.current {
+            Some((current, since)) if *current == matched => {
+                if !self.settled && now - *since >= self.min_duration {
+                    self.settled = true;
+                    events.extend(matched.map(FocusEvent::Settled));
+                }
+                return events;
+            }
+            Some((Some(current), since)) if self.settled => {
+                events.push(FocusEvent::Finished(FocusSuggestion {
+                    start: *since,
+                    end: now,
+                    matched: current.clone(),
+                }));
+            }
+            _ => {}
+        }
+        self.current = Some((matched, now));
+        self.settled = false;
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    struct Titles(Vec<&'static str>);
+
+    impl FocusProvider for Titles {
+        fn focused_title(&mut self) -> Result<String> {
+            Ok(self.0.remove(0).to_string())
+        }
+    }
+
+    fn rules() -> FocusRules {
+        FocusRules::new(&[
+            FocusRule {
+                pattern: r"(SCRUM-\d+)".into(),
+                ticket: Some("$1".into()),
+                description: "Working on $1".into(),
+                ..Default::default()
+            },
+            FocusRule {
+                pattern: "Zoom".into(),
+                project: Some("M".into()),
+                description: "Meeting".into(),
+                ..Default::default()
+            },
+        ])
+        .unwrap()
+    }
+
+    fn at(mins: i64) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 9, 22)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap()
+            + TimeDelta::minutes(mins)
+    }
+
+    #[test]
+    fn test_first_matching_rule_fills_in_groups() {
+        let matched = rules().match_title("SCRUM-17 Zoom - Jira").unwrap();
+        assert_eq!(matched.ticket.as_deref(), Some("SCRUM-17"));
+        assert_eq!(matched.description, "Working on SCRUM-17");
+        assert_eq!(matched.project, None);
+        assert_eq!(rules().match_title("Terminal"), None);
+    }
+
+    #[test]
+    fn test_suggests_rules_that_matched_long_enough() {
+        let titles = Titles(vec![
+            "SCRUM-17 - Jira",
+            "SCRUM-17 - Jira",
+            "Zoom Meeting",
+            "Terminal",
+            "SCRUM-17 - Jira",
+        ]);
+        let mut tracker = FocusTracker::new(Box::new(titles), rules(), TimeDelta::minutes(5));
+
+        assert_eq!(tracker.poll(at(0)), vec![]);
+        let Some(FocusEvent::Settled(scrum)) = tracker.poll(at(6)).pop() else {
+            panic!("expected SCRUM-17 to settle");
+        };
+        assert_eq!(scrum.ticket.as_deref(), Some("SCRUM-17"));
+        assert_eq!(
+            tracker.poll(at(20)),
+            vec![FocusEvent::Finished(FocusSuggestion {
+                start: at(0),
+                end: at(20),
+                matched: scrum,
+            })]
+        );
+        // Zoom never settled, so it is not suggested
+        assert_eq!(tracker.poll(at(22)), vec![]);
+        assert_eq!(tracker.poll(at(23)), vec![]);
+    }
+}
@@ -0,0 +1,71 @@
+use itertools::Itertools;
+
+/// Score of the query as a fuzzy match in the text, higher is better; `None` if the characters
+/// of the query don't all appear in the text in order. Ignores case and rewards runs of matching
+/// characters and matches at the start of words. An empty query matches everything with `0`.
+pub fn score(query: &str, text: &str) -> Option<u32> {
+    let query: Vec<char> = query
+        .to_lowercase()
+        .chars()
+        .filter(|it| !it.is_whitespace())
+        .collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let Some(first) = query.first() else {
+        return Some(0);
+    };
+    // Greedy from every occurrence of the first character, so that `ops` in `Cloud Ops`
+    // matches the word and not the `o` of `Cloud`
+    text.iter()
+        .positions(|it| it == first)
+        .filter_map(|start| score_from(&query, &text, start))
+        .max()
+}
+
+fn score_from(query: &[char], text: &[char], start: usize) -> Option<u32> {
+    let mut score = 0;
+    let mut next = start;
+    let mut previous_match = None;
+    for wanted in query {
+        let found = next + text[next..].iter().position(|it| it == wanted)?;
+        score += 1;
+        if previous_match.is_some_and(|it| it + 1 == found) {
+            score += 4;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous_match = Some(found);
+        next = found + 1;
+    }
+    Some(score)
+}
+
+/// Best score of the query in any of the texts, see [score]
+pub fn best_score<'a>(query: &str, texts: impl IntoIterator<Item = &'a str>) -> Option<u32> {
+    texts.into_iter().filter_map(|it| score(query, it)).max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runs_and_word_starts_score_higher() {
+        assert_eq!(score("", "anything"), Some(0));
+        assert_eq!(score("xyz", "PLATFORM"), None);
+        assert_eq!(score("fp", "PLATFORM"), None);
+
+        let platform = score("plat", "PLATFORM").unwrap();
+        let scattered = score("plat", "People and talent").unwrap();
+        assert!(platform > scattered, "{platform} vs {scattered}");
+
+        let word_start = score("ops", "Cloud Ops").unwrap();
+        let inside = score("ops", "Cyclops").unwrap();
+        assert!(word_start > inside, "{word_start} vs {inside}");
+
+        assert_eq!(
+            best_score("cops", ["CO", "Cloud Ops"]),
+            score("cops", "Cloud Ops")
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{Result, eyre::Context};
+use time::{Date, Duration, Month, format_description::FormatItem, macros::format_description};
+use tracing::{info, warn};
+
+use crate::config::{HolidayConfig, HolidayRegion};
+
+const ICS_DAY: &[FormatItem<'static>] = format_description!("[year][month][day]");
+
+/// Public holidays of the configured region and calendar, which are not expected to be worked
+#[derive(Debug, Clone, Default)]
+pub struct Holidays {
+    region: Option<HolidayRegion>,
+    /// All-day events of the cached calendar
+    listed: BTreeMap<Date, String>,
+}
+
+impl Holidays {
+    /// Holidays of the bundled region and the calendar cached by [fetch], if any
+    pub fn load(config: &HolidayConfig, data_dir: &Path) -> Result<Self> {
+        let mut listed = BTreeMap::new();
+        let path = cache_path(data_dir);
+        if config.ics_url.is_some() && path.exists() {
+            let content = fs::read_to_string(&path)
+                .wrap_err_with(|| format!("reading holiday calendar {}", path.display()))?;
+            listed.extend(parse_ics(&content));
+        }
+        Ok(Self {
+            region: config.region,
+            listed,
+        })
+    }
+
+    /// Name of the holiday on the day, the calendar's if both have one
+    pub fn name_of(&self, day: Date) -> Option<String> {
+        self.listed.get(&day).cloned().or_else(|| {
+            self.region
+                .and_then(|region| region_holidays(region, day.year()).remove(&day))
+                .map(str::to_string)
+        })
+    }
+
+    pub fn between(&self, range: RangeInclusive<Date>) -> Vec<(Date, String)> {
+        let mut holidays: BTreeMap<Date, String> = BTreeMap::new();
+        if let Some(region) = self.region {
+            for year in range.start().year()..=range.end().year() {
+                let of_year = region_holidays(region, year);
+                holidays.extend(
+                    of_year
+                        .into_iter()
+                        .map(|(day, name)| (day, name.to_string())),
+                );
+            }
+        }
+        holidays.extend(self.listed.clone());
+        holidays
+            .into_iter()
+            .filter(|(day, _)| range.contains(day))
+            .collect()
+    }
+}
+
+/// Where the calendar of [crate::config::HolidayConfig::ics_url] is kept between starts
+pub fn cache_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("holidays.ics")
+}
+
+/// Downloads the calendar into the [cache_path], keeping the previous one if that fails
+pub fn fetch(url: &str, data_dir: &Path) -> Result<()> {
+    let content = ureq::get(url)
+        .call()
+        .and_then(|mut it| it.body_mut().read_to_string())
+        .wrap_err_with(|| format!("fetching holiday calendar {url}"))?;
+    let holidays = parse_ics(&content);
+    if holidays.is_empty() {
+        warn!("Holiday calendar {url} has no all-day events, keeping the cached one");
+        return Ok(());
+    }
+    fs::create_dir_all(data_dir).wrap_err("creating data dir")?;
+    let path = cache_path(data_dir);
+    fs::write(&path, content).wrap_err_with(|| format!("writing {}", path.display()))?;
+    info!("Fetched {} holidays from {url}", holidays.len());
+    Ok(())
+}
+
+/// All-day events of an iCalendar file, each day of the ones spanning several days
+fn parse_ics(content: &str) -> Vec<(Date, String)> {
+    // Long lines continue on the next one, which then starts with a space or tab
+    let unfolded = content
+        .replace("\r\n", "\n")
+        .replace("\n ", "")
+        .replace("\n\t", "");
+    let mut holidays = vec![];
+    let mut event: Option<(Option<Date>, Option<Date>, String)> = None;
+    for line in unfolded.lines() {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        // Parameters like `DTSTART;VALUE=DATE` are not needed
+        let name = name.split(';').next().unwrap_or_default();
+        match (name, &mut event) {
+            ("BEGIN", _) if value == "VEVENT" => event = Some((None, None, String::new())),
+            ("DTSTART", Some((start, _, _))) => *start = parse_ics_day(value),
+            ("DTEND", Some((_, end, _))) => *end = parse_ics_day(value),
+            ("SUMMARY", Some((_, _, summary))) => *summary = unescape_ics(value),
+            ("END", Some(_)) if value == "VEVENT" => {
+                let Some((Some(start), end, summary)) = event.take() else {
+                    continue;
+                };
+                // The end is exclusive, all-day events without one last a day
+                let end = end
+                    .filter(|it| *it > start)
+                    .unwrap_or(start + Duration::DAY);
+                let days = (end - start).whole_days();
+                holidays.extend((0..days).map(|it| (start + Duration::days(it), summary.clone())));
+            }
+            _ => {}
+        }
+    }
+    holidays
+}
+
+/// Only dates without time are all-day events
+fn parse_ics_day(value: &str) -> Option<Date> {
+    Date::parse(value, ICS_DAY).ok()
+}
+
+fn unescape_ics(value: &str) -> String {
+    value
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+        .trim()
+        .to_string()
+}
+
+/// Nationwide public holidays, the ones of single states are left to a calendar
+fn region_holidays(region: HolidayRegion, year: i32) -> BTreeMap<Date, &'static str> {
+    let fixed = |month: Month, day: u8| Date::from_calendar_date(year, month, day).ok();
+    let easter = easter_sunday(year);
+    let after_easter = |days: i64| easter.map(|it| it + Duration::days(days));
+    let holidays = match region {
+        HolidayRegion::At => vec![
+            (fixed(Month::January, 1), "New Year's Day"),
+            (fixed(Month::January, 6), "Epiphany"),
+            (after_easter(1), "Easter Monday"),
+            (fixed(Month::May, 1), "Labour Day"),
+            (after_easter(39), "Ascension Day"),
+            (after_easter(50), "Whit Monday"),
+            (after_easter(60), "Corpus Christi"),
+            (fixed(Month::August, 15), "Assumption Day"),
+            (fixed(Month::October, 26), "National Day"),
+            (fixed(Month::November, 1), "All Saints' Day"),
+            (fixed(Month::December, 8), "Immaculate Conception"),
+            (fixed(Month::December, 25), "Christmas Day"),
+            (fixed(Month::December, 26), "St. Stephen's Day"),
+        ],
+        HolidayRegion::De => vec![
+            (fixed(Month::January, 1), "New Year's Day"),
+            (after_easter(-2), "Good Friday"),
+            (after_easter(1), "Easter Monday"),
+            (fixed(Month::May, 1), "Labour Day"),
+            (after_easter(39), "Ascension Day"),
+            (after_easter(50), "Whit Monday"),
+            (fixed(Month::October, 3), "German Unity Day"),
+            (fixed(Month::December, 25), "Christmas Day"),
+            (fixed(Month::December, 26), "Boxing Day"),
+        ],
+    };
+    holidays
+        .into_iter()
+        .filter_map(|(day, name)| Some((day?, name)))
+        .collect()
+}
+
+/// Gregorian Easter Sunday, see <https://en.wikipedia.org/wiki/Date_of_Easter#Anonymous_Gregorian_algorithm>
+fn easter_sunday(year: i32) -> Option<Date> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = Month::try_from(((h + l - 7 * m + 114) / 31) as u8).ok()?;
+    let day = ((h + l - 7 * m + 114) % 31 + 1) as u8;
+    Date::from_calendar_date(year, month, day).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use time::macros::date;
+
+    use super::*;
+
+    #[test]
+    fn test_region_holidays_follow_easter() {
+        assert_eq!(easter_sunday(2025), Some(date!(2025 - 04 - 20)));
+        assert_eq!(easter_sunday(2026), Some(date!(2026 - 04 - 05)));
+
+        let holidays = Holidays {
+            region: Some(HolidayRegion::At),
+            listed: BTreeMap::new(),
+        };
+        assert_eq!(
+            holidays.name_of(date!(2026 - 05 - 14)).as_deref(),
+            Some("Ascension Day")
+        );
+        assert_eq!(
+            holidays.name_of(date!(2026 - 10 - 26)).as_deref(),
+            Some("National Day")
+        );
+        assert_eq!(holidays.name_of(date!(2026 - 10 - 27)), None);
+        assert_eq!(
+            holidays
+                .between(date!(2025 - 12 - 24)..=date!(2026 - 01 - 06))
+                .into_iter()
+                .map(|(day, _)| day)
+                .collect_vec(),
+            [
+                date!(2025 - 12 - 25),
+                date!(2025 - 12 - 26),
+                date!(2026 - 01 - 01),
+                date!(2026 - 01 - 06),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_calendar_all_day_events_override_region() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART;VALUE=DATE:20261231\r\n\
+            DTEND;VALUE=DATE:20270102\r\n\
+            SUMMARY:Company\\, closed over\r\n  New Year\r\n\
+            END:VEVENT\r\n\
+            BEGIN:VEVENT\r\n\
+            DTSTART:20261015T090000Z\r\n\
+            SUMMARY:Meeting\r\n\
+            END:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let holidays = Holidays {
+            region: Some(HolidayRegion::De),
+            listed: parse_ics(ics).into_iter().collect(),
+        };
+
+        assert_eq!(
+            holidays.between(date!(2026 - 10 - 01)..=date!(2027 - 01 - 05)),
+            [
+                (date!(2026 - 10 - 03), "German Unity Day".to_string()),
+                (date!(2026 - 12 - 25), "Christmas Day".to_string()),
+                (date!(2026 - 12 - 26), "Boxing Day".to_string()),
+                (
+                    date!(2026 - 12 - 31),
+                    "Company, closed over New Year".to_string()
+                ),
+                (
+                    date!(2027 - 01 - 01),
+                    "Company, closed over New Year".to_string()
+                ),
+            ]
+        );
+        assert_eq!(holidays.name_of(date!(2026 - 10 - 15)), None);
+    }
+}
@@ -0,0 +1,224 @@
+use std::{
+    process::Command,
+    sync::mpsc::{self, Receiver, TryRecvError},
+    time::Duration,
+};
+
+use chrono::{NaiveDateTime, TimeDelta};
+use color_eyre::{
+    Result,
+    eyre::{Context, bail, eyre},
+};
+use tracing::warn;
+
+use crate::config::IdleConfig;
+
+/// Source of the time since the last user input on this machine, measured without blocking
+/// the caller.
+pub trait IdleProvider {
+    /// Starts measuring, unless a measurement is still running
+    fn start(&mut self);
+    /// Result of the started measurement once it's done
+    fn finished(&mut self) -> Option<Result<Duration>>;
+}
+
+/// Runs a command that prints the idle time in milliseconds, e.g. `xprintidle` on X11.
+/// On Wayland, compositor-specific tools with the same output can be configured.
+/// It runs on a thread of its own, so that a slow command doesn't hold up the UI.
+pub struct CommandIdleProvider {
+    command: String,
+    running: Option<Receiver<Result<Duration>>>,
+}
+
+impl CommandIdleProvider {
+    pub fn new(command: String) -> Self {
+        Self {
+            command,
+            running: None,
+        }
+    }
+}
+
+impl IdleProvider for CommandIdleProvider {
+    fn start(&mut self) {
+        if self.running.is_some() {
+            return;
+        }
+        let (tx, rx) = mpsc::channel();
+        let command = self.command.clone();
+        std::thread::spawn(move || {
+            let _ = tx.send(run_idle_command(&command));
+        });
+        self.running = Some(rx);
+    }
+
+    fn finished(&mut self) -> Option<Result<Duration>> {
+        let result = match self.running.as_ref()?.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => Err(eyre!("idle command {} vanished", self.command)),
+        };
+        self.running = None;
+        Some(result)
+    }
+}
+
+fn run_idle_command(command: &str) -> Result<Duration> {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .output()
+        .wrap_err_with(|| format!("running idle command {command}"))?;
+    if !output.status.success() {
+        bail!("idle command {command} failed: {}", output.status);
+    }
+    let millis: u64 = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .wrap_err("idle command output must be milliseconds")?;
+    Ok(Duration::from_millis(millis))
+}
+
+/// A span of time without user input that has ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdleSpan {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+}
+
+impl IdleSpan {
+    pub fn duration(&self) -> TimeDelta {
+        self.end - self.start
+    }
+}
+
+/// Polls an [IdleProvider] and reports idle spans longer than the threshold once the user is back.
+pub struct IdleTracker {
+    provider: Box<dyn IdleProvider>,
+    threshold: Duration,
+    poll_interval: TimeDelta,
+    last_poll: Option<NaiveDateTime>,
+    idle_since: Option<NaiveDateTime>,
+    disabled: bool,
+}
+
+impl IdleTracker {
+    pub fn from_config(config: &IdleConfig) -> Option<Self> {
+        let threshold_mins = config.pause_after_mins?;
+        let provider = CommandIdleProvider::new(config.command.clone());
+        Some(Self::new(
+            Box::new(provider),
+            Duration::from_secs(threshold_mins as u64 * 60),
+        ))
+    }
+
+    pub fn new(provider: Box<dyn IdleProvider>, threshold: Duration) -> Self {
+        Self {
+            provider,
+            threshold,
+            poll_interval: TimeDelta::seconds(15),
+            last_poll: None,
+            idle_since: None,
+            disabled: false,
+        }
+    }
+
+    /// Call regularly, e.g. on tick. Returns the idle span once the user returns from it.
+    /// Measurements are started every poll interval and picked up by a later call.
+    pub fn poll(&mut self, now: NaiveDateTime) -> Option<IdleSpan> {
+        if self.disabled {
+            return None;
+        }
+        if self
+            .last_poll
+            .is_none_or(|last_poll| now - last_poll >= self.poll_interval)
+        {
+            self.last_poll = Some(now);
+            self.provider.start();
+        }
+
+        let idle_time = match self.provider.finished()? {
+            Ok(idle_time) => idle_time,
+            Err(err) => {
+                warn!("Disabling idle detection: {err:?}");
+                self.disabled = true;
+                return None;
+            }
+        };
+        let idle_delta = TimeDelta::from_std(idle_time).unwrap_or(TimeDelta::MAX);
+
+        if idle_time >= self.threshold {
+            // Keep the first observation, later ones might have been interrupted by brief input
+            self.idle_since.get_or_insert(now - idle_delta);
+            None
+        } else {
+            let start = self.idle_since.take()?;
+            let end = now - idle_delta;
+            Some(IdleSpan { start, end })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    /// Finishes each measurement right away
+    struct FakeProvider(Rc<RefCell<Duration>>, bool);
+
+    impl IdleProvider for FakeProvider {
+        fn start(&mut self) {
+            self.1 = true;
+        }
+
+        fn finished(&mut self) -> Option<Result<Duration>> {
+            std::mem::take(&mut self.1).then(|| Ok(*self.0.borrow()))
+        }
+    }
+
+    fn at(hour: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 9, 22)
+            .unwrap()
+            .and_hms_opt(hour, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reports_span_after_return() {
+        let idle = Rc::new(RefCell::new(Duration::ZERO));
+        let provider = FakeProvider(idle.clone(), false);
+        let mut tracker = IdleTracker::new(Box::new(provider), Duration::from_secs(10 * 60));
+
+        assert_eq!(tracker.poll(at(10, 0)), None);
+
+        *idle.borrow_mut() = Duration::from_secs(12 * 60);
+        assert_eq!(tracker.poll(at(10, 12)), None);
+        assert!(tracker.idle_since.is_some());
+
+        *idle.borrow_mut() = Duration::from_secs(60);
+        let span = tracker.poll(at(10, 45)).expect("span after return");
+        assert_eq!(span.start, at(10, 0));
+        assert_eq!(span.end, at(10, 44));
+        assert_eq!(span.duration(), TimeDelta::minutes(44));
+        assert!(tracker.idle_since.is_none());
+    }
+
+    #[test]
+    fn test_short_idle_is_ignored_and_polls_are_throttled() {
+        let idle = Rc::new(RefCell::new(Duration::from_secs(5 * 60)));
+        let provider = FakeProvider(idle.clone(), false);
+        let mut tracker = IdleTracker::new(Box::new(provider), Duration::from_secs(10 * 60));
+
+        assert_eq!(tracker.poll(at(10, 0)), None);
+        assert!(tracker.idle_since.is_none());
+
+        *idle.borrow_mut() = Duration::from_secs(20 * 60);
+        let just_after = at(10, 0) + TimeDelta::seconds(5);
+        assert_eq!(tracker.poll(just_after), None);
+        assert!(tracker.idle_since.is_none(), "polled within interval");
+    }
+}
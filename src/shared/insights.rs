@@ -0,0 +1,143 @@
+use std::str::FromStr;
+
+use itertools::Itertools;
+use strum::{Display, EnumString};
+
+use crate::{
+    config::Config,
+    persist::{TimeEntry, UsageMetric},
+    shared::summary::TimesheetSummary,
+};
+
+/// Feature whose uses are counted, stored by its snake case name
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum Feature {
+    Edit,
+    Split,
+    Merge,
+    Export,
+    MonthExport,
+    JiraExport,
+    CopyBookings,
+    Template,
+}
+
+impl Feature {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Feature::Edit => "Edits",
+            Feature::Split => "Splits",
+            Feature::Merge => "Merges",
+            Feature::Export => "Exports",
+            Feature::MonthExport => "Month exports",
+            Feature::JiraExport => "Jira exports",
+            Feature::CopyBookings => "Copied bookings",
+            Feature::Template => "Inserted templates",
+        }
+    }
+}
+
+/// Averages over the days of a range, from the usage metrics and time entries
+#[derive(Debug, PartialEq)]
+pub struct Insights {
+    /// Days on which any feature was used
+    pub active_days: usize,
+    /// Label and average uses per active day, most used first
+    pub uses_per_day: Vec<(String, f64)>,
+    /// Average changes of project or ticket per day with work, `None` without any
+    pub switches_per_day: Option<f64>,
+}
+
+impl Insights {
+    pub fn calculate(metrics: &[UsageMetric], entries: Vec<TimeEntry>, config: &Config) -> Self {
+        let active_days = metrics.iter().map(|it| &it.day).unique().count();
+        let uses_per_day = metrics
+            .iter()
+            .into_group_map_by(|it| it.feature.as_str())
+            .into_iter()
+            .map(|(feature, metrics)| {
+                let label = Feature::from_str(feature)
+                    .map(|it| it.label().to_string())
+                    .unwrap_or_else(|_| feature.to_string());
+                let uses: i32 = metrics.iter().map(|it| it.uses).sum();
+                (label, uses as f64 / active_days as f64)
+            })
+            .sorted_by(|(a_label, a), (b_label, b)| b.total_cmp(a).then(a_label.cmp(b_label)))
+            .collect();
+
+        let switches = entries
+            .into_iter()
+            .into_group_map_by(|it| it.timesheet_day.clone())
+            .into_values()
+            .map(|entries| TimesheetSummary::new(entries, config).stats())
+            .filter(|stats| stats.entries > 0)
+            .map(|stats| stats.switches)
+            .collect_vec();
+        let switches_per_day = (!switches.is_empty())
+            .then(|| switches.iter().sum::<usize>() as f64 / switches.len() as f64);
+
+        Self {
+            active_days,
+            uses_per_day,
+            switches_per_day,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metric(day: &str, feature: Feature, uses: i32) -> UsageMetric {
+        UsageMetric {
+            day: day.to_string(),
+            feature: feature.to_string(),
+            uses,
+        }
+    }
+
+    fn entry(day: &str, start_time: &str, ticket: &str) -> TimeEntry {
+        TimeEntry {
+            id: format!("{day}-{start_time}"),
+            timesheet_day: day.to_string(),
+            ticket_key: Some(ticket.to_string()),
+            ..TimeEntry::sample(start_time, 30)
+        }
+    }
+
+    #[test]
+    fn test_averages_over_active_days() {
+        let metrics = vec![
+            metric("2025-09-22", Feature::Edit, 10),
+            metric("2025-09-22", Feature::Export, 1),
+            metric("2025-09-23", Feature::Edit, 20),
+        ];
+        let entries = vec![
+            entry("2025-09-22", "09:00", "PLAT-1"),
+            entry("2025-09-22", "09:30", "PLAT-2"),
+            entry("2025-09-22", "10:00", "PLAT-1"),
+            entry("2025-09-23", "09:00", "PLAT-1"),
+        ];
+
+        let insights = Insights::calculate(&metrics, entries, &Config::default());
+
+        assert_eq!(
+            insights,
+            Insights {
+                active_days: 2,
+                uses_per_day: vec![("Edits".to_string(), 15.0), ("Exports".to_string(), 0.5)],
+                switches_per_day: Some(1.0),
+            }
+        );
+    }
+
+    #[test]
+    fn test_nothing_recorded() {
+        let insights = Insights::calculate(&[], vec![], &Config::default());
+
+        assert_eq!(insights.active_days, 0);
+        assert!(insights.uses_per_day.is_empty());
+        assert_eq!(insights.switches_per_day, None);
+    }
+}
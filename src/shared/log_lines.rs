@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, FixedOffset};
+use regex::Regex;
+use tracing::Level;
+
+/// Line of the log file. Continuation lines, e.g. of backtraces, take the level of their event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogLine {
+    pub level: Level,
+    pub text: String,
+}
+
+impl LogLine {
+    /// Whether the line is at most as verbose as `max_level` and contains the query,
+    /// ignoring case
+    pub fn is_shown(&self, max_level: Level, query: &str) -> bool {
+        self.level <= max_level
+            && (query.is_empty() || self.text.to_lowercase().contains(&query.to_lowercase()))
+    }
+}
+
+/// Splits complete lines of the log into [LogLine]s, `previous` is the level of the line
+/// before them
+pub fn parse_lines(content: &str, mut previous: Level) -> Vec<LogLine> {
+    content
+        .lines()
+        .map(|line| {
+            if let Some(level) = level_of(line) {
+                previous = level;
+            }
+            LogLine {
+                level: previous,
+                text: line.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// Level of lines like `2025-09-22T08:40:00.123Z  INFO src/app.rs:85: message`
+fn level_of(line: &str) -> Option<Level> {
+    let mut words = line.split_whitespace();
+    let timestamp = words.next()?;
+    if !timestamp.starts_with(|it: char| it.is_ascii_digit()) || !timestamp.contains('T') {
+        return None;
+    }
+    match words.next()? {
+        "ERROR" => Some(Level::ERROR),
+        "WARN" => Some(Level::WARN),
+        "INFO" => Some(Level::INFO),
+        "DEBUG" => Some(Level::DEBUG),
+        "TRACE" => Some(Level::TRACE),
+        _ => None,
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref ISO_DAY: Regex = Regex::new(r"\b\d{4}-\d{2}-\d{2}\b").expect("valid regex");
+}
+
+/// Copy of a log that tells nothing about when it was written: timestamps become the seconds
+/// since the first one, and days are numbered in the order they first appear, e.g. `day-1`
+pub fn anonymize(content: &str) -> String {
+    let mut first: Option<DateTime<FixedOffset>> = None;
+    let mut days: HashMap<String, usize> = HashMap::new();
+    content
+        .lines()
+        .map(|line| {
+            let (timestamp, rest) = line.split_once(' ').unwrap_or((line, ""));
+            let line = match DateTime::parse_from_rfc3339(timestamp) {
+                Ok(time) => {
+                    let since = time - *first.get_or_insert(time);
+                    let secs = since.num_milliseconds() as f64 / 1000.0;
+                    format!("+{secs:.3}s {rest}")
+                }
+                Err(_) => line.to_string(),
+            };
+            ISO_DAY
+                .replace_all(&line, |caps: &regex::Captures| {
+                    let next = days.len() + 1;
+                    format!("day-{}", days.entry(caps[0].to_string()).or_insert(next))
+                })
+                .into_owned()
+        })
+        .map(|line| line + "\n")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_continuation_lines_take_level_of_event() {
+        let content = "\
+2025-09-22T08:40:00.123456Z  INFO src/app.rs:85: Started
+2025-09-22T08:40:01.000000Z ERROR src/persist.rs:12: Failed to save
+   0: sheet_shark::persist::store_entry
+2025-09-22T08:40:02.000000Z DEBUG src/app.rs:230: Tick";
+        let lines = parse_lines(content, Level::INFO);
+
+        let levels: Vec<_> = lines.iter().map(|it| it.level).collect();
+        assert_eq!(
+            levels,
+            vec![Level::INFO, Level::ERROR, Level::ERROR, Level::DEBUG]
+        );
+        assert_eq!(
+            parse_lines("   1: continued", Level::WARN)[0].level,
+            Level::WARN
+        );
+
+        let shown = |max_level, query| {
+            lines
+                .iter()
+                .filter(|it| it.is_shown(max_level, query))
+                .count()
+        };
+        assert_eq!(shown(Level::TRACE, ""), 4);
+        assert_eq!(shown(Level::INFO, ""), 3);
+        assert_eq!(shown(Level::WARN, ""), 2);
+        assert_eq!(shown(Level::TRACE, "failed"), 1);
+    }
+
+    #[test]
+    fn test_anonymize_makes_times_relative_and_numbers_days() {
+        let content = "\
+2026-10-15T08:40:00.100000Z DEBUG source=\"home\" action=\"EnterSelect\" day=2026-10-15
+2026-10-15T08:40:02.350000Z DEBUG source=\"app\" action=\"SetActivePage\"
+   at 2026-10-14 and 2026-10-15
+2026-10-15T08:41:00.100000Z DEBUG source=\"home\" action=\"ExitEdit\" day=2026-10-14";
+
+        assert_eq!(
+            anonymize(content),
+            "\
++0.000s DEBUG source=\"home\" action=\"EnterSelect\" day=day-1
++2.250s DEBUG source=\"app\" action=\"SetActivePage\"
+   at day-2 and day-1
++60.000s DEBUG source=\"home\" action=\"ExitEdit\" day=day-2
+"
+        );
+    }
+}
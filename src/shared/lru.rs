@@ -0,0 +1,73 @@
+use std::collections::VecDeque;
+
+/// Map that forgets the least recently used key once full.
+/// Linear lookups, so only meant for the handful of items a UI keeps around.
+pub struct LruCache<K, V> {
+    capacity: usize,
+    /// Most recently used first
+    items: VecDeque<(K, V)>,
+}
+
+impl<K: PartialEq, V> LruCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            items: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Marks the key as most recently used if present
+    pub fn get(&mut self, key: &K) -> Option<&V> {
+        let idx = self.items.iter().position(|(it, _)| it == key)?;
+        let item = self.items.remove(idx).expect("index just found");
+        self.items.push_front(item);
+        self.items.front().map(|(_, value)| value)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        self.remove(&key);
+        if self.items.len() >= self.capacity {
+            self.items.pop_back();
+        }
+        self.items.push_front((key, value));
+    }
+
+    pub fn remove(&mut self, key: &K) {
+        self.items.retain(|(it, _)| it != key);
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        cache.insert(1, "one");
+        cache.insert(2, "two");
+        assert_eq!(cache.get(&1), Some(&"one"));
+
+        cache.insert(3, "three");
+        assert_eq!(cache.get(&2), None, "2 was used least recently");
+        assert_eq!(cache.get(&1), Some(&"one"));
+        assert_eq!(cache.get(&3), Some(&"three"));
+    }
+
+    #[test]
+    fn test_insert_replaces_existing_key() {
+        let mut cache = LruCache::new(2);
+        cache.insert(1, "one");
+        cache.insert(1, "uno");
+        cache.insert(2, "two");
+        assert_eq!(cache.get(&1), Some(&"uno"));
+        assert_eq!(cache.get(&2), Some(&"two"));
+
+        cache.remove(&1);
+        assert_eq!(cache.get(&1), None);
+    }
+}
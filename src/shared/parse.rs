@@ -0,0 +1,164 @@
+use chrono::NaiveTime;
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+
+lazy_static! {
+    static ref LEADING_TICKET: Regex =
+        Regex::new(r"^([A-Z][A-Z0-9]*-\d+)[\s:]*(.*)$").expect("valid ticket regex");
+    /// `09:00-09:30`, `9.00 – 9.30`, `9:00 AM to 9:30 AM` and the like
+    static ref TIME_RANGE: Regex = Regex::new(
+        r"(?i)\b(\d{1,2})[:.](\d{2})\s*([ap])?\.?m?\.?\s*(?:-|–|—|to|bis)\s*(\d{1,2})[:.](\d{2})\s*([ap])?\.?m?\.?"
+    )
+    .expect("valid time range regex");
+}
+
+/// Splits a leading ticket like `SCRUM-17: ` off the description
+pub fn split_ticket(description: &str) -> (Option<String>, String) {
+    let description = description.trim();
+    match LEADING_TICKET.captures(description) {
+        Some(captures) => (Some(captures[1].to_string()), captures[2].to_string()),
+        None => (None, description.to_string()),
+    }
+}
+
+/// Entry found in text pasted from elsewhere, see [pasted_entries]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PastedEntry {
+    pub start: NaiveTime,
+    pub duration_mins: u32,
+    pub ticket_key: Option<String>,
+    pub description: String,
+}
+
+/// Entries in lines like `09:00-09:30 SCRUM-12 standup`, by start time. Agendas pasted from
+/// e.g. Outlook have the subject on the line after the times, which is used if the line
+/// with the times has no text of its own. Lines without times are otherwise ignored.
+pub fn pasted_entries(text: &str) -> Vec<PastedEntry> {
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    let mut entries = vec![];
+    for (idx, line) in lines.iter().enumerate() {
+        let Some(captures) = TIME_RANGE.captures(line) else {
+            continue;
+        };
+        let Some((start, end)) = parse_range(&captures) else {
+            continue;
+        };
+        let range = captures.get(0).expect("whole match");
+        let rest = format!("{} {}", &line[..range.start()], &line[range.end()..]);
+        let mut text = trim_separators(&rest).to_string();
+        if text.is_empty() {
+            text = lines[idx + 1..]
+                .iter()
+                .take_while(|it| !TIME_RANGE.is_match(it))
+                .find(|it| !it.is_empty())
+                .map(|it| trim_separators(it).to_string())
+                .unwrap_or_default();
+        }
+        let (ticket_key, description) = split_ticket(&text);
+        entries.push(PastedEntry {
+            start,
+            duration_mins: (end - start).num_minutes() as u32,
+            ticket_key,
+            description,
+        });
+    }
+    entries.sort_by_key(|it| it.start);
+    entries
+}
+
+/// Start and end of the range, `None` if it isn't a valid time or ends before it starts.
+/// A start without AM/PM takes the one of the end, if that doesn't put it after the end.
+fn parse_range(captures: &Captures) -> Option<(NaiveTime, NaiveTime)> {
+    let part = |idx: usize| captures.get(idx).map(|it| it.as_str());
+    let end_half = part(6);
+    let end = to_time(part(4)?, part(5)?, end_half)?;
+    let start = match part(3) {
+        Some(half) => to_time(part(1)?, part(2)?, Some(half))?,
+        None => to_time(part(1)?, part(2)?, end_half)
+            .filter(|it| *it <= end)
+            .or_else(|| to_time(part(1)?, part(2)?, None))?,
+    };
+    (start < end).then_some((start, end))
+}
+
+/// `half` is `a` or `p` for 12-hour times
+fn to_time(hour: &str, minute: &str, half: Option<&str>) -> Option<NaiveTime> {
+    let mut hour: u32 = hour.parse().ok()?;
+    let minute: u32 = minute.parse().ok()?;
+    match half.map(str::to_lowercase).as_deref() {
+        Some("a") if hour == 12 => hour = 0,
+        Some("p") if hour < 12 => hour += 12,
+        _ => {}
+    }
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+fn trim_separators(text: &str) -> &str {
+    text.trim_matches(|it: char| it.is_whitespace() || matches!(it, ':' | '|' | '-' | '–' | ','))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_pasted_lines_with_ranges() {
+        let text = "\
+10:00 - 11:15 | PLAT-3: Code review
+09:00-09:30 SCRUM-12 standup
+Lunch is not an entry
+11:30 – 1:00 PM Customer call
+17:00-16:00 ends before it starts";
+
+        assert_eq!(
+            pasted_entries(text),
+            vec![
+                PastedEntry {
+                    start: time(9, 0),
+                    duration_mins: 30,
+                    ticket_key: Some("SCRUM-12".into()),
+                    description: "standup".into(),
+                },
+                PastedEntry {
+                    start: time(10, 0),
+                    duration_mins: 75,
+                    ticket_key: Some("PLAT-3".into()),
+                    description: "Code review".into(),
+                },
+                PastedEntry {
+                    start: time(11, 30),
+                    duration_mins: 90,
+                    ticket_key: None,
+                    description: "Customer call".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pasted_agenda_with_subject_below_times() {
+        let text = "\
+Tuesday, September 23, 2025
+
+9:00 AM - 9:15 AM
+
+Daily Standup
+Microsoft Teams Meeting
+2:00 PM to 3:00 PM
+Sprint Review";
+
+        let entries = pasted_entries(text);
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].start, time(9, 0));
+        assert_eq!(entries[0].duration_mins, 15);
+        assert_eq!(entries[0].description, "Daily Standup");
+        assert_eq!(entries[1].start, time(14, 0));
+        assert_eq!(entries[1].duration_mins, 60);
+        assert_eq!(entries[1].description, "Sprint Review");
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{persist::TimeEntry, shared::BREAK_PROJECT_KEY};
+
+/// Narrows down loaded entries, e.g. from [crate::persist::Command::LoadEntriesInRange].
+/// Empty lists match everything, breaks and empty entries are left out unless asked for.
+#[derive(Debug, Default, Clone)]
+pub struct EntryFilter {
+    pub projects: Vec<String>,
+    pub tickets: Vec<String>,
+    pub billable: Option<bool>,
+    pub include_breaks: bool,
+}
+
+impl EntryFilter {
+    pub fn matches(&self, entry: &TimeEntry) -> bool {
+        if entry.duration_mins <= 0 {
+            return false;
+        }
+        if entry.project_key == BREAK_PROJECT_KEY {
+            return self.include_breaks;
+        }
+        let ticket = entry.ticket_key.as_deref().unwrap_or_default();
+        (self.projects.is_empty() || self.projects.contains(&entry.project_key))
+            && (self.tickets.is_empty() || self.tickets.iter().any(|it| it == ticket))
+            && self.billable.is_none_or(|it| it == entry.billable)
+    }
+
+    pub fn apply(&self, entries: Vec<TimeEntry>) -> Vec<TimeEntry> {
+        entries
+            .into_iter()
+            .filter(|entry| self.matches(entry))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    Day,
+    Project,
+    Ticket,
+    WorkType,
+}
+
+/// Working time of all entries sharing a day, project, ticket or work type
+#[derive(Debug, Serialize, PartialEq, Eq)]
+pub struct Aggregate {
+    /// Day as `YYYY-MM-DD`, project key, ticket or work type key, `-` for entries without one
+    pub key: String,
+    pub entries: usize,
+    pub duration_mins: i64,
+    pub billable_mins: i64,
+}
+
+/// Sums up the entries by key, in order of the keys
+pub fn aggregate(entries: &[TimeEntry], group_by: GroupBy) -> Vec<Aggregate> {
+    let mut groups: BTreeMap<&str, Aggregate> = BTreeMap::new();
+    for entry in entries {
+        let key = match group_by {
+            GroupBy::Day => entry.timesheet_day.as_str(),
+            GroupBy::Project => entry.project_key.as_str(),
+            GroupBy::Ticket => entry.ticket_key.as_deref().unwrap_or("-"),
+            GroupBy::WorkType => entry.work_type.as_deref().unwrap_or("-"),
+        };
+        let group = groups.entry(key).or_insert_with(|| Aggregate {
+            key: key.to_string(),
+            entries: 0,
+            duration_mins: 0,
+            billable_mins: 0,
+        });
+        let duration_mins = entry.duration_mins as i64;
+        group.entries += 1;
+        group.duration_mins += duration_mins;
+        if entry.billable {
+            group.billable_mins += duration_mins;
+        }
+    }
+    groups.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(
+        day: &str,
+        project: &str,
+        ticket: Option<&str>,
+        mins: i32,
+        billable: bool,
+    ) -> TimeEntry {
+        TimeEntry {
+            id: format!("{day}-{project}-{mins}"),
+            timesheet_day: day.to_string(),
+            billable,
+            project_key: project.to_string(),
+            ticket_key: ticket.map(str::to_string),
+            ..TimeEntry::sample("09:00", mins)
+        }
+    }
+
+    fn entries() -> Vec<TimeEntry> {
+        vec![
+            entry("2025-09-01", "W", Some("PLAT-1"), 60, true),
+            entry("2025-09-01", BREAK_PROJECT_KEY, None, 30, false),
+            entry("2025-09-01", "E", None, 45, false),
+            entry("2025-09-02", "W", Some("PLAT-2"), 90, false),
+            entry("2025-09-02", "W", Some("PLAT-1"), 0, true),
+        ]
+    }
+
+    #[test]
+    fn test_filter_by_project_and_billable() {
+        let only_w = EntryFilter {
+            projects: vec!["W".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(only_w.apply(entries()).len(), 2);
+
+        let billable = EntryFilter {
+            billable: Some(true),
+            ..Default::default()
+        };
+        let matching = billable.apply(entries());
+        assert_eq!(matching.len(), 1);
+        assert_eq!(matching[0].ticket_key.as_deref(), Some("PLAT-1"));
+    }
+
+    #[test]
+    fn test_breaks_only_if_asked_for() {
+        assert_eq!(EntryFilter::default().apply(entries()).len(), 3);
+        let with_breaks = EntryFilter {
+            include_breaks: true,
+            ..Default::default()
+        };
+        assert_eq!(with_breaks.apply(entries()).len(), 4);
+    }
+
+    #[test]
+    fn test_aggregate_by_ticket() {
+        let entries = EntryFilter::default().apply(entries());
+
+        assert_eq!(
+            aggregate(&entries, GroupBy::Ticket),
+            vec![
+                Aggregate {
+                    key: "-".to_string(),
+                    entries: 1,
+                    duration_mins: 45,
+                    billable_mins: 0,
+                },
+                Aggregate {
+                    key: "PLAT-1".to_string(),
+                    entries: 1,
+                    duration_mins: 60,
+                    billable_mins: 60,
+                },
+                Aggregate {
+                    key: "PLAT-2".to_string(),
+                    entries: 1,
+                    duration_mins: 90,
+                    billable_mins: 0,
+                },
+            ]
+        );
+        let by_day = aggregate(&entries, GroupBy::Day);
+        assert_eq!(by_day[0].duration_mins, 105);
+        assert_eq!(by_day[1].duration_mins, 90);
+    }
+}
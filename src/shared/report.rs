@@ -0,0 +1,53 @@
+use color_eyre::{Result, eyre::bail};
+
+/// Trims the query of a report and makes sure that it only reads, i.e. that it is a single
+/// `SELECT` or `WITH` statement. Semicolons are only allowed at the very end, even in
+/// string literals, as statements are not parsed any further.
+pub fn validate_select(sql: &str) -> Result<&str> {
+    let sql = sql.trim().trim_end_matches(';').trim_end();
+    let keyword = sql
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    if keyword != "select" && keyword != "with" {
+        bail!("Reports must start with SELECT or WITH, not {keyword:?}");
+    }
+    if sql.contains(';') {
+        bail!("Reports must be a single statement without semicolons");
+    }
+    Ok(sql)
+}
+
+/// Quotes a column name for use in SQL, e.g. `sum(a)` to `"sum(a)"`
+pub fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accepts_select_and_with() {
+        assert_eq!(
+            validate_select("  select * from time_entry;\n").unwrap(),
+            "select * from time_entry"
+        );
+        assert!(validate_select("WITH x AS (SELECT 1) SELECT * FROM x").is_ok());
+    }
+
+    #[test]
+    fn test_rejects_writes_and_multiple_statements() {
+        assert!(validate_select("DELETE FROM time_entry").is_err());
+        assert!(validate_select("").is_err());
+        assert!(validate_select("SELECT 1; DROP TABLE time_entry").is_err());
+        assert!(validate_select("PRAGMA query_only = 0").is_err());
+    }
+
+    #[test]
+    fn test_quotes_identifiers() {
+        assert_eq!(quote_identifier("sum(a)"), "\"sum(a)\"");
+        assert_eq!(quote_identifier("we\"ird"), "\"we\"\"ird\"");
+    }
+}
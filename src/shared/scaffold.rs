@@ -0,0 +1,169 @@
+use chrono::NaiveTime;
+use color_eyre::{
+    Result,
+    eyre::{Context, eyre},
+};
+use time::Weekday;
+
+use crate::{
+    config::{Config, ScaffoldEntry},
+    persist::EntryTemplate,
+    shared::time_expr,
+};
+
+/// Entry of a scaffold with the template filled in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScaffoldItem {
+    pub start: NaiveTime,
+    pub duration_mins: u32,
+    pub project_key: Option<String>,
+    pub ticket_key: Option<String>,
+    pub description: String,
+    pub billable: bool,
+}
+
+/// Configured entries for the weekday, empty if there are none
+pub fn entries_for(config: &Config, weekday: Weekday) -> &[ScaffoldEntry] {
+    let name = weekday.to_string().to_lowercase();
+    config
+        .scaffold
+        .days
+        .get(&name)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// Whether the templates need to be loaded to resolve the entries
+pub fn needs_templates(entries: &[ScaffoldEntry]) -> bool {
+    entries.iter().any(|it| it.template.is_some())
+}
+
+/// Fills in the referenced templates, ordered by start time
+pub fn resolve(
+    entries: &[ScaffoldEntry],
+    templates: &[EntryTemplate],
+    config: &Config,
+) -> Result<Vec<ScaffoldItem>> {
+    let mut items = entries
+        .iter()
+        .map(|entry| resolve_entry(entry, templates, config))
+        .collect::<Result<Vec<_>>>()?;
+    items.sort_by_key(|it| it.start);
+    Ok(items)
+}
+
+fn resolve_entry(
+    entry: &ScaffoldEntry,
+    templates: &[EntryTemplate],
+    config: &Config,
+) -> Result<ScaffoldItem> {
+    let start = NaiveTime::parse_from_str(&entry.start, "%H:%M")
+        .wrap_err_with(|| format!("Scaffold start {:?} is not HH:MM", entry.start))?;
+    let template = match &entry.template {
+        Some(name) => Some(
+            templates
+                .iter()
+                .find(|it| it.name == *name)
+                .ok_or_else(|| eyre!("No template named {name} for the scaffold"))?,
+        ),
+        None => None,
+    };
+    let duration_mins = match (&entry.duration, template) {
+        (Some(duration), _) => {
+            u32::try_from(time_expr::evaluate_duration(duration)?.as_secs() / 60)
+                .map_err(|_| eyre!("Scaffold entry at {} is too long", entry.start))?
+        }
+        (None, Some(template)) => template.duration_mins.max(0) as u32,
+        (None, None) => return Err(eyre!("Scaffold entry at {} has no duration", entry.start)),
+    };
+    let project_key = entry
+        .project
+        .clone()
+        .or_else(|| template.map(|it| it.project_key.clone()))
+        .filter(|it| *it != config.default_project_key);
+    Ok(ScaffoldItem {
+        start,
+        duration_mins,
+        project_key,
+        ticket_key: entry
+            .ticket
+            .clone()
+            .or_else(|| template.and_then(|it| it.ticket_key.clone())),
+        description: entry
+            .description
+            .clone()
+            .or_else(|| template.map(|it| it.description.clone()))
+            .unwrap_or_default(),
+        billable: entry
+            .billable
+            .or_else(|| template.map(|it| it.billable))
+            .unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::config::ScaffoldConfig;
+
+    fn entry(start: &str) -> ScaffoldEntry {
+        ScaffoldEntry {
+            start: start.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_resolve_fills_in_templates() {
+        let standup = EntryTemplate {
+            name: "standup".into(),
+            project_key: "W".into(),
+            ticket_key: Some("SCRUM-1".into()),
+            duration_mins: 15,
+            description: "Standup".into(),
+            billable: true,
+        };
+        let planning = ScaffoldEntry {
+            duration: Some("1h".into()),
+            description: Some("Planning".into()),
+            ..entry("10:00")
+        };
+        let short_standup = ScaffoldEntry {
+            template: Some("standup".into()),
+            duration: Some("10m".into()),
+            ..entry("09:00")
+        };
+        let config = Config {
+            default_project_key: "E".into(),
+            scaffold: ScaffoldConfig {
+                auto_apply: false,
+                days: HashMap::from([("monday".into(), vec![planning, short_standup])]),
+            },
+            ..Default::default()
+        };
+
+        let entries = entries_for(&config, Weekday::Monday);
+        assert!(needs_templates(entries));
+        assert!(entries_for(&config, Weekday::Tuesday).is_empty());
+        let items = resolve(entries, &[standup], &config).unwrap();
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].start, NaiveTime::from_hms_opt(9, 0, 0).unwrap());
+        assert_eq!(items[0].duration_mins, 10);
+        assert_eq!(items[0].project_key.as_deref(), Some("W"));
+        assert_eq!(items[0].ticket_key.as_deref(), Some("SCRUM-1"));
+        assert!(items[0].billable);
+        assert_eq!(items[1].duration_mins, 60);
+        assert_eq!(items[1].project_key, None);
+        assert_eq!(items[1].description, "Planning");
+
+        assert!(resolve(&[entry("09:00")], &[], &config).is_err());
+        let unknown = ScaffoldEntry {
+            template: Some("retro".into()),
+            ..entry("09:00")
+        };
+        assert!(resolve(&[unknown], &[], &config).is_err());
+    }
+}
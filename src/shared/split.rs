@@ -0,0 +1,104 @@
+use color_eyre::{Result, eyre::bail};
+
+/// Part of an entry to book on a ticket
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Share {
+    pub percent: u32,
+    pub ticket: String,
+}
+
+/// Parses shares like `50% SCRUM-1, 30% SCRUM-2, 20% PLAT-9`, which must add up to 100%.
+/// The percent sign is optional and the ticket may also come first, like `SCRUM-1 50`.
+pub fn parse_shares(input: &str) -> Result<Vec<Share>> {
+    let mut shares = vec![];
+    for part in input.split([',', ';']).map(str::trim) {
+        if part.is_empty() {
+            continue;
+        }
+        let words: Vec<_> = part.split_whitespace().collect();
+        let [first, second] = words[..] else {
+            bail!("Expected a percentage and a ticket in {part:?}");
+        };
+        let (percent, ticket) = match (parse_percent(first), parse_percent(second)) {
+            (Some(percent), None) => (percent, second),
+            (None, Some(percent)) => (percent, first),
+            _ => bail!("Expected a percentage and a ticket in {part:?}"),
+        };
+        if percent == 0 {
+            bail!("{ticket} has no share");
+        }
+        shares.push(Share {
+            percent,
+            ticket: ticket.to_uppercase(),
+        });
+    }
+    if shares.len() < 2 {
+        bail!("Split across at least two tickets");
+    }
+    let total: u32 = shares.iter().map(|it| it.percent).sum();
+    if total != 100 {
+        bail!("Shares add up to {total}%, not 100%");
+    }
+    Ok(shares)
+}
+
+fn parse_percent(word: &str) -> Option<u32> {
+    word.strip_suffix('%').unwrap_or(word).parse().ok()
+}
+
+/// Whole minutes per share that add up to exactly the total. Minutes lost to rounding down go
+/// to the shares with the largest remainders, earlier ones first on ties.
+pub fn apportion(total_mins: u64, percents: &[u32]) -> Vec<u64> {
+    let exact: Vec<_> = percents
+        .iter()
+        .map(|percent| total_mins * *percent as u64)
+        .collect();
+    let mut mins: Vec<_> = exact.iter().map(|it| it / 100).collect();
+    let mut by_remainder: Vec<_> = (0..exact.len()).collect();
+    by_remainder.sort_by_key(|idx| std::cmp::Reverse(exact[*idx] % 100));
+    let missing = total_mins - mins.iter().sum::<u64>();
+    for idx in by_remainder.into_iter().take(missing as usize) {
+        mins[idx] += 1;
+    }
+    mins
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_shares() {
+        let shares = parse_shares("50% SCRUM-1, 30 scrum-2; PLAT-9 20%").unwrap();
+        assert_eq!(
+            shares,
+            vec![
+                Share {
+                    percent: 50,
+                    ticket: "SCRUM-1".into()
+                },
+                Share {
+                    percent: 30,
+                    ticket: "SCRUM-2".into()
+                },
+                Share {
+                    percent: 20,
+                    ticket: "PLAT-9".into()
+                },
+            ]
+        );
+        assert!(parse_shares("50% SCRUM-1, 40% SCRUM-2").is_err());
+        assert!(parse_shares("100% SCRUM-1").is_err());
+        assert!(parse_shares("50% SCRUM-1, half SCRUM-2").is_err());
+    }
+
+    #[test]
+    fn test_apportion_sums_to_total() {
+        assert_eq!(apportion(60, &[50, 30, 20]), vec![30, 18, 12]);
+        assert_eq!(apportion(10, &[34, 33, 33]), vec![4, 3, 3]);
+        assert_eq!(apportion(25, &[50, 50]), vec![13, 12]);
+        for total in 0..200 {
+            assert_eq!(apportion(total, &[17, 41, 42]).iter().sum::<u64>(), total);
+        }
+    }
+}
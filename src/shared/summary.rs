@@ -1,21 +1,24 @@
 use std::collections::HashMap;
 
+use itertools::Itertools;
 use serde::Serialize;
 use time::Duration;
 
 use crate::{
-    config::{Config, ProjectConfig},
+    config::{Config, ProjectConfig, WorkKind},
     persist::TimeEntry,
     shared::{
         BREAK_PROJECT_KEY,
-        defrag::{self, DefragmentedEntry},
+        defrag::{self, DefragStrategy, DefragmentedEntry},
     },
 };
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct ProjectSummary {
     pub config: Option<ProjectConfig>,
     pub ticket_sums: HashMap<String, Duration>,
+    /// Distinct descriptions per ticket, in the order they were first worked on
+    pub ticket_descriptions: HashMap<String, Vec<String>>,
     pub first_start: Option<String>,
 }
 
@@ -26,6 +29,14 @@ impl ProjectSummary {
             .map(|c| c.internal_name.as_str())
             .unwrap_or("❔")
     }
+
+    /// What was done on the ticket, empty if no entry has a description
+    pub fn descriptions_of(&self, ticket: &str) -> String {
+        self.ticket_descriptions
+            .get(ticket)
+            .map(|it| it.join("; "))
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Serialize, Debug, Clone)]
@@ -34,12 +45,76 @@ pub struct Break {
     pub duration_mins: u32,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 pub struct TimesheetSummary {
     pub projects: HashMap<String, ProjectSummary>,
     pub start_time: Option<String>,
     pub end_time: Option<String>,
     pub breaks: Vec<Break>,
+    /// Part of the working time that is billable
+    pub billable: Duration,
+    /// Working time by work type key, entries without one are left out
+    pub work_types: HashMap<String, Duration>,
+    /// Time away by absence key, not part of any project
+    pub absences: HashMap<String, Duration>,
+    /// Non-empty working entries by start time, breaks excluded
+    #[serde(skip)]
+    pub entries: Vec<TimeEntry>,
+}
+
+/// How the working time of a day was split up
+#[derive(Debug, PartialEq)]
+pub struct DayStats {
+    pub entries: usize,
+    pub distinct_tickets: usize,
+    /// Longest run of back-to-back entries without a break or gap
+    pub longest_block: Duration,
+    /// Changes of project or ticket between consecutive entries
+    pub switches: usize,
+    /// See [Self::switches], per working hour
+    pub switches_per_hour: f64,
+}
+
+/// What the rows of the summary stand for, see [TimesheetSummary::grouped]
+#[derive(Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryGrouping {
+    /// Tickets under their project, see [TimesheetSummary::projects]
+    #[default]
+    Project,
+    Ticket,
+    WorkType,
+    /// Text before the first `:` of the description, or else its first word
+    Description,
+}
+
+impl SummaryGrouping {
+    /// The grouping after this one, starting over after the last
+    pub fn next(self) -> Self {
+        match self {
+            Self::Project => Self::Ticket,
+            Self::Ticket => Self::WorkType,
+            Self::WorkType => Self::Description,
+            Self::Description => Self::Project,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Project => "Project",
+            Self::Ticket => "Ticket",
+            Self::WorkType => "Work type",
+            Self::Description => "Description",
+        }
+    }
+}
+
+/// Working time of the entries with the same ticket, work type or description prefix
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SummaryGroup {
+    /// `-` for entries without one
+    pub key: String,
+    pub duration: Duration,
 }
 
 #[derive(Serialize)]
@@ -47,16 +122,23 @@ pub struct SummaryJson {
     #[serde(flatten)]
     pub summary: TimesheetSummary,
     pub defragmented: Vec<DefragmentedEntry>,
+    pub grouping: SummaryGrouping,
+    /// Rows of any grouping other than by project, which are in the summary already
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<SummaryGroup>,
 }
 
 impl TimesheetSummary {
-    pub fn new(entries: Vec<TimeEntry>) -> Self {
-        let config = Config::get();
+    pub fn new(entries: Vec<TimeEntry>, config: &Config) -> Self {
         let mut projects: HashMap<String, ProjectSummary> = HashMap::new();
 
         let mut start_time: Option<String> = None;
         let mut end_time: Option<String> = None;
         let mut breaks: Vec<Break> = Vec::new();
+        let mut billable = Duration::ZERO;
+        let mut work_types: HashMap<String, Duration> = HashMap::new();
+        let mut absences: HashMap<String, Duration> = HashMap::new();
+        let mut work_entries: Vec<TimeEntry> = Vec::new();
 
         for entry in entries.iter() {
             let duration = Duration::minutes(entry.duration_mins as i64);
@@ -75,6 +157,17 @@ impl TimesheetSummary {
                 });
                 continue;
             }
+            if let Some(absence) = config.absence_of_project(project_key) {
+                *absences.entry(absence.to_string()).or_default() += duration;
+                continue;
+            }
+            if entry.billable {
+                billable += duration;
+            }
+            if let Some(work_type) = &entry.work_type {
+                *work_types.entry(work_type.clone()).or_default() += duration;
+            }
+            work_entries.push(entry.clone());
 
             let project_summary = projects
                 .entry(project_key.clone())
@@ -134,12 +227,120 @@ impl TimesheetSummary {
                 .max();
         }
 
+        work_entries.sort_by(|a, b| a.start_time.cmp(&b.start_time));
+        for entry in &work_entries {
+            let description = entry.description.trim();
+            if description.is_empty() {
+                continue;
+            }
+            let Some(project_summary) = projects.get_mut(&entry.project_key) else {
+                continue;
+            };
+            let ticket = entry.ticket_key.as_deref().unwrap_or("-").to_string();
+            let descriptions = project_summary
+                .ticket_descriptions
+                .entry(ticket)
+                .or_default();
+            if !descriptions.iter().any(|it| it == description) {
+                descriptions.push(description.to_string());
+            }
+        }
+
         Self {
             projects,
             start_time,
             end_time,
             breaks,
+            billable,
+            work_types,
+            absences,
+            entries: work_entries,
+        }
+    }
+
+    pub fn stats(&self) -> DayStats {
+        let distinct_tickets = self
+            .entries
+            .iter()
+            .filter_map(|entry| entry.ticket_key.as_deref())
+            .unique()
+            .count();
+
+        let mut longest_block = Duration::ZERO;
+        let mut block = Duration::ZERO;
+        let mut block_end: Option<u32> = None;
+        let mut switches = 0;
+        for (idx, entry) in self.entries.iter().enumerate() {
+            let duration = Duration::minutes(entry.duration_mins as i64);
+            let start = parse_minutes(&entry.start_time);
+            if start.is_some() && start == block_end {
+                block += duration;
+            } else {
+                block = duration;
+            }
+            block_end = start.map(|it| it + entry.duration_mins as u32);
+            longest_block = longest_block.max(block);
+
+            if let Some(previous) = idx.checked_sub(1).map(|it| &self.entries[it])
+                && (previous.project_key != entry.project_key
+                    || previous.ticket_key != entry.ticket_key)
+            {
+                switches += 1;
+            }
+        }
+
+        let working_hours = self.calculate_total_duration().as_seconds_f64() / 3600.0;
+        let switches_per_hour = if working_hours > 0.0 {
+            switches as f64 / working_hours
+        } else {
+            0.0
+        };
+
+        DayStats {
+            entries: self.entries.len(),
+            distinct_tickets,
+            longest_block,
+            switches,
+            switches_per_hour,
+        }
+    }
+
+    /// Working time by the grouping, longest first. Empty for [SummaryGrouping::Project], which
+    /// are the [Self::projects] and their tickets.
+    pub fn grouped(&self, grouping: SummaryGrouping) -> Vec<SummaryGroup> {
+        if grouping == SummaryGrouping::Project {
+            return vec![];
+        }
+        let mut groups: HashMap<String, Duration> = HashMap::new();
+        for entry in &self.entries {
+            let key = match grouping {
+                SummaryGrouping::Project => unreachable!("returned above"),
+                SummaryGrouping::Ticket => entry.ticket_key.clone(),
+                SummaryGrouping::WorkType => entry.work_type.clone(),
+                SummaryGrouping::Description => description_prefix(&entry.description),
+            };
+            *groups.entry(key.unwrap_or_else(|| "-".into())).or_default() +=
+                Duration::minutes(entry.duration_mins as i64);
+        }
+        groups
+            .into_iter()
+            .map(|(key, duration)| SummaryGroup { key, duration })
+            .sorted_by(|a, b| b.duration.cmp(&a.duration).then_with(|| a.key.cmp(&b.key)))
+            .collect()
+    }
+
+    /// Time in meetings and in deep work, by what the work types count as
+    pub fn meeting_and_deep_work(&self, config: &Config) -> (Duration, Duration) {
+        let mut meeting = Duration::ZERO;
+        let mut deep_work = Duration::ZERO;
+        for (work_type, duration) in &self.work_types {
+            match config.work_types.get(work_type).and_then(|it| it.counts_as) {
+                Some(WorkKind::Meeting) => meeting += *duration,
+                Some(WorkKind::DeepWork) => deep_work += *duration,
+                None => {}
+            }
         }
+        (meeting, deep_work)
     }
 
     fn create_project_summary(project_key: &str, config: &Config) -> ProjectSummary {
@@ -148,6 +349,7 @@ impl TimesheetSummary {
         ProjectSummary {
             config: project_config,
             ticket_sums: HashMap::new(),
+            ticket_descriptions: HashMap::new(),
             first_start: None,
         }
     }
@@ -160,6 +362,11 @@ impl TimesheetSummary {
             .sum()
     }
 
+    /// Counts towards the expected working time, but is not worked on any project
+    pub fn calculate_absence_duration(&self) -> Duration {
+        self.absences.values().sum()
+    }
+
     pub fn calculate_break_duration(&self) -> Duration {
         self.projects
             .get(BREAK_PROJECT_KEY)
@@ -170,13 +377,257 @@ impl TimesheetSummary {
 
 impl SummaryJson {
     /// Creates a SummaryJson from entries, calculating both the summary and defragmented timeline
-    pub fn from_entries(entries: Vec<TimeEntry>) -> Self {
-        let summary = TimesheetSummary::new(entries);
-        let defragmented = defrag::calculate(&summary);
+    pub fn from_entries(entries: Vec<TimeEntry>, config: &Config) -> Self {
+        Self::from_summary(
+            TimesheetSummary::new(entries, config),
+            config.export.defrag_strategy,
+            SummaryGrouping::Project,
+        )
+    }
+
+    /// Adds the defragmented timeline and the groups to a summary that was already calculated
+    pub fn from_summary(
+        summary: TimesheetSummary,
+        strategy: DefragStrategy,
+        grouping: SummaryGrouping,
+    ) -> Self {
+        let defragmented = defrag::calculate(&summary, strategy);
+        let groups = summary.grouped(grouping);
 
         Self {
             summary,
             defragmented,
+            grouping,
+            groups,
+        }
+    }
+}
+
+/// See [SummaryGrouping::Description], `None` if there is no description
+fn description_prefix(description: &str) -> Option<String> {
+    let description = description.trim();
+    let prefix = match description.split_once(':') {
+        Some((prefix, _)) => prefix.trim(),
+        None => description.split_whitespace().next().unwrap_or_default(),
+    };
+    (!prefix.is_empty()).then(|| prefix.to_string())
+}
+
+fn parse_minutes(time: &str) -> Option<u32> {
+    let (hours, minutes) = time.split_once(':')?;
+    Some(hours.parse::<u32>().ok()? * 60 + minutes.parse::<u32>().ok()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AbsenceConfig, WorkTypeConfig};
+
+    fn entry(start_time: &str, duration_mins: i32, project_key: &str, ticket: &str) -> TimeEntry {
+        TimeEntry {
+            id: start_time.to_string(),
+            timesheet_day: "2026-10-14".to_string(),
+            project_key: project_key.to_string(),
+            ticket_key: Some(ticket.to_string()),
+            ..TimeEntry::sample(start_time, duration_mins)
         }
     }
+
+    #[test]
+    fn test_stats_split_blocks_at_breaks_and_gaps() {
+        let entries = vec![
+            entry("09:00", 60, "E", "SCRUM-1"),
+            entry("10:00", 30, "E", "SCRUM-2"),
+            entry("10:30", 30, BREAK_PROJECT_KEY, "-"),
+            entry("11:00", 45, "E", "SCRUM-2"),
+            entry("11:45", 45, "M", "SCRUM-1"),
+            entry("12:30", 0, "E", "SCRUM-3"),
+            entry("13:00", 60, "E", "SCRUM-1"),
+        ];
+
+        let stats = TimesheetSummary::new(entries, &Config::default()).stats();
+
+        assert_eq!(
+            stats,
+            DayStats {
+                entries: 5,
+                distinct_tickets: 2,
+                longest_block: Duration::minutes(90),
+                switches: 3,
+                // SCRUM-1 → SCRUM-2 → M/SCRUM-1 → E/SCRUM-1 in 4 hours
+                switches_per_hour: 0.75,
+            }
+        );
+    }
+
+    #[test]
+    fn test_stats_of_empty_day() {
+        let stats = TimesheetSummary::new(vec![], &Config::default()).stats();
+
+        assert_eq!(stats.entries, 0);
+        assert_eq!(stats.longest_block, Duration::ZERO);
+        assert_eq!(stats.switches_per_hour, 0.0);
+    }
+
+    #[test]
+    fn test_meeting_and_deep_work_by_work_type() {
+        let work_type = |label: &str, counts_as| WorkTypeConfig {
+            label: label.to_string(),
+            counts_as,
+        };
+        let config = Config {
+            work_types: HashMap::from([
+                (
+                    "dev".into(),
+                    work_type("Development", Some(WorkKind::DeepWork)),
+                ),
+                (
+                    "meeting".into(),
+                    work_type("Meeting", Some(WorkKind::Meeting)),
+                ),
+                ("admin".into(), work_type("Admin", None)),
+            ]),
+            ..Default::default()
+        };
+        let with_type = |start, mins, project, work_type: &str| TimeEntry {
+            work_type: Some(work_type.to_string()),
+            ..entry(start, mins, project, "SCRUM-1")
+        };
+        let entries = vec![
+            with_type("09:00", 30, "E", "meeting"),
+            with_type("09:30", 90, "E", "dev"),
+            with_type("11:00", 30, BREAK_PROJECT_KEY, "meeting"),
+            with_type("11:30", 60, "E", "dev"),
+            with_type("12:30", 15, "E", "admin"),
+            entry("12:45", 15, "E", "SCRUM-2"),
+        ];
+
+        let summary = TimesheetSummary::new(entries, &config);
+
+        assert_eq!(summary.work_types["dev"], Duration::minutes(150));
+        assert_eq!(summary.work_types["meeting"], Duration::minutes(30));
+        assert_eq!(summary.work_types.len(), 3);
+        assert_eq!(
+            summary.meeting_and_deep_work(&config),
+            (Duration::minutes(30), Duration::minutes(150))
+        );
+    }
+
+    #[test]
+    fn test_absences_are_kept_out_of_projects() {
+        let config = Config {
+            absences: HashMap::from([(
+                "vacation_half_day".into(),
+                AbsenceConfig {
+                    label: "Vacation (half day)".into(),
+                    project: "VACATION".into(),
+                    duration: "4h".into(),
+                },
+            )]),
+            ..Default::default()
+        };
+        let entries = vec![
+            entry("08:00", 240, "VACATION", "-"),
+            entry("12:00", 30, BREAK_PROJECT_KEY, "-"),
+            entry("12:30", 210, "E", "SCRUM-1"),
+        ];
+
+        let summary = TimesheetSummary::new(entries, &config);
+
+        assert_eq!(summary.projects.keys().collect_vec(), vec!["E"]);
+        assert_eq!(summary.calculate_total_duration(), Duration::minutes(210));
+        assert_eq!(summary.calculate_absence_duration(), Duration::minutes(240));
+        assert_eq!(summary.stats().entries, 1);
+        assert_eq!(summary.start_time.as_deref(), Some("08:00"));
+    }
+
+    #[test]
+    fn test_distinct_descriptions_per_ticket_in_order() {
+        let described = |start_time, ticket, description: &str| TimeEntry {
+            description: description.to_string(),
+            ..entry(start_time, 30, "E", ticket)
+        };
+        let entries = vec![
+            described("11:00", "SCRUM-1", "review"),
+            described("09:00", "SCRUM-1", "implement login"),
+            described("09:30", "SCRUM-2", " "),
+            described("10:00", "SCRUM-1", "implement login "),
+        ];
+
+        let summary = TimesheetSummary::new(entries, &Config::default());
+
+        let project = &summary.projects["E"];
+        assert_eq!(
+            project.descriptions_of("SCRUM-1"),
+            "implement login; review"
+        );
+        assert_eq!(project.descriptions_of("SCRUM-2"), "");
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(
+            json["projects"]["E"]["ticket_descriptions"]["SCRUM-1"],
+            serde_json::json!(["implement login", "review"])
+        );
+    }
+
+    #[test]
+    fn test_groups_by_ticket_work_type_and_description_prefix() {
+        let typed =
+            |start_time, mins, ticket, work_type: Option<&str>, description: &str| TimeEntry {
+                work_type: work_type.map(str::to_string),
+                description: description.to_string(),
+                ..entry(start_time, mins, "E", ticket)
+            };
+        let entries = vec![
+            typed("09:00", 15, "SCRUM-1", Some("meeting"), "Standup"),
+            typed("09:15", 60, "SCRUM-2", Some("dev"), "Review: login form"),
+            typed("10:15", 30, "SCRUM-1", None, "Review: search"),
+            TimeEntry {
+                description: "Lunch".into(),
+                ..entry("10:45", 15, BREAK_PROJECT_KEY, "-")
+            },
+            typed("11:00", 30, "SCRUM-2", Some("dev"), " "),
+        ];
+        let summary = TimesheetSummary::new(entries, &Config::default());
+        let groups = |grouping| {
+            summary
+                .grouped(grouping)
+                .into_iter()
+                .map(|it| (it.key, it.duration.whole_minutes()))
+                .collect_vec()
+        };
+
+        assert_eq!(groups(SummaryGrouping::Project), vec![]);
+        assert_eq!(
+            groups(SummaryGrouping::Ticket),
+            vec![("SCRUM-2".into(), 90), ("SCRUM-1".into(), 45)]
+        );
+        assert_eq!(
+            groups(SummaryGrouping::WorkType),
+            vec![("dev".into(), 90), ("-".into(), 30), ("meeting".into(), 15)]
+        );
+        assert_eq!(
+            groups(SummaryGrouping::Description),
+            vec![
+                ("Review".into(), 90),
+                ("-".into(), 30),
+                ("Standup".into(), 15)
+            ]
+        );
+
+        let json = serde_json::to_value(SummaryJson::from_summary(
+            summary.clone(),
+            DefragStrategy::default(),
+            SummaryGrouping::WorkType,
+        ))
+        .unwrap();
+        assert_eq!(json["grouping"], "work_type");
+        assert_eq!(json["groups"][0]["key"], "dev");
+        let json = serde_json::to_value(SummaryJson::from_summary(
+            summary,
+            DefragStrategy::default(),
+            SummaryGrouping::Project,
+        ))
+        .unwrap();
+        assert!(json.get("groups").is_none());
+    }
 }
@@ -0,0 +1,125 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::config::Config;
+
+lazy_static! {
+    /// Jira-like keys with or without the dash, like `scrum17`
+    static ref JIRA_LIKE: Regex =
+        Regex::new(r"^([A-Za-z][A-Za-z0-9]*?)-?(\d+)$").expect("valid ticket regex");
+}
+
+/// Project key configured for the ticket's prefix, the longest matching prefix wins.
+/// Prefixes may end in `*`, e.g. `SCRUM-*`, which is the same as `SCRUM-`.
+pub fn project_for<'a>(config: &'a Config, ticket: &str) -> Option<&'a str> {
+    let ticket = ticket.trim();
+    if ticket.is_empty() {
+        return None;
+    }
+    config
+        .ticket_projects
+        .iter()
+        .map(|(prefix, project)| (prefix.trim_end_matches('*'), project))
+        .filter(|(prefix, _)| ticket.starts_with(prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, project)| project.as_str())
+}
+
+/// Ticket key as its project expects it, e.g. `SCRUM-17` for ` scrum17`. The project is the
+/// one of the entry, if set, or the one the ticket would fill in. Projects with a
+/// [crate::config::ProjectConfig::ticket_pattern] get the first of the trimmed, uppercased and
+/// dashed key that matches it, others that book on Jira the dashed one. Everything else is
+/// only trimmed, so that validation can point out keys that don't fit.
+pub fn normalize(config: &Config, project_key: Option<&str>, ticket: &str) -> String {
+    let trimmed = ticket.trim();
+    let upper = trimmed.to_uppercase();
+    let dashed = match JIRA_LIKE.captures(&upper) {
+        Some(captures) => format!("{}-{}", &captures[1], &captures[2]),
+        None => upper.clone(),
+    };
+    let project_key = project_key
+        .filter(|it| !it.is_empty())
+        .or_else(|| project_for(config, &dashed))
+        .unwrap_or(&config.default_project_key);
+    let Some(project) = config.projects.get(project_key) else {
+        return trimmed.to_string();
+    };
+    match &project.ticket_pattern {
+        Some(pattern) => {
+            let Ok(regex) = pattern.regex() else {
+                return trimmed.to_string();
+            };
+            [trimmed, &upper, &dashed]
+                .into_iter()
+                .find(|it| regex.is_match(it))
+                .unwrap_or(trimmed)
+                .to_string()
+        }
+        None if project.jira_url.is_some() => dashed,
+        None => trimmed.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::config::ProjectConfig;
+
+    fn config() -> Config {
+        let ticket_projects = HashMap::from([
+            ("SCRUM-*".to_string(), "W".to_string()),
+            ("SCRUM-9".to_string(), "M".to_string()),
+            ("PLAT-".to_string(), "P".to_string()),
+        ]);
+        Config {
+            ticket_projects,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let config = config();
+
+        assert_eq!(project_for(&config, "SCRUM-17"), Some("W"));
+        assert_eq!(project_for(&config, "SCRUM-93"), Some("M"));
+        assert_eq!(project_for(&config, " PLAT-3"), Some("P"));
+    }
+
+    #[test]
+    fn test_unknown_and_empty_tickets_have_no_project() {
+        let config = config();
+
+        assert_eq!(project_for(&config, "OPS-1"), None);
+        assert_eq!(project_for(&config, ""), None);
+    }
+
+    #[test]
+    fn test_normalize_fits_the_project() {
+        let mut config = config();
+        let project = |ticket_pattern: Option<&str>, jira_url: Option<&str>| ProjectConfig {
+            jira_url: jira_url.map(str::to_string),
+            ticket_pattern: ticket_pattern.map(|it| it.to_string().into()),
+            ..Default::default()
+        };
+        config.projects = HashMap::from([
+            ("W".to_string(), project(Some(r"SCRUM-\d+"), None)),
+            (
+                "P".to_string(),
+                project(None, Some("https://jira.example.com")),
+            ),
+            ("L".to_string(), project(Some(r"[a-z]+"), None)),
+            ("X".to_string(), project(None, None)),
+        ]);
+
+        assert_eq!(normalize(&config, None, " scrum17 "), "SCRUM-17");
+        assert_eq!(normalize(&config, Some("W"), "scrum-17"), "SCRUM-17");
+        assert_eq!(normalize(&config, Some("P"), "plat3"), "PLAT-3");
+        assert_eq!(normalize(&config, Some("L"), " support "), "support");
+        assert_eq!(normalize(&config, Some("W"), "ops 1"), "ops 1");
+        assert_eq!(normalize(&config, Some("X"), "v2"), "v2");
+        assert_eq!(normalize(&config, None, ""), "");
+    }
+}
@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use time::Date;
+
+use crate::persist::TicketSuggestion;
+
+/// Age after which a use only counts half as much for the ranking
+const HALF_LIFE_DAYS: f64 = 14.0;
+
+/// Sums up the uses per ticket and day, best first. Every use counts less the older it is,
+/// so that tickets used a lot in the past stop outranking the ones worked on now.
+pub fn rank(uses_per_day: Vec<(String, Date, u32)>, today: Date) -> Vec<TicketSuggestion> {
+    let mut scores: HashMap<String, (TicketSuggestion, f64)> = HashMap::new();
+    for (ticket_key, day, uses) in uses_per_day {
+        let days_ago = (today - day).whole_days().max(0) as f64;
+        let score = uses as f64 * 0.5f64.powf(days_ago / HALF_LIFE_DAYS);
+        let (suggestion, total_score) = scores.entry(ticket_key.clone()).or_insert_with(|| {
+            let suggestion = TicketSuggestion {
+                ticket_key,
+                uses: 0,
+                last_used: day,
+            };
+            (suggestion, 0.0)
+        });
+        suggestion.uses += uses;
+        suggestion.last_used = suggestion.last_used.max(day);
+        *total_score += score;
+    }
+    scores
+        .into_values()
+        .sorted_by(|(a, a_score), (b, b_score)| {
+            b_score
+                .total_cmp(a_score)
+                .then_with(|| a.ticket_key.cmp(&b.ticket_key))
+        })
+        .map(|(suggestion, _)| suggestion)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::date;
+
+    use super::*;
+
+    #[test]
+    fn test_recent_tickets_outrank_stale_frequent_ones() {
+        let today = date!(2025 - 09 - 22);
+        let uses = vec![
+            ("OLD-1".to_string(), date!(2025 - 06 - 02), 20),
+            ("OLD-1".to_string(), date!(2025 - 06 - 03), 20),
+            ("NEW-2".to_string(), date!(2025 - 09 - 19), 3),
+            ("NEW-2".to_string(), date!(2025 - 09 - 22), 2),
+            ("MID-3".to_string(), date!(2025 - 09 - 01), 4),
+        ];
+
+        let ranked = rank(uses, today);
+
+        let keys = ranked.iter().map(|it| it.ticket_key.as_str()).collect_vec();
+        assert_eq!(keys, ["NEW-2", "MID-3", "OLD-1"]);
+        assert_eq!(ranked[0].uses, 5);
+        assert_eq!(ranked[0].last_used, today);
+        assert_eq!(ranked[2].uses, 40);
+        assert_eq!(ranked[2].last_used, date!(2025 - 06 - 03));
+    }
+}
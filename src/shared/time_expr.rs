@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use color_eyre::{
+    Result,
+    eyre::{bail, eyre},
+};
+use humantime::parse_duration;
+
+/// Evaluates sums and products of durations, like `3*25m`, `1h-10m` or `45+15`.
+/// Bare numbers are minutes, unless they are multiplied with a duration.
+pub fn evaluate_duration(expr: &str) -> Result<Duration> {
+    let secs = evaluate_secs(expr)?;
+    if secs < 0 {
+        bail!("{} is negative", expr.trim());
+    }
+    Ok(Duration::from_secs(secs as u64))
+}
+
+/// Minutes to move by, like `+30`, `-1h` or `-2*15m`, positive if there is no sign
+pub fn evaluate_offset_mins(expr: &str) -> Result<i64> {
+    let secs = evaluate_secs(expr)?;
+    if secs % 60 != 0 {
+        bail!("{} is not a whole number of minutes", expr.trim());
+    }
+    Ok(secs / 60)
+}
+
+/// Signed seconds of the sum, which may start with a sign
+fn evaluate_secs(expr: &str) -> Result<i64> {
+    let expr = expr.trim();
+    if expr.is_empty() {
+        bail!("Empty duration");
+    }
+    let too_large = || eyre!("{expr} is too large");
+    let mut total_secs: i64 = 0;
+    let mut sign = 1;
+    let mut term_start = 0;
+    for (idx, chr) in expr.char_indices() {
+        if chr == '+' || chr == '-' {
+            if idx > 0 {
+                let term = sign * evaluate_product(&expr[term_start..idx])?;
+                total_secs = total_secs.checked_add(term).ok_or_else(too_large)?;
+            }
+            sign = if chr == '+' { 1 } else { -1 };
+            term_start = idx + 1;
+        }
+    }
+    let term = sign * evaluate_product(&expr[term_start..])?;
+    total_secs.checked_add(term).ok_or_else(too_large)
+}
+
+/// Seconds of a term like `3*25m`, where at most one factor may have a unit
+fn evaluate_product(term: &str) -> Result<i64> {
+    let mut multiplier: i64 = 1;
+    let mut duration = None;
+    for factor in term.split('*').map(str::trim) {
+        if factor.is_empty() {
+            bail!("Missing a number in {:?}", term.trim());
+        }
+        if let Ok(number) = factor.parse::<i64>() {
+            multiplier = multiplier
+                .checked_mul(number)
+                .ok_or_else(|| eyre!("{term} is too large"))?;
+        } else if duration.is_some() {
+            bail!("Cannot multiply two durations in {:?}", term.trim());
+        } else {
+            duration = Some(parse_duration(factor)?);
+        }
+    }
+    let secs = match duration {
+        Some(duration) => i64::try_from(duration.as_secs())?,
+        None => 60,
+    };
+    secs.checked_mul(multiplier)
+        .ok_or_else(|| eyre!("{term} is too large"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mins(expr: &str) -> u64 {
+        evaluate_duration(expr).unwrap().as_secs() / 60
+    }
+
+    #[test]
+    fn test_plain_durations() {
+        assert_eq!(mins("45"), 45);
+        assert_eq!(mins("1h 30m"), 90);
+        assert_eq!(mins(" 25m "), 25);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(mins("3*25m"), 75);
+        assert_eq!(mins("25m * 3"), 75);
+        assert_eq!(mins("1h-10m"), 50);
+        assert_eq!(mins("45+15"), 60);
+        assert_eq!(mins("3*25m+10"), 85);
+        assert_eq!(mins("2*3"), 6);
+    }
+
+    #[test]
+    fn test_offsets() {
+        assert_eq!(evaluate_offset_mins("+30").unwrap(), 30);
+        assert_eq!(evaluate_offset_mins("30m").unwrap(), 30);
+        assert_eq!(evaluate_offset_mins("-1h").unwrap(), -60);
+        assert_eq!(evaluate_offset_mins(" -2*15m ").unwrap(), -30);
+        assert_eq!(evaluate_offset_mins("-1h+10m").unwrap(), -50);
+        assert!(evaluate_offset_mins("-").is_err());
+        assert!(evaluate_offset_mins("+30s").is_err());
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        assert!(evaluate_duration("").is_err());
+        assert!(evaluate_duration("10m-1h").is_err());
+        assert!(evaluate_duration("-10m").is_err());
+        assert!(evaluate_duration("1h*2h").is_err());
+        assert!(evaluate_duration("1h+").is_err());
+        assert!(evaluate_duration("soon").is_err());
+    }
+
+    #[test]
+    fn test_overflow_is_an_error() {
+        assert!(evaluate_duration("9223372036854775807*2m").is_err());
+        assert!(evaluate_duration("153722867280912930m+153722867280912930m").is_err());
+        assert!(evaluate_offset_mins("-153722867280912930m-153722867280912930m").is_err());
+        assert!(evaluate_duration("18446744073709551615s").is_err());
+    }
+}
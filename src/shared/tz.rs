@@ -0,0 +1,240 @@
+use chrono::{
+    FixedOffset, Local, MappedLocalTime, NaiveDate, NaiveDateTime, NaiveTime, Offset, TimeDelta,
+    TimeZone,
+};
+use time::Date;
+
+/// Time zone the wall-clock times of a day are interpreted in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DayZone {
+    /// The zone of this machine, including its DST transitions
+    Local,
+    /// Recorded somewhere else, only the offset from UTC is known
+    Fixed(FixedOffset),
+}
+
+/// How a wall-clock time maps to actual instants on a given day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WallClock {
+    Unique(FixedOffset),
+    /// The clock was turned back over this time, so it happened twice
+    Repeated {
+        earlier: FixedOffset,
+        later: FixedOffset,
+    },
+    /// The clock was turned forward over this time, so it never happened
+    Skipped,
+}
+
+impl DayZone {
+    /// Local unless the day was recorded with an offset the local zone doesn't have on that day
+    pub fn of(day: Date, recorded_offset_mins: Option<i32>) -> Self {
+        let Some(recorded) = recorded_offset_mins.and_then(|mins| FixedOffset::east_opt(mins * 60))
+        else {
+            return DayZone::Local;
+        };
+        let day = naive_date(day);
+        let local_offsets =
+            [NaiveTime::MIN, NaiveTime::from_hms_opt(23, 59, 0).unwrap()].map(|time| {
+                Local
+                    .offset_from_local_datetime(&day.and_time(time))
+                    .earliest()
+            });
+        if local_offsets.contains(&Some(recorded)) {
+            DayZone::Local
+        } else {
+            DayZone::Fixed(recorded)
+        }
+    }
+
+    pub fn resolve(&self, day: Date, time: NaiveTime) -> WallClock {
+        let local = naive_date(day).and_time(time);
+        match self {
+            DayZone::Local => resolve(&Local, local),
+            DayZone::Fixed(offset) => resolve(offset, local),
+        }
+    }
+
+    /// Wall-clock time that many minutes after the start, across DST transitions
+    pub fn wall_clock_after(&self, day: Date, start: NaiveTime, mins: i64) -> NaiveTime {
+        let local = naive_date(day).and_time(start);
+        match self {
+            DayZone::Local => wall_clock_after(&Local, local, mins),
+            DayZone::Fixed(offset) => wall_clock_after(offset, local, mins),
+        }
+        .time()
+    }
+
+    /// Minutes that actually pass between the wall-clock times, across DST transitions
+    pub fn minutes_between(&self, day: Date, start: NaiveTime, end: NaiveTime) -> i64 {
+        let (start, end) = (
+            naive_date(day).and_time(start),
+            naive_date(day).and_time(end),
+        );
+        match self {
+            DayZone::Local => minutes_between(&Local, start, end),
+            DayZone::Fixed(offset) => minutes_between(offset, start, end),
+        }
+    }
+
+    /// `UTC+09:00` for days recorded elsewhere, nothing for local days
+    pub fn label(&self) -> Option<String> {
+        match self {
+            DayZone::Local => None,
+            DayZone::Fixed(offset) => Some(format!("UTC{offset}")),
+        }
+    }
+}
+
+/// Offset from UTC of this machine right now
+pub fn current_offset_mins() -> i32 {
+    Local::now().offset().local_minus_utc() / 60
+}
+
+fn resolve<Tz: TimeZone>(tz: &Tz, local: NaiveDateTime) -> WallClock {
+    match tz.from_local_datetime(&local) {
+        MappedLocalTime::Single(at) => WallClock::Unique(at.offset().fix()),
+        MappedLocalTime::Ambiguous(earlier, later) => WallClock::Repeated {
+            earlier: earlier.offset().fix(),
+            later: later.offset().fix(),
+        },
+        MappedLocalTime::None => WallClock::Skipped,
+    }
+}
+
+fn wall_clock_after<Tz: TimeZone>(tz: &Tz, start: NaiveDateTime, mins: i64) -> NaiveDateTime {
+    match tz.from_local_datetime(&start).earliest() {
+        Some(at) => (at + TimeDelta::minutes(mins)).naive_local(),
+        // Started in a gap, there is no instant to count from
+        None => start + TimeDelta::minutes(mins),
+    }
+}
+
+fn minutes_between<Tz: TimeZone>(tz: &Tz, start: NaiveDateTime, end: NaiveDateTime) -> i64 {
+    match (
+        tz.from_local_datetime(&start).earliest(),
+        tz.from_local_datetime(&end).earliest(),
+    ) {
+        (Some(start), Some(end)) => (end - start).num_minutes(),
+        // One of them is in a gap, so only the clock can be compared
+        _ => (end - start).num_minutes(),
+    }
+}
+
+fn naive_date(day: Date) -> NaiveDate {
+    NaiveDate::from_ymd_opt(day.year(), day.month() as u32, day.day() as u32)
+        .expect("time and chrono to agree on valid dates")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Central European time of 2025, with DST from March 30 to October 26
+    #[derive(Debug, Clone, Copy)]
+    struct Cet;
+
+    fn winter() -> FixedOffset {
+        FixedOffset::east_opt(3600).unwrap()
+    }
+
+    fn summer() -> FixedOffset {
+        FixedOffset::east_opt(7200).unwrap()
+    }
+
+    fn utc(month: u32, day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, month, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    impl TimeZone for Cet {
+        type Offset = FixedOffset;
+
+        fn from_offset(_: &FixedOffset) -> Self {
+            Cet
+        }
+
+        fn offset_from_local_date(&self, local: &NaiveDate) -> MappedLocalTime<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_local_datetime(
+            &self,
+            local: &NaiveDateTime,
+        ) -> MappedLocalTime<FixedOffset> {
+            let fitting: Vec<_> = [summer(), winter()]
+                .into_iter()
+                .filter(|offset| self.offset_from_utc_datetime(&(*local - *offset)) == *offset)
+                .collect();
+            match fitting[..] {
+                [offset] => MappedLocalTime::Single(offset),
+                [earlier, later] => MappedLocalTime::Ambiguous(earlier, later),
+                _ => MappedLocalTime::None,
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            self.offset_from_utc_datetime(&utc.and_time(NaiveTime::MIN))
+        }
+
+        fn offset_from_utc_datetime(&self, at: &NaiveDateTime) -> FixedOffset {
+            if (utc(3, 30, 1)..utc(10, 26, 1)).contains(at) {
+                summer()
+            } else {
+                winter()
+            }
+        }
+    }
+
+    fn local(month: u32, day: u32, time: &str) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, month, day)
+            .unwrap()
+            .and_time(NaiveTime::parse_from_str(time, "%H:%M").unwrap())
+    }
+
+    #[test]
+    fn test_resolve_dst_transitions() {
+        assert_eq!(resolve(&Cet, local(3, 30, "02:30")), WallClock::Skipped);
+        assert_eq!(
+            resolve(&Cet, local(10, 26, "02:30")),
+            WallClock::Repeated {
+                earlier: summer(),
+                later: winter()
+            }
+        );
+        assert_eq!(
+            resolve(&Cet, local(10, 26, "03:30")),
+            WallClock::Unique(winter())
+        );
+    }
+
+    #[test]
+    fn test_wall_clock_after_counts_real_minutes() {
+        // 01:00 to 02:00 summer time, then another 02:00 in winter time
+        assert_eq!(
+            wall_clock_after(&Cet, local(10, 26, "01:00"), 120),
+            local(10, 26, "02:00")
+        );
+        assert_eq!(
+            wall_clock_after(&Cet, local(3, 30, "01:00"), 120),
+            local(3, 30, "04:00")
+        );
+        assert_eq!(
+            wall_clock_after(&winter(), local(10, 26, "01:00"), 120),
+            local(10, 26, "03:00")
+        );
+    }
+
+    #[test]
+    fn test_minutes_between_counts_real_minutes() {
+        let between = |month, day, start, end| {
+            minutes_between(&Cet, local(month, day, start), local(month, day, end))
+        };
+        assert_eq!(between(10, 26, "01:00", "03:00"), 180);
+        assert_eq!(between(3, 30, "01:00", "04:00"), 120);
+        assert_eq!(between(3, 30, "02:30", "04:00"), 90);
+        assert_eq!(between(6, 1, "09:30", "09:00"), -30);
+    }
+}
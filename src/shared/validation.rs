@@ -0,0 +1,649 @@
+use std::collections::HashSet;
+
+use chrono::NaiveTime;
+use time::{Date, Weekday, format_description::FormatItem, macros::format_description};
+
+use crate::{
+    config::{Config, ValidationRule, WorkingHours},
+    persist::TimeEntry,
+    shared::{
+        BREAK_PROJECT_KEY,
+        tz::{DayZone, WallClock},
+    },
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// Index of the offending entry, `None` for problems concerning the whole day
+    pub entry_idx: Option<usize>,
+    pub rule: ValidationRule,
+    pub severity: Severity,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    /// Blocks exporting
+    Hard,
+}
+
+/// Runs the configured validation rules over the entries of a day.
+/// Entries without duration are not checked, since they are still being filled.
+pub fn validate(entries: &[TimeEntry], zone: DayZone, config: &Config) -> Vec<Violation> {
+    let mut validator = Validator {
+        config,
+        violations: Vec::new(),
+    };
+    let working_hours = validator.parse_working_hours();
+    validator.check_day_overflow(entries);
+
+    for (idx, entry) in entries.iter().enumerate() {
+        if entry.duration_mins <= 0 {
+            continue;
+        }
+        validator.check_max_length(idx, entry);
+        validator.check_description(idx, entry);
+        validator.check_ticket_format(idx, entry);
+        validator.check_wall_clock(idx, entry, zone);
+        if let Some((from, until)) = working_hours {
+            validator.check_working_hours(idx, entry, from, until);
+        }
+    }
+
+    validator.violations
+}
+
+/// Entries booked on one of the archived tickets, which might be a typo or an old habit
+pub fn check_archived(
+    entries: &[TimeEntry],
+    archived: &HashSet<String>,
+    config: &Config,
+) -> Vec<Violation> {
+    let mut validator = Validator {
+        config,
+        violations: Vec::new(),
+    };
+    for (idx, entry) in entries.iter().enumerate() {
+        let Some(ticket) = &entry.ticket_key else {
+            continue;
+        };
+        if entry.duration_mins > 0 && archived.contains(ticket) {
+            let message = format!("{}: {ticket} is archived", entry.start_time);
+            validator.report(Some(idx), ValidationRule::ArchivedTicket, message);
+        }
+    }
+    validator.violations
+}
+
+/// Work logged on the public holiday, as a single problem of the whole day. Absences and
+/// breaks are no work.
+pub fn check_holiday(entries: &[TimeEntry], holiday: &str, config: &Config) -> Vec<Violation> {
+    let mut validator = Validator {
+        config,
+        violations: Vec::new(),
+    };
+    let worked_mins: i32 = entries
+        .iter()
+        .filter(|it| it.project_key != BREAK_PROJECT_KEY)
+        .filter(|it| config.absence_of_project(&it.project_key).is_none())
+        .map(|it| it.duration_mins.max(0))
+        .sum();
+    if worked_mins > 0 {
+        let message = format!("{worked_mins}m logged on {holiday}, a public holiday");
+        validator.report(None, ValidationRule::Holiday, message);
+    }
+    validator.violations
+}
+
+/// Working time of the day beyond [crate::config::ValidationConfig::max_day_mins], or below
+/// the minimum on a workday. The minimum only applies once `min_applies`, that is when the day
+/// is over and no public holiday. Absences count towards it, like towards the daily target.
+pub fn check_day_duration(
+    entries: &[TimeEntry],
+    weekday: Weekday,
+    min_applies: bool,
+    config: &Config,
+) -> Vec<Violation> {
+    let mut validator = Validator {
+        config,
+        violations: Vec::new(),
+    };
+    let (mut worked_mins, mut absent_mins) = (0, 0);
+    for entry in entries
+        .iter()
+        .filter(|it| it.project_key != BREAK_PROJECT_KEY)
+    {
+        let mins = entry.duration_mins.max(0) as u32;
+        if config.absence_of_project(&entry.project_key).is_some() {
+            absent_mins += mins;
+        } else {
+            worked_mins += mins;
+        }
+    }
+    let validation = &config.validation;
+    let message = match (validation.max_day_mins, validation.min_day_mins) {
+        (Some(max_mins), _) if worked_mins > max_mins => format!(
+            "{} worked, more than the maximum of {}",
+            format_mins(worked_mins),
+            format_mins(max_mins)
+        ),
+        (_, Some(min_mins))
+            if min_applies
+                && validation.is_workday(weekday)
+                && worked_mins + absent_mins < min_mins =>
+        {
+            format!(
+                "Only {} logged on a workday, less than the minimum of {}",
+                format_mins(worked_mins + absent_mins),
+                format_mins(min_mins)
+            )
+        }
+        _ => return vec![],
+    };
+    validator.report(None, ValidationRule::DayDuration, message);
+    validator.violations
+}
+
+/// Problems that would leave the day half-booked in Jira, which all block the export: hard
+/// violations of the configured rules, entries that would be left out of the bookings and
+/// changes to the entries that were not saved yet
+pub fn check_jira_export(
+    entries: &[TimeEntry],
+    unsaved: &[TimeEntry],
+    zone: DayZone,
+    config: &Config,
+) -> Vec<Violation> {
+    let mut violations = validate(entries, zone, config);
+    violations.retain(|it| it.severity == Severity::Hard);
+    let mut validator = Validator { config, violations };
+    for (idx, entry) in entries.iter().enumerate() {
+        let books_on_jira = config
+            .projects
+            .get(&entry.project_key)
+            .is_some_and(|it| it.jira_url.is_some());
+        if !books_on_jira {
+            continue;
+        }
+        let message = match &entry.ticket_key {
+            None if entry.duration_mins > 0 => {
+                format!(
+                    "{}: no ticket to book {}m on",
+                    entry.start_time, entry.duration_mins
+                )
+            }
+            Some(ticket) if entry.duration_mins <= 0 => {
+                format!("{}: {ticket} has no duration", entry.start_time)
+            }
+            _ => continue,
+        };
+        validator.report_blocking(Some(idx), ValidationRule::JiraBooking, message);
+    }
+    for entry in unsaved {
+        let idx = entries.iter().position(|it| it.id == entry.id);
+        let message = format!("{}: changes are not saved yet", entry.start_time);
+        validator.report_blocking(idx, ValidationRule::Unsaved, message);
+    }
+    validator.violations
+}
+
+/// Entries that must not be saved, since their times wrapped around midnight
+pub fn unsaveable_idxs(violations: &[Violation]) -> HashSet<usize> {
+    violations
+        .iter()
+        .filter(|it| it.rule == ValidationRule::DayOverflow)
+        .filter_map(|it| it.entry_idx)
+        .collect()
+}
+
+pub fn has_hard_violations(violations: &[Violation]) -> bool {
+    violations.iter().any(|it| it.severity == Severity::Hard)
+}
+
+/// Worst severity of the violations concerning a single entry
+pub fn worst_severity_of(violations: &[Violation], entry_idx: usize) -> Option<Severity> {
+    violations
+        .iter()
+        .filter(|it| it.entry_idx == Some(entry_idx))
+        .map(|it| it.severity)
+        .max()
+}
+
+struct Validator<'a> {
+    config: &'a Config,
+    violations: Vec<Violation>,
+}
+
+impl Validator<'_> {
+    fn report(&mut self, entry_idx: Option<usize>, rule: ValidationRule, message: String) {
+        let severity = if self.config.validation.hard_rules.contains(&rule) {
+            Severity::Hard
+        } else {
+            Severity::Warning
+        };
+        self.violations.push(Violation {
+            entry_idx,
+            rule,
+            severity,
+            message,
+        });
+    }
+
+    fn report_blocking(&mut self, entry_idx: Option<usize>, rule: ValidationRule, message: String) {
+        self.violations.push(Violation {
+            entry_idx,
+            rule,
+            severity: Severity::Hard,
+            message,
+        });
+    }
+
+    fn parse_working_hours(&mut self) -> Option<(NaiveTime, NaiveTime)> {
+        let WorkingHours { from, until } = self.config.validation.working_hours.as_ref()?;
+        match (parse_time(from), parse_time(until)) {
+            (Some(from), Some(until)) => Some((from, until)),
+            _ => {
+                let message = format!("Invalid working hours in config: {from} - {until}");
+                self.report(None, ValidationRule::WorkingHours, message);
+                None
+            }
+        }
+    }
+
+    /// Times wrap around at midnight, so an entry ending after it and the entries following
+    /// it would silently show up early in the morning instead
+    fn check_day_overflow(&mut self, entries: &[TimeEntry]) {
+        let mut overflowed_at: Option<NaiveTime> = None;
+        for (idx, entry) in entries.iter().enumerate() {
+            let Some(start) = parse_time(&entry.start_time) else {
+                continue;
+            };
+            let end_mins = minutes_of(start) + entry.duration_mins.max(0) as u32;
+            let message = match overflowed_at {
+                Some(overflowed) if start < overflowed => {
+                    format!(
+                        "{}: follows an entry that ends after midnight",
+                        entry.start_time
+                    )
+                }
+                _ if end_mins > MINS_PER_DAY => {
+                    overflowed_at = Some(start);
+                    format!(
+                        "{}: {}m end after midnight, at {:02}:{:02} the next day",
+                        entry.start_time,
+                        entry.duration_mins,
+                        (end_mins - MINS_PER_DAY) / 60,
+                        end_mins % 60
+                    )
+                }
+                _ => continue,
+            };
+            self.report_blocking(Some(idx), ValidationRule::DayOverflow, message);
+        }
+    }
+
+    fn check_max_length(&mut self, idx: usize, entry: &TimeEntry) {
+        let Some(max_mins) = self.config.validation.max_entry_mins else {
+            return;
+        };
+        let is_work = entry.project_key != BREAK_PROJECT_KEY
+            && self.config.absence_of_project(&entry.project_key).is_none();
+        if is_work && entry.duration_mins as u32 > max_mins {
+            let message = format!(
+                "{}: {}m is longer than the maximum of {max_mins}m",
+                entry.start_time, entry.duration_mins
+            );
+            self.report(Some(idx), ValidationRule::MaxEntryLength, message);
+        }
+    }
+
+    fn check_description(&mut self, idx: usize, entry: &TimeEntry) {
+        let Some(project) = self.config.projects.get(&entry.project_key) else {
+            return;
+        };
+        if project.description_required && entry.description.trim().is_empty() {
+            let message = format!(
+                "{}: {} requires a description",
+                entry.start_time, project.internal_name
+            );
+            self.report(Some(idx), ValidationRule::DescriptionRequired, message);
+        }
+    }
+
+    fn check_ticket_format(&mut self, idx: usize, entry: &TimeEntry) {
+        let Some(project) = self.config.projects.get(&entry.project_key) else {
+            return;
+        };
+        let (Some(pattern), Some(ticket)) = (&project.ticket_pattern, &entry.ticket_key) else {
+            return;
+        };
+        match pattern.regex() {
+            Ok(regex) if regex.is_match(ticket) => {}
+            Ok(_) => {
+                let message = format!(
+                    "{}: {ticket} does not match the ticket format of {} ({})",
+                    entry.start_time,
+                    project.internal_name,
+                    pattern.as_str()
+                );
+                self.report(Some(idx), ValidationRule::TicketFormat, message);
+            }
+            Err(err) => {
+                let message = format!("Invalid ticket pattern for {}: {err}", entry.project_key);
+                self.report(None, ValidationRule::TicketFormat, message);
+            }
+        }
+    }
+
+    fn check_working_hours(
+        &mut self,
+        idx: usize,
+        entry: &TimeEntry,
+        from: NaiveTime,
+        until: NaiveTime,
+    ) {
+        if entry.project_key == BREAK_PROJECT_KEY
+            || self.config.absence_of_project(&entry.project_key).is_some()
+        {
+            return;
+        }
+        let Some(start) = parse_time(&entry.start_time) else {
+            return;
+        };
+        let end_mins = minutes_of(start) + entry.duration_mins as u32;
+        if start < from || end_mins > minutes_of(until) {
+            let message = format!(
+                "{}: work outside of working hours ({} - {})",
+                entry.start_time,
+                from.format("%H:%M"),
+                until.format("%H:%M")
+            );
+            self.report(Some(idx), ValidationRule::WorkingHours, message);
+        }
+    }
+
+    fn check_wall_clock(&mut self, idx: usize, entry: &TimeEntry, zone: DayZone) {
+        let (Ok(day), Some(start)) = (
+            Date::parse(&entry.timesheet_day, ISO_DAY),
+            parse_time(&entry.start_time),
+        ) else {
+            return;
+        };
+        let message = match zone.resolve(day, start) {
+            WallClock::Unique(_) => return,
+            WallClock::Repeated { earlier, later } => format!(
+                "{} happens twice on this day (UTC{earlier} and UTC{later})",
+                entry.start_time
+            ),
+            WallClock::Skipped => format!("{} does not exist on this day", entry.start_time),
+        };
+        self.report(Some(idx), ValidationRule::WallClock, message);
+    }
+}
+
+fn format_mins(mins: u32) -> String {
+    format!("{}h {:02}m", mins / 60, mins % 60)
+}
+
+fn parse_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time, "%H:%M").ok()
+}
+
+const ISO_DAY: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
+const MINS_PER_DAY: u32 = 24 * 60;
+
+fn minutes_of(time: NaiveTime) -> u32 {
+    use chrono::Timelike;
+    time.hour() * 60 + time.minute()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::config::{ProjectConfig, ValidationConfig};
+
+    fn entry(
+        start_time: &str,
+        duration_mins: i32,
+        project: &str,
+        ticket: Option<&str>,
+    ) -> TimeEntry {
+        TimeEntry {
+            id: "1".to_string(),
+            timesheet_day: "2025-09-22".to_string(),
+            project_key: project.to_string(),
+            ticket_key: ticket.map(str::to_string),
+            ..TimeEntry::sample(start_time, duration_mins)
+        }
+    }
+
+    fn utc() -> DayZone {
+        DayZone::Fixed(chrono::FixedOffset::east_opt(0).unwrap())
+    }
+
+    fn config(validation: ValidationConfig) -> Config {
+        let mut projects = HashMap::new();
+        projects.insert(
+            "W".to_string(),
+            ProjectConfig {
+                internal_name: "Work".to_string(),
+                description_required: true,
+                ticket_pattern: Some(r"SCRUM-\d+".to_string().into()),
+                ..Default::default()
+            },
+        );
+        Config {
+            projects,
+            validation,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_valid_day_has_no_violations() {
+        let config = config(Default::default());
+        let mut work = entry("09:00", 60, "W", Some("SCRUM-17"));
+        work.description = "standup".to_string();
+        let entries = vec![work, entry("10:00", 0, "W", None)];
+
+        assert_eq!(validate(&entries, utc(), &config), vec![]);
+    }
+
+    #[test]
+    fn test_project_rules_are_hard_by_default() {
+        let config = config(Default::default());
+        let entries = vec![entry("09:00", 60, "W", Some("scrum17"))];
+
+        let violations = validate(&entries, utc(), &config);
+
+        let rules: Vec<_> = violations.iter().map(|it| it.rule).collect();
+        assert_eq!(
+            rules,
+            vec![
+                ValidationRule::DescriptionRequired,
+                ValidationRule::TicketFormat
+            ]
+        );
+        assert!(has_hard_violations(&violations));
+        assert_eq!(worst_severity_of(&violations, 0), Some(Severity::Hard));
+    }
+
+    #[test]
+    fn test_max_length_and_working_hours_are_warnings() {
+        let config = config(ValidationConfig {
+            max_entry_mins: Some(120),
+            working_hours: Some(WorkingHours {
+                from: "07:00".to_string(),
+                until: "19:00".to_string(),
+            }),
+            ..Default::default()
+        });
+        let entries = vec![
+            entry("06:30", 30, "E", None),
+            entry("07:00", 180, "E", None),
+            entry("10:00", 180, BREAK_PROJECT_KEY, None),
+            entry("18:30", 60, "E", None),
+        ];
+
+        let violations = validate(&entries, utc(), &config);
+
+        let found: Vec<_> = violations
+            .iter()
+            .map(|it| (it.entry_idx, it.rule))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (Some(0), ValidationRule::WorkingHours),
+                (Some(1), ValidationRule::MaxEntryLength),
+                (Some(3), ValidationRule::WorkingHours),
+            ]
+        );
+        assert!(!has_hard_violations(&violations));
+        assert_eq!(worst_severity_of(&violations, 2), None);
+    }
+
+    #[test]
+    fn test_entries_past_midnight_block_saving() {
+        let config = config(Default::default());
+        let entries = vec![
+            entry("22:00", 60, "E", None),
+            entry("23:00", 500, "E", None),
+            entry("07:20", 30, "E", None),
+        ];
+
+        let violations = validate(&entries, utc(), &config);
+
+        assert_eq!(
+            violations.iter().map(|it| &it.message).collect::<Vec<_>>(),
+            vec![
+                "23:00: 500m end after midnight, at 07:20 the next day",
+                "07:20: follows an entry that ends after midnight",
+            ]
+        );
+        assert!(has_hard_violations(&violations));
+        assert_eq!(unsaveable_idxs(&violations), HashSet::from([1, 2]));
+        assert!(validate(&[entry("23:00", 60, "E", None)], utc(), &config).is_empty());
+    }
+
+    #[test]
+    fn test_archived_tickets_are_warnings() {
+        let config = config(Default::default());
+        let archived = HashSet::from(["SCRUM-1".to_string()]);
+        let entries = vec![
+            entry("09:00", 60, "W", Some("SCRUM-1")),
+            entry("10:00", 60, "W", Some("SCRUM-2")),
+            entry("11:00", 0, "W", Some("SCRUM-1")),
+        ];
+
+        let violations = check_archived(&entries, &archived, &config);
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].entry_idx, Some(0));
+        assert_eq!(violations[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_work_on_holiday_is_one_warning() {
+        let config = config(Default::default());
+        let entries = vec![
+            entry("09:00", 60, "W", Some("SCRUM-1")),
+            entry("10:00", 30, BREAK_PROJECT_KEY, None),
+            entry("10:30", 45, "E", None),
+        ];
+
+        let violations = check_holiday(&entries, "Christmas Day", &config);
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].entry_idx, None);
+        assert_eq!(violations[0].severity, Severity::Warning);
+        assert_eq!(
+            violations[0].message,
+            "105m logged on Christmas Day, a public holiday"
+        );
+        assert!(check_holiday(&entries[1..2], "Christmas Day", &config).is_empty());
+    }
+
+    #[test]
+    fn test_day_duration_outside_limits_is_warned() {
+        let config = config(ValidationConfig {
+            max_day_mins: Some(600),
+            min_day_mins: Some(240),
+            ..Default::default()
+        });
+        let long_day = vec![
+            entry("07:00", 300, "W", Some("SCRUM-1")),
+            entry("12:00", 30, BREAK_PROJECT_KEY, None),
+            entry("12:30", 330, "W", Some("SCRUM-1")),
+        ];
+        let short_day = vec![entry("09:00", 120, "W", Some("SCRUM-1"))];
+
+        let violations = check_day_duration(&long_day, Weekday::Monday, true, &config);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].rule, ValidationRule::DayDuration);
+        assert_eq!(
+            violations[0].message,
+            "10h 30m worked, more than the maximum of 10h 00m"
+        );
+        let violations = check_day_duration(&short_day, Weekday::Monday, true, &config);
+        assert_eq!(
+            violations[0].message,
+            "Only 2h 00m logged on a workday, less than the minimum of 4h 00m"
+        );
+        // Not a workday, or not over yet
+        assert!(check_day_duration(&short_day, Weekday::Saturday, true, &config).is_empty());
+        assert!(check_day_duration(&short_day, Weekday::Monday, false, &config).is_empty());
+    }
+
+    #[test]
+    fn test_jira_export_is_blocked_by_unbookable_entries() {
+        let mut config = config(Default::default());
+        config.projects.get_mut("W").unwrap().description_required = false;
+        config.projects.get_mut("W").unwrap().jira_url = Some("https://jira".to_string());
+        let mut unsaved = entry("11:00", 30, "W", Some("SCRUM-3"));
+        unsaved.id = "3".to_string();
+        let entries = vec![
+            entry("09:00", 60, "W", None),
+            entry("10:00", 0, "W", Some("SCRUM-2")),
+            entry("10:00", 60, "E", None),
+            unsaved.clone(),
+            entry("11:30", 30, "W", Some("scrum4")),
+        ];
+        let mut other_unsaved = entry("12:00", 30, "W", Some("SCRUM-5"));
+        other_unsaved.id = "5".to_string();
+
+        let violations = check_jira_export(&entries, &[unsaved, other_unsaved], utc(), &config);
+
+        let found: Vec<_> = violations
+            .iter()
+            .map(|it| (it.entry_idx, it.rule))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (Some(4), ValidationRule::TicketFormat),
+                (Some(0), ValidationRule::JiraBooking),
+                (Some(1), ValidationRule::JiraBooking),
+                (Some(3), ValidationRule::Unsaved),
+                (None, ValidationRule::Unsaved),
+            ]
+        );
+        assert!(violations.iter().all(|it| it.severity == Severity::Hard));
+    }
+
+    #[test]
+    fn test_invalid_pattern_is_reported_for_day() {
+        let mut config = config(Default::default());
+        config.projects.get_mut("W").unwrap().ticket_pattern = Some("(".to_string().into());
+        let mut work = entry("09:00", 60, "W", Some("SCRUM-17"));
+        work.description = "standup".to_string();
+
+        let violations = validate(&[work], utc(), &config);
+
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].entry_idx, None);
+    }
+}
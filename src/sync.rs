@@ -0,0 +1,569 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    env, fs,
+    path::{Path, PathBuf},
+    process::{self, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use color_eyre::{
+    Result,
+    eyre::{Context, OptionExt, bail},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{SyncConfig, SyncStrategy, SyncTarget},
+    persist::TimeEntry,
+};
+
+/// Latest change of each day that was pushed, so that unchanged days are not fetched
+pub const INDEX_FILE: &str = "index.json";
+
+/// File of the day on the remote
+pub fn day_file(iso_day: &str) -> String {
+    format!("{iso_day}.json")
+}
+
+/// A day as it is pushed, with the times of its last changes. Times are UTC in RFC 3339 with
+/// milliseconds, so that they order as strings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncDay {
+    pub day: String,
+    pub notes: String,
+    pub utc_offset_mins: Option<i32>,
+    /// When the notes or offset changed last, `None` if never since syncing was added
+    pub changed_at: Option<String>,
+    /// Ordered by start time and id
+    pub entries: Vec<SyncEntry>,
+    /// Ordered by id
+    pub tombstones: Vec<Tombstone>,
+}
+
+/// Entry with its links, the revision is left at 0 since it only counts local writes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncEntry {
+    #[serde(flatten)]
+    pub entry: TimeEntry,
+    #[serde(default)]
+    pub links: Vec<String>,
+    pub changed_at: Option<String>,
+}
+
+/// Deleted entry, kept so that the deletion wins over older versions of other machines
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tombstone {
+    pub id: String,
+    pub deleted_at: String,
+}
+
+/// Latest change of each day that was pushed, stored as [INDEX_FILE]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncIndex {
+    pub days: BTreeMap<String, String>,
+}
+
+impl SyncDay {
+    pub fn empty(day: &str) -> Self {
+        Self {
+            day: day.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Time of the latest change of the day, `None` if nothing was stamped
+    pub fn stamp(&self) -> Option<&str> {
+        let entries = self
+            .entries
+            .iter()
+            .filter_map(|it| it.changed_at.as_deref());
+        let tombstones = self.tombstones.iter().map(|it| it.deleted_at.as_str());
+        self.changed_at
+            .as_deref()
+            .into_iter()
+            .chain(entries)
+            .chain(tombstones)
+            .max()
+    }
+
+    /// Orders entries and tombstones the same way on every machine
+    pub fn normalize(&mut self) {
+        self.entries.sort_by(|a, b| {
+            (&a.entry.start_time, &a.entry.id).cmp(&(&b.entry.start_time, &b.entry.id))
+        });
+        self.tombstones.sort_by(|a, b| a.id.cmp(&b.id));
+    }
+}
+
+/// Version of an entry on one side
+enum Version<'a> {
+    Alive(&'a SyncEntry),
+    Deleted(&'a Tombstone),
+}
+
+impl Version<'_> {
+    fn stamp(&self) -> &str {
+        match self {
+            Self::Alive(entry) => entry.changed_at.as_deref().unwrap_or_default(),
+            Self::Deleted(tombstone) => &tombstone.deleted_at,
+        }
+    }
+}
+
+fn versions(day: &SyncDay) -> BTreeMap<&str, Version<'_>> {
+    let alive = day
+        .entries
+        .iter()
+        .map(|it| (it.entry.id.as_str(), Version::Alive(it)));
+    let deleted = day
+        .tombstones
+        .iter()
+        .map(|it| (it.id.as_str(), Version::Deleted(it)));
+    deleted.chain(alive).collect()
+}
+
+/// The day as it should be on both sides. With [SyncStrategy::Merge] the latest version of
+/// each entry wins, deleted or not, and the notes go with their own time. With
+/// [SyncStrategy::LastWriteWins] the side with the latest change wins as a whole. Ties keep
+/// the local version.
+pub fn merge(local: &SyncDay, remote: &SyncDay, strategy: SyncStrategy) -> SyncDay {
+    let mut merged = match strategy {
+        SyncStrategy::LastWriteWins => {
+            let (mut winner, loser) = if remote.stamp() > local.stamp() {
+                (remote.clone(), local)
+            } else {
+                (local.clone(), remote)
+            };
+            // Entries only the other side has are deleted as of the winning change, so that
+            // they don't come back from machines that merge
+            let known = winner
+                .entries
+                .iter()
+                .map(|it| it.entry.id.clone())
+                .chain(winner.tombstones.iter().map(|it| it.id.clone()))
+                .collect::<BTreeSet<_>>();
+            let deleted_at = winner.stamp().unwrap_or_default().to_string();
+            let lost = loser
+                .entries
+                .iter()
+                .map(|it| Tombstone {
+                    id: it.entry.id.clone(),
+                    deleted_at: deleted_at.clone(),
+                })
+                .chain(loser.tombstones.iter().cloned())
+                .filter(|it| !known.contains(&it.id))
+                .collect::<Vec<_>>();
+            winner.tombstones.extend(lost);
+            winner.day = local.day.clone();
+            winner
+        }
+        SyncStrategy::Merge => {
+            let local_versions = versions(local);
+            let mut remote_versions = versions(remote);
+            let mut merged = SyncDay::empty(&local.day);
+            let mut take = |version: &Version| match version {
+                Version::Alive(entry) => merged.entries.push((*entry).clone()),
+                Version::Deleted(tombstone) => merged.tombstones.push((*tombstone).clone()),
+            };
+            for (id, mine) in &local_versions {
+                match remote_versions.remove(id) {
+                    Some(theirs) if theirs.stamp().cmp(mine.stamp()) == Ordering::Greater => {
+                        take(&theirs)
+                    }
+                    _ => take(mine),
+                }
+            }
+            remote_versions.values().for_each(take);
+            let (notes, utc_offset_mins, changed_at) = if remote.changed_at > local.changed_at {
+                (&remote.notes, remote.utc_offset_mins, &remote.changed_at)
+            } else {
+                (&local.notes, local.utc_offset_mins, &local.changed_at)
+            };
+            merged.notes = notes.clone();
+            merged.utc_offset_mins = utc_offset_mins;
+            merged.changed_at = changed_at.clone();
+            merged
+        }
+    };
+    merged.normalize();
+    merged
+}
+
+/// Place the day files are pushed to and pulled from
+pub trait Remote {
+    /// Brings the local view of the remote up to date, e.g. pulls a Git repository
+    fn prepare(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Content of the file, `None` if it doesn't exist yet
+    fn get(&mut self, name: &str) -> Result<Option<String>>;
+
+    fn put(&mut self, name: &str, content: &str) -> Result<()>;
+
+    /// Publishes the files that were put, e.g. commits and pushes them
+    fn finish(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The configured remote, failing if a setting it needs is missing
+pub fn remote_for(config: &SyncConfig) -> Result<Box<dyn Remote>> {
+    let path = || {
+        config
+            .path
+            .clone()
+            .ok_or_eyre("The sync target needs a path in the sync config")
+    };
+    let timeout = Duration::from_secs(config.timeout_secs);
+    Ok(match config.target {
+        SyncTarget::Dir => Box::new(DirRemote { path: path()? }),
+        SyncTarget::Git => Box::new(GitRemote {
+            files: DirRemote { path: path()? },
+            changed: false,
+            timeout,
+        }),
+        SyncTarget::Webdav => {
+            let url = config
+                .url
+                .clone()
+                .ok_or_eyre("The webdav sync target needs a url in the sync config")?;
+            let auth = match &config.username {
+                Some(username) => {
+                    let password = password(config, timeout)?;
+                    let credentials = BASE64_STANDARD.encode(format!("{username}:{password}"));
+                    Some(format!("Basic {credentials}"))
+                }
+                None => None,
+            };
+            let agent = ureq::Agent::config_builder()
+                .timeout_connect(Some(timeout))
+                .timeout_recv_response(Some(timeout))
+                .timeout_recv_body(Some(timeout))
+                .timeout_send_body(Some(timeout))
+                .build()
+                .new_agent();
+            Box::new(WebDavRemote {
+                agent,
+                url: url.trim_end_matches('/').to_string(),
+                auth,
+            })
+        }
+    })
+}
+
+/// Password of the webdav target, from the command or variable if configured
+fn password(config: &SyncConfig, timeout: Duration) -> Result<String> {
+    if let Some(command) = &config.password_command {
+        let mut shell = process::Command::new("sh");
+        shell.arg("-c").arg(command);
+        let output = output_within(&mut shell, timeout)
+            .wrap_err_with(|| format!("running password command {command}"))?;
+        if !output.status.success() {
+            bail!(
+                "password command {command} failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        let stdout = String::from_utf8(output.stdout).wrap_err("reading password")?;
+        // Like pass, only the first line is the password
+        return Ok(stdout.lines().next().unwrap_or_default().to_string());
+    }
+    if let Some(name) = &config.password_env {
+        return env::var(name).wrap_err_with(|| format!("reading password from ${name}"));
+    }
+    Ok(config.password.clone().unwrap_or_default())
+}
+
+/// Output of the command, killing it once it runs longer than the timeout, e.g. while it waits
+/// for a remote that doesn't answer
+fn output_within(command: &mut process::Command, timeout: Duration) -> Result<process::Output> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let deadline = Instant::now() + timeout;
+    while child.try_wait()?.is_none() {
+        if Instant::now() >= deadline {
+            child.kill()?;
+            child.wait()?;
+            bail!("gave up after {}s", timeout.as_secs());
+        }
+        thread::sleep(Duration::from_millis(20));
+    }
+    Ok(child.wait_with_output()?)
+}
+
+/// Folder shared by other means, e.g. Syncthing, a network drive or a mounted bucket
+pub struct DirRemote {
+    pub path: PathBuf,
+}
+
+impl Remote for DirRemote {
+    fn get(&mut self, name: &str) -> Result<Option<String>> {
+        let path = self.path.join(name);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content =
+            fs::read_to_string(&path).wrap_err_with(|| format!("reading {}", path.display()))?;
+        Ok(Some(content))
+    }
+
+    fn put(&mut self, name: &str, content: &str) -> Result<()> {
+        fs::create_dir_all(&self.path)
+            .wrap_err_with(|| format!("creating {}", self.path.display()))?;
+        // Renamed into place, so that others never see half a file
+        let path = self.path.join(name);
+        let partial = self.path.join(format!(".{name}.partial"));
+        fs::write(&partial, content).wrap_err_with(|| format!("writing {}", partial.display()))?;
+        fs::rename(&partial, &path).wrap_err_with(|| format!("replacing {}", path.display()))
+    }
+}
+
+/// Clone of a Git repository, pulled before syncing and pushed afterwards if a file changed
+pub struct GitRemote {
+    files: DirRemote,
+    changed: bool,
+    /// For each git command
+    timeout: Duration,
+}
+
+impl Remote for GitRemote {
+    fn prepare(&mut self) -> Result<()> {
+        if has_upstream(&self.files.path, self.timeout)? {
+            git(
+                &self.files.path,
+                &["pull", "--rebase", "--autostash", "--quiet"],
+                self.timeout,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn get(&mut self, name: &str) -> Result<Option<String>> {
+        self.files.get(name)
+    }
+
+    fn put(&mut self, name: &str, content: &str) -> Result<()> {
+        self.changed = true;
+        self.files.put(name, content)
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if !self.changed {
+            return Ok(());
+        }
+        let (path, timeout) = (&self.files.path, self.timeout);
+        git(path, &["add", "--all", "--", "*.json"], timeout)?;
+        git(
+            path,
+            &["commit", "--quiet", "-m", "Sync timesheets"],
+            timeout,
+        )?;
+        if has_upstream(path, timeout)? {
+            git(path, &["push", "--quiet"], timeout)?;
+        }
+        self.changed = false;
+        Ok(())
+    }
+}
+
+fn has_upstream(path: &Path, timeout: Duration) -> Result<bool> {
+    let output = git_output(path, &["rev-parse", "--abbrev-ref", "@{upstream}"], timeout)?;
+    Ok(output.status.success())
+}
+
+fn git(path: &Path, args: &[&str], timeout: Duration) -> Result<()> {
+    let output = git_output(path, args, timeout)?;
+    if !output.status.success() {
+        bail!(
+            "git {} in {} failed: {}",
+            args.join(" "),
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+fn git_output(path: &Path, args: &[&str], timeout: Duration) -> Result<process::Output> {
+    let mut git = process::Command::new("git");
+    // Fails instead of asking for credentials on the terminal of the TUI
+    git.arg("-C")
+        .arg(path)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0");
+    output_within(&mut git, timeout)
+        .wrap_err_with(|| format!("running git {} in {}", args.join(" "), path.display()))
+}
+
+/// Folder on a WebDAV server, e.g. Nextcloud, which has to exist already
+pub struct WebDavRemote {
+    agent: ureq::Agent,
+    url: String,
+    auth: Option<String>,
+}
+
+impl Remote for WebDavRemote {
+    fn get(&mut self, name: &str) -> Result<Option<String>> {
+        let mut request = self.agent.get(format!("{}/{name}", self.url));
+        if let Some(auth) = &self.auth {
+            request = request.header("Authorization", auth);
+        }
+        match request
+            .call()
+            .and_then(|mut it| it.body_mut().read_to_string())
+        {
+            Ok(content) => Ok(Some(content)),
+            Err(ureq::Error::StatusCode(404)) => Ok(None),
+            Err(err) => Err(err).wrap_err_with(|| format!("fetching {name} from {}", self.url)),
+        }
+    }
+
+    fn put(&mut self, name: &str, content: &str) -> Result<()> {
+        let mut request = self.agent.put(format!("{}/{name}", self.url));
+        if let Some(auth) = &self.auth {
+            request = request.header("Authorization", auth);
+        }
+        request
+            .header("Content-Type", "application/json")
+            .send(content)
+            .wrap_err_with(|| format!("uploading {name} to {}", self.url))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, start_time: &str, description: &str, changed_at: &str) -> SyncEntry {
+        SyncEntry {
+            entry: TimeEntry {
+                id: id.to_string(),
+                description: description.to_string(),
+                ..TimeEntry::sample(start_time, 30)
+            },
+            links: vec![],
+            changed_at: Some(changed_at.to_string()),
+        }
+    }
+
+    fn tombstone(id: &str, deleted_at: &str) -> Tombstone {
+        Tombstone {
+            id: id.to_string(),
+            deleted_at: deleted_at.to_string(),
+        }
+    }
+
+    fn descriptions(day: &SyncDay) -> Vec<&str> {
+        day.entries
+            .iter()
+            .map(|it| it.entry.description.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_merge_keeps_latest_version_of_each_entry() {
+        let local = SyncDay {
+            notes: "mine".to_string(),
+            changed_at: Some("2026-10-15T08:00:00.000Z".to_string()),
+            entries: vec![
+                entry("a", "09:00", "standup", "2026-10-15T09:00:00.000Z"),
+                entry("b", "09:30", "planning", "2026-10-15T09:40:00.000Z"),
+                entry("c", "11:00", "review", "2026-10-15T11:00:00.000Z"),
+            ],
+            ..SyncDay::empty("2026-10-15")
+        };
+        let remote = SyncDay {
+            notes: "theirs".to_string(),
+            changed_at: Some("2026-10-15T10:00:00.000Z".to_string()),
+            entries: vec![
+                entry("a", "09:00", "daily", "2026-10-15T09:10:00.000Z"),
+                entry("b", "09:30", "sprint planning", "2026-10-15T09:35:00.000Z"),
+                entry("d", "10:00", "coffee", "2026-10-15T10:00:00.000Z"),
+            ],
+            tombstones: vec![tombstone("c", "2026-10-15T11:30:00.000Z")],
+            ..SyncDay::empty("2026-10-15")
+        };
+
+        let merged = merge(&local, &remote, SyncStrategy::Merge);
+        assert_eq!(descriptions(&merged), ["daily", "planning", "coffee"]);
+        assert_eq!(merged.notes, "theirs");
+        assert_eq!(
+            merged.tombstones,
+            [tombstone("c", "2026-10-15T11:30:00.000Z")]
+        );
+        assert_eq!(merged.stamp(), Some("2026-10-15T11:30:00.000Z"));
+
+        // Deleted after the remote change, so the entry stays deleted
+        let mut local = merged.clone();
+        local.entries.retain(|it| it.entry.id != "d");
+        local
+            .tombstones
+            .push(tombstone("d", "2026-10-15T12:00:00.000Z"));
+        let merged = merge(&local, &remote, SyncStrategy::Merge);
+        assert_eq!(descriptions(&merged), ["daily", "planning"]);
+        assert_eq!(merge(&merged, &merged, SyncStrategy::Merge), merged);
+    }
+
+    #[test]
+    fn test_last_write_wins_takes_whole_day_of_latest_change() {
+        let local = SyncDay {
+            entries: vec![
+                entry("a", "09:00", "standup", "2026-10-15T09:00:00.000Z"),
+                entry("b", "09:30", "planning", "2026-10-15T12:00:00.000Z"),
+            ],
+            ..SyncDay::empty("2026-10-15")
+        };
+        let remote = SyncDay {
+            entries: vec![entry("a", "09:00", "daily", "2026-10-15T13:00:00.000Z")],
+            tombstones: vec![tombstone("c", "2026-10-15T08:00:00.000Z")],
+            ..SyncDay::empty("2026-10-15")
+        };
+
+        let merged = merge(&local, &remote, SyncStrategy::LastWriteWins);
+        assert_eq!(descriptions(&merged), ["daily"]);
+        assert_eq!(
+            merged.tombstones,
+            [
+                tombstone("b", "2026-10-15T13:00:00.000Z"),
+                tombstone("c", "2026-10-15T08:00:00.000Z")
+            ]
+        );
+        let merged = merge(&remote, &local, SyncStrategy::LastWriteWins);
+        assert_eq!(descriptions(&merged), ["daily"]);
+    }
+
+    #[test]
+    fn test_password_command_wins_over_plain_password() {
+        let config = SyncConfig {
+            password: Some("plain".to_string()),
+            password_command: Some("printf 'secret\\nlogin: me'".to_string()),
+            ..SyncConfig::default()
+        };
+        let timeout = Duration::from_secs(5);
+        assert_eq!(password(&config, timeout).unwrap(), "secret");
+
+        let config = SyncConfig {
+            password_command: Some("exit 1".to_string()),
+            ..config
+        };
+        assert!(password(&config, timeout).is_err());
+    }
+
+    #[test]
+    fn test_command_running_too_long_is_killed() {
+        let started = Instant::now();
+        let mut sleep = process::Command::new("sleep");
+        sleep.arg("10");
+        let err = output_within(&mut sleep, Duration::from_millis(100)).unwrap_err();
+        assert!(err.to_string().contains("gave up"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
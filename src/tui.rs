@@ -3,6 +3,7 @@
 use std::{
     io::{Stdout, stdout},
     ops::{Deref, DerefMut},
+    sync::Arc,
     time::Duration,
 };
 
@@ -19,9 +20,12 @@ use futures::{FutureExt, StreamExt};
 use ratatui::backend::CrosstermBackend as Backend;
 use serde::{Deserialize, Serialize};
 use tokio::{
-    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    sync::{
+        Notify,
+        mpsc::{self, UnboundedReceiver, UnboundedSender},
+    },
     task::JoinHandle,
-    time::interval,
+    time::{Instant, interval},
 };
 use tokio_util::sync::CancellationToken;
 use tracing::error;
@@ -41,6 +45,14 @@ pub enum Event {
     Resize(u16, u16),
 }
 
+/// Draws fewer frames after a while without input, see
+/// [crate::config::DesktopConfig::idle_render_after_secs]
+#[derive(Clone, Copy, Debug)]
+pub struct IdleRender {
+    pub after: Duration,
+    pub frame_rate: f64,
+}
+
 pub struct Tui {
     pub terminal: ratatui::Terminal<Backend<Stdout>>,
     pub task: JoinHandle<()>,
@@ -51,6 +63,9 @@ pub struct Tui {
     pub tick_rate: f64,
     pub mouse: bool,
     pub paste: bool,
+    pub idle_render: Option<IdleRender>,
+    /// Counts as input for [IdleRender], e.g. for changes that didn't come from the terminal
+    wake: Arc<Notify>,
 }
 
 impl Tui {
@@ -66,6 +81,8 @@ impl Tui {
             tick_rate: 4.0,
             mouse: false,
             paste: false,
+            idle_render: None,
+            wake: Arc::new(Notify::new()),
         })
     }
 
@@ -79,6 +96,16 @@ impl Tui {
         self
     }
 
+    pub fn idle_render(mut self, idle_render: Option<IdleRender>) -> Self {
+        self.idle_render = idle_render;
+        self
+    }
+
+    /// Goes back to the full frame rate if it was lowered by [IdleRender]
+    pub fn wake(&self) {
+        self.wake.notify_one();
+    }
+
     pub fn mouse(mut self, mouse: bool) -> Self {
         self.mouse = mouse;
         self
@@ -97,6 +124,8 @@ impl Tui {
             self.cancellation_token.clone(),
             self.tick_rate,
             self.frame_rate,
+            self.idle_render,
+            self.wake.clone(),
         );
         self.task = tokio::spawn(async {
             event_loop.await;
@@ -108,10 +137,14 @@ impl Tui {
         cancellation_token: CancellationToken,
         tick_rate: f64,
         frame_rate: f64,
+        idle_render: Option<IdleRender>,
+        wake: Arc<Notify>,
     ) {
         let mut event_stream = EventStream::new();
         let mut tick_interval = interval(Duration::from_secs_f64(1.0 / tick_rate));
         let mut render_interval = interval(Duration::from_secs_f64(1.0 / frame_rate));
+        let mut last_input = Instant::now();
+        let mut idle = false;
 
         // if this fails, then it's likely a bug in the calling code
         event_tx
@@ -122,6 +155,14 @@ impl Tui {
                 _ = cancellation_token.cancelled() => {
                     break;
                 }
+                _ = wake.notified() => {
+                    last_input = Instant::now();
+                    if idle {
+                        idle = false;
+                        render_interval = interval(Duration::from_secs_f64(1.0 / frame_rate));
+                    }
+                    continue;
+                }
                 _ = tick_interval.tick() => Event::Tick,
                 _ = render_interval.tick() => Event::Render,
                 crossterm_event = event_stream.next().fuse() => match crossterm_event {
@@ -138,6 +179,24 @@ impl Tui {
                     None => break, // the event stream has stopped and will not produce any more events
                 },
             };
+            match (&event, idle_render) {
+                (Event::Tick | Event::Render, Some(idle_render))
+                    if !idle && last_input.elapsed() >= idle_render.after =>
+                {
+                    idle = true;
+                    let period = Duration::from_secs_f64(1.0 / idle_render.frame_rate);
+                    render_interval = interval(period);
+                }
+                (Event::Tick | Event::Render, _) => {}
+                _ => {
+                    last_input = Instant::now();
+                    if idle {
+                        // The first tick of a new interval is immediate
+                        idle = false;
+                        render_interval = interval(Duration::from_secs_f64(1.0 / frame_rate));
+                    }
+                }
+            }
             if event_tx.send(event).is_err() {
                 // the receiver has been dropped, so there's no point in continuing the loop
                 break;
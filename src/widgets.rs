@@ -16,6 +16,7 @@ pub mod table_popup {
         list_state: &'a mut ListState,
         items: Vec<ListItem<'a>>,
         constraints: Vec<Constraint>,
+        spanned_columns: usize,
     }
 
     impl<'a> TablePopup<'a> {
@@ -33,16 +34,25 @@ pub mod table_popup {
                 list_state,
                 items,
                 constraints: constraints.into_iter().collect_vec(),
+                spanned_columns: 1,
             }
         }
 
+        /// Widens the popup over the following columns, for items that don't fit one column
+        pub fn spanning(mut self, columns: usize) -> Self {
+            self.spanned_columns = columns.max(1);
+            self
+        }
+
         fn find_best_area(&self, constraints: &[Constraint], area: Rect) -> Option<Rect> {
             let (row_idx, col_idx) = self.table_state.selected_cell()?;
             let height_above = row_idx as u16;
 
-            let column_rect = Layout::horizontal(constraints)
+            let columns = Layout::horizontal(constraints)
                 .spacing(ASSUMED_SPACING)
-                .split(area)[col_idx];
+                .split(area);
+            let last_col_idx = (col_idx + self.spanned_columns - 1).min(columns.len() - 1);
+            let column_rect = columns[col_idx].union(columns[last_col_idx]);
             let above_and_below = Layout::vertical([
                 Constraint::Length(ASSUMED_HEADER_HEIGHT),
                 Constraint::Length(height_above),
@@ -87,3 +97,173 @@ pub mod table_popup {
         }
     }
 }
+
+pub mod list_popup {
+    use ratatui::{
+        prelude::*,
+        style::palette::tailwind::{INDIGO, SLATE},
+        widgets::{Block, BorderType, Clear, List, ListItem, ListState, Padding, Widget},
+    };
+
+    /// Bordered list centered over the area it is rendered to, e.g. for pickers and overviews.
+    pub struct ListPopup<'a> {
+        title: Line<'a>,
+        list_state: &'a mut ListState,
+        items: Vec<ListItem<'a>>,
+        footer: Option<Line<'a>>,
+        width_percent: u16,
+    }
+
+    impl<'a> ListPopup<'a> {
+        pub fn new<T>(title: T, list_state: &'a mut ListState, items: Vec<ListItem<'a>>) -> Self
+        where
+            T: Into<Line<'a>>,
+        {
+            Self {
+                title: title.into(),
+                list_state,
+                items,
+                footer: None,
+                width_percent: 60,
+            }
+        }
+
+        /// Hint shown in the bottom border, e.g. for relevant keys
+        pub fn footer<T>(mut self, footer: T) -> Self
+        where
+            T: Into<Line<'a>>,
+        {
+            self.footer = Some(footer.into());
+            self
+        }
+
+        pub fn width_percent(mut self, width_percent: u16) -> Self {
+            self.width_percent = width_percent;
+            self
+        }
+    }
+
+    impl Widget for ListPopup<'_> {
+        fn render(self, area: Rect, buf: &mut Buffer)
+        where
+            Self: Sized,
+        {
+            let border_and_padding = 2;
+            let height = (self.items.len() as u16 + border_and_padding).max(3);
+            let area = centered_rect(area, self.width_percent, height);
+
+            Clear.render(area, buf);
+
+            let mut block = Block::bordered()
+                .border_type(BorderType::Rounded)
+                .padding(Padding::horizontal(1))
+                .title(self.title)
+                .style(Style::new().bg(INDIGO.c950));
+            if let Some(footer) = self.footer {
+                block = block.title_bottom(footer.right_aligned());
+            }
+
+            let list = List::new(self.items)
+                .block(block)
+                .highlight_style(Style::new().bg(SLATE.c800).add_modifier(Modifier::BOLD));
+            StatefulWidget::render(list, area, buf, self.list_state);
+        }
+    }
+
+    /// Horizontally centered by percentage, vertically centered by absolute height
+    pub fn centered_rect(area: Rect, width_percent: u16, height: u16) -> Rect {
+        let [area] = Layout::horizontal([Constraint::Percentage(width_percent)])
+            .flex(layout::Flex::Center)
+            .areas(area);
+        let [area] = Layout::vertical([Constraint::Length(height)])
+            .flex(layout::Flex::Center)
+            .areas(area);
+        area
+    }
+}
+
+pub mod text_popup {
+    use crossterm::event::{KeyCode, KeyEvent};
+    use ratatui::{
+        prelude::*,
+        style::palette::tailwind::INDIGO,
+        widgets::{Block, BorderType, Clear, Padding, Paragraph, Widget, Wrap},
+    };
+
+    use super::list_popup::centered_rect;
+
+    /// Text being entered in a [TextPopup], Enter submits it
+    #[derive(Default)]
+    pub struct TextInput {
+        buf: String,
+    }
+
+    pub enum TextInputOutcome {
+        Editing,
+        Submitted(String),
+        Cancelled,
+    }
+
+    impl TextInput {
+        pub fn new(initial: String) -> Self {
+            Self { buf: initial }
+        }
+
+        pub fn text(&self) -> &str {
+            &self.buf
+        }
+
+        pub fn handle_key_event(&mut self, key: KeyEvent) -> TextInputOutcome {
+            match key.code {
+                KeyCode::Enter => return TextInputOutcome::Submitted(self.buf.trim().to_string()),
+                KeyCode::Esc => return TextInputOutcome::Cancelled,
+                KeyCode::Char(chr) => self.buf.push(chr),
+                KeyCode::Backspace => {
+                    self.buf.pop();
+                }
+                _ => {}
+            }
+            TextInputOutcome::Editing
+        }
+    }
+
+    /// Centered box for entering a longer text, wrapped over several lines.
+    pub struct TextPopup<'a> {
+        title: Line<'a>,
+        input: &'a TextInput,
+    }
+
+    impl<'a> TextPopup<'a> {
+        pub fn new<T>(title: T, input: &'a TextInput) -> Self
+        where
+            T: Into<Line<'a>>,
+        {
+            Self {
+                title: title.into(),
+                input,
+            }
+        }
+    }
+
+    impl Widget for TextPopup<'_> {
+        fn render(self, area: Rect, buf: &mut Buffer)
+        where
+            Self: Sized,
+        {
+            let area = centered_rect(area, 60, 6);
+            Clear.render(area, buf);
+
+            let block = Block::bordered()
+                .border_type(BorderType::Rounded)
+                .padding(Padding::horizontal(1))
+                .title(self.title)
+                .title_bottom(Line::from(" <Enter> Save  <Esc> Cancel ").right_aligned())
+                .style(Style::new().bg(INDIGO.c950));
+            let text = format!("{}▏", self.input.buf);
+            Paragraph::new(text)
+                .wrap(Wrap { trim: false })
+                .block(block)
+                .render(area, buf);
+        }
+    }
+}